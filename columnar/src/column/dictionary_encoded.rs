@@ -54,6 +54,16 @@ impl BytesColumn {
         self.term_ord_column.values_for_doc(row_id)
     }
 
+    /// Fills the given `output` buffer with the first bytes value of the row.
+    ///
+    /// Returns `false`, and leaves `output` untouched, if the row has no value.
+    pub fn first_bytes(&self, row_id: RowId, output: &mut Vec<u8>) -> io::Result<bool> {
+        let Some(ord) = self.term_ord_column.first(row_id) else {
+            return Ok(false);
+        };
+        self.ord_to_bytes(ord, output)
+    }
+
     /// Returns the column of ordinals
     pub fn ords(&self) -> &Column<u64> {
         &self.term_ord_column
@@ -83,6 +83,16 @@ impl<T: PartialOrd + Copy + Debug + Send + Sync + 'static> Column<T> {
         self.values.max_value()
     }
 
+    /// Returns true if and only if the row has at least one value.
+    ///
+    /// This makes it possible to tell a missing value from a default value, like `0`,
+    /// without reading the value.
+    #[inline]
+    pub fn has_value(&self, row_id: RowId) -> bool {
+        self.index.has_value(row_id)
+    }
+
+    /// Returns the first value of the row, or `None` if the row has no value.
     #[inline]
     pub fn first(&self, row_id: RowId) -> Option<T> {
         self.values_for_doc(row_id).next()
@@ -40,18 +40,42 @@ pub fn serialize_column_mappable_to_u64<T: MonotonicallyMappableToU64>(
     Ok(())
 }
 
+/// Splits a serialized column into its leading part, whose length is stored in the last 4 bytes,
+/// and the rest of its body.
+///
+/// Returns an `InvalidData` error instead of panicking if the column is truncated or corrupted.
+fn split_len_prefixed_part(bytes: OwnedBytes, part: &str) -> io::Result<(OwnedBytes, OwnedBytes)> {
+    if bytes.len() < 4 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Column of {} bytes is too short to hold its {part} length.",
+                bytes.len()
+            ),
+        ));
+    }
+    let (body, part_num_bytes_payload) = bytes.rsplit(4);
+    let part_num_bytes_array: [u8; 4] = part_num_bytes_payload.as_slice().try_into().unwrap();
+    let part_num_bytes = u32::from_le_bytes(part_num_bytes_array) as usize;
+    if part_num_bytes > body.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            // The length is stored right after the body.
+            format!(
+                "The {part} length read at offset {} of the column ({part_num_bytes} bytes) \
+                 exceeds the column body.",
+                body.len()
+            ),
+        ));
+    }
+    Ok(body.split(part_num_bytes))
+}
+
 pub fn open_column_u64<T: MonotonicallyMappableToU64>(
     bytes: OwnedBytes,
     format_version: Version,
 ) -> io::Result<Column<T>> {
-    let (body, column_index_num_bytes_payload) = bytes.rsplit(4);
-    let column_index_num_bytes = u32::from_le_bytes(
-        column_index_num_bytes_payload
-            .as_slice()
-            .try_into()
-            .unwrap(),
-    );
-    let (column_index_data, column_values_data) = body.split(column_index_num_bytes as usize);
+    let (column_index_data, column_values_data) = split_len_prefixed_part(bytes, "column index")?;
     let column_index = crate::column_index::open_column_index(column_index_data, format_version)?;
     let column_values = load_u64_based_column_values(column_values_data)?;
     Ok(Column {
@@ -64,14 +88,7 @@ pub fn open_column_u128<T: MonotonicallyMappableToU128>(
     bytes: OwnedBytes,
     format_version: Version,
 ) -> io::Result<Column<T>> {
-    let (body, column_index_num_bytes_payload) = bytes.rsplit(4);
-    let column_index_num_bytes = u32::from_le_bytes(
-        column_index_num_bytes_payload
-            .as_slice()
-            .try_into()
-            .unwrap(),
-    );
-    let (column_index_data, column_values_data) = body.split(column_index_num_bytes as usize);
+    let (column_index_data, column_values_data) = split_len_prefixed_part(bytes, "column index")?;
     let column_index = crate::column_index::open_column_index(column_index_data, format_version)?;
     let column_values = crate::column_values::open_u128_mapped(column_values_data)?;
     Ok(Column {
@@ -87,14 +104,7 @@ pub fn open_column_u128_as_compact_u64(
     bytes: OwnedBytes,
     format_version: Version,
 ) -> io::Result<Column<u64>> {
-    let (body, column_index_num_bytes_payload) = bytes.rsplit(4);
-    let column_index_num_bytes = u32::from_le_bytes(
-        column_index_num_bytes_payload
-            .as_slice()
-            .try_into()
-            .unwrap(),
-    );
-    let (column_index_data, column_values_data) = body.split(column_index_num_bytes as usize);
+    let (column_index_data, column_values_data) = split_len_prefixed_part(bytes, "column index")?;
     let column_index = crate::column_index::open_column_index(column_index_data, format_version)?;
     let column_values = crate::column_values::open_u128_as_compact_u64(column_values_data)?;
     Ok(Column {
@@ -104,9 +114,7 @@ pub fn open_column_u128_as_compact_u64(
 }
 
 pub fn open_column_bytes(data: OwnedBytes, format_version: Version) -> io::Result<BytesColumn> {
-    let (body, dictionary_len_bytes) = data.rsplit(4);
-    let dictionary_len = u32::from_le_bytes(dictionary_len_bytes.as_slice().try_into().unwrap());
-    let (dictionary_bytes, column_bytes) = body.split(dictionary_len as usize);
+    let (dictionary_bytes, column_bytes) = split_len_prefixed_part(data, "dictionary")?;
     let dictionary = Arc::new(Dictionary::from_bytes(dictionary_bytes)?);
     let term_ord_column = crate::column::open_column_u64::<u64>(column_bytes, format_version)?;
     Ok(BytesColumn {
@@ -84,14 +84,26 @@ impl ColumnarReader {
     }
 
     fn open_inner(file_slice: FileSlice) -> io::Result<ColumnarReader> {
-        let (file_slice_without_sstable_len, footer_slice) = file_slice
-            .split_from_end(mem::size_of::<u64>() + 4 + format_version::VERSION_FOOTER_NUM_BYTES);
+        let footer_num_bytes = mem::size_of::<u64>() + 4 + format_version::VERSION_FOOTER_NUM_BYTES;
+        if file_slice.len() < footer_num_bytes {
+            return Err(io_invalid_data(format!(
+                "Columnar file of {} bytes is too short to hold its footer.",
+                file_slice.len()
+            )));
+        }
+        let (file_slice_without_sstable_len, footer_slice) =
+            file_slice.split_from_end(footer_num_bytes);
         let footer_bytes = footer_slice.read_bytes()?;
         let sstable_len = u64::deserialize(&mut &footer_bytes[0..8])?;
         let num_rows = u32::deserialize(&mut &footer_bytes[8..12])?;
         let version_footer_bytes: [u8; format_version::VERSION_FOOTER_NUM_BYTES] =
             footer_bytes[12..].try_into().unwrap();
         let format_version = format_version::parse_footer(version_footer_bytes)?;
+        if sstable_len > file_slice_without_sstable_len.len() as u64 {
+            return Err(io_invalid_data(format!(
+                "The column dictionary length ({sstable_len} bytes) exceeds the columnar file."
+            )));
+        }
         let (column_data, sstable) =
             file_slice_without_sstable_len.split_from_end(sstable_len as usize);
         let column_dictionary = Dictionary::open(sstable)?;
@@ -192,6 +204,11 @@ impl ColumnarReader {
 
 #[cfg(test)]
 mod tests {
+    use std::io;
+
+    use common::file_slice::FileSlice;
+
+    use crate::dynamic_column::DynamicColumnHandle;
     use crate::{ColumnType, ColumnarReader, ColumnarWriter};
 
     #[test]
@@ -224,6 +241,36 @@ mod tests {
         assert_eq!(columns[0].1.column_type(), ColumnType::U64);
     }
 
+    #[test]
+    fn test_open_corrupted_columnar() {
+        let error = ColumnarReader::open(vec![1u8, 2u8, 3u8]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let mut columnar_writer = ColumnarWriter::default();
+        columnar_writer.record_numerical(0, "count", 1u64);
+        let mut buffer = Vec::new();
+        columnar_writer.serialize(1, &mut buffer).unwrap();
+        let columnar = ColumnarReader::open(buffer).unwrap();
+        let column_handle = columnar.read_columns("count").unwrap().pop().unwrap();
+        assert!(column_handle.open().is_ok());
+        // Truncated column.
+        let truncated_column_handle = DynamicColumnHandle {
+            file_slice: column_handle.file_slice().slice(0..2),
+            ..column_handle.clone()
+        };
+        let error = truncated_column_handle.open().err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        // Column index length exceeding the column.
+        let mut corrupted_column = vec![0u8; 4];
+        corrupted_column.extend_from_slice(&u32::MAX.to_le_bytes());
+        let corrupted_column_handle = DynamicColumnHandle {
+            file_slice: FileSlice::from(corrupted_column),
+            ..column_handle
+        };
+        let error = corrupted_column_handle.open_u64_lenient().err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
     #[test]
     #[should_panic(expected = "Input type forbidden")]
     fn test_list_columns_strict_typing_panics_on_wrong_types() {
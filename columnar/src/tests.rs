@@ -208,6 +208,10 @@ fn test_dictionary_encoded_bytes() {
         .collect();
     assert_eq!(index, &[None, Some(0), None, Some(2), Some(1)]);
     assert_eq!(bytes_col.num_rows(), 5);
+    let mut first_bytes = Vec::new();
+    assert!(!bytes_col.first_bytes(0, &mut first_bytes).unwrap());
+    assert!(bytes_col.first_bytes(3, &mut first_bytes).unwrap());
+    assert_eq!(first_bytes, b"c");
     let mut term_buffer = Vec::new();
     let term_ords = bytes_col.ords();
     assert_eq!(term_ords.first(0), None);
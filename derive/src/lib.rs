@@ -0,0 +1,412 @@
+//! `#[derive(TypedDocument)]` implements `tantivy::schema::TypedDocument` for a struct
+//! with named fields.
+//!
+//! Each field of the struct is mapped to a schema field of the same name.
+//! The type of the schema field is inferred from the Rust type:
+//!
+//! | Rust type  | Schema field |
+//! |------------|--------------|
+//! | `String`   | text         |
+//! | `u64`      | u64          |
+//! | `i64`      | i64          |
+//! | `f64`      | f64          |
+//! | `bool`     | bool         |
+//! | `DateTime` | date         |
+//! | `Vec<u8>`  | bytes        |
+//!
+//! These types can also be wrapped in an `Option` (the field may be missing) or a `Vec`
+//! (multivalued field).
+//!
+//! The field options are defined with the `#[tantivy(..)]` attribute:
+//! - `stored`, `indexed`, `fast`: same as the flags of the same name. Text fields are always
+//!   indexed, as configured by `text`, `string` and `tokenizer`: `indexed` is rejected for them.
+//! - `text` (default for text fields): the field is tokenized with the default tokenizer.
+//! - `string`: the field is indexed as a single token, like the `STRING` flag.
+//! - `tokenizer = "name"`: the field is tokenized with the given tokenizer.
+//! - `name = "name"`: the name of the schema field, if different from the struct field name.
+//! - `skip`: the field is not part of the schema. It is set to its default value when converting
+//!   from a document.
+//!
+//! ```ignore
+//! #[derive(TypedDocument)]
+//! struct Book {
+//!     #[tantivy(text, stored)]
+//!     title: String,
+//!     #[tantivy(string, stored, name = "isbn_code")]
+//!     isbn: String,
+//!     #[tantivy(indexed, fast, stored)]
+//!     year: u64,
+//!     #[tantivy(stored)]
+//!     tags: Vec<String>,
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, Ident, LitStr, PathArguments,
+    Type,
+};
+
+/// Derives `tantivy::schema::TypedDocument`.
+///
+/// See the [crate documentation](crate) for the supported types and attributes.
+#[proc_macro_derive(TypedDocument, attributes(tantivy))]
+pub fn derive_typed_document(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Cardinality {
+    Single,
+    Optional,
+    Multi,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ValueKind {
+    Text,
+    U64,
+    I64,
+    F64,
+    Bool,
+    Date,
+    Bytes,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    name: Option<String>,
+    string: bool,
+    tokenizer: Option<String>,
+    stored: bool,
+    indexed: bool,
+    fast: bool,
+    skip: bool,
+}
+
+struct TypedField {
+    ident: Ident,
+    name: String,
+    cardinality: Cardinality,
+    kind: ValueKind,
+    attrs: FieldAttrs,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "TypedDocument can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named_fields) = &data.fields else {
+        return Err(syn::Error::new(
+            input.span(),
+            "TypedDocument can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut typed_fields = Vec::new();
+    let mut skipped_fields = Vec::new();
+    for field in &named_fields.named {
+        let ident = field.ident.clone().expect("named fields have an ident");
+        let attrs = parse_field_attrs(field)?;
+        if attrs.skip {
+            skipped_fields.push(ident);
+            continue;
+        }
+        let (cardinality, kind) = classify(&field.ty)?;
+        if kind == ValueKind::Text && attrs.indexed {
+            return Err(syn::Error::new(
+                field.span(),
+                "`indexed` is not supported for text fields, which are always indexed: use \
+                 `text`, `string` or `tokenizer` to configure their indexing",
+            ));
+        }
+        let name = attrs
+            .name
+            .clone()
+            .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+        typed_fields.push(TypedField {
+            ident,
+            name,
+            cardinality,
+            kind,
+            attrs,
+        });
+    }
+
+    let register_fields = typed_fields.iter().map(register_field);
+    let to_document = typed_fields.iter().map(field_to_document);
+    let from_document = typed_fields
+        .iter()
+        .enumerate()
+        .map(|(idx, typed_field)| field_from_document(typed_field, &local_ident(idx)));
+    // The values are bound to generated locals rather than to the field names, which could
+    // shadow the parameters of `from_document` (e.g. a field named `doc`).
+    let idents = typed_fields.iter().map(|typed_field| &typed_field.ident);
+    let locals = (0..typed_fields.len()).map(local_ident);
+
+    let struct_ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::tantivy::schema::TypedDocument for #struct_ident #ty_generics
+        #where_clause
+        {
+            fn register_fields(schema_builder: &mut ::tantivy::schema::SchemaBuilder) {
+                #(#register_fields)*
+            }
+
+            fn to_document(
+                &self,
+                schema: &::tantivy::schema::Schema,
+            ) -> ::tantivy::Result<::tantivy::schema::TantivyDocument> {
+                let mut doc = ::tantivy::schema::TantivyDocument::default();
+                #(#to_document)*
+                Ok(doc)
+            }
+
+            fn from_document(
+                doc: &::tantivy::schema::TantivyDocument,
+                schema: &::tantivy::schema::Schema,
+            ) -> ::tantivy::Result<Self> {
+                #(#from_document)*
+                Ok(Self {
+                    #(#idents: #locals,)*
+                    #(#skipped_fields: ::core::default::Default::default(),)*
+                })
+            }
+        }
+    })
+}
+
+fn local_ident(idx: usize) -> Ident {
+    Ident::new(
+        &format!("__tantivy_field_{idx}"),
+        proc_macro2::Span::call_site(),
+    )
+}
+
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("tantivy") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                attrs.name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("tokenizer") {
+                attrs.tokenizer = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("text") {
+                attrs.string = false;
+            } else if meta.path.is_ident("string") {
+                attrs.string = true;
+            } else if meta.path.is_ident("stored") {
+                attrs.stored = true;
+            } else if meta.path.is_ident("indexed") {
+                attrs.indexed = true;
+            } else if meta.path.is_ident("fast") {
+                attrs.fast = true;
+            } else if meta.path.is_ident("skip") {
+                attrs.skip = true;
+            } else {
+                return Err(meta.error("unsupported tantivy attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn generic_argument(segment: &syn::PathSegment) -> Option<&Type> {
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+fn classify(ty: &Type) -> syn::Result<(Cardinality, ValueKind)> {
+    let segment = last_segment(ty).ok_or_else(|| unsupported_type(ty))?;
+    let inner_ty = generic_argument(segment);
+    match (segment.ident.to_string().as_str(), inner_ty) {
+        ("Option", Some(inner_ty)) => Ok((Cardinality::Optional, value_kind(inner_ty)?)),
+        ("Vec", Some(inner_ty)) if value_kind(ty).is_err() => {
+            Ok((Cardinality::Multi, value_kind(inner_ty)?))
+        }
+        _ => Ok((Cardinality::Single, value_kind(ty)?)),
+    }
+}
+
+fn value_kind(ty: &Type) -> syn::Result<ValueKind> {
+    let segment = last_segment(ty).ok_or_else(|| unsupported_type(ty))?;
+    let kind = match segment.ident.to_string().as_str() {
+        "String" => ValueKind::Text,
+        "u64" => ValueKind::U64,
+        "i64" => ValueKind::I64,
+        "f64" => ValueKind::F64,
+        "bool" => ValueKind::Bool,
+        "DateTime" => ValueKind::Date,
+        "Vec" => {
+            let is_bytes = generic_argument(segment)
+                .and_then(last_segment)
+                .map(|inner_segment| inner_segment.ident == "u8")
+                .unwrap_or(false);
+            if !is_bytes {
+                return Err(unsupported_type(ty));
+            }
+            ValueKind::Bytes
+        }
+        _ => return Err(unsupported_type(ty)),
+    };
+    Ok(kind)
+}
+
+fn unsupported_type(ty: &Type) -> syn::Error {
+    syn::Error::new(
+        ty.span(),
+        "unsupported field type, expected one of String, u64, i64, f64, bool, DateTime, Vec<u8>, \
+         optionally wrapped in an Option or a Vec",
+    )
+}
+
+fn register_field(typed_field: &TypedField) -> TokenStream2 {
+    let name = &typed_field.name;
+    let attrs = &typed_field.attrs;
+    let stored = attrs.stored.then(|| quote!(.set_stored()));
+    let indexed = attrs.indexed.then(|| quote!(.set_indexed()));
+    if typed_field.kind == ValueKind::Text {
+        let (default_tokenizer, index_option) = if attrs.string {
+            ("raw", quote!(Basic))
+        } else {
+            ("default", quote!(WithFreqsAndPositions))
+        };
+        let tokenizer = attrs.tokenizer.as_deref().unwrap_or(default_tokenizer);
+        let fast = attrs.fast.then(|| quote!(.set_fast(None)));
+        return quote! {
+            schema_builder.add_text_field(
+                #name,
+                ::tantivy::schema::TextOptions::default()
+                    .set_indexing_options(
+                        ::tantivy::schema::TextFieldIndexing::default()
+                            .set_tokenizer(#tokenizer)
+                            .set_index_option(::tantivy::schema::IndexRecordOption::#index_option),
+                    )
+                    #stored
+                    #fast,
+            );
+        };
+    }
+    let fast = attrs.fast.then(|| quote!(.set_fast()));
+    let (add_field, options) = match typed_field.kind {
+        ValueKind::U64 => (quote!(add_u64_field), quote!(NumericOptions)),
+        ValueKind::I64 => (quote!(add_i64_field), quote!(NumericOptions)),
+        ValueKind::F64 => (quote!(add_f64_field), quote!(NumericOptions)),
+        ValueKind::Bool => (quote!(add_bool_field), quote!(NumericOptions)),
+        ValueKind::Date => (quote!(add_date_field), quote!(DateOptions)),
+        ValueKind::Bytes => (quote!(add_bytes_field), quote!(BytesOptions)),
+        ValueKind::Text => unreachable!(),
+    };
+    quote! {
+        schema_builder.#add_field(
+            #name,
+            ::tantivy::schema::#options::default() #stored #indexed #fast,
+        );
+    }
+}
+
+fn field_to_document(typed_field: &TypedField) -> TokenStream2 {
+    let ident = &typed_field.ident;
+    let name = &typed_field.name;
+    let add_value = match typed_field.kind {
+        ValueKind::Text => quote!(doc.add_text(field, value)),
+        ValueKind::U64 => quote!(doc.add_u64(field, *value)),
+        ValueKind::I64 => quote!(doc.add_i64(field, *value)),
+        ValueKind::F64 => quote!(doc.add_f64(field, *value)),
+        ValueKind::Bool => quote!(doc.add_bool(field, *value)),
+        ValueKind::Date => quote!(doc.add_date(field, *value)),
+        ValueKind::Bytes => quote!(doc.add_bytes(field, value)),
+    };
+    let add_values = match typed_field.cardinality {
+        Cardinality::Single => quote! {
+            let value = &self.#ident;
+            #add_value;
+        },
+        Cardinality::Optional => quote! {
+            if let Some(value) = &self.#ident {
+                #add_value;
+            }
+        },
+        Cardinality::Multi => quote! {
+            for value in &self.#ident {
+                #add_value;
+            }
+        },
+    };
+    quote! {
+        {
+            let field = schema.get_field(#name)?;
+            #add_values
+        }
+    }
+}
+
+fn field_from_document(typed_field: &TypedField, local: &Ident) -> TokenStream2 {
+    let name = &typed_field.name;
+    let (as_value, type_name) = match typed_field.kind {
+        ValueKind::Text => (quote!(as_str(&value).map(ToString::to_string)), "text"),
+        ValueKind::U64 => (quote!(as_u64(&value)), "u64"),
+        ValueKind::I64 => (quote!(as_i64(&value)), "i64"),
+        ValueKind::F64 => (quote!(as_f64(&value)), "f64"),
+        ValueKind::Bool => (quote!(as_bool(&value)), "bool"),
+        ValueKind::Date => (quote!(as_datetime(&value)), "date"),
+        ValueKind::Bytes => (quote!(as_bytes(&value).map(<[u8]>::to_vec)), "bytes"),
+    };
+    let error_msg = format!("Field {name:?} does not hold a {type_name} value.");
+    let convert = quote! {
+        ::tantivy::schema::Value::#as_value.ok_or_else(|| {
+            ::tantivy::TantivyError::SchemaError(#error_msg.to_string())
+        })
+    };
+    let value = match typed_field.cardinality {
+        Cardinality::Single => quote! {
+            match doc.get_first(field) {
+                Some(value) => #convert?,
+                None => ::core::default::Default::default(),
+            }
+        },
+        Cardinality::Optional => quote! {
+            match doc.get_first(field) {
+                Some(value) => Some(#convert?),
+                None => None,
+            }
+        },
+        Cardinality::Multi => quote! {
+            doc.get_all(field)
+                .map(|value| #convert)
+                .collect::<::tantivy::Result<_>>()?
+        },
+    };
+    quote! {
+        let #local = {
+            let field = schema.get_field(#name)?;
+            #value
+        };
+    }
+}
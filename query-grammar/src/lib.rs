@@ -16,11 +16,34 @@ pub struct Error;
 
 /// Parse a query
 pub fn parse_query(query: &str) -> Result<UserInputAst, Error> {
-    let (_remaining, user_input_ast) = parse_to_ast(query).map_err(|_| Error)?;
-    Ok(user_input_ast)
+    parse_query_with_extensions(query, &[])
 }
 
 /// Parse a query, trying to recover from syntax errors, and giving hints toward fixing errors.
 pub fn parse_query_lenient(query: &str) -> (UserInputAst, Vec<LenientError>) {
-    parse_to_ast_lenient(query)
+    parse_query_lenient_with_extensions(query, &[])
+}
+
+/// Parse a query, in which `name(args)` is parsed as a [`UserInputLeaf::Extension`] if `name`
+/// is one of `extension_names`.
+///
+/// `AND`, `OR`, `NOT` and `IN` are operators of the query language, and are never parsed as
+/// extension names.
+pub fn parse_query_with_extensions(
+    query: &str,
+    extension_names: &[&str],
+) -> Result<UserInputAst, Error> {
+    let (_remaining, user_input_ast) = parse_to_ast(query, extension_names).map_err(|_| Error)?;
+    Ok(user_input_ast)
+}
+
+/// Parse a query leniently, in which `name(args)` is parsed as a [`UserInputLeaf::Extension`]
+/// if `name` is one of `extension_names`.
+///
+/// See [`parse_query_with_extensions`] and [`parse_query_lenient`].
+pub fn parse_query_lenient_with_extensions(
+    query: &str,
+    extension_names: &[&str],
+) -> (UserInputAst, Vec<LenientError>) {
+    parse_to_ast_lenient(query, extension_names)
 }
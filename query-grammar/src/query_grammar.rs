@@ -223,10 +223,27 @@ fn simple_term_infallible(
     }
 }
 
+/// A word ending with an unescaped `*`, such as `pre*`, is a prefix query.
+///
+/// Returns the word without its trailing `*`, and whether it is a prefix.
+fn split_prefix_wildcard(delimiter: Delimiter, mut phrase: String, prefix: bool) -> (String, bool) {
+    if delimiter == Delimiter::None
+        && !prefix
+        && phrase.len() > 1
+        && phrase.ends_with('*')
+        && !phrase.ends_with("\\*")
+    {
+        phrase.pop();
+        return (phrase, true);
+    }
+    (phrase, prefix)
+}
+
 fn term_or_phrase(inp: &str) -> IResult<&str, UserInputLeaf> {
     map(
         tuple((simple_term, fallible(slop_or_prefix_val))),
         |((delimiter, phrase), (slop, prefix))| {
+            let (phrase, prefix) = split_prefix_wildcard(delimiter, phrase, prefix);
             UserInputLiteral {
                 field_name: None,
                 phrase,
@@ -245,6 +262,7 @@ fn term_or_phrase_infallible(inp: &str) -> JResult<&str, Option<UserInputLeaf>>
         tuple_infallible((simple_term_infallible(")^"), slop_or_prefix_val)),
         |((delimiter_phrase, (slop, prefix)), errors)| {
             let leaf = if let Some((delimiter, phrase)) = delimiter_phrase {
+                let (phrase, prefix) = split_prefix_wildcard(delimiter, phrase, prefix);
                 Some(
                     UserInputLiteral {
                         field_name: None,
@@ -274,11 +292,15 @@ fn term_or_phrase_infallible(inp: &str) -> JResult<&str, Option<UserInputLeaf>>
     )(inp)
 }
 
-fn term_group(inp: &str) -> IResult<&str, UserInputAst> {
+fn term_group<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputAst> {
     map(
         tuple((
             terminated(field_name, multispace0),
-            delimited(tuple((char('('), multispace0)), ast, char(')')),
+            delimited(
+                tuple((char('('), multispace0)),
+                |inp| ast(inp, extension_names),
+                char(')'),
+            ),
         )),
         |(field_name, mut ast)| {
             ast.set_default_field(field_name);
@@ -301,16 +323,22 @@ fn term_group_precond(inp: &str) -> IResult<&str, (), ()> {
     .map_err(|e| e.map(|_| ()))
 }
 
-fn term_group_infallible(inp: &str) -> JResult<&str, UserInputAst> {
+fn term_group_infallible<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> JResult<&'a str, UserInputAst> {
     let (inp, (field_name, _, _, _)) =
         tuple((field_name, multispace0, char('('), multispace0))(inp).expect("precondition failed");
 
     let res = delimited_infallible(
         nothing,
-        map(ast_infallible, |(mut ast, errors)| {
-            ast.set_default_field(field_name.to_string());
-            (ast, errors)
-        }),
+        map(
+            |inp| ast_infallible(inp, extension_names),
+            |(mut ast, errors)| {
+                ast.set_default_field(field_name.to_string());
+                (ast, errors)
+            },
+        ),
         opt_i_err(char(')'), "expected ')'"),
     )(inp);
     res
@@ -345,15 +373,137 @@ fn exists_infallible(inp: &str) -> JResult<&str, UserInputAst> {
     Ok((inp, (exists, Vec::new())))
 }
 
-fn literal(inp: &str) -> IResult<&str, UserInputAst> {
+/// Consume a regex delimited by slashes, such as `/ab+c/`. Slashes can be escaped inside
+/// of the regex as `\/`. Return the regex with escaped slashes already interpreted.
+fn regex(inp: &str) -> IResult<&str, String> {
+    map(
+        terminated(
+            delimited(
+                char('/'),
+                recognize(many0(alt((preceded(char('\\'), anychar), none_of("/\\"))))),
+                char('/'),
+            ),
+            // a regex can't be followed by anything but the end of a clause
+            peek(alt((multispace1, eof, recognize(one_of(")^"))))),
+        ),
+        |pattern: &str| pattern.replace("\\/", "/"),
+    )(inp)
+}
+
+fn regex_leaf(inp: &str) -> IResult<&str, UserInputLeaf> {
+    map(
+        tuple((field_name, multispace0, regex)),
+        |(field, _, pattern)| UserInputLeaf::Regex { field, pattern },
+    )(inp)
+}
+
+fn regex_precond(inp: &str) -> IResult<&str, (), ()> {
+    value((), peek(regex_leaf))(inp).map_err(|e| e.map(|_| ()))
+}
+
+fn regex_infallible(inp: &str) -> JResult<&str, UserInputAst> {
+    let (inp, regex) = regex_leaf(inp).expect("precondition failed");
+    Ok((inp, (regex.into(), Vec::new())))
+}
+
+/// Operators of the query language, which are never parsed as extension names.
+const RESERVED_EXTENSION_NAMES: &[&str] = &["AND", "OR", "NOT", "IN"];
+
+fn is_extension_name(name: &str, extension_names: &[&str]) -> bool {
+    !RESERVED_EXTENSION_NAMES.contains(&name) && extension_names.contains(&name)
+}
+
+/// Consume the arguments of an extension, delimited by parentheses, such as `(a, b, 5)`.
+/// Nested parentheses must be balanced, unless they are escaped with a `\`. Return the arguments
+/// verbatim.
+fn extension_args(inp: &str) -> IResult<&str, &str> {
+    let mut chars = inp.char_indices();
+    if !matches!(chars.next(), Some((_, '('))) {
+        return Err(nom::Err::Error(Error::new(inp, ErrorKind::Char)));
+    }
+    let mut depth = 0usize;
+    let mut in_escape = false;
+    for (pos, c) in chars {
+        if in_escape {
+            in_escape = false;
+            continue;
+        }
+        match c {
+            '\\' => in_escape = true,
+            '(' => depth += 1,
+            ')' if depth == 0 => return Ok((&inp[pos + 1..], &inp[1..pos])),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    Err(nom::Err::Error(Error::new(inp, ErrorKind::Char)))
+}
+
+/// Consume an extension such as `near(a, b, 5)`: a name immediately followed by its arguments.
+/// Only the names in `extension_names` are recognized: anything else is left to the other
+/// parsers, e.g. `iphone(2020)` remains a term followed by a group.
+fn extension<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputLeaf> {
+    let name = verify(
+        recognize(tuple((
+            satisfy(|c| c.is_ascii_alphabetic() || c == '_'),
+            many0(satisfy(|c| c.is_ascii_alphanumeric() || c == '_')),
+        ))),
+        |name: &str| is_extension_name(name, extension_names),
+    );
+    map(
+        terminated(
+            tuple((name, extension_args)),
+            // an extension can't be followed by anything but the end of a clause
+            peek(alt((multispace1, eof, recognize(one_of(")^"))))),
+        ),
+        |(name, args): (&str, &str)| UserInputLeaf::Extension {
+            field: None,
+            name: name.to_string(),
+            args: args.to_string(),
+        },
+    )(inp)
+}
+
+fn extension_leaf<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputLeaf> {
+    map(
+        tuple((opt(field_name), multispace0, |inp| {
+            extension(inp, extension_names)
+        })),
+        |(field_name, _, leaf)| leaf.set_field(field_name),
+    )(inp)
+}
+
+fn extension_precond<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, (), ()> {
+    value((), peek(|inp| extension_leaf(inp, extension_names)))(inp).map_err(|e| e.map(|_| ()))
+}
+
+fn extension_infallible<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> JResult<&'a str, UserInputAst> {
+    let (inp, extension) = extension_leaf(inp, extension_names).expect("precondition failed");
+    Ok((inp, (extension.into(), Vec::new())))
+}
+
+fn literal<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputAst> {
     // * alone is already parsed by our caller, so if `exists` succeed, we can be confident
     // something (a field name) got parsed before
     alt((
+        map(regex_leaf, UserInputAst::from),
         map(
-            tuple((opt(field_name), alt((range, set, exists, term_or_phrase)))),
+            tuple((
+                opt(field_name),
+                alt((
+                    range,
+                    set,
+                    exists,
+                    |inp| extension(inp, extension_names),
+                    term_or_phrase,
+                )),
+            )),
             |(field_name, leaf): (Option<String>, UserInputLeaf)| leaf.set_field(field_name).into(),
         ),
-        term_group,
+        |inp| term_group(inp, extension_names),
     ))(inp)
 }
 
@@ -396,17 +546,34 @@ fn literal_no_group_infallible(inp: &str) -> JResult<&str, Option<UserInputAst>>
     )(inp)
 }
 
-fn literal_infallible(inp: &str) -> JResult<&str, Option<UserInputAst>> {
+fn literal_infallible<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> JResult<&'a str, Option<UserInputAst>> {
     alt_infallible(
         (
             (
                 term_group_precond,
-                map(term_group_infallible, |(group, errs)| (Some(group), errs)),
+                map(
+                    |inp| term_group_infallible(inp, extension_names),
+                    |(group, errs)| (Some(group), errs),
+                ),
             ),
             (
                 exists_precond,
                 map(exists_infallible, |(exists, errs)| (Some(exists), errs)),
             ),
+            (
+                regex_precond,
+                map(regex_infallible, |(regex, errs)| (Some(regex), errs)),
+            ),
+            (
+                |inp| extension_precond(inp, extension_names),
+                map(
+                    |inp| extension_infallible(inp, extension_names),
+                    |(extension, errs)| (Some(extension), errs),
+                ),
+            ),
         ),
         literal_no_group_infallible,
     )(inp)
@@ -676,16 +843,24 @@ fn negate(expr: UserInputAst) -> UserInputAst {
     expr.unary(Occur::MustNot)
 }
 
-fn leaf(inp: &str) -> IResult<&str, UserInputAst> {
+fn leaf<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputAst> {
     alt((
-        delimited(char('('), ast, char(')')),
+        delimited(char('('), |inp| ast(inp, extension_names), char(')')),
         map(char('*'), |_| UserInputAst::from(UserInputLeaf::All)),
-        map(preceded(tuple((tag("NOT"), multispace1)), leaf), negate),
-        literal,
+        map(
+            preceded(tuple((tag("NOT"), multispace1)), |inp| {
+                leaf(inp, extension_names)
+            }),
+            negate,
+        ),
+        |inp| literal(inp, extension_names),
     ))(inp)
 }
 
-fn leaf_infallible(inp: &str) -> JResult<&str, Option<UserInputAst>> {
+fn leaf_infallible<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> JResult<&'a str, Option<UserInputAst>> {
     alt_infallible(
         (
             (
@@ -693,7 +868,7 @@ fn leaf_infallible(inp: &str) -> JResult<&str, Option<UserInputAst>> {
                 map(
                     delimited_infallible(
                         nothing,
-                        ast_infallible,
+                        |inp| ast_infallible(inp, extension_names),
                         opt_i_err(char(')'), "expected ')'"),
                     ),
                     |(ast, errs)| (Some(ast), errs),
@@ -709,12 +884,15 @@ fn leaf_infallible(inp: &str) -> JResult<&str, Option<UserInputAst>> {
                 value((), tag("NOT ")),
                 delimited_infallible(
                     space0_infallible,
-                    map(leaf_infallible, |(res, err)| (res.map(negate), err)),
+                    map(
+                        |inp| leaf_infallible(inp, extension_names),
+                        |(res, err)| (res.map(negate), err),
+                    ),
                     nothing,
                 ),
             ),
         ),
-        literal_infallible,
+        |inp| literal_infallible(inp, extension_names),
     )(inp)
 }
 
@@ -731,9 +909,9 @@ fn boost(inp: &str) -> JResult<&str, Option<f64>> {
     opt_i(preceded(char('^'), positive_float_number))(inp)
 }
 
-fn boosted_leaf(inp: &str) -> IResult<&str, UserInputAst> {
+fn boosted_leaf<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputAst> {
     map(
-        tuple((leaf, fallible(boost))),
+        tuple((|inp| leaf(inp, extension_names), fallible(boost))),
         |(leaf, boost_opt)| match boost_opt {
             Some(boost) if (boost - 1.0).abs() > f64::EPSILON => {
                 UserInputAst::Boost(Box::new(leaf), boost)
@@ -743,9 +921,12 @@ fn boosted_leaf(inp: &str) -> IResult<&str, UserInputAst> {
     )(inp)
 }
 
-fn boosted_leaf_infallible(inp: &str) -> JResult<&str, Option<UserInputAst>> {
+fn boosted_leaf_infallible<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> JResult<&'a str, Option<UserInputAst>> {
     map(
-        tuple_infallible((leaf_infallible, boost)),
+        tuple_infallible((|inp| leaf_infallible(inp, extension_names), boost)),
         |((leaf, boost_opt), error)| match boost_opt {
             Some(boost) if (boost - 1.0).abs() > f64::EPSILON => (
                 leaf.map(|leaf| UserInputAst::Boost(Box::new(leaf), boost)),
@@ -763,19 +944,25 @@ fn occur_symbol(inp: &str) -> JResult<&str, Option<Occur>> {
     )))(inp)
 }
 
-fn occur_leaf(inp: &str) -> IResult<&str, (Option<Occur>, UserInputAst)> {
-    tuple((fallible(occur_symbol), boosted_leaf))(inp)
+fn occur_leaf<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> IResult<&'a str, (Option<Occur>, UserInputAst)> {
+    tuple((fallible(occur_symbol), |inp| {
+        boosted_leaf(inp, extension_names)
+    }))(inp)
 }
 
 #[allow(clippy::type_complexity)]
-fn operand_occur_leaf_infallible(
-    inp: &str,
-) -> JResult<&str, (Option<BinaryOperand>, Option<Occur>, Option<UserInputAst>)> {
+fn operand_occur_leaf_infallible<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> JResult<&'a str, (Option<BinaryOperand>, Option<Occur>, Option<UserInputAst>)> {
     // TODO maybe this should support multiple chained AND/OR, and "fuse" them?
     tuple_infallible((
         delimited_infallible(nothing, opt_i(binary_operand), space0_infallible),
         occur_symbol,
-        boosted_leaf_infallible,
+        |inp| boosted_leaf_infallible(inp, extension_names),
     ))(inp)
 }
 
@@ -927,32 +1114,43 @@ fn aggregate_infallible_expressions(
     }
 }
 
-fn operand_leaf(inp: &str) -> IResult<&str, (Option<BinaryOperand>, Option<Occur>, UserInputAst)> {
+#[allow(clippy::type_complexity)]
+fn operand_leaf<'a>(
+    inp: &'a str,
+    extension_names: &[&str],
+) -> IResult<&'a str, (Option<BinaryOperand>, Option<Occur>, UserInputAst)> {
     map(
         tuple((
             terminated(opt(binary_operand), multispace0),
-            terminated(occur_leaf, multispace0),
+            terminated(|inp| occur_leaf(inp, extension_names), multispace0),
         )),
         |(operand, (occur, ast))| (operand, occur, ast),
     )(inp)
 }
 
-fn ast(inp: &str) -> IResult<&str, UserInputAst> {
+fn ast<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputAst> {
     let boolean_expr = map_res(
-        separated_pair(occur_leaf, multispace1, many1(operand_leaf)),
+        separated_pair(
+            |inp| occur_leaf(inp, extension_names),
+            multispace1,
+            many1(|inp| operand_leaf(inp, extension_names)),
+        ),
         |(left, right)| aggregate_binary_expressions(left, right),
     );
-    let single_leaf = map(occur_leaf, |(occur, ast)| {
-        if occur == Some(Occur::MustNot) {
-            ast.unary(Occur::MustNot)
-        } else {
-            ast
-        }
-    });
+    let single_leaf = map(
+        |inp| occur_leaf(inp, extension_names),
+        |(occur, ast)| {
+            if occur == Some(Occur::MustNot) {
+                ast.unary(Occur::MustNot)
+            } else {
+                ast
+            }
+        },
+    );
     delimited(multispace0, alt((boolean_expr, single_leaf)), multispace0)(inp)
 }
 
-fn ast_infallible(inp: &str) -> JResult<&str, UserInputAst> {
+fn ast_infallible<'a>(inp: &'a str, extension_names: &[&str]) -> JResult<&'a str, UserInputAst> {
     // ast() parse either `term AND term OR term` or `+term term -term`
     // both are locally ambiguous, and as we allow error, it's hard to permit backtracking.
     // Instead, we allow a mix of both syntaxes, trying to make sense of what a user meant.
@@ -961,7 +1159,9 @@ fn ast_infallible(inp: &str) -> JResult<&str, UserInputAst> {
     // even if it's not very sensical.
 
     let expression = map(
-        separated_list_infallible(space1_infallible, operand_occur_leaf_infallible),
+        separated_list_infallible(space1_infallible, |inp| {
+            operand_occur_leaf_infallible(inp, extension_names)
+        }),
         |(leaf, mut err)| {
             let (res, mut err2) = aggregate_infallible_expressions(leaf);
             err.append(&mut err2);
@@ -972,17 +1172,24 @@ fn ast_infallible(inp: &str) -> JResult<&str, UserInputAst> {
     delimited_infallible(space0_infallible, expression, space0_infallible)(inp)
 }
 
-pub fn parse_to_ast(inp: &str) -> IResult<&str, UserInputAst> {
-    map(delimited(multispace0, opt(ast), eof), |opt_ast| {
-        rewrite_ast(opt_ast.unwrap_or_else(UserInputAst::empty_query))
-    })(inp)
+/// Parses a query, recognizing the extensions `name(args)` whose name is in `extension_names`.
+pub fn parse_to_ast<'a>(inp: &'a str, extension_names: &[&str]) -> IResult<&'a str, UserInputAst> {
+    map(
+        delimited(multispace0, opt(|inp| ast(inp, extension_names)), eof),
+        |opt_ast| rewrite_ast(opt_ast.unwrap_or_else(UserInputAst::empty_query)),
+    )(inp)
 }
 
-pub fn parse_to_ast_lenient(query_str: &str) -> (UserInputAst, Vec<LenientError>) {
+/// Parses a query leniently, recognizing the extensions `name(args)` whose name is in
+/// `extension_names`.
+pub fn parse_to_ast_lenient(
+    query_str: &str,
+    extension_names: &[&str],
+) -> (UserInputAst, Vec<LenientError>) {
     if query_str.trim().is_empty() {
         return (UserInputAst::Clause(Vec::new()), Vec::new());
     }
-    let (left, (res, mut errors)) = ast_infallible(query_str).unwrap();
+    let (left, (res, mut errors)) = ast_infallible(query_str, extension_names).unwrap();
     if !left.trim().is_empty() {
         errors.push(LenientErrorInternal {
             pos: left.len(),
@@ -1080,11 +1287,20 @@ mod test {
 
     #[track_caller]
     fn test_parse_query_to_ast_helper(query: &str, expected: &str) {
-        let query_strict = parse_to_ast(query).unwrap().1;
+        test_parse_query_with_extensions_to_ast_helper(query, &[], expected);
+    }
+
+    #[track_caller]
+    fn test_parse_query_with_extensions_to_ast_helper(
+        query: &str,
+        extension_names: &[&str],
+        expected: &str,
+    ) {
+        let query_strict = parse_to_ast(query, extension_names).unwrap().1;
         let query_strict_str = format!("{query_strict:?}");
         assert_eq!(query_strict_str, expected, "strict parser failed");
 
-        let (query_lenient, errs) = parse_to_ast_lenient(query);
+        let (query_lenient, errs) = parse_to_ast_lenient(query, extension_names);
         let query_lenient_str = format!("{query_lenient:?}");
         assert_eq!(query_lenient_str, expected, "lenient parser failed");
         assert!(
@@ -1096,11 +1312,11 @@ mod test {
     #[track_caller]
     fn test_is_parse_err(query: &str, lenient_expected: &str) {
         assert!(
-            parse_to_ast(query).is_err(),
+            parse_to_ast(query, &[]).is_err(),
             "strict parser succeeded where an error was expected."
         );
 
-        let (query_lenient, errs) = parse_to_ast_lenient(query);
+        let (query_lenient, errs) = parse_to_ast_lenient(query, &[]);
         let query_lenient_str = format!("{query_lenient:?}");
         assert_eq!(query_lenient_str, lenient_expected, "lenient parser failed");
         assert!(!errs.is_empty());
@@ -1215,7 +1431,7 @@ mod test {
 
     #[test]
     fn test_occur_leaf() {
-        let (_, (occur, ast)) = super::occur_leaf("+abc").unwrap();
+        let (_, (occur, ast)) = super::occur_leaf("+abc", &[]).unwrap();
         assert_eq!(occur, Some(Occur::Must));
         assert_eq!(format!("{ast:?}"), "abc");
     }
@@ -1278,7 +1494,7 @@ mod test {
     #[test]
     fn test_range_parser() {
         // testing the range() parser separately
-        let res = literal("title: <hello")
+        let res = literal("title: <hello", &[])
             .expect("Cannot parse flexible bound word")
             .1;
         let expected = UserInputLeaf::Range {
@@ -1287,7 +1503,7 @@ mod test {
             upper: UserInputBound::Exclusive("hello".to_string()),
         }
         .into();
-        let res2 = literal("title:{* TO hello}")
+        let res2 = literal("title:{* TO hello}", &[])
             .expect("Cannot parse ununbounded to word")
             .1;
         assert_eq!(res, expected);
@@ -1299,10 +1515,10 @@ mod test {
             upper: UserInputBound::Unbounded,
         }
         .into();
-        let res3 = literal("weight: >=71.2")
+        let res3 = literal("weight: >=71.2", &[])
             .expect("Cannot parse flexible bound float")
             .1;
-        let res4 = literal("weight:[71.2 TO *}")
+        let res4 = literal("weight:[71.2 TO *}", &[])
             .expect("Cannot parse float to unbounded")
             .1;
         assert_eq!(res3, expected_weight);
@@ -1314,9 +1530,12 @@ mod test {
             upper: UserInputBound::Inclusive("2021-08-02T18:54:42+02:30".to_string()),
         }
         .into();
-        let res5 = literal("date_field:{2015-08-02T18:54:42Z TO 2021-08-02T18:54:42+02:30]")
-            .expect("Cannot parse date range")
-            .1;
+        let res5 = literal(
+            "date_field:{2015-08-02T18:54:42Z TO 2021-08-02T18:54:42+02:30]",
+            &[],
+        )
+        .expect("Cannot parse date range")
+        .1;
         assert_eq!(res5, expected_dates);
 
         let expected_flexible_dates = UserInputLeaf::Range {
@@ -1326,7 +1545,7 @@ mod test {
         }
         .into();
 
-        let res6 = literal("date_field: <=2021-08-02T18:54:42.12345+02:30")
+        let res6 = literal("date_field: <=2021-08-02T18:54:42.12345+02:30", &[])
             .expect("Cannot parse date range")
             .1;
         assert_eq!(res6, expected_flexible_dates);
@@ -1337,8 +1556,10 @@ mod test {
             upper: UserInputBound::Unbounded,
         }
         .into();
-        let res1 = literal("ip: >=::1").expect("Cannot parse ip v6 format").1;
-        let res2 = literal("ip:[::1 TO *}")
+        let res1 = literal("ip: >=::1", &[])
+            .expect("Cannot parse ip v6 format")
+            .1;
+        let res2 = literal("ip:[::1 TO *}", &[])
             .expect("Cannot parse ip v6 format")
             .1;
         assert_eq!(res1, expected_weight);
@@ -1351,7 +1572,7 @@ mod test {
             upper: UserInputBound::Exclusive("::0.0.0.52".to_string()),
         }
         .into();
-        let res1 = literal("ip:[::0.0.0.50 TO ::0.0.0.52}")
+        let res1 = literal("ip:[::0.0.0.50 TO ::0.0.0.52}", &[])
             .expect("Cannot parse ip v6 format")
             .1;
         assert_eq!(res1, expected_weight);
@@ -1362,14 +1583,14 @@ mod test {
         let literal = |query| literal_infallible(query).unwrap().1 .0.unwrap();
 
         // same tests as non-lenient
-        let res = literal("title: <hello");
+        let res = literal("title: <hello", &[]);
         let expected = UserInputLeaf::Range {
             field: Some("title".to_string()),
             lower: UserInputBound::Unbounded,
             upper: UserInputBound::Exclusive("hello".to_string()),
         }
         .into();
-        let res2 = literal("title:{* TO hello}");
+        let res2 = literal("title:{* TO hello}", &[]);
         assert_eq!(res, expected);
         assert_eq!(res2, expected);
 
@@ -1379,8 +1600,8 @@ mod test {
             upper: UserInputBound::Unbounded,
         }
         .into();
-        let res3 = literal("weight: >=71.2");
-        let res4 = literal("weight:[71.2 TO *}");
+        let res3 = literal("weight: >=71.2", &[]);
+        let res4 = literal("weight:[71.2 TO *}", &[]);
         assert_eq!(res3, expected_weight);
         assert_eq!(res4, expected_weight);
 
@@ -1390,7 +1611,10 @@ mod test {
             upper: UserInputBound::Inclusive("2021-08-02T18:54:42+02:30".to_string()),
         }
         .into();
-        let res5 = literal("date_field:{2015-08-02T18:54:42Z TO 2021-08-02T18:54:42+02:30]");
+        let res5 = literal(
+            "date_field:{2015-08-02T18:54:42Z TO 2021-08-02T18:54:42+02:30]",
+            &[],
+        );
         assert_eq!(res5, expected_dates);
 
         let expected_flexible_dates = UserInputLeaf::Range {
@@ -1400,7 +1624,7 @@ mod test {
         }
         .into();
 
-        let res6 = literal("date_field: <=2021-08-02T18:54:42.12345+02:30");
+        let res6 = literal("date_field: <=2021-08-02T18:54:42.12345+02:30", &[]);
         assert_eq!(res6, expected_flexible_dates);
         // IP Range Unbounded
         let expected_weight = UserInputLeaf::Range {
@@ -1409,8 +1633,8 @@ mod test {
             upper: UserInputBound::Unbounded,
         }
         .into();
-        let res1 = literal("ip: >=::1");
-        let res2 = literal("ip:[::1 TO *}");
+        let res1 = literal("ip: >=::1", &[]);
+        let res2 = literal("ip:[::1 TO *}", &[]);
         assert_eq!(res1, expected_weight);
         assert_eq!(res2, expected_weight);
 
@@ -1421,7 +1645,7 @@ mod test {
             upper: UserInputBound::Exclusive("::0.0.0.52".to_string()),
         }
         .into();
-        let res1 = literal("ip:[::0.0.0.50 TO ::0.0.0.52}");
+        let res1 = literal("ip:[::0.0.0.50 TO ::0.0.0.52}", &[]);
         assert_eq!(res1, expected_weight);
 
         // additional tests
@@ -1431,9 +1655,9 @@ mod test {
             upper: UserInputBound::Inclusive("::0.0.0.52".to_string()),
         }
         .into();
-        let res1 = literal("ip:[::0.0.0.50 TO ::0.0.0.52");
-        let res2 = literal("ip:[::0.0.0.50 ::0.0.0.52");
-        let res3 = literal("ip:[::0.0.0.50 ::0.0.0.52 AND ...");
+        let res1 = literal("ip:[::0.0.0.50 TO ::0.0.0.52", &[]);
+        let res2 = literal("ip:[::0.0.0.50 ::0.0.0.52", &[]);
+        let res3 = literal("ip:[::0.0.0.50 ::0.0.0.52 AND ...", &[]);
         assert_eq!(res1, expected_weight);
         assert_eq!(res2, expected_weight);
         assert_eq!(res3, expected_weight);
@@ -1444,9 +1668,9 @@ mod test {
             upper: UserInputBound::Unbounded,
         }
         .into();
-        let res1 = literal("ip:[::0.0.0.50 TO ");
-        let res2 = literal("ip:[::0.0.0.50 TO");
-        let res3 = literal("ip:[::0.0.0.50");
+        let res1 = literal("ip:[::0.0.0.50 TO ", &[]);
+        let res2 = literal("ip:[::0.0.0.50 TO", &[]);
+        let res3 = literal("ip:[::0.0.0.50", &[]);
         assert_eq!(res1, expected_weight);
         assert_eq!(res2, expected_weight);
         assert_eq!(res3, expected_weight);
@@ -1457,9 +1681,9 @@ mod test {
             upper: UserInputBound::Unbounded,
         }
         .into();
-        let res1 = literal("ip:[ ");
-        let res2 = literal("ip:{ ");
-        let res3 = literal("ip:[");
+        let res1 = literal("ip:[ ", &[]);
+        let res2 = literal("ip:{ ", &[]);
+        let res3 = literal("ip:[", &[]);
         assert_eq!(res1, expected_weight);
         assert_eq!(res2, expected_weight);
         assert_eq!(res3, expected_weight);
@@ -1624,10 +1848,89 @@ mod test {
         // an exist followed by default term being b
         test_is_parse_err("a:*b", "(*\"a\":* *b)");
 
-        // this is a term query (not a phrase prefix)
+        // this is a term prefix query (not a phrase prefix)
         test_parse_query_to_ast_helper("a:b*", "\"a\":b*");
     }
 
+    #[test]
+    fn test_prefix_query() {
+        let prefix_literal = |query: &str| match parse_to_ast(query, &[]).unwrap().1 {
+            UserInputAst::Leaf(leaf) => match *leaf {
+                UserInputLeaf::Literal(literal) => (literal.phrase, literal.prefix),
+                _ => panic!("expected a literal"),
+            },
+            _ => panic!("expected a leaf"),
+        };
+        assert_eq!(prefix_literal("a:pre*"), ("pre".to_string(), true));
+        assert_eq!(prefix_literal("pre*"), ("pre".to_string(), true));
+        assert_eq!(prefix_literal("a:b*c"), ("b*c".to_string(), false));
+        assert_eq!(prefix_literal(r#"a:b\*"#), (r#"b\*"#.to_string(), false));
+        assert_eq!(prefix_literal("a:\"b\"*"), ("b".to_string(), true));
+        test_parse_query_to_ast_helper("a:pre* b", "(*\"a\":pre* *b)");
+    }
+
+    #[test]
+    fn test_regex_query() {
+        test_parse_query_to_ast_helper("a:/ab+c/", "\"a\":/ab+c/");
+        test_parse_query_to_ast_helper("a: /ab+c/", "\"a\":/ab+c/");
+        test_parse_query_to_ast_helper("a:/[a-z]{2,3}/ b", "(*\"a\":/[a-z]{2,3}/ *b)");
+        test_parse_query_to_ast_helper(r#"a:/usr\/bin/"#, r#""a":/usr\/bin/"#);
+        test_parse_query_to_ast_helper(r#"a:/\d+/"#, r#""a":/\d+/"#);
+        test_parse_query_to_ast_helper("a:/ab+c/^2", "(\"a\":/ab+c/)^2");
+        test_parse_query_to_ast_helper("-a:/ab+c/ b", "(-\"a\":/ab+c/ *b)");
+        // a regex requires a field
+        test_parse_query_to_ast_helper("/ab+c/", "/ab+c/");
+        // this is not a regex
+        test_parse_query_to_ast_helper("a:/path/to", "\"a\":/path/to");
+    }
+
+    #[test]
+    fn test_extension_query() {
+        let extension_names = &["near", "circle", "f"];
+        let test_helper = |query: &str, expected: &str| {
+            test_parse_query_with_extensions_to_ast_helper(query, extension_names, expected)
+        };
+        test_helper("near(a,b,5)", "near(a,b,5)");
+        test_helper(
+            "geo:circle(48.8, 2.3, 10km)",
+            "\"geo\":circle(48.8, 2.3, 10km)",
+        );
+        test_helper("f(a (b) \\))^2 c", "(*(f(a (b) \\)))^2 *c)");
+        test_helper("-near(a,b,5) c", "(-near(a,b,5) *c)");
+        test_helper(
+            "title:(near(a,b,5) c)",
+            "(*\"title\":near(a,b,5) *\"title\":c)",
+        );
+        // an extension can't be followed by a word, nor have unbalanced parentheses
+        assert!(parse_to_ast("f(a)b", extension_names).is_err());
+        assert!(parse_to_ast("f(a", extension_names).is_err());
+    }
+
+    #[test]
+    fn test_extension_query_unknown_names() {
+        let parse_both = |query: &str, extension_names: &[&str]| {
+            let strict = format!(
+                "{:?}",
+                parse_to_ast(query, extension_names).map(|(_, ast)| ast)
+            );
+            let (lenient, errs) = parse_to_ast_lenient(query, extension_names);
+            (strict, format!("{lenient:?}"), errs.len())
+        };
+        for query in [
+            "iphone(2020)",
+            "a AND(b OR c)",
+            "a OR(b)",
+            "NOT(x) y",
+            "field: IN(a)",
+        ] {
+            let without_extensions = parse_both(query, &[]);
+            // Unknown names and operators are parsed as if there was no extension at all.
+            let with_extensions = parse_both(query, &["near", "AND", "OR", "NOT", "IN"]);
+            assert_eq!(with_extensions, without_extensions, "{query}");
+        }
+        test_parse_query_with_extensions_to_ast_helper("iphone(2020)", &["iphone"], "iphone(2020)");
+    }
+
     #[test]
     fn test_not_queries_are_consistent() {
         test_parse_query_to_ast_helper("tata -toto", "(*tata -toto)");
@@ -19,6 +19,18 @@ pub enum UserInputLeaf {
     Exists {
         field: String,
     },
+    Regex {
+        field: String,
+        pattern: String,
+    },
+    /// A custom syntax `name(args)`, interpreted by the handler registered for `name`.
+    ///
+    /// The arguments are kept verbatim.
+    Extension {
+        field: Option<String>,
+        name: String,
+        args: String,
+    },
 }
 
 impl UserInputLeaf {
@@ -42,6 +54,18 @@ impl UserInputLeaf {
             UserInputLeaf::Exists { field: _ } => UserInputLeaf::Exists {
                 field: field.expect("Exist query without a field isn't allowed"),
             },
+            UserInputLeaf::Regex {
+                field: previous_field,
+                pattern,
+            } => UserInputLeaf::Regex {
+                field: field.unwrap_or(previous_field),
+                pattern,
+            },
+            UserInputLeaf::Extension {
+                field: _,
+                name,
+                args,
+            } => UserInputLeaf::Extension { field, name, args },
         }
     }
 
@@ -61,6 +85,9 @@ impl UserInputLeaf {
             UserInputLeaf::Set { ref mut field, .. } if field.is_none() => {
                 *field = Some(default_field)
             }
+            UserInputLeaf::Extension { ref mut field, .. } if field.is_none() => {
+                *field = Some(default_field)
+            }
             _ => (), // field was already set, do nothing
         }
     }
@@ -103,6 +130,17 @@ impl Debug for UserInputLeaf {
             UserInputLeaf::Exists { field } => {
                 write!(formatter, "\"{field}\":*")
             }
+            UserInputLeaf::Regex { field, pattern } => {
+                // TODO properly escape field (in case of \")
+                write!(formatter, "\"{field}\":/{}/", pattern.replace('/', "\\/"))
+            }
+            UserInputLeaf::Extension { field, name, args } => {
+                if let Some(ref field) = field {
+                    // TODO properly escape field (in case of \")
+                    write!(formatter, "\"{field}\":")?;
+                }
+                write!(formatter, "{name}({args})")
+            }
         }
     }
 }
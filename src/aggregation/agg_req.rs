@@ -102,6 +102,15 @@ impl Aggregation {
         );
         fast_field_names.extend(get_fast_field_names(&self.sub_aggregation));
     }
+
+    fn requires_scoring(&self) -> bool {
+        let sorts_by_score = self
+            .agg
+            .as_top_hits()
+            .map(TopHitsAggregationReq::sorts_by_score)
+            .unwrap_or(false);
+        sorts_by_score || requires_scoring(&self.sub_aggregation)
+    }
 }
 
 /// Extract all fast field names used in the tree.
@@ -113,6 +122,12 @@ pub fn get_fast_field_names(aggs: &Aggregations) -> HashSet<String> {
     fast_field_names
 }
 
+/// Returns true if an aggregation of the tree needs the scores of the documents, e.g. a
+/// `top_hits` aggregation sorting by `_score`.
+pub(crate) fn requires_scoring(aggs: &Aggregations) -> bool {
+    aggs.values().any(Aggregation::requires_scoring)
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 /// All aggregation types.
 pub enum AggregationVariants {
@@ -2,6 +2,7 @@
 
 use std::collections::HashMap;
 use std::io;
+use std::sync::{Arc, Mutex};
 
 use columnar::{Column, ColumnBlockAccessor, ColumnType, DynamicColumn, StrColumn};
 
@@ -18,7 +19,7 @@ use super::segment_agg_result::AggregationLimits;
 use super::VecWithNames;
 use crate::aggregation::{f64_to_fastfield_u64, Key};
 use crate::index::SegmentReader;
-use crate::SegmentOrdinal;
+use crate::{DocId, Score, SegmentOrdinal};
 
 #[derive(Default)]
 pub(crate) struct AggregationsWithAccessor {
@@ -35,6 +36,32 @@ impl AggregationsWithAccessor {
     }
 }
 
+/// The scores of the block of documents currently flushed to the aggregations of a segment.
+///
+/// Only recorded if an aggregation of the request sorts by score, see
+/// [`requires_scoring`](super::agg_req::requires_scoring). The handle is shared by all of the
+/// aggregations of the segment.
+#[derive(Clone, Default)]
+pub(crate) struct DocScores(Arc<Mutex<Vec<(DocId, Score)>>>);
+
+impl DocScores {
+    /// Replaces the recorded scores by the scores of the next block of documents, and clears
+    /// `block_scores` so that it can be reused. Documents are expected in increasing doc id order.
+    pub fn set_block(&self, block_scores: &mut Vec<(DocId, Score)>) {
+        std::mem::swap(&mut *self.0.lock().unwrap(), block_scores);
+        block_scores.clear();
+    }
+
+    /// Returns the score of a document, if it was recorded.
+    pub fn score(&self, doc: DocId) -> Option<Score> {
+        let doc_scores = self.0.lock().unwrap();
+        let idx = doc_scores
+            .binary_search_by_key(&doc, |&(doc, _)| doc)
+            .ok()?;
+        Some(doc_scores[idx].1)
+    }
+}
+
 pub struct AggregationWithAccessor {
     pub(crate) segment_ordinal: SegmentOrdinal,
     /// In general there can be buckets without fast field access, e.g. buckets that are created
@@ -59,6 +86,8 @@ pub struct AggregationWithAccessor {
     /// Map field names to all associated column accessors.
     /// This field is used for `docvalue_fields`, which is currently only supported for `top_hits`.
     pub(crate) value_accessors: HashMap<String, Vec<DynamicColumn>>,
+    /// Scores of the collected documents, used by `top_hits` to sort by score.
+    pub(crate) doc_scores: DocScores,
     pub(crate) agg: Aggregation,
 }
 
@@ -70,6 +99,7 @@ impl AggregationWithAccessor {
         reader: &SegmentReader,
         segment_ordinal: SegmentOrdinal,
         limits: AggregationLimits,
+        doc_scores: &DocScores,
     ) -> crate::Result<Vec<AggregationWithAccessor>> {
         let mut agg = agg.clone();
 
@@ -89,7 +119,9 @@ impl AggregationWithAccessor {
                     reader,
                     segment_ordinal,
                     &limits,
+                    doc_scores,
                 )?,
+                doc_scores: doc_scores.clone(),
                 agg: agg.clone(),
                 limits: limits.new_guard(),
                 missing_value_for_accessor: None,
@@ -118,7 +150,9 @@ impl AggregationWithAccessor {
                     reader,
                     segment_ordinal,
                     &limits,
+                    doc_scores,
                 )?,
+                doc_scores: doc_scores.clone(),
                 agg: agg.clone(),
                 limits: limits.new_guard(),
                 missing_value_for_accessor: None,
@@ -252,7 +286,9 @@ impl AggregationWithAccessor {
                             reader,
                             segment_ordinal,
                             &limits,
+                            doc_scores,
                         )?,
+                        doc_scores: doc_scores.clone(),
                         agg: agg.clone(),
                         str_dict_column: str_dict_column.clone(),
                         limits: limits.new_guard(),
@@ -366,6 +402,7 @@ pub(crate) fn get_aggs_with_segment_accessor_and_validate(
     reader: &SegmentReader,
     segment_ordinal: SegmentOrdinal,
     limits: &AggregationLimits,
+    doc_scores: &DocScores,
 ) -> crate::Result<AggregationsWithAccessor> {
     let mut aggss = Vec::new();
     for (key, agg) in aggs.iter() {
@@ -375,6 +412,7 @@ pub(crate) fn get_aggs_with_segment_accessor_and_validate(
             reader,
             segment_ordinal,
             limits.clone(),
+            doc_scores,
         )?;
         for agg in aggs {
             aggss.push((key.to_string(), agg));
@@ -548,6 +548,102 @@ fn test_aggregation_level2_single_segment_distributed_collector() -> crate::Resu
     test_aggregation_level2(true, true)
 }
 
+#[test]
+fn test_aggregation_level3_merge_across_segments_and_indexes() -> crate::Result<()> {
+    let segment_and_values: Vec<Vec<(f64, String)>> = (0..3)
+        .map(|segment_ord| {
+            (0..50)
+                .map(|i| {
+                    let val = (segment_ord * 50 + i) as f64;
+                    // The terms have distinct doc counts, so that their order is deterministic.
+                    let term = if i % 10 == 0 {
+                        "terma"
+                    } else if i % 3 == 0 {
+                        "termb"
+                    } else {
+                        "termc"
+                    };
+                    (val, term.to_string())
+                })
+                .collect()
+        })
+        .collect();
+    let agg_req: Aggregations = serde_json::from_value(json!({
+        "terms": {
+            "terms": { "field": "string_id" },
+            "aggs": {
+                "histogram": {
+                    "histogram": { "field": "score", "interval": 50.0 },
+                    "aggs": {
+                        "range": {
+                            "range": {
+                                "field": "score_f64",
+                                "ranges": [ { "to": 75.0 }, { "from": 75.0 } ]
+                            },
+                            "aggs": { "avg": { "avg": { "field": "score_i64" } } }
+                        }
+                    }
+                }
+            }
+        }
+    }))
+    .unwrap();
+    let search = |index: &Index| -> crate::Result<Value> {
+        let collector = get_collector(agg_req.clone());
+        let agg_res = index.reader()?.searcher().search(&AllQuery, &collector)?;
+        Ok(serde_json::to_value(agg_res)?)
+    };
+
+    let expected = search(&get_test_index_from_values_and_terms(
+        true,
+        &segment_and_values,
+    )?)?;
+    let num_docs: u64 = expected["terms"]["buckets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bucket| bucket["doc_count"].as_u64().unwrap())
+        .sum();
+    assert_eq!(num_docs, 150);
+    let term_bucket = &expected["terms"]["buckets"][0];
+    let range_buckets = &term_bucket["histogram"]["buckets"][1]["range"]["buckets"];
+    assert_eq!(range_buckets[0]["key"], "*-75");
+    assert_eq!(
+        range_buckets[0]["doc_count"].as_u64().unwrap()
+            + range_buckets[1]["doc_count"].as_u64().unwrap(),
+        term_bucket["histogram"]["buckets"][1]["doc_count"]
+            .as_u64()
+            .unwrap()
+    );
+
+    // The sub-aggregations are merged across segments.
+    let multi_segments_index = get_test_index_from_values_and_terms(false, &segment_and_values)?;
+    assert_eq!(search(&multi_segments_index)?, expected);
+
+    // The intermediate results of independent indexes are serialized and merged.
+    let mut merged_res: Option<IntermediateAggregationResults> = None;
+    for shard_values in [&segment_and_values[..1], &segment_and_values[1..]] {
+        let shard_index = get_test_index_from_values_and_terms(false, shard_values)?;
+        let collector =
+            DistributedAggregationCollector::from_aggs(agg_req.clone(), Default::default());
+        let shard_res = shard_index
+            .reader()?
+            .searcher()
+            .search(&AllQuery, &collector)?;
+        let shard_res: IntermediateAggregationResults =
+            serde_json::from_str(&serde_json::to_string(&shard_res)?)?;
+        match merged_res.as_mut() {
+            Some(merged_res) => merged_res.merge_fruits(shard_res)?,
+            None => merged_res = Some(shard_res),
+        }
+    }
+    let merged_res = merged_res
+        .unwrap()
+        .into_final_result(agg_req.clone(), &Default::default())?;
+    assert_eq!(serde_json::to_value(merged_res)?, expected);
+    Ok(())
+}
+
 #[test]
 fn test_aggregation_invalid_requests() -> crate::Result<()> {
     let index = get_test_index_2_segments(false)?;
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use time::{Date, Month, OffsetDateTime};
 
 use super::{HistogramAggregation, HistogramBounds};
 use crate::aggregation::*;
@@ -6,15 +7,16 @@ use crate::aggregation::*;
 /// DateHistogramAggregation is similar to `HistogramAggregation`, but it can only be used with date
 /// type.
 ///
-/// Currently only **fixed time** intervals are supported. Calendar-aware time intervals are not
-/// supported.
+/// The buckets are either defined by a **fixed time** interval (`fixed_interval`), or by a
+/// **calendar-aware** interval (`calendar_interval`), such as a month.
 ///
 /// Like the histogram, values are rounded down into the closest bucket.
 ///
 /// For this calculation all fastfield values are converted to f64.
 ///
 /// # Limitations/Compatibility
-/// Only fixed time intervals are supported.
+/// Time zones are fixed offsets from UTC. Named time zones, e.g. with daylight saving time, are
+/// not supported. The `key_as_string` of the buckets is formatted in UTC.
 ///
 /// # JSON Format
 /// ```json
@@ -28,6 +30,18 @@ use crate::aggregation::*;
 /// }
 /// ```
 ///
+/// ```json
+/// {
+///     "sales_per_month": {
+///         "date_histogram": {
+///             "field": "date",
+///             "calendar_interval": "month",
+///             "time_zone": "+01:00"
+///         }
+///     }
+/// }
+/// ```
+///
 /// Response
 /// See [`BucketEntry`](crate::aggregation::agg_result::BucketEntry)
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -35,9 +49,6 @@ pub struct DateHistogramAggregationReq {
     #[doc(hidden)]
     /// Only for validation
     interval: Option<String>,
-    #[doc(hidden)]
-    /// Only for validation
-    calendar_interval: Option<String>,
     /// The field to aggregate on.
     pub field: String,
     /// The format to format dates. Unsupported currently.
@@ -63,8 +74,25 @@ pub struct DateHistogramAggregationReq {
     /// Fractional time values are not supported, but you can address this by shifting to another
     /// time unit (e.g., `1.5h` could instead be specified as `90m`).
     ///
-    /// `Option` for validation, the parameter is not optional
+    /// Either `fixed_interval` or `calendar_interval` has to be set.
     pub fixed_interval: Option<String>,
+    /// The calendar-aware interval to chunk your data range.
+    ///
+    /// Calendar-aware intervals account for the varying duration of months, quarters and years:
+    /// each bucket starts at midnight, in the `time_zone` of the aggregation, on the first day of
+    /// its unit. Only a single unit is supported, e.g. `1M` but not `2M`.
+    ///
+    /// The accepted calendar intervals are:
+    /// * `minute`, `1m`
+    /// * `hour`, `1h`
+    /// * `day`, `1d`
+    /// * `week`, `1w`: weeks start on Monday.
+    /// * `month`, `1M`
+    /// * `quarter`, `1q`
+    /// * `year`, `1y`
+    ///
+    /// Either `fixed_interval` or `calendar_interval` has to be set.
+    pub calendar_interval: Option<String>,
     /// Intervals implicitly defines an absolute grid of buckets `[interval * k, interval * (k +
     /// 1))`.
     ///
@@ -75,6 +103,12 @@ pub struct DateHistogramAggregationReq {
     /// The `offset` parameter is has the same syntax as the `fixed_interval` parameter, but
     /// also allows for negative values.
     pub offset: Option<String>,
+    /// The time zone in which the buckets are computed, as a fixed offset from UTC, e.g.
+    /// `+01:00` or `-05:30`. Defaults to `UTC`.
+    ///
+    /// With a time zone, daily buckets start at midnight in the time zone instead of midnight
+    /// UTC. The keys of the buckets remain UTC timestamps.
+    pub time_zone: Option<String>,
     /// The minimum number of documents in a bucket to be returned. Defaults to 0.
     pub min_doc_count: Option<u64>,
     /// Limits the data range to `[min, max]` closed interval.
@@ -119,20 +153,35 @@ pub struct DateHistogramAggregationReq {
 impl DateHistogramAggregationReq {
     pub(crate) fn to_histogram_req(&self) -> crate::Result<HistogramAggregation> {
         self.validate()?;
+        let offset = self
+            .offset
+            .as_ref()
+            .map(|offset| parse_offset_into_milliseconds(offset))
+            .transpose()?
+            .unwrap_or(0);
+        let time_zone_offset = self
+            .time_zone
+            .as_ref()
+            .map(|time_zone| parse_time_zone_into_milliseconds(time_zone))
+            .transpose()?
+            .unwrap_or(0);
+        let (interval, grid_offset, calendar_months) = match &self.fixed_interval {
+            Some(fixed_interval) => (parse_into_milliseconds(fixed_interval)?, 0, None),
+            None => parse_calendar_interval(self.calendar_interval.as_ref().unwrap())?.to_grid(),
+        };
+        // The buckets start at midnight in the time zone, i.e. `time_zone_offset` before
+        // midnight UTC.
+        let offset = offset + grid_offset - time_zone_offset;
         Ok(HistogramAggregation {
             field: self.field.to_string(),
-            interval: parse_into_milliseconds(self.fixed_interval.as_ref().unwrap())? as f64,
-            offset: self
-                .offset
-                .as_ref()
-                .map(|offset| parse_offset_into_milliseconds(offset))
-                .transpose()?
-                .map(|el| el as f64),
+            interval: interval as f64,
+            offset: (offset != 0).then_some(offset as f64),
             min_doc_count: self.min_doc_count,
             hard_bounds: self.hard_bounds,
             extended_bounds: self.extended_bounds,
             keyed: self.keyed,
             is_normalized_to_ns: false,
+            calendar_months,
         })
     }
 
@@ -140,13 +189,7 @@ impl DateHistogramAggregationReq {
         if let Some(interval) = self.interval.as_ref() {
             return Err(crate::TantivyError::InvalidArgument(format!(
                 "`interval` parameter {interval:?} in date histogram is unsupported, only \
-                 `fixed_interval` is supported"
-            )));
-        }
-        if let Some(interval) = self.calendar_interval.as_ref() {
-            return Err(crate::TantivyError::InvalidArgument(format!(
-                "`calendar_interval` parameter {interval:?} in date histogram is unsupported, \
-                 only `fixed_interval` is supported"
+                 `fixed_interval` and `calendar_interval` are supported"
             )));
         }
         if self.format.is_some() {
@@ -155,18 +198,107 @@ impl DateHistogramAggregationReq {
             ));
         }
 
-        if self.fixed_interval.is_none() {
-            return Err(crate::TantivyError::InvalidArgument(
-                "fixed_interval in date histogram is missing".to_string(),
-            ));
+        match (&self.fixed_interval, &self.calendar_interval) {
+            (Some(fixed_interval), None) => {
+                parse_into_milliseconds(fixed_interval)?;
+            }
+            (None, Some(calendar_interval)) => {
+                parse_calendar_interval(calendar_interval)?;
+            }
+            (None, None) => {
+                return Err(crate::TantivyError::InvalidArgument(
+                    "fixed_interval or calendar_interval in date histogram is missing".to_string(),
+                ));
+            }
+            (Some(_), Some(_)) => {
+                return Err(crate::TantivyError::InvalidArgument(
+                    "fixed_interval and calendar_interval in date histogram cannot be set at the \
+                     same time"
+                        .to_string(),
+                ));
+            }
         }
 
-        parse_into_milliseconds(self.fixed_interval.as_ref().unwrap())?;
+        if let Some(time_zone) = self.time_zone.as_ref() {
+            parse_time_zone_into_milliseconds(time_zone)?;
+        }
 
         Ok(())
     }
 }
 
+const MILLISECONDS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Calendar-aware interval of a date histogram.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CalendarInterval {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+impl CalendarInterval {
+    /// Returns the interval and the offset of the grid of buckets, in milliseconds, and the
+    /// number of months of the buckets, for the intervals without a fixed duration.
+    fn to_grid(self) -> (i64, i64, Option<u32>) {
+        match self {
+            CalendarInterval::Minute => (60 * 1000, 0, None),
+            CalendarInterval::Hour => (60 * 60 * 1000, 0, None),
+            CalendarInterval::Day => (MILLISECONDS_PER_DAY, 0, None),
+            // The epoch is a Thursday, the first Monday is 4 days later.
+            CalendarInterval::Week => (7 * MILLISECONDS_PER_DAY, 4 * MILLISECONDS_PER_DAY, None),
+            CalendarInterval::Month => (0, 0, Some(1)),
+            CalendarInterval::Quarter => (0, 0, Some(3)),
+            CalendarInterval::Year => (0, 0, Some(12)),
+        }
+    }
+}
+
+/// Grid of the buckets of a calendar interval of months, on timestamps in nanoseconds.
+///
+/// The buckets start on the first day of a month, at midnight UTC, shifted by `shift_ns`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CalendarMonthsGrid {
+    num_months: i64,
+    shift_ns: i64,
+}
+
+impl CalendarMonthsGrid {
+    pub(crate) fn new(num_months: u32, shift_ns: i64) -> CalendarMonthsGrid {
+        CalendarMonthsGrid {
+            num_months: i64::from(num_months),
+            shift_ns,
+        }
+    }
+
+    /// Returns the position of the bucket of the given timestamp.
+    pub(crate) fn bucket_pos(&self, timestamp_ns: i64) -> i64 {
+        let datetime = OffsetDateTime::from_unix_timestamp_nanos(
+            timestamp_ns.saturating_sub(self.shift_ns).into(),
+        )
+        .expect("any i64 timestamp in nanoseconds is a valid OffsetDateTime");
+        let month_ord = i64::from(datetime.year()) * 12 + i64::from(u8::from(datetime.month())) - 1;
+        month_ord.div_euclid(self.num_months)
+    }
+
+    /// Returns the start of the bucket at the given position, as a timestamp.
+    pub(crate) fn bucket_key(&self, bucket_pos: i64) -> i64 {
+        let month_ord = bucket_pos * self.num_months;
+        let month = Month::try_from(month_ord.rem_euclid(12) as u8 + 1)
+            .expect("the month number is in 1..=12");
+        let first_day = Date::from_calendar_date(month_ord.div_euclid(12) as i32, month, 1)
+            .expect("bucket positions are derived from i64 timestamps");
+        let timestamp_ns = first_day.midnight().assume_utc().unix_timestamp_nanos();
+        i64::try_from(timestamp_ns)
+            .unwrap_or(if timestamp_ns < 0 { i64::MIN } else { i64::MAX })
+            .saturating_add(self.shift_ns)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 /// Errors when parsing the fixed interval for `DateHistogramAggregationReq`.
 pub enum DateHistogramParseError {
@@ -185,6 +317,59 @@ pub enum DateHistogramParseError {
     /// Value out of bounds
     #[error("passed value is out of bounds: {0:?}")]
     OutOfBounds(String),
+    /// Calendar interval not recognized
+    #[error("Calendar interval not recognized in passed String {0:?}")]
+    CalendarIntervalNotRecognized(String),
+    /// Time zone invalid
+    #[error("passed time zone is invalid {0:?}")]
+    InvalidTimeZone(String),
+}
+
+fn parse_calendar_interval(input: &str) -> Result<CalendarInterval, AggregationError> {
+    let calendar_interval = match input {
+        "minute" | "1m" => CalendarInterval::Minute,
+        "hour" | "1h" => CalendarInterval::Hour,
+        "day" | "1d" => CalendarInterval::Day,
+        "week" | "1w" => CalendarInterval::Week,
+        "month" | "1M" => CalendarInterval::Month,
+        "quarter" | "1q" => CalendarInterval::Quarter,
+        "year" | "1y" => CalendarInterval::Year,
+        _ => {
+            return Err(
+                DateHistogramParseError::CalendarIntervalNotRecognized(input.to_string()).into(),
+            )
+        }
+    };
+    Ok(calendar_interval)
+}
+
+/// Parses a time zone given as an offset from UTC (`+01:00`, `-05:30`, `UTC` or `Z`) into
+/// milliseconds.
+fn parse_time_zone_into_milliseconds(input: &str) -> Result<i64, AggregationError> {
+    if input == "UTC" || input == "Z" {
+        return Ok(0);
+    }
+    let invalid_time_zone = || DateHistogramParseError::InvalidTimeZone(input.to_string());
+    let (sign, hours_minutes) = match input.as_bytes().first() {
+        Some(b'+') => (1, &input[1..]),
+        Some(b'-') => (-1, &input[1..]),
+        _ => return Err(invalid_time_zone().into()),
+    };
+    let (hours, minutes) = hours_minutes
+        .split_once(':')
+        .ok_or_else(invalid_time_zone)?;
+    let parse_two_digits = |digits: &str| {
+        if digits.len() != 2 || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid_time_zone());
+        }
+        digits.parse::<i64>().map_err(|_| invalid_time_zone())
+    };
+    let hours = parse_two_digits(hours)?;
+    let minutes = parse_two_digits(minutes)?;
+    if hours > 18 || minutes >= 60 {
+        return Err(invalid_time_zone().into());
+    }
+    Ok(sign * (hours * 60 + minutes) * 60 * 1000)
 }
 
 fn parse_offset_into_milliseconds(input: &str) -> Result<i64, AggregationError> {
@@ -296,6 +481,53 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_time_zone_into_milliseconds() {
+        assert_eq!(parse_time_zone_into_milliseconds("UTC").unwrap(), 0);
+        assert_eq!(parse_time_zone_into_milliseconds("Z").unwrap(), 0);
+        assert_eq!(
+            parse_time_zone_into_milliseconds("+01:00").unwrap(),
+            3_600_000
+        );
+        assert_eq!(
+            parse_time_zone_into_milliseconds("-05:30").unwrap(),
+            -19_800_000
+        );
+        for invalid_time_zone in [
+            "01:00",
+            "+1:00",
+            "+0100",
+            "+19:00",
+            "+01:60",
+            "Europe/Paris",
+        ] {
+            assert_eq!(
+                parse_time_zone_into_milliseconds(invalid_time_zone).unwrap_err(),
+                DateHistogramParseError::InvalidTimeZone(invalid_time_zone.to_string()).into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_calendar_interval() {
+        assert_eq!(
+            parse_calendar_interval("month").unwrap(),
+            CalendarInterval::Month
+        );
+        assert_eq!(
+            parse_calendar_interval("1M").unwrap(),
+            CalendarInterval::Month
+        );
+        assert_eq!(
+            parse_calendar_interval("1m").unwrap(),
+            CalendarInterval::Minute
+        );
+        assert_eq!(
+            parse_calendar_interval("2M").unwrap_err(),
+            DateHistogramParseError::CalendarIntervalNotRecognized("2M".to_string()).into()
+        );
+    }
+
     #[test]
     fn test_parse_into_milliseconds_do_not_accept_non_ascii() {
         assert!(parse_into_milliseconds("１m").is_err());
@@ -646,6 +878,94 @@ pub mod tests {
             assert_eq!(res, expected_res);
         }
     }
+    #[test]
+    fn histogram_test_calendar_interval_force_merge_segments() {
+        histogram_test_calendar_interval_merge_segments(true)
+    }
+
+    #[test]
+    fn histogram_test_calendar_interval() {
+        histogram_test_calendar_interval_merge_segments(false)
+    }
+
+    fn histogram_test_calendar_interval_merge_segments(merge_segments: bool) {
+        let docs = vec![
+            vec![r#"{ "date": "2015-01-01T12:10:30Z" }"#],
+            vec![r#"{ "date": "2015-01-31T23:30:00Z" }"#],
+            vec![r#"{ "date": "2015-02-15T00:00:00Z" }"#],
+            vec![r#"{ "date": "2015-04-01T00:00:00Z" }"#],
+        ];
+        let index = get_test_index_from_docs(merge_segments, &docs).unwrap();
+        let date_histogram = |calendar_interval: &str, time_zone: Option<&str>| {
+            let agg_req: Aggregations = serde_json::from_value(json!({
+                "sales_over_time": {
+                    "date_histogram": {
+                        "field": "date",
+                        "calendar_interval": calendar_interval,
+                        "time_zone": time_zone,
+                    }
+                }
+            }))
+            .unwrap();
+            let res = exec_request(agg_req, &index).unwrap();
+            res["sales_over_time"]["buckets"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|bucket| {
+                    (
+                        bucket["key_as_string"].as_str().unwrap().to_string(),
+                        bucket["doc_count"].as_u64().unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let buckets = |expected: &[(&str, u64)]| {
+            expected
+                .iter()
+                .map(|(key, doc_count)| (key.to_string(), *doc_count))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            date_histogram("month", None),
+            buckets(&[
+                ("2015-01-01T00:00:00Z", 2),
+                ("2015-02-01T00:00:00Z", 1),
+                ("2015-03-01T00:00:00Z", 0),
+                ("2015-04-01T00:00:00Z", 1),
+            ])
+        );
+        // The months start at midnight in the time zone.
+        assert_eq!(
+            date_histogram("1M", Some("+01:00")),
+            buckets(&[
+                ("2014-12-31T23:00:00Z", 1),
+                ("2015-01-31T23:00:00Z", 2),
+                ("2015-02-28T23:00:00Z", 0),
+                ("2015-03-31T23:00:00Z", 1),
+            ])
+        );
+        assert_eq!(
+            date_histogram("quarter", None),
+            buckets(&[("2015-01-01T00:00:00Z", 3), ("2015-04-01T00:00:00Z", 1)])
+        );
+        assert_eq!(
+            date_histogram("year", Some("-05:00")),
+            buckets(&[("2015-01-01T05:00:00Z", 4)])
+        );
+        // 2015-01-01 is a Thursday, its week starts on Monday 2014-12-29.
+        let weeks = date_histogram("week", None);
+        assert_eq!(weeks.len(), 14);
+        assert_eq!(weeks[0], ("2014-12-29T00:00:00Z".to_string(), 1));
+        assert_eq!(weeks[4], ("2015-01-26T00:00:00Z".to_string(), 1));
+        assert_eq!(weeks[13], ("2015-03-30T00:00:00Z".to_string(), 1));
+        assert_eq!(
+            date_histogram("day", Some("+01:00"))[0],
+            ("2014-12-31T23:00:00Z".to_string(), 1)
+        );
+    }
+
     #[test]
     fn histogram_test_invalid_req() {
         let docs = vec![];
@@ -669,7 +989,7 @@ pub mod tests {
         let err = exec_request(agg_req, &index).unwrap_err();
         assert_eq!(
             err.to_string(),
-            r#"An invalid argument was passed: '`interval` parameter "30d" in date histogram is unsupported, only `fixed_interval` is supported'"#
+            r#"An invalid argument was passed: '`interval` parameter "30d" in date histogram is unsupported, only `fixed_interval` and `calendar_interval` are supported'"#
         );
     }
 }
@@ -4,6 +4,7 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use tantivy_bitpacker::minmax;
 
+use super::date_histogram::CalendarMonthsGrid;
 use crate::aggregation::agg_limits::MemoryConsumption;
 use crate::aggregation::agg_req::Aggregations;
 use crate::aggregation::agg_req_with_accessor::{
@@ -124,6 +125,10 @@ pub struct HistogramAggregation {
     /// Whether the values are normalized to ns for date time values. Defaults to false.
     #[serde(default)]
     pub is_normalized_to_ns: bool,
+    /// Width of the buckets in calendar months, for the calendar intervals of a date histogram
+    /// with a variable duration (month, quarter, year). Overrides `interval`.
+    #[serde(skip)]
+    pub(crate) calendar_months: Option<u32>,
 }
 
 impl HistogramAggregation {
@@ -145,7 +150,7 @@ impl HistogramAggregation {
     }
 
     fn validate(&self) -> crate::Result<()> {
-        if self.interval <= 0.0f64 {
+        if self.calendar_months.is_none() && self.interval <= 0.0f64 {
             return Err(TantivyError::InvalidArgument(
                 "interval must be a positive value".to_string(),
             ));
@@ -258,8 +263,7 @@ pub struct SegmentHistogramCollector {
     sub_aggregations: FxHashMap<i64, Box<dyn SegmentAggregationCollector>>,
     sub_aggregation_blueprint: Option<Box<dyn SegmentAggregationCollector>>,
     column_type: ColumnType,
-    interval: f64,
-    offset: f64,
+    grid: BucketGrid,
     bounds: HistogramBounds,
     accessor_idx: usize,
 }
@@ -299,9 +303,7 @@ impl SegmentAggregationCollector for SegmentHistogramCollector {
         let mem_pre = self.get_memory_consumption();
 
         let bounds = self.bounds;
-        let interval = self.interval;
-        let offset = self.offset;
-        let get_bucket_pos = |val| (get_bucket_pos_f64(val, interval, offset) as i64);
+        let grid = self.grid;
 
         bucket_agg_accessor
             .column_block_accessor
@@ -313,11 +315,11 @@ impl SegmentAggregationCollector for SegmentHistogramCollector {
         {
             let val = self.f64_from_fastfield_u64(val);
 
-            let bucket_pos = get_bucket_pos(val);
+            let bucket_pos = grid.bucket_pos(val);
 
             if bounds.contains(val) {
                 let bucket = self.buckets.entry(bucket_pos).or_insert_with(|| {
-                    let key = get_bucket_key_from_pos(bucket_pos as f64, interval, offset);
+                    let key = grid.bucket_key(bucket_pos);
                     SegmentHistogramBucketEntry { key, doc_count: 0 }
                 });
                 bucket.doc_count += 1;
@@ -408,8 +410,7 @@ impl SegmentHistogramCollector {
         Ok(Self {
             buckets: Default::default(),
             column_type: field_type,
-            interval: req.interval,
-            offset: req.offset.unwrap_or(0.0),
+            grid: BucketGrid::from_req(&req),
             bounds,
             sub_aggregations: Default::default(),
             sub_aggregation_blueprint,
@@ -433,6 +434,49 @@ fn get_bucket_key_from_pos(bucket_pos: f64, interval: f64, offset: f64) -> f64 {
     bucket_pos * interval + offset
 }
 
+/// The grid on which the values are rounded down to their bucket.
+#[derive(Clone, Copy, Debug)]
+enum BucketGrid {
+    /// Buckets of a fixed width: `[offset + interval * k, offset + interval * (k + 1))`.
+    Fixed { interval: f64, offset: f64 },
+    /// Buckets of a number of calendar months, on date time values in nanoseconds.
+    Months(CalendarMonthsGrid),
+}
+
+impl BucketGrid {
+    fn from_req(req: &HistogramAggregation) -> BucketGrid {
+        let offset = req.offset.unwrap_or(0.0);
+        if let Some(num_months) = req.calendar_months {
+            BucketGrid::Months(CalendarMonthsGrid::new(num_months, offset as i64))
+        } else {
+            BucketGrid::Fixed {
+                interval: req.interval,
+                offset,
+            }
+        }
+    }
+
+    #[inline]
+    fn bucket_pos(&self, val: f64) -> i64 {
+        match self {
+            BucketGrid::Fixed { interval, offset } => {
+                get_bucket_pos_f64(val, *interval, *offset) as i64
+            }
+            BucketGrid::Months(grid) => grid.bucket_pos(val as i64),
+        }
+    }
+
+    #[inline]
+    fn bucket_key(&self, bucket_pos: i64) -> f64 {
+        match self {
+            BucketGrid::Fixed { interval, offset } => {
+                get_bucket_key_from_pos(bucket_pos as f64, *interval, *offset)
+            }
+            BucketGrid::Months(grid) => grid.bucket_key(bucket_pos) as f64,
+        }
+    }
+}
+
 // Convert to BucketEntry and fill gaps
 fn intermediate_buckets_to_final_buckets_fill_gaps(
     buckets: Vec<IntermediateHistogramBucketEntry>,
@@ -447,7 +491,7 @@ fn intermediate_buckets_to_final_buckets_fill_gaps(
     let min_max = minmax(buckets.iter().map(|bucket| bucket.key));
 
     // memory check upfront
-    let (_, first_bucket_num, last_bucket_num) =
+    let (first_bucket_num, last_bucket_num) =
         generate_bucket_pos_with_opt_minmax(histogram_req, min_max);
 
     // It's based on user input, so we need to account for overflows
@@ -565,13 +609,13 @@ fn get_req_min_max(req: &HistogramAggregation, min_max: Option<(f64, f64)>) -> (
 pub(crate) fn generate_bucket_pos_with_opt_minmax(
     req: &HistogramAggregation,
     min_max: Option<(f64, f64)>,
-) -> (f64, i64, i64) {
+) -> (i64, i64) {
     let (min, max) = get_req_min_max(req, min_max);
 
-    let offset = req.offset.unwrap_or(0.0);
-    let first_bucket_num = get_bucket_pos_f64(min, req.interval, offset) as i64;
-    let last_bucket_num = get_bucket_pos_f64(max, req.interval, offset) as i64;
-    (offset, first_bucket_num, last_bucket_num)
+    let grid = BucketGrid::from_req(req);
+    let first_bucket_num = grid.bucket_pos(min);
+    let last_bucket_num = grid.bucket_pos(max);
+    (first_bucket_num, last_bucket_num)
 }
 
 /// Generates buckets with req.interval
@@ -581,12 +625,11 @@ pub(crate) fn generate_buckets_with_opt_minmax(
     req: &HistogramAggregation,
     min_max: Option<(f64, f64)>,
 ) -> Vec<f64> {
-    let (offset, first_bucket_num, last_bucket_num) =
-        generate_bucket_pos_with_opt_minmax(req, min_max);
+    let (first_bucket_num, last_bucket_num) = generate_bucket_pos_with_opt_minmax(req, min_max);
+    let grid = BucketGrid::from_req(req);
     let mut buckets = Vec::with_capacity((first_bucket_num..=last_bucket_num).count());
     for bucket_pos in first_bucket_num..=last_bucket_num {
-        let bucket_key = bucket_pos as f64 * req.interval + offset;
-        buckets.push(bucket_key);
+        buckets.push(grid.bucket_key(bucket_pos));
     }
 
     buckets
@@ -1322,6 +1365,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn histogram_hard_bounds_sub_aggregation_test() -> crate::Result<()> {
+        let index = get_test_index_2_segments(false)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "range": {
+                "range": {
+                    "field": "score_f64",
+                    "ranges": [
+                        { "key": "low", "to": 10.0 },
+                        { "key": "high", "from": 10.0 }
+                    ]
+                },
+                "aggs": {
+                    "histogram": {
+                        "histogram": {
+                            "field": "score_f64",
+                            "interval": 5.0,
+                            "hard_bounds": {
+                                "min": 0.0,
+                                "max": 20.0,
+                            },
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request(agg_req, &index)?;
+
+        let low_buckets = &res["range"]["buckets"][0]["histogram"]["buckets"];
+        assert_eq!(res["range"]["buckets"][0]["key"], "low");
+        assert_eq!(low_buckets[0]["key"], 0.0);
+        assert_eq!(low_buckets[0]["doc_count"], 2);
+        assert_eq!(low_buckets[1]["key"], 5.0);
+        assert_eq!(low_buckets[1]["doc_count"], 3);
+        assert_eq!(low_buckets[2], Value::Null);
+
+        // The values above the hard bounds are counted by the parent bucket only.
+        let high_buckets = &res["range"]["buckets"][1]["histogram"]["buckets"];
+        assert_eq!(res["range"]["buckets"][1]["key"], "high");
+        assert_eq!(res["range"]["buckets"][1]["doc_count"], 4);
+        assert_eq!(high_buckets[0]["key"], 10.0);
+        assert_eq!(high_buckets[0]["doc_count"], 2);
+        assert_eq!(high_buckets[1], Value::Null);
+
+        Ok(())
+    }
+
     #[test]
     fn histogram_keyed_buckets_test() -> crate::Result<()> {
         let index = get_test_index_with_num_docs(false, 100)?;
@@ -543,6 +543,14 @@ mod tests {
         assert_eq!(res["range"]["buckets"][2]["doc_count"], 10);
         assert_eq!(res["range"]["buckets"][3]["key"], "0.2-*");
         assert_eq!(res["range"]["buckets"][3]["doc_count"], 80);
+        // The sub-aggregation is computed on the documents of each bucket.
+        assert_eq!(
+            res["range"]["buckets"][0]["avg"]["value"],
+            serde_json::Value::Null
+        );
+        assert_eq!(res["range"]["buckets"][1]["avg"]["value"], 4.5);
+        assert_eq!(res["range"]["buckets"][2]["avg"]["value"], 14.5);
+        assert_eq!(res["range"]["buckets"][3]["avg"]["value"], 59.5);
 
         Ok(())
     }
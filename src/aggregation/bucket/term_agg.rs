@@ -1377,6 +1377,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn terms_aggregation_numeric_error_count_test() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let field = schema_builder.add_i64_field("i64_field", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut writer: IndexWriter = index.writer_with_num_threads(1, 15_000_000)?;
+            writer.set_merge_policy(Box::new(NoMergePolicy));
+            // -3 doesn't make it from the first segment.
+            for value in [-1i64, -1, 2, 2, 2, -3] {
+                writer.add_document(doc!(field => value))?;
+            }
+            writer.commit()?;
+            // 2 doesn't make it from the second segment.
+            for value in [-1i64, -1, 2, -3, -3] {
+                writer.add_document(doc!(field => value))?;
+            }
+            writer.commit()?;
+        }
+        assert_eq!(index.searchable_segments()?.len(), 2);
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "my_numbers": {
+                "terms": {
+                    "field": "i64_field",
+                    "size": 2,
+                    "segment_size": 2
+                },
+            }
+        }))
+        .unwrap();
+
+        let res = exec_request(agg_req, &index)?;
+
+        assert_eq!(
+            res["my_numbers"]["buckets"],
+            json!([
+                { "key": -1.0, "doc_count": 4 },
+                { "key": 2.0, "doc_count": 3 }
+            ])
+        );
+        assert_eq!(res["my_numbers"]["sum_other_doc_count"], 4);
+        assert_eq!(res["my_numbers"]["doc_count_error_upper_bound"], 2);
+
+        Ok(())
+    }
+
     #[test]
     fn terms_aggregation_term_bucket_limit() -> crate::Result<()> {
         let terms: Vec<String> = (0..20_000).map(|el| el.to_string()).collect();
@@ -1,7 +1,7 @@
-use super::agg_req::Aggregations;
-use super::agg_req_with_accessor::AggregationsWithAccessor;
+use super::agg_req::{requires_scoring, Aggregations};
+use super::agg_req_with_accessor::{AggregationsWithAccessor, DocScores};
 use super::agg_result::AggregationResults;
-use super::buf_collector::BufAggregationCollector;
+use super::buf_collector::{BufAggregationCollector, DOC_BLOCK_SIZE};
 use super::intermediate_agg_result::IntermediateAggregationResults;
 use super::segment_agg_result::{
     build_segment_agg_collector, AggregationLimits, SegmentAggregationCollector,
@@ -9,7 +9,7 @@ use super::segment_agg_result::{
 use crate::aggregation::agg_req_with_accessor::get_aggs_with_segment_accessor_and_validate;
 use crate::collector::{Collector, SegmentCollector};
 use crate::index::SegmentReader;
-use crate::{DocId, SegmentOrdinal, TantivyError};
+use crate::{DocId, Score, SegmentOrdinal, TantivyError};
 
 /// The default max bucket count, before the aggregation fails.
 pub const DEFAULT_BUCKET_LIMIT: u32 = 65000;
@@ -77,7 +77,7 @@ impl Collector for DistributedAggregationCollector {
     }
 
     fn requires_scoring(&self) -> bool {
-        false
+        requires_scoring(&self.agg)
     }
 
     fn merge_fruits(
@@ -107,7 +107,7 @@ impl Collector for AggregationCollector {
     }
 
     fn requires_scoring(&self) -> bool {
-        false
+        requires_scoring(&self.agg)
     }
 
     fn merge_fruits(
@@ -137,6 +137,10 @@ fn merge_fruits(
 pub struct AggregationSegmentCollector {
     aggs_with_accessor: AggregationsWithAccessor,
     agg_collector: BufAggregationCollector,
+    // Set if the scores of the documents are used by the aggregations.
+    doc_scores: Option<DocScores>,
+    // The scores of the documents staged in `agg_collector`.
+    staged_scores: Vec<(DocId, Score)>,
     error: Option<TantivyError>,
 }
 
@@ -149,13 +153,27 @@ impl AggregationSegmentCollector {
         segment_ordinal: SegmentOrdinal,
         limits: &AggregationLimits,
     ) -> crate::Result<Self> {
-        let mut aggs_with_accessor =
-            get_aggs_with_segment_accessor_and_validate(agg, reader, segment_ordinal, limits)?;
+        let doc_scores = DocScores::default();
+        let mut aggs_with_accessor = get_aggs_with_segment_accessor_and_validate(
+            agg,
+            reader,
+            segment_ordinal,
+            limits,
+            &doc_scores,
+        )?;
         let result =
             BufAggregationCollector::new(build_segment_agg_collector(&mut aggs_with_accessor)?);
+        let doc_scores = requires_scoring(agg).then_some(doc_scores);
+        if doc_scores.is_some() {
+            // The staged scores and the scores of the flushed block.
+            let scores_mem = 2 * DOC_BLOCK_SIZE * std::mem::size_of::<(DocId, Score)>();
+            limits.add_memory_consumed(scores_mem as u64)?;
+        }
         Ok(AggregationSegmentCollector {
             aggs_with_accessor,
             agg_collector: result,
+            doc_scores,
+            staged_scores: Vec::with_capacity(DOC_BLOCK_SIZE),
             error: None,
         })
     }
@@ -165,10 +183,18 @@ impl SegmentCollector for AggregationSegmentCollector {
     type Fruit = crate::Result<IntermediateAggregationResults>;
 
     #[inline]
-    fn collect(&mut self, doc: DocId, _score: crate::Score) {
+    fn collect(&mut self, doc: DocId, score: crate::Score) {
         if self.error.is_some() {
             return;
         }
+        if let Some(doc_scores) = &self.doc_scores {
+            self.staged_scores.push((doc, score));
+            // `agg_collector` flushes its staged documents once it holds a full block, so the
+            // scores of the block are published right before.
+            if self.staged_scores.len() == DOC_BLOCK_SIZE {
+                doc_scores.set_block(&mut self.staged_scores);
+            }
+        }
         if let Err(err) = self
             .agg_collector
             .collect(doc, &mut self.aggs_with_accessor)
@@ -196,6 +222,9 @@ impl SegmentCollector for AggregationSegmentCollector {
         if let Some(err) = self.error {
             return Err(err);
         }
+        if let Some(doc_scores) = &self.doc_scores {
+            doc_scores.set_block(&mut self.staged_scores);
+        }
         self.agg_collector.flush(&mut self.aggs_with_accessor)?;
 
         let mut sub_aggregation_res = IntermediateAggregationResults::default();
@@ -267,7 +267,10 @@ impl SegmentAggregationCollector for SegmentCardinalityCollector {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-/// The percentiles collector used during segment collection and for merging results.
+/// The cardinality collector used during segment collection and for merging results.
+///
+/// Its HyperLogLog++ sketch is serialized with the intermediate results, so that the results
+/// of several indexes can be merged before computing the final estimate.
 pub struct CardinalityCollector {
     sketch: HyperLogLogPlus<u64, BuildSaltedHasher>,
 }
@@ -315,7 +318,10 @@ mod tests {
     use columnar::MonotonicallyMappableToU64;
 
     use crate::aggregation::agg_req::Aggregations;
+    use crate::aggregation::intermediate_agg_result::IntermediateAggregationResults;
     use crate::aggregation::tests::{exec_request, get_test_index_from_terms};
+    use crate::aggregation::DistributedAggregationCollector;
+    use crate::query::AllQuery;
     use crate::schema::{IntoIpv6Addr, Schema, FAST};
     use crate::Index;
 
@@ -463,4 +469,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn cardinality_aggregation_across_indexes() -> crate::Result<()> {
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "cardinality": {
+                "cardinality": {
+                    "field": "id"
+                },
+            }
+        }))
+        .unwrap();
+        let intermediate_result = |ids: std::ops::Range<u64>| -> crate::Result<String> {
+            let mut schema_builder = Schema::builder();
+            let id_field = schema_builder.add_u64_field("id", FAST);
+            let index = Index::create_in_ram(schema_builder.build());
+            let mut writer = index.writer_for_tests()?;
+            for id in ids {
+                writer.add_document(doc!(id_field => id))?;
+            }
+            writer.commit()?;
+            let collector =
+                DistributedAggregationCollector::from_aggs(agg_req.clone(), Default::default());
+            let intermediate_result = index.reader()?.searcher().search(&AllQuery, &collector)?;
+            Ok(serde_json::to_string(&intermediate_result)?)
+        };
+
+        // The sketches of the indexes are serialized, e.g. to be sent to a root node, and
+        // merged there.
+        let mut merged_result: IntermediateAggregationResults =
+            serde_json::from_str(&intermediate_result(0..1000)?)?;
+        merged_result.merge_fruits(serde_json::from_str(&intermediate_result(500..1500)?)?)?;
+        let res = merged_result.into_final_result(agg_req, &Default::default())?;
+        let res = serde_json::to_value(res)?;
+        let cardinality = res["cardinality"]["value"].as_f64().unwrap();
+        assert!((1485.0..=1515.0).contains(&cardinality), "{cardinality}");
+
+        Ok(())
+    }
 }
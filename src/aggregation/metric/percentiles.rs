@@ -347,6 +347,7 @@ mod tests {
         exec_request_with_query, get_test_index_from_values, get_test_index_from_values_and_terms,
     };
     use crate::aggregation::AggregationCollector;
+    use crate::indexer::NoMergePolicy;
     use crate::query::AllQuery;
     use crate::schema::{Schema, FAST};
     use crate::Index;
@@ -583,6 +584,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_aggregation_percentiles_u64_multi_seg() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let latency_field = schema_builder.add_u64_field("latency_ms", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer = index.writer_for_tests()?;
+            index_writer.set_merge_policy(Box::new(NoMergePolicy));
+            for latency_ms in 1..=1000u64 {
+                index_writer.add_document(doc!(latency_field => latency_ms))?;
+                if latency_ms % 300 == 0 {
+                    index_writer.commit()?;
+                }
+            }
+            index_writer.commit()?;
+        }
+        assert_eq!(index.searchable_segment_metas()?.len(), 4);
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "latency_percentiles": {
+                "percentiles": {
+                    "field": "latency_ms",
+                    "percents": [50, 90, 99]
+                }
+            }
+        }))
+        .unwrap();
+        let res = exec_request_with_query(agg_req, &index, None)?;
+        let vals = &res["latency_percentiles"]["values"];
+        for (percent, exact_value) in [("50.0", 500.0), ("90.0", 900.0), ("99.0", 990.0)] {
+            let val = vals[percent].as_f64().unwrap();
+            assert_le!(val, exact_value * 1.02);
+            assert_ge!(val, exact_value * 0.98);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_percentiles_missing_sub_agg() -> crate::Result<()> {
         // This test verifies the `collect` method (in contrast to `collect_block`), which is
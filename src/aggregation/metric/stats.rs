@@ -390,6 +390,55 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_aggregation_stats_negative_values() -> crate::Result<()> {
+        for merge_segments in [false, true] {
+            let index = get_test_index_from_values(merge_segments, &[-10.0, -3.5, 2.0])?;
+
+            let agg_req: Aggregations = serde_json::from_value(json!({
+                "stats_i64": {
+                    "stats": {
+                        "field": "score_i64",
+                    },
+                },
+                "stats_f64": {
+                    "stats": {
+                        "field": "score_f64",
+                    },
+                },
+            }))
+            .unwrap();
+
+            let collector = AggregationCollector::from_aggs(agg_req, Default::default());
+            let searcher = index.reader()?.searcher();
+            let agg_res: AggregationResults = searcher.search(&AllQuery, &collector)?;
+
+            let res: Value = serde_json::from_str(&serde_json::to_string(&agg_res)?)?;
+            assert_eq!(
+                res["stats_i64"],
+                json!({
+                    "avg": -3.6666666666666665,
+                    "count": 3,
+                    "max": 2.0,
+                    "min": -10.0,
+                    "sum": -11.0
+                })
+            );
+            assert_eq!(
+                res["stats_f64"],
+                json!({
+                    "avg": -3.8333333333333335,
+                    "count": 3,
+                    "max": 2.0,
+                    "min": -10.0,
+                    "sum": -11.5
+                })
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_aggregation_stats() -> crate::Result<()> {
         let index = get_test_index_2_segments(false)?;
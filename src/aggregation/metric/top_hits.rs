@@ -3,12 +3,13 @@ use std::net::Ipv6Addr;
 
 use columnar::{Column, ColumnType, ColumnarReader, DynamicColumn};
 use common::json_path_writer::JSON_PATH_SEGMENT_SEP_STR;
-use common::DateTime;
+use common::{f64_to_u64, DateTime};
 use regex::Regex;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::{TopHitsMetricResult, TopHitsVecEntry};
+use crate::aggregation::agg_req_with_accessor::DocScores;
 use crate::aggregation::bucket::Order;
 use crate::aggregation::intermediate_agg_result::{
     IntermediateAggregationResult, IntermediateMetricResult,
@@ -19,6 +20,9 @@ use crate::collector::TopNComputer;
 use crate::schema::OwnedValue;
 use crate::{DocAddress, DocId, SegmentOrdinal};
 
+/// The sort key sorting the documents by their score.
+const SCORE_SORT_KEY: &str = "_score";
+
 /// # Top Hits
 ///
 /// The top hits aggregation is a useful tool to answer questions like:
@@ -65,7 +69,9 @@ use crate::{DocAddress, DocId, SegmentOrdinal};
 ///
 /// This request will return an object containing the top two documents, sorted
 /// by the `date` field in descending order. You can also sort by multiple fields, which
-/// helps to resolve ties. The aggregation object for each bucket will look like:
+/// helps to resolve ties. The special `_score` sort key sorts the documents by the score
+/// computed by the query, e.g. `{ "_score": "desc" }`. The aggregation object for each
+/// bucket will look like:
 /// ```JSON
 /// {
 ///     "hits": [
@@ -229,9 +235,17 @@ impl TopHitsAggregationReq {
         self.sort
             .iter()
             .map(|KeyOrder { field, .. }| field.as_str())
+            .filter(|field| *field != SCORE_SORT_KEY)
             .collect()
     }
 
+    /// Returns true if the documents are sorted by their score.
+    pub(crate) fn sorts_by_score(&self) -> bool {
+        self.sort
+            .iter()
+            .any(|KeyOrder { field, .. }| field == SCORE_SORT_KEY)
+    }
+
     /// Return fields accessed by the aggregator's value retrieval.
     pub fn value_field_names(&self) -> Vec<&str> {
         self.doc_value_fields.iter().map(|s| s.as_str()).collect()
@@ -534,19 +548,27 @@ impl TopHitsSegmentCollector {
         doc_id: crate::DocId,
         req: &TopHitsAggregationReq,
         accessors: &[(Column<u64>, ColumnType)],
+        doc_scores: &DocScores,
     ) -> crate::Result<()> {
+        // The `_score` sort key has no accessor.
+        let mut accessors = accessors.iter();
         let sorts: Vec<DocValueAndOrder> = req
             .sort
             .iter()
-            .enumerate()
-            .map(|(idx, KeyOrder { order, .. })| {
+            .map(|KeyOrder { field, order }| {
                 let order = *order;
-                let value = accessors
-                    .get(idx)
-                    .expect("could not find field in accessors")
-                    .0
-                    .values_for_doc(doc_id)
-                    .next();
+                let value = if field == SCORE_SORT_KEY {
+                    doc_scores
+                        .score(doc_id)
+                        .map(|score| f64_to_u64(score as f64))
+                } else {
+                    accessors
+                        .next()
+                        .expect("could not find field in accessors")
+                        .0
+                        .values_for_doc(doc_id)
+                        .next()
+                };
                 DocValueAndOrder { value, order }
             })
             .collect();
@@ -598,7 +620,8 @@ impl SegmentAggregationCollector for TopHitsSegmentCollector {
             .as_top_hits()
             .expect("aggregation request must be of type top hits");
         let accessors = &agg_with_accessor.aggs.values[self.accessor_idx].accessors;
-        self.collect_with(doc_id, tophits_req, accessors)?;
+        let doc_scores = &agg_with_accessor.aggs.values[self.accessor_idx].doc_scores;
+        self.collect_with(doc_id, tophits_req, accessors, doc_scores)?;
         Ok(())
     }
 
@@ -613,9 +636,10 @@ impl SegmentAggregationCollector for TopHitsSegmentCollector {
             .as_top_hits()
             .expect("aggregation request must be of type top hits");
         let accessors = &agg_with_accessor.aggs.values[self.accessor_idx].accessors;
+        let doc_scores = &agg_with_accessor.aggs.values[self.accessor_idx].doc_scores;
         // TODO: Consider getting fields with the column block accessor.
         for doc in docs {
-            self.collect_with(*doc, tophits_req, accessors)?;
+            self.collect_with(*doc, tophits_req, accessors, doc_scores)?;
         }
         Ok(())
     }
@@ -634,9 +658,11 @@ mod tests {
     use crate::aggregation::bucket::tests::get_test_index_from_docs;
     use crate::aggregation::tests::get_test_index_from_values;
     use crate::aggregation::AggregationCollector;
-    use crate::collector::ComparableDoc;
-    use crate::query::AllQuery;
-    use crate::schema::OwnedValue;
+    use crate::collector::{Collector, ComparableDoc};
+    use crate::indexer::NoMergePolicy;
+    use crate::query::{AllQuery, QueryParser};
+    use crate::schema::{OwnedValue, Schema, FAST, STRING, TEXT};
+    use crate::{doc, Index, IndexWriter};
 
     fn invert_order(cmp_feature: DocValueAndOrder) -> DocValueAndOrder {
         let DocValueAndOrder { value, order } = cmp_feature;
@@ -913,4 +939,102 @@ mod tests {
     fn test_aggregation_top_hits_multi_segment() -> crate::Result<()> {
         test_aggregation_top_hits(false)
     }
+
+    #[test]
+    fn test_aggregation_top_hits_sort_by_score() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_text_field("category", STRING | FAST);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let id = schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(category => "a", body => "sea foo bar", id => 1u64))?;
+        index_writer.add_document(doc!(category => "a", body => "sea sea sea", id => 2u64))?;
+        index_writer.add_document(doc!(category => "b", body => "sea sea foo", id => 4u64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(category => "a", body => "sea sea foo", id => 3u64))?;
+        index_writer.add_document(doc!(category => "b", body => "sea foo bar", id => 5u64))?;
+        index_writer.add_document(doc!(category => "a", body => "foo bar baz", id => 6u64))?;
+        index_writer.commit()?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "by_category": {
+                "terms": { "field": "category" },
+                "aggs": {
+                    "top": {
+                        "top_hits": {
+                            "size": 2,
+                            "sort": [ { "_score": "desc" } ],
+                            "docvalue_fields": [ "id" ]
+                        }
+                    }
+                }
+            }
+        }))?;
+        let collector = AggregationCollector::from_aggs(agg_req, Default::default());
+        assert!(collector.requires_scoring());
+
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![body]).parse_query("sea")?;
+        let agg_res = serde_json::to_value(searcher.search(&query, &collector)?)?;
+        let hit_ids = |bucket: usize| -> Vec<Value> {
+            agg_res["by_category"]["buckets"][bucket]["top"]["hits"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|hit| hit["docvalue_fields"]["id"].clone())
+                .collect()
+        };
+        assert_eq!(agg_res["by_category"]["buckets"][0]["key"], "a");
+        assert_eq!(hit_ids(0), vec![json!([2]), json!([3])]);
+        assert_eq!(agg_res["by_category"]["buckets"][1]["key"], "b");
+        assert_eq!(hit_ids(1), vec![json!([4]), json!([5])]);
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "top": { "top_hits": { "size": 2, "sort": [ { "id": "desc" } ] } }
+        }))?;
+        assert!(!AggregationCollector::from_aggs(agg_req, Default::default()).requires_scoring());
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregation_top_hits_sort_by_score_multiple_blocks() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let id = schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for doc_id in 0..150u64 {
+            let text = if doc_id == 70 || doc_id == 130 {
+                "sea sea sea"
+            } else {
+                "sea foo bar"
+            };
+            index_writer.add_document(doc!(body => text, id => doc_id))?;
+        }
+        index_writer.commit()?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "top": {
+                "top_hits": {
+                    "size": 2,
+                    "sort": [ { "_score": "desc" }, { "id": "desc" } ],
+                    "docvalue_fields": [ "id" ]
+                }
+            }
+        }))?;
+        let collector = AggregationCollector::from_aggs(agg_req, Default::default());
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![body]).parse_query("sea")?;
+        let agg_res = serde_json::to_value(searcher.search(&query, &collector)?)?;
+        let hit_ids: Vec<Value> = agg_res["top"]["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| hit["docvalue_fields"]["id"].clone())
+            .collect();
+        assert_eq!(hit_ids, vec![json!([130]), json!([70])]);
+        Ok(())
+    }
 }
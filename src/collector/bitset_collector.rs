@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use common::BitSet;
+
+use super::{Collector, SegmentCollector};
+use crate::index::{SegmentId, SegmentReader};
+use crate::{DocId, Score, SegmentOrdinal};
+
+/// Collector materializing the matching documents of each segment into a [`BitSet`].
+///
+/// The bitsets are keyed by segment id, and each of them has the `max_doc` of its segment
+/// as max value, like the alive bitset of a segment. They can therefore be:
+/// - cached, as long as the segments are part of the index. A bitset can be serialized with
+///   [`write_alive_bitset`](crate::fastfield::write_alive_bitset) and reopened as an
+///   [`AliveBitSet`](crate::fastfield::AliveBitSet).
+/// - intersected or merged with one another, using the operations of [`BitSet`].
+/// - searched again with a [`BitSetQuery`](crate::query::BitSetQuery), e.g. as the filter of a
+///   later query.
+///
+/// Deleted documents are not collected.
+///
+/// ```rust
+/// use tantivy::collector::{BitSetCollector, Count};
+/// use tantivy::query::{BitSetQuery, QueryParser};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "The Name of the Wind"))?;
+/// index_writer.add_document(doc!(title => "The Diary of Muadib"))?;
+/// index_writer.add_document(doc!(title => "A Dairy Cow"))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("the")?;
+/// let bitsets = searcher.search(&query, &BitSetCollector)?;
+/// let num_docs: usize = bitsets.values().map(|bitset| bitset.len()).sum();
+/// assert_eq!(num_docs, 2);
+/// assert_eq!(searcher.search(&BitSetQuery::new(bitsets), &Count)?, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct BitSetCollector;
+
+impl Collector for BitSetCollector {
+    type Fruit = HashMap<SegmentId, BitSet>;
+    type Child = BitSetSegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<BitSetSegmentCollector> {
+        Ok(BitSetSegmentCollector {
+            segment_id: segment_reader.segment_id(),
+            bitset: BitSet::with_max_value(segment_reader.max_doc()),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_bitsets: Vec<(SegmentId, BitSet)>,
+    ) -> crate::Result<HashMap<SegmentId, BitSet>> {
+        Ok(segment_bitsets.into_iter().collect())
+    }
+}
+
+pub struct BitSetSegmentCollector {
+    segment_id: SegmentId,
+    bitset: BitSet,
+}
+
+impl SegmentCollector for BitSetSegmentCollector {
+    type Fruit = (SegmentId, BitSet);
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        self.bitset.insert(doc);
+    }
+
+    fn collect_block(&mut self, docs: &[DocId]) {
+        for &doc in docs {
+            self.bitset.insert(doc);
+        }
+    }
+
+    fn harvest(self) -> (SegmentId, BitSet) {
+        (self.segment_id, self.bitset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitSetCollector;
+    use crate::directory::OwnedBytes;
+    use crate::fastfield::{write_alive_bitset, AliveBitSet};
+    use crate::indexer::NoMergePolicy;
+    use crate::query::TermQuery;
+    use crate::schema::{IndexRecordOption, Schema, STRING};
+    use crate::{doc, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_bitset_collector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(color => "red", id => "0"))?;
+        index_writer.add_document(doc!(color => "blue", id => "1"))?;
+        index_writer.add_document(doc!(color => "red", id => "2"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(color => "red", id => "3"))?;
+        index_writer.add_document(doc!(color => "red", id => "4"))?;
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(id, "4"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let query = TermQuery::new(
+            Term::from_field_text(color, "red"),
+            IndexRecordOption::Basic,
+        );
+        let bitsets = searcher.search(&query, &BitSetCollector)?;
+        assert_eq!(bitsets.len(), 2);
+        for segment_reader in searcher.segment_readers() {
+            let bitset = &bitsets[&segment_reader.segment_id()];
+            assert_eq!(bitset.max_value(), segment_reader.max_doc());
+            let docs: Vec<u32> = (0..bitset.max_value())
+                .filter(|doc| bitset.contains(*doc))
+                .collect();
+            if segment_reader.max_doc() == 3 {
+                assert_eq!(docs, vec![0, 2]);
+            } else {
+                assert_eq!(docs, vec![0]);
+            }
+            // The bitset can be stored like an alive bitset.
+            let mut buffer = Vec::new();
+            write_alive_bitset(bitset, &mut buffer)?;
+            let alive_bitset = AliveBitSet::open(OwnedBytes::new(buffer));
+            assert_eq!(alive_bitset.num_alive_docs(), docs.len());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use columnar::Column;
+
+use crate::collector::{Collector, SegmentCollector};
+use crate::{DocAddress, DocId, Score, SegmentOrdinal, SegmentReader};
+
+/// Group of documents sharing the same value for the collapse field of a
+/// [`CollapseCollector`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollapsedGroup {
+    /// Value of the collapse field shared by the documents of the group.
+    pub key: u64,
+    /// Number of matching documents in the group, including the ones that are
+    /// not part of `hits`.
+    pub count: u64,
+    /// Best scoring documents of the group, sorted by decreasing score.
+    ///
+    /// The first one is the hit representing the group.
+    pub hits: Vec<(Score, DocAddress)>,
+}
+
+impl CollapsedGroup {
+    /// Returns the number of matching documents of the group that were collapsed,
+    /// i.e. that are not part of its `hits`.
+    pub fn num_collapsed_hits(&self) -> u64 {
+        self.count.saturating_sub(self.hits.len() as u64)
+    }
+
+    fn top_score(&self) -> Score {
+        self.hits.first().map_or(0.0, |(score, _)| *score)
+    }
+
+    fn top_doc_address(&self) -> Option<DocAddress> {
+        self.hits.first().map(|(_, doc_address)| *doc_address)
+    }
+}
+
+/// Compares hits by decreasing score, ties being broken by increasing doc address.
+fn compare_hits<D: Ord>(left: &(Score, D), right: &(Score, D)) -> Ordering {
+    right.0.total_cmp(&left.0).then(left.1.cmp(&right.1))
+}
+
+/// Collapses the matching documents by the value of a `u64` fast field, and returns
+/// the top groups, ranked by the score of their best document.
+///
+/// Each group comes with up to `inner_hits` of its best scoring documents, one by default.
+/// The groups and their hits are merged across segments, so that the result is the same
+/// as if the index had a single segment. Each group also reports the number of matching
+/// documents it contains, so that "one result per domain" style listings can display how
+/// many other results were folded into each hit.
+///
+/// A document with several values for the collapse field belongs to the group of its first
+/// value. Documents without a value for the collapse field are ignored.
+///
+/// The hits of every group are kept while collecting, so the memory usage is proportional
+/// to the number of distinct values of the collapse field among the matching documents.
+///
+/// ```rust
+/// use tantivy::collector::CollapseCollector;
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let thread = schema_builder.add_u64_field("thread", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "tantivy release", thread => 1u64))?;
+/// index_writer.add_document(doc!(title => "re: tantivy release", thread => 1u64))?;
+/// index_writer.add_document(doc!(title => "tantivy tantivy benchmark", thread => 2u64))?;
+/// index_writer.add_document(doc!(title => "lucene release", thread => 3u64))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("tantivy")?;
+/// // One result per thread, with up to two messages of each thread.
+/// let collector = CollapseCollector::new("thread", 10).with_inner_hits(2);
+/// let groups = searcher.search(&query, &collector)?;
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].key, 2);
+/// assert_eq!(groups[0].hits.len(), 1);
+/// assert_eq!(groups[1].key, 1);
+/// assert_eq!(groups[1].hits.len(), 2);
+/// assert_eq!(groups[1].count, 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CollapseCollector {
+    collapse_field: String,
+    limit: usize,
+    inner_hits: usize,
+}
+
+impl CollapseCollector {
+    /// Creates a collector returning the `limit` best groups of documents sharing the same
+    /// value for the `u64` fast field `collapse_field`.
+    pub fn new(collapse_field: impl ToString, limit: usize) -> CollapseCollector {
+        CollapseCollector {
+            collapse_field: collapse_field.to_string(),
+            limit,
+            inner_hits: 1,
+        }
+    }
+
+    /// Sets the maximum number of hits returned for each group.
+    ///
+    /// # Panics
+    /// The method panics if `inner_hits` is 0.
+    #[must_use]
+    pub fn with_inner_hits(mut self, inner_hits: usize) -> CollapseCollector {
+        assert!(
+            inner_hits >= 1,
+            "The number of inner hits must be strictly greater than 0."
+        );
+        self.inner_hits = inner_hits;
+        self
+    }
+}
+
+impl Collector for CollapseCollector {
+    type Fruit = Vec<CollapsedGroup>;
+    type Child = CollapseSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<CollapseSegmentCollector> {
+        // The column is missing from the segments where no document has a value.
+        let collapse_column_opt = segment_reader
+            .fast_fields()
+            .column_opt::<u64>(&self.collapse_field)?;
+        Ok(CollapseSegmentCollector {
+            segment_ord: segment_local_id,
+            collapse_column_opt,
+            inner_hits: self.inner_hits,
+            groups: HashMap::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_groups: Vec<Vec<CollapsedGroup>>,
+    ) -> crate::Result<Vec<CollapsedGroup>> {
+        let mut groups_per_key: HashMap<u64, (u64, Vec<(Score, DocAddress)>)> = HashMap::new();
+        for segment_groups in segment_groups {
+            for group in segment_groups {
+                let (count, hits) = groups_per_key.entry(group.key).or_default();
+                *count += group.count;
+                hits.extend(group.hits);
+            }
+        }
+        let mut groups: Vec<CollapsedGroup> = groups_per_key
+            .into_iter()
+            .map(|(key, (count, mut hits))| {
+                hits.sort_by(compare_hits);
+                hits.truncate(self.inner_hits);
+                CollapsedGroup { key, count, hits }
+            })
+            .collect();
+        groups.sort_by(|left, right| {
+            right
+                .top_score()
+                .total_cmp(&left.top_score())
+                .then(left.top_doc_address().cmp(&right.top_doc_address()))
+        });
+        groups.truncate(self.limit);
+        Ok(groups)
+    }
+}
+
+pub struct CollapseSegmentCollector {
+    segment_ord: SegmentOrdinal,
+    collapse_column_opt: Option<Column<u64>>,
+    inner_hits: usize,
+    // The number of matching documents and the hits of each group, sorted by
+    // decreasing score.
+    groups: HashMap<u64, (u64, Vec<(Score, DocId)>)>,
+}
+
+impl SegmentCollector for CollapseSegmentCollector {
+    type Fruit = Vec<CollapsedGroup>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let Some(key) = self
+            .collapse_column_opt
+            .as_ref()
+            .and_then(|collapse_column| collapse_column.first(doc))
+        else {
+            return;
+        };
+        let (count, hits) = self.groups.entry(key).or_default();
+        *count += 1;
+        // Documents are collected by increasing doc id, so that on a tie on the score,
+        // the new hit comes after the ones already collected.
+        let position = hits.partition_point(|(hit_score, _)| *hit_score >= score);
+        if position < self.inner_hits {
+            hits.insert(position, (score, doc));
+            hits.truncate(self.inner_hits);
+        }
+    }
+
+    fn harvest(self) -> Vec<CollapsedGroup> {
+        let segment_ord = self.segment_ord;
+        self.groups
+            .into_iter()
+            .map(|(key, (count, hits))| CollapsedGroup {
+                key,
+                count,
+                hits: hits
+                    .into_iter()
+                    .map(|(score, doc_id)| (score, DocAddress::new(segment_ord, doc_id)))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CollapseCollector;
+    use crate::query::{AllQuery, QueryParser};
+    use crate::schema::{Schema, FAST, TEXT};
+    use crate::{doc, DocAddress, Index, IndexWriter};
+
+    fn create_index() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let thread = schema_builder.add_u64_field("thread", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "apple", thread => 1u64))?;
+        index_writer.add_document(doc!(title => "apple apple apple", thread => 2u64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(title => "apple apple", thread => 1u64))?;
+        index_writer.add_document(doc!(title => "apple", thread => 2u64))?;
+        index_writer.add_document(doc!(title => "apple", thread => 3u64))?;
+        index_writer.add_document(doc!(title => "apple apple apple apple"))?;
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    #[test]
+    fn test_collapse_across_segments() -> crate::Result<()> {
+        let index = create_index()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let title = index.schema().get_field("title").unwrap();
+        let query = QueryParser::for_index(&index, vec![title]).parse_query("apple")?;
+
+        let collector = CollapseCollector::new("thread", 2).with_inner_hits(3);
+        let groups = searcher.search(&query, &collector)?;
+        let keys: Vec<u64> = groups.iter().map(|group| group.key).collect();
+        assert_eq!(keys, vec![2, 1]);
+        // The hits of each group come from both segments.
+        let hits: Vec<DocAddress> = groups[0]
+            .hits
+            .iter()
+            .map(|(_, doc_address)| *doc_address)
+            .collect();
+        assert_eq!(hits, vec![DocAddress::new(0, 1), DocAddress::new(1, 1)]);
+        let hits: Vec<DocAddress> = groups[1]
+            .hits
+            .iter()
+            .map(|(_, doc_address)| *doc_address)
+            .collect();
+        assert_eq!(hits, vec![DocAddress::new(1, 0), DocAddress::new(0, 0)]);
+        assert!(groups[0].hits[0].0 > groups[1].hits[0].0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collapse_inner_hits_limit() -> crate::Result<()> {
+        let index = create_index()?;
+        let searcher = index.reader()?.searcher();
+        let groups = searcher.search(&AllQuery, &CollapseCollector::new("thread", 10))?;
+        assert_eq!(groups.len(), 3);
+        assert!(groups.iter().all(|group| group.hits.len() == 1));
+        let counts: Vec<(u64, u64)> = groups
+            .iter()
+            .map(|group| (group.key, group.count))
+            .collect();
+        assert_eq!(counts, vec![(1, 2), (2, 2), (3, 1)]);
+        let num_collapsed_hits: u64 = groups.iter().map(|group| group.num_collapsed_hits()).sum();
+        assert_eq!(num_collapsed_hits, 2);
+        // On a tie on the score, the first document wins.
+        assert_eq!(groups[0].key, 1);
+        assert_eq!(groups[0].hits[0].1, DocAddress::new(0, 0));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of inner hits must be strictly greater than 0.")]
+    fn test_collapse_zero_inner_hits() {
+        let _ = CollapseCollector::new("thread", 10).with_inner_hits(0);
+    }
+}
@@ -1,10 +1,16 @@
 use super::Collector;
 use crate::collector::SegmentCollector;
+use crate::query::Weight;
 use crate::{DocId, Score, SegmentOrdinal, SegmentReader};
 
 /// `CountCollector` collector only counts how many
 /// documents match the query.
 ///
+/// The documents are not scored, and the count relies on [`Weight::count`], which
+/// can avoid iterating through the matching documents (e.g. for a term query
+/// over a segment without deletes).
+/// [`Searcher::count`](crate::Searcher::count) is a shortcut for this collector.
+///
 /// ```rust
 /// use tantivy::collector::Count;
 /// use tantivy::query::QueryParser;
@@ -55,6 +61,15 @@ impl Collector for Count {
     fn merge_fruits(&self, segment_counts: Vec<usize>) -> crate::Result<usize> {
         Ok(segment_counts.into_iter().sum())
     }
+
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        _segment_ord: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> crate::Result<usize> {
+        Ok(weight.count(reader)? as usize)
+    }
 }
 
 #[derive(Default)]
@@ -69,6 +84,10 @@ impl SegmentCollector for SegmentCountCollector {
         self.count += 1;
     }
 
+    fn collect_block(&mut self, docs: &[DocId]) {
+        self.count += docs.len();
+    }
+
     fn harvest(self) -> usize {
         self.count
     }
@@ -78,6 +97,9 @@ impl SegmentCollector for SegmentCountCollector {
 mod tests {
     use super::{Count, SegmentCountCollector};
     use crate::collector::{Collector, SegmentCollector};
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, STRING};
+    use crate::{doc, Index, IndexWriter, Term};
 
     #[test]
     fn test_count_collect_does_not_requires_scoring() {
@@ -107,4 +129,41 @@ mod tests {
             assert_eq!(count_collector.harvest(), 2);
         }
     }
+
+    #[test]
+    fn test_count_skips_deleted_docs() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for (i, color_value) in ["red", "blue", "red", "red"].iter().enumerate() {
+            index_writer.add_document(doc!(color => *color_value))?;
+            if i == 1 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        let red_query = TermQuery::new(
+            Term::from_field_text(color, "red"),
+            IndexRecordOption::Basic,
+        );
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.search(&red_query, &Count)?, 3);
+        assert_eq!(searcher.count(&red_query)?, 3);
+        assert_eq!(searcher.count(&AllQuery)?, 4);
+
+        let mut segment_collector = SegmentCountCollector::default();
+        segment_collector.collect_block(&[0, 1, 2]);
+        assert_eq!(segment_collector.harvest(), 3);
+
+        index_writer.delete_term(Term::from_field_text(color, "blue"));
+        index_writer.delete_term(Term::from_field_text(color, "red"));
+        index_writer.add_document(doc!(color => "red"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.search(&red_query, &Count)?, 1);
+        assert_eq!(searcher.count(&red_query)?, 1);
+        assert_eq!(searcher.search(&AllQuery, &Count)?, 1);
+        Ok(())
+    }
 }
@@ -53,7 +53,8 @@ fn facet_depth(facet_bytes: &[u8]) -> usize {
 /// facet you want to extract as argument.
 ///
 /// Facet counts will only be computed for the facet that are direct children
-/// of such a root facet.
+/// of such a root facet. The count of a child facet includes the documents
+/// associated with any of its descendants.
 ///
 /// For instance, if your index represents books, your hierarchy of facets
 /// may contain `category`, `language`.
@@ -65,6 +66,10 @@ fn facet_depth(facet_bytes: &[u8]) -> usize {
 /// the breakdown of counts for the direct children of `/category`
 /// (e.g. `/category/fiction`, `/category/biography`, `/category/personal_development`).
 ///
+/// Several roots can be requested in a single pass, including roots that are
+/// ancestors of one another. For instance, a UI showing a `/category` panel and
+/// a `/category/electronics` drill-down panel can request both at once.
+///
 /// Once collection is finished, you can harvest its results in the form
 /// of a [`FacetCounts`] object, and extract your facet counts from it.
 ///
@@ -163,6 +168,21 @@ fn facet_depth(facet_bytes: &[u8]) -> usize {
 ///
 ///     {
 ///         let mut facet_collector = FacetCollector::for_field("facet");
+///         facet_collector.add_facet("/category");
+///         facet_collector.add_facet("/category/fiction");
+///         let facet_counts = searcher.search(&AllQuery, &facet_collector)?;
+///
+///         // Each root has its own top `k`.
+///         assert_eq!(facet_counts.top_k("/category", 1), vec![
+///             (&Facet::from("/category/fiction"), 3)
+///         ]);
+///         assert_eq!(facet_counts.top_k("/category/fiction", 1), vec![
+///             (&Facet::from("/category/fiction/fantasy"), 2)
+///         ]);
+///     }
+///
+///     {
+///         let mut facet_collector = FacetCollector::for_field("facet");
 ///         facet_collector.add_facet("/");
 ///         let facet_counts = searcher.search(&AllQuery, &facet_collector)?;
 ///
@@ -187,6 +207,12 @@ pub struct FacetCollector {
 
 pub struct FacetSegmentCollector {
     reader: FacetReader,
+    layers: Vec<FacetLayerCollector>,
+}
+
+/// Counts the children of a set of requested facets, none of which
+/// is an ancestor of another.
+struct FacetLayerCollector {
     // collapse facet_id -> count
     counts: Vec<u64>,
     // facet_ord -> compressed collapse facet_id
@@ -214,23 +240,45 @@ impl FacetCollector {
     /// will record the counts of all of the direct children of the facet country
     /// (e.g. `/country/FR`, `/country/UK`).
     ///
-    /// Adding two facets within which one is the prefix of the other is forbidden.
-    /// If you need the correct number of unique documents for two such facets,
-    /// just add them in a separate `FacetCollector`.
+    /// A facet may be an ancestor of another added facet, e.g. `/country` and
+    /// `/country/europe`. The counts of both are then computed in the same pass.
     pub fn add_facet<T>(&mut self, facet_from: T)
     where Facet: From<T> {
-        let facet = Facet::from(facet_from);
-        for old_facet in &self.facets {
-            assert!(
-                !old_facet.is_prefix_of(&facet),
-                "Tried to add a facet which is a descendant of an already added facet."
-            );
-            assert!(
-                !facet.is_prefix_of(old_facet),
-                "Tried to add a facet which is an ancestor of an already added facet."
-            );
+        self.facets.insert(Facet::from(facet_from));
+    }
+
+    /// Adds a facet along with all of its ancestors, except the root facet.
+    ///
+    /// This is what a drill-down navigation typically needs: once the user selected
+    /// `/category/electronics/phones`, the counts of the children of `/category`,
+    /// `/category/electronics` and `/category/electronics/phones` are all displayed.
+    pub fn add_facet_with_ancestors<T>(&mut self, facet_from: T)
+    where Facet: From<T> {
+        let mut next_facet = Some(Facet::from(facet_from));
+        while let Some(facet) = next_facet.filter(|facet| !facet.is_root()) {
+            next_facet = facet.parent();
+            self.facets.insert(facet);
+        }
+    }
+
+    /// Splits the requested facets into layers, such that no facet of a layer
+    /// is an ancestor of another facet of the same layer.
+    ///
+    /// The layer of a facet is its number of requested ancestors.
+    fn facet_layers(&self) -> Vec<BTreeSet<Facet>> {
+        let mut layers: Vec<BTreeSet<Facet>> = Vec::new();
+        for facet in &self.facets {
+            let num_ancestors = self
+                .facets
+                .iter()
+                .filter(|other_facet| other_facet.is_prefix_of(facet))
+                .count();
+            if layers.len() <= num_ancestors {
+                layers.resize_with(num_ancestors + 1, BTreeSet::default);
+            }
+            layers[num_ancestors].insert(facet.clone());
         }
-        self.facets.insert(facet);
+        layers
     }
 }
 
@@ -269,15 +317,22 @@ impl Collector for FacetCollector {
     ) -> crate::Result<FacetSegmentCollector> {
         let facet_reader = reader.facet_reader(&self.field_name)?;
         let facet_dict = facet_reader.facet_dict();
-        let collapse_mapping: Vec<(u64, usize)> =
-            compute_collapse_mapping(facet_dict, &self.facets)?;
-        let (compressed_collapse_mapping, unique_facet_ords) = compress_mapping(&collapse_mapping);
-        let counts = vec![0u64; unique_facet_ords.len()];
+        let mut layers = Vec::new();
+        for facets in self.facet_layers() {
+            let collapse_mapping: Vec<(u64, usize)> =
+                compute_collapse_mapping(facet_dict, &facets)?;
+            let (compressed_collapse_mapping, unique_facet_ords) =
+                compress_mapping(&collapse_mapping);
+            let counts = vec![0u64; unique_facet_ords.len()];
+            layers.push(FacetLayerCollector {
+                counts,
+                compressed_collapse_mapping,
+                unique_facet_ords,
+            });
+        }
         Ok(FacetSegmentCollector {
             reader: facet_reader,
-            compressed_collapse_mapping,
-            counts,
-            unique_facet_ords,
+            layers,
         })
     }
 
@@ -368,11 +423,13 @@ impl SegmentCollector for FacetSegmentCollector {
     type Fruit = FacetCounts;
 
     fn collect(&mut self, doc: DocId, _: Score) {
-        let mut previous_collapsed_ord: usize = usize::MAX;
-        for facet_ord in self.reader.facet_ords(doc) {
-            let collapsed_ord = self.compressed_collapse_mapping[facet_ord as usize];
-            self.counts[collapsed_ord] += u64::from(collapsed_ord != previous_collapsed_ord);
-            previous_collapsed_ord = collapsed_ord;
+        for layer in &mut self.layers {
+            let mut previous_collapsed_ord: usize = usize::MAX;
+            for facet_ord in self.reader.facet_ords(doc) {
+                let collapsed_ord = layer.compressed_collapse_mapping[facet_ord as usize];
+                layer.counts[collapsed_ord] += u64::from(collapsed_ord != previous_collapsed_ord);
+                previous_collapsed_ord = collapsed_ord;
+            }
         }
     }
 
@@ -383,24 +440,27 @@ impl SegmentCollector for FacetSegmentCollector {
     fn harvest(self) -> FacetCounts {
         let mut facet_counts = BTreeMap::new();
         let facet_dict = self.reader.facet_dict();
-        for (collapsed_facet_ord, count) in self.counts.iter().cloned().enumerate() {
-            if count == 0 {
-                continue;
-            }
-            let mut facet = vec![];
-            let (facet_ord, facet_depth) = self.unique_facet_ords[collapsed_facet_ord];
-            // TODO handle errors.
-            if facet_dict.ord_to_term(facet_ord, &mut facet).is_ok() {
-                if let Some((end_collapsed_facet, _)) = facet
-                    .iter()
-                    .enumerate()
-                    .filter(|(_pos, &b)| b == 0u8)
-                    .nth(facet_depth)
-                {
-                    facet.truncate(end_collapsed_facet);
+        // The children of the facets of different layers are distinct.
+        for layer in &self.layers {
+            for (collapsed_facet_ord, count) in layer.counts.iter().cloned().enumerate() {
+                if count == 0 {
+                    continue;
                 }
-                if let Ok(facet) = Facet::from_encoded(facet) {
-                    facet_counts.insert(facet, count);
+                let mut facet = vec![];
+                let (facet_ord, facet_depth) = layer.unique_facet_ords[collapsed_facet_ord];
+                // TODO handle errors.
+                if facet_dict.ord_to_term(facet_ord, &mut facet).is_ok() {
+                    if let Some((end_collapsed_facet, _)) = facet
+                        .iter()
+                        .enumerate()
+                        .filter(|(_pos, &b)| b == 0u8)
+                        .nth(facet_depth)
+                    {
+                        facet.truncate(end_collapsed_facet);
+                    }
+                    if let Ok(facet) = Facet::from_encoded(facet) {
+                        facet_counts.insert(facet, count);
+                    }
                 }
             }
         }
@@ -417,34 +477,44 @@ pub struct FacetCounts {
 
 pub struct FacetChildIterator<'a> {
     underlying: btree_map::Range<'a, Facet, u64>,
+    // Length of the encoded parent facet, separator included.
+    child_offset: usize,
 }
 
 impl<'a> Iterator for FacetChildIterator<'a> {
     type Item = (&'a Facet, u64);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.underlying.next().map(|(facet, count)| (facet, *count))
+        // Skips the deeper descendants, counted for another requested facet.
+        let child_offset = self.child_offset;
+        self.underlying
+            .find(|(facet, _)| !facet.encoded_str().as_bytes()[child_offset..].contains(&0u8))
+            .map(|(facet, count)| (facet, *count))
     }
 }
 
 impl FacetCounts {
-    /// Returns an iterator over all of the facet count pairs inside this result.
+    /// Returns an iterator over the counts of the direct children of the given facet.
     /// See the documentation for [`FacetCollector`] for a usage example.
     pub fn get<T>(&self, facet_from: T) -> FacetChildIterator<'_>
     where Facet: From<T> {
         let facet = Facet::from(facet_from);
         let lower_bound = Bound::Excluded(facet.clone());
-        let upper_bound = if facet.is_root() {
-            Bound::Unbounded
+        let (upper_bound, child_offset) = if facet.is_root() {
+            (Bound::Unbounded, 0)
         } else {
             let mut facet_after_bytes: String = facet.encoded_str().to_owned();
             facet_after_bytes.push('\u{1}');
+            let child_offset = facet_after_bytes.len();
             let facet_after = Facet::from_encoded_string(facet_after_bytes);
-            Bound::Excluded(facet_after)
+            (Bound::Excluded(facet_after), child_offset)
         };
         let underlying: btree_map::Range<'_, _, _> =
             self.facet_counts.range((lower_bound, upper_bound));
-        FacetChildIterator { underlying }
+        FacetChildIterator {
+            underlying,
+            child_offset,
+        }
     }
 
     /// Returns a vector of top `k` facets with their counts, sorted highest-to-lowest by counts.
@@ -634,13 +704,101 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "Tried to add a facet which is a descendant of an already added facet."
-    )]
-    fn test_misused_facet_collector() {
+    fn test_facet_layers() {
         let mut facet_collector = FacetCollector::for_field("facet");
-        facet_collector.add_facet(Facet::from("/country"));
         facet_collector.add_facet(Facet::from("/country/europe"));
+        facet_collector.add_facet(Facet::from("/country"));
+        facet_collector.add_facet(Facet::from("/brand"));
+        facet_collector.add_facet(Facet::from("/"));
+        let layers: Vec<Vec<String>> = facet_collector
+            .facet_layers()
+            .iter()
+            .map(|facets| facets.iter().map(Facet::to_string).collect())
+            .collect();
+        assert_eq!(
+            layers,
+            vec![
+                vec!["/".to_string()],
+                vec!["/brand".to_string(), "/country".to_string()],
+                vec!["/country/europe".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_facet_with_ancestors() {
+        let mut facet_collector = FacetCollector::for_field("facet");
+        facet_collector.add_facet_with_ancestors("/category/electronics/phones");
+        facet_collector.add_facet_with_ancestors("/");
+        let facets: Vec<String> = facet_collector
+            .facets
+            .iter()
+            .map(Facet::to_string)
+            .collect();
+        assert_eq!(
+            facets,
+            vec![
+                "/category".to_string(),
+                "/category/electronics".to_string(),
+                "/category/electronics/phones".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_facet_collector_nested_roots() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let facet_field = schema_builder.add_facet_field("facet", FacetOptions::default());
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            facet_field => Facet::from("/category/electronics/phones"),
+            facet_field => Facet::from("/category/electronics/laptops"),
+            facet_field => Facet::from("/brand/acme"),
+        ))?;
+        index_writer.add_document(doc!(
+            facet_field => Facet::from("/category/electronics/phones/android"),
+            facet_field => Facet::from("/brand/initech"),
+        ))?;
+        index_writer.add_document(doc!(
+            facet_field => Facet::from("/category/books"),
+            facet_field => Facet::from("/brand/acme"),
+        ))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let mut facet_collector = FacetCollector::for_field("facet");
+        facet_collector.add_facet("/brand");
+        facet_collector.add_facet("/category");
+        facet_collector.add_facet("/category/electronics");
+        let counts = searcher.search(&AllQuery, &facet_collector)?;
+        // The counts of `/category/electronics` children are not listed with `/category`.
+        let facets: Vec<(&Facet, u64)> = counts.get("/category").collect();
+        assert_eq!(
+            facets,
+            vec![
+                (&Facet::from("/category/books"), 1),
+                (&Facet::from("/category/electronics"), 2),
+            ]
+        );
+        assert_eq!(
+            counts.top_k("/category/electronics", 1),
+            vec![(&Facet::from("/category/electronics/phones"), 2)]
+        );
+        let facets: Vec<(&Facet, u64)> = counts.get("/category/electronics").collect();
+        assert_eq!(
+            facets,
+            vec![
+                (&Facet::from("/category/electronics/laptops"), 1),
+                (&Facet::from("/category/electronics/phones"), 2),
+            ]
+        );
+        assert_eq!(
+            counts.top_k("/brand", 1),
+            vec![(&Facet::from("/brand/acme"), 2)]
+        );
+        Ok(())
     }
 
     #[test]
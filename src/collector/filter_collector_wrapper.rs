@@ -119,6 +119,7 @@ where
             column_opt,
             segment_collector,
             predicate: self.predicate.clone(),
+            accepted_docs: Vec::new(),
             t_predicate_value: PhantomData,
         })
     }
@@ -139,6 +140,8 @@ pub struct FilterSegmentCollector<TSegmentCollector, TPredicate, TPredicateValue
     column_opt: Option<Column<TPredicateValue>>,
     segment_collector: TSegmentCollector,
     predicate: TPredicate,
+    // Buffer of the accepted documents of a block.
+    accepted_docs: Vec<DocId>,
     t_predicate_value: PhantomData<TPredicateValue>,
 }
 
@@ -176,6 +179,20 @@ where
         }
     }
 
+    fn collect_block(&mut self, docs: &[DocId]) {
+        let mut accepted_docs = std::mem::take(&mut self.accepted_docs);
+        accepted_docs.clear();
+        for &doc in docs {
+            if self.accept_document(doc) {
+                accepted_docs.push(doc);
+            }
+        }
+        if !accepted_docs.is_empty() {
+            self.segment_collector.collect_block(&accepted_docs);
+        }
+        self.accepted_docs = accepted_docs;
+    }
+
     fn harvest(self) -> TSegmentCollector::Fruit {
         self.segment_collector.harvest()
     }
@@ -0,0 +1,376 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use columnar::{Column, ColumnType};
+
+use crate::aggregation::f64_from_fastfield_u64;
+use crate::collector::{Collector, SegmentCollector};
+use crate::{DocId, Order, Score, SegmentOrdinal, SegmentReader};
+
+/// Metric by which the groups of a [`GroupByCollector`] are ranked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupMetric {
+    /// Number of documents of the group.
+    Count,
+    /// Sum of the metric field values of the group.
+    Sum,
+    /// Minimum of the metric field values of the group.
+    Min,
+    /// Maximum of the metric field values of the group.
+    Max,
+}
+
+/// Metrics computed for a group of a [`GroupByCollector`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupStats {
+    /// Number of documents of the group.
+    pub count: u64,
+    /// Sum of the metric field values of the group.
+    pub sum: f64,
+    /// Minimum of the metric field values of the group,
+    /// `None` if no document of the group has a value.
+    pub min: Option<f64>,
+    /// Maximum of the metric field values of the group,
+    /// `None` if no document of the group has a value.
+    pub max: Option<f64>,
+}
+
+impl Default for GroupStats {
+    fn default() -> Self {
+        GroupStats {
+            count: 0,
+            sum: 0.0,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+impl GroupStats {
+    fn record_doc(&mut self) {
+        self.count += 1;
+    }
+
+    fn record_value(&mut self, value: f64) {
+        self.sum += value;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+    }
+
+    fn merge(&mut self, other: &GroupStats) {
+        self.count += other.count;
+        self.sum += other.sum;
+        if let Some(min) = other.min {
+            self.min = Some(self.min.map_or(min, |self_min| self_min.min(min)));
+        }
+        if let Some(max) = other.max {
+            self.max = Some(self.max.map_or(max, |self_max| self_max.max(max)));
+        }
+    }
+
+    fn metric(&self, metric: GroupMetric) -> Option<f64> {
+        match metric {
+            GroupMetric::Count => Some(self.count as f64),
+            GroupMetric::Sum => Some(self.sum),
+            GroupMetric::Min => self.min,
+            GroupMetric::Max => self.max,
+        }
+    }
+}
+
+/// Groups the matching documents by the value of a `u64` fast field, computes
+/// metrics on another numerical fast field for each group, and returns the top
+/// groups by one of these metrics.
+///
+/// A document with several values for the group field belongs to several groups.
+/// Documents without a value for the group field are ignored.
+///
+/// ```rust
+/// use tantivy::collector::{GroupByCollector, GroupMetric};
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, Index, Order};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let product = schema_builder.add_text_field("product", TEXT);
+/// let customer = schema_builder.add_u64_field("customer", FAST);
+/// let price = schema_builder.add_f64_field("price", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(product => "red shoes", customer => 1u64, price => 60.0))?;
+/// index_writer.add_document(doc!(product => "blue shoes", customer => 2u64, price => 80.0))?;
+/// index_writer.add_document(doc!(product => "green shoes", customer => 1u64, price => 40.0))?;
+/// index_writer.add_document(doc!(product => "red hat", customer => 3u64, price => 500.0))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![product]).parse_query("shoes")?;
+/// // Top customers by spend among the shoe purchases.
+/// let collector = GroupByCollector::new("customer", 10)
+///     .with_metric_field("price")
+///     .order_by(GroupMetric::Sum, Order::Desc);
+/// let groups = searcher.search(&query, &collector)?;
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].0, 1);
+/// assert_eq!(groups[0].1.count, 2);
+/// assert_eq!(groups[0].1.sum, 100.0);
+/// assert_eq!(groups[1].0, 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GroupByCollector {
+    group_field: String,
+    metric_field: Option<String>,
+    metric: GroupMetric,
+    order: Order,
+    limit: usize,
+}
+
+impl GroupByCollector {
+    /// Creates a collector returning the `limit` largest groups of the `u64` fast field
+    /// `group_field`.
+    pub fn new(group_field: impl ToString, limit: usize) -> GroupByCollector {
+        GroupByCollector {
+            group_field: group_field.to_string(),
+            metric_field: None,
+            metric: GroupMetric::Count,
+            order: Order::Desc,
+            limit,
+        }
+    }
+
+    /// Sets the numerical fast field on which the sum, min and max of each group
+    /// are computed.
+    #[must_use]
+    pub fn with_metric_field(mut self, metric_field: impl ToString) -> GroupByCollector {
+        self.metric_field = Some(metric_field.to_string());
+        self
+    }
+
+    /// Sets the metric by which the groups are ranked, and the order of the ranking.
+    ///
+    /// Ties are broken by the value of the group field. Groups without a value for
+    /// the metric come last.
+    #[must_use]
+    pub fn order_by(mut self, metric: GroupMetric, order: Order) -> GroupByCollector {
+        self.metric = metric;
+        self.order = order;
+        self
+    }
+
+    fn compare_groups(&self, left: &(u64, GroupStats), right: &(u64, GroupStats)) -> Ordering {
+        let ordering = match (left.1.metric(self.metric), right.1.metric(self.metric)) {
+            (Some(left_metric), Some(right_metric)) => {
+                let ordering = left_metric.total_cmp(&right_metric);
+                if self.order.is_desc() {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        ordering.then(left.0.cmp(&right.0))
+    }
+}
+
+pub struct GroupBySegmentCollector {
+    group_column_opt: Option<Column<u64>>,
+    metric_column_opt: Option<(Column<u64>, ColumnType)>,
+    groups: HashMap<u64, GroupStats>,
+}
+
+impl Collector for GroupByCollector {
+    type Fruit = Vec<(u64, GroupStats)>;
+    type Child = GroupBySegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<GroupBySegmentCollector> {
+        let fast_fields = segment_reader.fast_fields();
+        // The columns are missing from the segments where no document has a value.
+        let group_column_opt = fast_fields.column_opt::<u64>(&self.group_field)?;
+        let metric_column_opt = if let Some(metric_field) = self.metric_field.as_ref() {
+            fast_fields.u64_lenient_for_type(
+                Some(&[
+                    ColumnType::U64,
+                    ColumnType::I64,
+                    ColumnType::F64,
+                    ColumnType::Bool,
+                ]),
+                metric_field,
+            )?
+        } else {
+            None
+        };
+        Ok(GroupBySegmentCollector {
+            group_column_opt,
+            metric_column_opt,
+            groups: HashMap::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_groups: Vec<HashMap<u64, GroupStats>>,
+    ) -> crate::Result<Vec<(u64, GroupStats)>> {
+        let mut groups: HashMap<u64, GroupStats> = HashMap::new();
+        for segment_groups in segment_groups {
+            for (group, stats) in segment_groups {
+                groups.entry(group).or_default().merge(&stats);
+            }
+        }
+        let mut groups: Vec<(u64, GroupStats)> = groups.into_iter().collect();
+        groups.sort_unstable_by(|left, right| self.compare_groups(left, right));
+        groups.truncate(self.limit);
+        Ok(groups)
+    }
+}
+
+impl SegmentCollector for GroupBySegmentCollector {
+    type Fruit = HashMap<u64, GroupStats>;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        let Some(group_column) = self.group_column_opt.as_ref() else {
+            return;
+        };
+        let metric_value_opt = match self.metric_column_opt.as_ref() {
+            Some((metric_column, column_type)) => metric_column
+                .first(doc)
+                .map(|value| f64_from_fastfield_u64(value, column_type)),
+            None => None,
+        };
+        for group in group_column.values_for_doc(doc) {
+            let stats = self.groups.entry(group).or_default();
+            stats.record_doc();
+            if let Some(metric_value) = metric_value_opt {
+                stats.record_value(metric_value);
+            }
+        }
+    }
+
+    fn harvest(self) -> HashMap<u64, GroupStats> {
+        self.groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GroupByCollector, GroupMetric, GroupStats};
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, FAST, STRING};
+    use crate::{doc, Index, IndexWriter, Order, Term};
+
+    fn create_index() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_text_field("category", STRING);
+        let customer = schema_builder.add_u64_field("customer", FAST);
+        let spend = schema_builder.add_i64_field("spend", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(category => "shoes", customer => 1u64, spend => 10i64))?;
+        index_writer.add_document(doc!(category => "shoes", customer => 2u64, spend => 30i64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(category => "shoes", customer => 1u64, spend => -5i64))?;
+        index_writer.add_document(doc!(category => "shoes", customer => 3u64))?;
+        index_writer.add_document(doc!(category => "hats", customer => 3u64, spend => 100i64))?;
+        index_writer.add_document(doc!(category => "shoes", spend => 1i64))?;
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    #[test]
+    fn test_group_by_count() -> crate::Result<()> {
+        let index = create_index()?;
+        let searcher = index.reader()?.searcher();
+        let groups = searcher.search(&AllQuery, &GroupByCollector::new("customer", 2))?;
+        let counts: Vec<(u64, u64)> = groups
+            .iter()
+            .map(|(group, stats)| (*group, stats.count))
+            .collect();
+        // Ties are broken by the group value.
+        assert_eq!(counts, vec![(1, 2), (3, 2)]);
+        // No metric field.
+        assert_eq!(groups[0].1.sum, 0.0);
+        assert_eq!(groups[0].1.max, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_metrics() -> crate::Result<()> {
+        let index = create_index()?;
+        let category = index.schema().get_field("category").unwrap();
+        let searcher = index.reader()?.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(category, "shoes"),
+            IndexRecordOption::Basic,
+        );
+        let collector = GroupByCollector::new("customer", 10)
+            .with_metric_field("spend")
+            .order_by(GroupMetric::Sum, Order::Desc);
+        let groups = searcher.search(&query, &collector)?;
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    2,
+                    GroupStats {
+                        count: 1,
+                        sum: 30.0,
+                        min: Some(30.0),
+                        max: Some(30.0),
+                    }
+                ),
+                (
+                    1,
+                    GroupStats {
+                        count: 2,
+                        sum: 5.0,
+                        min: Some(-5.0),
+                        max: Some(10.0),
+                    }
+                ),
+                (
+                    3,
+                    GroupStats {
+                        count: 1,
+                        sum: 0.0,
+                        min: None,
+                        max: None,
+                    }
+                ),
+            ]
+        );
+
+        // Groups without a value for the metric come last.
+        let collector = GroupByCollector::new("customer", 10)
+            .with_metric_field("spend")
+            .order_by(GroupMetric::Min, Order::Asc);
+        let groups: Vec<u64> = searcher
+            .search(&query, &collector)?
+            .into_iter()
+            .map(|(group, _)| group)
+            .collect();
+        assert_eq!(groups, vec![1, 2, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_missing_field() -> crate::Result<()> {
+        let index = create_index()?;
+        let searcher = index.reader()?.searcher();
+        let collector = GroupByCollector::new("unknown", 10).with_metric_field("unknown");
+        assert!(searcher.search(&AllQuery, &collector)?.is_empty());
+        Ok(())
+    }
+}
@@ -91,6 +91,12 @@ pub use self::count_collector::Count;
 mod histogram_collector;
 pub use histogram_collector::HistogramCollector;
 
+mod group_by_collector;
+pub use self::group_by_collector::{GroupByCollector, GroupMetric, GroupStats};
+
+mod collapse_collector;
+pub use self::collapse_collector::{CollapseCollector, CollapsedGroup};
+
 mod multi_collector;
 pub use self::multi_collector::{FruitHandle, MultiCollector, MultiFruit};
 
@@ -103,6 +109,8 @@ pub use self::top_score_collector::{TopDocs, TopNComputer};
 mod custom_score_top_collector;
 pub use self::custom_score_top_collector::{CustomScorer, CustomSegmentScorer};
 
+mod score_breakdown_top_collector;
+mod str_fast_field_top_collector;
 mod tweak_score_top_collector;
 pub use self::tweak_score_top_collector::{ScoreSegmentTweaker, ScoreTweaker};
 mod facet_collector;
@@ -112,6 +120,9 @@ use crate::query::Weight;
 mod docset_collector;
 pub use self::docset_collector::DocSetCollector;
 
+mod bitset_collector;
+pub use self::bitset_collector::BitSetCollector;
+
 mod filter_collector_wrapper;
 pub use self::filter_collector_wrapper::{BytesFilterCollector, FilterCollector};
 
@@ -250,6 +250,12 @@ impl SegmentCollector for MultiCollectorChild {
         }
     }
 
+    fn collect_block(&mut self, docs: &[DocId]) {
+        for child in &mut self.children {
+            child.collect_block(docs);
+        }
+    }
+
     fn harvest(self) -> MultiFruit {
         MultiFruit {
             sub_fruits: self
@@ -265,10 +271,10 @@ impl SegmentCollector for MultiCollectorChild {
 mod tests {
 
     use super::*;
-    use crate::collector::{Count, TopDocs};
-    use crate::query::TermQuery;
-    use crate::schema::{IndexRecordOption, Schema, TEXT};
-    use crate::{Index, Term};
+    use crate::collector::{Count, FacetCollector, TopDocs};
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{Facet, FacetOptions, IndexRecordOption, Schema, TEXT};
+    use crate::{Index, IndexWriter, Term};
 
     #[test]
     fn test_multi_collector() -> crate::Result<()> {
@@ -301,4 +307,39 @@ mod tests {
         assert_eq!(topdocs_handler.extract(&mut multifruits).len(), 2);
         Ok(())
     }
+
+    #[test]
+    fn test_multi_collector_without_scoring() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_facet_field("category", FacetOptions::default());
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..300 {
+            let facet = if i % 3 == 0 { "/books" } else { "/music" };
+            index_writer.add_document(doc!(category => Facet::from(facet)))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        // None of the collectors requires scoring: the docs are collected by block.
+        let mut collectors = MultiCollector::new();
+        let count_handler = collectors.add_collector(Count);
+        let mut facet_collector = FacetCollector::for_field("category");
+        facet_collector.add_facet("/");
+        let facet_handler = collectors.add_collector(facet_collector);
+        assert!(!collectors.requires_scoring());
+        let mut multifruits = searcher.search(&AllQuery, &collectors)?;
+
+        assert_eq!(count_handler.extract(&mut multifruits), 300);
+        let facet_counts = facet_handler.extract(&mut multifruits);
+        let facets: Vec<(String, u64)> = facet_counts
+            .get("/")
+            .map(|(facet, count)| (facet.to_string(), count))
+            .collect();
+        assert_eq!(
+            facets,
+            vec![("/books".to_string(), 100), ("/music".to_string(), 200)]
+        );
+        Ok(())
+    }
 }
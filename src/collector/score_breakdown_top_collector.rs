@@ -0,0 +1,102 @@
+use crate::collector::top_score_collector::TopScoreSegmentCollector;
+use crate::collector::{Collector, SegmentCollector, TopDocs};
+use crate::query::{ScoreBreakdown, Weight};
+use crate::{DocAddress, DocId, Result, Score, SegmentOrdinal, SegmentReader};
+
+pub(crate) struct ScoreBreakdownTopCollector {
+    top_docs: TopDocs,
+}
+
+impl ScoreBreakdownTopCollector {
+    pub fn new(top_docs: TopDocs) -> ScoreBreakdownTopCollector {
+        ScoreBreakdownTopCollector { top_docs }
+    }
+}
+
+impl Collector for ScoreBreakdownTopCollector {
+    type Fruit = Vec<(Score, DocAddress, ScoreBreakdown)>;
+
+    type Child = ScoreBreakdownTopSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> Result<Self::Child> {
+        let segment_collector = self.top_docs.for_segment(segment_local_id, reader)?;
+        Ok(ScoreBreakdownTopSegmentCollector(segment_collector))
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> Result<Self::Fruit> {
+        // The breakdowns are moved aside, so that the top docs can be merged by `TopDocs`.
+        let mut breakdowns = Vec::new();
+        let segment_top_docs: Vec<Vec<(Score, DocAddress)>> = segment_fruits
+            .into_iter()
+            .map(|segment_fruit| {
+                segment_fruit
+                    .into_iter()
+                    .map(|(score, doc_address, breakdown)| {
+                        breakdowns.push((doc_address, breakdown));
+                        (score, doc_address)
+                    })
+                    .collect()
+            })
+            .collect();
+        breakdowns.sort_unstable_by_key(|(doc_address, _)| *doc_address);
+        let top_docs = self.top_docs.merge_fruits(segment_top_docs)?;
+        Ok(top_docs
+            .into_iter()
+            .map(|(score, doc_address)| {
+                let breakdown = breakdowns
+                    .binary_search_by_key(&doc_address, |(doc_address, _)| *doc_address)
+                    .map(|pos| std::mem::take(&mut breakdowns[pos].1))
+                    .unwrap_or_default();
+                (score, doc_address, breakdown)
+            })
+            .collect())
+    }
+
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: u32,
+        reader: &SegmentReader,
+    ) -> Result<Self::Fruit> {
+        let segment_top_docs = self.top_docs.collect_segment(weight, segment_ord, reader)?;
+        // Only the top docs of the segment are broken down, once they are known.
+        segment_top_docs
+            .into_iter()
+            .map(|(score, doc_address)| {
+                let mut breakdown = ScoreBreakdown::default();
+                weight.score_breakdown(reader, doc_address.doc_id, 1.0, &mut breakdown)?;
+                Ok((score, doc_address, breakdown))
+            })
+            .collect()
+    }
+}
+
+/// Segment Collector associated with `TopDocs::with_score_breakdown`.
+///
+/// The segment collector does not have access to the query, so that the score
+/// breakdowns are only computed when the segment is collected by the searcher.
+pub struct ScoreBreakdownTopSegmentCollector(TopScoreSegmentCollector);
+
+impl SegmentCollector for ScoreBreakdownTopSegmentCollector {
+    type Fruit = Vec<(Score, DocAddress, ScoreBreakdown)>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        self.0.collect(doc, score);
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        self.0
+            .harvest()
+            .into_iter()
+            .map(|(score, doc_address)| (score, doc_address, ScoreBreakdown::default()))
+            .collect()
+    }
+}
@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+
+use columnar::StrColumn;
+
+use crate::collector::top_collector::{TopCollector, TopSegmentCollector};
+use crate::collector::{Collector, SegmentCollector};
+use crate::{DocAddress, DocId, Order, Result, Score, SegmentOrdinal, SegmentReader};
+
+/// Collector ranking the documents by the value of a `str` fast field.
+///
+/// Within a segment, documents are ranked by the ordinal of their term, which follows the
+/// order of the terms. The top documents of each segment are then resolved to their terms,
+/// which are comparable across segments.
+pub(crate) struct StrFastFieldTopCollector {
+    top_collector: TopCollector<u64>,
+    field: String,
+    order: Order,
+}
+
+impl StrFastFieldTopCollector {
+    pub fn new(
+        top_collector: TopCollector<u64>,
+        field: String,
+        order: Order,
+    ) -> StrFastFieldTopCollector {
+        StrFastFieldTopCollector {
+            top_collector,
+            field,
+            order,
+        }
+    }
+}
+
+/// Compares the values of two documents, documents without a value coming last.
+fn compare_values(left: &Option<String>, right: &Option<String>, order: &Order) -> Ordering {
+    match (left, right) {
+        (Some(left), Some(right)) if order.is_desc() => right.cmp(left),
+        (Some(left), Some(right)) => left.cmp(right),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl Collector for StrFastFieldTopCollector {
+    type Fruit = Vec<(Option<String>, DocAddress)>;
+
+    type Child = StrFastFieldTopSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> Result<Self::Child> {
+        let str_column_opt = reader.fast_fields().str(&self.field)?;
+        Ok(StrFastFieldTopSegmentCollector {
+            segment_collector: self.top_collector.for_segment(segment_local_id, reader),
+            str_column_opt,
+            order: self.order.clone(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<<Self::Child as SegmentCollector>::Fruit>,
+    ) -> Result<Self::Fruit> {
+        let mut top_docs = Vec::new();
+        for segment_fruit in segment_fruits {
+            top_docs.extend(segment_fruit?);
+        }
+        top_docs.sort_by(|(left_value, left_doc), (right_value, right_doc)| {
+            compare_values(left_value, right_value, &self.order).then(left_doc.cmp(right_doc))
+        });
+        Ok(top_docs
+            .into_iter()
+            .skip(self.top_collector.offset)
+            .take(self.top_collector.limit)
+            .collect())
+    }
+}
+
+pub(crate) struct StrFastFieldTopSegmentCollector {
+    segment_collector: TopSegmentCollector<u64>,
+    str_column_opt: Option<StrColumn>,
+    order: Order,
+}
+
+impl StrFastFieldTopSegmentCollector {
+    /// Returns the feature of the document, such that the top features are the ones
+    /// ranked first. Documents without a value get the lowest feature, 0.
+    fn feature(&self, doc: DocId) -> u64 {
+        let Some(str_column) = self.str_column_opt.as_ref() else {
+            return 0;
+        };
+        // A document with several terms is ranked by its highest term in descending order,
+        // and by its lowest term in ascending order.
+        let term_ords = str_column.term_ords(doc);
+        if self.order.is_desc() {
+            term_ords.max().map_or(0, |term_ord| term_ord + 1)
+        } else {
+            term_ords.min().map_or(0, |term_ord| u64::MAX - term_ord)
+        }
+    }
+}
+
+/// Inverse of [`StrFastFieldTopSegmentCollector::feature`].
+fn feature_to_term_ord(feature: u64, order: &Order) -> Option<u64> {
+    if feature == 0 {
+        None
+    } else if order.is_desc() {
+        Some(feature - 1)
+    } else {
+        Some(u64::MAX - feature)
+    }
+}
+
+impl SegmentCollector for StrFastFieldTopSegmentCollector {
+    type Fruit = Result<Vec<(Option<String>, DocAddress)>>;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        let feature = self.feature(doc);
+        self.segment_collector.collect(doc, feature);
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        let mut top_docs = Vec::new();
+        for (feature, doc_address) in self.segment_collector.harvest() {
+            let term_ord_opt = feature_to_term_ord(feature, &self.order);
+            let value_opt = match (self.str_column_opt.as_ref(), term_ord_opt) {
+                (Some(str_column), Some(term_ord)) => {
+                    let mut value = String::new();
+                    str_column.ord_to_str(term_ord, &mut value)?;
+                    Some(value)
+                }
+                _ => None,
+            };
+            top_docs.push((value_opt, doc_address));
+        }
+        Ok(top_docs)
+    }
+}
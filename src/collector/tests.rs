@@ -70,6 +70,29 @@ pub fn test_filter_collector() -> crate::Result<()> {
     Ok(())
 }
 
+#[test]
+pub fn test_filter_collector_without_scoring() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let price = schema_builder.add_u64_field("price", FAST);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+    for i in 0..1_000u64 {
+        index_writer.add_document(doc!(price => i))?;
+    }
+    index_writer.add_document(doc!())?;
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+
+    // `Count` does not require scoring, so that the documents are filtered by block.
+    let filter_collector =
+        FilterCollector::new("price".to_string(), |value: u64| value % 10 == 0, Count);
+    assert_eq!(searcher.search(&AllQuery, &filter_collector)?, 100);
+    let filter_none_collector =
+        FilterCollector::new("price".to_string(), |value: u64| value > 1_000, Count);
+    assert_eq!(searcher.search(&AllQuery, &filter_none_collector)?, 0);
+    Ok(())
+}
+
 /// Stores all of the doc ids.
 /// This collector is only used for tests.
 /// It is unusable in practise, as it does
@@ -2,19 +2,21 @@ use std::fmt;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use columnar::ColumnValues;
+use columnar::{Column, ColumnValues};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 use super::Collector;
 use crate::collector::custom_score_top_collector::CustomScoreTopCollector;
+use crate::collector::score_breakdown_top_collector::ScoreBreakdownTopCollector;
+use crate::collector::str_fast_field_top_collector::StrFastFieldTopCollector;
 use crate::collector::top_collector::{ComparableDoc, TopCollector, TopSegmentCollector};
 use crate::collector::tweak_score_top_collector::TweakedScoreTopCollector;
 use crate::collector::{
     CustomScorer, CustomSegmentScorer, ScoreSegmentTweaker, ScoreTweaker, SegmentCollector,
 };
 use crate::fastfield::{FastFieldNotAvailableError, FastValue};
-use crate::query::Weight;
+use crate::query::{ScoreBreakdown, Weight};
 use crate::{DocAddress, DocId, Order, Score, SegmentOrdinal, SegmentReader, TantivyError};
 
 struct FastFieldConvertCollector<
@@ -128,26 +130,69 @@ where
 /// # Ok(())
 /// # }
 /// ```
-pub struct TopDocs(TopCollector<Score>);
+pub struct TopDocs {
+    top_collector: TopCollector<Score>,
+    min_score: Option<Score>,
+}
 
 impl fmt::Debug for TopDocs {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
             "TopDocs(limit={}, offset={})",
-            self.0.limit, self.0.offset
+            self.top_collector.limit, self.top_collector.offset
         )
     }
 }
 
+/// Returns the largest score strictly lower than `score`.
+///
+/// [`Weight::for_each_pruning`] only reports the documents scoring strictly above its
+/// threshold, so this is the threshold reporting all documents scoring at least `score`.
+fn score_just_below(score: Score) -> Score {
+    if score == 0.0 {
+        -Score::from_bits(1)
+    } else if score == Score::NEG_INFINITY {
+        score
+    } else if score > 0.0 {
+        Score::from_bits(score.to_bits() - 1)
+    } else {
+        Score::from_bits(score.to_bits() + 1)
+    }
+}
+
+enum SortColumn {
+    SingleValued(Arc<dyn ColumnValues<u64>>),
+    // Documents are ranked by their highest value in descending order, and by their lowest
+    // value in ascending order.
+    MultiValued {
+        column: Column<u64>,
+        default_value: u64,
+    },
+}
+
 struct ScorerByFastFieldReader {
-    sort_column: Arc<dyn ColumnValues<u64>>,
+    sort_column: SortColumn,
     order: Order,
 }
 
 impl CustomSegmentScorer<u64> for ScorerByFastFieldReader {
     fn score(&mut self, doc: DocId) -> u64 {
-        let value = self.sort_column.get_val(doc);
+        let value = match &self.sort_column {
+            SortColumn::SingleValued(column_values) => column_values.get_val(doc),
+            SortColumn::MultiValued {
+                column,
+                default_value,
+            } => {
+                let values = column.values_for_doc(doc);
+                let value_opt = if self.order.is_desc() {
+                    values.max()
+                } else {
+                    values.min()
+                };
+                value_opt.unwrap_or(*default_value)
+            }
+        };
         if self.order.is_desc() {
             value
         } else {
@@ -179,8 +224,16 @@ impl CustomScorer<u64> for ScorerByField {
         if self.order.is_asc() {
             default_value = u64::MAX;
         }
+        let sort_column = if sort_column.get_cardinality().is_multivalue() {
+            SortColumn::MultiValued {
+                column: sort_column,
+                default_value,
+            }
+        } else {
+            SortColumn::SingleValued(sort_column.first_or_default_col(default_value))
+        };
         Ok(ScorerByFastFieldReader {
-            sort_column: sort_column.first_or_default_col(default_value),
+            sort_column,
             order: self.order.clone(),
         })
     }
@@ -192,7 +245,10 @@ impl TopDocs {
     /// # Panics
     /// The method panics if limit is 0
     pub fn with_limit(limit: usize) -> TopDocs {
-        TopDocs(TopCollector::with_limit(limit))
+        TopDocs {
+            top_collector: TopCollector::with_limit(limit),
+            min_score: None,
+        }
     }
 
     /// Skip the first "offset" documents when collecting.
@@ -200,6 +256,11 @@ impl TopDocs {
     /// This is equivalent to `OFFSET` in MySQL or PostgreSQL and `start` in
     /// Lucene's TopDocsCollector.
     ///
+    /// The page `n` (starting at 0) of a result list split in pages of `page_size` documents
+    /// is fetched with `TopDocs::with_limit(page_size).and_offset(n * page_size)`. Each
+    /// segment still keeps its `offset + limit` best documents, but only the documents of the
+    /// page are returned.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -237,7 +298,32 @@ impl TopDocs {
     /// ```
     #[must_use]
     pub fn and_offset(self, offset: usize) -> TopDocs {
-        TopDocs(self.0.and_offset(offset))
+        TopDocs {
+            top_collector: self.top_collector.and_offset(offset),
+            min_score: self.min_score,
+        }
+    }
+
+    /// Only collects the documents with a score greater or equal to `min_score`.
+    ///
+    /// Documents scoring below `min_score` are never collected, so the search may
+    /// return fewer than `limit` documents, or none at all. The threshold is passed
+    /// to the scorers from the start, which lets them skip the blocks of documents
+    /// that cannot reach it.
+    ///
+    /// The minimum score does not apply to the collectors ordering documents
+    /// by something else than their score (e.g. [`TopDocs::order_by_fast_field`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_score` is NaN.
+    #[must_use]
+    pub fn and_min_score(self, min_score: Score) -> TopDocs {
+        assert!(!min_score.is_nan(), "min_score must not be NaN.");
+        TopDocs {
+            top_collector: self.top_collector,
+            min_score: Some(min_score),
+        }
     }
 
     /// Set top-K to rank documents by a given fast field.
@@ -321,7 +407,7 @@ impl TopDocs {
                 field: field.to_string(),
                 order,
             },
-            self.0.into_tscore(),
+            self.top_collector.into_tscore(),
         )
     }
 
@@ -334,6 +420,9 @@ impl TopDocs {
     /// Note that this method is a generic. The requested fast field type will be often
     /// inferred in your code by the rust compiler.
     ///
+    /// If the field is multi-valued, documents are ranked by their highest value in
+    /// descending order, and by their lowest value in ascending order.
+    ///
     /// Implementation-wise, for performance reason, tantivy will manipulate the u64 representation
     /// of your fast field until the last moment.
     ///
@@ -411,6 +500,62 @@ impl TopDocs {
         }
     }
 
+    /// Set top-K to rank documents by a given `str` fast field.
+    ///
+    /// Documents are compared by their term, in the lexicographic order of its bytes.
+    /// Within a segment, the ordinals of the terms are compared, so that terms are only loaded
+    /// for the top documents of each segment.
+    ///
+    /// Documents without a value for the field come last, with a `None` value.
+    /// Documents with several values are ranked by their highest value in descending order,
+    /// and by their lowest value in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tantivy::collector::TopDocs;
+    /// use tantivy::query::AllQuery;
+    /// use tantivy::schema::{Schema, FAST, STRING};
+    /// use tantivy::{doc, DocAddress, Index, Order};
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let city = schema_builder.add_text_field("city", STRING | FAST);
+    /// let index = Index::create_in_ram(schema_builder.build());
+    /// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+    /// index_writer.add_document(doc!(city => "Paris"))?;
+    /// index_writer.add_document(doc!(city => "Lyon"))?;
+    /// index_writer.add_document(doc!())?;
+    /// index_writer.commit()?;
+    ///
+    /// let searcher = index.reader()?.searcher();
+    /// let top_docs = searcher.search(
+    ///     &AllQuery,
+    ///     &TopDocs::with_limit(3).order_by_str_fast_field("city", Order::Asc),
+    /// )?;
+    /// assert_eq!(
+    ///     top_docs,
+    ///     vec![
+    ///         (Some("Lyon".to_string()), DocAddress::new(0, 1)),
+    ///         (Some("Paris".to_string()), DocAddress::new(0, 0)),
+    ///         (None, DocAddress::new(0, 2)),
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn order_by_str_fast_field(
+        self,
+        fast_field: impl ToString,
+        order: Order,
+    ) -> impl Collector<Fruit = Vec<(Option<String>, DocAddress)>> {
+        StrFastFieldTopCollector::new(
+            self.top_collector.into_tscore(),
+            fast_field.to_string(),
+            order,
+        )
+    }
+
     /// Ranks the documents using a custom score.
     ///
     /// This method offers a convenient way to tweak or replace
@@ -516,7 +661,7 @@ impl TopDocs {
         TScoreSegmentTweaker: ScoreSegmentTweaker<TScore> + 'static,
         TScoreTweaker: ScoreTweaker<TScore, Child = TScoreSegmentTweaker> + Send + Sync,
     {
-        TweakedScoreTopCollector::new(score_tweaker, self.0.into_tscore())
+        TweakedScoreTopCollector::new(score_tweaker, self.top_collector.into_tscore())
     }
 
     /// Ranks the documents using a custom score.
@@ -629,7 +774,54 @@ impl TopDocs {
         TCustomSegmentScorer: CustomSegmentScorer<TScore> + 'static,
         TCustomScorer: CustomScorer<TScore, Child = TCustomSegmentScorer> + Send + Sync,
     {
-        CustomScoreTopCollector::new(custom_score, self.0.into_tscore())
+        CustomScoreTopCollector::new(custom_score, self.top_collector.into_tscore())
+    }
+
+    /// Returns, alongside each hit, a [`ScoreBreakdown`] listing the partial score
+    /// of each of the terms of the query matching the document.
+    ///
+    /// The breakdowns are only computed for the top documents of each segment, once these
+    /// are known, and are much cheaper to compute than an
+    /// [`Explanation`](crate::query::Explanation). They are a good fit for logging
+    /// the scoring details of a sample of the production queries.
+    ///
+    /// The breakdowns are computed by the [`Searcher`](crate::Searcher). Within a
+    /// [`MultiCollector`](crate::collector::MultiCollector), the breakdowns are empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use tantivy::collector::TopDocs;
+    /// use tantivy::query::QueryParser;
+    /// use tantivy::schema::{Schema, TEXT};
+    /// use tantivy::{doc, Index, Term};
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let schema = schema_builder.build();
+    /// let index = Index::create_in_ram(schema);
+    ///
+    /// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+    /// index_writer.add_document(doc!(title => "The Diary of Muadib"))?;
+    /// index_writer.add_document(doc!(title => "The Diary of a Young Girl"))?;
+    /// index_writer.commit()?;
+    ///
+    /// let searcher = index.reader()?.searcher();
+    /// let query_parser = QueryParser::for_index(&index, vec![title]);
+    /// let query = query_parser.parse_query("diary girl")?;
+    /// let top_docs = searcher.search(&query, &TopDocs::with_limit(1).with_score_breakdown())?;
+    ///
+    /// let (score, _doc_address, breakdown) = &top_docs[0];
+    /// assert!(breakdown.term_score(&Term::from_field_text(title, "girl")).is_some());
+    /// assert!((breakdown.total_score() - score).abs() < 1e-5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_score_breakdown(
+        self,
+    ) -> impl Collector<Fruit = Vec<(Score, DocAddress, ScoreBreakdown)>> {
+        ScoreBreakdownTopCollector::new(self)
     }
 }
 
@@ -643,8 +835,11 @@ impl Collector for TopDocs {
         segment_local_id: SegmentOrdinal,
         reader: &SegmentReader,
     ) -> crate::Result<Self::Child> {
-        let collector = self.0.for_segment(segment_local_id, reader);
-        Ok(TopScoreSegmentCollector(collector))
+        let collector = self.top_collector.for_segment(segment_local_id, reader);
+        Ok(TopScoreSegmentCollector {
+            collector,
+            min_score: self.min_score,
+        })
     }
 
     fn requires_scoring(&self) -> bool {
@@ -655,7 +850,7 @@ impl Collector for TopDocs {
         &self,
         child_fruits: Vec<Vec<(Score, DocAddress)>>,
     ) -> crate::Result<Self::Fruit> {
-        self.0.merge_fruits(child_fruits)
+        self.top_collector.merge_fruits(child_fruits)
     }
 
     fn collect_segment(
@@ -664,24 +859,25 @@ impl Collector for TopDocs {
         segment_ord: u32,
         reader: &SegmentReader,
     ) -> crate::Result<<Self::Child as SegmentCollector>::Fruit> {
-        let heap_len = self.0.limit + self.0.offset;
+        let heap_len = self.top_collector.limit + self.top_collector.offset;
         let mut top_n: TopNComputer<_, _> = TopNComputer::new(heap_len);
+        let min_threshold = self.min_score.map_or(Score::MIN, score_just_below);
 
         if let Some(alive_bitset) = reader.alive_bitset() {
-            let mut threshold = Score::MIN;
+            let mut threshold = min_threshold;
             top_n.threshold = Some(threshold);
-            weight.for_each_pruning(Score::MIN, reader, &mut |doc, score| {
+            weight.for_each_pruning(min_threshold, reader, &mut |doc, score| {
                 if alive_bitset.is_deleted(doc) {
                     return threshold;
                 }
                 top_n.push(score, doc);
-                threshold = top_n.threshold.unwrap_or(Score::MIN);
+                threshold = top_n.threshold.unwrap_or(Score::MIN).max(min_threshold);
                 threshold
             })?;
         } else {
-            weight.for_each_pruning(Score::MIN, reader, &mut |doc, score| {
+            weight.for_each_pruning(min_threshold, reader, &mut |doc, score| {
                 top_n.push(score, doc);
-                top_n.threshold.unwrap_or(Score::MIN)
+                top_n.threshold.unwrap_or(Score::MIN).max(min_threshold)
             })?;
         }
 
@@ -703,17 +899,25 @@ impl Collector for TopDocs {
 }
 
 /// Segment Collector associated with `TopDocs`.
-pub struct TopScoreSegmentCollector(TopSegmentCollector<Score>);
+pub struct TopScoreSegmentCollector {
+    collector: TopSegmentCollector<Score>,
+    min_score: Option<Score>,
+}
 
 impl SegmentCollector for TopScoreSegmentCollector {
     type Fruit = Vec<(Score, DocAddress)>;
 
     fn collect(&mut self, doc: DocId, score: Score) {
-        self.0.collect(doc, score);
+        if let Some(min_score) = self.min_score {
+            if score < min_score {
+                return;
+            }
+        }
+        self.collector.collect(doc, score);
     }
 
     fn harvest(self) -> Vec<(Score, DocAddress)> {
-        self.0.harvest()
+        self.collector.harvest()
     }
 }
 
@@ -866,14 +1070,15 @@ where
 mod tests {
     use super::{TopDocs, TopNComputer};
     use crate::collector::top_collector::ComparableDoc;
-    use crate::collector::Collector;
+    use crate::collector::{Collector, Count};
+    use crate::indexer::NoMergePolicy;
     use crate::query::{AllQuery, Query, QueryParser};
-    use crate::schema::{Field, Schema, FAST, STORED, TEXT};
+    use crate::schema::{Field, Schema, FAST, STORED, STRING, TEXT};
     use crate::time::format_description::well_known::Rfc3339;
     use crate::time::OffsetDateTime;
     use crate::{
         assert_nearly_equals, DateTime, DocAddress, DocId, Index, IndexWriter, Order, Score,
-        SegmentReader,
+        SegmentReader, Term,
     };
 
     fn make_index() -> crate::Result<Index> {
@@ -896,6 +1101,52 @@ mod tests {
             crate::assert_nearly_equals!(result.0, expected.0);
         }
     }
+    #[test]
+    fn test_top_docs_with_score_breakdown() -> crate::Result<()> {
+        let index = make_index()?;
+        let text_field = index.schema().get_field("text").unwrap();
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let query = query_parser.parse_query("droopy^2 (+happy +tax) -like")?;
+        let searcher = index.reader()?.searcher();
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(4))?;
+        let top_docs_with_breakdown =
+            searcher.search(&query, &TopDocs::with_limit(4).with_score_breakdown())?;
+        assert_eq!(top_docs_with_breakdown.len(), 2);
+        for ((score, doc_address), (score_with_breakdown, doc_address_with_breakdown, breakdown)) in
+            top_docs.iter().zip(top_docs_with_breakdown.iter())
+        {
+            assert_eq!(doc_address, doc_address_with_breakdown);
+            assert_nearly_equals!(*score, *score_with_breakdown);
+            assert_nearly_equals!(*score, breakdown.total_score());
+            assert_eq!(breakdown.other_score(), 0.0);
+        }
+        let term = |text: &str| Term::from_field_text(text_field, text);
+        let droopy_breakdown = &top_docs_with_breakdown[0].2;
+        assert_eq!(top_docs_with_breakdown[0].1, DocAddress::new(0, 1));
+        assert_eq!(droopy_breakdown.term_scores().len(), 3);
+        assert!(droopy_breakdown.term_score(&term("droopy")).unwrap() > 0.0);
+        let hello_breakdown = &top_docs_with_breakdown[1].2;
+        assert_eq!(top_docs_with_breakdown[1].1, DocAddress::new(0, 0));
+        assert_eq!(hello_breakdown.term_score(&term("droopy")), None);
+        assert_eq!(hello_breakdown.term_scores().len(), 2);
+        assert_eq!(hello_breakdown.term_scores()[0].0, term("happy"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_docs_with_score_breakdown_non_term_clauses() -> crate::Result<()> {
+        let index = make_index()?;
+        let searcher = index.reader()?.searcher();
+        let top_docs =
+            searcher.search(&AllQuery, &TopDocs::with_limit(2).with_score_breakdown())?;
+        assert_eq!(top_docs.len(), 2);
+        for (score, _, breakdown) in &top_docs {
+            assert!(breakdown.term_scores().is_empty());
+            assert_nearly_equals!(breakdown.other_score(), *score);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_topn_computer_serde() {
         let computer: TopNComputer<u32, u32> = TopNComputer::new(1);
@@ -982,6 +1233,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_top_collector_min_score() -> crate::Result<()> {
+        let index = make_index()?;
+        let field = index.schema().get_field("text").unwrap();
+        let query_parser = QueryParser::for_index(&index, vec![field]);
+        let text_query = query_parser.parse_query("droopy tax")?;
+        let searcher = index.reader()?.searcher();
+        let all_docs = searcher.search(&text_query, &TopDocs::with_limit(4))?;
+        assert_eq!(all_docs.len(), 3);
+
+        let top_docs = searcher.search(&text_query, &TopDocs::with_limit(4).and_min_score(0.5))?;
+        assert_results_equals(&top_docs, &all_docs[..2]);
+        assert_eq!(top_docs.len(), 2);
+
+        // Documents scoring exactly the minimum score are collected.
+        let min_score = all_docs[1].0;
+        let top_docs = searcher.search(
+            &text_query,
+            &TopDocs::with_limit(4).and_min_score(min_score),
+        )?;
+        assert_eq!(top_docs.len(), 2);
+
+        let top_docs = searcher.search(&text_query, &TopDocs::with_limit(4).and_min_score(1.0))?;
+        assert!(top_docs.is_empty());
+
+        // Without `collect_segment`, through the segment collector.
+        let (top_docs, count) = searcher.search(
+            &text_query,
+            &(TopDocs::with_limit(4).and_min_score(0.5), Count),
+        )?;
+        assert_eq!(top_docs.len(), 2);
+        assert_eq!(count, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_just_below() {
+        use super::score_just_below;
+        for score in [0.0, 1.0, -1.0, 0.5, Score::MAX, Score::INFINITY] {
+            assert!(score_just_below(score) < score);
+        }
+        assert_eq!(score_just_below(1.0), 0.99999994);
+        assert_eq!(score_just_below(-1.0), -1.0000001);
+        assert_eq!(score_just_below(Score::INFINITY), Score::MAX);
+        assert!(score_just_below(0.0) > -Score::MIN_POSITIVE);
+    }
+
     #[test]
     fn test_top_collector_not_at_capacity_with_offset() {
         let index = make_index().unwrap();
@@ -997,6 +1295,50 @@ mod tests {
         assert_results_equals(&score_docs[..], &[(0.48527452, DocAddress::new(0, 0))]);
     }
 
+    #[test]
+    fn test_top_collector_pagination() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let size = schema_builder.add_u64_field(SIZE, FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for i in 0..14u64 {
+            let body = "beer ".repeat(1 + (i as usize * 7) % 5);
+            index_writer.add_document(doc!(text => body, size => (i * 11) % 17))?;
+            if i % 5 == 4 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        let query = QueryParser::for_index(&index, vec![text]).parse_query("beer")?;
+
+        let all_docs = searcher.search(&query, &TopDocs::with_limit(14))?;
+        let mut paged_docs = Vec::new();
+        for page in 0..5 {
+            let page_docs =
+                searcher.search(&query, &TopDocs::with_limit(3).and_offset(page * 3))?;
+            assert_eq!(page_docs.len(), if page == 4 { 2 } else { 3 });
+            paged_docs.extend(page_docs);
+        }
+        assert_eq!(paged_docs, all_docs);
+
+        let all_sizes = searcher.search(
+            &query,
+            &TopDocs::with_limit(14).order_by_u64_field(SIZE, Order::Desc),
+        )?;
+        let page_sizes = searcher.search(
+            &query,
+            &TopDocs::with_limit(3)
+                .and_offset(12)
+                .order_by_u64_field(SIZE, Order::Desc),
+        )?;
+        assert_eq!(page_sizes, &all_sizes[12..]);
+        Ok(())
+    }
+
     #[test]
     fn test_top_collector_at_capacity() {
         let index = make_index().unwrap();
@@ -1155,6 +1497,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_top_field_collector_most_recent_matching_docs() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let published = schema_builder.add_date_field("published", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        // Two segments, each holding matching and non-matching articles.
+        for segment_ord in 0..2i64 {
+            for i in 0..5i64 {
+                let day = segment_ord * 5 + i;
+                let topic = if day % 2 == 0 { "rust" } else { "other" };
+                index_writer.add_document(doc!(
+                    title => format!("{topic} news"),
+                    published => DateTime::from_timestamp_secs(day * 86_400),
+                ))?;
+            }
+            index_writer.commit()?;
+        }
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![title]).parse_query("rust")?;
+        let top_collector = TopDocs::with_limit(3).order_by_fast_field("published", Order::Desc);
+        let top_docs: Vec<(DateTime, DocAddress)> = searcher.search(&query, &top_collector)?;
+        let days: Vec<i64> = top_docs
+            .iter()
+            .map(|(date, _)| date.into_timestamp_secs() / 86_400)
+            .collect();
+        assert_eq!(days, vec![8, 6, 4]);
+        Ok(())
+    }
+
     #[test]
     fn test_top_field_collector_i64() -> crate::Result<()> {
         let mut schema_builder = Schema::builder();
@@ -1292,6 +1665,45 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_tweak_score_with_recency_decay() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let age_days = schema_builder.add_u64_field("age_days", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "rust rust rust", age_days => 30u64))?;
+        index_writer.add_document(doc!(title => "rust", age_days => 1u64))?;
+        index_writer.add_document(doc!(title => "other", age_days => 0u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![title]).parse_query("rust")?;
+
+        let bm25_top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+        assert_eq!(bm25_top_docs[0].1, DocAddress::new(0, 0));
+
+        // The score is halved every week.
+        let collector = TopDocs::with_limit(2).tweak_score(|segment_reader: &SegmentReader| {
+            let age_days_reader = segment_reader
+                .fast_fields()
+                .u64("age_days")
+                .unwrap()
+                .first_or_default_col(0);
+            move |doc: DocId, original_score: Score| {
+                let age_days = age_days_reader.get_val(doc) as Score;
+                original_score * 0.5f32.powf(age_days / 7.0)
+            }
+        });
+        let top_docs: Vec<(Score, DocAddress)> = searcher.search(&query, &collector)?;
+        let doc_addresses: Vec<DocAddress> = top_docs.iter().map(|(_, doc)| *doc).collect();
+        assert_eq!(
+            doc_addresses,
+            vec![DocAddress::new(0, 1), DocAddress::new(0, 0)]
+        );
+        assert!(top_docs[0].0 > top_docs[1].0);
+        Ok(())
+    }
+
     #[test]
     fn test_custom_score_top_collector_with_offset() {
         let index = make_index().unwrap();
@@ -1374,4 +1786,94 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_str_fast_field_order_across_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let name = schema_builder.add_text_field("name", STRING | FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(name => "delta"))?;
+        index_writer.add_document(doc!(name => "alpha"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(name => "charlie"))?;
+        index_writer.add_document(doc!())?;
+        index_writer.add_document(doc!(name => "bravo", name => "echo"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let value = |name: &str| Some(name.to_string());
+
+        let top_docs = searcher.search(
+            &AllQuery,
+            &TopDocs::with_limit(10).order_by_str_fast_field("name", Order::Asc),
+        )?;
+        let values: Vec<Option<String>> = top_docs.into_iter().map(|(value, _)| value).collect();
+        assert_eq!(
+            values,
+            vec![
+                value("alpha"),
+                value("bravo"),
+                value("charlie"),
+                value("delta"),
+                None
+            ]
+        );
+
+        let top_docs = searcher.search(
+            &AllQuery,
+            &TopDocs::with_limit(2)
+                .and_offset(1)
+                .order_by_str_fast_field("name", Order::Desc),
+        )?;
+        let values: Vec<Option<String>> = top_docs.into_iter().map(|(value, _)| value).collect();
+        assert_eq!(values, vec![value("delta"), value("charlie")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_field_multivalued_order() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field(TITLE, TEXT);
+        let size = schema_builder.add_u64_field(SIZE, FAST);
+        let schema = schema_builder.build();
+        let (index, query) = index("beer", title, schema, |index_writer| {
+            index_writer
+                .add_document(doc!(title => "bottle of beer", size => 33u64, size => 12u64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "growler of beer", size => 64u64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "keg of beer", size => 5u64, size => 100u64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "empty beer"))
+                .unwrap();
+        });
+        let searcher = index.reader()?.searcher();
+
+        let top_collector = TopDocs::with_limit(4).order_by_fast_field(SIZE, Order::Desc);
+        let top_docs: Vec<(u64, DocAddress)> = searcher.search(&query, &top_collector)?;
+        assert_eq!(
+            &top_docs[..],
+            &[
+                (100, DocAddress::new(0, 2)),
+                (64, DocAddress::new(0, 1)),
+                (33, DocAddress::new(0, 0)),
+                (0, DocAddress::new(0, 3)),
+            ]
+        );
+        let top_collector = TopDocs::with_limit(3).order_by_fast_field(SIZE, Order::Asc);
+        let top_docs: Vec<(u64, DocAddress)> = searcher.search(&query, &top_collector)?;
+        assert_eq!(
+            &top_docs[..],
+            &[
+                (5, DocAddress::new(0, 2)),
+                (12, DocAddress::new(0, 0)),
+                (64, DocAddress::new(0, 1)),
+            ]
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,297 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::index::SegmentReader;
+use crate::query::{CostEstimate, Explanation, ScoreBreakdown, Scorer, Weight};
+use crate::{DocId, DocSet, Score, TantivyError, TERMINATED};
+
+/// Number of documents a cancellable scorer goes through between two
+/// checks of its cancellation token.
+const CHECK_INTERVAL: u32 = 64;
+
+/// Token making it possible to abort an in-flight search.
+///
+/// The token is passed to
+/// [`Searcher::search_with_cancellation_token`](crate::Searcher::search_with_cancellation_token),
+/// and can be cancelled from any thread, for instance when the client that issued the
+/// query disconnects. The search then stops iterating over the matching documents,
+/// and returns [`TantivyError::Cancelled`].
+///
+/// Cloning the token returns a handle to the same token.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a new token, which is not cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels the searches using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns [`TantivyError::Cancelled`] if the token was cancelled.
+    pub(crate) fn check(&self) -> crate::Result<()> {
+        if self.is_cancelled() {
+            return Err(TantivyError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+/// Weight wrapper whose scorers stop matching documents
+/// as soon as the cancellation token is cancelled.
+pub(crate) struct CancellableWeight {
+    weight: Box<dyn Weight>,
+    cancellation_token: CancellationToken,
+}
+
+impl CancellableWeight {
+    pub fn new(weight: Box<dyn Weight>, cancellation_token: CancellationToken) -> Self {
+        CancellableWeight {
+            weight,
+            cancellation_token,
+        }
+    }
+}
+
+impl Weight for CancellableWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        self.cancellation_token.check()?;
+        Ok(Box::new(CancellableScorer {
+            scorer: self.weight.scorer(reader, boost)?,
+            cancellation_token: self.cancellation_token.clone(),
+            num_docs_since_check: 0,
+            cancelled: false,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        self.weight.explain(reader, doc)
+    }
+
+    fn score_breakdown(
+        &self,
+        reader: &SegmentReader,
+        doc: DocId,
+        boost: Score,
+        breakdown: &mut ScoreBreakdown,
+    ) -> crate::Result<()> {
+        self.weight.score_breakdown(reader, doc, boost, breakdown)
+    }
+
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        self.weight.estimate_cost(reader)
+    }
+
+    fn for_each_pruning(
+        &self,
+        threshold: Score,
+        reader: &SegmentReader,
+        callback: &mut dyn FnMut(DocId, Score) -> Score,
+    ) -> crate::Result<()> {
+        self.cancellation_token.check()?;
+        // Delegating to the wrapped weight preserves its optimizations, such as BlockWAND.
+        // Once the token is cancelled, an infinite threshold stops the collection.
+        let cancellation_token = &self.cancellation_token;
+        self.weight
+            .for_each_pruning(threshold, reader, &mut |doc, score| {
+                if cancellation_token.is_cancelled() {
+                    return Score::INFINITY;
+                }
+                callback(doc, score)
+            })
+    }
+}
+
+struct CancellableScorer {
+    scorer: Box<dyn Scorer>,
+    cancellation_token: CancellationToken,
+    num_docs_since_check: u32,
+    cancelled: bool,
+}
+
+impl CancellableScorer {
+    fn check_cancelled(&mut self) -> bool {
+        self.num_docs_since_check += 1;
+        if self.num_docs_since_check >= CHECK_INTERVAL {
+            self.num_docs_since_check = 0;
+            self.cancelled = self.cancellation_token.is_cancelled();
+        }
+        self.cancelled
+    }
+}
+
+impl DocSet for CancellableScorer {
+    fn advance(&mut self) -> DocId {
+        if self.check_cancelled() {
+            return TERMINATED;
+        }
+        self.scorer.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.check_cancelled() {
+            return TERMINATED;
+        }
+        self.scorer.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        if self.cancelled {
+            return TERMINATED;
+        }
+        self.scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer.size_hint()
+    }
+}
+
+impl Scorer for CancellableScorer {
+    fn score(&mut self) -> Score {
+        self.scorer.score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::CancellationToken;
+    use crate::collector::{Collector, Count, SegmentCollector, TopDocs};
+    use crate::query::{AllQuery, QueryParser};
+    use crate::schema::{Schema, TEXT};
+    use crate::{DocId, Index, IndexWriter, Score, SegmentReader, TantivyError};
+
+    /// Collector cancelling the token once it collected `limit` documents.
+    struct CancellingCollector {
+        cancellation_token: CancellationToken,
+        limit: usize,
+        num_collected: Arc<AtomicUsize>,
+    }
+
+    struct CancellingSegmentCollector {
+        cancellation_token: CancellationToken,
+        limit: usize,
+        num_collected: Arc<AtomicUsize>,
+    }
+
+    impl Collector for CancellingCollector {
+        type Fruit = ();
+        type Child = CancellingSegmentCollector;
+
+        fn for_segment(
+            &self,
+            _segment_local_id: u32,
+            _segment: &SegmentReader,
+        ) -> crate::Result<CancellingSegmentCollector> {
+            Ok(CancellingSegmentCollector {
+                cancellation_token: self.cancellation_token.clone(),
+                limit: self.limit,
+                num_collected: self.num_collected.clone(),
+            })
+        }
+
+        fn requires_scoring(&self) -> bool {
+            false
+        }
+
+        fn merge_fruits(&self, _segment_fruits: Vec<()>) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SegmentCollector for CancellingSegmentCollector {
+        type Fruit = ();
+
+        fn collect(&mut self, _doc: DocId, _score: Score) {
+            if self.num_collected.fetch_add(1, Ordering::Relaxed) + 1 == self.limit {
+                self.cancellation_token.cancel();
+            }
+        }
+
+        fn harvest(self) {}
+    }
+
+    fn create_index(num_docs: usize) -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..num_docs {
+            let body = if i % 3 == 0 { "hello world" } else { "hello" };
+            index_writer.add_document(doc!(text => body))?;
+        }
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    #[test]
+    fn test_search_not_cancelled() -> crate::Result<()> {
+        let index = create_index(100)?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![]);
+        let query = query_parser.parse_query("text:hello text:world")?;
+        let cancellation_token = CancellationToken::new();
+        let count = searcher.search_with_cancellation_token(&query, &Count, &cancellation_token)?;
+        assert_eq!(count, 100);
+        let top_docs = TopDocs::with_limit(5);
+        assert_eq!(
+            searcher.search_with_cancellation_token(&query, &top_docs, &cancellation_token)?,
+            searcher.search(&query, &top_docs)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_cancelled_before_start() -> crate::Result<()> {
+        let index = create_index(100)?;
+        let searcher = index.reader()?.searcher();
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+        assert!(matches!(
+            searcher.search_with_cancellation_token(&AllQuery, &Count, &cancellation_token),
+            Err(TantivyError::Cancelled)
+        ));
+        assert!(matches!(
+            searcher.search_with_cancellation_token(
+                &AllQuery,
+                &TopDocs::with_limit(10),
+                &cancellation_token
+            ),
+            Err(TantivyError::Cancelled)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_cancelled_while_collecting() -> crate::Result<()> {
+        let num_docs = 10_000;
+        let index = create_index(num_docs)?;
+        let searcher = index.reader()?.searcher();
+        let cancellation_token = CancellationToken::new();
+        let collector = CancellingCollector {
+            cancellation_token: cancellation_token.clone(),
+            limit: 10,
+            num_collected: Arc::default(),
+        };
+        assert!(matches!(
+            searcher.search_with_cancellation_token(&AllQuery, &collector, &cancellation_token),
+            Err(TantivyError::Cancelled)
+        ));
+        assert!(collector.num_collected.load(Ordering::Relaxed) < num_docs / 10);
+        Ok(())
+    }
+}
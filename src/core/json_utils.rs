@@ -4,7 +4,7 @@ use rustc_hash::FxHashMap;
 
 use crate::postings::{IndexingContext, IndexingPosition, PostingsWriter};
 use crate::schema::document::{ReferenceValue, ReferenceValueLeaf, Value};
-use crate::schema::Type;
+use crate::schema::{Type, DEFAULT_POSITION_GAP};
 use crate::time::format_description::well_known::Rfc3339;
 use crate::time::{OffsetDateTime, UtcOffset};
 use crate::tokenizer::TextAnalyzer;
@@ -139,6 +139,7 @@ pub(crate) fn index_json_value<'a, V: Value<'a>>(
                     term_buffer,
                     ctx,
                     indexing_position,
+                    DEFAULT_POSITION_GAP,
                 );
             }
             ReferenceValueLeaf::U64(val) => {
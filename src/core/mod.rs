@@ -1,14 +1,18 @@
+mod cancellation;
 mod executor;
 #[doc(hidden)]
 pub mod json_utils;
 pub mod searcher;
+mod typed_hits;
 
 use std::path::Path;
 
 use once_cell::sync::Lazy;
 
+pub(crate) use self::cancellation::CancellableWeight;
+pub use self::cancellation::CancellationToken;
 pub use self::executor::Executor;
-pub use self::searcher::{Searcher, SearcherGeneration};
+pub use self::searcher::{FastFieldValuesOrder, SearchStream, Searcher, SearcherGeneration};
 
 /// The meta file contains all the information about the list of segments and the schema
 /// of the index.
@@ -1,16 +1,42 @@
-use std::collections::BTreeMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 use std::{fmt, io};
 
-use crate::collector::Collector;
-use crate::core::Executor;
-use crate::index::{SegmentId, SegmentReader};
-use crate::query::{Bm25StatisticsProvider, EnableScoring, Query};
+use columnar::{Column, DynamicColumn, HasAssociatedColumnType};
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+
+use crate::collector::{Collector, SegmentCollector, TopDocs};
+use crate::core::{typed_hits, CancellableWeight, CancellationToken, Executor};
+use crate::index::{SegmentComponent, SegmentId, SegmentReader};
+use crate::postings::Postings;
+use crate::query::{
+    intersect_scorers, Bm25StatisticsProvider, BooleanQuery, ConstScoreQuery, EnableScoring, Query,
+    QueryPlan, Scorer, SegmentQueryPlan, TermQuery, Weight,
+};
 use crate::schema::document::DocumentDeserialize;
-use crate::schema::{Schema, Term};
+use crate::schema::{IndexRecordOption, Schema, Term};
 use crate::space_usage::SearcherSpaceUsage;
 use crate::store::{CacheStats, StoreReader};
-use crate::{DocAddress, Index, Opstamp, TrackedObject};
+use crate::{
+    DocAddress, DocId, DocSet, Index, Opstamp, Score, SegmentOrdinal, TantivyError, TrackedObject,
+    TERMINATED,
+};
+
+/// Order of the values returned by [`Searcher::fast_field_values`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FastFieldValuesOrder {
+    /// Values are returned by increasing doc address. The values of a multivalued
+    /// document are returned in the order in which they are stored.
+    #[default]
+    DocAddress,
+    /// Values are returned by increasing value, ties being broken by increasing
+    /// doc address.
+    ///
+    /// The values of each segment are sorted in memory, and then merged across segments.
+    Value,
+}
 
 /// Identifies the searcher generation accessed by a [`Searcher`].
 ///
@@ -86,10 +112,46 @@ impl Searcher {
     /// The searcher uses the segment ordinal to route the
     /// request to the right `Segment`.
     pub fn doc<D: DocumentDeserialize>(&self, doc_address: DocAddress) -> crate::Result<D> {
-        let store_reader = &self.inner.store_readers[doc_address.segment_ord as usize];
+        let store_reader = self.store_reader(doc_address.segment_ord)?;
         store_reader.get(doc_address.doc_id)
     }
 
+    /// Fetches the documents of the given addresses, in order.
+    ///
+    /// The documents are decompressed ahead in the search thread pool of the index (See
+    /// [`Index::set_multithread_executor`]) while the returned iterator is consumed. This
+    /// keeps the calling thread responsive when fetching many documents, e.g. to export them.
+    /// When called from a thread of the search thread pool, e.g. from a collector, the
+    /// documents are decompressed in the calling thread instead. See [`StoreReader::get_many`].
+    pub fn docs<'a, D: DocumentDeserialize + 'a>(
+        &'a self,
+        doc_addresses: &[DocAddress],
+    ) -> crate::Result<impl Iterator<Item = crate::Result<D>> + 'a> {
+        let executor = self.inner.index.search_executor();
+        let mut segment_docs = Vec::new();
+        let segment_doc_addresses = doc_addresses
+            .iter()
+            .chunk_by(|doc_address| doc_address.segment_ord);
+        for (segment_ord, doc_addresses) in &segment_doc_addresses {
+            let store_reader = self.store_reader(segment_ord)?;
+            let doc_ids = doc_addresses
+                .map(|doc_address| doc_address.doc_id)
+                .collect();
+            segment_docs.push(store_reader.get_many(doc_ids, executor)?);
+        }
+        Ok(segment_docs.into_iter().flatten())
+    }
+
+    fn store_reader(&self, segment_ord: u32) -> crate::Result<&StoreReader> {
+        self.inner.store_readers[segment_ord as usize]
+            .as_ref()
+            .ok_or_else(|| {
+                crate::TantivyError::InvalidArgument(format!(
+                    "The store of segment #{segment_ord} was not opened."
+                ))
+            })
+    }
+
     /// The cache stats for the underlying store reader.
     ///
     /// Aggregates the sum for each segment store reader.
@@ -98,6 +160,7 @@ impl Searcher {
             .inner
             .store_readers
             .iter()
+            .flatten()
             .map(|reader| reader.cache_stats())
             .sum();
         cache_stats
@@ -110,7 +173,7 @@ impl Searcher {
         doc_address: DocAddress,
     ) -> crate::Result<D> {
         let executor = self.inner.index.search_executor();
-        let store_reader = &self.inner.store_readers[doc_address.segment_ord as usize];
+        let store_reader = self.store_reader(doc_address.segment_ord)?;
         store_reader.get_async(doc_address.doc_id, executor).await
     }
 
@@ -128,6 +191,108 @@ impl Searcher {
             .sum::<u64>()
     }
 
+    /// Returns the number of alive documents matching the query.
+    ///
+    /// This is a shortcut for [`Query::count`]: the documents are neither scored
+    /// nor loaded from the doc store.
+    pub fn count(&self, query: &dyn Query) -> crate::Result<usize> {
+        query.count(self)
+    }
+
+    /// Returns, for each segment, the number of alive documents matching both queries.
+    ///
+    /// The counts are indexed by segment ordinal, and sum up to the number of documents
+    /// of the searcher matching both queries. Each query skips to the next document of the
+    /// other one, so that counting the co-occurrences of a rare and a frequent term
+    /// is cheap. This is handy to build co-occurrence matrices, or to suggest related terms.
+    pub fn cooccurrence_counts(
+        &self,
+        left_query: &dyn Query,
+        right_query: &dyn Query,
+    ) -> crate::Result<Vec<u32>> {
+        let enable_scoring = EnableScoring::disabled_from_searcher(self);
+        let left_weight = left_query.weight(enable_scoring)?;
+        let right_weight = right_query.weight(enable_scoring)?;
+        self.segment_readers()
+            .iter()
+            .map(|segment_reader| {
+                let mut intersection = intersect_scorers(vec![
+                    left_weight.scorer(segment_reader, 1.0)?,
+                    right_weight.scorer(segment_reader, 1.0)?,
+                ]);
+                let count = if let Some(alive_bitset) = segment_reader.alive_bitset() {
+                    intersection.count(alive_bitset)
+                } else {
+                    intersection.count_including_deleted()
+                };
+                Ok(count)
+            })
+            .collect()
+    }
+
+    /// Returns the values of the fast field `field_name` of all of the alive documents
+    /// of the searcher, along with their doc address.
+    ///
+    /// Documents without a value are skipped, and a multivalued document yields one
+    /// item per value. Segments in which the field has no column of type `T` are
+    /// skipped as well.
+    ///
+    /// This is meant for bulk exports, e.g. to extract features for a machine learning
+    /// pipeline. With [`FastFieldValuesOrder::DocAddress`] the values are read lazily.
+    pub fn fast_field_values<T>(
+        &self,
+        field_name: &str,
+        order: FastFieldValuesOrder,
+    ) -> crate::Result<Box<dyn Iterator<Item = (DocAddress, T)>>>
+    where
+        T: HasAssociatedColumnType,
+        DynamicColumn: Into<Option<Column<T>>>,
+    {
+        let mut segment_values = Vec::new();
+        for (segment_ord, segment_reader) in self.segment_readers().iter().enumerate() {
+            let Some(column) = segment_reader.fast_fields().column_opt::<T>(field_name)? else {
+                continue;
+            };
+            let alive_bitset_opt = segment_reader.alive_bitset().cloned();
+            let segment_ord = segment_ord as SegmentOrdinal;
+            let values = (0..segment_reader.max_doc())
+                .filter(move |doc| {
+                    alive_bitset_opt
+                        .as_ref()
+                        .map_or(true, |alive_bitset| alive_bitset.is_alive(*doc))
+                })
+                .flat_map(move |doc: DocId| {
+                    let column_values = column.values.clone();
+                    column.index.value_row_ids(doc).map(move |row_id| {
+                        (
+                            DocAddress::new(segment_ord, doc),
+                            column_values.get_val(row_id),
+                        )
+                    })
+                });
+            segment_values.push(values);
+        }
+        match order {
+            FastFieldValuesOrder::DocAddress => Ok(Box::new(segment_values.into_iter().flatten())),
+            FastFieldValuesOrder::Value => {
+                let sorted_segment_values = segment_values.into_iter().map(|values| {
+                    let mut values: Vec<(DocAddress, T)> = values.collect();
+                    // The sort is stable, so that equal values stay sorted by doc address.
+                    values.sort_by(|left, right| {
+                        left.1.partial_cmp(&right.1).unwrap_or(Ordering::Equal)
+                    });
+                    values
+                });
+                Ok(Box::new(sorted_segment_values.kmerge_by(|left, right| {
+                    match left.1.partial_cmp(&right.1) {
+                        Some(Ordering::Equal) | None => left.0 < right.0,
+                        Some(ordering) => ordering == Ordering::Less,
+                    }
+                })))
+            }
+        }
+    }
+
     /// Return the overall number of documents containing
     /// the given term.
     pub fn doc_freq(&self, term: &Term) -> crate::Result<u64> {
@@ -154,6 +319,10 @@ impl Searcher {
     }
 
     /// Return the list of segment readers
+    ///
+    /// The position of a segment reader in the list is its segment ordinal. The ordinals
+    /// do not change over the lifetime of the searcher, and are the ones used in the
+    /// [`DocAddress`] of the documents of the segment.
     pub fn segment_readers(&self) -> &[SegmentReader] {
         &self.inner.segment_readers
     }
@@ -163,6 +332,115 @@ impl Searcher {
         &self.inner.segment_readers[segment_ord as usize]
     }
 
+    /// Creates the weight of the query to be passed to [`Searcher::search_segment`].
+    ///
+    /// Scoring is enabled if the collector requires it.
+    pub fn weight<C: Collector>(
+        &self,
+        query: &dyn Query,
+        collector: &C,
+    ) -> crate::Result<Box<dyn Weight>> {
+        let enabled_scoring = if collector.requires_scoring() {
+            EnableScoring::enabled_from_searcher(self)
+        } else {
+            EnableScoring::disabled_from_searcher(self)
+        };
+        query.weight(enabled_scoring)
+    }
+
+    /// Collects the documents of a single segment matching the weight.
+    ///
+    /// Together with [`Searcher::weight`] and [`Collector::merge_fruits`], this makes it
+    /// possible to implement a custom scheduling of the segments, e.g. searching the
+    /// small segments first or skipping some of them, while [`Searcher::search`]
+    /// searches all of the segments.
+    ///
+    /// ```rust
+    /// use tantivy::collector::{Collector, Count};
+    /// use tantivy::query::AllQuery;
+    /// use tantivy::schema::{Schema, TEXT};
+    /// use tantivy::{doc, Index, IndexWriter};
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let index = Index::create_in_ram(schema_builder.build());
+    /// let mut index_writer: IndexWriter = index.writer(15_000_000)?;
+    /// index_writer.add_document(doc!(title => "The Name of the Wind"))?;
+    /// index_writer.commit()?;
+    /// index_writer.add_document(doc!(title => "Dune"))?;
+    /// index_writer.add_document(doc!(title => "La Vénus d'Ille"))?;
+    /// index_writer.commit()?;
+    ///
+    /// let searcher = index.reader()?.searcher();
+    /// let weight = searcher.weight(&AllQuery, &Count)?;
+    /// // Searches the smallest segments first.
+    /// let mut segment_ords: Vec<u32> = (0..searcher.segment_readers().len() as u32).collect();
+    /// segment_ords.sort_by_key(|&segment_ord| searcher.segment_reader(segment_ord).num_docs());
+    /// let mut fruits = Vec::new();
+    /// for segment_ord in segment_ords {
+    ///     fruits.push(searcher.search_segment(segment_ord, weight.as_ref(), &Count)?);
+    /// }
+    /// assert_eq!(Count.merge_fruits(fruits)?, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search_segment<C: Collector>(
+        &self,
+        segment_ord: SegmentOrdinal,
+        weight: &dyn Weight,
+        collector: &C,
+    ) -> crate::Result<<C::Child as SegmentCollector>::Fruit> {
+        let segment_reader = self
+            .segment_readers()
+            .get(segment_ord as usize)
+            .ok_or_else(|| {
+                TantivyError::InvalidArgument(format!(
+                    "Segment ordinal {segment_ord} is out of bounds, the searcher has {} segments.",
+                    self.segment_readers().len()
+                ))
+            })?;
+        collector.collect_segment(weight, segment_ord, segment_reader)
+    }
+
+    /// Returns the terms of the query that appear in the given document,
+    /// together with the positions at which they appear.
+    ///
+    /// Terms are deduplicated. The positions of a term are empty if its field
+    /// was not indexed with positions.
+    ///
+    /// This is a building block for custom highlighters or explain-style
+    /// UIs. It does not check that the document actually matches the query.
+    pub fn matching_terms(
+        &self,
+        query: &dyn Query,
+        doc_address: DocAddress,
+    ) -> crate::Result<BTreeMap<Term, Vec<u32>>> {
+        let segment_reader = self.segment_reader(doc_address.segment_ord);
+        let mut terms: BTreeSet<&Term> = BTreeSet::new();
+        query.query_terms(&mut |term, _| {
+            terms.insert(term);
+        });
+        let mut matching_terms = BTreeMap::new();
+        for term in terms {
+            let inverted_index = segment_reader.inverted_index(term.field())?;
+            let Some(mut postings) =
+                inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)?
+            else {
+                continue;
+            };
+            if postings.doc() > doc_address.doc_id
+                || postings.seek(doc_address.doc_id) != doc_address.doc_id
+            {
+                continue;
+            }
+            let mut positions = Vec::new();
+            postings.positions(&mut positions);
+            matching_terms.insert(term.clone(), positions);
+        }
+        Ok(matching_terms)
+    }
+
     /// Runs a query on the segment readers wrapped by the searcher.
     ///
     /// Search works as follows :
@@ -185,6 +463,114 @@ impl Searcher {
         self.search_with_statistics_provider(query, collector, self)
     }
 
+    /// Runs a query with a [`TopDocs`] collector, and deserializes each hit into a `T`.
+    ///
+    /// `T` is deserialized with serde from an object holding the score of the hit under
+    /// the `_score` key, and the values of the fields of the document named like the fields
+    /// of `T`. The values of stored fields are read from the doc store, those of the other
+    /// fast fields from their fast field. Only the fields of `T` are read, so that a `T` made
+    /// of fast fields only does not access the doc store at all.
+    ///
+    /// The shape of the values of a field is decided by the type of the matching field of
+    /// `T`: a sequence (e.g. a `Vec`) gets all of the values of the field, even if the
+    /// document has only one, and any other type gets its single value. If `T` is not a
+    /// struct (e.g. a map), it is deserialized from all of the stored fields.
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use tantivy::collector::TopDocs;
+    /// use tantivy::query::QueryParser;
+    /// use tantivy::schema::{Schema, FAST, STORED, TEXT};
+    /// use tantivy::{doc, Index, Score};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct BookHit {
+    ///     #[serde(rename = "_score")]
+    ///     score: Score,
+    ///     title: String,
+    ///     year: u64,
+    /// }
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT | STORED);
+    /// let year = schema_builder.add_u64_field("year", FAST);
+    /// let index = Index::create_in_ram(schema_builder.build());
+    /// let mut index_writer = index.writer(15_000_000)?;
+    /// index_writer.add_document(doc!(title => "Of Mice and Men", year => 1937u64))?;
+    /// index_writer.add_document(doc!(title => "The Old Man and the Sea", year => 1952u64))?;
+    /// index_writer.commit()?;
+    ///
+    /// let searcher = index.reader()?.searcher();
+    /// let query = QueryParser::for_index(&index, vec![title]).parse_query("sea")?;
+    /// let hits: Vec<BookHit> = searcher.search_into(&query, &TopDocs::with_limit(10))?;
+    /// assert_eq!(hits.len(), 1);
+    /// assert_eq!(hits[0].title, "The Old Man and the Sea");
+    /// assert_eq!(hits[0].year, 1952);
+    /// assert!(hits[0].score > 0.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search_into<T: DeserializeOwned>(
+        &self,
+        query: &dyn Query,
+        top_docs: &TopDocs,
+    ) -> crate::Result<Vec<T>> {
+        let field_names = typed_hits::struct_field_names::<T>();
+        self.search(query, top_docs)?
+            .into_iter()
+            .map(|(score, doc_address)| {
+                typed_hits::deserialize_hit(self, score, doc_address, field_names)
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over all of the alive documents matching the query, with their
+    /// score, by increasing doc address.
+    ///
+    /// Unlike [`Searcher::search`], the hits are produced lazily: the scorer of a segment is
+    /// only created once the hits of the previous segments have been consumed, and dropping
+    /// the iterator stops the search. This suits consumers that process every match, e.g. to
+    /// export them or to join them with another data source, without collector semantics.
+    ///
+    /// Only the weight of the query is computed upfront. Errors occurring while opening the
+    /// scorer of a segment are returned by the iterator.
+    ///
+    /// ```rust
+    /// use tantivy::query::QueryParser;
+    /// use tantivy::schema::{Schema, TEXT};
+    /// use tantivy::{doc, DocAddress, Index};
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let index = Index::create_in_ram(schema_builder.build());
+    /// let mut index_writer = index.writer(15_000_000)?;
+    /// index_writer.add_document(doc!(title => "The Name of the Wind"))?;
+    /// index_writer.add_document(doc!(title => "A Dairy Cow"))?;
+    /// index_writer.add_document(doc!(title => "The Diary of Muadib"))?;
+    /// index_writer.commit()?;
+    ///
+    /// let searcher = index.reader()?.searcher();
+    /// let query = QueryParser::for_index(&index, vec![title]).parse_query("the")?;
+    /// let doc_addresses = searcher
+    ///     .search_stream(&query)?
+    ///     .map(|hit| hit.map(|(_score, doc_address)| doc_address))
+    ///     .collect::<tantivy::Result<Vec<DocAddress>>>()?;
+    /// assert_eq!(doc_addresses, vec![DocAddress::new(0, 0), DocAddress::new(0, 2)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search_stream(&self, query: &dyn Query) -> crate::Result<SearchStream> {
+        let weight = query.weight(EnableScoring::enabled_from_searcher(self))?;
+        Ok(SearchStream {
+            searcher: self.clone(),
+            weight,
+            next_segment_ord: 0,
+            segment_scorer: None,
+        })
+    }
+
     /// Same as [`search(...)`](Searcher::search) but allows specifying
     /// a [Bm25StatisticsProvider].
     ///
@@ -235,6 +621,121 @@ impl Searcher {
         collector.merge_fruits(fruits)
     }
 
+    /// Same as [`search(...)`](Searcher::search), but the search can be aborted
+    /// with the given [`CancellationToken`].
+    ///
+    /// The token is checked cooperatively while iterating over the matching documents,
+    /// so that cancelling it frees the search threads almost immediately.
+    ///
+    /// Returns [`TantivyError::Cancelled`](crate::TantivyError::Cancelled) if the token
+    /// was cancelled before the search completed.
+    pub fn search_with_cancellation_token<C: Collector>(
+        &self,
+        query: &dyn Query,
+        collector: &C,
+        cancellation_token: &CancellationToken,
+    ) -> crate::Result<C::Fruit> {
+        cancellation_token.check()?;
+        let enabled_scoring = if collector.requires_scoring() {
+            EnableScoring::enabled_from_searcher(self)
+        } else {
+            EnableScoring::disabled_from_searcher(self)
+        };
+        let weight =
+            CancellableWeight::new(query.weight(enabled_scoring)?, cancellation_token.clone());
+        let executor = self.inner.index.search_executor();
+        let segment_readers = self.segment_readers();
+        let fruits = executor.map(
+            |(segment_ord, segment_reader)| {
+                cancellation_token.check()?;
+                let fruit =
+                    collector.collect_segment(&weight, segment_ord as u32, segment_reader)?;
+                // The scorers stop early once the token is cancelled,
+                // so the fruit may be incomplete.
+                cancellation_token.check()?;
+                Ok(fruit)
+            },
+            segment_readers.iter().enumerate(),
+        )?;
+        collector.merge_fruits(fruits)
+    }
+
+    /// Same as [`search(...)`](Searcher::search), but only returns the documents
+    /// of the given tenant.
+    ///
+    /// The documents are filtered on the value of the
+    /// [routing field](crate::IndexSettings::routing_field) of the index,
+    /// and the segments holding the documents of other tenants only are not searched at all.
+    ///
+    /// Returns an error if the index has no routing field.
+    pub fn search_with_routing_key<C: Collector>(
+        &self,
+        query: &dyn Query,
+        collector: &C,
+        routing_key: &str,
+    ) -> crate::Result<C::Fruit> {
+        let routing_field = self.index().routing_field()?.ok_or_else(|| {
+            TantivyError::InvalidArgument("The index has no routing field.".to_string())
+        })?;
+        // The filter does not contribute to the score.
+        let routing_filter = ConstScoreQuery::new(
+            Box::new(TermQuery::new(
+                Term::from_field_text(routing_field, routing_key),
+                IndexRecordOption::Basic,
+            )),
+            0.0,
+        );
+        let routed_query =
+            BooleanQuery::intersection(vec![query.box_clone(), Box::new(routing_filter)]);
+        let enabled_scoring = if collector.requires_scoring() {
+            EnableScoring::enabled_from_searcher(self)
+        } else {
+            EnableScoring::disabled_from_searcher(self)
+        };
+        let weight = routed_query.weight(enabled_scoring)?;
+        let executor = self.inner.index.search_executor();
+        // Segment ordinals are preserved, so that the document addresses stay valid.
+        let may_contain_routing_key =
+            |segment_reader: &SegmentReader| match segment_reader.routing_key() {
+                Some(segment_routing_key) => segment_routing_key == routing_key,
+                None => true,
+            };
+        let segment_readers = self
+            .segment_readers()
+            .iter()
+            .enumerate()
+            .filter(|(_, segment_reader)| may_contain_routing_key(segment_reader));
+        let fruits = executor.map(
+            |(segment_ord, segment_reader)| {
+                collector.collect_segment(weight.as_ref(), segment_ord as u32, segment_reader)
+            },
+            segment_readers,
+        )?;
+        collector.merge_fruits(fruits)
+    }
+
+    /// Describes how the query would be executed, without executing it.
+    ///
+    /// The plan contains the query tree, as rewritten by the query parser, and for each
+    /// segment the estimated number of documents the query goes through and the number
+    /// of terms it expands to.
+    ///
+    /// The plan is estimated from the term dictionaries and the document frequencies of
+    /// the terms, with scoring disabled: no scorer is built and no postings are read.
+    /// See [`Weight::estimate_cost`](crate::query::Weight::estimate_cost).
+    pub fn explain_plan(&self, query: &dyn Query) -> crate::Result<QueryPlan> {
+        let weight = query.weight(EnableScoring::disabled_from_searcher(self))?;
+        let mut segments = Vec::with_capacity(self.segment_readers().len());
+        for (segment_ord, segment_reader) in self.segment_readers().iter().enumerate() {
+            segments.push(SegmentQueryPlan::new(
+                segment_ord as u32,
+                segment_reader.num_docs(),
+                weight.estimate_cost(segment_reader)?,
+            ));
+        }
+        Ok(QueryPlan::new(format!("{query:#?}"), segments))
+    }
+
     /// Summarize total space usage of this searcher.
     pub fn space_usage(&self) -> io::Result<SearcherSpaceUsage> {
         let mut space_usage = SearcherSpaceUsage::new();
@@ -245,6 +746,45 @@ impl Searcher {
     }
 }
 
+/// Iterator over the hits of a query, returned by [`Searcher::search_stream`].
+///
+/// The hits are `(Score, DocAddress)` pairs, by increasing doc address.
+pub struct SearchStream {
+    searcher: Searcher,
+    weight: Box<dyn Weight>,
+    next_segment_ord: SegmentOrdinal,
+    segment_scorer: Option<(SegmentOrdinal, Box<dyn Scorer>)>,
+}
+
+impl Iterator for SearchStream {
+    type Item = crate::Result<(Score, DocAddress)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((segment_ord, scorer)) = self.segment_scorer.as_mut() {
+                let alive_bitset_opt = self.searcher.segment_reader(*segment_ord).alive_bitset();
+                let mut doc = scorer.doc();
+                while doc != TERMINATED {
+                    if alive_bitset_opt.map_or(true, |alive_bitset| alive_bitset.is_alive(doc)) {
+                        let score = scorer.score();
+                        scorer.advance();
+                        return Some(Ok((score, DocAddress::new(*segment_ord, doc))));
+                    }
+                    doc = scorer.advance();
+                }
+                self.segment_scorer = None;
+            }
+            let segment_ord = self.next_segment_ord;
+            let segment_reader = self.searcher.segment_readers().get(segment_ord as usize)?;
+            self.next_segment_ord += 1;
+            match self.weight.scorer(segment_reader, 1.0) {
+                Ok(scorer) => self.segment_scorer = Some((segment_ord, scorer)),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
 impl From<Arc<SearcherInner>> for Searcher {
     fn from(inner: Arc<SearcherInner>) -> Self {
         Searcher { inner }
@@ -259,7 +799,8 @@ pub(crate) struct SearcherInner {
     schema: Schema,
     index: Index,
     segment_readers: Vec<SegmentReader>,
-    store_readers: Vec<StoreReader>,
+    // `None` for segments opened without their store component.
+    store_readers: Vec<Option<StoreReader>>,
     generation: TrackedObject<SearcherGeneration>,
 }
 
@@ -280,9 +821,17 @@ impl SearcherInner {
             generation.segments(),
             "Set of segments referenced by this Searcher and its SearcherGeneration must match"
         );
-        let store_readers: Vec<StoreReader> = segment_readers
+        let store_readers: Vec<Option<StoreReader>> = segment_readers
             .iter()
-            .map(|segment_reader| segment_reader.get_store_reader(doc_store_cache_num_blocks))
+            .map(|segment_reader| {
+                if segment_reader.has_component(SegmentComponent::Store) {
+                    segment_reader
+                        .get_store_reader(doc_store_cache_num_blocks)
+                        .map(Some)
+                } else {
+                    Ok(None)
+                }
+            })
             .collect::<io::Result<Vec<_>>>()?;
 
         Ok(SearcherInner {
@@ -1,14 +1,21 @@
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use itertools::Itertools;
+
 use crate::collector::Count;
-use crate::directory::{RamDirectory, WatchCallback};
-use crate::index::SegmentId;
+use crate::directory::error::{DeleteError, OpenReadError, OpenWriteError};
+use crate::directory::{FileHandle, RamDirectory, WatchCallback, WatchHandle, WritePtr};
+use crate::index::{SegmentComponent, SegmentId};
 use crate::indexer::{LogMergePolicy, NoMergePolicy};
 use crate::postings::Postings;
-use crate::query::TermQuery;
-use crate::schema::{Field, IndexRecordOption, Schema, INDEXED, STRING, TEXT};
+use crate::query::{PhraseQuery, TermQuery};
+use crate::schema::{Field, IndexRecordOption, Schema, FAST, INDEXED, STORED, STRING, TEXT};
 use crate::tokenizer::TokenizerManager;
 use crate::{
-    Directory, DocSet, Index, IndexBuilder, IndexReader, IndexSettings, IndexWriter, ReloadPolicy,
-    TantivyDocument, Term,
+    doc, Directory, DocSet, Index, IndexBuilder, IndexReader, IndexSettings, IndexWriter,
+    ReloadPolicy, SegmentReader, TantivyDocument, TantivyError, Term,
 };
 
 #[test]
@@ -25,6 +32,48 @@ fn test_indexer_for_field() {
     );
 }
 
+#[test]
+fn test_index_tokenize() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    schema_builder.add_u64_field("num_likes", INDEXED);
+    schema_builder.add_text_field("body", TEXT);
+    schema_builder.add_text_field("id", STRING);
+    let index = Index::create_in_ram(schema_builder.build());
+
+    let tokens = index.tokenize("body", "Happy tax payer")?;
+    let tokens: Vec<(usize, &str, usize, usize)> = tokens
+        .iter()
+        .map(|token| {
+            (
+                token.position,
+                token.text.as_str(),
+                token.offset_from,
+                token.offset_to,
+            )
+        })
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![(0, "happy", 0, 5), (1, "tax", 6, 9), (2, "payer", 10, 15)]
+    );
+    let tokens = index.tokenize("id", "Happy tax payer")?;
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].text, "Happy tax payer");
+    let tokens = index.tokenize("whitespace", "Happy tax payer!")?;
+    assert_eq!(tokens.len(), 3);
+    assert_eq!(tokens[2].text, "payer!");
+
+    assert!(matches!(
+        index.tokenize("num_likes", "3"),
+        Err(TantivyError::SchemaError(_))
+    ));
+    assert!(matches!(
+        index.tokenize("unknown", "text"),
+        Err(TantivyError::InvalidArgument(_))
+    ));
+    Ok(())
+}
+
 #[test]
 fn test_set_tokenizer_manager() {
     let mut schema_builder = Schema::builder();
@@ -106,8 +155,85 @@ fn open_or_create_exists_but_schema_does_not_match() {
     let err = Index::open_or_create(directory, Schema::builder().build());
     assert_eq!(
         format!("{:?}", err.unwrap_err()),
-        "SchemaError(\"An index exists but the schema does not match.\")"
+        "SchemaError(\"An index exists but the schema does not match: field `num_likes` was \
+         removed.\")"
+    );
+}
+
+#[test]
+fn open_or_create_with_new_fields() {
+    let directory = RamDirectory::create();
+    Index::create(
+        directory.clone(),
+        throw_away_schema(),
+        IndexSettings::default(),
+    )
+    .unwrap();
+    let mut schema_builder = Schema::builder();
+    schema_builder.add_u64_field("num_likes", INDEXED);
+    schema_builder.add_text_field("title", TEXT);
+    let extended_schema = schema_builder.build();
+    let index = Index::open_or_create(directory.clone(), extended_schema.clone()).unwrap();
+    assert_eq!(index.schema(), extended_schema);
+
+    // New fields must be appended after the existing ones.
+    let mut schema_builder = Schema::builder();
+    schema_builder.add_text_field("title", TEXT);
+    schema_builder.add_u64_field("num_likes", INDEXED);
+    let err = Index::open_with_schema(directory, schema_builder.build());
+    assert!(matches!(err, Err(TantivyError::SchemaError(_))));
+}
+
+#[test]
+fn test_open_with_schema_new_fields() -> crate::Result<()> {
+    let directory = RamDirectory::create();
+    let schema = throw_away_schema();
+    let num_likes = schema.get_field("num_likes").unwrap();
+    let index = Index::create(directory.clone(), schema, IndexSettings::default())?;
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.add_document(doc!(num_likes => 1u64))?;
+    index_writer.add_document(doc!(num_likes => 2u64))?;
+    index_writer.commit()?;
+    drop(index_writer);
+
+    let mut schema_builder = Schema::builder();
+    schema_builder.add_u64_field("num_likes", INDEXED);
+    let title = schema_builder.add_text_field("title", TEXT | STORED);
+    let rating = schema_builder.add_u64_field("rating", FAST);
+    let index = Index::open_with_schema(directory.clone(), schema_builder.build())?;
+    let title_query = TermQuery::new(
+        Term::from_field_text(title, "hello"),
+        IndexRecordOption::WithFreqs,
     );
+
+    // The segment written with the old schema has no data for the new fields.
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.search(&title_query, &Count)?, 0);
+    let segment_reader = searcher.segment_reader(0);
+    assert!(segment_reader
+        .fast_fields()
+        .column_opt::<u64>("rating")?
+        .is_none());
+
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.add_document(doc!(num_likes => 3u64, title => "hello", rating => 5u64))?;
+    index_writer.commit()?;
+    let segment_ids = index.searchable_segment_ids()?;
+    index_writer.merge(&segment_ids).wait()?;
+    index_writer.wait_merging_threads()?;
+
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.num_docs(), 3);
+    assert_eq!(searcher.segment_readers().len(), 1);
+    assert_eq!(searcher.search(&title_query, &Count)?, 1);
+    let ratings = searcher.segment_reader(0).fast_fields().u64("rating")?;
+    let num_rated_docs = (0..3).filter(|&doc| ratings.first(doc).is_some()).count();
+    assert_eq!(num_rated_docs, 1);
+
+    // The extended schema was persisted by the commit.
+    let reopened_index = Index::open(directory)?;
+    assert_eq!(reopened_index.schema(), index.schema());
+    Ok(())
 }
 
 fn throw_away_schema() -> Schema {
@@ -276,6 +402,96 @@ fn garbage_collect_works_as_intended() -> crate::Result<()> {
     Ok(())
 }
 
+/// A `RamDirectory` that does not advertise that deleted files remain readable,
+/// so that the garbage collection has to defer the deletion of opened files.
+#[derive(Clone, Debug)]
+struct DeferredDeleteDirectory(RamDirectory);
+
+impl Directory for DeferredDeleteDirectory {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        self.0.get_file_handle(path)
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        self.0.delete(path)
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        self.0.exists(path)
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        self.0.open_write(path)
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        self.0.atomic_read(path)
+    }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.0.atomic_write(path, data)
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        self.0.sync_directory()
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> crate::Result<WatchHandle> {
+        self.0.watch(watch_callback)
+    }
+}
+
+#[test]
+fn garbage_collect_defers_deletion_of_opened_files() -> crate::Result<()> {
+    let ram_directory = RamDirectory::create();
+    let directory = DeferredDeleteDirectory(ram_directory.clone());
+    let schema = throw_away_schema();
+    let field = schema.get_field("num_likes").unwrap();
+    let index = Index::create(directory, schema, IndexSettings::default())?;
+
+    let mut writer: IndexWriter = index.writer_with_num_threads(1, 32_000_000).unwrap();
+    for _seg in 0..8 {
+        for i in 0u64..1_000u64 {
+            writer.add_document(doc!(field => i))?;
+        }
+        writer.commit()?;
+    }
+
+    let mem_right_after_commit = ram_directory.total_mem_usage();
+
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    assert_eq!(reader.searcher().num_docs(), 8_000);
+    assert_eq!(reader.searcher().segment_readers().len(), 8);
+
+    writer.wait_merging_threads()?;
+
+    // The files of the merged segments are still opened by the reader,
+    // so they cannot have been garbage collected yet.
+    let mem_right_after_merge_finished = ram_directory.total_mem_usage();
+    assert!(
+        mem_right_after_merge_finished > mem_right_after_commit,
+        "(mem after merge){mem_right_after_merge_finished} is expected > (mem before \
+         merge){mem_right_after_commit}"
+    );
+
+    reader.reload().unwrap();
+    let searcher = reader.searcher();
+    assert_eq!(searcher.segment_readers().len(), 1);
+    assert_eq!(searcher.num_docs(), 8_000);
+
+    let writer: IndexWriter = index.writer_with_num_threads(1, 32_000_000).unwrap();
+    writer.garbage_collect_files().wait()?;
+    let mem_after_gc = ram_directory.total_mem_usage();
+    assert!(
+        mem_after_gc < mem_right_after_commit,
+        "(mem after gc){mem_after_gc} is expected < (mem before merge){mem_right_after_commit}"
+    );
+    Ok(())
+}
+
 #[test]
 fn test_single_segment_index_writer() -> crate::Result<()> {
     let mut schema_builder = Schema::builder();
@@ -300,6 +516,215 @@ fn test_single_segment_index_writer() -> crate::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_index_reader_skip_components() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", TEXT | STORED);
+    let schema = schema_builder.build();
+    let index = Index::create_in_ram(schema);
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.add_document(doc!(text_field=>"hello happy tax payer"))?;
+    index_writer.add_document(doc!(text_field=>"hello"))?;
+    index_writer.commit()?;
+    let reader = index
+        .reader_builder()
+        .skip_components(vec![SegmentComponent::Positions, SegmentComponent::Store])
+        .try_into()?;
+    let searcher = reader.searcher();
+    let segment_reader = searcher.segment_reader(0);
+    assert!(!segment_reader.has_component(SegmentComponent::Positions));
+    assert!(!segment_reader.has_component(SegmentComponent::Store));
+    assert!(segment_reader.has_component(SegmentComponent::Postings));
+    let term_query = TermQuery::new(
+        Term::from_field_text(text_field, "hello"),
+        IndexRecordOption::WithFreqsAndPositions,
+    );
+    assert_eq!(searcher.search(&term_query, &Count)?, 2);
+    let phrase_query = PhraseQuery::new(vec![
+        Term::from_field_text(text_field, "happy"),
+        Term::from_field_text(text_field, "tax"),
+    ]);
+    assert!(matches!(
+        searcher.search(&phrase_query, &Count),
+        Err(TantivyError::InvalidArgument(_))
+    ));
+    assert!(searcher
+        .doc::<TantivyDocument>(crate::DocAddress::new(0, 0))
+        .is_err());
+    let segment = index.searchable_segments()?.into_iter().next().unwrap();
+    assert!(SegmentReader::open_without_components(&segment, &[SegmentComponent::Terms]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_searcher_matching_terms() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", TEXT);
+    let id_field = schema_builder.add_text_field("id", STRING);
+    let schema = schema_builder.build();
+    let index = Index::create_in_ram(schema);
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.add_document(doc!(text_field=>"a b a c", id_field=>"doc0"))?;
+    index_writer.add_document(doc!(text_field=>"c d", id_field=>"doc1"))?;
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    let term_a = Term::from_field_text(text_field, "a");
+    let term_d = Term::from_field_text(text_field, "d");
+    let term_id = Term::from_field_text(id_field, "doc0");
+    let query = crate::query::BooleanQuery::new_multiterms_query(vec![
+        term_a.clone(),
+        term_d.clone(),
+        term_id.clone(),
+    ]);
+    let matching_terms = searcher.matching_terms(&query, crate::DocAddress::new(0, 0))?;
+    assert_eq!(matching_terms.len(), 2);
+    assert_eq!(matching_terms.get(&term_a), Some(&vec![0, 2]));
+    assert_eq!(matching_terms.get(&term_id), Some(&Vec::new()));
+    let matching_terms = searcher.matching_terms(&query, crate::DocAddress::new(0, 1))?;
+    assert_eq!(matching_terms.len(), 1);
+    assert_eq!(matching_terms.get(&term_d), Some(&vec![1]));
+    Ok(())
+}
+
+#[test]
+fn test_searcher_cooccurrence_counts() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", TEXT);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    index_writer.add_document(doc!(text_field=>"rust search"))?;
+    index_writer.add_document(doc!(text_field=>"rust engine"))?;
+    index_writer.add_document(doc!(text_field=>"search engine"))?;
+    index_writer.commit()?;
+    index_writer.add_document(doc!(text_field=>"rust search engine"))?;
+    index_writer.add_document(doc!(text_field=>"rust search"))?;
+    index_writer.commit()?;
+    index_writer.delete_term(Term::from_field_text(text_field, "engine"));
+    index_writer.add_document(doc!(text_field=>"search rust"))?;
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    let term_query = |text: &str| {
+        TermQuery::new(
+            Term::from_field_text(text_field, text),
+            IndexRecordOption::Basic,
+        )
+    };
+    let counts = searcher.cooccurrence_counts(&term_query("rust"), &term_query("search"))?;
+    let mut counts_per_num_docs: Vec<(u32, u32)> = searcher
+        .segment_readers()
+        .iter()
+        .map(|segment_reader| segment_reader.max_doc())
+        .zip(counts)
+        .collect();
+    counts_per_num_docs.sort();
+    assert_eq!(counts_per_num_docs, vec![(1, 1), (2, 1), (3, 1)]);
+    let counts = searcher.cooccurrence_counts(&term_query("rust"), &term_query("missing"))?;
+    assert_eq!(counts, vec![0, 0, 0]);
+    Ok(())
+}
+
+#[test]
+fn test_searcher_fast_field_values() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let id_field = schema_builder.add_text_field("id", STRING);
+    let value_field = schema_builder.add_u64_field("value", FAST);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    index_writer.add_document(doc!(id_field=>"a", value_field=>5u64))?;
+    index_writer.add_document(doc!(id_field=>"b", value_field=>7u64, value_field=>3u64))?;
+    index_writer.add_document(doc!(id_field=>"c"))?;
+    index_writer.commit()?;
+    index_writer.add_document(doc!(id_field=>"d", value_field=>4u64))?;
+    index_writer.add_document(doc!(id_field=>"e", value_field=>1u64))?;
+    index_writer.commit()?;
+    index_writer.delete_term(Term::from_field_text(id_field, "e"));
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.segment_readers().len(), 2);
+
+    let values: Vec<(crate::DocAddress, u64)> = searcher
+        .fast_field_values("value", crate::FastFieldValuesOrder::DocAddress)?
+        .collect();
+    assert_eq!(values.len(), 4);
+    assert!(values.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    let mut sorted_values: Vec<u64> = values.iter().map(|(_, value)| *value).collect();
+    sorted_values.sort();
+    assert_eq!(sorted_values, vec![3, 4, 5, 7]);
+
+    let values_by_value: Vec<(crate::DocAddress, u64)> = searcher
+        .fast_field_values("value", crate::FastFieldValuesOrder::Value)?
+        .collect();
+    let value_order: Vec<u64> = values_by_value.iter().map(|(_, value)| *value).collect();
+    assert_eq!(value_order, vec![3, 4, 5, 7]);
+    // Both values of the multivalued document are returned, next to each other in doc order.
+    let multivalued_doc = values_by_value[0].0;
+    assert_eq!(values_by_value[3].0, multivalued_doc);
+    let positions: Vec<usize> = values
+        .iter()
+        .positions(|(doc_address, _)| *doc_address == multivalued_doc)
+        .collect();
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions[1], positions[0] + 1);
+
+    assert_eq!(
+        searcher
+            .fast_field_values::<u64>("missing", crate::FastFieldValuesOrder::Value)?
+            .count(),
+        0
+    );
+    Ok(())
+}
+
+#[test]
+fn test_search_segment() -> crate::Result<()> {
+    use crate::collector::{Collector, TopDocs};
+    use crate::DocAddress;
+
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", TEXT);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut writer: IndexWriter = index.writer_for_tests()?;
+    writer.set_merge_policy(Box::new(NoMergePolicy));
+    writer.add_document(doc!(text_field=>"a b"))?;
+    writer.add_document(doc!(text_field=>"a"))?;
+    writer.commit()?;
+    writer.add_document(doc!(text_field=>"b"))?;
+    writer.add_document(doc!(text_field=>"a a"))?;
+    writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.segment_readers().len(), 2);
+    let query = TermQuery::new(
+        Term::from_field_text(text_field, "a"),
+        IndexRecordOption::WithFreqs,
+    );
+    let collector = TopDocs::with_limit(10);
+    let weight = searcher.weight(&query, &collector)?;
+    // The segments are searched in the reverse order.
+    let fruits = vec![
+        searcher.search_segment(1, weight.as_ref(), &collector)?,
+        searcher.search_segment(0, weight.as_ref(), &collector)?,
+    ];
+    let top_docs: Vec<DocAddress> = collector
+        .merge_fruits(fruits)?
+        .into_iter()
+        .map(|(_, doc_address)| doc_address)
+        .collect();
+    let expected_top_docs: Vec<DocAddress> = searcher
+        .search(&query, &collector)?
+        .into_iter()
+        .map(|(_, doc_address)| doc_address)
+        .collect();
+    assert_eq!(top_docs.len(), 3);
+    assert_eq!(top_docs, expected_top_docs);
+    assert!(matches!(
+        searcher.search_segment(2, weight.as_ref(), &Count),
+        Err(TantivyError::InvalidArgument(_))
+    ));
+    Ok(())
+}
+
 #[test]
 fn test_merging_segment_update_docfreq() {
     let mut schema_builder = Schema::builder();
@@ -466,3 +891,241 @@ fn test_non_text_json_term_freq_bitpacked() {
         assert_eq!(postings.term_freq(), 1u32);
     }
 }
+
+#[test]
+fn test_searcher_search_into() -> crate::Result<()> {
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use crate::collector::TopDocs;
+    use crate::Score;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Hit {
+        #[serde(rename = "_score")]
+        score: Score,
+        title: String,
+        tags: Vec<String>,
+        rank: u64,
+        #[serde(default)]
+        missing: Option<i64>,
+    }
+
+    #[derive(Deserialize)]
+    struct FastFieldsOnlyHit {
+        rank: u64,
+        category: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct MultiValuedHit {
+        tags: Vec<String>,
+        ranks: Vec<u64>,
+    }
+
+    let mut schema_builder = Schema::builder();
+    let title = schema_builder.add_text_field("title", TEXT | STORED);
+    let tags = schema_builder.add_text_field("tags", STRING | STORED);
+    let category = schema_builder.add_text_field("category", STRING | FAST);
+    let rank = schema_builder.add_u64_field("rank", FAST);
+    let ranks = schema_builder.add_u64_field("ranks", FAST);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    index_writer.add_document(doc!(
+        title => "red apple",
+        tags => "fruit",
+        tags => "red",
+        category => "food",
+        rank => 2u64,
+        ranks => 3u64,
+        ranks => 4u64,
+    ))?;
+    index_writer.commit()?;
+    index_writer.add_document(doc!(
+        title => "red car",
+        tags => "red",
+        category => "transport",
+        rank => 1u64,
+        ranks => 5u64,
+    ))?;
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    let query = TermQuery::new(
+        Term::from_field_text(title, "apple"),
+        IndexRecordOption::WithFreqs,
+    );
+
+    let hits: Vec<Hit> = searcher.search_into(&query, &TopDocs::with_limit(10))?;
+    let top_docs = searcher.search(&query, &TopDocs::with_limit(10))?;
+    assert_eq!(
+        hits,
+        vec![Hit {
+            score: top_docs[0].0,
+            title: "red apple".to_string(),
+            tags: vec!["fruit".to_string(), "red".to_string()],
+            rank: 2,
+            missing: None,
+        }]
+    );
+
+    // Fast fields are read from their column, without accessing the doc store.
+    let red_query = TermQuery::new(Term::from_field_text(tags, "red"), IndexRecordOption::Basic);
+    let mut fast_hits: Vec<FastFieldsOnlyHit> =
+        searcher.search_into(&red_query, &TopDocs::with_limit(10))?;
+    fast_hits.sort_by_key(|hit| hit.rank);
+    let fast_hits: Vec<(u64, &str)> = fast_hits
+        .iter()
+        .map(|hit| (hit.rank, hit.category.as_str()))
+        .collect();
+    assert_eq!(fast_hits, vec![(1, "transport"), (2, "food")]);
+
+    // Sequences get all of the values of a field, whether it has one or several.
+    let mut multi_valued_hits: Vec<MultiValuedHit> =
+        searcher.search_into(&red_query, &TopDocs::with_limit(10))?;
+    multi_valued_hits.sort_by_key(|hit| hit.ranks[0]);
+    assert_eq!(
+        multi_valued_hits,
+        vec![
+            MultiValuedHit {
+                tags: vec!["fruit".to_string(), "red".to_string()],
+                ranks: vec![3, 4],
+            },
+            MultiValuedHit {
+                tags: vec!["red".to_string()],
+                ranks: vec![5],
+            },
+        ]
+    );
+
+    // Types that are not structs get all of the stored fields.
+    let map_hits: Vec<BTreeMap<String, serde_json::Value>> =
+        searcher.search_into(&query, &TopDocs::with_limit(10))?;
+    assert_eq!(
+        map_hits[0].keys().collect::<Vec<_>>(),
+        vec!["_score", "tags", "title"]
+    );
+
+    // Other types get the single value of a field, and fail on several values.
+    #[derive(Deserialize, Debug)]
+    struct SingleTagHit {
+        #[allow(dead_code)]
+        tags: String,
+    }
+    assert!(matches!(
+        searcher.search_into::<SingleTagHit>(&query, &TopDocs::with_limit(10)),
+        Err(TantivyError::DeserializeError(_))
+    ));
+
+    // A field that cannot be deserialized is reported as an error.
+    #[derive(Deserialize, Debug)]
+    struct InvalidHit {
+        #[allow(dead_code)]
+        title: u64,
+    }
+    assert!(matches!(
+        searcher.search_into::<InvalidHit>(&query, &TopDocs::with_limit(10)),
+        Err(TantivyError::DeserializeError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_searcher_search_stream() -> crate::Result<()> {
+    use crate::collector::TopDocs;
+    use crate::DocAddress;
+
+    let mut schema_builder = Schema::builder();
+    let text = schema_builder.add_text_field("text", TEXT);
+    let id = schema_builder.add_u64_field("id", INDEXED);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    index_writer.add_document(doc!(text => "a b", id => 0u64))?;
+    index_writer.add_document(doc!(text => "b", id => 1u64))?;
+    index_writer.add_document(doc!(text => "a a", id => 2u64))?;
+    index_writer.commit()?;
+    index_writer.add_document(doc!(text => "a", id => 3u64))?;
+    index_writer.add_document(doc!(text => "a", id => 4u64))?;
+    index_writer.commit()?;
+    index_writer.delete_term(Term::from_field_u64(id, 3));
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    let query = TermQuery::new(
+        Term::from_field_text(text, "a"),
+        IndexRecordOption::WithFreqs,
+    );
+    let (large_segment_ord, small_segment_ord) = if searcher.segment_reader(0).max_doc() == 3 {
+        (0, 1)
+    } else {
+        (1, 0)
+    };
+
+    let hits = searcher
+        .search_stream(&query)?
+        .collect::<crate::Result<Vec<_>>>()?;
+    let doc_addresses: Vec<DocAddress> = hits.iter().map(|(_, doc_address)| *doc_address).collect();
+    let mut expected_doc_addresses = vec![
+        DocAddress::new(large_segment_ord, 0),
+        DocAddress::new(large_segment_ord, 2),
+        DocAddress::new(small_segment_ord, 1),
+    ];
+    expected_doc_addresses.sort();
+    assert_eq!(doc_addresses, expected_doc_addresses);
+    // The scores are the ones computed by a regular search.
+    let mut top_docs = searcher.search(&query, &TopDocs::with_limit(10))?;
+    top_docs.sort_by_key(|(_, doc_address)| *doc_address);
+    assert_eq!(hits, top_docs);
+
+    // Hits are produced lazily.
+    let mut search_stream = searcher.search_stream(&query)?;
+    assert_eq!(search_stream.next().unwrap()?.1, expected_doc_addresses[0]);
+    assert_eq!(search_stream.count(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_searcher_docs() -> crate::Result<()> {
+    use crate::collector::DocSetCollector;
+    use crate::query::AllQuery;
+    use crate::schema::Value;
+    use crate::DocAddress;
+
+    let mut schema_builder = Schema::builder();
+    let id = schema_builder.add_u64_field("id", INDEXED | STORED);
+    let mut index = Index::create_in_ram(schema_builder.build());
+    index.set_multithread_executor(2)?;
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    for id_value in 0..3u64 {
+        index_writer.add_document(doc!(id => id_value))?;
+    }
+    index_writer.commit()?;
+    for id_value in 3..5u64 {
+        index_writer.add_document(doc!(id => id_value))?;
+    }
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+
+    let mut doc_addresses: Vec<DocAddress> = searcher
+        .search(&AllQuery, &DocSetCollector)?
+        .into_iter()
+        .collect();
+    doc_addresses.sort();
+    doc_addresses.reverse();
+    let ids: Vec<u64> = searcher
+        .docs::<TantivyDocument>(&doc_addresses)?
+        .map(|doc| Ok(doc?.get_first(id).and_then(|value| value.as_u64()).unwrap()))
+        .collect::<crate::Result<_>>()?;
+    let expected_ids: Vec<u64> = doc_addresses
+        .iter()
+        .map(|doc_address| {
+            let doc: TantivyDocument = searcher.doc(*doc_address)?;
+            Ok(doc.get_first(id).and_then(|value| value.as_u64()).unwrap())
+        })
+        .collect::<crate::Result<_>>()?;
+    assert_eq!(ids.len(), 5);
+    assert_eq!(ids, expected_ids);
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+
+use columnar::DynamicColumn;
+use serde::de::value::MapDeserializer;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde_json::Value as JsonValue;
+
+use crate::schema::document::DeserializeError;
+use crate::schema::{Document, OwnedValue, Schema, TantivyDocument};
+use crate::{DocAddress, DocId, Score, Searcher, TantivyError};
+
+/// Key under which the score of a hit is exposed to its struct.
+pub(crate) const SCORE_KEY: &str = "_score";
+
+/// Deserializer recording the field names of the struct deserialized from it, and failing
+/// right after.
+struct StructFieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de, 'a> de::Deserializer<'de> for StructFieldNames<'a> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("the field names were recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Returns the names of the fields of `T`, or an empty slice if `T` is not deserialized
+/// from a struct (e.g. a map).
+pub(crate) fn struct_field_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut field_names: &'static [&'static str] = &[];
+    let _ = T::deserialize(StructFieldNames(&mut field_names));
+    field_names
+}
+
+/// Deserializer of the values of a field of a hit.
+///
+/// The shape of the values is decided by the type they are deserialized into: a sequence
+/// (e.g. a `Vec`) gets all of the values, even if there is only one, while any other type
+/// gets the single value of the field. Self-describing types, like `serde_json::Value`,
+/// get the value itself if there is only one, and an array otherwise.
+struct FieldValues(Vec<JsonValue>);
+
+impl FieldValues {
+    fn into_json(mut self) -> JsonValue {
+        if self.0.len() == 1 {
+            self.0.pop().unwrap()
+        } else {
+            JsonValue::Array(self.0)
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for FieldValues {
+    type Deserializer = FieldValues;
+
+    fn into_deserializer(self) -> FieldValues {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for FieldValues {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.into_json().deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        JsonValue::Array(self.0).deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.into_json().deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf unit
+        unit_struct map struct identifier ignored_any
+    }
+}
+
+/// Builds the hit of `T` for the given document.
+///
+/// The hit is deserialized from a map holding the score under [`SCORE_KEY`] and the
+/// values of the fields listed in `field_names`, or of all of the stored fields if
+/// `field_names` is empty. Fields that are not stored are read from their fast field.
+/// See [`FieldValues`] for how the values of a field are deserialized.
+pub(crate) fn deserialize_hit<T: DeserializeOwned>(
+    searcher: &Searcher,
+    score: Score,
+    doc_address: DocAddress,
+    field_names: &[&str],
+) -> crate::Result<T> {
+    let schema = searcher.schema();
+    let mut hit: BTreeMap<String, FieldValues> = BTreeMap::new();
+    let is_requested =
+        |field_name: &str| field_names.is_empty() || field_names.contains(&field_name);
+    let requires_doc = schema
+        .fields()
+        .any(|(_, field_entry)| field_entry.is_stored() && is_requested(field_entry.name()));
+    if requires_doc {
+        let doc: TantivyDocument = searcher.doc(doc_address)?;
+        for (field_name, values) in doc.to_named_doc(schema).0 {
+            if !is_requested(&field_name) {
+                continue;
+            }
+            let values = values.iter().map(to_json).collect::<crate::Result<_>>()?;
+            hit.insert(field_name, FieldValues(values));
+        }
+    }
+    for &field_name in field_names {
+        if hit.contains_key(field_name) || !is_fast_field(schema, field_name) {
+            continue;
+        }
+        let values = fast_field_values(searcher, doc_address, field_name)?;
+        if !values.is_empty() {
+            hit.insert(field_name.to_string(), FieldValues(values));
+        }
+    }
+    hit.insert(
+        SCORE_KEY.to_string(),
+        FieldValues(vec![JsonValue::from(score)]),
+    );
+    T::deserialize(MapDeserializer::new(hit.into_iter())).map_err(|err: serde_json::Error| {
+        TantivyError::DeserializeError(DeserializeError::custom(format!(
+            "could not deserialize the hit {doc_address:?}: {err}"
+        )))
+    })
+}
+
+fn is_fast_field(schema: &Schema, field_name: &str) -> bool {
+    schema
+        .get_field(field_name)
+        .map_or(false, |field| schema.get_field_entry(field).is_fast())
+}
+
+fn to_json<V: serde::Serialize>(value: &V) -> crate::Result<JsonValue> {
+    serde_json::to_value(value)
+        .map_err(|err| TantivyError::DeserializeError(DeserializeError::custom(err)))
+}
+
+/// Returns the values of the fast field of the document.
+fn fast_field_values(
+    searcher: &Searcher,
+    doc_address: DocAddress,
+    field_name: &str,
+) -> crate::Result<Vec<JsonValue>> {
+    let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+    let doc: DocId = doc_address.doc_id;
+    let mut values: Vec<OwnedValue> = Vec::new();
+    for column_handle in segment_reader
+        .fast_fields()
+        .dynamic_column_handles(field_name)?
+    {
+        match column_handle.open()? {
+            DynamicColumn::Bool(column) => {
+                values.extend(column.values_for_doc(doc).map(OwnedValue::Bool))
+            }
+            DynamicColumn::I64(column) => {
+                values.extend(column.values_for_doc(doc).map(OwnedValue::I64))
+            }
+            DynamicColumn::U64(column) => {
+                values.extend(column.values_for_doc(doc).map(OwnedValue::U64))
+            }
+            DynamicColumn::F64(column) => {
+                values.extend(column.values_for_doc(doc).map(OwnedValue::F64))
+            }
+            DynamicColumn::IpAddr(column) => {
+                values.extend(column.values_for_doc(doc).map(OwnedValue::IpAddr))
+            }
+            DynamicColumn::DateTime(column) => {
+                values.extend(column.values_for_doc(doc).map(OwnedValue::Date))
+            }
+            DynamicColumn::Bytes(column) => {
+                for ord in column.term_ords(doc) {
+                    let mut bytes = Vec::new();
+                    if column.ord_to_bytes(ord, &mut bytes)? {
+                        values.push(OwnedValue::Bytes(bytes));
+                    }
+                }
+            }
+            DynamicColumn::Str(column) => {
+                for ord in column.term_ords(doc) {
+                    let mut text = String::new();
+                    if column.ord_to_str(ord, &mut text)? {
+                        values.push(OwnedValue::Str(text));
+                    }
+                }
+            }
+        }
+    }
+    values.iter().map(to_json).collect()
+}
@@ -8,6 +8,10 @@ use crate::directory::{FileSlice, TerminatingWrite, WritePtr};
 use crate::schema::Field;
 use crate::space_usage::{FieldUsage, PerFieldSpaceUsage};
 
+fn io_invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
 #[derive(Eq, PartialEq, Hash, Copy, Ord, PartialOrd, Clone, Debug)]
 pub struct FileAddr {
     field: Field,
@@ -110,8 +114,18 @@ impl CompositeFile {
     /// `FileSlice`.
     pub fn open(data: &FileSlice) -> io::Result<CompositeFile> {
         let end = data.len();
+        if end < 4 {
+            return Err(io_invalid_data(format!(
+                "Composite file of {end} bytes is too short to hold its footer length."
+            )));
+        }
         let footer_len_data = data.slice_from(end - 4).read_bytes()?;
         let footer_len = u32::deserialize(&mut footer_len_data.as_slice())? as usize;
+        if footer_len > end - 4 {
+            return Err(io_invalid_data(format!(
+                "The footer length ({footer_len} bytes) exceeds the composite file ({end} bytes)."
+            )));
+        }
         let footer_start = end - 4 - footer_len;
         let footer_data = data
             .slice(footer_start..footer_start + footer_len)
@@ -126,6 +140,12 @@ impl CompositeFile {
         let mut offset = 0;
         for _ in 0..num_fields {
             offset += VInt::deserialize(&mut footer_buffer)?.0 as usize;
+            if offset > footer_start {
+                return Err(io_invalid_data(format!(
+                    "Offset {offset} of the composite file exceeds the start of its footer \
+                     ({footer_start})."
+                )));
+            }
             let file_addr = FileAddr::deserialize(&mut footer_buffer)?;
             offsets.push(offset);
             file_addrs.push(file_addr);
@@ -187,9 +207,20 @@ mod test {
     use common::{BinarySerializable, VInt};
 
     use super::{CompositeFile, CompositeWrite};
-    use crate::directory::{Directory, RamDirectory};
+    use crate::directory::{Directory, FileSlice, RamDirectory};
     use crate::schema::Field;
 
+    #[test]
+    fn test_composite_file_corrupted() {
+        let too_short = FileSlice::from(vec![1u8, 2u8]);
+        assert!(CompositeFile::open(&too_short).is_err());
+        let mut footer_too_long = vec![0u8; 8];
+        footer_too_long.extend_from_slice(&100u32.to_le_bytes());
+        let footer_too_long = FileSlice::from(footer_too_long);
+        let error = CompositeFile::open(&footer_too_long).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
     #[test]
     fn test_composite_file() -> crate::Result<()> {
         let path = Path::new("test_path");
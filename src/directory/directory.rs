@@ -134,6 +134,16 @@ pub trait Directory: DirectoryClone + fmt::Debug + Send + Sync + 'static {
     /// [`DeleteError::FileDoesNotExist`].
     fn delete(&self, path: &Path) -> Result<(), DeleteError>;
 
+    /// Returns true if the [`FileSlice`]s opened before the deletion of a file
+    /// remain readable after it, as expected by [`Directory::delete()`].
+    ///
+    /// Directories that cannot guarantee it should return false. The
+    /// [`ManagedDirectory`][crate::directory::ManagedDirectory] then defers the
+    /// garbage collection of the files that are still opened for reading.
+    fn keeps_deleted_files_readable(&self) -> bool {
+        false
+    }
+
     /// Returns true if and only if the file exists
     fn exists(&self, path: &Path) -> Result<bool, OpenReadError>;
 
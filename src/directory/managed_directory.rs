@@ -1,20 +1,25 @@
 use std::collections::HashSet;
 use std::io::Write;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, RwLockWriteGuard};
-use std::{io, result};
+use std::{fmt, io, result};
 
+use async_trait::async_trait;
+use common::HasLen;
 use crc32fast::Hasher;
 
 use crate::core::MANAGED_FILEPATH;
 use crate::directory::error::{DeleteError, LockError, OpenReadError, OpenWriteError};
 use crate::directory::footer::{Footer, FooterProxy};
 use crate::directory::{
-    DirectoryLock, FileHandle, FileSlice, GarbageCollectionResult, Lock, WatchCallback,
-    WatchHandle, WritePtr, META_LOCK,
+    CommitRetainedFiles, DirectoryLock, FileHandle, FileSlice, GarbageCollectionCandidate,
+    GarbageCollectionDryRun, GarbageCollectionResult, Lock, OwnedBytes, WatchCallback, WatchHandle,
+    WritePtr, META_LOCK,
 };
 use crate::error::DataCorruption;
-use crate::Directory;
+use crate::index::{CommitMeta, SegmentMeta};
+use crate::{Directory, Inventory, TrackedObject};
 
 /// Returns true if the file is "managed".
 /// Non-managed file are not subject to garbage collection.
@@ -36,10 +41,59 @@ fn is_managed(path: &Path) -> bool {
 /// Thanks to this list, it implements a `garbage_collect` method
 /// that removes the files that were created by tantivy and are not
 /// useful anymore.
-#[derive(Debug)]
+///
+/// If the underlying directory does not keep deleted files readable (see
+/// [`Directory::keeps_deleted_files_readable()`]), the managed directory also
+/// keeps track of the [`FileSlice`]s it opened for reading. These files are not
+/// garbage collected until the last of these file slices is dropped.
 pub struct ManagedDirectory {
     directory: Box<dyn Directory>,
     meta_informations: Arc<RwLock<MetaInformation>>,
+    opened_files: Inventory<PathBuf>,
+}
+
+impl fmt::Debug for ManagedDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ManagedDirectory")
+            .field("directory", &self.directory)
+            .field("meta_informations", &self.meta_informations)
+            .finish()
+    }
+}
+
+/// File handle returned by the managed directory.
+///
+/// As long as it is alive, its path is registered as opened
+/// and the file will not be garbage collected.
+///
+/// The [`OwnedBytes`] read from it are not tracked: they may outlive
+/// the handle, and hence the file.
+struct TrackedFileHandle {
+    file_slice: FileSlice,
+    path: TrackedObject<PathBuf>,
+}
+
+impl fmt::Debug for TrackedFileHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TrackedFileHandle({:?})", self.path.as_path())
+    }
+}
+
+impl HasLen for TrackedFileHandle {
+    fn len(&self) -> usize {
+        self.file_slice.len()
+    }
+}
+
+#[async_trait]
+impl FileHandle for TrackedFileHandle {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        self.file_slice.read_bytes_slice(range)
+    }
+
+    async fn read_bytes_async(&self, byte_range: Range<usize>) -> io::Result<OwnedBytes> {
+        self.file_slice.read_bytes_slice_async(byte_range).await
+    }
 }
 
 #[derive(Debug, Default)]
@@ -77,11 +131,13 @@ impl ManagedDirectory {
                     meta_informations: Arc::new(RwLock::new(MetaInformation {
                         managed_paths: managed_files,
                     })),
+                    opened_files: Inventory::default(),
                 })
             }
             Err(OpenReadError::FileDoesNotExist(_)) => Ok(ManagedDirectory {
                 directory,
                 meta_informations: Arc::default(),
+                opened_files: Inventory::default(),
             }),
             io_err @ Err(OpenReadError::IoError { .. }) => Err(io_err.err().unwrap().into()),
             Err(OpenReadError::IncompatibleIndex(incompatibility)) => {
@@ -106,55 +162,29 @@ impl ManagedDirectory {
     /// If a file cannot be deleted (for permission reasons for instance)
     /// an error is simply logged, and the file remains in the list of managed
     /// files.
+    ///
+    /// If the underlying directory does not keep deleted files readable, the files
+    /// that are still opened for reading (e.g. by a `Searcher` on an older commit)
+    /// are not deleted either. They are reported as failed to delete, and will be
+    /// deleted by a later garbage collection.
     pub fn garbage_collect<L: FnOnce() -> HashSet<PathBuf>>(
         &mut self,
         get_living_files: L,
     ) -> crate::Result<GarbageCollectionResult> {
         info!("Garbage collect");
-        let mut files_to_delete = vec![];
-
-        // It is crucial to get the living files after acquiring the
-        // read lock of meta information. That way, we
-        // avoid the following scenario.
-        //
-        // 1) we get the list of living files.
-        // 2) someone creates a new file.
-        // 3) we start garbage collection and remove this file
-        // even though it is a living file.
-        //
-        // releasing the lock as .delete() will use it too.
-        {
-            let meta_informations_rlock = self
-                .meta_informations
-                .read()
-                .expect("Managed directory rlock poisoned in garbage collect.");
-
-            // The point of this second "file" lock is to enforce the following scenario
-            // 1) process B tries to load a new set of searcher.
-            // The list of segments is loaded
-            // 2) writer change meta.json (for instance after a merge or a commit)
-            // 3) gc kicks in.
-            // 4) gc removes a file that was useful for process B, before process B opened it.
-            match self.acquire_lock(&META_LOCK) {
-                Ok(_meta_lock) => {
-                    let living_files = get_living_files();
-                    for managed_path in &meta_informations_rlock.managed_paths {
-                        if !living_files.contains(managed_path) {
-                            files_to_delete.push(managed_path.clone());
-                        }
-                    }
-                }
-                Err(err) => {
-                    error!("Failed to acquire lock for GC");
-                    return Err(crate::TantivyError::from(err));
-                }
-            }
-        }
+        let files_to_delete = self.list_unused_files(get_living_files)?;
 
         let mut failed_to_delete_files = vec![];
         let mut deleted_files = vec![];
 
+        let opened_files = self.opened_files();
+
         for file_to_delete in files_to_delete {
+            if opened_files.contains(&file_to_delete) {
+                info!("Not deleting {:?}: it is still opened", file_to_delete);
+                failed_to_delete_files.push(file_to_delete);
+                continue;
+            }
             match self.delete(&file_to_delete) {
                 Ok(_) => {
                     info!("Deleted {:?}", file_to_delete);
@@ -199,6 +229,145 @@ impl ManagedDirectory {
         })
     }
 
+    fn opened_files(&self) -> HashSet<PathBuf> {
+        self.opened_files
+            .list()
+            .iter()
+            .map(|opened_file| opened_file.to_path_buf())
+            .collect()
+    }
+
+    /// Lists the files that [`ManagedDirectory::garbage_collect`] would try to
+    /// delete, along with their size, without deleting anything.
+    ///
+    /// The directory does not know about the commit history: the returned
+    /// [`GarbageCollectionDryRun::commit_history`] is empty.
+    pub fn garbage_collect_dry_run<L: FnOnce() -> HashSet<PathBuf>>(
+        &self,
+        get_living_files: L,
+    ) -> crate::Result<GarbageCollectionDryRun> {
+        let opened_files = self.opened_files();
+        let candidates = self
+            .list_unused_files(get_living_files)?
+            .into_iter()
+            .map(|path| self.garbage_collection_candidate(path, &opened_files))
+            .collect();
+        Ok(GarbageCollectionDryRun {
+            candidates,
+            commit_history: Vec::new(),
+        })
+    }
+
+    /// Returns the files of each commit of the commit history that are not used by any
+    /// other commit, nor by any of the given living files.
+    ///
+    /// The files which do not exist are skipped.
+    pub(crate) fn commit_retained_files(
+        &self,
+        commit_history: &[CommitMeta],
+        living_files: &HashSet<PathBuf>,
+    ) -> Vec<CommitRetainedFiles> {
+        let opened_files = self.opened_files();
+        let files_per_commit: Vec<HashSet<PathBuf>> = commit_history
+            .iter()
+            .map(|commit_meta| {
+                commit_meta
+                    .segments
+                    .iter()
+                    .flat_map(SegmentMeta::list_files)
+                    .collect()
+            })
+            .collect();
+        commit_history
+            .iter()
+            .enumerate()
+            .map(|(commit_ord, commit_meta)| {
+                let files = files_per_commit[commit_ord]
+                    .iter()
+                    .filter(|path| {
+                        !living_files.contains(*path)
+                            && files_per_commit.iter().enumerate().all(
+                                |(other_ord, other_files)| {
+                                    other_ord == commit_ord || !other_files.contains(*path)
+                                },
+                            )
+                    })
+                    .map(|path| self.garbage_collection_candidate(path.clone(), &opened_files))
+                    .filter(|candidate| candidate.num_bytes.is_some())
+                    .collect();
+                CommitRetainedFiles {
+                    opstamp: commit_meta.opstamp,
+                    files,
+                }
+            })
+            .collect()
+    }
+
+    fn garbage_collection_candidate(
+        &self,
+        path: PathBuf,
+        opened_files: &HashSet<PathBuf>,
+    ) -> GarbageCollectionCandidate {
+        let num_bytes = self
+            .directory
+            .open_read(&path)
+            .ok()
+            .map(|file_slice| file_slice.len() as u64);
+        let is_opened = opened_files.contains(&path);
+        GarbageCollectionCandidate {
+            path,
+            num_bytes,
+            is_opened,
+        }
+    }
+
+    /// Returns the managed files that are not living files.
+    fn list_unused_files<L: FnOnce() -> HashSet<PathBuf>>(
+        &self,
+        get_living_files: L,
+    ) -> crate::Result<Vec<PathBuf>> {
+        let mut files_to_delete = vec![];
+
+        // It is crucial to get the living files after acquiring the
+        // read lock of meta information. That way, we
+        // avoid the following scenario.
+        //
+        // 1) we get the list of living files.
+        // 2) someone creates a new file.
+        // 3) we start garbage collection and remove this file
+        // even though it is a living file.
+        //
+        // releasing the lock as .delete() will use it too.
+        {
+            let meta_informations_rlock = self
+                .meta_informations
+                .read()
+                .expect("Managed directory rlock poisoned in garbage collect.");
+
+            // The point of this second "file" lock is to enforce the following scenario
+            // 1) process B tries to load a new set of searcher.
+            // The list of segments is loaded
+            // 2) writer change meta.json (for instance after a merge or a commit)
+            // 3) gc kicks in.
+            // 4) gc removes a file that was useful for process B, before process B opened it.
+            match self.acquire_lock(&META_LOCK) {
+                Ok(_meta_lock) => {
+                    let living_files = get_living_files();
+                    for managed_path in &meta_informations_rlock.managed_paths {
+                        if !living_files.contains(managed_path) {
+                            files_to_delete.push(managed_path.clone());
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("Failed to acquire lock for GC");
+                    return Err(crate::TantivyError::from(err));
+                }
+            }
+        }
+        Ok(files_to_delete)
+    }
+
     /// Registers a file as managed
     ///
     /// This method must be called before the file is
@@ -256,6 +425,31 @@ impl ManagedDirectory {
         Ok(footer.crc() == crc)
     }
 
+    /// Returns the footer of a managed file, and its content without the footer.
+    ///
+    /// Unlike [`ManagedDirectory::validate_checksum`], the content of the file is not read.
+    pub(crate) fn read_footer(
+        &self,
+        path: &Path,
+    ) -> result::Result<(Footer, FileSlice), OpenReadError> {
+        let reader = self.directory.open_read(path)?;
+        Footer::extract_footer(reader)
+            .map_err(|io_error| OpenReadError::wrap_io_error(io_error, path.to_path_buf()))
+    }
+
+    /// Atomically replaces the content of a managed file, with a footer of the current
+    /// version.
+    ///
+    /// The readers that opened the file before keep reading its previous content.
+    pub(crate) fn atomic_rewrite(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let mut hasher = Hasher::new();
+        hasher.update(data);
+        let mut buffer = Vec::with_capacity(data.len());
+        buffer.extend_from_slice(data);
+        Footer::new(hasher.finalize()).append_footer(&mut buffer)?;
+        self.atomic_write(path, &buffer)
+    }
+
     /// List all managed files
     pub fn list_managed_files(&self) -> HashSet<PathBuf> {
         let managed_paths = self
@@ -279,7 +473,14 @@ impl Directory for ManagedDirectory {
         let (footer, reader) = Footer::extract_footer(file_slice)
             .map_err(|io_error| OpenReadError::wrap_io_error(io_error, path.to_path_buf()))?;
         footer.is_compatible()?;
-        Ok(reader)
+        if !is_managed(path) || self.directory.keeps_deleted_files_readable() {
+            return Ok(reader);
+        }
+        let tracked_file_handle = TrackedFileHandle {
+            file_slice: reader,
+            path: self.opened_files.track(path.to_path_buf()),
+        };
+        Ok(FileSlice::new(Arc::new(tracked_file_handle)))
     }
 
     fn open_write(&self, path: &Path) -> result::Result<WritePtr, OpenWriteError> {
@@ -307,6 +508,10 @@ impl Directory for ManagedDirectory {
         self.directory.delete(path)
     }
 
+    fn keeps_deleted_files_readable(&self) -> bool {
+        self.directory.keeps_deleted_files_readable()
+    }
+
     fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
         self.directory.exists(path)
     }
@@ -330,6 +535,7 @@ impl Clone for ManagedDirectory {
         ManagedDirectory {
             directory: self.directory.box_clone(),
             meta_informations: Arc::clone(&self.meta_informations),
+            opened_files: self.opened_files.clone(),
         }
     }
 }
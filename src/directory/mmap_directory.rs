@@ -406,6 +406,11 @@ impl Directory for MmapDirectory {
         Ok(())
     }
 
+    fn keeps_deleted_files_readable(&self) -> bool {
+        // Windows forbids deleting a file that is memory mapped.
+        cfg!(not(target_os = "windows"))
+    }
+
     fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
         let full_path = self.resolve_path(path);
         full_path
@@ -16,6 +16,7 @@ pub mod error;
 
 mod composite_file;
 
+use std::collections::HashMap;
 use std::io::BufWriter;
 use std::path::PathBuf;
 
@@ -25,8 +26,11 @@ pub use common::{AntiCallToken, OwnedBytes, TerminatingWrite};
 pub(crate) use self::composite_file::{CompositeFile, CompositeWrite};
 pub use self::directory::{Directory, DirectoryClone, DirectoryLock};
 pub use self::directory_lock::{Lock, INDEX_WRITER_LOCK, META_LOCK};
+pub(crate) use self::footer::Footer;
 pub use self::ram_directory::RamDirectory;
 pub use self::watch_event_router::{WatchCallback, WatchCallbackList, WatchHandle};
+use crate::index::SegmentId;
+use crate::Opstamp;
 
 /// Outcome of the Garbage collection
 pub struct GarbageCollectionResult {
@@ -42,6 +46,103 @@ pub struct GarbageCollectionResult {
     pub failed_to_delete_files: Vec<PathBuf>,
 }
 
+/// A file that is not used by the index anymore, and would be deleted
+/// by a garbage collection.
+#[derive(Clone, Debug)]
+pub struct GarbageCollectionCandidate {
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Size of the file in bytes, or `None` if the file could not be opened.
+    pub num_bytes: Option<u64>,
+    /// True if the file is still opened for reading, e.g. by a `Searcher` on an
+    /// older commit, and the directory does not keep deleted files readable.
+    /// Such a file is only deleted by a garbage collection happening after it is closed.
+    pub is_opened: bool,
+}
+
+impl GarbageCollectionCandidate {
+    /// Returns the id of the segment the file belongs to, if it is a segment file.
+    pub fn segment_id(&self) -> Option<SegmentId> {
+        let file_name = self.path.file_name()?.to_str()?;
+        let (uuid_string, _extension) = file_name.split_once('.')?;
+        SegmentId::from_uuid_string(uuid_string).ok()
+    }
+}
+
+/// The files of a commit of the commit history that no other commit uses.
+///
+/// They are not deleted by a garbage collection as long as the commit is part of the
+/// commit history (see
+/// [`IndexSettings::commit_history_size`](crate::IndexSettings::commit_history_size)),
+/// and are reclaimed by the first garbage collection following its removal.
+#[derive(Clone, Debug)]
+pub struct CommitRetainedFiles {
+    /// Opstamp of the commit.
+    pub opstamp: Opstamp,
+    /// The files only used by the commit.
+    pub files: Vec<GarbageCollectionCandidate>,
+}
+
+/// Outcome of a garbage collection dry run: the files that a garbage
+/// collection would try to delete, without deleting them.
+#[derive(Clone, Debug, Default)]
+pub struct GarbageCollectionDryRun {
+    /// The files that are not used by the index anymore.
+    pub candidates: Vec<GarbageCollectionCandidate>,
+    /// For each commit of the commit history, from the most recent to the oldest,
+    /// the files that are only kept for this commit.
+    pub commit_history: Vec<CommitRetainedFiles>,
+}
+
+impl GarbageCollectionDryRun {
+    /// Returns the number of bytes a garbage collection would reclaim.
+    ///
+    /// The files still opened for reading are not counted.
+    pub fn num_bytes_reclaimed(&self) -> u64 {
+        self.reclaimable_candidates()
+            .filter_map(|candidate| candidate.num_bytes)
+            .sum()
+    }
+
+    /// Returns the number of bytes a garbage collection would reclaim, per segment.
+    ///
+    /// The files that do not belong to a segment, and the files still opened
+    /// for reading are not counted.
+    pub fn num_bytes_reclaimed_per_segment(&self) -> HashMap<SegmentId, u64> {
+        let mut num_bytes_per_segment: HashMap<SegmentId, u64> = HashMap::new();
+        for candidate in self.reclaimable_candidates() {
+            if let Some(segment_id) = candidate.segment_id() {
+                *num_bytes_per_segment.entry(segment_id).or_default() +=
+                    candidate.num_bytes.unwrap_or(0);
+            }
+        }
+        num_bytes_per_segment
+    }
+
+    /// Returns the number of bytes kept for each commit of the commit history, that is the
+    /// number of bytes a garbage collection would reclaim once the commit leaves the
+    /// commit history.
+    pub fn num_bytes_retained_per_commit(&self) -> HashMap<Opstamp, u64> {
+        self.commit_history
+            .iter()
+            .map(|commit_retained_files| {
+                let num_bytes = commit_retained_files
+                    .files
+                    .iter()
+                    .filter_map(|file| file.num_bytes)
+                    .sum();
+                (commit_retained_files.opstamp, num_bytes)
+            })
+            .collect()
+    }
+
+    fn reclaimable_candidates(&self) -> impl Iterator<Item = &GarbageCollectionCandidate> {
+        self.candidates
+            .iter()
+            .filter(|candidate| !candidate.is_opened)
+    }
+}
+
 #[cfg(all(feature = "mmap", unix))]
 pub use memmap2::Advice;
 
@@ -186,6 +186,11 @@ impl Directory for RamDirectory {
         self.fs.write().unwrap().delete(path)
     }
 
+    fn keeps_deleted_files_readable(&self) -> bool {
+        // The file slices own the data of the file.
+        true
+    }
+
     fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
         Ok(self
             .fs
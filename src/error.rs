@@ -52,6 +52,12 @@ impl fmt::Debug for DataCorruption {
     }
 }
 
+impl From<DataCorruption> for io::Error {
+    fn from(data_corruption: DataCorruption) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{data_corruption:?}"))
+    }
+}
+
 /// The library's error enum
 #[derive(Debug, Clone, Error)]
 pub enum TantivyError {
@@ -110,6 +116,9 @@ pub enum TantivyError {
     #[error("Deserialize error: {0}")]
     /// An error occurred while attempting to deserialize a document.
     DeserializeError(DeserializeError),
+    /// The search was aborted through its [`CancellationToken`](crate::CancellationToken).
+    #[error("The search was cancelled")]
+    Cancelled,
 }
 
 impl From<io::Error> for TantivyError {
@@ -14,6 +14,11 @@
 //! Fields have to be declared as `FAST` in the schema.
 //! Currently supported fields are: u64, i64, f64, bytes, ip and text.
 //!
+//! Documents are not required to have a value: a missing value is distinguished from a
+//! default value like `0`, see [`Column::first`] and [`Column::has_value`]. Depending on
+//! the density of the values, the documents having a value are tracked with a dense or
+//! a sparse index.
+//!
 //! Fast fields are stored in with [different codecs](columnar). The best codec is detected
 //! automatically, when serializing.
 //!
@@ -25,6 +30,7 @@ use columnar::MonotonicallyMappableToU64;
 pub use self::alive_bitset::{intersect_alive_bitsets, write_alive_bitset, AliveBitSet};
 pub use self::error::{FastFieldNotAvailableError, Result};
 pub use self::facet_reader::FacetReader;
+pub use self::numeric_column::NumericColumn;
 pub use self::readers::FastFieldReaders;
 pub use self::writer::FastFieldsWriter;
 use crate::schema::Type;
@@ -33,6 +39,7 @@ use crate::DateTime;
 mod alive_bitset;
 mod error;
 mod facet_reader;
+mod numeric_column;
 mod readers;
 mod writer;
 
@@ -982,6 +989,72 @@ mod tests {
         assert_eq!(column.first(2), None);
     }
 
+    #[test]
+    fn test_optional_fast_field_missing_values() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let dense = schema_builder.add_u64_field("dense", FAST);
+        let sparse = schema_builder.add_u64_field("sparse", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for doc in 0..10_000u64 {
+            let mut document = TantivyDocument::default();
+            if doc % 2 == 0 {
+                document.add_u64(dense, 0u64);
+            }
+            if doc % 1_000 == 0 {
+                document.add_u64(sparse, 0u64);
+            }
+            index_writer.add_document(document)?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let fast_fields = searcher.segment_reader(0u32).fast_fields();
+        for field_name in ["dense", "sparse"] {
+            let column = fast_fields.u64(field_name)?;
+            assert_eq!(column.get_cardinality(), columnar::Cardinality::Optional);
+            assert_eq!(column.first(0), Some(0));
+            assert!(column.has_value(0));
+            assert_eq!(column.first(1), None);
+            assert!(!column.has_value(1));
+            let numeric_column = fast_fields.numeric(field_name)?.unwrap();
+            assert!(numeric_column.has_value(2_000));
+            assert!(!numeric_column.has_value(9_999));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytes_fast_field_first_bytes() {
+        let mut schema_builder = Schema::builder();
+        let payload_field = schema_builder.add_bytes_field("payload", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer: IndexWriter = index.writer_for_tests().unwrap();
+        let embedding: Vec<u8> = [0.5f32, -1.0f32]
+            .iter()
+            .flat_map(|val| val.to_le_bytes())
+            .collect();
+        index_writer
+            .add_document(doc!(payload_field=>embedding.clone()))
+            .unwrap();
+        index_writer
+            .add_document(TantivyDocument::default())
+            .unwrap();
+        index_writer
+            .add_document(doc!(payload_field=>vec![0u8; 32]))
+            .unwrap();
+        index_writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let fastfields = searcher.segment_reader(0u32).fast_fields();
+        let column = fastfields.bytes("payload").unwrap().unwrap();
+        let mut buffer = Vec::new();
+        assert!(column.first_bytes(0, &mut buffer).unwrap());
+        assert_eq!(buffer, embedding);
+        assert!(!column.first_bytes(1, &mut buffer).unwrap());
+        assert!(column.first_bytes(2, &mut buffer).unwrap());
+        assert_eq!(buffer, vec![0u8; 32]);
+    }
+
     #[test]
     fn test_mapping_bug_docids_for_value_range() {
         let mut schema_builder = Schema::builder();
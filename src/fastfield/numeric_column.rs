@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use columnar::{Column, ColumnType, ColumnValues, MonotonicallyMappableToU64};
+
+use crate::fastfield::FastValue;
+use crate::schema::Type;
+use crate::{DateTime, DocId};
+
+/// Column types that can be opened as a [`NumericColumn`].
+pub(crate) const NUMERIC_COLUMN_TYPES: &[ColumnType] = &[
+    ColumnType::U64,
+    ColumnType::I64,
+    ColumnType::F64,
+    ColumnType::Bool,
+    ColumnType::DateTime,
+];
+
+/// Type-erased view over a numeric fast field (`u64`, `i64`, `f64`, `bool` or `DateTime`).
+///
+/// Whatever the type of the fast field, its values are exposed as `u64`, using the
+/// same monotonic mapping as the one used to encode them. Comparing, sorting or
+/// bucketing the `u64` values therefore gives the same result as doing it on the
+/// typed values, so that a collector only needs a single code path for all of the
+/// numeric types.
+///
+/// The typed values are only recovered on the fly, when needed, with
+/// [`NumericColumn::to_value`] or [`NumericColumn::to_f64`].
+///
+/// See [`FastFieldReaders::numeric`](crate::fastfield::FastFieldReaders::numeric).
+#[derive(Clone)]
+pub struct NumericColumn {
+    column: Column<u64>,
+    column_type: ColumnType,
+}
+
+impl NumericColumn {
+    pub(crate) fn new(column: Column<u64>, column_type: ColumnType) -> NumericColumn {
+        debug_assert!(NUMERIC_COLUMN_TYPES.contains(&column_type));
+        NumericColumn {
+            column,
+            column_type,
+        }
+    }
+
+    /// Returns the type of the values of the fast field.
+    pub fn column_type(&self) -> ColumnType {
+        self.column_type
+    }
+
+    /// Returns the underlying column of `u64` values.
+    pub fn u64_column(&self) -> &Column<u64> {
+        &self.column
+    }
+
+    /// Returns true if and only if the document has at least one value.
+    pub fn has_value(&self, doc: DocId) -> bool {
+        self.column.has_value(doc)
+    }
+
+    /// Returns the first `u64` value of the document, if any.
+    pub fn first(&self, doc: DocId) -> Option<u64> {
+        self.column.first(doc)
+    }
+
+    /// Returns the `u64` values of the document.
+    pub fn values_for_doc(&self, doc: DocId) -> impl Iterator<Item = u64> + '_ {
+        self.column.values_for_doc(doc)
+    }
+
+    /// Returns a dense view of the column, yielding the first `u64` value of each
+    /// document, or `default_value` for the documents without value.
+    pub fn first_or_default_col(&self, default_value: u64) -> Arc<dyn ColumnValues<u64>> {
+        self.column.clone().first_or_default_col(default_value)
+    }
+
+    /// Converts a `u64` value of the column to its typed value.
+    ///
+    /// Returns `None` if `T` is not the type of the fast field.
+    pub fn to_value<T: FastValue>(&self, val: u64) -> Option<T> {
+        if T::to_type() != Type::from(self.column_type) {
+            return None;
+        }
+        Some(T::from_u64(val))
+    }
+
+    /// Converts a typed value to the `u64` value it is represented with in the column,
+    /// for instance to define the boundaries of a range or of a histogram.
+    ///
+    /// Returns `None` if `T` is not the type of the fast field.
+    pub fn from_value<T: FastValue>(&self, value: T) -> Option<u64> {
+        if T::to_type() != Type::from(self.column_type) {
+            return None;
+        }
+        Some(value.to_u64())
+    }
+
+    /// Converts a `u64` value of the column to a `f64`.
+    ///
+    /// `DateTime` values are converted to their timestamp in nanoseconds.
+    pub fn to_f64(&self, val: u64) -> f64 {
+        match self.column_type {
+            ColumnType::I64 => i64::from_u64(val) as f64,
+            ColumnType::F64 => f64::from_u64(val),
+            ColumnType::DateTime => DateTime::from_u64(val).into_timestamp_nanos() as f64,
+            _ => val as f64,
+        }
+    }
+
+    /// Converts a `f64` to the closest `u64` value of the column.
+    ///
+    /// `DateTime` values are expected as a timestamp in nanoseconds.
+    /// Values out of the range of the type of the fast field are saturated.
+    pub fn from_f64(&self, value: f64) -> u64 {
+        match self.column_type {
+            ColumnType::I64 => (value as i64).to_u64(),
+            ColumnType::F64 => value.to_u64(),
+            ColumnType::DateTime => DateTime::from_timestamp_nanos(value as i64).to_u64(),
+            ColumnType::Bool => u64::from(value >= 1.0),
+            _ => value as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{Schema, FAST, STRING};
+    use crate::{doc, DateTime, Index, IndexWriter};
+
+    #[test]
+    fn test_numeric_column_conversions() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let u64_field = schema_builder.add_u64_field("u64", FAST);
+        let i64_field = schema_builder.add_i64_field("i64", FAST);
+        let f64_field = schema_builder.add_f64_field("f64", FAST);
+        let date_field = schema_builder.add_date_field("date", FAST);
+        let text_field = schema_builder.add_text_field("text", STRING | FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            u64_field => 3u64,
+            i64_field => -3i64,
+            f64_field => -1.5f64,
+            date_field => DateTime::from_timestamp_secs(2),
+            text_field => "hello",
+        ))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let fast_fields = searcher.segment_reader(0).fast_fields();
+
+        let u64_column = fast_fields.numeric("u64")?.unwrap();
+        let val = u64_column.first(0).unwrap();
+        assert_eq!(u64_column.to_value::<u64>(val), Some(3));
+        assert_eq!(u64_column.to_value::<i64>(val), None);
+        assert_eq!(u64_column.to_f64(val), 3.0);
+
+        let i64_column = fast_fields.numeric("i64")?.unwrap();
+        let val = i64_column.first(0).unwrap();
+        assert_eq!(i64_column.to_value::<i64>(val), Some(-3));
+        assert_eq!(i64_column.to_f64(val), -3.0);
+        assert_eq!(i64_column.from_value(-3i64), Some(val));
+        assert_eq!(i64_column.from_f64(-3.0), val);
+        assert!(i64_column.from_f64(-4.0) < val);
+
+        let f64_column = fast_fields.numeric("f64")?.unwrap();
+        let val = f64_column.first(0).unwrap();
+        assert_eq!(f64_column.to_value::<f64>(val), Some(-1.5));
+        assert_eq!(f64_column.to_f64(val), -1.5);
+        assert_eq!(f64_column.from_f64(-1.5), val);
+        assert!(f64_column.from_f64(-1.0) > val);
+
+        let date_column = fast_fields.numeric("date")?.unwrap();
+        let val = date_column.first(0).unwrap();
+        assert_eq!(
+            date_column.to_value::<DateTime>(val),
+            Some(DateTime::from_timestamp_secs(2))
+        );
+        assert_eq!(date_column.to_f64(val), 2_000_000_000.0);
+        assert_eq!(date_column.from_f64(2_000_000_000.0), val);
+
+        assert!(fast_fields.numeric("text")?.is_none());
+        assert!(fast_fields.numeric("missing")?.is_none());
+        Ok(())
+    }
+}
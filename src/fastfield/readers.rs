@@ -10,6 +10,8 @@ use common::ByteCount;
 
 use crate::core::json_utils::encode_column_name;
 use crate::directory::FileSlice;
+use crate::error::DataCorruption;
+use crate::fastfield::numeric_column::{NumericColumn, NUMERIC_COLUMN_TYPES};
 use crate::schema::{Field, FieldEntry, FieldType, Schema};
 use crate::space_usage::{FieldUsage, PerFieldSpaceUsage};
 use crate::TantivyError;
@@ -125,7 +127,7 @@ impl FastFieldReaders {
         else {
             return Ok(None);
         };
-        let dynamic_column = dynamic_column_handle.open()?;
+        let dynamic_column = open_column(field_name, &dynamic_column_handle)?;
         Ok(dynamic_column.into())
     }
 
@@ -202,7 +204,7 @@ impl FastFieldReaders {
         else {
             return Ok(None);
         };
-        let dynamic_column = dynamic_column_handle.open()?;
+        let dynamic_column = open_column(field_name, &dynamic_column_handle)?;
         Ok(dynamic_column.into())
     }
 
@@ -213,7 +215,7 @@ impl FastFieldReaders {
         else {
             return Ok(None);
         };
-        let dynamic_column = dynamic_column_handle.open()?;
+        let dynamic_column = open_column(field_name, &dynamic_column_handle)?;
         Ok(dynamic_column.into())
     }
 
@@ -284,7 +286,7 @@ impl FastFieldReaders {
                     continue;
                 }
             }
-            if let Some(col_u64) = col.open_u64_lenient()? {
+            if let Some(col_u64) = open_column_u64_lenient(field_name, &col)? {
                 return Ok(Some((col_u64, col.column_type())));
             }
         }
@@ -312,7 +314,7 @@ impl FastFieldReaders {
                     continue;
                 }
             }
-            if let Some(col_u64) = col.open_u64_lenient()? {
+            if let Some(col_u64) = open_column_u64_lenient(field_name, &col)? {
                 columns_and_types.push((col_u64, col.column_type()));
             }
         }
@@ -330,6 +332,15 @@ impl FastFieldReaders {
         self.u64_lenient_for_type(None, field_name)
     }
 
+    /// Returns a [`NumericColumn`], exposing any numeric fast field (`u64`, `i64`, `f64`,
+    /// `bool`, `DateTime`) as `u64` values, with converters to the typed values.
+    ///
+    /// Returns Ok(None) if the field does not have a numeric column.
+    pub fn numeric(&self, field_name: &str) -> crate::Result<Option<NumericColumn>> {
+        let column_opt = self.u64_lenient_for_type(Some(NUMERIC_COLUMN_TYPES), field_name)?;
+        Ok(column_opt.map(|(column, column_type)| NumericColumn::new(column, column_type)))
+    }
+
     /// Returns the `i64` fast field reader reader associated with `field`.
     ///
     /// If `field` is not a i64 fast field, this method returns an Error.
@@ -352,6 +363,44 @@ impl FastFieldReaders {
     }
 }
 
+/// Converts the error raised when opening the column of a fast field.
+///
+/// Corrupted columns are reported as [`DataCorruption`] errors, naming the field and the type of
+/// the column, rather than as plain io errors.
+fn column_open_error(
+    field_name: &str,
+    column_handle: &DynamicColumnHandle,
+    io_error: io::Error,
+) -> TantivyError {
+    if io_error.kind() != io::ErrorKind::InvalidData {
+        return io_error.into();
+    }
+    DataCorruption::comment_only(format!(
+        "Fast field {field_name:?} has a corrupted {:?} column ({} bytes): {io_error}",
+        column_handle.column_type(),
+        column_handle.num_bytes()
+    ))
+    .into()
+}
+
+fn open_column(
+    field_name: &str,
+    column_handle: &DynamicColumnHandle,
+) -> crate::Result<DynamicColumn> {
+    column_handle
+        .open()
+        .map_err(|io_error| column_open_error(field_name, column_handle, io_error))
+}
+
+fn open_column_u64_lenient(
+    field_name: &str,
+    column_handle: &DynamicColumnHandle,
+) -> crate::Result<Option<Column<u64>>> {
+    column_handle
+        .open_u64_lenient()
+        .map_err(|io_error| column_open_error(field_name, column_handle, io_error))
+}
+
 #[cfg(test)]
 mod tests {
     use columnar::ColumnType;
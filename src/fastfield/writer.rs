@@ -5,7 +5,7 @@ use common::{DateTimePrecision, JsonPathWriter};
 use tokenizer_api::Token;
 
 use crate::schema::document::{Document, ReferenceValue, ReferenceValueLeaf, Value};
-use crate::schema::{value_type_to_column_type, Field, FieldType, Schema, Type};
+use crate::schema::{value_type_to_column_type, Facet, Field, FieldType, Schema, Type};
 use crate::tokenizer::{TextAnalyzer, TokenizerManager};
 use crate::{DocId, TantivyError};
 
@@ -117,6 +117,16 @@ impl FastFieldsWriter {
         Ok(())
     }
 
+    /// Adds a facet derived from the values of a document to the fast field of a facet field.
+    ///
+    /// See [`DateOptions::set_date_facet`](crate::schema::DateOptions::set_date_facet).
+    pub(crate) fn add_derived_facet(&mut self, doc_id: DocId, field: Field, facet: &Facet) {
+        if let Some(field_name) = &self.fast_field_names[field.field_id() as usize] {
+            self.columnar_writer
+                .record_str(doc_id, field_name, facet.encoded_str());
+        }
+    }
+
     fn add_doc_value<'a, V: Value<'a>>(
         &mut self,
         doc_id: DocId,
@@ -32,9 +32,10 @@ mod tests {
 
     use once_cell::sync::Lazy;
 
+    use crate::collector::TopDocs;
     use crate::directory::{CompositeFile, Directory, RamDirectory, WritePtr};
     use crate::fieldnorm::{FieldNormReader, FieldNormsSerializer, FieldNormsWriter};
-    use crate::query::{EnableScoring, Query, TermQuery};
+    use crate::query::{EnableScoring, PhraseQuery, Query, TermQuery};
     use crate::schema::{
         Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, STORED, TEXT,
     };
@@ -151,4 +152,34 @@ mod tests {
         assert_eq!(scorer.advance(), TERMINATED);
         Ok(())
     }
+
+    #[test]
+    fn test_fieldnorm_disabled_phrase_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_fieldnorms(false),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_for_tests()?;
+        writer.add_document(doc!(text=>"red apple"))?;
+        writer.add_document(doc!(text=>"a very long text about a red apple and a pear"))?;
+        writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert!(searcher
+            .segment_reader(0)
+            .fieldnorms_readers()
+            .get_field(text)?
+            .is_none());
+        let query = PhraseQuery::new(vec![
+            Term::from_field_text(text, "red"),
+            Term::from_field_text(text, "apple"),
+        ]);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+        assert_eq!(top_docs.len(), 2);
+        assert!((top_docs[0].0 - top_docs[1].0).abs() < 0.001f32);
+        Ok(())
+    }
 }
@@ -0,0 +1,419 @@
+use serde::Serialize;
+
+use crate::error::DataCorruption;
+use crate::index::{Segment, SegmentId, SegmentReader};
+use crate::schema::{Field, IndexRecordOption};
+use crate::termdict::TermOrdinal;
+use crate::{DocId, DocSet, TERMINATED};
+
+/// Maximum number of terms per field, stored documents and fast field rows
+/// sampled in each segment.
+const NUM_SAMPLES: usize = 1_000;
+
+/// An inconsistency found while checking a segment.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum HealthIssue {
+    /// A component of the segment could not be opened or read. The checks relying on
+    /// it are skipped.
+    UnreadableComponent {
+        /// The component, e.g. `postings` or `store`.
+        component: String,
+        /// Name of the field, for the components holding the data of each field separately.
+        field: Option<String>,
+        /// The error raised when reading the component.
+        error: String,
+    },
+    /// A posting list contains a doc id that is not lower than the segment `max_doc`.
+    PostingsDocIdOutOfBounds {
+        /// Name of the field of the term.
+        field: String,
+        /// The out of bounds doc id.
+        doc: DocId,
+    },
+    /// The number of documents of a posting list differs from the doc freq of its term.
+    PostingsDocFreqMismatch {
+        /// Name of the field of the term.
+        field: String,
+        /// Doc freq recorded in the term dictionary.
+        doc_freq: u32,
+        /// Number of documents found in the posting list.
+        num_docs: u32,
+    },
+    /// The doc store does not hold exactly `max_doc` documents.
+    StoreDocCountMismatch {
+        /// Number of documents in the doc store.
+        num_store_docs: DocId,
+    },
+    /// A document of the doc store could not be read.
+    UnreadableStoredDoc {
+        /// The doc id of the document.
+        doc: DocId,
+        /// The error raised when reading the document.
+        error: String,
+    },
+    /// The alive bitset does not cover exactly `max_doc` documents.
+    AliveBitSetOutOfBounds {
+        /// Number of documents covered by the alive bitset.
+        bitset_max_value: u32,
+    },
+    /// The number of alive documents in the alive bitset differs from the segment meta.
+    AliveDocCountMismatch {
+        /// Number of alive documents in the alive bitset.
+        num_alive_docs: u32,
+    },
+    /// A fast field column does not have one row per document.
+    FastFieldRowCountMismatch {
+        /// Name of the fast field.
+        field: String,
+        /// Number of rows of the column.
+        num_rows: u32,
+    },
+}
+
+/// Result of the checks of a single segment.
+#[derive(Clone, Debug, Serialize)]
+pub struct SegmentHealthReport {
+    /// The id of the segment.
+    pub segment_id: SegmentId,
+    /// The `max_doc` of the segment.
+    pub max_doc: DocId,
+    /// The number of alive documents of the segment.
+    pub num_docs: DocId,
+    /// Number of terms whose posting list was read.
+    pub num_terms_sampled: usize,
+    /// Number of stored documents that were read.
+    pub num_docs_sampled: usize,
+    /// Number of fast field values that were read.
+    pub num_fast_field_values_sampled: usize,
+    /// The inconsistencies found in the segment.
+    pub issues: Vec<HealthIssue>,
+}
+
+impl SegmentHealthReport {
+    /// Returns true if no issue was found in the segment.
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn push_unreadable_component(
+        &mut self,
+        component: &str,
+        field: Option<&str>,
+        error: impl ToString,
+    ) {
+        self.issues.push(HealthIssue::UnreadableComponent {
+            component: component.to_string(),
+            field: field.map(str::to_string),
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Result of [`Index::health_report()`](crate::Index::health_report).
+#[derive(Clone, Debug, Serialize)]
+pub struct IndexHealthReport {
+    /// The reports of the searchable segments.
+    pub segments: Vec<SegmentHealthReport>,
+}
+
+impl IndexHealthReport {
+    /// Returns true if no issue was found in any segment.
+    pub fn is_healthy(&self) -> bool {
+        self.segments.iter().all(SegmentHealthReport::is_healthy)
+    }
+
+    /// Returns the issues of all segments, along with the id of their segment.
+    pub fn issues(&self) -> impl Iterator<Item = (SegmentId, &HealthIssue)> + '_ {
+        self.segments.iter().flat_map(|segment_report| {
+            segment_report
+                .issues
+                .iter()
+                .map(move |issue| (segment_report.segment_id, issue))
+        })
+    }
+}
+
+fn sample_stride(num_items: usize) -> usize {
+    (num_items / NUM_SAMPLES).max(1)
+}
+
+/// Checks a segment.
+///
+/// A component that cannot be opened or read is reported as an
+/// [`HealthIssue::UnreadableComponent`], and the other components are still checked.
+pub(crate) fn segment_health_report(segment: &Segment) -> SegmentHealthReport {
+    let mut report = SegmentHealthReport {
+        segment_id: segment.id(),
+        max_doc: segment.meta().max_doc(),
+        num_docs: segment.meta().num_docs(),
+        num_terms_sampled: 0,
+        num_docs_sampled: 0,
+        num_fast_field_values_sampled: 0,
+        issues: Vec::new(),
+    };
+    let segment_reader = match SegmentReader::open(segment) {
+        Ok(segment_reader) => segment_reader,
+        Err(error) => {
+            report.push_unreadable_component("segment", None, error);
+            return report;
+        }
+    };
+    check_alive_bitset(&segment_reader, &mut report);
+    check_postings(&segment_reader, &mut report);
+    check_store(&segment_reader, &mut report);
+    check_fast_fields(&segment_reader, &mut report);
+    report
+}
+
+fn check_alive_bitset(segment_reader: &SegmentReader, report: &mut SegmentHealthReport) {
+    let Some(alive_bitset) = segment_reader.alive_bitset() else {
+        return;
+    };
+    let bitset_max_value = alive_bitset.bitset().max_value();
+    if bitset_max_value != report.max_doc {
+        report
+            .issues
+            .push(HealthIssue::AliveBitSetOutOfBounds { bitset_max_value });
+    }
+    let num_alive_docs = alive_bitset.num_alive_docs() as u32;
+    if num_alive_docs != report.num_docs {
+        report
+            .issues
+            .push(HealthIssue::AliveDocCountMismatch { num_alive_docs });
+    }
+}
+
+fn check_postings(segment_reader: &SegmentReader, report: &mut SegmentHealthReport) {
+    let schema = segment_reader.schema();
+    for (field, field_entry) in schema.fields() {
+        if !field_entry.is_indexed() {
+            continue;
+        }
+        if let Err(error) = check_field_postings(segment_reader, field, report) {
+            report.push_unreadable_component("postings", Some(field_entry.name()), error);
+        }
+    }
+}
+
+/// Checks the postings of the sampled terms of a field.
+///
+/// The sampled terms are looked up by their ordinal, so that the term dictionary is
+/// not streamed through entirely.
+fn check_field_postings(
+    segment_reader: &SegmentReader,
+    field: Field,
+    report: &mut SegmentHealthReport,
+) -> crate::Result<()> {
+    let field_name = segment_reader.schema().get_field_name(field);
+    let inverted_index = segment_reader.inverted_index(field)?;
+    let term_dict = inverted_index.terms();
+    let num_terms = term_dict.num_terms();
+    let mut term_bytes = Vec::new();
+    for term_ord in (0..num_terms).step_by(sample_stride(num_terms)) {
+        if !term_dict.ord_to_term(term_ord as TermOrdinal, &mut term_bytes)? {
+            return Err(DataCorruption::comment_only(format!(
+                "term ordinal {term_ord} is missing from the term dictionary"
+            ))
+            .into());
+        }
+        let Some(term_info) = term_dict.get(&term_bytes)? else {
+            return Err(DataCorruption::comment_only(format!(
+                "term ordinal {term_ord} has no term info"
+            ))
+            .into());
+        };
+        let mut postings =
+            inverted_index.read_postings_from_terminfo(&term_info, IndexRecordOption::Basic)?;
+        let mut num_docs = 0u32;
+        while postings.doc() != TERMINATED {
+            if postings.doc() >= report.max_doc {
+                report.issues.push(HealthIssue::PostingsDocIdOutOfBounds {
+                    field: field_name.to_string(),
+                    doc: postings.doc(),
+                });
+                break;
+            }
+            num_docs += 1;
+            postings.advance();
+        }
+        if postings.doc() == TERMINATED && num_docs != term_info.doc_freq {
+            report.issues.push(HealthIssue::PostingsDocFreqMismatch {
+                field: field_name.to_string(),
+                doc_freq: term_info.doc_freq,
+                num_docs,
+            });
+        }
+        report.num_terms_sampled += 1;
+    }
+    Ok(())
+}
+
+fn check_store(segment_reader: &SegmentReader, report: &mut SegmentHealthReport) {
+    let store_reader = match segment_reader.get_store_reader(1) {
+        Ok(store_reader) => store_reader,
+        Err(error) => {
+            report.push_unreadable_component("store", None, error);
+            return;
+        }
+    };
+    let num_store_docs = store_reader
+        .block_checkpoints()
+        .last()
+        .map(|checkpoint| checkpoint.doc_range.end)
+        .unwrap_or(0);
+    if num_store_docs != report.max_doc {
+        report
+            .issues
+            .push(HealthIssue::StoreDocCountMismatch { num_store_docs });
+    }
+    let num_docs = num_store_docs.min(report.max_doc);
+    let stride = sample_stride(num_docs as usize);
+    for doc in (0..num_docs).step_by(stride) {
+        if segment_reader.is_deleted(doc) {
+            continue;
+        }
+        if let Err(error) = store_reader.get_document_bytes(doc) {
+            report.issues.push(HealthIssue::UnreadableStoredDoc {
+                doc,
+                error: error.to_string(),
+            });
+        }
+        report.num_docs_sampled += 1;
+    }
+}
+
+fn check_fast_fields(segment_reader: &SegmentReader, report: &mut SegmentHealthReport) {
+    let schema = segment_reader.schema();
+    let fast_fields = segment_reader.fast_fields();
+    let stride = sample_stride(report.max_doc as usize);
+    for (_, field_entry) in schema.fields() {
+        if !field_entry.is_fast() {
+            continue;
+        }
+        let field_name = field_entry.name();
+        let columns = match fast_fields.u64_lenient_for_type_all(None, field_name) {
+            Ok(columns) => columns,
+            Err(error) => {
+                report.push_unreadable_component("fast fields", Some(field_name), error);
+                continue;
+            }
+        };
+        for (column, _) in columns {
+            let num_rows = column.num_docs();
+            if num_rows != report.max_doc {
+                report.issues.push(HealthIssue::FastFieldRowCountMismatch {
+                    field: field_name.to_string(),
+                    num_rows,
+                });
+                continue;
+            }
+            for doc in (0..num_rows).step_by(stride) {
+                report.num_fast_field_values_sampled += column.values_for_doc(doc).count();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HealthIssue, NUM_SAMPLES};
+    use crate::directory::Directory;
+    use crate::index::SegmentComponent;
+    use crate::indexer::NoMergePolicy;
+    use crate::schema::{Schema, FAST, STORED, STRING, TEXT};
+    use crate::{doc, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_health_report_healthy_index() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING | FAST);
+        let body = schema_builder.add_text_field("body", TEXT | STORED);
+        let score = schema_builder.add_u64_field("score", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..10u64 {
+            index_writer.add_document(doc!(
+                id => format!("doc{i}"),
+                body => "hello happy world",
+                score => i,
+            ))?;
+        }
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(id, "doc3"));
+        index_writer.commit()?;
+
+        let health_report = index.health_report()?;
+        assert!(health_report.is_healthy());
+        assert_eq!(health_report.issues().count(), 0);
+        assert_eq!(health_report.segments.len(), 1);
+        let segment_report = &health_report.segments[0];
+        assert_eq!(segment_report.max_doc, 10);
+        assert_eq!(segment_report.num_docs, 9);
+        // 10 ids and 3 body terms.
+        assert_eq!(segment_report.num_terms_sampled, 13);
+        assert_eq!(segment_report.num_docs_sampled, 9);
+        // One id and one score per document.
+        assert_eq!(segment_report.num_fast_field_values_sampled, 20);
+        Ok(())
+    }
+
+    #[test]
+    fn test_health_report_samples_large_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_u64_field("id", FAST | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let num_docs = NUM_SAMPLES as u64 * 3;
+        for i in 0..num_docs {
+            index_writer.add_document(doc!(id => i))?;
+        }
+        index_writer.commit()?;
+
+        let health_report = index.health_report()?;
+        assert!(health_report.is_healthy());
+        let segment_report = &health_report.segments[0];
+        assert_eq!(segment_report.num_terms_sampled, 0);
+        assert_eq!(segment_report.num_docs_sampled, NUM_SAMPLES);
+        assert_eq!(segment_report.num_fast_field_values_sampled, NUM_SAMPLES);
+        Ok(())
+    }
+
+    #[test]
+    fn test_health_report_unreadable_component() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_u64_field("id", FAST | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(id => 1u64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(id => 2u64))?;
+        index_writer.commit()?;
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 2);
+        let broken_segment_id = segment_metas[0].id();
+        index
+            .directory()
+            .delete(&segment_metas[0].relative_path(SegmentComponent::FastFields))?;
+
+        // The broken segment is reported, and the other one is still checked.
+        let health_report = index.health_report()?;
+        assert!(!health_report.is_healthy());
+        assert_eq!(health_report.segments.len(), 2);
+        let issues: Vec<_> = health_report.issues().collect();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].0, broken_segment_id);
+        assert!(matches!(
+            issues[0].1,
+            HealthIssue::UnreadableComponent { component, field: None, .. } if component == "segment"
+        ));
+        let healthy_segment_report = health_report
+            .segments
+            .iter()
+            .find(|segment_report| segment_report.segment_id != broken_segment_id)
+            .unwrap();
+        assert!(healthy_segment_report.is_healthy());
+        assert_eq!(healthy_segment_report.num_docs_sampled, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::directory::RamDirectory;
+use crate::indexer::SingleSegmentIndexWriter;
+use crate::schema::document::Document;
+use crate::schema::Schema;
+use crate::{
+    Index, IndexBuilder, IndexReader, ReloadPolicy, Searcher, TantivyDocument, TantivyError,
+};
+
+/// A small, short-lived index held in anonymous memory.
+///
+/// `InMemoryIndex` is a convenience wrapper for ephemeral search over small corpora, e.g.
+/// searching within the data of a single user session. The documents are indexed in the
+/// calling thread by a single segment writer, into a [`RamDirectory`]: no file is created,
+/// no indexing or merge thread is started, and there is no commit nor garbage collection to
+/// go through. Dropping the `InMemoryIndex` releases all of its memory.
+///
+/// The segment is serialized in the same format as on disk, and is searched with the
+/// regular segment readers: there are no lighter in-memory data structures, and searching
+/// an `InMemoryIndex` is as fast as searching a [`RamDirectory`] index.
+///
+/// The index is searched with the usual [`Query`](crate::query::Query) and
+/// [`Collector`](crate::collector::Collector) API, through its [`Searcher`].
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, InMemoryIndex};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let mut writer = InMemoryIndex::writer(schema_builder.build(), 15_000_000)?;
+/// writer.add_document(doc!(title => "The Old Man and the Sea"))?;
+/// writer.add_document(doc!(title => "The Sun Also Rises"))?;
+/// let in_memory_index = writer.finish()?;
+///
+/// let query = QueryParser::for_index(in_memory_index.index(), vec![title]).parse_query("sea")?;
+/// assert_eq!(in_memory_index.searcher().search(&query, &Count)?, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct InMemoryIndex {
+    index: Index,
+    searcher: Searcher,
+}
+
+impl InMemoryIndex {
+    /// Creates a writer for an `InMemoryIndex` with the given schema.
+    ///
+    /// The writer accepts documents as long as its memory usage is below
+    /// `memory_budget_in_bytes`. Use [`IndexBuilder::in_memory_index_writer`] to
+    /// configure the tokenizers or the settings of the index.
+    pub fn writer<D: Document>(
+        schema: Schema,
+        memory_budget_in_bytes: usize,
+    ) -> crate::Result<InMemoryIndexWriter<D>> {
+        Index::builder()
+            .schema(schema)
+            .in_memory_index_writer(memory_budget_in_bytes)
+    }
+
+    /// Returns the underlying [`Index`], e.g. to create a
+    /// [`QueryParser`](crate::query::QueryParser).
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Returns the searcher of the index.
+    pub fn searcher(&self) -> &Searcher {
+        &self.searcher
+    }
+}
+
+/// Writer of an [`InMemoryIndex`].
+///
+/// See [`InMemoryIndex::writer`].
+pub struct InMemoryIndexWriter<D: Document = TantivyDocument> {
+    single_segment_index_writer: SingleSegmentIndexWriter<D>,
+    memory_budget_in_bytes: usize,
+}
+
+impl<D: Document> InMemoryIndexWriter<D> {
+    pub(crate) fn new(
+        index_builder: IndexBuilder,
+        memory_budget_in_bytes: usize,
+    ) -> crate::Result<InMemoryIndexWriter<D>> {
+        let single_segment_index_writer = index_builder
+            .single_segment_index_writer(RamDirectory::create(), memory_budget_in_bytes)?;
+        Ok(InMemoryIndexWriter {
+            single_segment_index_writer,
+            memory_budget_in_bytes,
+        })
+    }
+
+    /// Returns the memory used by the documents indexed so far, in bytes.
+    pub fn mem_usage(&self) -> usize {
+        self.single_segment_index_writer.mem_usage()
+    }
+
+    /// Indexes a document.
+    ///
+    /// Returns a [`TantivyError::InvalidArgument`] error, without indexing the document,
+    /// if the memory budget of the writer is exhausted.
+    pub fn add_document(&mut self, document: D) -> crate::Result<()> {
+        if self.mem_usage() >= self.memory_budget_in_bytes {
+            return Err(TantivyError::InvalidArgument(format!(
+                "The memory budget of the in-memory index ({} bytes) is exhausted.",
+                self.memory_budget_in_bytes
+            )));
+        }
+        self.single_segment_index_writer.add_document(document)
+    }
+
+    /// Finishes the index, and opens its searcher.
+    pub fn finish(self) -> crate::Result<InMemoryIndex> {
+        let index = self.single_segment_index_writer.finalize()?;
+        let reader: IndexReader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        Ok(InMemoryIndex {
+            index,
+            searcher: reader.searcher(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InMemoryIndex;
+    use crate::collector::{Count, TopDocs};
+    use crate::query::TermQuery;
+    use crate::schema::{IndexRecordOption, Schema, FAST, STRING};
+    use crate::{doc, Order, TantivyError, Term};
+
+    #[test]
+    fn test_in_memory_index() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let rank = schema_builder.add_u64_field("rank", FAST);
+        let mut writer = InMemoryIndex::writer(schema_builder.build(), 15_000_000)?;
+        for i in 0..10u64 {
+            let tag_value = if i % 2 == 0 { "even" } else { "odd" };
+            writer.add_document(doc!(tag => tag_value, rank => i))?;
+        }
+        let in_memory_index = writer.finish()?;
+        let searcher = in_memory_index.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        assert_eq!(searcher.num_docs(), 10);
+        let query = TermQuery::new(Term::from_field_text(tag, "odd"), IndexRecordOption::Basic);
+        assert_eq!(searcher.search(&query, &Count)?, 5);
+        let top_docs = searcher.search(
+            &query,
+            &TopDocs::with_limit(1).order_by_u64_field("rank", Order::Desc),
+        )?;
+        assert_eq!(top_docs[0].0, 9);
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_memory_index_memory_budget() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let memory_budget_in_bytes = 3_000_000;
+        let mut writer = InMemoryIndex::writer(schema_builder.build(), memory_budget_in_bytes)?;
+        let mut num_docs = 0u64;
+        let err = loop {
+            if let Err(err) = writer.add_document(doc!(tag => format!("tag{num_docs}"))) {
+                break err;
+            }
+            num_docs += 1;
+        };
+        assert!(matches!(err, TantivyError::InvalidArgument(_)));
+        assert!(num_docs > 0);
+        assert!(writer.mem_usage() >= memory_budget_in_bytes);
+        let in_memory_index = writer.finish()?;
+        assert_eq!(in_memory_index.searcher().num_docs(), num_docs);
+        Ok(())
+    }
+}
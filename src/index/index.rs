@@ -3,11 +3,18 @@ use std::fmt;
 #[cfg(feature = "mmap")]
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::thread::available_parallelism;
 
+use rayon::ThreadPool;
+
+use super::health_report::segment_health_report;
+use super::migration::migrate_components;
 use super::segment::Segment;
 use super::segment_reader::merge_field_meta_data;
-use super::{FieldMetadata, IndexSettings};
+use super::{
+    ComponentMigration, FieldMetadata, InMemoryIndexWriter, IndexHealthReport, IndexSettings,
+};
 use crate::core::{Executor, META_FILEPATH};
 use crate::directory::error::OpenReadError;
 #[cfg(feature = "mmap")]
@@ -16,13 +23,14 @@ use crate::directory::{Directory, ManagedDirectory, RamDirectory, INDEX_WRITER_L
 use crate::error::{DataCorruption, TantivyError};
 use crate::index::{IndexMeta, SegmentId, SegmentMeta, SegmentMetaInventory};
 use crate::indexer::index_writer::{MAX_NUM_THREAD, MEMORY_BUDGET_NUM_BYTES_MIN};
+use crate::indexer::oplog::read_oplog;
 use crate::indexer::segment_updater::save_metas;
-use crate::indexer::{IndexWriter, SingleSegmentIndexWriter};
+use crate::indexer::{IndexWriter, MergeDocIdMapping, SingleSegmentIndexWriter, UserOperation};
 use crate::reader::{IndexReader, IndexReaderBuilder};
-use crate::schema::document::Document;
-use crate::schema::{Field, FieldType, Schema};
-use crate::tokenizer::{TextAnalyzer, TokenizerManager};
-use crate::SegmentReader;
+use crate::schema::document::{Document, DocumentDeserialize};
+use crate::schema::{Field, FieldChange, FieldType, Schema};
+use crate::tokenizer::{TextAnalyzer, Token, TokenizerManager};
+use crate::{Opstamp, SegmentReader};
 
 fn load_metas(
     directory: &dyn Directory,
@@ -46,6 +54,14 @@ fn load_metas(
         .map_err(From::from)
 }
 
+fn checkout_commit(metas: &IndexMeta, opstamp: Opstamp) -> crate::Result<IndexMeta> {
+    metas.checkout(opstamp).ok_or_else(|| {
+        TantivyError::InvalidArgument(format!(
+            "Commit with opstamp {opstamp} is not part of the commit history."
+        ))
+    })
+}
+
 /// Save the index meta file.
 /// This operation is atomic :
 /// Either
@@ -67,6 +83,7 @@ fn save_new_metas(
             schema,
             opstamp: 0u64,
             payload: None,
+            commit_history: Vec::new(),
         },
         directory,
     )?;
@@ -192,6 +209,17 @@ impl IndexBuilder {
         Ok(index_simple_writer)
     }
 
+    /// Creates a writer for an [`InMemoryIndex`](crate::InMemoryIndex), a small single segment
+    /// index built in the calling thread and held in anonymous memory.
+    ///
+    /// See [`InMemoryIndex::writer`](crate::InMemoryIndex::writer).
+    pub fn in_memory_index_writer<D: Document>(
+        self,
+        memory_budget_in_bytes: usize,
+    ) -> crate::Result<InMemoryIndexWriter<D>> {
+        InMemoryIndexWriter::new(self, memory_budget_in_bytes)
+    }
+
     /// Creates a new index in a temp directory.
     ///
     /// The index will use the [`MmapDirectory`] in a newly created directory.
@@ -222,17 +250,13 @@ impl IndexBuilder {
         }
         let mut index = Index::open(dir)?;
         index.set_tokenizers(self.tokenizer_manager.clone());
-        if index.schema() == self.get_expect_schema()? {
-            Ok(index)
-        } else {
-            Err(TantivyError::SchemaError(
-                "An index exists but the schema does not match.".to_string(),
-            ))
-        }
+        index.extend_schema(self.get_expect_schema()?)?;
+        Ok(index)
     }
 
     fn validate(&self) -> crate::Result<()> {
-        if let Some(_schema) = self.schema.as_ref() {
+        if let Some(schema) = self.schema.as_ref() {
+            resolve_routing_field(schema, &self.index_settings)?;
             Ok(())
         } else {
             Err(TantivyError::InvalidArgument(
@@ -262,6 +286,35 @@ impl IndexBuilder {
     }
 }
 
+/// Returns the routing field of the index, after checking it is
+/// an indexed text field using the `raw` tokenizer.
+///
+/// The routing key of a document is its untokenized value, so that it would not match
+/// the indexed terms of a tokenized field.
+fn resolve_routing_field(
+    schema: &Schema,
+    settings: &IndexSettings,
+) -> crate::Result<Option<Field>> {
+    let Some(routing_field_name) = settings.routing_field.as_deref() else {
+        return Ok(None);
+    };
+    let routing_field = schema.get_field(routing_field_name)?;
+    match schema.get_field_entry(routing_field).field_type() {
+        FieldType::Str(text_options)
+            if text_options
+                .get_indexing_options()
+                .map(|indexing_options| indexing_options.tokenizer() == "raw")
+                .unwrap_or(false) =>
+        {
+            Ok(Some(routing_field))
+        }
+        _ => Err(TantivyError::SchemaError(format!(
+            "The routing field {routing_field_name:?} must be an indexed text field using the raw \
+             tokenizer."
+        ))),
+    }
+}
+
 /// Search Index
 #[derive(Clone)]
 pub struct Index {
@@ -269,9 +322,13 @@ pub struct Index {
     schema: Schema,
     settings: IndexSettings,
     executor: Executor,
+    indexing_thread_pool: Option<Arc<ThreadPool>>,
+    merge_thread_pool: Option<Arc<ThreadPool>>,
     tokenizers: TokenizerManager,
     fast_field_tokenizers: TokenizerManager,
     inventory: SegmentMetaInventory,
+    // If set, the index is pinned to the commit with the given opstamp.
+    commit_opstamp: Option<Opstamp>,
 }
 
 impl Index {
@@ -315,6 +372,36 @@ impl Index {
         self.set_multithread_executor(default_num_threads)
     }
 
+    /// Runs the indexing workers of the index writers created afterwards in the given
+    /// thread pool, instead of spawning a dedicated thread for each of them.
+    ///
+    /// This lets embedders control the CPU quotas and the names of the threads used
+    /// by tantivy. An indexing worker occupies a thread of the pool until the next commit,
+    /// so the pool should have at least as many threads as the index writer has workers,
+    /// and should not be shared with the merges.
+    pub fn set_indexing_thread_pool(&mut self, thread_pool: Arc<ThreadPool>) {
+        self.indexing_thread_pool = Some(thread_pool);
+    }
+
+    /// Returns the thread pool running the indexing workers, if one was set.
+    pub fn indexing_thread_pool(&self) -> Option<&Arc<ThreadPool>> {
+        self.indexing_thread_pool.as_ref()
+    }
+
+    /// Runs the merges of the index writers created afterwards in the given thread pool,
+    /// instead of a thread pool of 4 threads owned by each index writer.
+    ///
+    /// The number of merges running at the same time is still bounded by the
+    /// [`MergeScheduler`](crate::indexer::MergeScheduler) of the index writer.
+    pub fn set_merge_thread_pool(&mut self, thread_pool: Arc<ThreadPool>) {
+        self.merge_thread_pool = Some(thread_pool);
+    }
+
+    /// Returns the thread pool running the merges, if one was set.
+    pub fn merge_thread_pool(&self) -> Option<&Arc<ThreadPool>> {
+        self.merge_thread_pool.as_ref()
+    }
+
     /// Creates a new index using the [`RamDirectory`].
     ///
     /// The index will be allocated in anonymous memory.
@@ -390,7 +477,10 @@ impl Index {
             tokenizers: TokenizerManager::default(),
             fast_field_tokenizers: TokenizerManager::default(),
             executor: Executor::single_thread(),
+            indexing_thread_pool: None,
+            merge_thread_pool: None,
             inventory,
+            commit_opstamp: None,
         }
     }
 
@@ -444,6 +534,53 @@ impl Index {
             })
     }
 
+    /// Tokenizes `text` and returns the resulting tokens, with their positions and offsets.
+    ///
+    /// `field_or_tokenizer_name` is either the name of a text field, in which case the
+    /// tokenizer used to index the field is used, or the name of a tokenizer registered
+    /// in the [`TokenizerManager`] of the index.
+    ///
+    /// This is useful to understand why a query does or does not match a document,
+    /// without having to index it.
+    ///
+    /// ```rust
+    /// use tantivy::schema::{Schema, TEXT};
+    /// use tantivy::Index;
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// schema_builder.add_text_field("title", TEXT);
+    /// let index = Index::create_in_ram(schema_builder.build());
+    ///
+    /// let tokens = index.tokenize("title", "Hello, World!")?;
+    /// let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+    /// assert_eq!(texts, vec!["hello", "world"]);
+    /// assert_eq!((tokens[1].offset_from, tokens[1].offset_to), (7, 12));
+    ///
+    /// let tokens = index.tokenize("raw", "Hello, World!")?;
+    /// assert_eq!(tokens.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tokenize(&self, field_or_tokenizer_name: &str, text: &str) -> crate::Result<Vec<Token>> {
+        let mut text_analyzer = if let Ok(field) = self.schema.get_field(field_or_tokenizer_name) {
+            self.tokenizer_for_field(field)?
+        } else {
+            self.tokenizers
+                .get(field_or_tokenizer_name)
+                .ok_or_else(|| {
+                    TantivyError::InvalidArgument(format!(
+                        "{field_or_tokenizer_name:?} is not a field nor a tokenizer."
+                    ))
+                })?
+        };
+        let mut tokens = Vec::new();
+        text_analyzer
+            .token_stream(text)
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        Ok(tokens)
+    }
+
     /// Create a default [`IndexReader`] for the given index.
     ///
     /// See [`Index.reader_builder()`].
@@ -516,9 +653,69 @@ impl Index {
         Ok(index)
     }
 
+    /// Opens the index using the provided directory, with a schema extending the one
+    /// of the index with new fields.
+    ///
+    /// The fields of the index schema must appear in the same order at the beginning
+    /// of `schema`, unchanged except for their aliases. The segments written before the new fields
+    /// were added behave as if none of their documents had a value for them.
+    ///
+    /// The new schema is persisted with the next commit.
+    pub fn open_with_schema<T: Into<Box<dyn Directory>>>(
+        directory: T,
+        schema: Schema,
+    ) -> crate::Result<Index> {
+        let mut index = Index::open(directory)?;
+        index.extend_schema(schema)?;
+        Ok(index)
+    }
+
+    /// Replaces the schema of the index by `schema`, provided it only adds new fields
+    /// or field aliases.
+    fn extend_schema(&mut self, schema: Schema) -> crate::Result<()> {
+        let schema_diff = self.schema.diff(&schema);
+        if !schema_diff.changes().iter().all(FieldChange::is_additive) {
+            return Err(TantivyError::SchemaError(format!(
+                "An index exists but the schema does not match: {schema_diff}."
+            )));
+        }
+        self.schema = schema;
+        Ok(())
+    }
+
+    /// Open the index using the provided directory, as it was right after the commit
+    /// with the given opstamp.
+    ///
+    /// The commit has to be either the last commit, or part of the commit history
+    /// (see [`IndexSettings::commit_history_size`]).
+    ///
+    /// The returned index is read-only: it is not possible to create an `IndexWriter` on it.
+    /// To make a past commit the last commit of the index again,
+    /// use [`IndexWriter::rollback_to`].
+    pub fn open_at<T: Into<Box<dyn Directory>>>(
+        directory: T,
+        opstamp: Opstamp,
+    ) -> crate::Result<Index> {
+        let directory = directory.into();
+        let directory = ManagedDirectory::wrap(directory)?;
+        let inventory = SegmentMetaInventory::default();
+        let metas = load_metas(&directory, &inventory)?;
+        let metas = checkout_commit(&metas, opstamp)?;
+        let mut index = Index::open_from_metas(directory, &metas, inventory);
+        index.commit_opstamp = Some(opstamp);
+        Ok(index)
+    }
+
     /// Reads the index meta file from the directory.
+    ///
+    /// If the index was opened at a given commit (see [`Index::open_at`]), the returned
+    /// `IndexMeta` is the one of this commit.
     pub fn load_metas(&self) -> crate::Result<IndexMeta> {
-        load_metas(self.directory(), &self.inventory)
+        let metas = load_metas(self.directory(), &self.inventory)?;
+        if let Some(opstamp) = self.commit_opstamp {
+            return checkout_commit(&metas, opstamp);
+        }
+        Ok(metas)
     }
 
     /// Open a new index writer. Attempts to acquire a lockfile.
@@ -545,6 +742,11 @@ impl Index {
         num_threads: usize,
         overall_memory_budget_in_bytes: usize,
     ) -> crate::Result<IndexWriter<D>> {
+        if let Some(opstamp) = self.commit_opstamp {
+            return Err(TantivyError::InvalidArgument(format!(
+                "The index was opened at commit {opstamp} and is read-only."
+            )));
+        }
         let directory_lock = self
             .directory
             .acquire_lock(&INDEX_WRITER_LOCK)
@@ -610,6 +812,41 @@ impl Index {
         &mut self.settings
     }
 
+    /// Returns the field used to partition the documents by tenant, if any.
+    ///
+    /// See [`IndexSettings::routing_field`].
+    pub(crate) fn routing_field(&self) -> crate::Result<Option<Field>> {
+        resolve_routing_field(&self.schema, &self.settings)
+    }
+
+    /// Reads the operation log of the index, starting from the given opstamp.
+    ///
+    /// Returns the committed add and delete operations whose opstamp is greater or
+    /// equal to `from_opstamp`, in the order of their opstamps. Passing the opstamp
+    /// following the last operation already processed makes it possible to
+    /// incrementally replay the changes of the index, e.g. on a replica.
+    ///
+    /// The log is empty unless [`IndexSettings::oplog`] is enabled. It only contains the
+    /// operations of the last [`IndexSettings::oplog_retained_commits`] commits.
+    pub fn read_oplog<D: Document + DocumentDeserialize>(
+        &self,
+        from_opstamp: Opstamp,
+    ) -> crate::Result<Vec<(Opstamp, UserOperation<D>)>> {
+        read_oplog(self.directory(), from_opstamp)
+    }
+
+    /// Returns the mapping between the documents of a segment created by a merge and the
+    /// documents of the segments it merged.
+    ///
+    /// Returns `None` if the segment was not created by a merge, or if
+    /// [`IndexSettings::merge_doc_id_mappings`] was disabled at the time of the merge.
+    pub fn merge_doc_id_mapping(
+        &self,
+        segment_id: SegmentId,
+    ) -> crate::Result<Option<MergeDocIdMapping>> {
+        MergeDocIdMapping::read(self.directory(), segment_id)
+    }
+
     /// Accessor to the index schema
     ///
     /// The schema is actually cloned.
@@ -664,6 +901,43 @@ impl Index {
             .collect())
     }
 
+    /// Samples the terms, stored documents and fast field values of the searchable
+    /// segments, and checks that the components of each segment are consistent
+    /// with each other.
+    ///
+    /// Unlike [`Index::validate_checksum()`], this does not read entire files, and is
+    /// cheap enough to be run periodically for monitoring. Only a sample of the terms of
+    /// each field is looked up in the term dictionary. A component of a segment that cannot
+    /// be read is reported as an issue of the segment, and the other components and segments
+    /// are still checked.
+    pub fn health_report(&self) -> crate::Result<IndexHealthReport> {
+        let segments = self
+            .searchable_segments()?
+            .iter()
+            .map(segment_health_report)
+            .collect();
+        Ok(IndexHealthReport { segments })
+    }
+
+    /// Rewrites in place the components written with an older format, and returns the paths
+    /// of the rewritten files.
+    ///
+    /// The segments of the last commit and of the commits kept in the commit history are
+    /// migrated. Each file is replaced atomically, with a footer stamped with the current
+    /// index format version: running the migrations again is a no-op. The readers opened
+    /// before keep reading the previous files.
+    ///
+    /// Migrations are never run implicitly, e.g. when opening the index: this has to be
+    /// called explicitly. It can take a while on a large index, and can be run in a
+    /// background thread. It fails with [`TantivyError::LockFailure`] if an `IndexWriter` is
+    /// open on the index.
+    pub fn migrate_components(
+        &self,
+        migrations: &[&dyn ComponentMigration],
+    ) -> crate::Result<Vec<PathBuf>> {
+        migrate_components(self, migrations)
+    }
+
     /// Returns the set of corrupted files
     pub fn validate_checksum(&self) -> crate::Result<HashSet<PathBuf>> {
         let managed_files = self.directory.list_managed_files();
@@ -8,7 +8,8 @@ use serde::{Deserialize, Serialize};
 
 use super::SegmentComponent;
 use crate::index::SegmentId;
-use crate::schema::Schema;
+use crate::postings::FieldTermStats;
+use crate::schema::{Field, Schema};
 use crate::store::Compressor;
 use crate::{Inventory, Opstamp, TrackedObject};
 
@@ -39,6 +40,8 @@ impl SegmentMetaInventory {
             max_doc,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
             deletes: None,
+            field_term_stats: Vec::new(),
+            routing_key: None,
         };
         SegmentMeta::from(self.inventory.track(inner))
     }
@@ -175,6 +178,72 @@ impl SegmentMeta {
         self.num_deleted_docs() > 0
     }
 
+    /// Returns the statistics about the terms of the given field,
+    /// computed when the segment was written.
+    ///
+    /// Returns `None` if the field is not indexed, has no terms in this segment,
+    /// or if the segment was written by a version of tantivy that did not
+    /// record these statistics.
+    pub fn field_term_stats(&self, field: Field) -> Option<&FieldTermStats> {
+        self.tracked
+            .field_term_stats
+            .iter()
+            .find(|field_term_stats| field_term_stats.field() == field)
+    }
+
+    /// Returns the average number of tokens of the given field per document of the segment,
+    /// as used by BM25.
+    ///
+    /// Deleted documents are taken into account, just like in the BM25 statistics.
+    /// Returns `None` if the [statistics about the terms](Self::field_term_stats) of the field,
+    /// or its number of tokens, are not available, or if the segment is empty.
+    pub fn average_field_length(&self, field: Field) -> Option<f32> {
+        let total_num_tokens = self.field_term_stats(field)?.total_num_tokens()?;
+        if self.max_doc() == 0 {
+            return None;
+        }
+        Some(total_num_tokens as f32 / self.max_doc() as f32)
+    }
+
+    /// Sets the statistics about the terms of the indexed fields.
+    pub(crate) fn with_field_term_stats(
+        self,
+        field_term_stats: Vec<FieldTermStats>,
+    ) -> SegmentMeta {
+        let tracked = self.tracked.map(move |inner_meta| InnerSegmentMeta {
+            segment_id: inner_meta.segment_id,
+            max_doc: inner_meta.max_doc,
+            deletes: inner_meta.deletes.clone(),
+            include_temp_doc_store: Arc::new(AtomicBool::new(true)),
+            field_term_stats,
+            routing_key: inner_meta.routing_key.clone(),
+        });
+        SegmentMeta { tracked }
+    }
+
+    /// Returns the value of the routing field shared by all of the documents of the segment.
+    ///
+    /// Returns `None` if the index has no routing field, or if the segment
+    /// may contain documents with different routing values.
+    ///
+    /// See [`IndexSettings::routing_field`].
+    pub fn routing_key(&self) -> Option<&str> {
+        self.tracked.routing_key.as_deref()
+    }
+
+    /// Sets the value of the routing field shared by all of the documents of the segment.
+    pub(crate) fn with_routing_key(self, routing_key: Option<String>) -> SegmentMeta {
+        let tracked = self.tracked.map(move |inner_meta| InnerSegmentMeta {
+            segment_id: inner_meta.segment_id,
+            max_doc: inner_meta.max_doc,
+            deletes: inner_meta.deletes.clone(),
+            include_temp_doc_store: Arc::new(AtomicBool::new(true)),
+            field_term_stats: inner_meta.field_term_stats.clone(),
+            routing_key,
+        });
+        SegmentMeta { tracked }
+    }
+
     /// Updates the max_doc value from the `SegmentMeta`.
     ///
     /// This method is only used when updating `max_doc` from 0
@@ -187,6 +256,8 @@ impl SegmentMeta {
             max_doc,
             deletes: None,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
+            field_term_stats: Vec::new(),
+            routing_key: None,
         });
         SegmentMeta { tracked }
     }
@@ -207,6 +278,8 @@ impl SegmentMeta {
             max_doc: inner_meta.max_doc,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
             deletes: Some(delete_meta),
+            field_term_stats: inner_meta.field_term_stats.clone(),
+            routing_key: inner_meta.routing_key.clone(),
         });
         SegmentMeta { tracked }
     }
@@ -222,6 +295,10 @@ struct InnerSegmentMeta {
     #[serde(skip)]
     #[serde(default = "default_temp_store")]
     pub(crate) include_temp_doc_store: Arc<AtomicBool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    field_term_stats: Vec<FieldTermStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    routing_key: Option<String>,
 }
 fn default_temp_store() -> Arc<AtomicBool> {
     Arc::new(AtomicBool::new(false))
@@ -239,6 +316,14 @@ fn return_true() -> bool {
     true
 }
 
+fn is_zero(val: &usize) -> bool {
+    *val == 0
+}
+
+fn is_false(val: &bool) -> bool {
+    !*val
+}
+
 fn is_true(val: &bool) -> bool {
     *val
 }
@@ -261,6 +346,73 @@ pub struct IndexSettings {
     #[serde(default = "default_docstore_blocksize")]
     /// The size of each block that will be compressed and written to disk
     pub docstore_blocksize: usize,
+    /// The number of past commits kept in the commit history of `meta.json`,
+    /// in addition to the last commit.
+    ///
+    /// The files of the segments referenced by the commit history are not
+    /// garbage collected, so that the index can be opened at, or rolled back to, any
+    /// of these commits.
+    /// (defaults: 0)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub commit_history_size: usize,
+    /// Name of the field used to partition the documents by tenant.
+    ///
+    /// When set, the documents are bucketed into segments according to the value of
+    /// this field, and segments holding different values are never merged together.
+    /// [`Searcher::search_with_routing_key`](crate::Searcher::search_with_routing_key)
+    /// then only searches the segments that may contain the given tenant.
+    ///
+    /// The routing field must be an indexed text field which is not tokenized,
+    /// like the [`STRING`](crate::schema::STRING) fields. It should have a moderate
+    /// number of distinct values: documents are only routed to
+    /// dedicated segments for a limited number of values at a time.
+    /// (defaults: None)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub routing_field: Option<String>,
+    /// If set to true, the add and delete operations of each commit are recorded in an
+    /// operation log stored alongside the index.
+    ///
+    /// The log can be read from a given opstamp with
+    /// [`Index::read_oplog`](crate::Index::read_oplog), for instance to feed a
+    /// change-data-capture pipeline or to catch up a replica.
+    /// Only the committed operations are recorded. Deletes by query and
+    /// [`IndexWriter::delete_all_documents`](crate::IndexWriter::delete_all_documents)
+    /// are not recorded.
+    /// (defaults: false)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub oplog: bool,
+    /// The number of commits kept in the operation log, if [`IndexSettings::oplog`]
+    /// is enabled.
+    ///
+    /// The files of the older commits are removed by the garbage collection following
+    /// each commit, and their operations cannot be read anymore.
+    /// (defaults: None, all of the commits are kept)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oplog_retained_commits: Option<usize>,
+    /// If set to true, each merge records the mapping between the documents of the
+    /// resulting segment and the documents of the merged segments.
+    ///
+    /// The mapping can be read with
+    /// [`Index::merge_doc_id_mapping`](crate::Index::merge_doc_id_mapping), for instance
+    /// to remap per-document data maintained outside of the index.
+    /// It is kept as long as the resulting segment is part of the index.
+    /// (defaults: false)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub merge_doc_id_mappings: bool,
+    /// The number of most frequent terms recorded per text field in the metadata of each
+    /// segment (see [`FieldTermStats::top_terms`]).
+    ///
+    /// The terms are stored in `meta.json`, which is read and rewritten on every commit:
+    /// this number is capped at 100.
+    /// (defaults: 0, no term is recorded)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub num_top_terms_per_field: usize,
 }
 
 /// Must be a function to be compatible with serde defaults
@@ -274,6 +426,12 @@ impl Default for IndexSettings {
             docstore_compression: Compressor::default(),
             docstore_blocksize: default_docstore_blocksize(),
             docstore_compress_dedicated_thread: true,
+            commit_history_size: 0,
+            routing_field: None,
+            oplog: false,
+            oplog_retained_commits: None,
+            merge_doc_id_mappings: false,
+            num_top_terms_per_field: 0,
         }
     }
 }
@@ -322,6 +480,25 @@ pub struct IndexMeta {
     /// This payload is entirely unused by tantivy.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<String>,
+    /// Past commits of the index, from the most recent to the oldest.
+    ///
+    /// See [`IndexSettings::commit_history_size`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commit_history: Vec<CommitMeta>,
+}
+
+/// Meta information about a past commit of the `Index`.
+///
+/// Past commits are kept in the commit history of the [`IndexMeta`].
+#[derive(Clone, Debug, Serialize)]
+pub struct CommitMeta {
+    /// List of `SegmentMeta` information associated with each segment of the commit.
+    pub segments: Vec<SegmentMeta>,
+    /// Opstamp associated with the commit.
+    pub opstamp: Opstamp,
+    /// Payload associated with the commit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -333,20 +510,45 @@ struct UntrackedIndexMeta {
     pub opstamp: Opstamp,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<String>,
+    #[serde(default)]
+    pub commit_history: Vec<UntrackedCommitMeta>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UntrackedCommitMeta {
+    pub segments: Vec<InnerSegmentMeta>,
+    pub opstamp: Opstamp,
+    #[serde(default)]
+    pub payload: Option<String>,
+}
+
+fn track_segments(
+    segments: Vec<InnerSegmentMeta>,
+    inventory: &SegmentMetaInventory,
+) -> Vec<SegmentMeta> {
+    segments
+        .into_iter()
+        .map(|inner_seg_meta| inner_seg_meta.track(inventory))
+        .collect::<Vec<SegmentMeta>>()
 }
 
 impl UntrackedIndexMeta {
     pub fn track(self, inventory: &SegmentMetaInventory) -> IndexMeta {
         IndexMeta {
             index_settings: self.index_settings,
-            segments: self
-                .segments
-                .into_iter()
-                .map(|inner_seg_meta| inner_seg_meta.track(inventory))
-                .collect::<Vec<SegmentMeta>>(),
+            segments: track_segments(self.segments, inventory),
             schema: self.schema,
             opstamp: self.opstamp,
             payload: self.payload,
+            commit_history: self
+                .commit_history
+                .into_iter()
+                .map(|commit_meta| CommitMeta {
+                    segments: track_segments(commit_meta.segments, inventory),
+                    opstamp: commit_meta.opstamp,
+                    payload: commit_meta.payload,
+                })
+                .collect(),
         }
     }
 }
@@ -364,7 +566,65 @@ impl IndexMeta {
             schema,
             opstamp: 0u64,
             payload: None,
+            commit_history: Vec::new(),
+        }
+    }
+
+    /// Returns the opstamps of the commits that can be checked out,
+    /// from the most recent to the oldest.
+    ///
+    /// The first opstamp is the opstamp of the last commit.
+    pub fn commit_opstamps(&self) -> Vec<Opstamp> {
+        std::iter::once(self.opstamp)
+            .chain(
+                self.commit_history
+                    .iter()
+                    .map(|commit_meta| commit_meta.opstamp),
+            )
+            .collect()
+    }
+
+    /// Returns the `IndexMeta` as it was right after the commit with the given opstamp,
+    /// or `None` if this commit is not part of the commit history.
+    pub(crate) fn checkout(&self, opstamp: Opstamp) -> Option<IndexMeta> {
+        if opstamp == self.opstamp {
+            return Some(self.clone());
+        }
+        let commit_ord = self
+            .commit_history
+            .iter()
+            .position(|commit_meta| commit_meta.opstamp == opstamp)?;
+        let commit_meta = &self.commit_history[commit_ord];
+        Some(IndexMeta {
+            index_settings: self.index_settings.clone(),
+            segments: commit_meta.segments.clone(),
+            schema: self.schema.clone(),
+            opstamp: commit_meta.opstamp,
+            payload: commit_meta.payload.clone(),
+            commit_history: self.commit_history[commit_ord + 1..].to_vec(),
+        })
+    }
+
+    /// Returns the commit history to record in the `IndexMeta` following this one.
+    ///
+    /// If `next_opstamp` is the opstamp of this `IndexMeta` (e.g. after a merge), the commit
+    /// history is left untouched. Otherwise, this `IndexMeta` is pushed to the history.
+    pub(crate) fn next_commit_history(
+        &self,
+        next_opstamp: Opstamp,
+        commit_history_size: usize,
+    ) -> Vec<CommitMeta> {
+        let mut commit_history = Vec::with_capacity(commit_history_size);
+        if next_opstamp != self.opstamp {
+            commit_history.push(CommitMeta {
+                segments: self.segments.clone(),
+                opstamp: self.opstamp,
+                payload: self.payload.clone(),
+            });
         }
+        commit_history.extend(self.commit_history.iter().cloned());
+        commit_history.truncate(commit_history_size);
+        commit_history
     }
 
     pub(crate) fn deserialize(
@@ -411,6 +671,7 @@ mod tests {
             schema,
             opstamp: 0u64,
             payload: None,
+            commit_history: Vec::new(),
         };
         let json = serde_json::ser::to_string(&index_metas).expect("serialization failed");
         assert_eq!(
@@ -439,11 +700,18 @@ mod tests {
                 }),
                 docstore_blocksize: 1_000_000,
                 docstore_compress_dedicated_thread: true,
+                commit_history_size: 0,
+                routing_field: None,
+                oplog: false,
+                oplog_retained_commits: None,
+                merge_doc_id_mappings: false,
+                num_top_terms_per_field: 0,
             },
             segments: Vec::new(),
             schema,
             opstamp: 0u64,
             payload: None,
+            commit_history: Vec::new(),
         };
         let json = serde_json::ser::to_string(&index_metas).expect("serialization failed");
         assert_eq!(
@@ -502,7 +770,13 @@ mod tests {
             IndexSettings {
                 docstore_compression: Compressor::default(),
                 docstore_compress_dedicated_thread: true,
-                docstore_blocksize: 16_384
+                docstore_blocksize: 16_384,
+                commit_history_size: 0,
+                routing_field: None,
+                oplog: false,
+                oplog_retained_commits: None,
+                merge_doc_id_mappings: false,
+                num_top_terms_per_field: 0,
             }
         );
         {
@@ -6,7 +6,7 @@ use fnv::FnvHashSet;
 
 use crate::directory::FileSlice;
 use crate::positions::PositionReader;
-use crate::postings::{BlockSegmentPostings, SegmentPostings, TermInfo};
+use crate::postings::{BlockSegmentPostings, SegmentPostings, TermInfo, TopImpactsReader};
 use crate::schema::{IndexRecordOption, Term, Type};
 use crate::termdict::TermDictionary;
 
@@ -25,7 +25,11 @@ use crate::termdict::TermDictionary;
 pub struct InvertedIndexReader {
     termdict: TermDictionary,
     postings_file_slice: FileSlice,
-    positions_file_slice: FileSlice,
+    // `None` if positions are not available, e.g. because the segment was opened
+    // without its positions component.
+    positions_file_slice: Option<FileSlice>,
+    // `None` if no term of the field has a top impacts list.
+    top_impacts_opt: Option<TopImpactsReader>,
     record_option: IndexRecordOption,
     total_num_tokens: u64,
 }
@@ -35,7 +39,8 @@ impl InvertedIndexReader {
     pub(crate) fn new(
         termdict: TermDictionary,
         postings_file_slice: FileSlice,
-        positions_file_slice: FileSlice,
+        positions_file_slice: Option<FileSlice>,
+        top_impacts_file_slice: Option<FileSlice>,
         record_option: IndexRecordOption,
     ) -> io::Result<InvertedIndexReader> {
         let (total_num_tokens_slice, postings_body) = postings_file_slice.split(8);
@@ -44,6 +49,9 @@ impl InvertedIndexReader {
             termdict,
             postings_file_slice: postings_body,
             positions_file_slice,
+            top_impacts_opt: top_impacts_file_slice
+                .map(TopImpactsReader::open)
+                .transpose()?,
             record_option,
             total_num_tokens,
         })
@@ -55,7 +63,8 @@ impl InvertedIndexReader {
         InvertedIndexReader {
             termdict: TermDictionary::empty(),
             postings_file_slice: FileSlice::empty(),
-            positions_file_slice: FileSlice::empty(),
+            positions_file_slice: None,
+            top_impacts_opt: None,
             record_option,
             total_num_tokens: 0u64,
         }
@@ -162,16 +171,17 @@ impl InvertedIndexReader {
         let option = option.downgrade(self.record_option);
 
         let block_postings = self.read_block_postings_from_terminfo(term_info, option)?;
-        let position_reader = {
-            if option.has_positions() {
-                let positions_data = self
-                    .positions_file_slice
-                    .read_bytes_slice(term_info.positions_range.clone())?;
+        // The positions range is empty if the positions of the term were pruned.
+        let position_reader = match &self.positions_file_slice {
+            Some(positions_file_slice)
+                if option.has_positions() && !term_info.positions_range.is_empty() =>
+            {
+                let positions_data =
+                    positions_file_slice.read_bytes_slice(term_info.positions_range.clone())?;
                 let position_reader = PositionReader::open(positions_data)?;
                 Some(position_reader)
-            } else {
-                None
             }
+            _ => None,
         };
         Ok(SegmentPostings::from_block_postings(
             block_postings,
@@ -179,6 +189,25 @@ impl InvertedIndexReader {
         ))
     }
 
+    /// Returns the postings of the top impacts list of a term, if it has one.
+    ///
+    /// The list holds the documents contributing the most to the score of the term,
+    /// with their term frequencies but without positions. See
+    /// [`TextFieldIndexing::set_top_impacts_per_term`](crate::schema::TextFieldIndexing::set_top_impacts_per_term).
+    pub fn read_top_impacts_postings_from_terminfo(
+        &self,
+        term_info: &TermInfo,
+        option: IndexRecordOption,
+    ) -> io::Result<Option<SegmentPostings>> {
+        let Some(top_impacts) = &self.top_impacts_opt else {
+            return Ok(None);
+        };
+        top_impacts.read_postings(
+            term_info.postings_range.start as u64,
+            option.downgrade(self.record_option),
+        )
+    }
+
     /// Returns the total number of tokens recorded for all documents
     /// (including deleted documents).
     pub fn total_num_tokens(&self) -> u64 {
@@ -275,13 +304,15 @@ impl InvertedIndexReader {
             let postings = self
                 .postings_file_slice
                 .read_bytes_slice_async(term_info.postings_range.clone());
-            if with_positions {
-                let positions = self
-                    .positions_file_slice
-                    .read_bytes_slice_async(term_info.positions_range.clone());
-                futures_util::future::try_join(postings, positions).await?;
-            } else {
-                postings.await?;
+            match &self.positions_file_slice {
+                Some(positions_file_slice) if with_positions => {
+                    let positions = positions_file_slice
+                        .read_bytes_slice_async(term_info.positions_range.clone());
+                    futures_util::future::try_join(postings, positions).await?;
+                }
+                _ => {
+                    postings.await?;
+                }
             }
             Ok(true)
         } else {
@@ -315,13 +346,14 @@ impl InvertedIndexReader {
         let postings = self
             .postings_file_slice
             .read_bytes_slice_async(postings_range);
-        if with_positions {
-            let positions = self
-                .positions_file_slice
-                .read_bytes_slice_async(positions_range);
-            futures_util::future::try_join(postings, positions).await?;
-        } else {
-            postings.await?;
+        match &self.positions_file_slice {
+            Some(positions_file_slice) if with_positions => {
+                let positions = positions_file_slice.read_bytes_slice_async(positions_range);
+                futures_util::future::try_join(postings, positions).await?;
+            }
+            _ => {
+                postings.await?;
+            }
         }
         Ok(true)
     }
@@ -333,8 +365,10 @@ impl InvertedIndexReader {
     /// [`Self::warm_postings`] instead.
     pub async fn warm_postings_full(&self, with_positions: bool) -> io::Result<()> {
         self.postings_file_slice.read_bytes_async().await?;
-        if with_positions {
-            self.positions_file_slice.read_bytes_async().await?;
+        if let Some(positions_file_slice) = &self.positions_file_slice {
+            if with_positions {
+                positions_file_slice.read_bytes_async().await?;
+            }
         }
         Ok(())
     }
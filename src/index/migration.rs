@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::directory::{Directory, OwnedBytes, INDEX_WRITER_LOCK};
+use crate::index::{Index, Segment, SegmentComponent};
+
+/// A rewrite of one component of the segments of an index, upgrading it from an older format.
+///
+/// Unlike a merge, a migration only rewrites a single file per segment: the other components
+/// of the segment, and its id, are left untouched. The migrations are run with
+/// [`Index::migrate_components`].
+///
+/// Tantivy does not ship any migration yet: the trait is the extension point for the next
+/// change of the format of a component.
+pub trait ComponentMigration: Send + Sync {
+    /// The component rewritten by the migration.
+    fn component(&self) -> SegmentComponent;
+
+    /// Returns true if the component must be rewritten when it was written with the given
+    /// index format version.
+    fn applies_to(&self, index_format_version: u32) -> bool;
+
+    /// Rewrites the component of a segment, given its current content.
+    ///
+    /// Returns the new content of the component. The footer of the file is handled by the
+    /// caller, and is not part of `data` nor of the returned content.
+    fn migrate(&self, segment: &Segment, data: OwnedBytes) -> crate::Result<Vec<u8>>;
+}
+
+/// Runs the migrations on each of the segments of the last commit and of the commit history.
+///
+/// See [`Index::migrate_components`].
+pub(crate) fn migrate_components(
+    index: &Index,
+    migrations: &[&dyn ComponentMigration],
+) -> crate::Result<Vec<PathBuf>> {
+    let directory = index.directory();
+    // No merge can delete a segment, nor can a commit change the segments, while the
+    // components are rewritten.
+    let _index_writer_lock = directory.acquire_lock(&INDEX_WRITER_LOCK)?;
+    let index_meta = index.load_metas()?;
+    let segment_metas = index_meta.segments.iter().chain(
+        index_meta
+            .commit_history
+            .iter()
+            .flat_map(|commit_meta| commit_meta.segments.iter()),
+    );
+    let mut migrated_segment_ids = HashSet::new();
+    let mut migrated_files = Vec::new();
+    for segment_meta in segment_metas {
+        // The segments are shared by the commits of the history.
+        if !migrated_segment_ids.insert(segment_meta.id()) {
+            continue;
+        }
+        let segment = index.segment(segment_meta.clone());
+        for migration in migrations {
+            let path = segment.relative_path(migration.component());
+            if !directory.exists(&path)? {
+                continue;
+            }
+            let (footer, data) = directory.read_footer(&path)?;
+            if !migration.applies_to(footer.version.index_format_version) {
+                continue;
+            }
+            let migrated_data = migration.migrate(&segment, data.read_bytes()?)?;
+            directory.atomic_rewrite(&path, &migrated_data)?;
+            info!("Migrated {:?}", path);
+            migrated_files.push(path);
+        }
+    }
+    Ok(migrated_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use super::ComponentMigration;
+    use crate::collector::TopDocs;
+    use crate::directory::{
+        CompositeFile, CompositeWrite, Directory, FileSlice, OwnedBytes, RamDirectory,
+    };
+    use crate::fieldnorm::FieldNormReader;
+    use crate::index::{IndexSettings, Segment, SegmentComponent};
+    use crate::query::QueryParser;
+    use crate::schema::{Schema, TEXT};
+    use crate::{doc, Index, IndexWriter, TantivyError, INDEX_FORMAT_VERSION};
+
+    const OLD_INDEX_FORMAT_VERSION: u32 = INDEX_FORMAT_VERSION - 1;
+
+    /// Rewrites the fieldnorms of each field of a composite file.
+    fn map_fieldnorms(
+        segment: &Segment,
+        data: OwnedBytes,
+        map_fieldnorms: impl Fn(&[u8]) -> Vec<u8>,
+    ) -> crate::Result<Vec<u8>> {
+        let composite_file = CompositeFile::open(&FileSlice::new(Arc::new(data)))?;
+        let mut buffer = Vec::new();
+        let mut composite_write = CompositeWrite::wrap(&mut buffer);
+        for (field, _) in segment.schema().fields() {
+            let Some(fieldnorms_file) = composite_file.open_read(field) else {
+                continue;
+            };
+            let write = composite_write.for_field(field);
+            write.write_all(&map_fieldnorms(fieldnorms_file.read_bytes()?.as_slice()))?;
+            write.flush()?;
+        }
+        composite_write.close()?;
+        Ok(buffer)
+    }
+
+    /// Encodes the fieldnorms on one byte, from a format where they were stored as
+    /// little-endian `u32`s.
+    struct CompactFieldNormsMigration;
+
+    impl ComponentMigration for CompactFieldNormsMigration {
+        fn component(&self) -> SegmentComponent {
+            SegmentComponent::FieldNorms
+        }
+
+        fn applies_to(&self, index_format_version: u32) -> bool {
+            index_format_version <= OLD_INDEX_FORMAT_VERSION
+        }
+
+        fn migrate(&self, segment: &Segment, data: OwnedBytes) -> crate::Result<Vec<u8>> {
+            map_fieldnorms(segment, data, |fieldnorms| {
+                fieldnorms
+                    .chunks_exact(4)
+                    .map(|bytes| {
+                        let fieldnorm = u32::from_le_bytes(bytes.try_into().unwrap());
+                        FieldNormReader::fieldnorm_to_id(fieldnorm)
+                    })
+                    .collect()
+            })
+        }
+    }
+
+    #[test]
+    fn test_migrate_components() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let ram_directory = RamDirectory::create();
+        let index = Index::create(
+            ram_directory.clone(),
+            schema_builder.build(),
+            IndexSettings {
+                commit_history_size: 1,
+                ..IndexSettings::default()
+            },
+        )?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "the old man and the sea"))?;
+        index_writer.add_document(doc!(title => "the sea"))?;
+        index_writer.commit()?;
+        // The first segment is only part of the commit history after this commit.
+        index_writer.delete_all_documents()?;
+        index_writer.add_document(doc!(title => "the sea"))?;
+        index_writer.commit()?;
+        // Migrations cannot run while an index writer is open.
+        assert!(matches!(
+            index.migrate_components(&[&CompactFieldNormsMigration]),
+            Err(TantivyError::LockFailure(..))
+        ));
+        drop(index_writer);
+
+        let search = |index: &Index| -> crate::Result<Vec<f32>> {
+            let searcher = index.reader()?.searcher();
+            let query = QueryParser::for_index(index, vec![title]).parse_query("sea")?;
+            let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+            Ok(top_docs.into_iter().map(|(score, _)| score).collect())
+        };
+        let old_opstamp = index.load_metas()?.commit_history[0].opstamp;
+        let old_commit = Index::open_at(ram_directory.clone(), old_opstamp)?;
+        let scores = search(&index)?;
+        let old_commit_scores = search(&old_commit)?;
+
+        // Rewrites the fieldnorms of every segment in the older format.
+        let mut segments = index.searchable_segments()?;
+        segments.extend(old_commit.searchable_segments()?);
+        assert_eq!(segments.len(), 2);
+        let mut expected_files = Vec::new();
+        for segment in &segments {
+            let path = segment.relative_path(SegmentComponent::FieldNorms);
+            let (footer, data) = index.directory().read_footer(&path)?;
+            let data = data.read_bytes()?;
+            let mut old_file = map_fieldnorms(segment, data.clone(), |fieldnorm_ids| {
+                fieldnorm_ids
+                    .iter()
+                    .flat_map(|&fieldnorm_id| {
+                        FieldNormReader::id_to_fieldnorm(fieldnorm_id).to_le_bytes()
+                    })
+                    .collect()
+            })?;
+            let mut old_footer = footer.clone();
+            old_footer.version.index_format_version = OLD_INDEX_FORMAT_VERSION;
+            old_footer.append_footer(&mut old_file)?;
+            ram_directory.atomic_write(&path, &old_file)?;
+            expected_files.push((path, footer, data));
+        }
+
+        let migrated_files = index.migrate_components(&[&CompactFieldNormsMigration])?;
+        assert_eq!(
+            migrated_files,
+            expected_files
+                .iter()
+                .map(|(path, _, _)| path.clone())
+                .collect::<Vec<_>>()
+        );
+        for (path, footer, data) in &expected_files {
+            let (migrated_footer, migrated_data) = index.directory().read_footer(path)?;
+            assert_eq!(&migrated_footer, footer);
+            assert_eq!(migrated_data.read_bytes()?.as_slice(), data.as_slice());
+        }
+        assert_eq!(search(&index)?, scores);
+        assert_eq!(search(&old_commit)?, old_commit_scores);
+
+        // The migration is only applied once.
+        assert!(index
+            .migrate_components(&[&CompactFieldNormsMigration])?
+            .is_empty());
+        assert!(index.validate_checksum()?.is_empty());
+        Ok(())
+    }
+}
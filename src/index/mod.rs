@@ -2,18 +2,28 @@
 //!
 //! It contains `Index` and `Segment`, where a `Index` consists of one or more `Segment`s.
 
+mod health_report;
+mod in_memory_index;
 mod index;
 mod index_meta;
 mod inverted_index_reader;
+mod migration;
+mod replica;
 mod segment;
 mod segment_component;
 mod segment_id;
 mod segment_reader;
 
+pub use self::health_report::{HealthIssue, IndexHealthReport, SegmentHealthReport};
+pub use self::in_memory_index::{InMemoryIndex, InMemoryIndexWriter};
 pub use self::index::{Index, IndexBuilder};
 pub(crate) use self::index_meta::SegmentMetaInventory;
-pub use self::index_meta::{IndexMeta, IndexSettings, Order, SegmentMeta};
+pub use self::index_meta::{CommitMeta, IndexMeta, IndexSettings, Order, SegmentMeta};
 pub use self::inverted_index_reader::InvertedIndexReader;
+pub use self::migration::ComponentMigration;
+pub use self::replica::{
+    CommitManifest, ManifestFile, ReplicaSyncResult, ReplicaSyncer, ReplicaTransport,
+};
 pub use self::segment::Segment;
 pub use self::segment_component::SegmentComponent;
 pub use self::segment_id::SegmentId;
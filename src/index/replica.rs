@@ -0,0 +1,559 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+use common::TerminatingWrite;
+use crc32fast::Hasher;
+use serde::{Deserialize, Serialize};
+
+use crate::core::META_FILEPATH;
+use crate::directory::error::DeleteError;
+use crate::directory::{Directory, FileSlice, ManagedDirectory};
+use crate::error::DataCorruption;
+use crate::index::{Index, IndexMeta, SegmentMetaInventory};
+use crate::Opstamp;
+
+/// A file of a commit, as listed in a [`CommitManifest`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Path of the file, relative to the directory of the index.
+    pub path: PathBuf,
+    /// Length of the file, without its footer.
+    pub len: u64,
+    /// CRC32 checksum of the file, without its footer.
+    pub checksum: u32,
+}
+
+/// The description of a commit of an index: its `meta.json` file, and the list of the files
+/// of its segments with their checksums.
+///
+/// The files of the segments of the commit history of the index are listed as well.
+///
+/// A manifest is serializable, so that it can be sent from a primary index to its replicas.
+/// See [`ReplicaSyncer`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitManifest {
+    /// Opstamp of the commit.
+    pub opstamp: Opstamp,
+    /// Content of the `meta.json` file of the commit.
+    pub meta_json: String,
+    /// Files of the commit, sorted by path.
+    pub files: Vec<ManifestFile>,
+}
+
+impl CommitManifest {
+    /// Builds the manifest of the last commit of an index.
+    pub fn for_index(index: &Index) -> crate::Result<CommitManifest> {
+        let directory = index.directory();
+        let meta_data = directory.atomic_read(&META_FILEPATH)?;
+        let meta_json = String::from_utf8(meta_data).map_err(|_| {
+            DataCorruption::new(
+                META_FILEPATH.to_path_buf(),
+                "Meta file does not contain valid utf8 file.".to_string(),
+            )
+        })?;
+        let metas = deserialize_meta(&meta_json)?;
+        let paths = segment_files(&metas);
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            // Not all of the components of a segment are necessarily written.
+            if !directory.exists(&path)? {
+                continue;
+            }
+            let (footer, data) = directory.read_footer(&path)?;
+            files.push(ManifestFile {
+                path,
+                len: data.len() as u64,
+                checksum: footer.crc(),
+            });
+        }
+        files.sort_by(|left, right| left.path.cmp(&right.path));
+        Ok(CommitManifest {
+            opstamp: metas.opstamp,
+            meta_json,
+            files,
+        })
+    }
+}
+
+fn deserialize_meta(meta_json: &str) -> crate::Result<IndexMeta> {
+    let inventory = SegmentMetaInventory::default();
+    let metas = IndexMeta::deserialize(meta_json, &inventory).map_err(|err| {
+        DataCorruption::new(
+            META_FILEPATH.to_path_buf(),
+            format!("Meta file cannot be deserialized. {err:?}."),
+        )
+    })?;
+    Ok(metas)
+}
+
+/// Returns the paths of the files of the segments of a commit and of its commit history.
+fn segment_files(metas: &IndexMeta) -> HashSet<PathBuf> {
+    metas
+        .segments
+        .iter()
+        .chain(
+            metas
+                .commit_history
+                .iter()
+                .flat_map(|commit_meta| commit_meta.segments.iter()),
+        )
+        .flat_map(|segment_meta| segment_meta.list_files())
+        .collect()
+}
+
+/// Computes the CRC32 checksum of a file slice, reading it chunk by chunk.
+fn compute_checksum(data: &FileSlice) -> io::Result<u32> {
+    let mut hasher = Hasher::new();
+    for chunk in data.stream_file_chunks() {
+        hasher.update(chunk?.as_slice());
+    }
+    Ok(hasher.finalize())
+}
+
+/// A writer computing the length and the CRC32 checksum of the bytes written through it.
+struct ChecksumWriter<W> {
+    underlying: W,
+    hasher: Hasher,
+    num_bytes: u64,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    fn new(underlying: W) -> ChecksumWriter<W> {
+        ChecksumWriter {
+            underlying,
+            hasher: Hasher::new(),
+            num_bytes: 0,
+        }
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written_len = self.underlying.write(buf)?;
+        self.hasher.update(&buf[..written_len]);
+        self.num_bytes += written_len as u64;
+        Ok(written_len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.underlying.flush()
+    }
+}
+
+/// Returns true if the path is a single file name, relative to the directory of the index.
+fn is_file_name(path: &Path) -> bool {
+    let mut components = path.components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+/// Transport used by a [`ReplicaSyncer`] to pull the commits of a primary index.
+///
+/// An [`Index`] is itself a transport, pulling the commits from the index, e.g. for a
+/// replica living in the same process. A remote primary typically serves the
+/// [`CommitManifest::for_index`] of its index, and the content of its files as returned by
+/// [`Directory::open_read`] on [`Index::directory`].
+pub trait ReplicaTransport {
+    /// Returns the manifest of the last commit of the primary index.
+    fn fetch_manifest(&self) -> crate::Result<CommitManifest>;
+
+    /// Writes the content of a file of the primary index, without its footer, to `output`.
+    ///
+    /// Files can be as large as a segment: the content should be streamed to `output` rather
+    /// than buffered in memory.
+    fn fetch_file(&self, path: &Path, output: &mut dyn Write) -> crate::Result<()>;
+}
+
+impl ReplicaTransport for Index {
+    fn fetch_manifest(&self) -> crate::Result<CommitManifest> {
+        CommitManifest::for_index(self)
+    }
+
+    fn fetch_file(&self, path: &Path, output: &mut dyn Write) -> crate::Result<()> {
+        let data = self.directory().open_read(path)?;
+        for chunk in data.stream_file_chunks() {
+            output.write_all(chunk?.as_slice())?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of a [`ReplicaSyncer::sync`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplicaSyncResult {
+    /// Opstamp of the commit installed on the replica.
+    pub opstamp: Opstamp,
+    /// Number of files fetched from the primary index.
+    pub num_fetched_files: usize,
+    /// Number of bytes fetched from the primary index.
+    pub num_fetched_bytes: u64,
+}
+
+/// Pulls the commits of a primary index into a read replica.
+///
+/// Each [`ReplicaSyncer::sync`] compares the [`CommitManifest`] of the primary index with the
+/// files of the replica, and only fetches the files that are missing or whose length or
+/// checksum differ. Once all of the files have been fetched and their checksums verified,
+/// the `meta.json` file of the commit is written atomically: the readers of the replica see
+/// either the previous commit or the new one. The files that are not part of the new commit
+/// are then garbage collected.
+///
+/// The replica must not be written to by an [`IndexWriter`](crate::IndexWriter).
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::directory::{ManagedDirectory, RamDirectory};
+/// use tantivy::query::AllQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index, IndexWriter, ReplicaSyncer};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let primary = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer: IndexWriter = primary.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "The Old Man and the Sea"))?;
+/// index_writer.commit()?;
+///
+/// let replica_directory = RamDirectory::create();
+/// let syncer = ReplicaSyncer::new(
+///     ManagedDirectory::wrap(Box::new(replica_directory.clone()))?,
+///     primary.clone(),
+/// );
+/// syncer.sync()?;
+/// let replica = Index::open(replica_directory)?;
+/// assert_eq!(replica.reader()?.searcher().search(&AllQuery, &Count)?, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplicaSyncer<T> {
+    directory: ManagedDirectory,
+    transport: T,
+}
+
+impl<T: ReplicaTransport> ReplicaSyncer<T> {
+    /// Creates a syncer pulling the commits of a primary index into `directory`, through
+    /// `transport`.
+    ///
+    /// For an existing replica, `directory` is typically the [`Index::directory`] of the
+    /// replica.
+    pub fn new(directory: ManagedDirectory, transport: T) -> ReplicaSyncer<T> {
+        ReplicaSyncer {
+            directory,
+            transport,
+        }
+    }
+
+    /// Returns true if the replica holds the file, with the expected length and checksum.
+    ///
+    /// The checksum is computed over the content of the file: the checksum recorded in its
+    /// footer is not trusted.
+    fn has_file(&self, file: &ManifestFile) -> crate::Result<bool> {
+        if !self.directory.exists(&file.path)? {
+            return Ok(false);
+        }
+        let (footer, data) = self.directory.read_footer(&file.path)?;
+        if data.len() as u64 != file.len || footer.crc() != file.checksum {
+            return Ok(false);
+        }
+        Ok(compute_checksum(&data)? == file.checksum)
+    }
+
+    /// Streams a file from the transport into the replica, verifying its length and checksum
+    /// over the received bytes.
+    ///
+    /// If the verification fails, the partially written file is deleted.
+    fn fetch_file(&self, file: &ManifestFile) -> crate::Result<()> {
+        // The replica may hold a file with the same path but a different content.
+        self.delete_file(&file.path)?;
+        let mut write = ChecksumWriter::new(self.directory.open_write(&file.path)?);
+        let fetch_result = self.transport.fetch_file(&file.path, &mut write);
+        let is_valid =
+            write.num_bytes == file.len && write.hasher.clone().finalize() == file.checksum;
+        if fetch_result.is_err() || !is_valid {
+            write.flush()?;
+            drop(write);
+            self.delete_file(&file.path)?;
+            fetch_result?;
+            return Err(DataCorruption::new(
+                file.path.clone(),
+                "The fetched file does not match the length or the checksum of the manifest."
+                    .to_string(),
+            )
+            .into());
+        }
+        write.underlying.terminate()?;
+        Ok(())
+    }
+
+    fn delete_file(&self, path: &Path) -> crate::Result<()> {
+        match self.directory.delete(path) {
+            Ok(()) | Err(DeleteError::FileDoesNotExist(_)) => Ok(()),
+            Err(DeleteError::IoError { io_error, .. }) => {
+                Err(io::Error::new(io_error.kind(), io_error.to_string()).into())
+            }
+        }
+    }
+
+    /// Pulls the last commit of the primary index, and installs it on the replica.
+    ///
+    /// If fetching a file fails, or if its checksum does not match the manifest, an error is
+    /// returned and the replica remains on its previous commit. The files fetched so far are
+    /// kept, and are not fetched again by the next sync.
+    pub fn sync(&self) -> crate::Result<ReplicaSyncResult> {
+        let manifest = self.transport.fetch_manifest()?;
+        // The manifest may come from a remote primary: its paths must not point outside of
+        // the directory of the replica, nor to files which are not part of a segment.
+        let expected_files = segment_files(&deserialize_meta(&manifest.meta_json)?);
+        for file in &manifest.files {
+            if !is_file_name(&file.path) || !expected_files.contains(&file.path) {
+                return Err(DataCorruption::new(
+                    file.path.clone(),
+                    "The manifest lists a file which is not a file of its segments.".to_string(),
+                )
+                .into());
+            }
+        }
+        let mut sync_result = ReplicaSyncResult {
+            opstamp: manifest.opstamp,
+            ..Default::default()
+        };
+        for file in &manifest.files {
+            if self.has_file(file)? {
+                continue;
+            }
+            self.fetch_file(file)?;
+            sync_result.num_fetched_files += 1;
+            sync_result.num_fetched_bytes += file.len;
+        }
+        self.directory
+            .atomic_write(&META_FILEPATH, manifest.meta_json.as_bytes())?;
+        let living_files: HashSet<PathBuf> = manifest
+            .files
+            .iter()
+            .map(|file| file.path.clone())
+            .chain(std::iter::once(META_FILEPATH.to_path_buf()))
+            .collect();
+        self.directory
+            .clone()
+            .garbage_collect(move || living_files)?;
+        Ok(sync_result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::{CommitManifest, ReplicaSyncer, ReplicaTransport};
+    use crate::collector::Count;
+    use crate::directory::{Directory, ManagedDirectory, RamDirectory};
+    use crate::query::TermQuery;
+    use crate::schema::{IndexRecordOption, Schema, STRING};
+    use crate::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyError, Term};
+
+    #[test]
+    fn test_replica_sync() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let primary = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = primary.writer_for_tests()?;
+        index_writer.add_document(doc!(tag => "a"))?;
+        index_writer.add_document(doc!(tag => "b"))?;
+        index_writer.commit()?;
+
+        let replica_directory = RamDirectory::create();
+        let syncer = ReplicaSyncer::new(
+            ManagedDirectory::wrap(Box::new(replica_directory.clone()))?,
+            primary.clone(),
+        );
+        let sync_result = syncer.sync()?;
+        assert!(sync_result.num_fetched_files > 0);
+        let replica = Index::open(replica_directory.clone())?;
+        let replica_reader: IndexReader = replica
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let count = |tag_value: &str| -> crate::Result<usize> {
+            let query = TermQuery::new(
+                Term::from_field_text(tag, tag_value),
+                IndexRecordOption::Basic,
+            );
+            replica_reader.searcher().search(&query, &Count)
+        };
+        assert_eq!(count("a")?, 1);
+
+        // Syncing again does not fetch anything.
+        let sync_result = syncer.sync()?;
+        assert_eq!(sync_result.num_fetched_files, 0);
+        assert_eq!(sync_result.num_fetched_bytes, 0);
+
+        // Only the files of the new segment are fetched.
+        index_writer.add_document(doc!(tag => "a"))?;
+        let opstamp = index_writer.commit()?;
+        let previous_files = CommitManifest::for_index(&primary)?.files.len();
+        let sync_result = syncer.sync()?;
+        assert_eq!(sync_result.opstamp, opstamp);
+        assert!(sync_result.num_fetched_files < previous_files);
+        replica_reader.reload()?;
+        assert_eq!(count("a")?, 2);
+
+        // The files of the merged segments are garbage collected.
+        let merged_files = CommitManifest::for_index(&primary)?.files;
+        let segment_ids = primary.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+        index_writer.wait_merging_threads()?;
+        syncer.sync()?;
+        replica_reader.reload()?;
+        assert_eq!(count("a")?, 2);
+        assert_eq!(count("b")?, 1);
+        for merged_file in merged_files {
+            assert!(!replica_directory.exists(&merged_file.path)?);
+        }
+        let manifest = CommitManifest::for_index(&Index::open(replica_directory)?)?;
+        assert_eq!(manifest, CommitManifest::for_index(&primary)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replica_sync_refetches_corrupted_files() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let primary = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = primary.writer_for_tests()?;
+        index_writer.add_document(doc!(tag => "a"))?;
+        index_writer.commit()?;
+
+        let replica_directory = RamDirectory::create();
+        let syncer = ReplicaSyncer::new(
+            ManagedDirectory::wrap(Box::new(replica_directory.clone()))?,
+            primary.clone(),
+        );
+        syncer.sync()?;
+
+        // Corrupt the content of a file of the replica, leaving its footer untouched.
+        let manifest = CommitManifest::for_index(&primary)?;
+        let corrupted_file = &manifest.files[0];
+        let mut data = replica_directory
+            .open_read(&corrupted_file.path)?
+            .read_bytes()?
+            .as_slice()
+            .to_vec();
+        data[0] ^= 1;
+        replica_directory.atomic_write(&corrupted_file.path, &data)?;
+
+        let sync_result = syncer.sync()?;
+        assert_eq!(sync_result.num_fetched_files, 1);
+        assert_eq!(sync_result.num_fetched_bytes, corrupted_file.len);
+        let manifest = CommitManifest::for_index(&Index::open(replica_directory)?)?;
+        assert_eq!(manifest, CommitManifest::for_index(&primary)?);
+        Ok(())
+    }
+
+    struct CorruptingTransport {
+        primary: Index,
+        corrupt: AtomicBool,
+    }
+
+    impl ReplicaTransport for CorruptingTransport {
+        fn fetch_manifest(&self) -> crate::Result<CommitManifest> {
+            self.primary.fetch_manifest()
+        }
+
+        fn fetch_file(&self, path: &Path, output: &mut dyn Write) -> crate::Result<()> {
+            let mut data = Vec::new();
+            self.primary.fetch_file(path, &mut data)?;
+            if self.corrupt.load(Ordering::Relaxed) {
+                data[0] ^= 1;
+            }
+            output.write_all(&data)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_replica_sync_checksum_mismatch() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let primary = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = primary.writer_for_tests()?;
+        index_writer.add_document(doc!(tag => "a"))?;
+        index_writer.commit()?;
+
+        let replica_directory = RamDirectory::create();
+        let syncer = ReplicaSyncer::new(
+            ManagedDirectory::wrap(Box::new(replica_directory.clone()))?,
+            CorruptingTransport {
+                primary,
+                corrupt: AtomicBool::new(true),
+            },
+        );
+        assert!(matches!(
+            syncer.sync(),
+            Err(TantivyError::DataCorruption(_))
+        ));
+        // The commit is not installed.
+        assert!(!replica_directory.exists(Path::new("meta.json"))?);
+
+        syncer.transport.corrupt.store(false, Ordering::Relaxed);
+        syncer.sync()?;
+        let replica = Index::open(replica_directory)?;
+        assert_eq!(replica.reader()?.searcher().num_docs(), 1);
+        Ok(())
+    }
+
+    struct PathTraversalTransport {
+        primary: Index,
+        path: &'static str,
+    }
+
+    impl ReplicaTransport for PathTraversalTransport {
+        fn fetch_manifest(&self) -> crate::Result<CommitManifest> {
+            let mut manifest = self.primary.fetch_manifest()?;
+            manifest.files[0].path = Path::new(self.path).to_path_buf();
+            Ok(manifest)
+        }
+
+        fn fetch_file(&self, path: &Path, output: &mut dyn Write) -> crate::Result<()> {
+            self.primary.fetch_file(path, output)
+        }
+    }
+
+    #[test]
+    fn test_replica_sync_rejects_unexpected_paths() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let primary = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = primary.writer_for_tests()?;
+        index_writer.add_document(doc!(tag => "a"))?;
+        index_writer.commit()?;
+
+        for path in [
+            "../escaped.idx",
+            "/tmp/escaped.idx",
+            "nested/file.idx",
+            "meta.json",
+        ] {
+            let replica_directory = RamDirectory::create();
+            let syncer = ReplicaSyncer::new(
+                ManagedDirectory::wrap(Box::new(replica_directory.clone()))?,
+                PathTraversalTransport {
+                    primary: primary.clone(),
+                    path,
+                },
+            );
+            assert!(matches!(
+                syncer.sync(),
+                Err(TantivyError::DataCorruption(_))
+            ));
+            assert!(!replica_directory.exists(Path::new(path))?);
+            assert!(!replica_directory.exists(Path::new("meta.json"))?);
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 use std::ops::BitOrAssign;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::{fmt, io};
 
+use common::ByteCount;
 use fnv::FnvHashMap;
 use itertools::Itertools;
 
@@ -12,8 +14,9 @@ use crate::fastfield::{intersect_alive_bitsets, AliveBitSet, FacetReader, FastFi
 use crate::fieldnorm::{FieldNormReader, FieldNormReaders};
 use crate::index::{InvertedIndexReader, Segment, SegmentComponent, SegmentId};
 use crate::json_utils::json_path_sep_to_dot;
-use crate::schema::{Field, IndexRecordOption, Schema, Type};
-use crate::space_usage::SegmentSpaceUsage;
+use crate::postings::AlivePostings;
+use crate::schema::{Field, IndexRecordOption, Schema, Term, Type};
+use crate::space_usage::{SegmentSpaceUsage, StoreSpaceUsage};
 use crate::store::StoreReader;
 use crate::termdict::TermDictionary;
 use crate::{DocId, Opstamp};
@@ -34,17 +37,20 @@ pub struct SegmentReader {
 
     segment_id: SegmentId,
     delete_opstamp: Option<Opstamp>,
+    routing_key: Option<String>,
 
     max_doc: DocId,
     num_docs: DocId,
 
     termdict_composite: CompositeFile,
     postings_composite: CompositeFile,
-    positions_composite: CompositeFile,
+    // `None` if the positions component was skipped when opening the segment.
+    positions_composite: Option<CompositeFile>,
     fast_fields_readers: FastFieldReaders,
     fieldnorm_readers: FieldNormReaders,
 
-    store_file: FileSlice,
+    // `None` if the store component was skipped when opening the segment.
+    store_file: Option<FileSlice>,
     alive_bitset_opt: Option<AliveBitSet>,
     schema: Schema,
 }
@@ -131,12 +137,62 @@ impl SegmentReader {
         &self.fieldnorm_readers
     }
 
+    /// Returns the files holding the inverted index of the field: its term dictionary,
+    /// its postings and their top impacts lists, its fieldnorms and, if `with_positions` is
+    /// true, its positions.
+    ///
+    /// The files that are missing, e.g. because no document of the segment has a
+    /// value for the field, are skipped.
+    pub(crate) fn inverted_index_files(
+        &self,
+        field: Field,
+        with_positions: bool,
+    ) -> Vec<FileSlice> {
+        let mut files = vec![
+            self.termdict_composite.open_read(field),
+            self.postings_composite.open_read(field),
+            self.postings_composite.open_read_with_idx(field, 1),
+            self.fieldnorm_readers.get_inner_file().open_read(field),
+        ];
+        if with_positions {
+            if let Some(positions_composite) = &self.positions_composite {
+                files.push(positions_composite.open_read(field));
+            }
+        }
+        files.into_iter().flatten().collect()
+    }
+
     /// Accessor to the segment's [`StoreReader`](crate::store::StoreReader).
     ///
     /// `cache_num_blocks` sets the number of decompressed blocks to be cached in an LRU.
-    /// The size of blocks is configurable, this should be reflexted in the
+    /// The size of blocks is configurable, see
+    /// [`IndexSettings::docstore_blocksize`](crate::IndexSettings::docstore_blocksize): the memory
+    /// used by the cache is roughly `cache_num_blocks` times the size of a block.
+    ///
+    /// Returns an error if the store component was skipped when opening the segment.
     pub fn get_store_reader(&self, cache_num_blocks: usize) -> io::Result<StoreReader> {
-        StoreReader::open(self.store_file.clone(), cache_num_blocks)
+        let store_file = self.store_file.clone().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "The store of segment {:?} was not opened.",
+                    self.segment_id.short_uuid_string()
+                ),
+            )
+        })?;
+        StoreReader::open(store_file, cache_num_blocks)
+    }
+
+    /// Returns true if the given component was opened with the segment reader.
+    ///
+    /// Components are only missing if they were skipped via
+    /// [`SegmentReader::open_without_components`].
+    pub fn has_component(&self, component: SegmentComponent) -> bool {
+        match component {
+            SegmentComponent::Positions => self.positions_composite.is_some(),
+            SegmentComponent::Store => self.store_file.is_some(),
+            _ => true,
+        }
     }
 
     /// Open a new segment for reading.
@@ -144,27 +200,67 @@ impl SegmentReader {
         Self::open_with_custom_alive_set(segment, None)
     }
 
+    /// Open a new segment for reading, without opening some of its components.
+    ///
+    /// This reduces the open latency and the mmap footprint of workloads
+    /// that do not need all of the segment data, e.g. aggregation-only workloads.
+    ///
+    /// Only [`SegmentComponent::Positions`] and [`SegmentComponent::Store`] can be skipped.
+    /// - Without positions, postings are read without positions, and phrase queries
+    /// return an error.
+    /// - Without store, [`SegmentReader::get_store_reader`] returns an error.
+    pub fn open_without_components(
+        segment: &Segment,
+        skipped_components: &[SegmentComponent],
+    ) -> crate::Result<SegmentReader> {
+        Self::open_with_options(segment, None, skipped_components)
+    }
+
     /// Open a new segment for reading.
     pub fn open_with_custom_alive_set(
         segment: &Segment,
         custom_bitset: Option<AliveBitSet>,
     ) -> crate::Result<SegmentReader> {
+        Self::open_with_options(segment, custom_bitset, &[])
+    }
+
+    fn open_with_options(
+        segment: &Segment,
+        custom_bitset: Option<AliveBitSet>,
+        skipped_components: &[SegmentComponent],
+    ) -> crate::Result<SegmentReader> {
+        for skipped_component in skipped_components {
+            if !matches!(
+                skipped_component,
+                SegmentComponent::Positions | SegmentComponent::Store
+            ) {
+                return Err(crate::TantivyError::InvalidArgument(
+                    "Only the positions and the store components can be skipped when opening a \
+                     segment."
+                        .to_string(),
+                ));
+            }
+        }
         let termdict_file = segment.open_read(SegmentComponent::Terms)?;
         let termdict_composite = CompositeFile::open(&termdict_file)?;
 
-        let store_file = segment.open_read(SegmentComponent::Store)?;
+        let store_file = if skipped_components.contains(&SegmentComponent::Store) {
+            None
+        } else {
+            Some(segment.open_read(SegmentComponent::Store)?)
+        };
 
         crate::fail_point!("SegmentReader::open#middle");
 
         let postings_file = segment.open_read(SegmentComponent::Postings)?;
         let postings_composite = CompositeFile::open(&postings_file)?;
 
-        let positions_composite = {
-            if let Ok(positions_file) = segment.open_read(SegmentComponent::Positions) {
-                CompositeFile::open(&positions_file)?
-            } else {
-                CompositeFile::empty()
-            }
+        let positions_composite = if skipped_components.contains(&SegmentComponent::Positions) {
+            None
+        } else if let Ok(positions_file) = segment.open_read(SegmentComponent::Positions) {
+            Some(CompositeFile::open(&positions_file)?)
+        } else {
+            Some(CompositeFile::empty())
         };
 
         let schema = segment.schema();
@@ -200,6 +296,7 @@ impl SegmentReader {
             fieldnorm_readers,
             segment_id: segment.id(),
             delete_opstamp: segment.meta().delete_opstamp(),
+            routing_key: segment.meta().routing_key().map(str::to_string),
             store_file,
             alive_bitset_opt,
             positions_composite,
@@ -250,28 +347,31 @@ impl SegmentReader {
         let record_option = record_option_opt.unwrap();
         let postings_file = postings_file_opt.unwrap();
 
-        let termdict_file: FileSlice =
-            self.termdict_composite.open_read(field).ok_or_else(|| {
-                DataCorruption::comment_only(format!(
-                    "Failed to open field {:?}'s term dictionary in the composite file. Has the \
-                     schema been modified?",
-                    field_entry.name()
-                ))
-            })?;
-
-        let positions_file = self.positions_composite.open_read(field).ok_or_else(|| {
-            let error_msg = format!(
-                "Failed to open field {:?}'s positions in the composite file. Has the schema been \
-                 modified?",
-                field_entry.name()
-            );
-            DataCorruption::comment_only(error_msg)
-        })?;
+        let termdict_file: FileSlice = self
+            .termdict_composite
+            .open_read(field)
+            .ok_or_else(|| self.missing_field_error(field_entry.name(), SegmentComponent::Terms))?;
+
+        let positions_file_opt = if let Some(positions_composite) = &self.positions_composite {
+            match positions_composite.open_read(field) {
+                Some(positions_file) => Some(positions_file),
+                // The positions of a field are only required if they were indexed.
+                None if !record_option.has_positions() => None,
+                None => {
+                    return Err(self
+                        .missing_field_error(field_entry.name(), SegmentComponent::Positions)
+                        .into());
+                }
+            }
+        } else {
+            None
+        };
 
         let inv_idx_reader = Arc::new(InvertedIndexReader::new(
             TermDictionary::open(termdict_file)?,
             postings_file,
-            positions_file,
+            positions_file_opt,
+            self.postings_composite.open_read_with_idx(field, 1),
             record_option,
         )?);
 
@@ -285,6 +385,42 @@ impl SegmentReader {
         Ok(inv_idx_reader)
     }
 
+    /// Error returned when the data of a field is missing from one of the composite
+    /// files of the segment.
+    fn missing_field_error(&self, field_name: &str, component: SegmentComponent) -> DataCorruption {
+        let (composite_name, extension) = match component {
+            SegmentComponent::Terms => ("term dictionary", "term"),
+            SegmentComponent::Positions => ("positions", "pos"),
+            _ => ("postings", "idx"),
+        };
+        let filepath = PathBuf::from(format!("{}.{extension}", self.segment_id.uuid_string()));
+        DataCorruption::new(
+            filepath,
+            format!(
+                "Field {field_name:?} not found in the {composite_name} composite file of segment \
+                 {}. Has the schema been modified?",
+                self.segment_id.short_uuid_string()
+            ),
+        )
+    }
+
+    /// Returns the postings of the term, skipping the deleted documents of the segment,
+    /// or `None` if the term does not appear in the segment.
+    ///
+    /// Unlike [`InvertedIndexReader::read_postings`], there is no need to check
+    /// [`SegmentReader::is_deleted`] for each of the documents returned.
+    pub fn read_postings(
+        &self,
+        term: &Term,
+        option: IndexRecordOption,
+    ) -> crate::Result<Option<AlivePostings>> {
+        let postings_opt = self
+            .inverted_index(term.field())?
+            .read_postings(term, option)?;
+        let alive_bitset_opt = self.alive_bitset_opt.clone();
+        Ok(postings_opt.map(|postings| AlivePostings::new(postings, alive_bitset_opt)))
+    }
+
     /// Returns the list of fields that have been indexed in the segment.
     /// The field list includes the field defined in the schema as well as the fields
     /// that have been indexed as a part of a JSON field.
@@ -387,6 +523,13 @@ impl SegmentReader {
         self.segment_id
     }
 
+    /// Returns the value of the routing field shared by all of the documents of the segment.
+    ///
+    /// See [`SegmentMeta::routing_key`](crate::index::SegmentMeta::routing_key).
+    pub fn routing_key(&self) -> Option<&str> {
+        self.routing_key.as_deref()
+    }
+
     /// Returns the delete opstamp
     pub fn delete_opstamp(&self) -> Option<Opstamp> {
         self.delete_opstamp
@@ -420,10 +563,17 @@ impl SegmentReader {
             self.num_docs(),
             self.termdict_composite.space_usage(),
             self.postings_composite.space_usage(),
-            self.positions_composite.space_usage(),
+            self.positions_composite
+                .as_ref()
+                .map(CompositeFile::space_usage)
+                .unwrap_or_else(|| CompositeFile::empty().space_usage()),
             self.fast_fields_readers.space_usage(self.schema())?,
             self.fieldnorm_readers.space_usage(),
-            self.get_store_reader(0)?.space_usage(),
+            if self.store_file.is_some() {
+                self.get_store_reader(0)?.space_usage()
+            } else {
+                StoreSpaceUsage::new(ByteCount::default(), ByteCount::default())
+            },
             self.alive_bitset_opt
                 .as_ref()
                 .map(AliveBitSet::space_usage)
@@ -515,9 +665,10 @@ impl fmt::Debug for SegmentReader {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::directory::CompositeWrite;
     use crate::index::Index;
     use crate::schema::{SchemaBuilder, Term, STORED, TEXT};
-    use crate::IndexWriter;
+    use crate::{Directory, IndexWriter};
 
     #[test]
     fn test_merge_field_meta_data_same() {
@@ -693,4 +844,28 @@ mod test {
         assert_eq!(vec![0u32, 2u32], docs);
         Ok(())
     }
+
+    #[test]
+    fn test_inverted_index_missing_term_dictionary() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let name = schema_builder.add_text_field("name", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(name => "tantivy"))?;
+        index_writer.commit()?;
+
+        // Replaces the term dictionary with an empty composite file.
+        let mut segment = index.searchable_segments()?.remove(0);
+        let term_path = segment.relative_path(SegmentComponent::Terms);
+        index.directory().delete(&term_path).unwrap();
+        CompositeWrite::wrap(segment.open_write(SegmentComponent::Terms)?).close()?;
+
+        let segment_reader = SegmentReader::open(&segment)?;
+        let error = segment_reader.inverted_index(name).err().unwrap();
+        let error_msg = format!("{error:?}");
+        assert!(error_msg.contains(&format!("{term_path:?}")));
+        assert!(error_msg.contains("Field \"name\" not found in the term dictionary"));
+        assert!(error_msg.contains(&segment.id().short_uuid_string()));
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
@@ -7,9 +8,11 @@ use common::BitSet;
 use smallvec::smallvec;
 
 use super::operation::{AddOperation, UserOperation};
-use super::segment_updater::SegmentUpdater;
+use super::segment_updater::{save_metas, SegmentUpdater};
 use super::{AddBatch, AddBatchReceiver, AddBatchSender, PreparedCommit};
-use crate::directory::{DirectoryLock, GarbageCollectionResult, TerminatingWrite};
+use crate::directory::{
+    DirectoryLock, GarbageCollectionDryRun, GarbageCollectionResult, TerminatingWrite,
+};
 use crate::error::TantivyError;
 use crate::fastfield::write_alive_bitset;
 use crate::index::{Index, Segment, SegmentComponent, SegmentId, SegmentMeta, SegmentReader};
@@ -17,11 +20,12 @@ use crate::indexer::delete_queue::{DeleteCursor, DeleteQueue};
 use crate::indexer::doc_opstamp_mapping::DocToOpstampMapping;
 use crate::indexer::index_writer_status::IndexWriterStatus;
 use crate::indexer::operation::DeleteOperation;
+use crate::indexer::oplog::OplogWriter;
 use crate::indexer::stamper::Stamper;
-use crate::indexer::{MergePolicy, SegmentEntry, SegmentWriter};
+use crate::indexer::{MergePolicy, MergeScheduler, SegmentEntry, SegmentWriter};
 use crate::query::{EnableScoring, Query, TermQuery};
-use crate::schema::document::Document;
-use crate::schema::{IndexRecordOption, TantivyDocument, Term};
+use crate::schema::document::{Document, Value};
+use crate::schema::{Field, IndexRecordOption, TantivyDocument, Term};
 use crate::{FutureResult, Opstamp};
 
 // Size of the margin for the `memory_arena`. A segment is closed when the remaining memory
@@ -35,6 +39,11 @@ pub const MEMORY_BUDGET_NUM_BYTES_MAX: usize = u32::MAX as usize - MARGIN_IN_BYT
 // We impose the number of index writer threads to be at most this.
 pub const MAX_NUM_THREAD: usize = 8;
 
+// Maximum number of segments an indexing thread routes documents to at the same time,
+// when the index has a routing field. The documents of the other routing keys
+// go to a single unrouted segment.
+const MAX_NUM_ROUTED_SEGMENT_WRITERS: usize = 4;
+
 // Add document will block if the number of docs waiting in the queue to be indexed
 // reaches `PIPELINE_MAX_SIZE_IN_DOCS`
 const PIPELINE_MAX_SIZE_IN_DOCS: usize = 10_000;
@@ -45,6 +54,40 @@ fn error_in_index_worker_thread(context: &str) -> TantivyError {
     ))
 }
 
+/// Handle of an indexing worker, running either in its own thread, or in the
+/// indexing thread pool of the index.
+enum IndexingWorkerHandle {
+    Thread(JoinHandle<crate::Result<()>>),
+    ThreadPool(oneshot::Receiver<thread::Result<crate::Result<()>>>),
+}
+
+impl IndexingWorkerHandle {
+    fn spawn<F>(index: &Index, thread_name: String, work: F) -> crate::Result<Self>
+    where F: FnOnce() -> crate::Result<()> + Send + 'static {
+        if let Some(thread_pool) = index.indexing_thread_pool() {
+            let (result_sender, result_receiver) = oneshot::channel();
+            thread_pool.spawn(move || {
+                // Panics are sent back to the index writer, rather than aborting the
+                // process as rayon does by default.
+                let _ = result_sender.send(panic::catch_unwind(AssertUnwindSafe(work)));
+            });
+            return Ok(IndexingWorkerHandle::ThreadPool(result_receiver));
+        }
+        let join_handle = thread::Builder::new().name(thread_name).spawn(work)?;
+        Ok(IndexingWorkerHandle::Thread(join_handle))
+    }
+
+    /// Waits for the worker to finish, like [`JoinHandle::join`].
+    fn join(self) -> thread::Result<crate::Result<()>> {
+        match self {
+            IndexingWorkerHandle::Thread(join_handle) => join_handle.join(),
+            IndexingWorkerHandle::ThreadPool(result_receiver) => result_receiver
+                .recv()
+                .unwrap_or_else(|_| Err(Box::new("The indexing worker was dropped."))),
+        }
+    }
+}
+
 /// `IndexWriter` is the user entry-point to add document to an index.
 ///
 /// It manages a small number of indexing thread, as well as a shared
@@ -61,7 +104,7 @@ pub struct IndexWriter<D: Document = TantivyDocument> {
     // The memory budget per thread, after which a commit is triggered.
     memory_budget_in_bytes_per_thread: usize,
 
-    workers_join_handle: Vec<JoinHandle<crate::Result<()>>>,
+    workers_join_handle: Vec<IndexingWorkerHandle>,
 
     index_writer_status: IndexWriterStatus<D>,
     operation_sender: AddBatchSender<D>,
@@ -76,6 +119,8 @@ pub struct IndexWriter<D: Document = TantivyDocument> {
 
     stamper: Stamper,
     committed_opstamp: Opstamp,
+
+    oplog_writer: Option<OplogWriter>,
 }
 
 fn compute_deleted_bitset(
@@ -167,21 +212,64 @@ pub(crate) fn advance_deletes(
 
 fn index_documents<D: Document>(
     memory_budget: usize,
-    segment: Segment,
+    index: &Index,
+    routing_field: Option<Field>,
     grouped_document_iterator: &mut dyn Iterator<Item = AddBatch<D>>,
     segment_updater: &SegmentUpdater,
-    mut delete_cursor: DeleteCursor,
+    delete_cursor: DeleteCursor,
 ) -> crate::Result<()> {
-    let mut segment_writer = SegmentWriter::for_segment(memory_budget, segment.clone())?;
+    // Without routing field, all of the documents go to the segment writer of the `None`
+    // routing key. Otherwise, each routing key gets its own segment writer, sharing the memory
+    // budget.
+    let segment_writer_memory_budget = if routing_field.is_some() {
+        memory_budget / (MAX_NUM_ROUTED_SEGMENT_WRITERS + 1)
+    } else {
+        memory_budget
+    };
+    let mut segment_writers: Vec<(Option<String>, Segment, SegmentWriter)> = Vec::new();
     for document_group in grouped_document_iterator {
         for doc in document_group {
-            segment_writer.add_document(doc)?;
+            let mut routing_key = routing_field
+                .and_then(|routing_field| extract_routing_key(&doc.document, routing_field));
+            let num_routed_segment_writers = segment_writers
+                .iter()
+                .filter(|(segment_routing_key, _, _)| segment_routing_key.is_some())
+                .count();
+            if num_routed_segment_writers >= MAX_NUM_ROUTED_SEGMENT_WRITERS
+                && !segment_writers
+                    .iter()
+                    .any(|(segment_routing_key, _, _)| *segment_routing_key == routing_key)
+            {
+                // Past the maximum number of routed segment writers, the documents
+                // end up in an unrouted segment.
+                routing_key = None;
+            }
+            let segment_writer_ord = if let Some(segment_writer_ord) = segment_writers
+                .iter()
+                .position(|(segment_routing_key, _, _)| *segment_routing_key == routing_key)
+            {
+                segment_writer_ord
+            } else {
+                let segment = index.new_segment();
+                let segment_writer =
+                    SegmentWriter::for_segment(segment_writer_memory_budget, segment.clone())?;
+                segment_writers.push((routing_key, segment, segment_writer));
+                segment_writers.len() - 1
+            };
+            segment_writers[segment_writer_ord].2.add_document(doc)?;
         }
-        let mem_usage = segment_writer.mem_usage();
+        let mem_usage: usize = segment_writers
+            .iter()
+            .map(|(_, _, segment_writer)| segment_writer.mem_usage())
+            .sum();
         if mem_usage >= memory_budget - MARGIN_IN_BYTES {
             info!(
-                "Buffer limit reached, flushing segment with maxdoc={}.",
-                segment_writer.max_doc()
+                "Buffer limit reached, flushing {} segment(s) with maxdoc={}.",
+                segment_writers.len(),
+                segment_writers
+                    .iter()
+                    .map(|(_, _, segment_writer)| segment_writer.max_doc())
+                    .sum::<u32>()
             );
             break;
         }
@@ -191,19 +279,42 @@ fn index_documents<D: Document>(
         return Ok(());
     }
 
+    for (routing_key, segment, segment_writer) in segment_writers {
+        finalize_segment(
+            segment,
+            segment_writer,
+            routing_key,
+            segment_updater,
+            delete_cursor.clone(),
+        )?;
+    }
+    Ok(())
+}
+
+fn finalize_segment(
+    segment: Segment,
+    segment_writer: SegmentWriter,
+    routing_key: Option<String>,
+    segment_updater: &SegmentUpdater,
+    mut delete_cursor: DeleteCursor,
+) -> crate::Result<()> {
     let max_doc = segment_writer.max_doc();
 
     // this is ensured by the call to peek before starting
     // the worker thread.
     assert!(max_doc > 0);
 
-    let doc_opstamps: Vec<Opstamp> = segment_writer.finalize()?;
+    let (doc_opstamps, field_term_stats) = segment_writer.finalize()?;
 
     let segment_with_max_doc = segment.with_max_doc(max_doc);
 
     let alive_bitset_opt = apply_deletes(&segment_with_max_doc, &mut delete_cursor, &doc_opstamps)?;
 
-    let meta = segment_with_max_doc.meta().clone();
+    let meta = segment_with_max_doc
+        .meta()
+        .clone()
+        .with_field_term_stats(field_term_stats)
+        .with_routing_key(routing_key);
     meta.untrack_temp_docstore();
     // update segment_updater inventory to remove tempstore
     let segment_entry = SegmentEntry::new(meta, delete_cursor, alive_bitset_opt);
@@ -211,6 +322,14 @@ fn index_documents<D: Document>(
     Ok(())
 }
 
+/// Returns the first value of the routing field of the document, if it is a string.
+fn extract_routing_key<D: Document>(document: &D, routing_field: Field) -> Option<String> {
+    document
+        .iter_fields_and_values()
+        .find(|(field, _)| *field == routing_field)
+        .and_then(|(_, value)| value.as_str().map(str::to_string))
+}
+
 /// `doc_opstamps` is required to be non-empty.
 fn apply_deletes(
     segment: &Segment,
@@ -294,6 +413,16 @@ impl<D: Document> IndexWriter<D> {
         let segment_updater =
             SegmentUpdater::create(index.clone(), stamper.clone(), &delete_queue.cursor())?;
 
+        let oplog_writer = if index.settings().oplog {
+            Some(OplogWriter::open(
+                index.directory(),
+                index.schema(),
+                current_opstamp,
+            )?)
+        } else {
+            None
+        };
+
         let mut index_writer = Self {
             _directory_lock: Some(directory_lock),
 
@@ -312,6 +441,8 @@ impl<D: Document> IndexWriter<D> {
             committed_opstamp: current_opstamp,
             stamper,
 
+            oplog_writer,
+
             worker_id: 0,
         };
         index_writer.start_workers()?;
@@ -400,41 +531,42 @@ impl<D: Document> IndexWriter<D> {
 
         let mem_budget = self.memory_budget_in_bytes_per_thread;
         let index = self.index.clone();
-        let join_handle: JoinHandle<crate::Result<()>> = thread::Builder::new()
-            .name(format!("thrd-tantivy-index{}", self.worker_id))
-            .spawn(move || {
-                loop {
-                    let mut document_iterator = document_receiver_clone
-                        .clone()
-                        .into_iter()
-                        .filter(|batch| !batch.is_empty())
-                        .peekable();
-
-                    // The peeking here is to avoid creating a new segment's files
-                    // if no document are available.
-                    //
-                    // This is a valid guarantee as the peeked document now belongs to
-                    // our local iterator.
-                    if let Some(batch) = document_iterator.peek() {
-                        assert!(!batch.is_empty());
-                        delete_cursor.skip_to(batch[0].opstamp);
-                    } else {
-                        // No more documents.
-                        // It happens when there is a commit, or if the `IndexWriter`
-                        // was dropped.
-                        index_writer_bomb.defuse();
-                        return Ok(());
-                    }
-
-                    index_documents(
-                        mem_budget,
-                        index.new_segment(),
-                        &mut document_iterator,
-                        &segment_updater,
-                        delete_cursor.clone(),
-                    )?;
+        let routing_field = self.index.routing_field()?;
+        let thread_name = format!("thrd-tantivy-index{}", self.worker_id);
+        let join_handle = IndexingWorkerHandle::spawn(&self.index, thread_name, move || {
+            loop {
+                let mut document_iterator = document_receiver_clone
+                    .clone()
+                    .into_iter()
+                    .filter(|batch| !batch.is_empty())
+                    .peekable();
+
+                // The peeking here is to avoid creating a new segment's files
+                // if no document are available.
+                //
+                // This is a valid guarantee as the peeked document now belongs to
+                // our local iterator.
+                if let Some(batch) = document_iterator.peek() {
+                    assert!(!batch.is_empty());
+                    delete_cursor.skip_to(batch[0].opstamp);
+                } else {
+                    // No more documents.
+                    // It happens when there is a commit, or if the `IndexWriter`
+                    // was dropped.
+                    index_writer_bomb.defuse();
+                    return Ok(());
                 }
-            })?;
+
+                index_documents(
+                    mem_budget,
+                    &index,
+                    routing_field,
+                    &mut document_iterator,
+                    &segment_updater,
+                    delete_cursor.clone(),
+                )?;
+            }
+        })?;
         self.worker_id += 1;
         self.workers_join_handle.push(join_handle);
         Ok(())
@@ -450,6 +582,19 @@ impl<D: Document> IndexWriter<D> {
         self.segment_updater.set_merge_policy(merge_policy);
     }
 
+    /// Accessor to the merge scheduler.
+    pub fn get_merge_scheduler(&self) -> MergeScheduler {
+        self.segment_updater.get_merge_scheduler()
+    }
+
+    /// Setter for the merge scheduler.
+    ///
+    /// The merge scheduler decides when the merges suggested by the merge policy,
+    /// or requested with [`IndexWriter::merge`], are run.
+    pub fn set_merge_scheduler(&self, merge_scheduler: MergeScheduler) {
+        self.segment_updater.set_merge_scheduler(merge_scheduler);
+    }
+
     fn start_workers(&mut self) -> crate::Result<()> {
         for _ in 0..self.num_threads {
             self.add_indexing_worker()?;
@@ -462,6 +607,16 @@ impl<D: Document> IndexWriter<D> {
         self.segment_updater.schedule_garbage_collect()
     }
 
+    /// Lists the files that [`IndexWriter::garbage_collect_files`] would delete,
+    /// along with the number of bytes it would reclaim, without deleting anything.
+    ///
+    /// Files that belong to a commit of the commit history are living files,
+    /// and are never listed as candidates. Instead, the files only kept for a commit
+    /// of the commit history are listed in [`GarbageCollectionDryRun::commit_history`].
+    pub fn garbage_collect_dry_run(&self) -> FutureResult<GarbageCollectionDryRun> {
+        self.segment_updater.schedule_garbage_collect_dry_run()
+    }
+
     /// Deletes all documents from the index
     ///
     /// Requires `commit`ing
@@ -545,6 +700,43 @@ impl<D: Document> IndexWriter<D> {
         // marks the segment updater as killed. From now on, all
         // segment updates will be ignored.
         self.segment_updater.kill();
+        self.recreate_after_kill()?;
+        Ok(self.committed_opstamp)
+    }
+
+    /// Rollback to a past commit.
+    ///
+    /// The commit has to be either the last commit, or part of the
+    /// commit history (see [`IndexSettings::commit_history_size`](crate::IndexSettings)).
+    ///
+    /// This cancels all of the updates that happened after the last commit,
+    /// and makes the given commit the last commit of the index again.
+    /// The commits that happened after it are dropped from the commit history,
+    /// and their files will eventually be garbage collected.
+    ///
+    /// The opstamp of the commit is returned.
+    pub fn rollback_to(&mut self, opstamp: Opstamp) -> crate::Result<Opstamp> {
+        info!("Rolling back to opstamp {opstamp}");
+        let index_meta = self.index.load_metas()?;
+        let Some(rolled_back_meta) = index_meta.checkout(opstamp) else {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Commit with opstamp {opstamp} is not part of the commit history."
+            )));
+        };
+        // marks the segment updater as killed. From now on, all
+        // segment updates will be ignored, and in particular, no
+        // merge will overwrite the meta.json we are about to write.
+        self.segment_updater.kill();
+        save_metas(&rolled_back_meta, self.index.directory())?;
+        self.recreate_after_kill()?;
+        Ok(self.committed_opstamp)
+    }
+
+    /// Replaces `self` by a new `IndexWriter`, starting from the
+    /// last commit recorded in the directory.
+    ///
+    /// The segment updater is expected to have been killed beforehand.
+    fn recreate_after_kill(&mut self) -> crate::Result<()> {
         let document_receiver_res = self.operation_receiver();
 
         // take the directory lock to create a new index_writer.
@@ -575,7 +767,7 @@ impl<D: Document> IndexWriter<D> {
             for _ in document_receiver {}
         }
 
-        Ok(self.committed_opstamp)
+        Ok(())
     }
 
     /// Prepares a commit.
@@ -653,6 +845,15 @@ impl<D: Document> IndexWriter<D> {
         &self.segment_updater
     }
 
+    /// Writes the operations recorded in the operation log since the last commit,
+    /// if the operation log is enabled.
+    pub(crate) fn flush_oplog(&self, commit_opstamp: Opstamp) -> crate::Result<()> {
+        if let Some(oplog_writer) = &self.oplog_writer {
+            oplog_writer.flush(self.index.directory(), commit_opstamp)?;
+        }
+        Ok(())
+    }
+
     /// Delete all documents containing a given term.
     ///
     /// Delete operation only affects documents that
@@ -662,11 +863,16 @@ impl<D: Document> IndexWriter<D> {
     /// Like adds, the deletion itself will be visible
     /// only after calling `commit()`.
     pub fn delete_term(&self, term: Term) -> Opstamp {
-        let query = TermQuery::new(term, IndexRecordOption::Basic);
+        let query = TermQuery::new(term.clone(), IndexRecordOption::Basic);
         // For backward compatibility, if Term is invalid for the index, do nothing but return an
         // Opstamp
-        self.delete_query(Box::new(query))
-            .unwrap_or_else(|_| self.stamper.stamp())
+        let Ok(opstamp) = self.delete_query(Box::new(query)) else {
+            return self.stamper.stamp();
+        };
+        if let Some(oplog_writer) = &self.oplog_writer {
+            oplog_writer.record([(opstamp, oplog_writer.serialize_delete(&term))]);
+        }
+        opstamp
     }
 
     /// Delete all documents matching a given query.
@@ -710,7 +916,16 @@ impl<D: Document> IndexWriter<D> {
     /// document queue.
     pub fn add_document(&self, document: D) -> crate::Result<Opstamp> {
         let opstamp = self.stamper.stamp();
+        let oplog_operation = self
+            .oplog_writer
+            .as_ref()
+            .map(|oplog_writer| oplog_writer.serialize_add(&document))
+            .transpose()?;
         self.send_add_documents_batch(smallvec![AddOperation { opstamp, document }])?;
+        // The operation is only recorded once the document was accepted by the workers.
+        if let Some(oplog_writer) = &self.oplog_writer {
+            oplog_writer.record(oplog_operation.map(|operation_bytes| (opstamp, operation_bytes)));
+        }
         Ok(opstamp)
     }
 
@@ -756,10 +971,14 @@ impl<D: Document> IndexWriter<D> {
         let (batch_opstamp, stamps) = self.get_batch_opstamps(count);
 
         let mut adds = AddBatch::default();
+        let mut oplog_operations = Vec::new();
 
         for (user_op, opstamp) in user_operations_it.zip(stamps) {
             match user_op {
                 UserOperation::Delete(term) => {
+                    if let Some(oplog_writer) = &self.oplog_writer {
+                        oplog_operations.push((opstamp, oplog_writer.serialize_delete(&term)));
+                    }
                     let query = TermQuery::new(term, IndexRecordOption::Basic);
                     let weight =
                         query.weight(EnableScoring::disabled_from_schema(&self.index.schema()))?;
@@ -770,12 +989,19 @@ impl<D: Document> IndexWriter<D> {
                     self.delete_queue.push(delete_operation);
                 }
                 UserOperation::Add(document) => {
+                    if let Some(oplog_writer) = &self.oplog_writer {
+                        oplog_operations.push((opstamp, oplog_writer.serialize_add(&document)?));
+                    }
                     let add_operation = AddOperation { opstamp, document };
                     adds.push(add_operation);
                 }
             }
         }
         self.send_add_documents_batch(adds)?;
+        // The operations are only recorded once the documents were accepted by the workers.
+        if let Some(oplog_writer) = &self.oplog_writer {
+            oplog_writer.record(oplog_operations);
+        }
         Ok(batch_opstamp)
     }
 
@@ -802,6 +1028,7 @@ impl<D: Document> Drop for IndexWriter<D> {
 mod tests {
     use std::collections::{HashMap, HashSet};
     use std::net::Ipv6Addr;
+    use std::sync::Arc;
 
     use columnar::{Column, MonotonicallyMappableToU128};
     use itertools::Itertools;
@@ -810,8 +1037,11 @@ mod tests {
     use super::super::operation::UserOperation;
     use crate::collector::{Count, TopDocs};
     use crate::directory::error::LockError;
+    use crate::directory::{Directory, GarbageCollectionCandidate, RamDirectory};
     use crate::error::*;
-    use crate::indexer::index_writer::MEMORY_BUDGET_NUM_BYTES_MIN;
+    use crate::indexer::index_writer::{
+        MAX_NUM_ROUTED_SEGMENT_WRITERS, MEMORY_BUDGET_NUM_BYTES_MIN,
+    };
     use crate::indexer::NoMergePolicy;
     use crate::query::{QueryParser, TermQuery};
     use crate::schema::{
@@ -821,8 +1051,8 @@ mod tests {
     };
     use crate::store::DOCSTORE_CACHE_CAPACITY;
     use crate::{
-        DateTime, DocAddress, Index, IndexSettings, IndexWriter, ReloadPolicy, TantivyDocument,
-        Term,
+        DateTime, DocAddress, Index, IndexSettings, IndexWriter, ReloadPolicy, SegmentId,
+        TantivyDocument, Term,
     };
 
     const LOREM: &str = "Doc Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do \
@@ -1044,6 +1274,376 @@ mod tests {
         Ok(())
     }
 
+    fn create_routed_index() -> crate::Result<(Index, schema::Field, schema::Field)> {
+        let mut schema_builder = schema::Schema::builder();
+        let tenant_field = schema_builder.add_text_field("tenant", STRING);
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::builder()
+            .schema(schema_builder.build())
+            .settings(IndexSettings {
+                routing_field: Some("tenant".to_string()),
+                ..Default::default()
+            })
+            .create_in_ram()?;
+        Ok((index, tenant_field, text_field))
+    }
+
+    #[test]
+    fn test_routing_field_buckets_documents_by_tenant() -> crate::Result<()> {
+        let (index, tenant_field, text_field) = create_routed_index()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for _ in 0..10 {
+            index_writer.add_document(doc!(tenant_field=>"a", text_field=>"hello"))?;
+            index_writer.add_document(doc!(tenant_field=>"b", text_field=>"hello"))?;
+        }
+        index_writer.add_document(doc!(text_field=>"hello"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let routing_keys: Vec<Option<&str>> = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment_reader| segment_reader.routing_key())
+            .sorted()
+            .collect();
+        assert_eq!(routing_keys, vec![None, Some("a"), Some("b")]);
+
+        let query = TermQuery::new(
+            Term::from_field_text(text_field, "hello"),
+            IndexRecordOption::WithFreqs,
+        );
+        assert_eq!(searcher.search(&query, &Count)?, 21);
+        assert_eq!(searcher.search_with_routing_key(&query, &Count, "a")?, 10);
+        assert_eq!(searcher.search_with_routing_key(&query, &Count, "b")?, 10);
+        assert_eq!(searcher.search_with_routing_key(&query, &Count, "c")?, 0);
+
+        // The routing filter does not change the scores.
+        let top_docs = searcher.search_with_routing_key(&query, &TopDocs::with_limit(20), "a")?;
+        assert_eq!(top_docs.len(), 10);
+        let score = searcher.search(&query, &TopDocs::with_limit(1))?[0].0;
+        for (doc_score, doc_address) in top_docs {
+            assert_eq!(doc_score, score);
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            assert_eq!(
+                doc.get_first(tenant_field).and_then(|v| v.as_str()),
+                Some("a")
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_routing_field_many_tenants() -> crate::Result<()> {
+        let (index, tenant_field, text_field) = create_routed_index()?;
+        let mut index_writer: IndexWriter = index.writer_with_num_threads(1, 50_000_000)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        let tenants: Vec<String> = (0..10).map(|i| format!("tenant{i}")).collect();
+        for tenant in &tenants {
+            index_writer.add_document(doc!(tenant_field=>tenant.as_str(), text_field=>"hello"))?;
+        }
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        // The documents of the tenants beyond the limit end up in an unrouted segment.
+        let num_routed_segments = searcher
+            .segment_readers()
+            .iter()
+            .filter(|segment_reader| segment_reader.routing_key().is_some())
+            .count();
+        assert_eq!(num_routed_segments, MAX_NUM_ROUTED_SEGMENT_WRITERS);
+        assert_eq!(
+            searcher.segment_readers().len(),
+            MAX_NUM_ROUTED_SEGMENT_WRITERS + 1
+        );
+        let query = TermQuery::new(
+            Term::from_field_text(text_field, "hello"),
+            IndexRecordOption::Basic,
+        );
+        for tenant in &tenants {
+            assert_eq!(searcher.search_with_routing_key(&query, &Count, tenant)?, 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_routing_field_merge() -> crate::Result<()> {
+        let (index, tenant_field, text_field) = create_routed_index()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for tenant in ["a", "b", "a", "b"] {
+            index_writer.add_document(doc!(tenant_field=>tenant, text_field=>"hello"))?;
+            index_writer.commit()?;
+        }
+        let segment_ids = |routing_key: &str| -> crate::Result<Vec<SegmentId>> {
+            Ok(index
+                .searchable_segment_metas()?
+                .iter()
+                .filter(|segment_meta| segment_meta.routing_key() == Some(routing_key))
+                .map(|segment_meta| segment_meta.id())
+                .collect())
+        };
+        let merged_segment_meta = index_writer.merge(&segment_ids("a")?).wait()?.unwrap();
+        assert_eq!(merged_segment_meta.routing_key(), Some("a"));
+
+        // Segments of different tenants lose the routing key when merged together.
+        let all_segment_ids = index.searchable_segment_ids()?;
+        let merged_segment_meta = index_writer.merge(&all_segment_ids).wait()?.unwrap();
+        assert_eq!(merged_segment_meta.routing_key(), None);
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(text_field, "hello"),
+            IndexRecordOption::Basic,
+        );
+        assert_eq!(searcher.search_with_routing_key(&query, &Count, "a")?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_routing_field_invalid() -> crate::Result<()> {
+        let settings = IndexSettings {
+            routing_field: Some("tenant".to_string()),
+            ..Default::default()
+        };
+        let mut schema_builder = schema::Schema::builder();
+        schema_builder.add_text_field("tenant", TEXT);
+        assert!(matches!(
+            Index::builder()
+                .schema(schema_builder.build())
+                .settings(settings.clone())
+                .create_in_ram(),
+            Err(TantivyError::SchemaError(_))
+        ));
+
+        let mut schema_builder = schema::Schema::builder();
+        schema_builder.add_u64_field("tenant", INDEXED);
+        let schema = schema_builder.build();
+        assert!(matches!(
+            Index::builder()
+                .schema(schema.clone())
+                .settings(settings)
+                .create_in_ram(),
+            Err(TantivyError::SchemaError(_))
+        ));
+
+        let index = Index::create_in_ram(schema);
+        let searcher = index.reader()?.searcher();
+        assert!(matches!(
+            searcher.search_with_routing_key(&crate::query::AllQuery, &Count, "a"),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_writer_with_user_thread_pools() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let mut index = Index::create_in_ram(schema_builder.build());
+        let indexing_thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name(|i| format!("embedder-indexing-{i}"))
+            .build()
+            .unwrap();
+        let merge_thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .thread_name(|i| format!("embedder-merge-{i}"))
+            .build()
+            .unwrap();
+        index.set_indexing_thread_pool(Arc::new(indexing_thread_pool));
+        index.set_merge_thread_pool(Arc::new(merge_thread_pool));
+        let mut index_writer: IndexWriter =
+            index.writer_with_num_threads(2, MEMORY_BUDGET_NUM_BYTES_MIN * 2)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for _ in 0..2 {
+            for i in 0..10 {
+                index_writer.add_document(doc!(text_field => format!("doc{i}")))?;
+            }
+            index_writer.commit()?;
+        }
+        let segment_ids = index.searchable_segment_ids()?;
+        assert!(segment_ids.len() >= 2);
+        index_writer.merge(&segment_ids).wait()?;
+        index_writer.wait_merging_threads()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        assert_eq!(searcher.num_docs(), 20);
+        Ok(())
+    }
+
+    #[test]
+    fn test_garbage_collect_dry_run() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for text in ["a", "b"] {
+            index_writer.add_document(doc!(text_field=>text))?;
+            index_writer.commit()?;
+        }
+        index_writer.garbage_collect_files().wait()?;
+        assert!(index_writer
+            .garbage_collect_dry_run()
+            .wait()?
+            .candidates
+            .is_empty());
+
+        let segment_ids = index.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+        let dry_run = index_writer.garbage_collect_dry_run().wait()?;
+        let num_bytes_per_segment = dry_run.num_bytes_reclaimed_per_segment();
+        let reclaimed_segment_ids: HashSet<SegmentId> =
+            num_bytes_per_segment.keys().copied().collect();
+        assert_eq!(
+            reclaimed_segment_ids,
+            segment_ids.iter().copied().collect::<HashSet<SegmentId>>()
+        );
+        assert!(dry_run.num_bytes_reclaimed() > 0);
+        assert_eq!(
+            dry_run.num_bytes_reclaimed(),
+            num_bytes_per_segment.values().sum::<u64>()
+        );
+        // The dry run does not delete anything.
+        for candidate in &dry_run.candidates {
+            assert!(!candidate.is_opened);
+            assert!(index.directory().exists(&candidate.path)?);
+        }
+
+        let gc_result = index_writer.garbage_collect_files().wait()?;
+        let deleted_files: HashSet<_> = gc_result.deleted_files.into_iter().collect();
+        let candidate_files: HashSet<_> = dry_run
+            .candidates
+            .into_iter()
+            .map(|candidate| candidate.path)
+            .collect();
+        assert_eq!(deleted_files, candidate_files);
+        Ok(())
+    }
+
+    #[test]
+    fn test_garbage_collect_dry_run_commit_history() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::builder()
+            .schema(schema_builder.build())
+            .settings(IndexSettings {
+                commit_history_size: 1,
+                ..Default::default()
+            })
+            .create_in_ram()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(text_field=>"a"))?;
+        let first_opstamp = index_writer.commit()?;
+        let first_segment_ids = index.searchable_segment_ids()?;
+        index_writer.add_document(doc!(text_field=>"b"))?;
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+
+        let dry_run = index_writer.garbage_collect_dry_run().wait()?;
+        // The segment of the first commit is kept for the commit history...
+        assert_eq!(dry_run.commit_history.len(), 1);
+        let commit_retained_files = &dry_run.commit_history[0];
+        assert_eq!(commit_retained_files.opstamp, first_opstamp);
+        let retained_segment_ids: HashSet<SegmentId> = commit_retained_files
+            .files
+            .iter()
+            .filter_map(GarbageCollectionCandidate::segment_id)
+            .collect();
+        assert_eq!(
+            retained_segment_ids,
+            first_segment_ids
+                .iter()
+                .copied()
+                .collect::<HashSet<SegmentId>>()
+        );
+        let num_bytes_per_commit = dry_run.num_bytes_retained_per_commit();
+        assert!(num_bytes_per_commit[&first_opstamp] > 0);
+        // ... while the segment of the second commit is reclaimed right away.
+        let reclaimed_segment_ids: HashSet<SegmentId> = dry_run
+            .num_bytes_reclaimed_per_segment()
+            .keys()
+            .copied()
+            .collect();
+        assert!(reclaimed_segment_ids.is_disjoint(&retained_segment_ids));
+        assert_eq!(reclaimed_segment_ids.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_history_and_rollback_to() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let directory = RamDirectory::create();
+        let index = Index::builder()
+            .schema(schema_builder.build())
+            .settings(IndexSettings {
+                commit_history_size: 2,
+                ..Default::default()
+            })
+            .create(directory.clone())?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        let mut commit_opstamps = Vec::new();
+        for text in ["a", "b", "c", "d"] {
+            index_writer.add_document(doc!(text_field=>text))?;
+            commit_opstamps.push(index_writer.commit()?);
+        }
+        index_writer.garbage_collect_files().wait()?;
+        assert_eq!(
+            index.load_metas()?.commit_opstamps(),
+            vec![commit_opstamps[3], commit_opstamps[2], commit_opstamps[1]]
+        );
+
+        // The oldest commit was dropped from the history.
+        assert!(matches!(
+            Index::open_at(directory.clone(), commit_opstamps[0]),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+
+        let past_index = Index::open_at(directory.clone(), commit_opstamps[1])?;
+        assert_eq!(past_index.reader()?.searcher().num_docs(), 2);
+        assert!(matches!(
+            past_index.writer_for_tests::<TantivyDocument>(),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+
+        assert_eq!(
+            index_writer.rollback_to(commit_opstamps[1])?,
+            commit_opstamps[1]
+        );
+        assert_eq!(index_writer.commit_opstamp(), commit_opstamps[1]);
+        assert_eq!(
+            index.load_metas()?.commit_opstamps(),
+            vec![commit_opstamps[1]]
+        );
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        assert_eq!(reader.searcher().num_docs(), 2);
+
+        index_writer.add_document(doc!(text_field=>"e"))?;
+        index_writer.commit()?;
+        index_writer.garbage_collect_files().wait()?;
+        reader.reload()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_docs(), 3);
+        assert_eq!(
+            searcher.doc_freq(&Term::from_field_text(text_field, "c"))?,
+            0
+        );
+        assert_eq!(
+            searcher.doc_freq(&Term::from_field_text(text_field, "e"))?,
+            1
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_merge_on_empty_segments_single_segment() -> crate::Result<()> {
         let mut schema_builder = schema::Schema::builder();
@@ -0,0 +1,245 @@
+//! Doc id mappings of the merges.
+//!
+//! When [`IndexSettings::merge_doc_id_mappings`](crate::IndexSettings::merge_doc_id_mappings)
+//! is enabled, each merge writes a file mapping the documents of the resulting segment to
+//! the documents of the merged segments. The file lives as long as the resulting segment.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use common::{BinarySerializable, VInt};
+
+use crate::directory::error::OpenReadError;
+use crate::directory::{Directory, ManagedDirectory};
+use crate::error::DataCorruption;
+use crate::index::SegmentId;
+use crate::indexer::doc_id_mapping::SegmentDocIdMapping;
+use crate::{DocAddress, DocId};
+
+const DOC_ID_MAPPING_FILE_EXTENSION: &str = "docmap";
+
+/// Returns the path of the doc id mapping file of the segment resulting from a merge.
+pub(crate) fn doc_id_mapping_filepath(segment_id: SegmentId) -> PathBuf {
+    PathBuf::from(format!(
+        "{}.{DOC_ID_MAPPING_FILE_EXTENSION}",
+        segment_id.uuid_string()
+    ))
+}
+
+/// Mapping between the documents of the segment resulting from a merge and the
+/// documents of the merged segments.
+///
+/// Applications maintaining per-document data outside of the index, e.g. embeddings or
+/// caches indexed by doc id, can use it to remap their data after a merge.
+/// Documents that were deleted in the merged segments are not part of the
+/// resulting segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeDocIdMapping {
+    old_segment_ids: Vec<SegmentId>,
+    // In the `DocAddress`, the `segment_ord` is the ordinal of the segment in `old_segment_ids`.
+    new_doc_id_to_old_doc_addr: Vec<DocAddress>,
+}
+
+impl MergeDocIdMapping {
+    pub(crate) fn new(
+        old_segment_ids: Vec<SegmentId>,
+        segment_doc_id_mapping: &SegmentDocIdMapping,
+    ) -> MergeDocIdMapping {
+        MergeDocIdMapping {
+            old_segment_ids,
+            new_doc_id_to_old_doc_addr: segment_doc_id_mapping.iter_old_doc_addrs().collect(),
+        }
+    }
+
+    /// Returns the ids of the segments that were merged.
+    pub fn old_segment_ids(&self) -> &[SegmentId] {
+        &self.old_segment_ids
+    }
+
+    /// Returns the number of documents of the segment resulting from the merge.
+    pub fn num_docs(&self) -> u32 {
+        self.new_doc_id_to_old_doc_addr.len() as u32
+    }
+
+    /// Returns the segment and the doc id a document of the resulting segment had
+    /// before the merge.
+    pub fn old_doc_address(&self, new_doc_id: DocId) -> Option<(SegmentId, DocId)> {
+        let old_doc_addr = self.new_doc_id_to_old_doc_addr.get(new_doc_id as usize)?;
+        let old_segment_id = self.old_segment_ids[old_doc_addr.segment_ord as usize];
+        Some((old_segment_id, old_doc_addr.doc_id))
+    }
+
+    /// Returns the doc id of a document of a merged segment in the resulting segment,
+    /// or `None` if the document was deleted.
+    pub fn new_doc_id(&self, old_segment_id: SegmentId, old_doc_id: DocId) -> Option<DocId> {
+        let segment_ord = self
+            .old_segment_ids
+            .iter()
+            .position(|segment_id| *segment_id == old_segment_id)?;
+        let old_doc_addr = DocAddress::new(segment_ord as u32, old_doc_id);
+        // The merged segments are stacked, so that the old doc addresses are sorted.
+        self.new_doc_id_to_old_doc_addr
+            .binary_search(&old_doc_addr)
+            .ok()
+            .map(|new_doc_id| new_doc_id as DocId)
+    }
+
+    pub(crate) fn write(
+        &self,
+        directory: &ManagedDirectory,
+        new_segment_id: SegmentId,
+    ) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        VInt(self.old_segment_ids.len() as u64).serialize(&mut buffer)?;
+        for old_segment_id in &self.old_segment_ids {
+            old_segment_id.uuid_string().serialize(&mut buffer)?;
+        }
+        VInt(self.new_doc_id_to_old_doc_addr.len() as u64).serialize(&mut buffer)?;
+        for old_doc_addr in &self.new_doc_id_to_old_doc_addr {
+            VInt(old_doc_addr.segment_ord as u64).serialize(&mut buffer)?;
+            VInt(old_doc_addr.doc_id as u64).serialize(&mut buffer)?;
+        }
+        directory.atomic_write(&doc_id_mapping_filepath(new_segment_id), &buffer)
+    }
+
+    /// Reads the mapping of the merge that created the given segment.
+    ///
+    /// Returns `None` if the segment was not created by a merge, or if the mapping
+    /// was not recorded.
+    pub(crate) fn read(
+        directory: &ManagedDirectory,
+        new_segment_id: SegmentId,
+    ) -> crate::Result<Option<MergeDocIdMapping>> {
+        let filepath = doc_id_mapping_filepath(new_segment_id);
+        let data = match directory.atomic_read(&filepath) {
+            Ok(data) => data,
+            Err(OpenReadError::FileDoesNotExist(_)) => return Ok(None),
+            Err(open_read_error) => return Err(open_read_error.into()),
+        };
+        Self::deserialize(&data, &filepath).map(Some)
+    }
+
+    fn deserialize(mut data: &[u8], filepath: &Path) -> crate::Result<MergeDocIdMapping> {
+        let num_old_segments = VInt::deserialize(&mut data)?.val() as usize;
+        let mut old_segment_ids = Vec::with_capacity(num_old_segments);
+        for _ in 0..num_old_segments {
+            let uuid_string = String::deserialize(&mut data)?;
+            let old_segment_id = SegmentId::from_uuid_string(&uuid_string).map_err(|_| {
+                DataCorruption::new(
+                    filepath.to_path_buf(),
+                    format!("Invalid segment id {uuid_string:?}."),
+                )
+            })?;
+            old_segment_ids.push(old_segment_id);
+        }
+        let num_docs = VInt::deserialize(&mut data)?.val() as usize;
+        let mut new_doc_id_to_old_doc_addr = Vec::with_capacity(num_docs);
+        for _ in 0..num_docs {
+            let segment_ord = VInt::deserialize(&mut data)?.val() as u32;
+            let doc_id = VInt::deserialize(&mut data)?.val() as DocId;
+            if segment_ord as usize >= num_old_segments {
+                return Err(DataCorruption::new(
+                    filepath.to_path_buf(),
+                    format!("Invalid segment ordinal {segment_ord}."),
+                )
+                .into());
+            }
+            new_doc_id_to_old_doc_addr.push(DocAddress::new(segment_ord, doc_id));
+        }
+        Ok(MergeDocIdMapping {
+            old_segment_ids,
+            new_doc_id_to_old_doc_addr,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::index::SegmentId;
+    use crate::indexer::NoMergePolicy;
+    use crate::schema::{Schema, STRING};
+    use crate::{doc, Index, IndexSettings, IndexWriter, Term};
+
+    fn merge_all_segments(index: &Index) -> crate::Result<(Vec<SegmentId>, SegmentId)> {
+        let id = index.schema().get_field("id").unwrap();
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for i in 0..3 {
+            index_writer.add_document(doc!(id => format!("a{i}")))?;
+        }
+        index_writer.commit()?;
+        for i in 0..2 {
+            index_writer.add_document(doc!(id => format!("b{i}")))?;
+        }
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(id, "a1"));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        let merged_segment_meta = index_writer.merge(&segment_ids).wait()?.unwrap();
+        index_writer.garbage_collect_files().wait()?;
+        Ok((segment_ids, merged_segment_meta.id()))
+    }
+
+    fn create_index(merge_doc_id_mappings: bool) -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("id", STRING);
+        let settings = IndexSettings {
+            merge_doc_id_mappings,
+            ..Default::default()
+        };
+        Index::builder()
+            .schema(schema_builder.build())
+            .settings(settings)
+            .create_in_ram()
+    }
+
+    #[test]
+    fn test_merge_doc_id_mapping() -> crate::Result<()> {
+        let index = create_index(true)?;
+        let (old_segment_ids, merged_segment_id) = merge_all_segments(&index)?;
+        let mapping = index.merge_doc_id_mapping(merged_segment_id)?.unwrap();
+        assert_eq!(mapping.old_segment_ids().len(), 2);
+        assert_eq!(mapping.num_docs(), 4);
+
+        let searcher = index.reader()?.searcher();
+        let merged_segment_reader = searcher.segment_reader(0);
+        assert_eq!(merged_segment_reader.segment_id(), merged_segment_id);
+        let a_segment_id = old_segment_ids
+            .iter()
+            .copied()
+            .find(|segment_id| {
+                mapping.new_doc_id(*segment_id, 2).is_some()
+                    && mapping.new_doc_id(*segment_id, 1).is_none()
+            })
+            .unwrap();
+        let b_segment_id = old_segment_ids
+            .iter()
+            .copied()
+            .find(|segment_id| *segment_id != a_segment_id)
+            .unwrap();
+        // "a1" was deleted.
+        for (old_segment_id, old_doc_id) in [
+            (a_segment_id, 0),
+            (a_segment_id, 2),
+            (b_segment_id, 0),
+            (b_segment_id, 1),
+        ] {
+            let new_doc_id = mapping.new_doc_id(old_segment_id, old_doc_id).unwrap();
+            assert_eq!(
+                mapping.old_doc_address(new_doc_id),
+                Some((old_segment_id, old_doc_id))
+            );
+        }
+        assert_eq!(mapping.old_doc_address(4), None);
+        assert_eq!(mapping.new_doc_id(merged_segment_id, 0), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_doc_id_mapping_disabled() -> crate::Result<()> {
+        let index = create_index(false)?;
+        let (_, merged_segment_id) = merge_all_segments(&index)?;
+        assert!(index.merge_doc_id_mapping(merged_segment_id)?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use crate::index::SegmentMeta;
+use crate::indexer::{MergeOperation, SegmentEntry};
+
+/// Number of threads of the merge thread pool, and therefore the maximum number
+/// of merges running at the same time.
+pub(crate) const NUM_MERGE_THREADS: usize = 4;
+
+/// `MergeScheduler` decides when the merges are run, once they have been
+/// requested (by the [`MergePolicy`](crate::indexer::MergePolicy) or by
+/// [`IndexWriter::merge`](crate::IndexWriter::merge)).
+///
+/// Merges that cannot start right away are queued. Their segments stay reserved,
+/// so that they are not part of another merge in the meantime. Merges run in the
+/// background: a backlog of merges never blocks commits nor the indexing threads.
+#[derive(Debug, Clone)]
+pub struct MergeScheduler {
+    max_concurrent_merges: usize,
+    prioritize_small_merges: bool,
+}
+
+impl Default for MergeScheduler {
+    fn default() -> MergeScheduler {
+        MergeScheduler {
+            max_concurrent_merges: NUM_MERGE_THREADS,
+            prioritize_small_merges: true,
+        }
+    }
+}
+
+impl MergeScheduler {
+    /// Creates a `MergeScheduler` running a single merge at a time, in the order
+    /// in which the merges were requested.
+    ///
+    /// This makes the sequence of merges deterministic, which is useful in tests.
+    pub fn serial() -> MergeScheduler {
+        MergeScheduler {
+            max_concurrent_merges: 1,
+            prioritize_small_merges: false,
+        }
+    }
+
+    /// Sets the maximum number of merges running at the same time.
+    ///
+    /// The value is capped to the number of merge threads (4).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_concurrent_merges` is 0.
+    pub fn set_max_concurrent_merges(&mut self, max_concurrent_merges: usize) {
+        assert!(
+            max_concurrent_merges > 0,
+            "max_concurrent_merges must be greater than 0."
+        );
+        self.max_concurrent_merges = max_concurrent_merges.min(NUM_MERGE_THREADS);
+    }
+
+    /// Returns the maximum number of merges running at the same time.
+    pub fn max_concurrent_merges(&self) -> usize {
+        self.max_concurrent_merges
+    }
+
+    /// Sets whether the queued merges involving the fewest documents run first.
+    ///
+    /// Small merges are quick, and each of them reduces the number of segments,
+    /// so running them first keeps the number of segments low when merges pile up.
+    /// If disabled, merges run in the order in which they were requested.
+    pub fn set_prioritize_small_merges(&mut self, prioritize_small_merges: bool) {
+        self.prioritize_small_merges = prioritize_small_merges;
+    }
+
+    /// Returns true if the queued merges involving the fewest documents run first.
+    pub fn prioritize_small_merges(&self) -> bool {
+        self.prioritize_small_merges
+    }
+}
+
+/// A merge waiting for its turn to run.
+pub(crate) struct PendingMerge {
+    pub merge_operation: MergeOperation,
+    pub segment_entries: Vec<SegmentEntry>,
+    pub merging_future_send: oneshot::Sender<crate::Result<Option<SegmentMeta>>>,
+}
+
+impl PendingMerge {
+    fn num_docs(&self) -> u64 {
+        self.segment_entries
+            .iter()
+            .map(|segment_entry| u64::from(segment_entry.meta().num_docs()))
+            .sum()
+    }
+}
+
+/// Queue of the merges that were requested but are not running yet.
+#[derive(Default)]
+pub(crate) struct MergeQueue {
+    pending_merges: Vec<PendingMerge>,
+    num_running_merges: usize,
+}
+
+impl MergeQueue {
+    pub fn push(&mut self, pending_merge: PendingMerge) {
+        self.pending_merges.push(pending_merge);
+    }
+
+    /// Pops the next merge to run, if the scheduler allows starting a new merge.
+    ///
+    /// The returned merge is counted as running until `end_merge` is called.
+    pub fn start_next_merge(&mut self, merge_scheduler: &MergeScheduler) -> Option<PendingMerge> {
+        if self.num_running_merges >= merge_scheduler.max_concurrent_merges() {
+            return None;
+        }
+        let next_merge_ord = if merge_scheduler.prioritize_small_merges() {
+            // `min_by_key` returns the first of the smallest merges.
+            self.pending_merges
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, pending_merge)| pending_merge.num_docs())
+                .map(|(ord, _)| ord)?
+        } else if self.pending_merges.is_empty() {
+            return None;
+        } else {
+            0
+        };
+        self.num_running_merges += 1;
+        Some(self.pending_merges.remove(next_merge_ord))
+    }
+
+    pub fn end_merge(&mut self) {
+        self.num_running_merges -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeScheduler, NUM_MERGE_THREADS};
+    use crate::indexer::merge_policy::tests::MergeWheneverPossible;
+    use crate::schema::{Schema, INDEXED};
+    use crate::{doc, Index, IndexWriter};
+
+    #[test]
+    fn test_merge_scheduler_settings() {
+        let mut merge_scheduler = MergeScheduler::default();
+        assert_eq!(merge_scheduler.max_concurrent_merges(), NUM_MERGE_THREADS);
+        assert!(merge_scheduler.prioritize_small_merges());
+        merge_scheduler.set_max_concurrent_merges(100);
+        assert_eq!(merge_scheduler.max_concurrent_merges(), NUM_MERGE_THREADS);
+        merge_scheduler.set_max_concurrent_merges(2);
+        assert_eq!(merge_scheduler.max_concurrent_merges(), 2);
+
+        let serial_scheduler = MergeScheduler::serial();
+        assert_eq!(serial_scheduler.max_concurrent_merges(), 1);
+        assert!(!serial_scheduler.prioritize_small_merges());
+    }
+
+    #[test]
+    #[should_panic(expected = "max_concurrent_merges must be greater than 0.")]
+    fn test_merge_scheduler_zero_concurrent_merges() {
+        MergeScheduler::default().set_max_concurrent_merges(0);
+    }
+
+    #[test]
+    fn test_serial_merge_scheduler() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let num = schema_builder.add_u64_field("num", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(MergeWheneverPossible));
+        index_writer.set_merge_scheduler(MergeScheduler::serial());
+        for i in 0..10u64 {
+            index_writer.add_document(doc!(num => i))?;
+            index_writer.commit()?;
+        }
+        index_writer.wait_merging_threads()?;
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 1);
+        assert_eq!(segment_metas[0].num_docs(), 10);
+        Ok(())
+    }
+}
@@ -15,7 +15,7 @@ use crate::fieldnorm::{FieldNormReader, FieldNormReaders, FieldNormsSerializer,
 use crate::index::{Segment, SegmentComponent, SegmentReader};
 use crate::indexer::doc_id_mapping::{MappingType, SegmentDocIdMapping};
 use crate::indexer::SegmentSerializer;
-use crate::postings::{InvertedIndexSerializer, Postings, SegmentPostings};
+use crate::postings::{FieldTermStats, InvertedIndexSerializer, Postings, SegmentPostings};
 use crate::schema::{value_type_to_column_type, Field, FieldType, Schema};
 use crate::store::StoreWriter;
 use crate::termdict::{TermMerger, TermOrdinal};
@@ -30,36 +30,31 @@ fn estimate_total_num_tokens_in_single_segment(
     reader: &SegmentReader,
     field: Field,
 ) -> crate::Result<u64> {
-    // There are no deletes. We can simply use the exact value saved into the posting list.
+    // The value saved into the posting list is the number of tokens of all of the documents
+    // of the segment, alive or not.
     // Note that this value is not necessarily exact as it could have been the result of a merge
     // between segments themselves containing deletes.
-    if !reader.has_deletes() {
-        return Ok(reader.inverted_index(field)?.total_num_tokens());
-    }
+    let segment_num_tokens = reader.inverted_index(field)?.total_num_tokens();
+    let Some(alive_bitset) = reader.alive_bitset() else {
+        // There are no deletes. We can simply use the saved value.
+        return Ok(segment_num_tokens);
+    };
 
-    // When there are deletes, we use an approximation either
-    // by using the fieldnorm.
+    // When there are deletes, we subtract the number of tokens of the deleted documents,
+    // given by their fieldnorm. Fieldnorms are lossy for long documents, but only the
+    // deleted documents contribute to the approximation: the fieldnorms of the alive ones
+    // are not read.
     if let Some(fieldnorm_reader) = reader.fieldnorms_readers().get_field(field)? {
-        let mut count: [usize; 256] = [0; 256];
-        for doc in reader.doc_ids_alive() {
-            let fieldnorm_id = fieldnorm_reader.fieldnorm_id(doc);
-            count[fieldnorm_id as usize] += 1;
-        }
-        let total_num_tokens = count
-            .iter()
-            .cloned()
-            .enumerate()
-            .map(|(fieldnorm_ord, count)| {
-                count as u64 * u64::from(FieldNormReader::id_to_fieldnorm(fieldnorm_ord as u8))
-            })
-            .sum::<u64>();
-        return Ok(total_num_tokens);
+        let deleted_num_tokens: u64 = (0..reader.max_doc())
+            .filter(|&doc| alive_bitset.is_deleted(doc))
+            .map(|doc| u64::from(fieldnorm_reader.fieldnorm(doc)))
+            .sum();
+        return Ok(segment_num_tokens.saturating_sub(deleted_num_tokens));
     }
 
     // There are no fieldnorms available.
     // Here we just do a pro-rata with the overall number of tokens an the ratio of
     // documents alive.
-    let segment_num_tokens = reader.inverted_index(field)?.total_num_tokens();
     if reader.max_doc() == 0 {
         // That supposedly never happens, but let's be a bit defensive here.
         return Ok(0u64);
@@ -201,11 +196,17 @@ impl IndexMerger {
         let mut fieldnorms_data = Vec::with_capacity(self.max_doc as usize);
         for field in fields {
             fieldnorms_data.clear();
+            // Segments written before the field was added to the schema have no fieldnorms
+            // for it, which is equivalent to all of their documents having no tokens.
             let fieldnorms_readers: Vec<FieldNormReader> = self
                 .readers
                 .iter()
-                .map(|reader| reader.get_fieldnorms_reader(field))
-                .collect::<Result<_, _>>()?;
+                .map(|reader| {
+                    let fieldnorm_reader_opt = reader.fieldnorms_readers().get_field(field)?;
+                    Ok(fieldnorm_reader_opt
+                        .unwrap_or_else(|| FieldNormReader::constant(reader.max_doc(), 0)))
+                })
+                .collect::<crate::Result<_>>()?;
             for old_doc_addr in doc_id_mapping.iter_old_doc_addrs() {
                 let fieldnorms_reader = &fieldnorms_readers[old_doc_addr.segment_ord as usize];
                 let fieldnorm_id = fieldnorms_reader.fieldnorm_id(old_doc_addr.doc_id);
@@ -426,6 +427,14 @@ impl IndexMerger {
             };
 
             field_serializer.new_term(term_bytes, total_doc_freq, has_term_freq)?;
+            // Positions pruned in one of the segments cannot be restored.
+            if has_term_freq
+                && segment_postings_containing_the_term
+                    .iter()
+                    .any(|(_, segment_postings)| !segment_postings.has_positions())
+            {
+                field_serializer.prune_term_positions();
+            }
 
             // We can now serialize this postings, by pushing each document to the
             // postings serializer.
@@ -524,8 +533,12 @@ impl IndexMerger {
     /// to the `SegmentSerializer`.
     ///
     /// # Returns
-    /// The number of documents in the resulting segment.
-    pub fn write(&self, mut serializer: SegmentSerializer) -> crate::Result<u32> {
+    /// The number of documents in the resulting segment, and the statistics
+    /// about the terms of its indexed fields.
+    pub fn write(
+        &self,
+        mut serializer: SegmentSerializer,
+    ) -> crate::Result<(u32, Vec<FieldTermStats>)> {
         let doc_id_mapping = self.get_doc_id_from_concatenated_data()?;
         debug!("write-fieldnorms");
         if let Some(fieldnorms_serializer) = serializer.extract_fieldnorms_serializer() {
@@ -548,8 +561,8 @@ impl IndexMerger {
         self.write_fast_fields(serializer.get_fast_field_write(), doc_id_mapping)?;
 
         debug!("close-serializer");
-        serializer.close()?;
-        Ok(self.max_doc)
+        let field_term_stats = serializer.close()?;
+        Ok((self.max_doc, field_term_stats))
     }
 }
 
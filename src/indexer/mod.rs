@@ -13,12 +13,16 @@ mod flat_map_with_buffer;
 pub(crate) mod index_writer;
 pub(crate) mod index_writer_status;
 mod log_merge_policy;
+pub(crate) mod merge_doc_id_mapping;
 mod merge_index_test;
 mod merge_operation;
 pub(crate) mod merge_policy;
+mod merge_scheduler;
 pub(crate) mod merger;
 pub(crate) mod operation;
+pub(crate) mod oplog;
 pub(crate) mod prepared_commit;
+mod reindexer;
 mod segment_entry;
 mod segment_manager;
 mod segment_register;
@@ -33,11 +37,14 @@ use smallvec::SmallVec;
 
 pub use self::index_writer::IndexWriter;
 pub use self::log_merge_policy::LogMergePolicy;
+pub use self::merge_doc_id_mapping::MergeDocIdMapping;
 pub use self::merge_operation::MergeOperation;
 pub use self::merge_policy::{MergeCandidate, MergePolicy, NoMergePolicy};
+pub use self::merge_scheduler::MergeScheduler;
 use self::operation::AddOperation;
 pub use self::operation::UserOperation;
 pub use self::prepared_commit::PreparedCommit;
+pub use self::reindexer::{ReindexProgress, Reindexer};
 pub use self::segment_entry::SegmentEntry;
 pub(crate) use self::segment_serializer::SegmentSerializer;
 pub use self::segment_updater::{merge_filtered_segments, merge_indices};
@@ -0,0 +1,326 @@
+//! Operation log of the index writer.
+//!
+//! When [`IndexSettings::oplog`](crate::IndexSettings::oplog) is enabled, the add and delete
+//! operations of each commit are written to a dedicated file of the index directory,
+//! named after the opstamp of the commit. The files are written once the operations
+//! are committed, so that the log never contains operations that were rolled back.
+//!
+//! The log is truncated by the garbage collection, which removes the files of the
+//! commits older than the last
+//! [`IndexSettings::oplog_retained_commits`](crate::IndexSettings::oplog_retained_commits).
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use common::{BinarySerializable, VInt};
+
+use crate::directory::error::DeleteError;
+use crate::directory::{Directory, ManagedDirectory};
+use crate::indexer::UserOperation;
+use crate::schema::document::{
+    BinaryDocumentDeserializer, BinaryDocumentSerializer, Document, DocumentDeserialize,
+};
+use crate::schema::{Schema, Term};
+use crate::{Opstamp, TantivyError};
+
+const OPLOG_FILE_EXTENSION: &str = "oplog";
+
+const ADD_CODE: u8 = 0;
+const DELETE_CODE: u8 = 1;
+
+fn oplog_filepath(commit_opstamp: Opstamp) -> PathBuf {
+    PathBuf::from(format!("{commit_opstamp:020}.{OPLOG_FILE_EXTENSION}"))
+}
+
+fn parse_oplog_filepath(filepath: &Path) -> Option<Opstamp> {
+    if filepath.extension()? != OPLOG_FILE_EXTENSION {
+        return None;
+    }
+    filepath.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Lists the files of the operation log, together with the opstamp of their commit,
+/// sorted by opstamp.
+pub(crate) fn list_oplog_files(directory: &ManagedDirectory) -> Vec<(Opstamp, PathBuf)> {
+    let mut oplog_files: Vec<(Opstamp, PathBuf)> = directory
+        .list_managed_files()
+        .into_iter()
+        .filter_map(|filepath| Some((parse_oplog_filepath(&filepath)?, filepath)))
+        .collect();
+    oplog_files.sort_unstable();
+    oplog_files
+}
+
+/// Returns the files of the operation log which must not be garbage collected, that is
+/// the files of the last `retained_commits` commits, or all of them if `None`.
+pub(crate) fn oplog_living_files(
+    directory: &ManagedDirectory,
+    retained_commits: Option<usize>,
+) -> HashSet<PathBuf> {
+    let oplog_files = list_oplog_files(directory);
+    let num_truncated_files = retained_commits
+        .map(|retained_commits| oplog_files.len().saturating_sub(retained_commits))
+        .unwrap_or(0);
+    oplog_files
+        .into_iter()
+        .skip(num_truncated_files)
+        .map(|(_, filepath)| filepath)
+        .collect()
+}
+
+/// Buffers the operations of the ongoing commit, until they are committed.
+pub(crate) struct OplogWriter {
+    schema: Schema,
+    pending_operations: Mutex<Vec<(Opstamp, Vec<u8>)>>,
+}
+
+impl OplogWriter {
+    /// Opens the operation log of the index.
+    ///
+    /// The files written for commits that did not make it to `meta.json`, e.g.
+    /// because of a crash or of a rollback, are removed.
+    pub fn open(
+        directory: &ManagedDirectory,
+        schema: Schema,
+        committed_opstamp: Opstamp,
+    ) -> crate::Result<OplogWriter> {
+        for (commit_opstamp, filepath) in list_oplog_files(directory) {
+            if commit_opstamp <= committed_opstamp {
+                continue;
+            }
+            match directory.delete(&filepath) {
+                Ok(()) | Err(DeleteError::FileDoesNotExist(_)) => {}
+                Err(DeleteError::IoError { io_error, .. }) => {
+                    return Err(TantivyError::IoError(io_error));
+                }
+            }
+        }
+        Ok(OplogWriter {
+            schema,
+            pending_operations: Mutex::default(),
+        })
+    }
+
+    /// Serializes an add operation, to be recorded with [`OplogWriter::record`] once
+    /// the document has been handed over to the indexing workers.
+    pub fn serialize_add<D: Document>(&self, document: &D) -> io::Result<Vec<u8>> {
+        let mut buffer = vec![ADD_CODE];
+        BinaryDocumentSerializer::new(&mut buffer, &self.schema)
+            .serialize_doc_with_all_fields(document)?;
+        Ok(buffer)
+    }
+
+    /// Serializes a delete operation, to be recorded with [`OplogWriter::record`].
+    pub fn serialize_delete(&self, term: &Term) -> Vec<u8> {
+        let mut buffer = vec![DELETE_CODE];
+        buffer.extend_from_slice(term.serialized_term());
+        buffer
+    }
+
+    /// Records serialized operations, which will be written by the next commit.
+    pub fn record(&self, operations: impl IntoIterator<Item = (Opstamp, Vec<u8>)>) {
+        self.pending_operations
+            .lock()
+            .expect("Oplog lock poisoned.")
+            .extend(operations);
+    }
+
+    /// Writes the operations recorded since the last commit to the file of the commit.
+    pub fn flush(&self, directory: &ManagedDirectory, commit_opstamp: Opstamp) -> io::Result<()> {
+        let mut pending_operations = std::mem::take(
+            &mut *self
+                .pending_operations
+                .lock()
+                .expect("Oplog lock poisoned."),
+        );
+        if pending_operations.is_empty() {
+            return Ok(());
+        }
+        // Operations may be recorded concurrently, in a slightly different order
+        // than their opstamps.
+        pending_operations.sort_by_key(|(opstamp, _)| *opstamp);
+        let mut buffer = Vec::new();
+        for (opstamp, operation_bytes) in &pending_operations {
+            opstamp.serialize(&mut buffer)?;
+            VInt(operation_bytes.len() as u64).serialize(&mut buffer)?;
+            buffer.extend_from_slice(operation_bytes);
+        }
+        directory.atomic_write(&oplog_filepath(commit_opstamp), &buffer)
+    }
+}
+
+/// Reads the operations of the operation log whose opstamp is greater or equal
+/// to `from_opstamp`, in the order of their opstamps.
+pub(crate) fn read_oplog<D: Document + DocumentDeserialize>(
+    directory: &ManagedDirectory,
+    from_opstamp: Opstamp,
+) -> crate::Result<Vec<(Opstamp, UserOperation<D>)>> {
+    let mut operations = Vec::new();
+    for (commit_opstamp, filepath) in list_oplog_files(directory) {
+        // The operations of a commit have a lower opstamp than the commit itself.
+        if commit_opstamp <= from_opstamp {
+            continue;
+        }
+        let data = directory.atomic_read(&filepath)?;
+        let mut cursor = &data[..];
+        while !cursor.is_empty() {
+            let opstamp = Opstamp::deserialize(&mut cursor)?;
+            let num_bytes = VInt::deserialize(&mut cursor)?.val() as usize;
+            if num_bytes > cursor.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("Truncated oplog file {filepath:?}."),
+                )
+                .into());
+            }
+            let (operation_bytes, rest) = cursor.split_at(num_bytes);
+            cursor = rest;
+            if opstamp < from_opstamp {
+                continue;
+            }
+            operations.push((opstamp, deserialize_operation(operation_bytes)?));
+        }
+    }
+    Ok(operations)
+}
+
+fn deserialize_operation<D: Document + DocumentDeserialize>(
+    operation_bytes: &[u8],
+) -> crate::Result<UserOperation<D>> {
+    let (code, mut payload) = operation_bytes
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Empty oplog operation."))?;
+    match *code {
+        ADD_CODE => {
+            let deserializer = BinaryDocumentDeserializer::from_reader(&mut payload)?;
+            Ok(UserOperation::Add(D::deserialize(deserializer)?))
+        }
+        DELETE_CODE => Ok(UserOperation::Delete(Term::wrap(payload.to_vec()))),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unknown oplog operation code {code}."),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::indexer::UserOperation;
+    use crate::schema::{Document, Schema, TantivyDocument, STRING, TEXT};
+    use crate::{doc, Index, IndexSettings, IndexWriter, Term};
+
+    fn create_index_with_oplog() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("id", STRING);
+        schema_builder.add_text_field("body", TEXT);
+        let settings = IndexSettings {
+            oplog: true,
+            ..Default::default()
+        };
+        Index::builder()
+            .schema(schema_builder.build())
+            .settings(settings)
+            .create_in_ram()
+    }
+
+    #[test]
+    fn test_oplog_records_committed_operations() -> crate::Result<()> {
+        let index = create_index_with_oplog()?;
+        let schema = index.schema();
+        let id = schema.get_field("id").unwrap();
+        let body = schema.get_field("body").unwrap();
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let add_opstamp = index_writer.add_document(doc!(id => "1", body => "hello"))?;
+        index_writer.add_document(doc!(id => "2", body => "happy tax payer"))?;
+        index_writer.commit()?;
+        let delete_opstamp = index_writer.delete_term(Term::from_field_text(id, "1"));
+        index_writer.commit()?;
+        // Rolled back operations are not recorded.
+        index_writer.add_document(doc!(id => "3", body => "rolled back"))?;
+        index_writer.rollback()?;
+        let run_opstamp = index_writer.run(vec![
+            UserOperation::Delete(Term::from_field_text(id, "2")),
+            UserOperation::Add(doc!(id => "4", body => "replaced")),
+        ])?;
+        index_writer.commit()?;
+
+        let operations = index.read_oplog::<TantivyDocument>(0)?;
+        assert_eq!(operations.len(), 5);
+        assert_eq!(operations[0].0, add_opstamp);
+        assert!(operations
+            .windows(2)
+            .all(|operations| operations[0].0 < operations[1].0));
+        let UserOperation::Add(doc) = &operations[0].1 else {
+            panic!("expected an add operation");
+        };
+        // The fields are recorded, whether they are stored or not.
+        assert_eq!(doc.to_json(&schema), r#"{"body":["hello"],"id":["1"]}"#);
+        assert_eq!(operations[2].0, delete_opstamp);
+        assert_eq!(
+            operations[2].1,
+            UserOperation::Delete(Term::from_field_text(id, "1"))
+        );
+        assert!(operations[4].0 < run_opstamp);
+
+        let operations = index.read_oplog::<TantivyDocument>(delete_opstamp)?;
+        assert_eq!(operations.len(), 3);
+        assert_eq!(operations[0].0, delete_opstamp);
+        assert!(index.read_oplog::<TantivyDocument>(run_opstamp)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_oplog_survives_garbage_collection() -> crate::Result<()> {
+        let index = create_index_with_oplog()?;
+        let id = index.schema().get_field("id").unwrap();
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..3 {
+            index_writer.add_document(doc!(id => i.to_string()))?;
+            index_writer.commit()?;
+        }
+        index_writer.garbage_collect_files().wait()?;
+        assert_eq!(index.read_oplog::<TantivyDocument>(0)?.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_oplog_retained_commits() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let settings = IndexSettings {
+            oplog: true,
+            oplog_retained_commits: Some(2),
+            ..Default::default()
+        };
+        let index = Index::builder()
+            .schema(schema_builder.build())
+            .settings(settings)
+            .create_in_ram()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let mut opstamps = Vec::new();
+        for i in 0..4 {
+            opstamps.push(index_writer.add_document(doc!(id => i.to_string()))?);
+            index_writer.commit()?;
+        }
+        // The commit garbage collects the files of the older commits.
+        let operations = index.read_oplog::<TantivyDocument>(0)?;
+        let operation_opstamps: Vec<_> = operations.iter().map(|(opstamp, _)| *opstamp).collect();
+        assert_eq!(operation_opstamps, opstamps[2..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_oplog_disabled() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(id => "1"))?;
+        index_writer.commit()?;
+        assert!(index.read_oplog::<TantivyDocument>(0)?.is_empty());
+        Ok(())
+    }
+}
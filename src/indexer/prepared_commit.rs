@@ -46,6 +46,12 @@ impl<'a, D: Document> PreparedCommit<'a, D> {
     /// At this point deletes have not been flushed yet.
     pub fn commit_future(self) -> FutureResult<Opstamp> {
         info!("committing {}", self.opstamp);
+        // The operation log is written before `meta.json`, so that no committed
+        // operation is missing from it. The log of a failed commit is discarded
+        // when the next index writer is created.
+        if let Err(err) = self.index_writer.flush_oplog(self.opstamp) {
+            return err.into();
+        }
         self.index_writer
             .segment_updater()
             .schedule_commit(self.opstamp, self.payload)
@@ -0,0 +1,478 @@
+use std::io;
+use std::net::Ipv6Addr;
+
+use columnar::{BytesColumn, Column, StrColumn};
+use common::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::index::{SegmentId, SegmentReader};
+use crate::schema::{Field, FieldType};
+use crate::{DocId, Index, IndexWriter, ReloadPolicy, TantivyDocument, TantivyError};
+
+const DOCSTORE_CACHE_NUM_BLOCKS: usize = 10;
+const DEFAULT_COMMIT_EVERY: u64 = 100_000;
+
+/// Progress of a [`Reindexer`] run.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReindexProgress {
+    /// Number of live documents of the source index that have been read.
+    pub num_docs_processed: u64,
+    /// Number of documents that have been added to the target index.
+    pub num_docs_written: u64,
+    /// Number of live documents in the source index.
+    pub num_docs_total: u64,
+}
+
+/// Checkpoint saved as the payload of the commits of the target index.
+#[derive(Serialize, Deserialize)]
+struct ReindexCheckpoint {
+    /// Next document to copy, or `None` if the reindex is complete.
+    position: Option<(SegmentId, DocId)>,
+    progress: ReindexProgress,
+}
+
+/// Copies all of the live documents of an index into another index, typically
+/// built with a new schema.
+///
+/// Documents are read back from the doc store. The values of fields that are fast
+/// but not stored are read from their fast field and appended to the document.
+/// (Facet and JSON fast fields are not restored that way.)
+///
+/// Each document then goes through a user-supplied transformation before it is
+/// added to the target index. The source index keeps serving searches during the
+/// whole operation, and the target index can be swapped in once the reindex is
+/// complete.
+///
+/// The target index is committed every [`Reindexer::commit_every`] documents.
+/// Each commit records a checkpoint in its payload, so that a run that was
+/// interrupted resumes where the last commit left it. Resuming requires the
+/// segments of the source index to be unchanged since that commit.
+///
+/// ```rust
+/// use tantivy::indexer::Reindexer;
+/// use tantivy::schema::{Document, Schema, STORED, TEXT};
+/// use tantivy::{doc, Index, IndexWriter, TantivyDocument};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT | STORED);
+/// let source_schema = schema_builder.build();
+/// let source = Index::create_in_ram(source_schema.clone());
+/// let mut index_writer: IndexWriter = source.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "The Old Man and the Sea"))?;
+/// index_writer.commit()?;
+///
+/// let mut schema_builder = Schema::builder();
+/// schema_builder.add_text_field("title", TEXT | STORED);
+/// schema_builder.add_text_field("subtitle", TEXT | STORED);
+/// let target_schema = schema_builder.build();
+/// let target = Index::create_in_ram(target_schema.clone());
+/// let mut target_writer: IndexWriter = target.writer(15_000_000)?;
+///
+/// let progress = Reindexer::new(&source).run(&mut target_writer, |doc| {
+///     let named_doc = doc.to_named_doc(&source_schema);
+///     Ok(Some(TantivyDocument::convert_named_doc(&target_schema, named_doc)?))
+/// })?;
+/// assert_eq!(progress.num_docs_written, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Reindexer<'a> {
+    source: Index,
+    commit_every: u64,
+    on_progress: Option<Box<dyn FnMut(&ReindexProgress) + 'a>>,
+}
+
+impl<'a> Reindexer<'a> {
+    /// Creates a `Reindexer` reading the documents of the `source` index.
+    pub fn new(source: &Index) -> Reindexer<'a> {
+        Reindexer {
+            source: source.clone(),
+            commit_every: DEFAULT_COMMIT_EVERY,
+            on_progress: None,
+        }
+    }
+
+    /// Sets the number of source documents copied between two commits of the
+    /// target index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_docs` is 0.
+    #[must_use]
+    pub fn commit_every(mut self, num_docs: u64) -> Self {
+        assert!(num_docs > 0, "commit_every must be greater than 0.");
+        self.commit_every = num_docs;
+        self
+    }
+
+    /// Sets a callback called with the progress of the reindex after each commit
+    /// of the target index.
+    #[must_use]
+    pub fn on_progress(mut self, on_progress: impl FnMut(&ReindexProgress) + 'a) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    /// Copies the live documents of the source index into the index of
+    /// `index_writer`, and commits it.
+    ///
+    /// `transform` receives each document of the source index, and returns the
+    /// document to add to the target index, or `None` to drop it.
+    ///
+    /// If the last commit of the target index holds a checkpoint of a previous
+    /// run, the documents that were already copied are skipped.
+    pub fn run<F>(
+        mut self,
+        index_writer: &mut IndexWriter,
+        mut transform: F,
+    ) -> crate::Result<ReindexProgress>
+    where
+        F: FnMut(TantivyDocument) -> crate::Result<Option<TantivyDocument>>,
+    {
+        let reader = self
+            .source
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let searcher = reader.searcher();
+        let checkpoint_opt = load_checkpoint(index_writer.index())?;
+        let mut progress = checkpoint_opt
+            .as_ref()
+            .map(|checkpoint| checkpoint.progress)
+            .unwrap_or_default();
+        progress.num_docs_total = searcher.num_docs();
+        let mut resume_from = match checkpoint_opt {
+            Some(ReindexCheckpoint { position: None, .. }) => {
+                self.report_progress(&progress);
+                return Ok(progress);
+            }
+            Some(checkpoint) => checkpoint.position,
+            None => None,
+        };
+        let mut segment_readers: Vec<&SegmentReader> = searcher.segment_readers().iter().collect();
+        segment_readers.sort_by_key(|segment_reader| segment_reader.segment_id());
+        if let Some((segment_id, _)) = resume_from {
+            if !segment_readers
+                .iter()
+                .any(|segment_reader| segment_reader.segment_id() == segment_id)
+            {
+                return Err(TantivyError::InvalidArgument(format!(
+                    "Cannot resume reindex: segment {segment_id} of the checkpoint is not part of \
+                     the source index anymore."
+                )));
+            }
+        }
+        let schema = self.source.schema();
+        let fast_only_fields: Vec<Field> = schema
+            .fields()
+            .filter(|(_, field_entry)| field_entry.is_fast() && !field_entry.is_stored())
+            .map(|(field, _)| field)
+            .collect();
+        let mut num_docs_since_commit = 0u64;
+        for segment_reader in segment_readers {
+            let segment_id = segment_reader.segment_id();
+            let first_doc = match resume_from {
+                Some((checkpoint_segment_id, next_doc)) if checkpoint_segment_id == segment_id => {
+                    resume_from = None;
+                    next_doc
+                }
+                Some(_) => continue,
+                None => 0,
+            };
+            let store_reader = segment_reader.get_store_reader(DOCSTORE_CACHE_NUM_BLOCKS)?;
+            let fast_field_values = FastFieldValues::open(segment_reader, &fast_only_fields)?;
+            for doc_id in segment_reader
+                .doc_ids_alive()
+                .filter(|doc_id| *doc_id >= first_doc)
+            {
+                let mut doc: TantivyDocument = store_reader.get(doc_id)?;
+                fast_field_values.append_to(doc_id, &mut doc)?;
+                progress.num_docs_processed += 1;
+                if let Some(transformed_doc) = transform(doc)? {
+                    index_writer.add_document(transformed_doc)?;
+                    progress.num_docs_written += 1;
+                }
+                num_docs_since_commit += 1;
+                if num_docs_since_commit >= self.commit_every {
+                    self.commit(index_writer, Some((segment_id, doc_id + 1)), &progress)?;
+                    num_docs_since_commit = 0;
+                }
+            }
+        }
+        self.commit(index_writer, None, &progress)?;
+        Ok(progress)
+    }
+
+    fn commit(
+        &mut self,
+        index_writer: &mut IndexWriter,
+        position: Option<(SegmentId, DocId)>,
+        progress: &ReindexProgress,
+    ) -> crate::Result<()> {
+        let checkpoint = ReindexCheckpoint {
+            position,
+            progress: *progress,
+        };
+        let payload = serde_json::to_string(&checkpoint)?;
+        let mut prepared_commit = index_writer.prepare_commit()?;
+        prepared_commit.set_payload(&payload);
+        prepared_commit.commit()?;
+        self.report_progress(progress);
+        Ok(())
+    }
+
+    fn report_progress(&mut self, progress: &ReindexProgress) {
+        if let Some(on_progress) = self.on_progress.as_mut() {
+            on_progress(progress);
+        }
+    }
+}
+
+fn load_checkpoint(index: &Index) -> crate::Result<Option<ReindexCheckpoint>> {
+    let Some(payload) = index.load_metas()?.payload else {
+        return Ok(None);
+    };
+    let checkpoint = serde_json::from_str(&payload).map_err(|_| {
+        TantivyError::InvalidArgument(
+            "The last commit of the target index was not made by a reindex.".to_string(),
+        )
+    })?;
+    Ok(Some(checkpoint))
+}
+
+enum FastFieldColumn {
+    U64(Column<u64>),
+    I64(Column<i64>),
+    F64(Column<f64>),
+    Bool(Column<bool>),
+    Date(Column<DateTime>),
+    IpAddr(Column<Ipv6Addr>),
+    Str(StrColumn),
+    Bytes(BytesColumn),
+}
+
+/// Fast field columns of a segment, for the fields that are not stored.
+struct FastFieldValues {
+    columns: Vec<(Field, FastFieldColumn)>,
+}
+
+impl FastFieldValues {
+    fn open(segment_reader: &SegmentReader, fields: &[Field]) -> crate::Result<FastFieldValues> {
+        let schema = segment_reader.schema();
+        let fast_fields = segment_reader.fast_fields();
+        let mut columns = Vec::new();
+        for &field in fields {
+            let field_entry = schema.get_field_entry(field);
+            let field_name = field_entry.name();
+            let column_opt = match field_entry.field_type() {
+                FieldType::U64(_) => fast_fields
+                    .column_opt(field_name)?
+                    .map(FastFieldColumn::U64),
+                FieldType::I64(_) => fast_fields
+                    .column_opt(field_name)?
+                    .map(FastFieldColumn::I64),
+                FieldType::F64(_) => fast_fields
+                    .column_opt(field_name)?
+                    .map(FastFieldColumn::F64),
+                FieldType::Bool(_) => fast_fields
+                    .column_opt(field_name)?
+                    .map(FastFieldColumn::Bool),
+                FieldType::Date(_) => fast_fields
+                    .column_opt(field_name)?
+                    .map(FastFieldColumn::Date),
+                FieldType::IpAddr(_) => fast_fields
+                    .column_opt(field_name)?
+                    .map(FastFieldColumn::IpAddr),
+                FieldType::Str(_) => fast_fields.str(field_name)?.map(FastFieldColumn::Str),
+                FieldType::Bytes(_) => fast_fields.bytes(field_name)?.map(FastFieldColumn::Bytes),
+                FieldType::Facet(_) | FieldType::JsonObject(_) => None,
+            };
+            if let Some(column) = column_opt {
+                columns.push((field, column));
+            }
+        }
+        Ok(FastFieldValues { columns })
+    }
+
+    fn append_to(&self, doc_id: DocId, doc: &mut TantivyDocument) -> io::Result<()> {
+        for (field, column) in &self.columns {
+            let field = *field;
+            match column {
+                FastFieldColumn::U64(column) => {
+                    for val in column.values_for_doc(doc_id) {
+                        doc.add_u64(field, val);
+                    }
+                }
+                FastFieldColumn::I64(column) => {
+                    for val in column.values_for_doc(doc_id) {
+                        doc.add_i64(field, val);
+                    }
+                }
+                FastFieldColumn::F64(column) => {
+                    for val in column.values_for_doc(doc_id) {
+                        doc.add_f64(field, val);
+                    }
+                }
+                FastFieldColumn::Bool(column) => {
+                    for val in column.values_for_doc(doc_id) {
+                        doc.add_bool(field, val);
+                    }
+                }
+                FastFieldColumn::Date(column) => {
+                    for val in column.values_for_doc(doc_id) {
+                        doc.add_date(field, val);
+                    }
+                }
+                FastFieldColumn::IpAddr(column) => {
+                    for val in column.values_for_doc(doc_id) {
+                        doc.add_ip_addr(field, val);
+                    }
+                }
+                FastFieldColumn::Str(column) => {
+                    let mut buffer = String::new();
+                    for term_ord in column.term_ords(doc_id) {
+                        buffer.clear();
+                        column.ord_to_str(term_ord, &mut buffer)?;
+                        doc.add_text(field, &buffer);
+                    }
+                }
+                FastFieldColumn::Bytes(column) => {
+                    let mut buffer = Vec::new();
+                    for term_ord in column.term_ords(doc_id) {
+                        buffer.clear();
+                        column.ord_to_bytes(term_ord, &mut buffer)?;
+                        doc.add_bytes(field, &buffer);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReindexProgress, Reindexer};
+    use crate::collector::Count;
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{
+        Document, IndexRecordOption, Schema, Value, FAST, INDEXED, STORED, STRING, TEXT,
+    };
+    use crate::{doc, Index, IndexWriter, TantivyDocument, Term};
+
+    fn source_index() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let year = schema_builder.add_u64_field("year", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "Dune", year => 1965u64))?;
+        index_writer.add_document(doc!(title => "Hyperion", year => 1989u64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(title => "Solaris", year => 1961u64))?;
+        index_writer.add_document(doc!(title => "Ubik", year => 1969u64))?;
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(title, "ubik"));
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    fn target_index() -> Index {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", STRING | STORED);
+        schema_builder.add_u64_field("year", INDEXED | STORED);
+        Index::create_in_ram(schema_builder.build())
+    }
+
+    fn to_target_doc(
+        source: &Index,
+        target: &Index,
+        doc: TantivyDocument,
+    ) -> crate::Result<Option<TantivyDocument>> {
+        let source_schema = source.schema();
+        let target_schema = target.schema();
+        let source_year = source_schema.get_field("year").unwrap();
+        let year = doc
+            .get_first(source_year)
+            .and_then(|val| val.as_u64())
+            .unwrap();
+        if year < 1962 {
+            return Ok(None);
+        }
+        let named_doc = doc.to_named_doc(&source_schema);
+        Ok(Some(TantivyDocument::convert_named_doc(
+            &target_schema,
+            named_doc,
+        )?))
+    }
+
+    #[test]
+    fn test_reindex_transform_and_progress() -> crate::Result<()> {
+        let source = source_index()?;
+        let target = target_index();
+        let mut target_writer: IndexWriter = target.writer_for_tests()?;
+        let mut reported = Vec::new();
+        let progress = Reindexer::new(&source)
+            .commit_every(1)
+            .on_progress(|progress| reported.push(*progress))
+            .run(&mut target_writer, |doc| {
+                to_target_doc(&source, &target, doc)
+            })?;
+        let expected_progress = ReindexProgress {
+            num_docs_processed: 3,
+            num_docs_written: 2,
+            num_docs_total: 3,
+        };
+        assert_eq!(progress, expected_progress);
+        assert_eq!(reported.len(), 4);
+        assert_eq!(reported.last(), Some(&expected_progress));
+
+        let searcher = target.reader()?.searcher();
+        assert_eq!(searcher.search(&AllQuery, &Count)?, 2);
+        let year = target.schema().get_field("year").unwrap();
+        let term_query = TermQuery::new(Term::from_field_u64(year, 1989), IndexRecordOption::Basic);
+        assert_eq!(searcher.search(&term_query, &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reindex_resume() -> crate::Result<()> {
+        let source = source_index()?;
+        let target = target_index();
+        let mut target_writer: IndexWriter = target.writer_for_tests()?;
+        let mut num_calls = 0;
+        let result = Reindexer::new(&source)
+            .commit_every(1)
+            .run(&mut target_writer, |doc| {
+                num_calls += 1;
+                if num_calls == 2 {
+                    return Err(crate::TantivyError::InternalError(
+                        "interrupted".to_string(),
+                    ));
+                }
+                to_target_doc(&source, &target, doc)
+            });
+        assert!(result.is_err());
+        drop(target_writer);
+
+        let mut target_writer: IndexWriter = target.writer_for_tests()?;
+        let mut num_resumed_calls = 0;
+        let progress = Reindexer::new(&source).run(&mut target_writer, |doc| {
+            num_resumed_calls += 1;
+            to_target_doc(&source, &target, doc)
+        })?;
+        assert_eq!(num_resumed_calls, 2);
+        assert_eq!(progress.num_docs_processed, 3);
+        assert_eq!(progress.num_docs_written, 2);
+        let searcher = target.reader()?.searcher();
+        assert_eq!(searcher.search(&AllQuery, &Count)?, 2);
+
+        // Running again on a completed reindex is a no-op.
+        let progress = Reindexer::new(&source).run(&mut target_writer, |_| {
+            panic!("no document should be read");
+        })?;
+        assert_eq!(progress.num_docs_written, 2);
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use common::TerminatingWrite;
 use crate::directory::WritePtr;
 use crate::fieldnorm::FieldNormsSerializer;
 use crate::index::{Segment, SegmentComponent};
-use crate::postings::InvertedIndexSerializer;
+use crate::postings::{FieldTermStats, InvertedIndexSerializer};
 use crate::store::StoreWriter;
 
 /// Segment serializer is in charge of laying out on disk
@@ -77,13 +77,15 @@ impl SegmentSerializer {
     }
 
     /// Finalize the segment serialization.
-    pub fn close(mut self) -> crate::Result<()> {
+    ///
+    /// Returns the statistics about the terms of the indexed fields.
+    pub fn close(mut self) -> crate::Result<Vec<FieldTermStats>> {
         if let Some(fieldnorms_serializer) = self.extract_fieldnorms_serializer() {
             fieldnorms_serializer.close()?;
         }
         self.fast_field_write.terminate()?;
-        self.postings_serializer.close()?;
+        let field_term_stats = self.postings_serializer.close()?;
         self.store_writer.close()?;
-        Ok(())
+        Ok(field_term_stats)
     }
 }
@@ -1,32 +1,35 @@
 use std::borrow::BorrowMut;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::io::Write;
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
 
 use rayon::{ThreadPool, ThreadPoolBuilder};
 
 use super::segment_manager::SegmentManager;
 use crate::core::META_FILEPATH;
-use crate::directory::{Directory, DirectoryClone, GarbageCollectionResult};
+use crate::directory::{
+    Directory, DirectoryClone, GarbageCollectionDryRun, GarbageCollectionResult,
+};
 use crate::fastfield::AliveBitSet;
 use crate::index::{Index, IndexMeta, IndexSettings, Segment, SegmentId, SegmentMeta};
 use crate::indexer::delete_queue::DeleteCursor;
 use crate::indexer::index_writer::advance_deletes;
+use crate::indexer::merge_doc_id_mapping::{doc_id_mapping_filepath, MergeDocIdMapping};
 use crate::indexer::merge_operation::MergeOperationInventory;
+use crate::indexer::merge_scheduler::{MergeQueue, PendingMerge, NUM_MERGE_THREADS};
 use crate::indexer::merger::IndexMerger;
+use crate::indexer::oplog::oplog_living_files;
 use crate::indexer::segment_manager::SegmentsStatus;
 use crate::indexer::stamper::Stamper;
 use crate::indexer::{
-    DefaultMergePolicy, MergeCandidate, MergeOperation, MergePolicy, SegmentEntry,
+    DefaultMergePolicy, MergeCandidate, MergeOperation, MergePolicy, MergeScheduler, SegmentEntry,
     SegmentSerializer,
 };
 use crate::{FutureResult, Opstamp};
 
-const NUM_MERGE_THREADS: usize = 4;
-
 /// Save the index meta file.
 /// This operation is atomic:
 /// Either
@@ -83,6 +86,34 @@ fn garbage_collect_files(
         .garbage_collect(move || segment_updater.list_files())
 }
 
+fn garbage_collect_files_dry_run(
+    segment_updater: SegmentUpdater,
+) -> crate::Result<GarbageCollectionDryRun> {
+    let index = segment_updater.index.clone();
+    let index_meta = segment_updater.load_meta();
+    // Files used by the last commit or by the segments that are not committed yet.
+    let current_files: HashSet<PathBuf> = index_meta
+        .segments
+        .iter()
+        .cloned()
+        .chain(
+            segment_updater
+                .segment_manager
+                .segment_entries()
+                .iter()
+                .map(|segment_entry| segment_entry.meta().clone()),
+        )
+        .flat_map(|segment_meta| segment_meta.list_files())
+        .collect();
+    let mut dry_run = index
+        .directory()
+        .garbage_collect_dry_run(move || segment_updater.list_files())?;
+    dry_run.commit_history = index
+        .directory()
+        .commit_retained_files(&index_meta.commit_history, &current_files);
+    Ok(dry_run)
+}
+
 /// Merges a list of segments the list of segment givens in the `segment_entries`.
 /// This function happens in the calling thread and is computationally expensive.
 fn merge(
@@ -120,14 +151,45 @@ fn merge(
     // ... we just serialize this index merger in our new segment to merge the segments.
     let segment_serializer = SegmentSerializer::for_segment(merged_segment.clone())?;
 
-    let num_docs = merger.write(segment_serializer)?;
+    let (num_docs, field_term_stats) = merger.write(segment_serializer)?;
 
     let merged_segment_id = merged_segment.id();
 
-    let segment_meta = index.new_segment_meta(merged_segment_id, num_docs);
+    if index.settings().merge_doc_id_mappings {
+        let old_segment_ids: Vec<SegmentId> = segments.iter().map(Segment::id).collect();
+        let doc_id_mapping = merger.get_doc_id_from_concatenated_data()?;
+        MergeDocIdMapping::new(old_segment_ids, &doc_id_mapping)
+            .write(index.directory(), merged_segment_id)?;
+    }
+
+    // The merged segment keeps the routing key only if all of the merged segments share it,
+    // which is always the case for the merges started by the merge policy.
+    let first_routing_key = segment_entries[0].meta().routing_key();
+    let routing_key = if segment_entries
+        .iter()
+        .all(|segment_entry| segment_entry.meta().routing_key() == first_routing_key)
+    {
+        first_routing_key.map(str::to_string)
+    } else {
+        None
+    };
+    let segment_meta = index
+        .new_segment_meta(merged_segment_id, num_docs)
+        .with_field_term_stats(field_term_stats)
+        .with_routing_key(routing_key);
     Ok(Some(SegmentEntry::new(segment_meta, delete_cursor, None)))
 }
 
+/// Splits the segments into groups of segments sharing the same routing key.
+fn group_by_routing_key(segment_metas: Vec<SegmentMeta>) -> Vec<Vec<SegmentMeta>> {
+    let mut groups: BTreeMap<Option<String>, Vec<SegmentMeta>> = BTreeMap::new();
+    for segment_meta in segment_metas {
+        let routing_key = segment_meta.routing_key().map(str::to_string);
+        groups.entry(routing_key).or_default().push(segment_meta);
+    }
+    groups.into_values().collect()
+}
+
 /// Advanced: Merges a list of segments from different indices in a new index.
 ///
 /// Returns `TantivyError` if the indices list is empty or their
@@ -222,9 +284,11 @@ pub fn merge_filtered_segments<T: Into<Box<dyn Directory>>>(
     let merger: IndexMerger =
         IndexMerger::open_with_custom_alive_set(merged_index.schema(), segments, filter_doc_ids)?;
     let segment_serializer = SegmentSerializer::for_segment(merged_segment)?;
-    let num_docs = merger.write(segment_serializer)?;
+    let (num_docs, field_term_stats) = merger.write(segment_serializer)?;
 
-    let segment_meta = merged_index.new_segment_meta(merged_segment_id, num_docs);
+    let segment_meta = merged_index
+        .new_segment_meta(merged_segment_id, num_docs)
+        .with_field_term_stats(field_term_stats);
 
     let stats = format!(
         "Segments Merge: [{}]",
@@ -243,6 +307,7 @@ pub fn merge_filtered_segments<T: Into<Box<dyn Directory>>>(
         schema: target_schema,
         opstamp: 0u64,
         payload: Some(stats),
+        commit_history: Vec::new(),
     };
 
     // save the meta.json
@@ -251,6 +316,22 @@ pub fn merge_filtered_segments<T: Into<Box<dyn Directory>>>(
     Ok(merged_index)
 }
 
+/// Ends a running merge of the `MergeQueue` when dropped, and starts the next queued merges.
+///
+/// The merge is ended even if it panics, so that it does not count as running forever.
+struct RunningMergeGuard(SegmentUpdater);
+
+impl Drop for RunningMergeGuard {
+    fn drop(&mut self) {
+        self.0
+            .merge_queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .end_merge();
+        self.0.start_pending_merges();
+    }
+}
+
 pub(crate) struct InnerSegmentUpdater {
     // we keep a copy of the current active IndexMeta to
     // avoid loading the file every time we need it in the
@@ -260,11 +341,13 @@ pub(crate) struct InnerSegmentUpdater {
     // the unique active `SegmentUpdater`.
     active_index_meta: RwLock<Arc<IndexMeta>>,
     pool: ThreadPool,
-    merge_thread_pool: ThreadPool,
+    merge_thread_pool: Arc<ThreadPool>,
 
     index: Index,
     segment_manager: SegmentManager,
     merge_policy: RwLock<Arc<dyn MergePolicy>>,
+    merge_scheduler: RwLock<MergeScheduler>,
+    merge_queue: Mutex<MergeQueue>,
     killed: AtomicBool,
     stamper: Stamper,
     merge_operations: MergeOperationInventory,
@@ -287,15 +370,20 @@ impl SegmentUpdater {
                     "Failed to spawn segment updater thread".to_string(),
                 )
             })?;
-        let merge_thread_pool = ThreadPoolBuilder::new()
-            .thread_name(|i| format!("merge_thread_{i}"))
-            .num_threads(NUM_MERGE_THREADS)
-            .build()
-            .map_err(|_| {
-                crate::TantivyError::SystemError(
-                    "Failed to spawn segment merging thread".to_string(),
-                )
-            })?;
+        let merge_thread_pool = if let Some(merge_thread_pool) = index.merge_thread_pool() {
+            merge_thread_pool.clone()
+        } else {
+            let merge_thread_pool = ThreadPoolBuilder::new()
+                .thread_name(|i| format!("merge_thread_{i}"))
+                .num_threads(NUM_MERGE_THREADS)
+                .build()
+                .map_err(|_| {
+                    crate::TantivyError::SystemError(
+                        "Failed to spawn segment merging thread".to_string(),
+                    )
+                })?;
+            Arc::new(merge_thread_pool)
+        };
         let index_meta = index.load_metas()?;
         Ok(SegmentUpdater(Arc::new(InnerSegmentUpdater {
             active_index_meta: RwLock::new(Arc::new(index_meta)),
@@ -304,6 +392,8 @@ impl SegmentUpdater {
             index,
             segment_manager,
             merge_policy: RwLock::new(Arc::new(DefaultMergePolicy::default())),
+            merge_scheduler: RwLock::new(MergeScheduler::default()),
+            merge_queue: Mutex::new(MergeQueue::default()),
             killed: AtomicBool::new(false),
             stamper,
             merge_operations: Default::default(),
@@ -319,6 +409,16 @@ impl SegmentUpdater {
         *self.merge_policy.write().unwrap() = arc_merge_policy;
     }
 
+    pub fn get_merge_scheduler(&self) -> MergeScheduler {
+        self.merge_scheduler.read().unwrap().clone()
+    }
+
+    pub fn set_merge_scheduler(&self, merge_scheduler: MergeScheduler) {
+        *self.merge_scheduler.write().unwrap() = merge_scheduler;
+        // The new scheduler may allow more merges to run.
+        self.start_pending_merges();
+    }
+
     fn schedule_task<T: 'static + Send, F: FnOnce() -> crate::Result<T> + 'static + Send>(
         &self,
         task: F,
@@ -395,12 +495,16 @@ impl SegmentUpdater {
             //
             // Segment 1 from disk 1, Segment 1 from disk 2, etc.
             commited_segment_metas.sort_by_key(|segment_meta| -(segment_meta.max_doc() as i32));
+            let commit_history = self
+                .load_meta()
+                .next_commit_history(opstamp, index.settings().commit_history_size);
             let index_meta = IndexMeta {
                 index_settings: index.settings().clone(),
                 segments: commited_segment_metas,
                 schema: index.schema(),
                 opstamp,
                 payload: commit_message,
+                commit_history,
             };
             // TODO add context to the error.
             save_metas(&index_meta, directory.box_clone().borrow_mut())?;
@@ -414,18 +518,32 @@ impl SegmentUpdater {
         self.schedule_task(move || garbage_collect_files(self_clone))
     }
 
+    pub fn schedule_garbage_collect_dry_run(&self) -> FutureResult<GarbageCollectionDryRun> {
+        let self_clone = self.clone();
+        self.schedule_task(move || garbage_collect_files_dry_run(self_clone))
+    }
+
     /// List the files that are useful to the index.
     ///
     /// This does not include lock files, or files that are obsolete
     /// but have not yet been deleted by the garbage collector.
     fn list_files(&self) -> HashSet<PathBuf> {
-        let mut files: HashSet<PathBuf> = self
-            .index
-            .list_all_segment_metas()
-            .into_iter()
+        let segment_metas = self.index.list_all_segment_metas();
+        let mut files: HashSet<PathBuf> = segment_metas
+            .iter()
             .flat_map(|segment_meta| segment_meta.list_files())
             .collect();
+        // The doc id mapping of a merge lives as long as the segment it created.
+        files.extend(
+            segment_metas
+                .iter()
+                .map(|segment_meta| doc_id_mapping_filepath(segment_meta.id())),
+        );
         files.insert(META_FILEPATH.to_path_buf());
+        files.extend(oplog_living_files(
+            self.index.directory(),
+            self.index.settings().oplog_retained_commits,
+        ));
         files
     }
 
@@ -463,7 +581,7 @@ impl SegmentUpdater {
     // The calling thread should not be block for a long time, as this only involve waiting for the
     // `SegmentUpdater` queue which in turns only contains lightweight operations.
     //
-    // The merge itself happens on a different thread.
+    // The merge itself happens on a different thread, when the `MergeScheduler` lets it start.
     //
     // When successful, this function returns a `Future` for a `Result<SegmentMeta>` that represents
     // the actual outcome of the merge operation.
@@ -484,7 +602,6 @@ impl SegmentUpdater {
             "Segment_ids cannot be empty."
         );
 
-        let segment_updater = self.clone();
         let segment_entries: Vec<SegmentEntry> = match self
             .segment_manager
             .start_merge(merge_operation.segment_ids())
@@ -504,16 +621,46 @@ impl SegmentUpdater {
         let (scheduled_result, merging_future_send) =
             FutureResult::create("Merge operation failed.");
 
+        self.merge_queue.lock().unwrap().push(PendingMerge {
+            merge_operation,
+            segment_entries,
+            merging_future_send,
+        });
+        self.start_pending_merges();
+
+        scheduled_result
+    }
+
+    /// Starts the queued merges, as long as the `MergeScheduler` allows it.
+    fn start_pending_merges(&self) {
+        let merge_scheduler = self.get_merge_scheduler();
+        let mut merge_queue = self.merge_queue.lock().unwrap();
+        while let Some(pending_merge) = merge_queue.start_next_merge(&merge_scheduler) {
+            self.spawn_merge(pending_merge);
+        }
+    }
+
+    fn spawn_merge(&self, pending_merge: PendingMerge) {
+        let segment_updater = self.clone();
         self.merge_thread_pool.spawn(move || {
+            let PendingMerge {
+                merge_operation,
+                segment_entries,
+                merging_future_send,
+            } = pending_merge;
             // The fact that `merge_operation` is moved here is important.
             // Its lifetime is used to track how many merging thread are currently running,
             // as well as which segment is currently in merge and therefore should not be
             // candidate for another merge.
-            match merge(
+            let running_merge_guard = RunningMergeGuard(segment_updater.clone());
+            let merge_result = merge(
                 &segment_updater.index,
                 segment_entries,
                 merge_operation.target_opstamp(),
-            ) {
+            );
+            // The merge thread is free again: let the next queued merge start.
+            drop(running_merge_guard);
+            match merge_result {
                 Ok(after_merge_segment_entry) => {
                     let res = segment_updater.end_merge(merge_operation, after_merge_segment_entry);
                     let _send_result = merging_future_send.send(res);
@@ -531,8 +678,6 @@ impl SegmentUpdater {
                 }
             }
         });
-
-        scheduled_result
     }
 
     pub(crate) fn get_mergeable_segments(&self) -> (Vec<SegmentMeta>, Vec<SegmentMeta>) {
@@ -548,19 +693,20 @@ impl SegmentUpdater {
         // We therefore consider merges using these two sets of segments independently.
         let merge_policy = self.get_merge_policy();
 
+        // Segments with different routing keys are never merged together either.
         let current_opstamp = self.stamper.stamp();
-        let mut merge_candidates: Vec<MergeOperation> = merge_policy
-            .compute_merge_candidates(&uncommitted_segments)
+        let mut merge_candidates: Vec<MergeOperation> = group_by_routing_key(uncommitted_segments)
             .into_iter()
+            .flat_map(|segments| merge_policy.compute_merge_candidates(&segments))
             .map(|merge_candidate| {
                 MergeOperation::new(&self.merge_operations, current_opstamp, merge_candidate.0)
             })
             .collect();
 
         let commit_opstamp = self.load_meta().opstamp;
-        let committed_merge_candidates = merge_policy
-            .compute_merge_candidates(&committed_segments)
+        let committed_merge_candidates = group_by_routing_key(committed_segments)
             .into_iter()
+            .flat_map(|segments| merge_policy.compute_merge_candidates(&segments))
             .map(|merge_candidate: MergeCandidate| {
                 MergeOperation::new(&self.merge_operations, commit_opstamp, merge_candidate.0)
             });
@@ -1,5 +1,5 @@
 use columnar::MonotonicallyMappableToU64;
-use common::JsonPathWriter;
+use common::{DateTime, JsonPathWriter};
 use itertools::Itertools;
 use tokenizer_api::BoxTokenStream;
 
@@ -10,11 +10,13 @@ use crate::index::{Segment, SegmentComponent};
 use crate::indexer::segment_serializer::SegmentSerializer;
 use crate::json_utils::{index_json_value, IndexingPositionsPerPath};
 use crate::postings::{
-    compute_table_memory_size, serialize_postings, IndexingContext, IndexingPosition,
-    PerFieldPostingsWriter, PostingsWriter,
+    compute_table_memory_size, serialize_postings, FieldTermStats, IndexingContext,
+    IndexingPosition, PerFieldPostingsWriter, PostingsWriter,
 };
 use crate::schema::document::{Document, Value};
-use crate::schema::{FieldEntry, FieldType, Schema, Term, DATE_TIME_PRECISION_INDEXED};
+use crate::schema::{
+    Facet, FieldEntry, FieldType, Schema, Term, DATE_TIME_PRECISION_INDEXED, DEFAULT_POSITION_GAP,
+};
 use crate::tokenizer::{FacetTokenizer, PreTokenizedStream, TextAnalyzer, Tokenizer};
 use crate::{DocId, Opstamp, TantivyError};
 
@@ -121,9 +123,12 @@ impl SegmentWriter {
     ///
     /// Finalize consumes the `SegmentWriter`, so that it cannot
     /// be used afterwards.
-    pub fn finalize(mut self) -> crate::Result<Vec<u64>> {
+    ///
+    /// Returns the opstamps of the documents, and the statistics
+    /// about the terms of the indexed fields.
+    pub fn finalize(mut self) -> crate::Result<(Vec<Opstamp>, Vec<FieldTermStats>)> {
         self.fieldnorms_writer.fill_up_to_max_doc(self.max_doc);
-        remap_and_write(
+        let field_term_stats = remap_and_write(
             self.schema,
             &self.per_field_postings_writers,
             self.ctx,
@@ -131,7 +136,7 @@ impl SegmentWriter {
             &self.fieldnorms_writer,
             self.segment_serializer,
         )?;
-        Ok(self.doc_opstamps)
+        Ok((self.doc_opstamps, field_term_stats))
     }
 
     /// Returns an estimation of the current memory usage of the segment writer.
@@ -146,9 +151,20 @@ impl SegmentWriter {
     fn index_document<D: Document>(&mut self, doc: &D) -> crate::Result<()> {
         let doc_id = self.max_doc;
 
+        // The values of the fields copied to other fields are indexed in the target fields
+        // as well.
+        let schema = &self.schema;
         // TODO: Can this be optimised a bit?
         let vals_grouped_by_field = doc
             .iter_fields_and_values()
+            .flat_map(|(field, value)| {
+                let copies: Vec<_> = schema
+                    .copy_to_fields(field)
+                    .iter()
+                    .map(|target| (*target, value.clone()))
+                    .collect();
+                std::iter::once((field, value)).chain(copies)
+            })
             .sorted_by_key(|(field, _)| *field)
             .group_by(|(field, _)| *field);
 
@@ -187,10 +203,16 @@ impl SegmentWriter {
                             term_buffer,
                             ctx,
                             &mut indexing_position,
+                            DEFAULT_POSITION_GAP,
                         );
                     }
                 }
-                FieldType::Str(_) => {
+                FieldType::Str(text_options) => {
+                    let position_gap = text_options
+                        .get_indexing_options()
+                        .map_or(DEFAULT_POSITION_GAP, |indexing_options| {
+                            indexing_options.position_gap()
+                        });
                     let mut indexing_position = IndexingPosition::default();
                     for value in values {
                         let value = value.as_value();
@@ -212,6 +234,7 @@ impl SegmentWriter {
                             term_buffer,
                             ctx,
                             &mut indexing_position,
+                            position_gap,
                         );
                     }
                     if field_entry.has_fieldnorms() {
@@ -342,6 +365,46 @@ impl SegmentWriter {
         Ok(())
     }
 
+    /// Indexes the facets derived from the date fields of the document.
+    ///
+    /// See [`DateOptions::set_date_facet`](crate::schema::DateOptions::set_date_facet).
+    fn index_date_facets<D: Document>(&mut self, doc: &D) {
+        let doc_id = self.max_doc;
+        for (field, value) in doc.iter_fields_and_values() {
+            let Some(facet_field) = self.schema.date_facet_field(field) else {
+                continue;
+            };
+            let Some(date) = value.as_value().as_datetime() else {
+                continue;
+            };
+            let facet = date_facet(date);
+            self.fast_field_writers
+                .add_derived_facet(doc_id, facet_field, &facet);
+            let facet_field_entry = self.schema.get_field_entry(facet_field);
+            if !facet_field_entry.is_indexed() {
+                continue;
+            }
+            let (term_buffer, ctx) = (&mut self.term_buffer, &mut self.ctx);
+            let postings_writer: &mut dyn PostingsWriter = self
+                .per_field_postings_writers
+                .get_for_field_mut(facet_field);
+            term_buffer.clear_with_field_and_type(
+                facet_field_entry.field_type().value_type(),
+                facet_field,
+            );
+            let mut facet_tokenizer = FacetTokenizer::default();
+            let mut token_stream = facet_tokenizer.token_stream(facet.encoded_str());
+            postings_writer.index_text(
+                doc_id,
+                &mut token_stream,
+                term_buffer,
+                ctx,
+                &mut IndexingPosition::default(),
+                DEFAULT_POSITION_GAP,
+            );
+        }
+    }
+
     /// Indexes a new document
     ///
     /// As a user, you should rather use `IndexWriter`'s add_document.
@@ -353,6 +416,7 @@ impl SegmentWriter {
         self.doc_opstamps.push(opstamp);
         self.fast_field_writers.add_document(&document)?;
         self.index_document(&document)?;
+        self.index_date_facets(&document);
         let doc_writer = self.segment_serializer.get_store_writer();
         doc_writer.store(&document, &self.schema)?;
         self.max_doc += 1;
@@ -380,6 +444,16 @@ impl SegmentWriter {
     }
 }
 
+/// Returns the facet of the day of a date in UTC, e.g. `/2017/05/12`.
+fn date_facet(date: DateTime) -> Facet {
+    let date = date.into_utc();
+    Facet::from_path([
+        date.year().to_string(),
+        format!("{:02}", u8::from(date.month())),
+        format!("{:02}", date.day()),
+    ])
+}
+
 /// This method is used as a trick to workaround the borrow checker
 /// Writes a view of a segment by pushing information
 /// to the `SegmentSerializer`.
@@ -392,7 +466,7 @@ fn remap_and_write(
     fast_field_writers: FastFieldsWriter,
     fieldnorms_writer: &FieldNormsWriter,
     mut serializer: SegmentSerializer,
-) -> crate::Result<()> {
+) -> crate::Result<Vec<FieldTermStats>> {
     debug!("remap-and-write");
     if let Some(fieldnorms_serializer) = serializer.extract_fieldnorms_serializer() {
         fieldnorms_writer.serialize(fieldnorms_serializer)?;
@@ -412,9 +486,7 @@ fn remap_and_write(
     fast_field_writers.serialize(serializer.get_fast_field_write())?;
 
     debug!("serializer-close");
-    serializer.close()?;
-
-    Ok(())
+    serializer.close()
 }
 
 #[cfg(test)]
@@ -428,7 +500,7 @@ mod tests {
     use crate::directory::RamDirectory;
     use crate::fastfield::FastValue;
     use crate::postings::{Postings, TermInfo};
-    use crate::query::{PhraseQuery, QueryParser};
+    use crate::query::{PhraseQuery, QueryParser, TermQuery};
     use crate::schema::{
         Document, IndexRecordOption, OwnedValue, Schema, TextFieldIndexing, TextOptions, Value,
         STORED, STRING, TEXT,
@@ -494,6 +566,87 @@ mod tests {
             Some("title")
         );
     }
+
+    #[test]
+    fn test_stored_only_fields() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let payload = schema_builder.add_text_field("payload", STORED);
+        let raw = schema_builder.add_bytes_field("raw", STORED);
+        let schema = schema_builder.build();
+        assert!(!schema.get_field_entry(payload).is_indexed());
+        assert!(!schema.get_field_entry(raw).is_indexed());
+        let index = Index::create_in_ram(schema);
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            title => "hello",
+            payload => "hello payload",
+            raw => vec![1u8, 2, 3],
+        ))?;
+        index_writer.commit()?;
+
+        // Stored-only fields are not tokenized, and do not get any term.
+        let segment_metas = index.searchable_segment_metas()?;
+        assert!(segment_metas[0].field_term_stats(title).is_some());
+        assert!(segment_metas[0].field_term_stats(payload).is_none());
+        assert!(segment_metas[0].field_term_stats(raw).is_none());
+        let searcher = index.reader()?.searcher();
+        let space_usage = searcher.segment_reader(0).space_usage()?;
+        let termdict_fields: Vec<_> = space_usage
+            .termdict()
+            .fields()
+            .filter(|(_, field_usage)| field_usage.total() > 0u64)
+            .map(|(field, _)| *field)
+            .collect();
+        assert_eq!(termdict_fields, vec![title]);
+        let query = QueryParser::for_index(&index, vec![title]).parse_query("payload")?;
+        assert_eq!(searcher.search(&query, &Count)?, 0);
+
+        // They travel with the documents.
+        let doc: TantivyDocument = searcher.doc(DocAddress::new(0, 0))?;
+        assert_eq!(
+            doc.get_first(payload).unwrap().as_value().as_str(),
+            Some("hello payload")
+        );
+        assert_eq!(
+            doc.get_first(raw).unwrap().as_value().as_bytes(),
+            Some(&[1u8, 2, 3][..])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_to_fields() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title =
+            schema_builder.add_text_field("title", (TEXT | STORED).set_copy_to("title_exact"));
+        let title_exact = schema_builder.add_text_field("title_exact", STRING | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "Hello World"))?;
+        index_writer.add_document(doc!(title => "hello", title_exact => "Custom Value"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let count = |field, text| {
+            let query =
+                TermQuery::new(Term::from_field_text(field, text), IndexRecordOption::Basic);
+            searcher.search(&query, &Count)
+        };
+        assert_eq!(count(title, "hello")?, 2);
+        assert_eq!(count(title, "world")?, 1);
+        // The copied values are indexed with the analyzer of the target field.
+        assert_eq!(count(title_exact, "Hello World")?, 1);
+        assert_eq!(count(title_exact, "hello")?, 1);
+        assert_eq!(count(title_exact, "Custom Value")?, 1);
+        assert_eq!(count(title, "custom")?, 0);
+
+        // The copied values are not stored in the target field.
+        let doc: TantivyDocument = searcher.doc(DocAddress::new(0, 0))?;
+        assert!(doc.get_first(title_exact).is_none());
+        Ok(())
+    }
+
     #[test]
     fn test_simple_json_indexing() {
         let mut schema_builder = Schema::builder();
@@ -1011,4 +1164,83 @@ mod tests {
             "Schema error: 'Error getting tokenizer for field: title'"
         );
     }
+
+    #[test]
+    fn test_date_facets() -> crate::Result<()> {
+        use crate::collector::FacetCollector;
+        use crate::query::AllQuery;
+        use crate::schema::{DateOptions, Facet, FacetOptions, INDEXED};
+
+        let mut schema_builder = Schema::builder();
+        let date = schema_builder.add_date_field(
+            "date",
+            DateOptions::from(INDEXED).set_date_facet("date_facet"),
+        );
+        let date_facet = schema_builder.add_facet_field("date_facet", FacetOptions::default());
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for date_str in [
+            "2017-05-12T10:00:00Z",
+            "2017-05-12T23:30:00-02:00",
+            "2017-11-02T08:00:00Z",
+            "2018-01-01T00:00:00Z",
+        ] {
+            let date_time = OffsetDateTime::parse(date_str, &Rfc3339).unwrap();
+            index_writer.add_document(doc!(date => DateTime::from_utc(date_time)))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let mut facet_collector = FacetCollector::for_field("date_facet");
+        facet_collector.add_facet("/2017");
+        let facet_counts = searcher.search(&AllQuery, &facet_collector)?;
+        let months: Vec<(&Facet, u64)> = facet_counts.get("/2017").collect();
+        assert_eq!(
+            months,
+            vec![(&Facet::from("/2017/05"), 2), (&Facet::from("/2017/11"), 1)]
+        );
+        // Facets are derived in UTC.
+        let day_query = |facet: &str| {
+            TermQuery::new(
+                Term::from_facet(date_facet, &Facet::from(facet)),
+                IndexRecordOption::Basic,
+            )
+        };
+        assert_eq!(searcher.search(&day_query("/2017/05/12"), &Count)?, 1);
+        assert_eq!(searcher.search(&day_query("/2017/05/13"), &Count)?, 1);
+        assert_eq!(searcher.search(&day_query("/2018"), &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "which is not a facet field")]
+    fn test_date_facets_into_non_facet_field() {
+        use crate::schema::{DateOptions, INDEXED};
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_date_field("date", DateOptions::from(INDEXED).set_date_facet("title"));
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.build();
+    }
+
+    #[test]
+    fn test_date_facets_deserialize_errors() {
+        use crate::schema::{DateOptions, FacetOptions, INDEXED};
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_date_field("date", DateOptions::from(INDEXED).set_date_facet("day"));
+        schema_builder.add_facet_field("day", FacetOptions::default());
+        let schema_json = serde_json::to_string(&schema_builder.build()).unwrap();
+        // A schema loaded from `meta.json` must not panic if it is inconsistent.
+        let unknown_field_json =
+            schema_json.replace(r#""date_facet":"day""#, r#""date_facet":"missing""#);
+        assert_ne!(unknown_field_json, schema_json);
+        let err = serde_json::from_str::<Schema>(&unknown_field_json).unwrap_err();
+        assert!(err.to_string().contains("unknown field missing"));
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_date_field("date", DateOptions::from(INDEXED).set_date_facet("title"));
+        schema_builder.add_text_field("title", TEXT);
+        assert!(schema_builder.try_build().is_err());
+    }
 }
@@ -39,15 +39,20 @@ impl<D: Document> SingleSegmentIndexWriter<D> {
 
     pub fn finalize(self) -> crate::Result<Index> {
         let max_doc = self.segment_writer.max_doc();
-        self.segment_writer.finalize()?;
+        let (_, field_term_stats) = self.segment_writer.finalize()?;
         let segment: Segment = self.segment.with_max_doc(max_doc);
         let index = segment.index();
+        let segment_meta = segment
+            .meta()
+            .clone()
+            .with_field_term_stats(field_term_stats);
         let index_meta = IndexMeta {
             index_settings: index.settings().clone(),
-            segments: vec![segment.meta().clone()],
+            segments: vec![segment_meta],
             schema: index.schema(),
             opstamp: 0,
             payload: None,
+            commit_history: Vec::new(),
         };
         save_metas(&index_meta, index.directory())?;
         index.directory().sync_directory()?;
@@ -204,7 +204,7 @@ pub mod termdict;
 
 mod reader;
 
-pub use self::reader::{IndexReader, IndexReaderBuilder, ReloadPolicy, Warmer};
+pub use self::reader::{AdaptiveWarmer, IndexReader, IndexReaderBuilder, ReloadPolicy, Warmer};
 pub mod snippet;
 
 mod docset;
@@ -218,12 +218,15 @@ use serde::{Deserialize, Serialize};
 pub use self::docset::{DocSet, COLLECT_BLOCK_BUFFER_LEN, TERMINATED};
 #[doc(hidden)]
 pub use crate::core::json_utils;
-pub use crate::core::{Executor, Searcher, SearcherGeneration};
+pub use crate::core::{
+    CancellationToken, Executor, FastFieldValuesOrder, SearchStream, Searcher, SearcherGeneration,
+};
 pub use crate::directory::Directory;
 #[allow(deprecated)] // Remove with index sorting
 pub use crate::index::{
-    Index, IndexBuilder, IndexMeta, IndexSettings, InvertedIndexReader, Order, Segment,
-    SegmentMeta, SegmentReader,
+    CommitManifest, CommitMeta, ComponentMigration, InMemoryIndex, InMemoryIndexWriter, Index,
+    IndexBuilder, IndexMeta, IndexSettings, InvertedIndexReader, ManifestFile, Order,
+    ReplicaSyncResult, ReplicaSyncer, ReplicaTransport, Segment, SegmentMeta, SegmentReader,
 };
 pub use crate::indexer::{IndexWriter, SingleSegmentIndexWriter};
 pub use crate::schema::{Document, TantivyDocument, Term};
@@ -282,7 +285,8 @@ pub fn version_string() -> &'static str {
 /// Defines tantivy's merging strategy
 pub mod merge_policy {
     pub use crate::indexer::{
-        DefaultMergePolicy, LogMergePolicy, MergeCandidate, MergePolicy, NoMergePolicy,
+        DefaultMergePolicy, LogMergePolicy, MergeCandidate, MergePolicy, MergeScheduler,
+        NoMergePolicy,
     };
 }
 
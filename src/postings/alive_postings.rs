@@ -0,0 +1,136 @@
+use crate::docset::DocSet;
+use crate::fastfield::AliveBitSet;
+use crate::postings::{Postings, SegmentPostings};
+use crate::{DocId, TERMINATED};
+
+/// Postings skipping the deleted documents of the segment.
+///
+/// The postings returned by
+/// [`InvertedIndexReader::read_postings`](crate::InvertedIndexReader::read_postings) include the
+/// deleted documents, which are usually filtered out by the collectors. `AlivePostings`, as
+/// returned by [`SegmentReader::read_postings`](crate::SegmentReader::read_postings), filters them
+/// out itself, which is handy to implement custom queries or to inspect the postings of a term.
+#[derive(Clone)]
+pub struct AlivePostings<TPostings: Postings = SegmentPostings> {
+    postings: TPostings,
+    alive_bitset_opt: Option<AliveBitSet>,
+}
+
+impl<TPostings: Postings> AlivePostings<TPostings> {
+    /// Wraps the postings, skipping the documents deleted in `alive_bitset_opt`.
+    pub fn new(postings: TPostings, alive_bitset_opt: Option<AliveBitSet>) -> Self {
+        let mut alive_postings = AlivePostings {
+            postings,
+            alive_bitset_opt,
+        };
+        alive_postings.skip_deleted_docs();
+        alive_postings
+    }
+
+    /// Returns the wrapped postings.
+    pub fn into_inner(self) -> TPostings {
+        self.postings
+    }
+
+    fn is_alive(&self, doc: DocId) -> bool {
+        self.alive_bitset_opt
+            .as_ref()
+            .map_or(true, |alive_bitset| alive_bitset.is_alive(doc))
+    }
+
+    /// Advances the postings until they are positioned on an alive document.
+    fn skip_deleted_docs(&mut self) -> DocId {
+        let mut doc = self.postings.doc();
+        while doc != TERMINATED && !self.is_alive(doc) {
+            doc = self.postings.advance();
+        }
+        doc
+    }
+}
+
+impl<TPostings: Postings> DocSet for AlivePostings<TPostings> {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance();
+        self.skip_deleted_docs()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target);
+        self.skip_deleted_docs()
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl<TPostings: Postings> Postings for AlivePostings<TPostings> {
+    fn term_freq(&self) -> u32 {
+        self.postings.term_freq()
+    }
+
+    fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>) {
+        self.postings.positions_with_offset(offset, output);
+    }
+
+    fn has_positions(&self) -> bool {
+        self.postings.has_positions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::docset::{DocSet, TERMINATED};
+    use crate::postings::Postings;
+    use crate::schema::{IndexRecordOption, Schema, STRING, TEXT};
+    use crate::{doc, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_alive_postings_skip_deleted_docs() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..5 {
+            index_writer.add_document(doc!(id => i.to_string(), text => "hello hello"))?;
+        }
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(id, "0"));
+        index_writer.delete_term(Term::from_field_text(id, "3"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let term = Term::from_field_text(text, "hello");
+
+        let mut postings = segment_reader
+            .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)?
+            .unwrap();
+        assert_eq!(postings.doc(), 1);
+        assert_eq!(postings.term_freq(), 2);
+        let mut positions = Vec::new();
+        postings.positions(&mut positions);
+        assert_eq!(positions, vec![0, 1]);
+        assert_eq!(postings.seek(3), 4);
+        assert_eq!(postings.advance(), TERMINATED);
+
+        let mut postings = segment_reader
+            .read_postings(&term, IndexRecordOption::Basic)?
+            .unwrap();
+        let mut docs = Vec::new();
+        while postings.doc() != TERMINATED {
+            docs.push(postings.doc());
+            postings.advance();
+        }
+        assert_eq!(docs, vec![1, 2, 4]);
+        let missing_term = Term::from_field_text(text, "missing");
+        assert!(segment_reader
+            .read_postings(&missing_term, IndexRecordOption::Basic)?
+            .is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,334 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::schema::Field;
+
+/// Maximum number of most frequent terms recorded per field.
+const MAX_NUM_TOP_TERMS: usize = 100;
+
+/// A term, and the number of documents containing it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopTerm {
+    /// Text of the term.
+    pub term: String,
+    /// Number of documents containing the term.
+    pub doc_freq: u32,
+}
+
+/// Statistics about the terms of an indexed field within a segment.
+///
+/// They are computed when the segment is written (on flush or merge) and
+/// stored in the segment metadata, so that the selectivity of a field can be
+/// estimated without scanning its term dictionary.
+///
+/// See [`SegmentMeta::field_term_stats`](crate::index::SegmentMeta::field_term_stats).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldTermStats {
+    field: Field,
+    num_terms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    total_num_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    top_terms: Vec<TopTerm>,
+}
+
+impl FieldTermStats {
+    /// Returns the field these statistics are about.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Returns the number of distinct terms of the field in the segment.
+    pub fn num_terms(&self) -> u64 {
+        self.num_terms
+    }
+
+    /// Returns the total number of tokens of the field in the segment, used by BM25 to
+    /// compute the average length of the field.
+    ///
+    /// After a merge, the tokens of the documents deleted in the merged segments are
+    /// estimated from their fieldnorms. Returns `None` for the segments written by a
+    /// version of tantivy that did not record it.
+    pub fn total_num_tokens(&self) -> Option<u64> {
+        self.total_num_tokens
+    }
+
+    /// Returns the most frequent terms of the field in the segment,
+    /// sorted by decreasing document frequency.
+    ///
+    /// The most frequent terms are only recorded for text fields, if
+    /// [`IndexSettings::num_top_terms_per_field`](crate::IndexSettings::num_top_terms_per_field)
+    /// is set.
+    pub fn top_terms(&self) -> &[TopTerm] {
+        &self.top_terms
+    }
+}
+
+/// Computes the [`FieldTermStats`] of a field, as its terms are serialized.
+pub(crate) struct FieldTermStatsCollector {
+    field: Field,
+    num_terms: u64,
+    total_num_tokens: u64,
+    num_top_terms: usize,
+    // Min-heap over (doc_freq, term). On ties, the largest term is evicted first,
+    // so that we keep the first terms in lexicographical order.
+    top_terms_opt: Option<BinaryHeap<Reverse<(u32, Reverse<Vec<u8>>)>>>,
+}
+
+impl FieldTermStatsCollector {
+    pub fn new(
+        field: Field,
+        total_num_tokens: u64,
+        num_top_terms: usize,
+    ) -> FieldTermStatsCollector {
+        let num_top_terms = num_top_terms.min(MAX_NUM_TOP_TERMS);
+        FieldTermStatsCollector {
+            field,
+            num_terms: 0,
+            total_num_tokens,
+            num_top_terms,
+            top_terms_opt: if num_top_terms > 0 {
+                Some(BinaryHeap::with_capacity(num_top_terms + 1))
+            } else {
+                None
+            },
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn record(&mut self, term: &[u8], doc_freq: u32) {
+        self.num_terms += 1;
+        let Some(top_terms) = self.top_terms_opt.as_mut() else {
+            return;
+        };
+        if top_terms.len() >= self.num_top_terms {
+            match top_terms.peek() {
+                Some(Reverse((min_doc_freq, _))) if *min_doc_freq < doc_freq => {
+                    top_terms.pop();
+                }
+                _ => return,
+            }
+        }
+        top_terms.push(Reverse((doc_freq, Reverse(term.to_vec()))));
+    }
+
+    pub fn finish(self) -> FieldTermStats {
+        let top_terms = self
+            .top_terms_opt
+            .map(|top_terms| {
+                // `into_sorted_vec` sorts by increasing `Reverse` order, that is
+                // by decreasing doc freq.
+                top_terms
+                    .into_sorted_vec()
+                    .into_iter()
+                    .filter_map(|Reverse((doc_freq, Reverse(term)))| {
+                        let term = String::from_utf8(term).ok()?;
+                        Some(TopTerm { term, doc_freq })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        FieldTermStats {
+            field: self.field,
+            num_terms: self.num_terms,
+            total_num_tokens: Some(self.total_num_tokens),
+            top_terms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldTermStats, FieldTermStatsCollector, TopTerm, MAX_NUM_TOP_TERMS};
+    use crate::schema::{Field, Schema, FAST, INDEXED, STRING, TEXT};
+    use crate::{Index, IndexSettings, IndexWriter, Term};
+
+    #[test]
+    fn test_field_term_stats_collector() {
+        let mut collector = FieldTermStatsCollector::new(Field::from_field_id(0), 0, 10);
+        for i in 0..100u32 {
+            collector.record(format!("term{i:03}").as_bytes(), i % 7);
+        }
+        let stats = collector.finish();
+        assert_eq!(stats.num_terms(), 100);
+        assert_eq!(stats.top_terms().len(), 10);
+        assert_eq!(
+            &stats.top_terms()[..3],
+            &[
+                TopTerm {
+                    term: "term006".to_string(),
+                    doc_freq: 6
+                },
+                TopTerm {
+                    term: "term013".to_string(),
+                    doc_freq: 6
+                },
+                TopTerm {
+                    term: "term020".to_string(),
+                    doc_freq: 6
+                },
+            ]
+        );
+        assert!(stats
+            .top_terms()
+            .iter()
+            .all(|top_term| top_term.doc_freq == 6));
+    }
+
+    #[test]
+    fn test_field_term_stats_collector_num_top_terms() {
+        let record_terms = |num_top_terms: usize| {
+            let mut collector =
+                FieldTermStatsCollector::new(Field::from_field_id(0), 0, num_top_terms);
+            for i in 0..1_000u32 {
+                collector.record(format!("term{i:04}").as_bytes(), i);
+            }
+            collector.finish()
+        };
+        assert!(record_terms(0).top_terms().is_empty());
+        assert_eq!(record_terms(3).top_terms()[0].term, "term0999");
+        assert_eq!(record_terms(3).top_terms().len(), 3);
+        assert_eq!(
+            record_terms(usize::MAX).top_terms().len(),
+            MAX_NUM_TOP_TERMS
+        );
+    }
+
+    #[test]
+    fn test_field_term_stats_in_segment_meta() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let id = schema_builder.add_text_field("id", STRING);
+        let num = schema_builder.add_u64_field("num", INDEXED);
+        let not_indexed = schema_builder.add_u64_field("not_indexed", FAST);
+        let settings = IndexSettings {
+            num_top_terms_per_field: 10,
+            ..IndexSettings::default()
+        };
+        let index = Index::builder()
+            .schema(schema_builder.build())
+            .settings(settings)
+            .create_in_ram()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..20u64 {
+            let body = if i % 2 == 0 { "hello world" } else { "hello" };
+            index_writer.add_document(doc!(
+                text => body,
+                id => format!("id{i}"),
+                num => i % 4,
+                not_indexed => i,
+            ))?;
+        }
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "happy world"))?;
+        index_writer.commit()?;
+
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 2);
+        let segment_meta = segment_metas
+            .iter()
+            .find(|segment_meta| segment_meta.max_doc() == 20)
+            .unwrap();
+        let text_stats = segment_meta.field_term_stats(text).unwrap();
+        assert_eq!(text_stats.num_terms(), 2);
+        assert_eq!(
+            text_stats.top_terms(),
+            &[
+                TopTerm {
+                    term: "hello".to_string(),
+                    doc_freq: 20
+                },
+                TopTerm {
+                    term: "world".to_string(),
+                    doc_freq: 10
+                },
+            ]
+        );
+        assert_eq!(segment_meta.field_term_stats(id).unwrap().num_terms(), 20);
+        let num_stats = segment_meta.field_term_stats(num).unwrap();
+        assert_eq!(num_stats.num_terms(), 4);
+        assert!(num_stats.top_terms().is_empty());
+        assert!(segment_meta.field_term_stats(not_indexed).is_none());
+
+        // Statistics survive reloading the metas, and are recomputed on merge.
+        let segment_ids = index.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+        index_writer.wait_merging_threads()?;
+        let segment_metas = index.load_metas()?.segments;
+        assert_eq!(segment_metas.len(), 1);
+        let text_stats = segment_metas[0].field_term_stats(text).unwrap();
+        assert_eq!(text_stats.num_terms(), 3);
+        assert_eq!(text_stats.top_terms()[0].doc_freq, 20);
+        assert_eq!(text_stats.top_terms()[1].doc_freq, 11);
+        Ok(())
+    }
+
+    #[test]
+    fn test_field_term_stats_total_num_tokens() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a b c", id => "0"))?;
+        index_writer.add_document(doc!(text => "a b c d e f g", id => "1"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "a b", id => "2"))?;
+        index_writer.commit()?;
+
+        let segment_metas = index.searchable_segment_metas()?;
+        let total_num_tokens: u64 = segment_metas
+            .iter()
+            .map(|segment_meta| {
+                segment_meta
+                    .field_term_stats(text)
+                    .unwrap()
+                    .total_num_tokens()
+                    .unwrap()
+            })
+            .sum();
+        assert_eq!(total_num_tokens, 12);
+        let segment_meta = segment_metas
+            .iter()
+            .find(|segment_meta| segment_meta.max_doc() == 2)
+            .unwrap();
+        assert_eq!(segment_meta.average_field_length(text), Some(5.0));
+
+        // The tokens of the deleted document are not counted in the merged segment.
+        index_writer.delete_term(Term::from_field_text(id, "1"));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+        index_writer.wait_merging_threads()?;
+        let segment_metas = index.load_metas()?.segments;
+        assert_eq!(segment_metas.len(), 1);
+        let text_stats = segment_metas[0].field_term_stats(text).unwrap();
+        assert_eq!(text_stats.total_num_tokens(), Some(5));
+        // The most frequent terms are not recorded by default.
+        assert!(text_stats.top_terms().is_empty());
+        assert_eq!(segment_metas[0].average_field_length(text), Some(2.5));
+        let searcher = index.reader()?.searcher();
+        assert_eq!(
+            searcher
+                .segment_reader(0)
+                .inverted_index(text)?
+                .total_num_tokens(),
+            5
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_field_term_stats_without_total_num_tokens() {
+        // Statistics recorded by a version of tantivy that did not count the tokens.
+        let field_term_stats: FieldTermStats =
+            serde_json::from_str(r#"{"field":0,"num_terms":3}"#).unwrap();
+        assert_eq!(field_term_stats.num_terms(), 3);
+        assert_eq!(field_term_stats.total_num_tokens(), None);
+    }
+}
@@ -46,6 +46,7 @@ impl<Rec: Recorder> PostingsWriter for JsonPostingsWriter<Rec> {
         term_buffer: &mut Term,
         ctx: &mut IndexingContext,
         indexing_position: &mut IndexingPosition,
+        position_gap: u32,
     ) {
         self.str_posting_writer.index_text(
             doc_id,
@@ -53,6 +54,7 @@ impl<Rec: Recorder> PostingsWriter for JsonPostingsWriter<Rec> {
             term_buffer,
             ctx,
             indexing_position,
+            position_gap,
         );
     }
 
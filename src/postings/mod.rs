@@ -1,11 +1,13 @@
 //! Postings module (also called inverted index)
 
+mod alive_postings;
 mod block_search;
 
 pub(crate) use self::block_search::branchless_binary_search;
 
 mod block_segment_postings;
 pub(crate) mod compression;
+mod field_term_stats;
 mod indexing_context;
 mod json_postings_writer;
 mod per_field_postings_writer;
@@ -16,10 +18,13 @@ mod segment_postings;
 mod serializer;
 mod skip;
 mod term_info;
+mod top_impacts;
 
 pub(crate) use stacker::compute_table_memory_size;
 
+pub use self::alive_postings::AlivePostings;
 pub use self::block_segment_postings::BlockSegmentPostings;
+pub use self::field_term_stats::{FieldTermStats, TopTerm};
 pub(crate) use self::indexing_context::IndexingContext;
 pub(crate) use self::per_field_postings_writer::PerFieldPostingsWriter;
 pub use self::postings::Postings;
@@ -28,6 +33,7 @@ pub use self::segment_postings::SegmentPostings;
 pub use self::serializer::{FieldSerializer, InvertedIndexSerializer};
 pub(crate) use self::skip::{BlockInfo, SkipReader};
 pub use self::term_info::TermInfo;
+pub(crate) use self::top_impacts::TopImpactsReader;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, PartialEq, Clone, Copy, Eq)]
@@ -42,17 +48,19 @@ pub mod tests {
     use std::mem;
 
     use super::{InvertedIndexSerializer, Postings};
+    use crate::collector::Count;
     use crate::docset::{DocSet, TERMINATED};
     use crate::fieldnorm::FieldNormReader;
     use crate::index::{Index, SegmentComponent, SegmentReader};
     use crate::indexer::operation::AddOperation;
-    use crate::indexer::SegmentWriter;
-    use crate::query::Scorer;
+    use crate::indexer::{NoMergePolicy, SegmentWriter};
+    use crate::query::{PhraseQuery, Scorer};
     use crate::schema::{
-        Field, IndexRecordOption, Schema, Term, TextFieldIndexing, TextOptions, INDEXED, TEXT,
+        Field, IndexRecordOption, Schema, Term, TextFieldIndexing, TextOptions, INDEXED, STRING,
+        TEXT,
     };
     use crate::tokenizer::{SimpleTokenizer, MAX_TOKEN_LEN};
-    use crate::{DocId, HasLen, IndexWriter, Score};
+    use crate::{DocId, HasLen, IndexWriter, Score, Searcher};
 
     #[test]
     pub fn test_position_write() -> crate::Result<()> {
@@ -76,6 +84,215 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn test_index_record_option_per_field() -> crate::Result<()> {
+        let text_options = |record_option: IndexRecordOption| {
+            TextOptions::default()
+                .set_indexing_options(TextFieldIndexing::default().set_index_option(record_option))
+        };
+        let mut schema_builder = Schema::builder();
+        let tags = schema_builder.add_text_field("tags", text_options(IndexRecordOption::Basic));
+        let summary =
+            schema_builder.add_text_field("summary", text_options(IndexRecordOption::WithFreqs));
+        let body = schema_builder.add_text_field(
+            "body",
+            text_options(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            tags => "abc def abc",
+            summary => "abc def abc",
+            body => "abc def abc",
+        ))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+
+        // Only the fields indexed with positions pay for the positions file.
+        let space_usage = segment_reader.space_usage()?;
+        let fields_with_positions: Vec<Field> = space_usage
+            .positions()
+            .fields()
+            .filter(|(_, field_usage)| field_usage.total() > 0u64)
+            .map(|(field, _)| *field)
+            .collect();
+        assert_eq!(fields_with_positions, vec![body]);
+
+        let mut positions = Vec::new();
+        for (field, expected_term_freq, expected_positions) in [
+            (tags, 1, vec![]),
+            (summary, 2, vec![]),
+            (body, 2, vec![0, 2]),
+        ] {
+            let term = Term::from_field_text(field, "abc");
+            // The requested record option is downgraded to the one of the field.
+            let mut postings = segment_reader
+                .inverted_index(field)?
+                .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)?
+                .unwrap();
+            assert_eq!(postings.doc(), 0);
+            assert_eq!(postings.term_freq(), expected_term_freq);
+            postings.positions(&mut positions);
+            assert_eq!(positions, expected_positions);
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_max_positions_per_term() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_max_positions_per_term(2),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a a b a a"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let term = Term::from_field_text(text, "a");
+        let mut postings = searcher
+            .segment_reader(0u32)
+            .inverted_index(text)?
+            .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)?
+            .unwrap();
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.term_freq(), 2);
+        let mut positions = Vec::new();
+        postings.positions(&mut positions);
+        assert_eq!(positions, vec![0, 1]);
+
+        let phrase_count = |phrase: &[&str]| {
+            let terms = phrase
+                .iter()
+                .map(|token| Term::from_field_text(text, token))
+                .collect();
+            searcher.search(&PhraseQuery::new(terms), &Count)
+        };
+        assert_eq!(phrase_count(&["a", "a"])?, 1);
+        assert_eq!(phrase_count(&["a", "b"])?, 1);
+        // The occurrences of "a" after the cap are not recorded.
+        assert_eq!(phrase_count(&["b", "a"])?, 0);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_position_gap() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let indexing_options =
+            TextFieldIndexing::default().set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let authors = schema_builder.add_text_field(
+            "authors",
+            TextOptions::default().set_indexing_options(indexing_options.clone()),
+        );
+        let authors_with_gap = schema_builder.add_text_field(
+            "authors_with_gap",
+            TextOptions::default().set_indexing_options(indexing_options.set_position_gap(100)),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            authors => "alice jones",
+            authors => "smith bob",
+            authors_with_gap => "alice jones",
+            authors_with_gap => "smith bob",
+        ))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let phrase_count = |field: Field, phrase: &[&str], slop: u32| {
+            let terms = phrase
+                .iter()
+                .map(|token| Term::from_field_text(field, token))
+                .collect();
+            let mut phrase_query = PhraseQuery::new(terms);
+            phrase_query.set_slop(slop);
+            searcher.search(&phrase_query, &Count)
+        };
+        for field in [authors, authors_with_gap] {
+            assert_eq!(phrase_count(field, &["alice", "jones"], 0)?, 1);
+            assert_eq!(phrase_count(field, &["smith", "bob"], 0)?, 1);
+            // The default gap already prevents exact phrases from matching across values.
+            assert_eq!(phrase_count(field, &["jones", "smith"], 0)?, 0);
+        }
+        assert_eq!(phrase_count(authors, &["jones", "smith"], 1)?, 1);
+        assert_eq!(phrase_count(authors, &["alice", "smith"], 2)?, 1);
+        assert_eq!(phrase_count(authors_with_gap, &["jones", "smith"], 1)?, 0);
+        assert_eq!(phrase_count(authors_with_gap, &["alice", "smith"], 10)?, 0);
+        assert_eq!(phrase_count(authors_with_gap, &["jones", "smith"], 100)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_positions_max_doc_freq() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_positions_max_doc_freq(2),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(id => "0", text => "the red cat"))?;
+        index_writer.add_document(doc!(id => "1", text => "the red dog"))?;
+        index_writer.add_document(doc!(id => "2", text => "red cat the"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(id => "3", text => "red fish"))?;
+        index_writer.commit()?;
+
+        let phrase_count = |searcher: &Searcher, phrase: &[&str]| {
+            let terms = phrase
+                .iter()
+                .map(|token| Term::from_field_text(text, token))
+                .collect();
+            searcher.search(&PhraseQuery::new(terms), &Count)
+        };
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        let inverted_index = segment_reader.inverted_index(text)?;
+        let postings_with_positions = |token: &str| -> crate::Result<bool> {
+            let term = Term::from_field_text(text, token);
+            Ok(inverted_index
+                .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)?
+                .unwrap()
+                .has_positions())
+        };
+        // "the" and "red" appear in 3 documents of the first segment.
+        assert!(!postings_with_positions("the")?);
+        assert!(!postings_with_positions("red")?);
+        assert!(postings_with_positions("cat")?);
+        // The terms without positions only filter the documents.
+        assert_eq!(phrase_count(&searcher, &["the", "cat"])?, 2);
+        assert_eq!(phrase_count(&searcher, &["cat", "the"])?, 2);
+        assert_eq!(phrase_count(&searcher, &["the", "red"])?, 2);
+        assert_eq!(phrase_count(&searcher, &["red", "fish"])?, 1);
+
+        // After the deletes, "the" only appears in one document, but its positions
+        // were pruned in the first segment and cannot be restored by the merge.
+        index_writer.delete_term(Term::from_field_text(id, "0"));
+        index_writer.delete_term(Term::from_field_text(id, "1"));
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        let term = Term::from_field_text(text, "the");
+        let postings = searcher
+            .segment_reader(0u32)
+            .inverted_index(text)?
+            .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)?
+            .unwrap();
+        assert!(!postings.has_positions());
+        assert_eq!(phrase_count(&searcher, &["the", "cat"])?, 1);
+        assert_eq!(phrase_count(&searcher, &["red", "fish"])?, 1);
+        Ok(())
+    }
     #[test]
     pub fn test_skip_positions() -> crate::Result<()> {
         let mut schema_builder = Schema::builder();
@@ -24,4 +24,14 @@ pub trait Postings: DocSet + 'static {
     fn positions(&mut self, output: &mut Vec<u32>) {
         self.positions_with_offset(0u32, output);
     }
+
+    /// Returns false if the positions of the term are not available, in which case
+    /// the positions returned are empty.
+    ///
+    /// This happens if the positions were not requested or not indexed, or if they were
+    /// pruned for this term (See
+    /// [`TextFieldIndexing::set_positions_max_doc_freq`](crate::schema::TextFieldIndexing::set_positions_max_doc_freq)).
+    fn has_positions(&self) -> bool {
+        true
+    }
 }
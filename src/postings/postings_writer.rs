@@ -10,12 +10,10 @@ use crate::postings::recorder::{BufferLender, Recorder};
 use crate::postings::{
     FieldSerializer, IndexingContext, InvertedIndexSerializer, PerFieldPostingsWriter,
 };
-use crate::schema::{Field, Schema, Term, Type};
+use crate::schema::{Field, Schema, Term, Type, DEFAULT_POSITION_GAP};
 use crate::tokenizer::{Token, TokenStream, MAX_TOKEN_LEN};
 use crate::DocId;
 
-const POSITION_GAP: u32 = 1;
-
 fn make_field_partition(
     term_offsets: &[(Field, OrderedPathId, &[u8], Addr)],
 ) -> Vec<(Field, Range<usize>)> {
@@ -124,6 +122,9 @@ pub(crate) trait PostingsWriter: Send + Sync {
     ) -> io::Result<()>;
 
     /// Tokenize a text and subscribe all of its token.
+    ///
+    /// `position_gap` positions are left after the last token, so that the tokens of
+    /// the next value of the field do not look adjacent to the tokens of this one.
     fn index_text(
         &mut self,
         doc_id: DocId,
@@ -131,6 +132,7 @@ pub(crate) trait PostingsWriter: Send + Sync {
         term_buffer: &mut Term,
         ctx: &mut IndexingContext,
         indexing_position: &mut IndexingPosition,
+        position_gap: u32,
     ) {
         let end_of_path_idx = term_buffer.len_bytes();
         let mut num_tokens = 0;
@@ -154,7 +156,7 @@ pub(crate) trait PostingsWriter: Send + Sync {
             num_tokens += 1;
         });
 
-        indexing_position.end_position = end_position + POSITION_GAP;
+        indexing_position.end_position = end_position + position_gap;
         indexing_position.num_tokens += num_tokens;
         term_buffer.truncate_value_bytes(end_of_path_idx);
     }
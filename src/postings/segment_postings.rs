@@ -260,6 +260,10 @@ impl Postings for SegmentPostings {
             output.clear();
         }
     }
+
+    fn has_positions(&self) -> bool {
+        self.position_reader.is_some()
+    }
 }
 
 #[cfg(test)]
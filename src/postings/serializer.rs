@@ -3,6 +3,8 @@ use std::io::{self, Write};
 
 use common::{BinarySerializable, CountingWriter, VInt};
 
+use super::field_term_stats::{FieldTermStats, FieldTermStatsCollector};
+use super::top_impacts::TopImpactsSerializer;
 use super::TermInfo;
 use crate::directory::{CompositeWrite, WritePtr};
 use crate::fieldnorm::FieldNormReader;
@@ -11,13 +13,13 @@ use crate::positions::PositionSerializer;
 use crate::postings::compression::{BlockEncoder, VIntEncoder, COMPRESSION_BLOCK_SIZE};
 use crate::postings::skip::SkipSerializer;
 use crate::query::Bm25Weight;
-use crate::schema::{Field, FieldEntry, FieldType, IndexRecordOption, Schema};
+use crate::schema::{Bm25Options, Field, FieldEntry, FieldType, IndexRecordOption, Schema};
 use crate::termdict::TermDictionaryBuilder;
 use crate::{DocId, Score};
 
 /// `InvertedIndexSerializer` is in charge of serializing
 /// postings on disk, in the
-/// * `.idx` (inverted index)
+/// * `.idx` (inverted index, and the top impacts lists of the terms)
 /// * `.pos` (positions file)
 /// * `.term` (term dictionary)
 ///
@@ -51,6 +53,9 @@ pub struct InvertedIndexSerializer {
     postings_write: CompositeWrite<WritePtr>,
     positions_write: CompositeWrite<WritePtr>,
     schema: Schema,
+    num_top_terms_per_field: usize,
+    field_term_stats: Vec<FieldTermStats>,
+    top_impacts: Vec<(Field, Vec<u8>)>,
 }
 
 impl InvertedIndexSerializer {
@@ -62,6 +67,9 @@ impl InvertedIndexSerializer {
             postings_write: CompositeWrite::wrap(segment.open_write(Postings)?),
             positions_write: CompositeWrite::wrap(segment.open_write(Positions)?),
             schema: segment.schema(),
+            num_top_terms_per_field: segment.index().settings().num_top_terms_per_field,
+            field_term_stats: Vec::new(),
+            top_impacts: Vec::new(),
         };
         Ok(inv_index_serializer)
     }
@@ -81,6 +89,13 @@ impl InvertedIndexSerializer {
         let postings_write = self.postings_write.for_field(field);
         let positions_write = self.positions_write.for_field(field);
         let field_type: FieldType = (*field_entry.field_type()).clone();
+        let num_top_terms = if matches!(field_type, FieldType::Str(_)) {
+            self.num_top_terms_per_field
+        } else {
+            0
+        };
+        let term_stats_collector =
+            FieldTermStatsCollector::new(field, total_num_tokens, num_top_terms);
         FieldSerializer::create(
             &field_type,
             total_num_tokens,
@@ -88,15 +103,26 @@ impl InvertedIndexSerializer {
             postings_write,
             positions_write,
             fieldnorm_reader,
+            term_stats_collector,
+            &mut self.field_term_stats,
+            &mut self.top_impacts,
         )
     }
 
     /// Closes the serializer.
-    pub fn close(self) -> io::Result<()> {
+    ///
+    /// Returns the statistics about the terms of the serialized fields.
+    pub fn close(mut self) -> io::Result<Vec<FieldTermStats>> {
         self.terms_write.close()?;
+        // The top impacts lists are stored next to the postings of their field.
+        for (field, top_impacts) in &self.top_impacts {
+            self.postings_write
+                .for_field_with_idx(*field, 1)
+                .write_all(top_impacts)?;
+        }
         self.postings_write.close()?;
         self.positions_write.close()?;
-        Ok(())
+        Ok(self.field_term_stats)
     }
 }
 
@@ -108,9 +134,18 @@ pub struct FieldSerializer<'a> {
     positions_serializer_opt: Option<PositionSerializer<&'a mut CountingWriter<WritePtr>>>,
     current_term_info: TermInfo,
     term_open: bool,
+    term_stats_collector: FieldTermStatsCollector,
+    field_term_stats: &'a mut Vec<FieldTermStats>,
+    top_impacts_serializer_opt: Option<TopImpactsSerializer>,
+    top_impacts: &'a mut Vec<(Field, Vec<u8>)>,
+    max_positions_per_term: Option<u32>,
+    positions_max_doc_freq: Option<u32>,
+    // True if the positions of the current term are not recorded.
+    term_positions_pruned: bool,
 }
 
 impl<'a> FieldSerializer<'a> {
+    #[allow(clippy::too_many_arguments)]
     fn create(
         field_type: &FieldType,
         total_num_tokens: u64,
@@ -118,22 +153,57 @@ impl<'a> FieldSerializer<'a> {
         postings_write: &'a mut CountingWriter<WritePtr>,
         positions_write: &'a mut CountingWriter<WritePtr>,
         fieldnorm_reader: Option<FieldNormReader>,
+        term_stats_collector: FieldTermStatsCollector,
+        field_term_stats: &'a mut Vec<FieldTermStats>,
+        top_impacts: &'a mut Vec<(Field, Vec<u8>)>,
     ) -> io::Result<FieldSerializer<'a>> {
         total_num_tokens.serialize(postings_write)?;
         let index_record_option = field_type
             .index_record_option()
             .unwrap_or(IndexRecordOption::Basic);
-        let term_dictionary_builder = TermDictionaryBuilder::create(term_dictionary_write)?;
+        let mut term_dictionary_builder = TermDictionaryBuilder::create(term_dictionary_write)?;
+        let text_indexing_options = match field_type {
+            FieldType::Str(text_options) => text_options.get_indexing_options(),
+            FieldType::JsonObject(json_object_options) => {
+                json_object_options.get_text_indexing_options()
+            }
+            _ => None,
+        };
+        if let Some(block_len) =
+            text_indexing_options.and_then(|text_indexing| text_indexing.term_dict_block_len())
+        {
+            term_dictionary_builder.set_block_len(block_len);
+        }
         let average_fieldnorm = fieldnorm_reader
             .as_ref()
             .map(|ff_reader| (total_num_tokens as Score / ff_reader.num_docs() as Score))
             .unwrap_or(0.0);
+        let bm25_options = text_indexing_options
+            .map(|text_indexing| text_indexing.bm25_options())
+            .unwrap_or_default();
+        // The impacts of the documents are only known if the field has term frequencies
+        // and fieldnorms.
+        let top_impacts_serializer_opt = match (
+            text_indexing_options.and_then(|text_indexing| text_indexing.top_impacts_per_term()),
+            fieldnorm_reader.as_ref(),
+        ) {
+            (Some(num_top_impacts), Some(fieldnorm_reader)) if index_record_option.has_freq() => {
+                Some(TopImpactsSerializer::new(
+                    num_top_impacts,
+                    average_fieldnorm,
+                    fieldnorm_reader.clone(),
+                    bm25_options,
+                ))
+            }
+            _ => None,
+        };
         let postings_serializer = PostingsSerializer::new(
             postings_write,
             average_fieldnorm,
             index_record_option,
             fieldnorm_reader,
-        );
+        )
+        .with_bm25_options(bm25_options);
         let positions_serializer_opt = if index_record_option.has_positions() {
             Some(PositionSerializer::new(positions_write))
         } else {
@@ -146,6 +216,15 @@ impl<'a> FieldSerializer<'a> {
             positions_serializer_opt,
             current_term_info: TermInfo::default(),
             term_open: false,
+            term_stats_collector,
+            field_term_stats,
+            top_impacts_serializer_opt,
+            top_impacts,
+            max_positions_per_term: text_indexing_options
+                .and_then(|text_indexing| text_indexing.max_positions_per_term()),
+            positions_max_doc_freq: text_indexing_options
+                .and_then(|text_indexing| text_indexing.positions_max_doc_freq()),
+            term_positions_pruned: false,
         })
     }
 
@@ -182,11 +261,30 @@ impl<'a> FieldSerializer<'a> {
         self.postings_serializer.clear();
         self.current_term_info = self.current_term_info();
         self.term_dictionary_builder.insert_key(term)?;
+        self.term_stats_collector.record(term, term_doc_freq);
         self.postings_serializer
             .new_term(term_doc_freq, record_term_freq);
+        if let Some(top_impacts_serializer) = self.top_impacts_serializer_opt.as_mut() {
+            top_impacts_serializer.new_term(
+                self.current_term_info.postings_range.start as u64,
+                term_doc_freq,
+                record_term_freq,
+            );
+        }
+        self.term_positions_pruned = self
+            .positions_max_doc_freq
+            .map_or(false, |max_doc_freq| term_doc_freq > max_doc_freq);
         Ok(())
     }
 
+    /// Skips the positions of the current term.
+    ///
+    /// This is required when merging a term whose positions were skipped in one of
+    /// the merged segments.
+    pub(crate) fn prune_term_positions(&mut self) {
+        self.term_positions_pruned = true;
+    }
+
     /// Serialize the information that a document contains for the current term:
     /// its term frequency, and the position deltas.
     ///
@@ -194,14 +292,29 @@ impl<'a> FieldSerializer<'a> {
     /// For instance, if the positions are `2, 3, 17`,
     /// `position_deltas` is `2, 1, 14`
     ///
-    /// Term frequencies and positions may be ignored by the serializer depending
+    /// Term frequencies and positions may be ignored or capped by the serializer depending
     /// on the configuration of the field in the `Schema`.
     pub fn write_doc(&mut self, doc_id: DocId, term_freq: u32, position_deltas: &[u32]) {
         self.current_term_info.doc_freq += 1;
+        let term_freq = match self.positions_serializer_opt.as_mut() {
+            Some(positions_serializer) if !self.term_positions_pruned => {
+                // The positions are read back according to the term frequencies,
+                // which are therefore capped as well.
+                let (term_freq, position_deltas) = match self.max_positions_per_term {
+                    Some(max_positions) if term_freq > max_positions => {
+                        (max_positions, &position_deltas[..max_positions as usize])
+                    }
+                    _ => (term_freq, position_deltas),
+                };
+                assert_eq!(term_freq as usize, position_deltas.len());
+                positions_serializer.write_positions_delta(position_deltas);
+                term_freq
+            }
+            _ => term_freq,
+        };
         self.postings_serializer.write_doc(doc_id, term_freq);
-        if let Some(ref mut positions_serializer) = self.positions_serializer_opt.as_mut() {
-            assert_eq!(term_freq as usize, position_deltas.len());
-            positions_serializer.write_positions_delta(position_deltas);
+        if let Some(top_impacts_serializer) = self.top_impacts_serializer_opt.as_mut() {
+            top_impacts_serializer.write_doc(doc_id, term_freq);
         }
     }
 
@@ -219,11 +332,18 @@ impl<'a> FieldSerializer<'a> {
             self.current_term_info.postings_range.end =
                 self.postings_serializer.written_bytes() as usize;
 
+            // The positions of a pruned term are left empty, which tells the readers
+            // that they are not available.
             if let Some(positions_serializer) = self.positions_serializer_opt.as_mut() {
-                positions_serializer.close_term()?;
+                if !self.term_positions_pruned {
+                    positions_serializer.close_term()?;
+                }
                 self.current_term_info.positions_range.end =
                     positions_serializer.written_bytes() as usize;
             }
+            if let Some(top_impacts_serializer) = self.top_impacts_serializer_opt.as_mut() {
+                top_impacts_serializer.close_term()?;
+            }
             self.term_dictionary_builder
                 .insert_value(&self.current_term_info)?;
             self.term_open = false;
@@ -239,6 +359,14 @@ impl<'a> FieldSerializer<'a> {
         }
         self.postings_serializer.close()?;
         self.term_dictionary_builder.finish()?;
+        if let Some(top_impacts_serializer) = self.top_impacts_serializer_opt {
+            if let Some(top_impacts) = top_impacts_serializer.finish()? {
+                self.top_impacts
+                    .push((self.term_stats_collector.field(), top_impacts));
+            }
+        }
+        self.field_term_stats
+            .push(self.term_stats_collector.finish());
         Ok(())
     }
 }
@@ -307,6 +435,7 @@ pub struct PostingsSerializer<W: Write> {
     bm25_weight: Option<Bm25Weight>,
     avg_fieldnorm: Score, /* Average number of term in the field for that segment.
                            * this value is used to compute the block wand information. */
+    bm25_options: Bm25Options,
     term_has_freq: bool,
 }
 
@@ -332,10 +461,18 @@ impl<W: Write> PostingsSerializer<W> {
             fieldnorm_reader,
             bm25_weight: None,
             avg_fieldnorm,
+            bm25_options: Bm25Options::default(),
             term_has_freq: false,
         }
     }
 
+    /// Sets the BM25 parameters of the field, used to compute the block wand information.
+    #[must_use]
+    pub fn with_bm25_options(mut self, bm25_options: Bm25Options) -> PostingsSerializer<W> {
+        self.bm25_options = bm25_options;
+        self
+    }
+
     pub fn new_term(&mut self, term_doc_freq: u32, record_term_freq: bool) {
         self.bm25_weight = None;
 
@@ -355,11 +492,14 @@ impl<W: Write> PostingsSerializer<W> {
             return;
         }
 
-        self.bm25_weight = Some(Bm25Weight::for_one_term_without_explain(
-            term_doc_freq as u64,
-            num_docs_in_segment,
-            self.avg_fieldnorm,
-        ));
+        self.bm25_weight = Some(
+            Bm25Weight::for_one_term_without_explain(
+                term_doc_freq as u64,
+                num_docs_in_segment,
+                self.avg_fieldnorm,
+            )
+            .with_bm25_options(self.bm25_options),
+        );
     }
 
     fn write_block(&mut self) {
@@ -467,11 +607,16 @@ impl<W: Write> PostingsSerializer<W> {
     /// start offset.
     /// When called after writing the postings of a term, this value is used as a
     /// end offset.
-    fn written_bytes(&self) -> u64 {
+    pub(crate) fn written_bytes(&self) -> u64 {
         self.output_write.written_bytes()
     }
 
-    fn clear(&mut self) {
+    /// Returns the underlying output, once all of the terms are closed.
+    pub(crate) fn into_output(self) -> W {
+        self.output_write.finish()
+    }
+
+    pub(crate) fn clear(&mut self) {
         self.block.clear();
         self.last_doc_id_encoded = 0;
     }
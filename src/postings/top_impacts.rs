@@ -0,0 +1,343 @@
+use std::cmp::Ordering;
+use std::io;
+use std::ops::Range;
+
+use common::{BinarySerializable, OwnedBytes};
+
+use super::serializer::PostingsSerializer;
+use super::{BlockSegmentPostings, SegmentPostings};
+use crate::directory::FileSlice;
+use crate::fieldnorm::FieldNormReader;
+use crate::query::Bm25Weight;
+use crate::schema::{Bm25Options, IndexRecordOption};
+use crate::{DocId, Score};
+
+/// Number of bytes of an entry of the table of the top impacts lists:
+/// the start of the postings of the term (u64), the length of its top impacts list (u32)
+/// and the start of its top impacts list (u64).
+const ENTRY_NUM_BYTES: usize = 20;
+
+fn io_invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Serializes the top impacts lists of the terms of a field.
+///
+/// The top impacts list of a term holds the `num_top_impacts` documents contributing the most
+/// to the BM25 score of the term, sorted by doc id and encoded like regular postings with
+/// their term frequencies. A list is only recorded for the terms appearing in more than
+/// `num_top_impacts` documents.
+///
+/// The lists are followed by a table, sorted by the start of the postings of their terms, and
+/// by the number of entries of the table.
+pub(crate) struct TopImpactsSerializer {
+    num_top_impacts: usize,
+    fieldnorm_reader: FieldNormReader,
+    bm25_weight: Bm25Weight,
+    postings_serializer: PostingsSerializer<Vec<u8>>,
+    // Start of the postings of the current term, if its top impacts are recorded.
+    term_postings_start: Option<u64>,
+    term_docs: Vec<(Score, DocId, u32)>,
+    entries: Vec<(u64, u32, u64)>,
+}
+
+impl TopImpactsSerializer {
+    pub fn new(
+        num_top_impacts: u32,
+        average_fieldnorm: Score,
+        fieldnorm_reader: FieldNormReader,
+        bm25_options: Bm25Options,
+    ) -> TopImpactsSerializer {
+        // The impact of a document does not depend on the document frequency of the term.
+        let bm25_weight = Bm25Weight::for_one_term_without_explain(
+            1,
+            u64::from(fieldnorm_reader.num_docs()).max(1),
+            average_fieldnorm,
+        )
+        .with_bm25_options(bm25_options);
+        let postings_serializer = PostingsSerializer::new(
+            Vec::new(),
+            average_fieldnorm,
+            IndexRecordOption::WithFreqs,
+            Some(fieldnorm_reader.clone()),
+        )
+        .with_bm25_options(bm25_options);
+        TopImpactsSerializer {
+            num_top_impacts: num_top_impacts as usize,
+            fieldnorm_reader,
+            bm25_weight,
+            postings_serializer,
+            term_postings_start: None,
+            term_docs: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Starts a new term, whose postings start at `postings_start`.
+    pub fn new_term(&mut self, postings_start: u64, term_doc_freq: u32, record_term_freq: bool) {
+        self.term_docs.clear();
+        self.term_postings_start = (record_term_freq
+            && term_doc_freq as usize > self.num_top_impacts)
+            .then_some(postings_start);
+    }
+
+    pub fn write_doc(&mut self, doc_id: DocId, term_freq: u32) {
+        if self.term_postings_start.is_none() {
+            return;
+        }
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(doc_id);
+        let impact = self.bm25_weight.tf_factor(fieldnorm_id, term_freq);
+        self.term_docs.push((impact, doc_id, term_freq));
+        if self.term_docs.len() >= 2 * self.num_top_impacts {
+            self.truncate_term_docs();
+        }
+    }
+
+    /// Keeps the `num_top_impacts` documents with the highest impacts. Ties are broken by
+    /// doc id.
+    fn truncate_term_docs(&mut self) {
+        if self.term_docs.len() <= self.num_top_impacts {
+            return;
+        }
+        self.term_docs
+            .select_nth_unstable_by(self.num_top_impacts - 1, |left, right| {
+                right
+                    .0
+                    .partial_cmp(&left.0)
+                    .unwrap_or(Ordering::Equal)
+                    .then(left.1.cmp(&right.1))
+            });
+        self.term_docs.truncate(self.num_top_impacts);
+    }
+
+    pub fn close_term(&mut self) -> io::Result<()> {
+        let Some(postings_start) = self.term_postings_start.take() else {
+            return Ok(());
+        };
+        self.truncate_term_docs();
+        self.term_docs
+            .sort_unstable_by_key(|&(_, doc_id, _)| doc_id);
+        let doc_freq = self.term_docs.len() as u32;
+        let top_impacts_start = self.postings_serializer.written_bytes();
+        self.postings_serializer.clear();
+        self.postings_serializer.new_term(doc_freq, true);
+        for &(_, doc_id, term_freq) in &self.term_docs {
+            self.postings_serializer.write_doc(doc_id, term_freq);
+        }
+        self.postings_serializer.close_term(doc_freq)?;
+        self.entries
+            .push((postings_start, doc_freq, top_impacts_start));
+        Ok(())
+    }
+
+    /// Returns the serialized top impacts lists, or `None` if no term has one.
+    pub fn finish(self) -> io::Result<Option<Vec<u8>>> {
+        if self.entries.is_empty() {
+            return Ok(None);
+        }
+        let mut output = self.postings_serializer.into_output();
+        for (postings_start, doc_freq, top_impacts_start) in &self.entries {
+            postings_start.serialize(&mut output)?;
+            doc_freq.serialize(&mut output)?;
+            top_impacts_start.serialize(&mut output)?;
+        }
+        (self.entries.len() as u64).serialize(&mut output)?;
+        Ok(Some(output))
+    }
+}
+
+/// Reader of the top impacts lists of the terms of a field.
+///
+/// See [`TextFieldIndexing::set_top_impacts_per_term`](crate::schema::TextFieldIndexing::set_top_impacts_per_term).
+pub(crate) struct TopImpactsReader {
+    lists: FileSlice,
+    entries: OwnedBytes,
+}
+
+impl TopImpactsReader {
+    pub fn open(file: FileSlice) -> io::Result<TopImpactsReader> {
+        if file.len() < 8 {
+            return Err(io_invalid_data(format!(
+                "Top impacts file is too short: {} bytes",
+                file.len()
+            )));
+        }
+        let (body, num_entries_slice) = file.split_from_end(8);
+        let num_entries = u64::deserialize(&mut num_entries_slice.read_bytes()?)?;
+        let entries_num_bytes = usize::try_from(num_entries)
+            .ok()
+            .and_then(|num_entries| num_entries.checked_mul(ENTRY_NUM_BYTES))
+            .filter(|&entries_num_bytes| entries_num_bytes <= body.len())
+            .ok_or_else(|| {
+                io_invalid_data(format!(
+                    "Top impacts file of {} bytes cannot hold {num_entries} entries",
+                    file.len()
+                ))
+            })?;
+        let (lists, entries) = body.split_from_end(entries_num_bytes);
+        Ok(TopImpactsReader {
+            lists,
+            entries: entries.read_bytes()?,
+        })
+    }
+
+    fn num_entries(&self) -> usize {
+        self.entries.len() / ENTRY_NUM_BYTES
+    }
+
+    fn entry(&self, entry_ord: usize) -> io::Result<(u64, u32, u64)> {
+        let mut bytes = &self.entries.as_slice()[entry_ord * ENTRY_NUM_BYTES..];
+        let postings_start = u64::deserialize(&mut bytes)?;
+        let doc_freq = u32::deserialize(&mut bytes)?;
+        let top_impacts_start = u64::deserialize(&mut bytes)?;
+        Ok((postings_start, doc_freq, top_impacts_start))
+    }
+
+    /// Returns the length and the byte range of the top impacts list of the term whose
+    /// postings start at `postings_start`, if it has one.
+    fn top_impacts_range(&self, postings_start: u64) -> io::Result<Option<(u32, Range<usize>)>> {
+        let num_entries = self.num_entries();
+        let (mut low, mut high) = (0, num_entries);
+        while low < high {
+            let mid = (low + high) / 2;
+            let (entry_postings_start, doc_freq, top_impacts_start) = self.entry(mid)?;
+            match entry_postings_start.cmp(&postings_start) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => {
+                    let top_impacts_end = if mid + 1 < num_entries {
+                        self.entry(mid + 1)?.2
+                    } else {
+                        self.lists.len() as u64
+                    };
+                    if top_impacts_start > top_impacts_end
+                        || top_impacts_end > self.lists.len() as u64
+                    {
+                        return Err(io_invalid_data(format!(
+                            "Invalid top impacts list range {top_impacts_start}..{top_impacts_end}"
+                        )));
+                    }
+                    return Ok(Some((
+                        doc_freq,
+                        top_impacts_start as usize..top_impacts_end as usize,
+                    )));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the top impacts list of the term whose postings start at `postings_start`, if
+    /// it has one.
+    pub fn read_postings(
+        &self,
+        postings_start: u64,
+        option: IndexRecordOption,
+    ) -> io::Result<Option<SegmentPostings>> {
+        let Some((doc_freq, byte_range)) = self.top_impacts_range(postings_start)? else {
+            return Ok(None);
+        };
+        let block_postings = BlockSegmentPostings::open(
+            doc_freq,
+            self.lists.slice(byte_range),
+            IndexRecordOption::WithFreqs,
+            option.downgrade(IndexRecordOption::WithFreqs),
+        )?;
+        Ok(Some(SegmentPostings::from_block_postings(
+            block_postings,
+            None,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopImpactsReader;
+    use crate::collector::{Count, TopDocs};
+    use crate::directory::FileSlice;
+    use crate::indexer::NoMergePolicy;
+    use crate::query::TermQuery;
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+    use crate::{doc, DocAddress, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_top_impacts_term_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqs)
+                .set_top_impacts_per_term(2),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(text => "a b c d"))?;
+        index_writer.add_document(doc!(text => "a a a"))?;
+        index_writer.add_document(doc!(text => "a b"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "a a b"))?;
+        index_writer.add_document(doc!(text => "a c d e f g"))?;
+        index_writer.add_document(doc!(text => "c"))?;
+        index_writer.commit()?;
+
+        let check_top_impacts = |index: &Index| -> crate::Result<()> {
+            let searcher = index.reader()?.searcher();
+            let term_query = |word: &str, top_impacts_only: bool| {
+                let mut query = TermQuery::new(
+                    Term::from_field_text(text, word),
+                    IndexRecordOption::WithFreqs,
+                );
+                query.set_top_impacts_only(top_impacts_only);
+                query
+            };
+            let top_docs = |query: &TermQuery| -> crate::Result<Vec<DocAddress>> {
+                let top_docs = searcher.search(query, &TopDocs::with_limit(2))?;
+                Ok(top_docs.into_iter().map(|(_, doc)| doc).collect())
+            };
+            let num_segments = searcher.segment_readers().len();
+            assert_eq!(searcher.search(&term_query("a", false), &Count)?, 5);
+            assert_eq!(
+                searcher.search(&term_query("a", true), &Count)?,
+                2 * num_segments
+            );
+            assert_eq!(
+                top_docs(&term_query("a", true))?,
+                top_docs(&term_query("a", false))?
+            );
+            // Terms appearing in at most 2 documents of a segment have no top impacts list.
+            assert_eq!(searcher.search(&term_query("d", true), &Count)?, 2);
+            Ok(())
+        };
+        check_top_impacts(&index)?;
+
+        let segment_ids = index.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+        index_writer.wait_merging_threads()?;
+        check_top_impacts(&index)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_impacts_reader_corrupted() {
+        let open_err_kind = |bytes: Vec<u8>| {
+            TopImpactsReader::open(FileSlice::from(bytes))
+                .err()
+                .unwrap()
+                .kind()
+        };
+        assert_eq!(open_err_kind(vec![1u8; 4]), std::io::ErrorKind::InvalidData);
+        let mut too_many_entries = vec![0u8; 30];
+        too_many_entries.extend_from_slice(&2u64.to_le_bytes());
+        assert_eq!(
+            open_err_kind(too_many_entries),
+            std::io::ErrorKind::InvalidData
+        );
+        let mut overflowing_entries = Vec::new();
+        overflowing_entries.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(
+            open_err_kind(overflowing_entries),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -6,7 +6,7 @@ use tantivy_fst::Automaton;
 
 use super::phrase_prefix_query::prefix_end;
 use crate::index::SegmentReader;
-use crate::query::{BitSetDocSet, ConstScorer, Explanation, Scorer, Weight};
+use crate::query::{BitSetDocSet, ConstScorer, CostEstimate, Explanation, Scorer, Weight};
 use crate::schema::{Field, IndexRecordOption};
 use crate::termdict::{TermDictionary, TermStreamer};
 use crate::{DocId, Score, TantivyError};
@@ -97,6 +97,18 @@ where
         Ok(Box::new(const_scorer))
     }
 
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        let inverted_index = reader.inverted_index(self.field)?;
+        let mut term_stream = self.automaton_stream(inverted_index.terms())?;
+        let mut cost_estimate = CostEstimate::default();
+        while term_stream.advance() {
+            cost_estimate.num_docs += term_stream.value().doc_freq as u64;
+            cost_estimate.num_expanded_terms += 1;
+        }
+        cost_estimate.num_docs = cost_estimate.num_docs.min(reader.max_doc() as u64);
+        Ok(cost_estimate)
+    }
+
     fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
         let mut scorer = self.scorer(reader, 1.0)?;
         if scorer.seek(doc) == doc {
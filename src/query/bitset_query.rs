@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use common::BitSet;
+
+use crate::index::{SegmentId, SegmentReader};
+use crate::query::explanation::does_not_match;
+use crate::query::{
+    BitSetDocSet, ConstScorer, EmptyScorer, EnableScoring, Explanation, Query, Scorer, Weight,
+};
+use crate::{DocId, DocSet, Score, TantivyError};
+
+/// Query matching the documents of precomputed per-segment bitsets, typically
+/// obtained with a [`BitSetCollector`](crate::collector::BitSetCollector).
+///
+/// The bitsets are keyed by segment id. The documents of the segments without a bitset
+/// do not match. Deleted documents do not match either, even if they are part of a bitset.
+///
+/// All of the matched documents get the score 1.0. A `BitSetQuery` is typically used as a
+/// cached filter, in a [`BooleanQuery`](crate::query::BooleanQuery).
+#[derive(Clone)]
+pub struct BitSetQuery {
+    bitsets: Arc<HashMap<SegmentId, BitSet>>,
+}
+
+impl BitSetQuery {
+    /// Creates a query matching the documents of the given bitsets.
+    pub fn new(bitsets: HashMap<SegmentId, BitSet>) -> BitSetQuery {
+        BitSetQuery {
+            bitsets: Arc::new(bitsets),
+        }
+    }
+}
+
+impl fmt::Debug for BitSetQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BitSetQuery(num_segments={})", self.bitsets.len())
+    }
+}
+
+impl Query for BitSetQuery {
+    fn weight(&self, _enable_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(BitSetWeight {
+            bitsets: self.bitsets.clone(),
+        }))
+    }
+}
+
+/// Weight associated with the `BitSetQuery` query.
+pub struct BitSetWeight {
+    bitsets: Arc<HashMap<SegmentId, BitSet>>,
+}
+
+impl Weight for BitSetWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let Some(bitset) = self.bitsets.get(&reader.segment_id()) else {
+            return Ok(Box::new(EmptyScorer));
+        };
+        if bitset.max_value() != reader.max_doc() {
+            return Err(TantivyError::InvalidArgument(format!(
+                "The bitset of segment {} has a max value of {}, but the segment has {} documents.",
+                reader.segment_id().short_uuid_string(),
+                bitset.max_value(),
+                reader.max_doc()
+            )));
+        }
+        let docset = BitSetDocSet::from(bitset.clone());
+        Ok(Box::new(ConstScorer::new(docset, boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        Ok(Explanation::new("BitSetQuery", 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use common::BitSet;
+
+    use super::BitSetQuery;
+    use crate::collector::{BitSetCollector, Count, DocSetCollector};
+    use crate::indexer::NoMergePolicy;
+    use crate::query::{BooleanQuery, Occur, Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, STRING};
+    use crate::{doc, Index, IndexWriter, TantivyError, Term};
+
+    #[test]
+    fn test_bitset_query_as_filter() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let size = schema_builder.add_text_field("size", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(color => "red", size => "small"))?;
+        index_writer.add_document(doc!(color => "blue", size => "small"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(color => "red", size => "large"))?;
+        index_writer.add_document(doc!(color => "red", size => "small"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let term_query = |field, text: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(field, text),
+                IndexRecordOption::Basic,
+            ))
+        };
+
+        let red_bitsets = searcher.search(&*term_query(color, "red"), &BitSetCollector)?;
+        let red_query = BitSetQuery::new(red_bitsets);
+        assert_eq!(searcher.search(&red_query, &Count)?, 3);
+        let small_and_red = BooleanQuery::new(vec![
+            (Occur::Must, term_query(size, "small")),
+            (Occur::Must, Box::new(red_query.clone())),
+        ]);
+        let docs = searcher.search(&small_and_red, &DocSetCollector)?;
+        assert_eq!(docs.len(), 2);
+        let expected_docs = searcher.search(
+            &BooleanQuery::new(vec![
+                (Occur::Must, term_query(size, "small")),
+                (Occur::Must, term_query(color, "red")),
+            ]),
+            &DocSetCollector,
+        )?;
+        assert_eq!(docs, expected_docs);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bitset_query_errors_on_max_value_mismatch() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let color = schema_builder.add_text_field("color", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(color => "red"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let segment_id = searcher.segment_reader(0).segment_id();
+
+        let query = BitSetQuery::new(HashMap::from([(segment_id, BitSet::with_max_value(10))]));
+        assert!(matches!(
+            searcher.search(&query, &Count),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+        // Segments without a bitset do not match.
+        let query = BitSetQuery::new(HashMap::new());
+        assert_eq!(searcher.search(&query, &Count)?, 0);
+        Ok(())
+    }
+}
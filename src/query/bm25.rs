@@ -2,12 +2,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::fieldnorm::FieldNormReader;
 use crate::query::Explanation;
-use crate::schema::Field;
+use crate::schema::{Bm25Options, Field, FieldType};
 use crate::{Score, Searcher, Term};
 
-const K1: Score = 1.2;
-const B: Score = 0.75;
-
 /// An interface to compute the statistics needed in BM25 scoring.
 ///
 /// The standard implementation is a [Searcher] but you can also
@@ -22,6 +19,28 @@ pub trait Bm25StatisticsProvider {
 
     /// The number of documents containing the given term.
     fn doc_freq(&self, term: &Term) -> crate::Result<u64>;
+
+    /// The BM25 parameters of the given field.
+    ///
+    /// Defaults to the standard parameters. The [Searcher] returns the parameters
+    /// declared in the schema.
+    fn bm25_options(&self, _field: Field) -> Bm25Options {
+        Bm25Options::default()
+    }
+}
+
+/// Returns the BM25 parameters declared for a field in the schema.
+pub(crate) fn field_bm25_options(field_type: &FieldType) -> Bm25Options {
+    let text_indexing_options = match field_type {
+        FieldType::Str(text_options) => text_options.get_indexing_options(),
+        FieldType::JsonObject(json_object_options) => {
+            json_object_options.get_text_indexing_options()
+        }
+        _ => None,
+    };
+    text_indexing_options
+        .map(|text_indexing_options| text_indexing_options.bm25_options())
+        .unwrap_or_default()
 }
 
 impl Bm25StatisticsProvider for Searcher {
@@ -47,6 +66,10 @@ impl Bm25StatisticsProvider for Searcher {
     fn doc_freq(&self, term: &Term) -> crate::Result<u64> {
         self.doc_freq(term)
     }
+
+    fn bm25_options(&self, field: Field) -> Bm25Options {
+        field_bm25_options(self.schema().get_field_entry(field).field_type())
+    }
 }
 
 pub(crate) fn idf(doc_freq: u64, doc_count: u64) -> Score {
@@ -55,15 +78,16 @@ pub(crate) fn idf(doc_freq: u64, doc_count: u64) -> Score {
     (1.0 + x).ln()
 }
 
-fn cached_tf_component(fieldnorm: u32, average_fieldnorm: Score) -> Score {
-    K1 * (1.0 - B + B * fieldnorm as Score / average_fieldnorm)
+fn cached_tf_component(fieldnorm: u32, average_fieldnorm: Score, options: Bm25Options) -> Score {
+    let (k1, b) = (options.k1(), options.b());
+    k1 * (1.0 - b + b * fieldnorm as Score / average_fieldnorm)
 }
 
-fn compute_tf_cache(average_fieldnorm: Score) -> [Score; 256] {
+fn compute_tf_cache(average_fieldnorm: Score, options: Bm25Options) -> [Score; 256] {
     let mut cache: [Score; 256] = [0.0; 256];
     for (fieldnorm_id, cache_mut) in cache.iter_mut().enumerate() {
         let fieldnorm = FieldNormReader::id_to_fieldnorm(fieldnorm_id as u8);
-        *cache_mut = cached_tf_component(fieldnorm, average_fieldnorm);
+        *cache_mut = cached_tf_component(fieldnorm, average_fieldnorm, options);
     }
     cache
 }
@@ -81,6 +105,7 @@ pub struct Bm25Weight {
     weight: Score,
     cache: [Score; 256],
     average_fieldnorm: Score,
+    options: Bm25Options,
 }
 
 impl Bm25Weight {
@@ -91,10 +116,26 @@ impl Bm25Weight {
             weight: self.weight * boost,
             cache: self.cache,
             average_fieldnorm: self.average_fieldnorm,
+            options: self.options,
+        }
+    }
+
+    /// Returns a weight scoring with the given BM25 parameters instead of the
+    /// default ones.
+    pub fn with_bm25_options(&self, options: Bm25Options) -> Bm25Weight {
+        let weight = self.weight / (1.0 + self.options.k1()) * (1.0 + options.k1());
+        Bm25Weight {
+            idf_explain: self.idf_explain.clone(),
+            weight,
+            cache: compute_tf_cache(self.average_fieldnorm, options),
+            average_fieldnorm: self.average_fieldnorm,
+            options,
         }
     }
 
     /// Construct a [Bm25Weight] for a phrase of terms.
+    ///
+    /// The BM25 parameters of the field are given by the statistics provider.
     pub fn for_terms(
         statistics: &dyn Bm25StatisticsProvider,
         terms: &[Term],
@@ -112,14 +153,14 @@ impl Bm25Weight {
         let total_num_tokens = statistics.total_num_tokens(field)?;
         let total_num_docs = statistics.total_num_docs()?;
         let average_fieldnorm = total_num_tokens as Score / total_num_docs as Score;
+        let options = statistics.bm25_options(field);
 
         if terms.len() == 1 {
             let term_doc_freq = statistics.doc_freq(&terms[0])?;
-            Ok(Bm25Weight::for_one_term(
-                term_doc_freq,
-                total_num_docs,
-                average_fieldnorm,
-            ))
+            Ok(
+                Bm25Weight::for_one_term(term_doc_freq, total_num_docs, average_fieldnorm)
+                    .with_bm25_options(options),
+            )
         } else {
             let mut idf_sum: Score = 0.0;
             for term in terms {
@@ -127,7 +168,7 @@ impl Bm25Weight {
                 idf_sum += idf(term_doc_freq, total_num_docs);
             }
             let idf_explain = Explanation::new("idf", idf_sum);
-            Ok(Bm25Weight::new(idf_explain, average_fieldnorm))
+            Ok(Bm25Weight::new(idf_explain, average_fieldnorm).with_bm25_options(options))
         }
     }
 
@@ -159,21 +200,25 @@ impl Bm25Weight {
     }
 
     pub(crate) fn new(idf_explain: Explanation, average_fieldnorm: Score) -> Bm25Weight {
-        let weight = idf_explain.value() * (1.0 + K1);
+        let options = Bm25Options::default();
+        let weight = idf_explain.value() * (1.0 + options.k1());
         Bm25Weight {
             idf_explain: Some(idf_explain),
             weight,
-            cache: compute_tf_cache(average_fieldnorm),
+            cache: compute_tf_cache(average_fieldnorm, options),
             average_fieldnorm,
+            options,
         }
     }
     pub(crate) fn new_without_explain(idf: f32, average_fieldnorm: Score) -> Bm25Weight {
-        let weight = idf * (1.0 + K1);
+        let options = Bm25Options::default();
+        let weight = idf * (1.0 + options.k1());
         Bm25Weight {
             idf_explain: None,
             weight,
-            cache: compute_tf_cache(average_fieldnorm),
+            cache: compute_tf_cache(average_fieldnorm, options),
             average_fieldnorm,
+            options,
         }
     }
 
@@ -211,8 +256,8 @@ impl Bm25Weight {
         );
 
         tf_explanation.add_const("freq, occurrences of term within document", term_freq);
-        tf_explanation.add_const("k1, term saturation parameter", K1);
-        tf_explanation.add_const("b, length normalization parameter", B);
+        tf_explanation.add_const("k1, term saturation parameter", self.options.k1());
+        tf_explanation.add_const("b, length normalization parameter", self.options.b());
         tf_explanation.add_const(
             "dl, length of field",
             FieldNormReader::id_to_fieldnorm(fieldnorm_id) as Score,
@@ -220,7 +265,7 @@ impl Bm25Weight {
         tf_explanation.add_const("avgdl, average length of field", self.average_fieldnorm);
 
         let mut explanation = Explanation::new("TermQuery, product of...", score);
-        explanation.add_detail(Explanation::new("(K1+1)", K1 + 1.0));
+        explanation.add_detail(Explanation::new("(K1+1)", self.options.k1() + 1.0));
         if let Some(idf_explain) = &self.idf_explain {
             explanation.add_detail(idf_explain.clone());
         }
@@ -233,11 +278,63 @@ impl Bm25Weight {
 mod tests {
 
     use super::idf;
-    use crate::{assert_nearly_equals, Score};
+    use crate::collector::TopDocs;
+    use crate::query::{Query, TermQuery};
+    use crate::schema::{
+        Bm25Options, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, TEXT,
+    };
+    use crate::{assert_nearly_equals, doc, DocAddress, Index, IndexWriter, Score, Term};
 
     #[test]
     fn test_idf() {
         let score: Score = 2.0;
         assert_nearly_equals!(idf(1, 2), score.ln());
     }
+
+    #[test]
+    fn test_bm25_options_per_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        // Neither the term frequency nor the length of the field impact the score.
+        let flat_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_bm25_options(Bm25Options::new(0.0, 0.0)),
+        );
+        let flat_text = schema_builder.add_text_field("flat_text", flat_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for content in ["hello", "hello hello hello world", "world"] {
+            index_writer.add_document(doc!(text => content, flat_text => content))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let search = |field| {
+            let query = TermQuery::new(
+                Term::from_field_text(field, "hello"),
+                IndexRecordOption::WithFreqs,
+            );
+            searcher.search(&query, &TopDocs::with_limit(10))
+        };
+        let top_docs = search(text)?;
+        assert_eq!(top_docs.len(), 2);
+        assert!(top_docs[0].0 > top_docs[1].0);
+
+        let top_docs = search(flat_text)?;
+        assert_eq!(top_docs.len(), 2);
+        assert_nearly_equals!(top_docs[0].0, idf(2, 3));
+        assert_nearly_equals!(top_docs[1].0, idf(2, 3));
+
+        let query = TermQuery::new(
+            Term::from_field_text(flat_text, "hello"),
+            IndexRecordOption::WithFreqs,
+        );
+        let explanation = query.explain(&searcher, DocAddress::new(0, 1))?;
+        assert!(explanation
+            .to_pretty_json()
+            .contains("k1, term saturation parameter"));
+        assert_nearly_equals!(explanation.value(), idf(2, 3));
+        Ok(())
+    }
 }
@@ -7,10 +7,12 @@ use crate::query::disjunction::Disjunction;
 use crate::query::explanation::does_not_match;
 use crate::query::score_combiner::{DoNothingCombiner, ScoreCombiner};
 use crate::query::term_query::TermScorer;
-use crate::query::weight::{for_each_docset_buffered, for_each_pruning_scorer, for_each_scorer};
+use crate::query::weight::{
+    for_each_docset_buffered, for_each_pruning_scorer, for_each_scorer, score_breakdown_whole,
+};
 use crate::query::{
-    intersect_scorers, EmptyScorer, Exclude, Explanation, Occur, RequiredOptionalScorer, Scorer,
-    Union, Weight,
+    intersect_scorers, CostEstimate, EmptyScorer, Exclude, Explanation, Occur,
+    RequiredOptionalScorer, ScoreBreakdown, Scorer, Union, Weight,
 };
 use crate::{DocId, Score};
 
@@ -130,9 +132,18 @@ impl<TScoreCombiner: ScoreCombiner> BooleanWeight<TScoreCombiner> {
         &self,
         reader: &SegmentReader,
         boost: Score,
+        scoring_enabled: bool,
     ) -> crate::Result<HashMap<Occur, Vec<Box<dyn Scorer>>>> {
+        // Without scores, optional should clauses next to must clauses cannot change the
+        // matching documents: their postings do not need to be opened.
+        let skip_optional_should = !scoring_enabled
+            && self.minimum_number_should_match == 0
+            && self.weights.iter().any(|(occur, _)| *occur == Occur::Must);
         let mut per_occur_scorers: HashMap<Occur, Vec<Box<dyn Scorer>>> = HashMap::new();
         for (occur, subweight) in &self.weights {
+            if skip_optional_should && *occur == Occur::Should {
+                continue;
+            }
             let sub_scorer: Box<dyn Scorer> = subweight.scorer(reader, boost)?;
             per_occur_scorers
                 .entry(*occur)
@@ -147,8 +158,9 @@ impl<TScoreCombiner: ScoreCombiner> BooleanWeight<TScoreCombiner> {
         reader: &SegmentReader,
         boost: Score,
         score_combiner_fn: impl Fn() -> TComplexScoreCombiner,
+        scoring_enabled: bool,
     ) -> crate::Result<SpecializedScorer> {
-        let mut per_occur_scorers = self.per_occur_scorers(reader, boost)?;
+        let mut per_occur_scorers = self.per_occur_scorers(reader, boost, scoring_enabled)?;
         // Indicate how should clauses are combined with other clauses.
         enum CombinationMethod {
             Ignored,
@@ -208,7 +220,7 @@ impl<TScoreCombiner: ScoreCombiner> BooleanWeight<TScoreCombiner> {
             }
             (CombinationMethod::Optional(should_scorer), Some(must_scorers)) => {
                 let must_scorer = intersect_scorers(must_scorers);
-                if self.scoring_enabled {
+                if scoring_enabled {
                     SpecializedScorer::Other(Box::new(
                         RequiredOptionalScorer::<_, _, TScoreCombiner>::new(
                             must_scorer,
@@ -256,18 +268,35 @@ impl<TScoreCombiner: ScoreCombiner + Sync> Weight for BooleanWeight<TScoreCombin
                 weight.scorer(reader, boost)
             }
         } else if self.scoring_enabled {
-            self.complex_scorer(reader, boost, &self.score_combiner_fn)
+            self.complex_scorer(reader, boost, &self.score_combiner_fn, true)
                 .map(|specialized_scorer| {
                     into_box_scorer(specialized_scorer, &self.score_combiner_fn)
                 })
         } else {
-            self.complex_scorer(reader, boost, DoNothingCombiner::default)
+            self.complex_scorer(reader, boost, DoNothingCombiner::default, false)
                 .map(|specialized_scorer| {
                     into_box_scorer(specialized_scorer, DoNothingCombiner::default)
                 })
         }
     }
 
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        if self.weights.len() == 1 {
+            let &(occur, ref weight) = &self.weights[0];
+            if occur == Occur::MustNot {
+                return Ok(0);
+            }
+            return weight.count(reader);
+        }
+        let scorer = self.complex_scorer(reader, 1.0, DoNothingCombiner::default, false)?;
+        let mut docset = into_box_scorer(scorer, DoNothingCombiner::default);
+        if let Some(alive_bitset) = reader.alive_bitset() {
+            Ok(docset.count(alive_bitset))
+        } else {
+            Ok(docset.count_including_deleted())
+        }
+    }
+
     fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
         let mut scorer = self.scorer(reader, 1.0)?;
         if scorer.seek(doc) != doc {
@@ -288,12 +317,61 @@ impl<TScoreCombiner: ScoreCombiner + Sync> Weight for BooleanWeight<TScoreCombin
         Ok(explanation)
     }
 
+    fn score_breakdown(
+        &self,
+        reader: &SegmentReader,
+        doc: DocId,
+        boost: Score,
+        breakdown: &mut ScoreBreakdown,
+    ) -> crate::Result<()> {
+        if !self.scoring_enabled || !TScoreCombiner::is_sum() {
+            // The score cannot be broken down into the scores of the clauses.
+            return score_breakdown_whole(self, reader, doc, boost, breakdown);
+        }
+        let mut scorer = self.scorer(reader, boost)?;
+        if scorer.doc() > doc || scorer.seek(doc) != doc {
+            return Ok(());
+        }
+        for (occur, subweight) in &self.weights {
+            if is_positive_occur(*occur) {
+                subweight.score_breakdown(reader, doc, boost, breakdown)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        // The documents of an intersection are bounded by its cheapest required clause,
+        // while the documents of a union add up.
+        let mut must_num_docs: Option<u64> = None;
+        let mut should_num_docs = 0;
+        let mut num_expanded_terms = 0;
+        for (occur, subweight) in &self.weights {
+            let cost_estimate = subweight.estimate_cost(reader)?;
+            num_expanded_terms += cost_estimate.num_expanded_terms;
+            match occur {
+                Occur::Must => {
+                    let num_docs = must_num_docs.unwrap_or(u64::MAX);
+                    must_num_docs = Some(num_docs.min(cost_estimate.num_docs));
+                }
+                Occur::Should => should_num_docs += cost_estimate.num_docs,
+                Occur::MustNot => {}
+            }
+        }
+        let num_docs = must_num_docs.unwrap_or(should_num_docs);
+        Ok(CostEstimate {
+            num_docs: num_docs.min(reader.max_doc() as u64),
+            num_expanded_terms,
+        })
+    }
+
     fn for_each(
         &self,
         reader: &SegmentReader,
         callback: &mut dyn FnMut(DocId, Score),
     ) -> crate::Result<()> {
-        let scorer = self.complex_scorer(reader, 1.0, &self.score_combiner_fn)?;
+        let scorer =
+            self.complex_scorer(reader, 1.0, &self.score_combiner_fn, self.scoring_enabled)?;
         match scorer {
             SpecializedScorer::TermUnion(term_scorers) => {
                 let mut union_scorer = Union::build(term_scorers, &self.score_combiner_fn);
@@ -311,12 +389,13 @@ impl<TScoreCombiner: ScoreCombiner + Sync> Weight for BooleanWeight<TScoreCombin
         reader: &SegmentReader,
         callback: &mut dyn FnMut(&[DocId]),
     ) -> crate::Result<()> {
-        let scorer = self.complex_scorer(reader, 1.0, || DoNothingCombiner)?;
+        // The documents are collected without their scores, whether or not scoring is enabled.
+        let scorer = self.complex_scorer(reader, 1.0, DoNothingCombiner::default, false)?;
         let mut buffer = [0u32; COLLECT_BLOCK_BUFFER_LEN];
 
         match scorer {
             SpecializedScorer::TermUnion(term_scorers) => {
-                let mut union_scorer = Union::build(term_scorers, &self.score_combiner_fn);
+                let mut union_scorer = Union::build(term_scorers, DoNothingCombiner::default);
                 for_each_docset_buffered(&mut union_scorer, &mut buffer, callback);
             }
             SpecializedScorer::Other(mut scorer) => {
@@ -342,7 +421,8 @@ impl<TScoreCombiner: ScoreCombiner + Sync> Weight for BooleanWeight<TScoreCombin
         reader: &SegmentReader,
         callback: &mut dyn FnMut(DocId, Score) -> Score,
     ) -> crate::Result<()> {
-        let scorer = self.complex_scorer(reader, 1.0, &self.score_combiner_fn)?;
+        let scorer =
+            self.complex_scorer(reader, 1.0, &self.score_combiner_fn, self.scoring_enabled)?;
         match scorer {
             SpecializedScorer::TermUnion(term_scorers) => {
                 super::block_wand(term_scorers, threshold, callback);
@@ -16,7 +16,7 @@ mod tests {
     use crate::query::term_query::TermScorer;
     use crate::query::{
         EnableScoring, Intersection, Occur, Query, QueryParser, RequiredOptionalScorer, Scorer,
-        TermQuery,
+        TermQuery, Weight,
     };
     use crate::schema::*;
     use crate::{assert_nearly_equals, DocAddress, DocId, Index, IndexWriter, Score};
@@ -105,6 +105,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn test_boolean_count_and_for_each_no_score() -> crate::Result<()> {
+        let (index, text_field) = aux_test_helper()?;
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_reader(0u32);
+        for (query_str, expected_docs) in [
+            ("+a b", vec![0, 1, 3]),
+            ("a b", vec![0, 1, 2, 3]),
+            ("+c -d b", vec![0, 1, 2]),
+            ("+(a d) +c", vec![0, 1, 3]),
+        ] {
+            let query = query_parser.parse_query(query_str)?;
+            // The scores are ignored, whether or not the weight was created with scoring.
+            for enable_scoring in [
+                EnableScoring::enabled_from_searcher(&searcher),
+                EnableScoring::disabled_from_searcher(&searcher),
+            ] {
+                let weight = query.weight(enable_scoring)?;
+                assert_eq!(weight.count(segment_reader)?, expected_docs.len() as u32);
+                let mut docs: Vec<DocId> = Vec::new();
+                weight.for_each_no_score(segment_reader, &mut |block| {
+                    docs.extend_from_slice(block)
+                })?;
+                assert_eq!(docs, expected_docs);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_boolean_count_with_deletes() -> crate::Result<()> {
+        let (index, text_field) = aux_test_helper()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.delete_term(Term::from_field_text(text_field, "d"));
+        index_writer.commit()?;
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let searcher = index.reader()?.searcher();
+        assert_eq!(query_parser.parse_query("a b")?.count(&searcher)?, 3);
+        assert_eq!(query_parser.parse_query("+a b")?.count(&searcher)?, 2);
+        Ok(())
+    }
+
     #[test]
     pub fn test_boolean_query() -> crate::Result<()> {
         let (index, text_field) = aux_test_helper()?;
@@ -2,7 +2,9 @@ use std::fmt;
 
 use crate::docset::COLLECT_BLOCK_BUFFER_LEN;
 use crate::fastfield::AliveBitSet;
-use crate::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use crate::query::{
+    CostEstimate, EnableScoring, Explanation, Query, ScoreBreakdown, Scorer, Weight,
+};
 use crate::{DocId, DocSet, Score, SegmentReader, Term};
 
 /// `BoostQuery` is a wrapper over a query used to boost its score.
@@ -80,6 +82,21 @@ impl Weight for BoostWeight {
         Ok(explanation)
     }
 
+    fn score_breakdown(
+        &self,
+        reader: &SegmentReader,
+        doc: DocId,
+        boost: Score,
+        breakdown: &mut ScoreBreakdown,
+    ) -> crate::Result<()> {
+        self.weight
+            .score_breakdown(reader, doc, boost * self.boost, breakdown)
+    }
+
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        self.weight.estimate_cost(reader)
+    }
+
     fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
         self.weight.count(reader)
     }
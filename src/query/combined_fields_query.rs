@@ -0,0 +1,591 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::docset::{DocSet, TERMINATED};
+use crate::fieldnorm::FieldNormReader;
+use crate::index::{Index, SegmentReader};
+use crate::postings::{Postings, SegmentPostings};
+use crate::query::bm25::idf;
+use crate::query::explanation::does_not_match;
+use crate::query::{
+    BooleanQuery, EmptyQuery, EnableScoring, Explanation, Occur, Query, Scorer, Weight,
+};
+use crate::schema::{Field, FieldType, IndexRecordOption};
+use crate::{DocId, Score, TantivyError, Term};
+
+/// Query scoring the text of a query over several text fields as if they were a single,
+/// combined field.
+///
+/// This is the BM25F scoring model: the term frequency of each field is normalized by the
+/// length of the field, following the [BM25 parameters](crate::schema::Bm25Options) of the
+/// field, and multiplied by the weight of the field. The normalized term frequencies are
+/// summed up into the term frequency of the combined field, which then saturates as in BM25.
+/// Unlike indexing the fields into a common field with
+/// [`TextOptions::set_copy_to`](crate::schema::TextOptions::set_copy_to), the weights of the
+/// fields can be changed from one query to another.
+///
+/// Compared to a [`BooleanQuery`] of term queries over each of the fields, a term frequent in
+/// one field and rare in another one does not get a higher score for its rare field, and the
+/// score of a document saturates over all of its fields.
+///
+/// The query text is analyzed with the
+/// [search tokenizer](crate::schema::TextFieldIndexing::search_tokenizer) of each field. The
+/// tokens of the different fields sharing a position are scored together as one term of the
+/// combined field, and the scores of the positions are summed up.
+///
+/// The document frequency of a term in the combined field is approximated by its highest
+/// document frequency over the fields, and its idf is computed from it. The `k1` parameter of
+/// the combined field is the average of the `k1` of the fields, weighted by their weights.
+///
+/// ```rust
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::CombinedFieldsQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, DocAddress, Index, IndexWriter};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let body = schema_builder.add_text_field("body", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer: IndexWriter = index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "The Sea", body => "A book about boats"))?;
+/// index_writer.add_document(doc!(title => "Boats", body => "A book about the sea"))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = CombinedFieldsQuery::new(&index, vec![(title, 3.0), (body, 1.0)], "The Sea")?;
+/// let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+/// assert_eq!(top_docs[0].1, DocAddress::new(0, 0));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CombinedFieldsQuery {
+    // The weighted terms of each position of the query text.
+    weighted_terms_per_position: Vec<Vec<(Term, Score)>>,
+}
+
+impl fmt::Debug for CombinedFieldsQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CombinedFieldsQuery({:?})",
+            self.weighted_terms_per_position
+        )
+    }
+}
+
+impl CombinedFieldsQuery {
+    /// Creates a query for the text `text` over the given fields of `index` and their weights.
+    ///
+    /// The text is analyzed with the search tokenizer of each field. The fields must be
+    /// indexed text fields.
+    pub fn new(
+        index: &Index,
+        field_weights: Vec<(Field, Score)>,
+        text: &str,
+    ) -> crate::Result<CombinedFieldsQuery> {
+        let schema = index.schema();
+        let mut weighted_terms_per_position: BTreeMap<usize, Vec<(Term, Score)>> = BTreeMap::new();
+        for (field, field_weight) in field_weights {
+            let field_entry = schema.get_field_entry(field);
+            let indexing_options = match field_entry.field_type() {
+                FieldType::Str(text_options) => text_options.get_indexing_options(),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                TantivyError::SchemaError(format!(
+                    "Field {:?} is not an indexed text field.",
+                    field_entry.name()
+                ))
+            })?;
+            let tokenizer_name = indexing_options.search_tokenizer();
+            let mut text_analyzer = index.tokenizers().get(tokenizer_name).ok_or_else(|| {
+                TantivyError::InvalidArgument(format!(
+                    "No tokenizer {tokenizer_name:?} found for field {:?}.",
+                    field_entry.name()
+                ))
+            })?;
+            text_analyzer.token_stream(text).process(&mut |token| {
+                weighted_terms_per_position
+                    .entry(token.position)
+                    .or_default()
+                    .push((Term::from_field_text(field, &token.text), field_weight));
+            });
+        }
+        Ok(CombinedFieldsQuery {
+            weighted_terms_per_position: weighted_terms_per_position.into_values().collect(),
+        })
+    }
+}
+
+impl Query for CombinedFieldsQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
+        match &self.weighted_terms_per_position[..] {
+            [] => EmptyQuery.weight(enable_scoring),
+            [weighted_terms] => combined_fields_weight(weighted_terms, enable_scoring),
+            _ => {
+                let subqueries: Vec<(Occur, Box<dyn Query>)> = self
+                    .weighted_terms_per_position
+                    .iter()
+                    .map(|weighted_terms| {
+                        let subquery: Box<dyn Query> = Box::new(CombinedFieldsQuery {
+                            weighted_terms_per_position: vec![weighted_terms.clone()],
+                        });
+                        (Occur::Should, subquery)
+                    })
+                    .collect();
+                BooleanQuery::new(subqueries).weight(enable_scoring)
+            }
+        }
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        for weighted_terms in &self.weighted_terms_per_position {
+            for (term, _) in weighted_terms {
+                visitor(term, false);
+            }
+        }
+    }
+}
+
+/// Builds the weight scoring the terms of a single position of the query text.
+fn combined_fields_weight(
+    weighted_terms: &[(Term, Score)],
+    enable_scoring: EnableScoring<'_>,
+) -> crate::Result<Box<dyn Weight>> {
+    let schema = enable_scoring.schema();
+    for (term, field_weight) in weighted_terms {
+        let field_entry = schema.get_field_entry(term.field());
+        if !field_entry.is_indexed() {
+            let error_msg = format!("Field {:?} is not indexed.", field_entry.name());
+            return Err(TantivyError::SchemaError(error_msg));
+        }
+        if !(field_weight.is_finite() && *field_weight > 0.0) {
+            return Err(TantivyError::InvalidArgument(format!(
+                "The weight of field {:?} must be positive, got {field_weight}.",
+                field_entry.name()
+            )));
+        }
+    }
+    let mut fields = Vec::with_capacity(weighted_terms.len());
+    let (idf, k1) = match enable_scoring {
+        EnableScoring::Enabled {
+            statistics_provider,
+            ..
+        } => {
+            let total_num_docs = statistics_provider.total_num_docs()?;
+            let mut doc_freq = 0u64;
+            let mut weighted_k1: Score = 0.0;
+            let mut total_weight: Score = 0.0;
+            for (term, field_weight) in weighted_terms {
+                doc_freq = doc_freq.max(statistics_provider.doc_freq(term)?);
+                let total_num_tokens = statistics_provider.total_num_tokens(term.field())?;
+                let options = statistics_provider.bm25_options(term.field());
+                weighted_k1 += field_weight * options.k1();
+                total_weight += field_weight;
+                fields.push(CombinedFieldParams {
+                    term: term.clone(),
+                    field_weight: *field_weight,
+                    b: options.b(),
+                    average_fieldnorm: total_num_tokens as Score / total_num_docs.max(1) as Score,
+                });
+            }
+            (idf(doc_freq, total_num_docs), weighted_k1 / total_weight)
+        }
+        EnableScoring::Disabled { .. } => {
+            for (term, field_weight) in weighted_terms {
+                fields.push(CombinedFieldParams {
+                    term: term.clone(),
+                    field_weight: *field_weight,
+                    b: 0.0,
+                    average_fieldnorm: 1.0,
+                });
+            }
+            (1.0, 1.0)
+        }
+    };
+    Ok(Box::new(CombinedFieldsWeight {
+        fields,
+        idf,
+        k1,
+        scoring_enabled: enable_scoring.is_scoring_enabled(),
+    }))
+}
+
+/// The term of a field, along with the parameters used to normalize its term frequency.
+#[derive(Clone)]
+struct CombinedFieldParams {
+    term: Term,
+    field_weight: Score,
+    b: Score,
+    average_fieldnorm: Score,
+}
+
+struct CombinedFieldsWeight {
+    fields: Vec<CombinedFieldParams>,
+    idf: Score,
+    k1: Score,
+    scoring_enabled: bool,
+}
+
+impl CombinedFieldsWeight {
+    fn specialized_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> crate::Result<CombinedFieldsScorer> {
+        let index_record_option = if self.scoring_enabled {
+            IndexRecordOption::WithFreqs
+        } else {
+            IndexRecordOption::Basic
+        };
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for params in &self.fields {
+            let field = params.term.field();
+            let fieldnorm_reader_opt = if self.scoring_enabled {
+                reader.fieldnorms_readers().get_field(field)?
+            } else {
+                None
+            };
+            let fieldnorm_reader = fieldnorm_reader_opt
+                .unwrap_or_else(|| FieldNormReader::constant(reader.max_doc(), 1));
+            let postings = reader
+                .inverted_index(field)?
+                .read_postings(&params.term, index_record_option)?
+                .unwrap_or_else(SegmentPostings::empty);
+            fields.push(CombinedField {
+                postings,
+                fieldnorm_reader,
+                params: params.clone(),
+            });
+        }
+        Ok(CombinedFieldsScorer::new(fields, self.idf * boost, self.k1))
+    }
+}
+
+impl Weight for CombinedFieldsWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.specialized_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.specialized_scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        Ok(scorer.explain())
+    }
+}
+
+struct CombinedField {
+    postings: SegmentPostings,
+    fieldnorm_reader: FieldNormReader,
+    params: CombinedFieldParams,
+}
+
+impl CombinedField {
+    /// Returns the length normalization of the field for a document of length `fieldnorm`.
+    fn length_norm(&self, fieldnorm: u32) -> Score {
+        let params = &self.params;
+        if params.average_fieldnorm <= 0.0 {
+            return 1.0;
+        }
+        1.0 - params.b + params.b * fieldnorm as Score / params.average_fieldnorm
+    }
+}
+
+/// Scorer of the union of the postings of a term in several fields, computing the BM25F score
+/// of the combined field.
+struct CombinedFieldsScorer {
+    fields: Vec<CombinedField>,
+    doc: DocId,
+    weight: Score,
+    k1: Score,
+}
+
+impl CombinedFieldsScorer {
+    fn new(fields: Vec<CombinedField>, idf: Score, k1: Score) -> Self {
+        let mut scorer = CombinedFieldsScorer {
+            fields,
+            doc: TERMINATED,
+            weight: idf * (1.0 + k1),
+            k1,
+        };
+        scorer.doc = scorer.min_doc();
+        scorer
+    }
+
+    fn min_doc(&self) -> DocId {
+        self.fields
+            .iter()
+            .map(|field| field.postings.doc())
+            .min()
+            .unwrap_or(TERMINATED)
+    }
+
+    /// Returns the term frequency of the current document in the combined field: the sum of
+    /// the weighted term frequencies of the fields, normalized by their lengths.
+    fn combined_term_freq(&self) -> Score {
+        self.fields
+            .iter()
+            .filter(|field| field.postings.doc() == self.doc)
+            .map(|field| {
+                let fieldnorm = field.fieldnorm_reader.fieldnorm(self.doc);
+                field.params.field_weight * field.postings.term_freq() as Score
+                    / field.length_norm(fieldnorm)
+            })
+            .sum()
+    }
+
+    fn tf_factor(&self, term_freq: Score) -> Score {
+        term_freq / (term_freq + self.k1)
+    }
+
+    fn explain(&self) -> Explanation {
+        let term_freq = self.combined_term_freq();
+        let mut explanation = Explanation::new(
+            "BM25F score, computed as boost * idf * (k1 + 1) * tf_factor",
+            self.score_from(term_freq),
+        );
+        let mut tf_explanation = Explanation::new(
+            "tf_factor, computed as freq / (freq + k1)",
+            self.tf_factor(term_freq),
+        );
+        let mut freq_explanation =
+            Explanation::new("freq, sum of the normalized term frequencies", term_freq);
+        for field in &self.fields {
+            if field.postings.doc() != self.doc {
+                continue;
+            }
+            let fieldnorm = field.fieldnorm_reader.fieldnorm(self.doc);
+            let mut field_explanation = Explanation::new(
+                "normalized term frequency, computed as weight * tf / (1 - b + b * dl / avgdl)",
+                field.params.field_weight * field.postings.term_freq() as Score
+                    / field.length_norm(fieldnorm),
+            );
+            field_explanation.add_const("weight, weight of the field", field.params.field_weight);
+            field_explanation.add_const(
+                "tf, term frequency in the field",
+                field.postings.term_freq() as Score,
+            );
+            field_explanation.add_const("b, length normalization parameter", field.params.b);
+            field_explanation.add_const("dl, length of the field", fieldnorm as Score);
+            field_explanation.add_const(
+                "avgdl, average length of the field",
+                field.params.average_fieldnorm,
+            );
+            freq_explanation.add_detail(field_explanation);
+        }
+        tf_explanation.add_detail(freq_explanation);
+        tf_explanation.add_const("k1, term saturation parameter", self.k1);
+        explanation.add_detail(tf_explanation);
+        explanation
+    }
+
+    fn score_from(&self, term_freq: Score) -> Score {
+        self.weight * self.tf_factor(term_freq)
+    }
+}
+
+impl DocSet for CombinedFieldsScorer {
+    fn advance(&mut self) -> DocId {
+        for field in &mut self.fields {
+            if field.postings.doc() == self.doc {
+                field.postings.advance();
+            }
+        }
+        self.doc = self.min_doc();
+        self.doc
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        for field in &mut self.fields {
+            if field.postings.doc() < target {
+                field.postings.seek(target);
+            }
+        }
+        self.doc = self.min_doc();
+        self.doc
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.fields
+            .iter()
+            .map(|field| field.postings.size_hint())
+            .sum()
+    }
+}
+
+impl Scorer for CombinedFieldsScorer {
+    fn score(&mut self) -> Score {
+        let term_freq = self.combined_term_freq();
+        self.score_from(term_freq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CombinedFieldsQuery;
+    use crate::collector::{Count, TopDocs};
+    use crate::query::{BooleanQuery, Occur, Query, TermQuery};
+    use crate::schema::{
+        Bm25Options, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, INDEXED, TEXT,
+    };
+    use crate::{doc, DocAddress, Index, IndexWriter, TantivyError, Term};
+
+    fn text_options_with_bm25(bm25_options: Bm25Options) -> TextOptions {
+        TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_bm25_options(bm25_options),
+        )
+    }
+
+    #[test]
+    fn test_combined_fields_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        // Without length normalization, the normalized term frequencies of the fields are
+        // their raw term frequencies.
+        let options = text_options_with_bm25(Bm25Options::new(1.2, 0.0));
+        let title = schema_builder.add_text_field("title", options.clone());
+        let body = schema_builder.add_text_field("body", options.clone());
+        let combined = schema_builder.add_text_field("combined", options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let docs = [
+            ("rust", "rust is a language"),
+            ("a book", "the rust programming language"),
+            ("rust rust", "rust"),
+            ("cooking", "pasta and pizza"),
+        ];
+        for (title_text, body_text) in docs {
+            index_writer.add_document(doc!(
+                title => title_text,
+                body => body_text,
+                combined => format!("{title_text} {body_text}"),
+            ))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        // The query text goes through the tokenizer of the fields.
+        let query = CombinedFieldsQuery::new(&index, vec![(title, 1.0), (body, 1.0)], "Rust")?;
+        assert_eq!(searcher.search(&query, &Count)?, 3);
+        // With unit weights, the scores are those of a field holding the text of both fields,
+        // as "rust" has the same document frequency in the body and in the combined field.
+        let combined_query = TermQuery::new(
+            Term::from_field_text(combined, "rust"),
+            IndexRecordOption::WithFreqs,
+        );
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(3))?;
+        let combined_top_docs = searcher.search(&combined_query, &TopDocs::with_limit(3))?;
+        for ((score, doc), (combined_score, combined_doc)) in
+            top_docs.iter().zip(combined_top_docs.iter())
+        {
+            assert_eq!(doc, combined_doc);
+            assert!((score - combined_score).abs() < 0.01 * combined_score);
+        }
+
+        // Weighting the title up favours the documents matching in the title.
+        let query = CombinedFieldsQuery::new(&index, vec![(title, 5.0), (body, 1.0)], "rust")?;
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(3))?;
+        assert_eq!(top_docs[0].1, DocAddress::new(0, 2));
+        assert_eq!(top_docs[1].1, DocAddress::new(0, 0));
+        let explanation = query.explain(&searcher, DocAddress::new(0, 0))?;
+        assert!((explanation.value() - top_docs[1].0).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combined_fields_query_bm25_options() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let flat_body = schema_builder.add_text_field(
+            "flat_body",
+            text_options_with_bm25(Bm25Options::new(1.2, 0.0)),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for body_text in ["rust", "rust is a systems programming language"] {
+            index_writer.add_document(doc!(
+                title => "a book",
+                body => body_text,
+                flat_body => body_text,
+            ))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let scores = |query: &CombinedFieldsQuery| -> crate::Result<Vec<f32>> {
+            Ok(searcher
+                .search(query, &TopDocs::with_limit(2))?
+                .into_iter()
+                .map(|(score, _)| score)
+                .collect())
+        };
+
+        // The length of the body is normalized following its BM25 parameters...
+        let query = CombinedFieldsQuery::new(&index, vec![(title, 1.0), (body, 1.0)], "rust")?;
+        let body_scores = scores(&query)?;
+        assert!(body_scores[0] > body_scores[1]);
+        // ... and ignored when the field disables the length normalization.
+        let query = CombinedFieldsQuery::new(&index, vec![(title, 1.0), (flat_body, 1.0)], "rust")?;
+        let flat_body_scores = scores(&query)?;
+        assert!((flat_body_scores[0] - flat_body_scores[1]).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_combined_fields_query_in_boolean_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let id = schema_builder.add_u64_field("id", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer
+            .add_document(doc!(title => "rust", body => "a systems language", id => 1u64))?;
+        index_writer.add_document(doc!(title => "go", body => "another language", id => 2u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let combined_query = |text: &str| -> crate::Result<Box<dyn Query>> {
+            Ok(Box::new(CombinedFieldsQuery::new(
+                &index,
+                vec![(title, 2.0), (body, 1.0)],
+                text,
+            )?))
+        };
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, combined_query("rust")?),
+            (Occur::Must, combined_query("language")?),
+        ]);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+        assert_eq!(top_docs.len(), 1);
+        assert_eq!(top_docs[0].1, DocAddress::new(0, 0));
+
+        // The scores of the tokens of the query text are summed up.
+        let query = combined_query("rust language")?;
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+        assert_eq!(top_docs.len(), 2);
+        assert_eq!(top_docs[0].1, DocAddress::new(0, 0));
+        assert_eq!(searcher.search(&combined_query("")?, &Count)?, 0);
+
+        let query = CombinedFieldsQuery::new(&index, vec![(title, 0.0)], "rust")?;
+        assert!(matches!(
+            searcher.search(&query, &Count),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            CombinedFieldsQuery::new(&index, vec![(id, 1.0)], "1"),
+            Err(TantivyError::SchemaError(_))
+        ));
+        Ok(())
+    }
+}
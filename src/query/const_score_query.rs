@@ -1,7 +1,7 @@
 use std::fmt;
 
 use crate::docset::COLLECT_BLOCK_BUFFER_LEN;
-use crate::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use crate::query::{CostEstimate, EnableScoring, Explanation, Query, Scorer, Weight};
 use crate::{DocId, DocSet, Score, SegmentReader, TantivyError, Term};
 
 /// `ConstScoreQuery` is a wrapper over a query to provide a constant score.
@@ -81,6 +81,10 @@ impl Weight for ConstWeight {
         Ok(explanation)
     }
 
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        self.weight.estimate_cost(reader)
+    }
+
     fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
         self.weight.count(reader)
     }
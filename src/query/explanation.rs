@@ -54,6 +54,22 @@ impl Explanation {
         self.value
     }
 
+    /// Returns the description of the current node.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the children of the current node, explaining
+    /// how its value was computed.
+    pub fn details(&self) -> &[Explanation] {
+        self.details.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns the extra context attached to the current node.
+    pub fn context(&self) -> &[String] {
+        self.context.as_deref().unwrap_or(&[])
+    }
+
     /// Add some detail, explaining some part of the current node formula.
     ///
     /// Details are treated as child of the current node.
@@ -1,9 +1,11 @@
 mod all_query;
 mod automaton_weight;
 mod bitset;
+mod bitset_query;
 mod bm25;
 mod boolean_query;
 mod boost_query;
+mod combined_fields_query;
 mod const_score_query;
 mod disjunction;
 mod disjunction_max_query;
@@ -18,9 +20,11 @@ mod phrase_prefix_query;
 mod phrase_query;
 mod query;
 mod query_parser;
+mod query_plan;
 mod range_query;
 mod regex_query;
 mod reqopt_scorer;
+mod score_breakdown;
 mod scorer;
 mod set_query;
 mod term_query;
@@ -36,9 +40,11 @@ pub use query_grammar::Occur;
 pub use self::all_query::{AllQuery, AllScorer, AllWeight};
 pub use self::automaton_weight::AutomatonWeight;
 pub use self::bitset::BitSetDocSet;
+pub use self::bitset_query::{BitSetQuery, BitSetWeight};
 pub use self::bm25::{Bm25StatisticsProvider, Bm25Weight};
 pub use self::boolean_query::{BooleanQuery, BooleanWeight};
 pub use self::boost_query::{BoostQuery, BoostWeight};
+pub use self::combined_fields_query::CombinedFieldsQuery;
 pub use self::const_score_query::{ConstScoreQuery, ConstScorer};
 pub use self::disjunction_max_query::DisjunctionMaxQuery;
 pub use self::empty_query::{EmptyQuery, EmptyScorer, EmptyWeight};
@@ -53,10 +59,17 @@ pub use self::more_like_this::{MoreLikeThisQuery, MoreLikeThisQueryBuilder};
 pub use self::phrase_prefix_query::PhrasePrefixQuery;
 pub use self::phrase_query::PhraseQuery;
 pub use self::query::{EnableScoring, Query, QueryClone};
-pub use self::query_parser::{QueryParser, QueryParserError};
-pub use self::range_query::{FastFieldRangeWeight, IPFastFieldRangeWeight, RangeQuery};
+pub use self::query_parser::{
+    QueryExpander, QueryParser, QueryParserError, QuerySynonyms, QuerySyntaxHandler,
+    StopWordsFallback,
+};
+pub use self::query_plan::{CostEstimate, QueryPlan, SegmentQueryPlan};
+pub use self::range_query::{
+    FastFieldRangeWeight, IPFastFieldRangeWeight, RangeFieldQuery, RangeQuery, RangeRelation,
+};
 pub use self::regex_query::RegexQuery;
 pub use self::reqopt_scorer::RequiredOptionalScorer;
+pub use self::score_breakdown::ScoreBreakdown;
 pub use self::score_combiner::{
     DisjunctionMaxCombiner, ScoreCombiner, SumCombiner, SumWithCoordsCombiner,
 };
@@ -4,7 +4,8 @@ use crate::index::SegmentReader;
 use crate::postings::SegmentPostings;
 use crate::query::bm25::Bm25Weight;
 use crate::query::explanation::does_not_match;
-use crate::query::{EmptyScorer, Explanation, Scorer, Weight};
+use crate::query::phrase_query::check_positions_component;
+use crate::query::{CostEstimate, EmptyScorer, Explanation, Scorer, Weight};
 use crate::schema::{IndexRecordOption, Term};
 use crate::{DocId, DocSet, Score};
 
@@ -47,6 +48,7 @@ impl PhrasePrefixWeight {
         reader: &SegmentReader,
         boost: Score,
     ) -> crate::Result<Option<PhrasePrefixScorer<SegmentPostings>>> {
+        check_positions_component(reader, self.prefix.1.field())?;
         let similarity_weight_opt = self
             .similarity_weight_opt
             .as_ref()
@@ -135,6 +137,43 @@ impl Weight for PhrasePrefixWeight {
         }
     }
 
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        // The phrase can only match the documents containing its rarest term, and one
+        // of the terms its prefix expands to.
+        let mut cost_estimate = CostEstimate {
+            num_docs: reader.max_doc() as u64,
+            num_expanded_terms: 0,
+        };
+        for (_, term) in &self.phrase_terms {
+            let inverted_index = reader.inverted_index(term.field())?;
+            match inverted_index.get_term_info(term)? {
+                Some(term_info) => {
+                    cost_estimate.num_docs = cost_estimate.num_docs.min(term_info.doc_freq as u64);
+                    cost_estimate.num_expanded_terms += 1;
+                }
+                None => cost_estimate.num_docs = 0,
+            }
+        }
+        let inv_index = reader.inverted_index(self.prefix.1.field())?;
+        let mut stream = inv_index
+            .terms()
+            .range()
+            .ge(self.prefix.1.serialized_value_bytes());
+        if let Some(end) = prefix_end(self.prefix.1.serialized_value_bytes()) {
+            stream = stream.lt(&end);
+        }
+        let mut stream = stream.into_stream()?;
+        let mut num_suffixes = 0;
+        let mut suffixes_doc_freq = 0;
+        while num_suffixes < self.max_expansions && stream.advance() {
+            suffixes_doc_freq += stream.value().doc_freq as u64;
+            num_suffixes += 1;
+        }
+        cost_estimate.num_docs = cost_estimate.num_docs.min(suffixes_doc_freq);
+        cost_estimate.num_expanded_terms += num_suffixes as u64;
+        Ok(cost_estimate)
+    }
+
     fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
         let scorer_opt = self.phrase_scorer(reader, 1.0)?;
         if scorer_opt.is_none() {
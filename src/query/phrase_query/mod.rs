@@ -6,6 +6,22 @@ pub use self::phrase_query::PhraseQuery;
 pub(crate) use self::phrase_scorer::intersection_count;
 pub use self::phrase_scorer::PhraseScorer;
 pub use self::phrase_weight::PhraseWeight;
+use crate::index::{SegmentComponent, SegmentReader};
+use crate::schema::Field;
+
+/// Returns an error if the positions were skipped when opening the segment reader
+/// (see [`SegmentReader::open_without_components`]), as a phrase cannot be matched
+/// without them.
+pub(crate) fn check_positions_component(reader: &SegmentReader, field: Field) -> crate::Result<()> {
+    if reader.has_component(SegmentComponent::Positions) {
+        return Ok(());
+    }
+    let field_name = reader.schema().get_field_name(field);
+    Err(crate::TantivyError::InvalidArgument(format!(
+        "Applied phrase query on field {field_name:?}, but the positions were skipped when \
+         opening the segment reader"
+    )))
+}
 
 #[cfg(test)]
 pub mod tests {
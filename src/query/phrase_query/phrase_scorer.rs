@@ -45,6 +45,9 @@ impl<TPostings: Postings> DocSet for PostingsWithOffset<TPostings> {
 
 pub struct PhraseScorer<TPostings: Postings> {
     intersection_docset: Intersection<PostingsWithOffset<TPostings>, PostingsWithOffset<TPostings>>,
+    // Ordinals, within the intersection, of the docsets having positions.
+    // The other ones only filter the documents.
+    positional_docsets: Vec<usize>,
     num_terms: usize,
     left_positions: Vec<u32>,
     right_positions: Vec<u32>,
@@ -381,9 +384,19 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
                 PostingsWithOffset::new(postings, (max_offset - offset) as u32)
             })
             .collect::<Vec<_>>();
+        let mut intersection_docset = Intersection::new(postings_with_offsets);
+        let positional_docsets: Vec<usize> = (0..num_docsets)
+            .filter(|&ord| {
+                intersection_docset
+                    .docset_mut_specialized(ord)
+                    .postings
+                    .has_positions()
+            })
+            .collect();
         let mut scorer = PhraseScorer {
-            intersection_docset: Intersection::new(postings_with_offsets),
-            num_terms: num_docsets,
+            intersection_docset,
+            num_terms: positional_docsets.len(),
+            positional_docsets,
             left_positions: Vec::with_capacity(100),
             right_positions: Vec::with_capacity(100),
             phrase_count: 0u32,
@@ -410,6 +423,11 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
     }
 
     fn phrase_match(&mut self) -> bool {
+        if self.num_terms == 0 {
+            // None of the terms have positions: the phrase is assumed to match once.
+            self.phrase_count = 1;
+            return true;
+        }
         if self.similarity_weight_opt.is_some() {
             let count = self.compute_phrase_count();
             self.phrase_count = count;
@@ -461,7 +479,7 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
     fn compute_phrase_match(&mut self) {
         {
             self.intersection_docset
-                .docset_mut_specialized(0)
+                .docset_mut_specialized(self.positional_docsets[0])
                 .positions(&mut self.left_positions);
             if self.has_slop() {
                 self.left_slops.clear();
@@ -470,7 +488,7 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
         for i in 1..self.num_terms - 1 {
             {
                 self.intersection_docset
-                    .docset_mut_specialized(i)
+                    .docset_mut_specialized(self.positional_docsets[i])
                     .positions(&mut self.right_positions);
             }
             if self.has_slop() {
@@ -500,7 +518,7 @@ impl<TPostings: Postings> PhraseScorer<TPostings> {
             }
         }
         self.intersection_docset
-            .docset_mut_specialized(self.num_terms - 1)
+            .docset_mut_specialized(self.positional_docsets[self.num_terms - 1])
             .positions(&mut self.right_positions);
     }
 
@@ -1,10 +1,10 @@
-use super::PhraseScorer;
+use super::{check_positions_component, PhraseScorer};
 use crate::fieldnorm::FieldNormReader;
 use crate::index::SegmentReader;
 use crate::postings::SegmentPostings;
 use crate::query::bm25::Bm25Weight;
 use crate::query::explanation::does_not_match;
-use crate::query::{EmptyScorer, Explanation, Scorer, Weight};
+use crate::query::{CostEstimate, EmptyScorer, Explanation, Scorer, Weight};
 use crate::schema::{IndexRecordOption, Term};
 use crate::{DocId, DocSet, Score};
 
@@ -44,6 +44,7 @@ impl PhraseWeight {
         reader: &SegmentReader,
         boost: Score,
     ) -> crate::Result<Option<PhraseScorer<SegmentPostings>>> {
+        check_positions_component(reader, self.phrase_terms[0].1.field())?;
         let similarity_weight_opt = self
             .similarity_weight_opt
             .as_ref()
@@ -95,6 +96,25 @@ impl Weight for PhraseWeight {
         }
     }
 
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        // The phrase can only match the documents containing its rarest term.
+        let mut cost_estimate = CostEstimate {
+            num_docs: reader.max_doc() as u64,
+            num_expanded_terms: 0,
+        };
+        for (_, term) in &self.phrase_terms {
+            let inverted_index = reader.inverted_index(term.field())?;
+            match inverted_index.get_term_info(term)? {
+                Some(term_info) => {
+                    cost_estimate.num_docs = cost_estimate.num_docs.min(term_info.doc_freq as u64);
+                    cost_estimate.num_expanded_terms += 1;
+                }
+                None => cost_estimate.num_docs = 0,
+            }
+        }
+        Ok(cost_estimate)
+    }
+
     fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
         let scorer_opt = self.phrase_scorer(reader, 1.0)?;
         if scorer_opt.is_none() {
@@ -181,6 +181,10 @@ impl Query for Box<dyn Query> {
         self.as_ref().weight(enabled_scoring)
     }
 
+    fn explain(&self, searcher: &Searcher, doc_address: DocAddress) -> crate::Result<Explanation> {
+        self.as_ref().explain(searcher, doc_address)
+    }
+
     fn count(&self, searcher: &Searcher) -> crate::Result<usize> {
         self.as_ref().count(searcher)
     }
@@ -1,8 +1,11 @@
 use std::fmt;
 use std::ops::Bound;
+use std::sync::Arc;
 
-use crate::query::Occur;
-use crate::schema::{Term, Type};
+use tantivy_fst::Regex;
+
+use crate::query::{Occur, Query};
+use crate::schema::{Field, Term, Type};
 use crate::Score;
 
 #[derive(Clone)]
@@ -23,6 +26,16 @@ pub enum LogicalLiteral {
         elements: Vec<Term>,
     },
     All,
+    Prefix(Term),
+    Regex {
+        field: Field,
+        pattern: String,
+        regex: Arc<Regex>,
+    },
+    Exists {
+        field_name: String,
+    },
+    Extension(Arc<dyn Query>),
 }
 
 pub enum LogicalAst {
@@ -121,6 +134,12 @@ impl fmt::Debug for LogicalLiteral {
                 write!(formatter, "]")
             }
             LogicalLiteral::All => write!(formatter, "*"),
+            LogicalLiteral::Prefix(ref term) => write!(formatter, "{term:?}*"),
+            LogicalLiteral::Regex {
+                field, ref pattern, ..
+            } => write!(formatter, "Regex(field={}, /{pattern}/)", field.field_id()),
+            LogicalLiteral::Exists { ref field_name } => write!(formatter, "\"{field_name}\":*"),
+            LogicalLiteral::Extension(ref query) => write!(formatter, "{query:?}"),
         }
     }
 }
@@ -1,4 +1,8 @@
+mod query_expander;
 mod query_parser;
+mod syntax_handler;
 
 pub mod logical_ast;
-pub use self::query_parser::{QueryParser, QueryParserError};
+pub use self::query_expander::{QueryExpander, QuerySynonyms};
+pub use self::query_parser::{QueryParser, QueryParserError, StopWordsFallback};
+pub use self::syntax_handler::QuerySyntaxHandler;
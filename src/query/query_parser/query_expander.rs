@@ -0,0 +1,106 @@
+use rustc_hash::FxHashMap;
+
+use crate::schema::Field;
+use crate::Score;
+
+/// Expands the words of a query into weighted alternatives, at parse time.
+///
+/// When a [`QueryParser`](crate::query::QueryParser) has a `QueryExpander`, each text term
+/// it produces is searched along with its alternatives, as a disjunction in which each
+/// alternative is boosted by its weight. As the expansion happens at query time, the
+/// alternatives can be updated without reindexing.
+///
+/// The expander is given the text of the term, as emitted by the search tokenizer of the
+/// field, and the alternatives it returns are searched verbatim. Terms of phrases and
+/// prefix terms are not expanded.
+///
+/// `QueryExpander` is implemented by closures, and by [`QuerySynonyms`].
+pub trait QueryExpander: Send + Sync + 'static {
+    /// Returns the alternatives of `text` in the given field, along with their weights.
+    fn expand(&self, field: Field, text: &str) -> Vec<(String, Score)>;
+}
+
+impl<F> QueryExpander for F
+where F: Fn(Field, &str) -> Vec<(String, Score)> + Send + Sync + 'static
+{
+    fn expand(&self, field: Field, text: &str) -> Vec<(String, Score)> {
+        (self)(field, text)
+    }
+}
+
+/// Dictionary of weighted synonyms, applied to all of the fields.
+///
+/// ```rust
+/// use tantivy::query::{QueryParser, QuerySynonyms};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::Index;
+///
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+///
+/// let mut query_parser = QueryParser::for_index(&index, vec![title]);
+/// query_parser.set_query_expander(
+///     QuerySynonyms::default()
+///         .add_synonym("car", "automobile", 0.8)
+///         .add_synonym("car", "vehicle", 0.5),
+/// );
+/// let query = query_parser.parse_query("Car").unwrap();
+/// assert_eq!(
+///     format!("{query:?}"),
+///     "BooleanQuery { subqueries: [\
+///         (Should, TermQuery(Term(field=0, type=Str, \"car\"))), \
+///         (Should, Boost(query=TermQuery(Term(field=0, type=Str, \"automobile\")), boost=0.8)), \
+///         (Should, Boost(query=TermQuery(Term(field=0, type=Str, \"vehicle\")), boost=0.5))\
+///     ], minimum_number_should_match: 1 }"
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct QuerySynonyms {
+    synonyms: FxHashMap<String, Vec<(String, Score)>>,
+}
+
+impl QuerySynonyms {
+    /// Adds a one-way synonym: `word` is expanded into `synonym`, with the given weight.
+    ///
+    /// If the synonym was already added for `word`, its weight is updated.
+    #[must_use]
+    pub fn add_synonym(mut self, word: &str, synonym: &str, weight: Score) -> QuerySynonyms {
+        let synonyms = self.synonyms.entry(word.to_string()).or_default();
+        if let Some(entry) = synonyms.iter_mut().find(|(text, _)| text == synonym) {
+            entry.1 = weight;
+        } else {
+            synonyms.push((synonym.to_string(), weight));
+        }
+        self
+    }
+}
+
+impl QueryExpander for QuerySynonyms {
+    fn expand(&self, _field: Field, text: &str) -> Vec<(String, Score)> {
+        self.synonyms.get(text).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QueryExpander, QuerySynonyms};
+    use crate::schema::Field;
+
+    #[test]
+    fn test_query_synonyms() {
+        let synonyms = QuerySynonyms::default()
+            .add_synonym("car", "automobile", 0.5)
+            .add_synonym("car", "vehicle", 0.3)
+            .add_synonym("car", "automobile", 0.8);
+        let field = Field::from_field_id(0);
+        assert_eq!(
+            synonyms.expand(field, "car"),
+            vec![
+                ("automobile".to_string(), 0.8),
+                ("vehicle".to_string(), 0.3)
+            ]
+        );
+        assert!(synonyms.expand(field, "automobile").is_empty());
+    }
+}
@@ -2,20 +2,24 @@ use std::net::{AddrParseError, IpAddr};
 use std::num::{ParseFloatError, ParseIntError};
 use std::ops::Bound;
 use std::str::{FromStr, ParseBoolError};
+use std::sync::Arc;
 
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use itertools::Itertools;
-use query_grammar::{UserInputAst, UserInputBound, UserInputLeaf, UserInputLiteral};
+use query_grammar::{Delimiter, UserInputAst, UserInputBound, UserInputLeaf, UserInputLiteral};
 use rustc_hash::FxHashMap;
+use tantivy_fst::Regex;
 
 use super::logical_ast::*;
+use super::query_expander::QueryExpander;
+use super::syntax_handler::QuerySyntaxHandler;
 use crate::index::Index;
 use crate::json_utils::convert_to_fast_value_and_append_to_json_term;
 use crate::query::range_query::{is_type_valid_for_fastfield_range_query, RangeQuery};
 use crate::query::{
-    AllQuery, BooleanQuery, BoostQuery, EmptyQuery, FuzzyTermQuery, Occur, PhrasePrefixQuery,
-    PhraseQuery, Query, TermQuery, TermSetQuery,
+    AllQuery, BooleanQuery, BoostQuery, EmptyQuery, ExistsQuery, FuzzyTermQuery, Occur,
+    PhrasePrefixQuery, PhraseQuery, Query, QueryClone, RegexQuery, TermQuery, TermSetQuery,
 };
 use crate::schema::{
     Facet, FacetParseError, Field, FieldType, IndexRecordOption, IntoIpv6Addr, JsonObjectOptions,
@@ -23,7 +27,7 @@ use crate::schema::{
 };
 use crate::time::format_description::well_known::Rfc3339;
 use crate::time::OffsetDateTime;
-use crate::tokenizer::{TextAnalyzer, TokenizerManager};
+use crate::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer, TokenizerManager};
 use crate::{DateTime, Score};
 
 /// Possible error that may happen when parsing a query.
@@ -102,6 +106,14 @@ pub enum QueryParserError {
     /// The format for the ip field is invalid.
     #[error("The ip field is malformed: {0}")]
     IpFormatError(#[from] AddrParseError),
+    /// The regex of a regex query is invalid.
+    #[error("The regex '{pattern}' is invalid: {message}")]
+    InvalidRegex {
+        /// The pattern of the regex
+        pattern: String,
+        /// The error reported by the regex parser
+        message: String,
+    },
 }
 
 /// Recursively remove empty clause from the AST
@@ -182,6 +194,25 @@ fn trim_ast(logical_ast: LogicalAst) -> Option<LogicalAst> {
 ///
 /// * all docs query: A plain `*` will match all documents in the index.
 ///
+/// * exists query: `field:*` will match all documents with at least one value in `field`, which
+///   must be a fast field.
+///
+/// * prefix terms: A word ending with `*` will match all the terms starting with the word, e.g.
+///   `title:barack*` will match documents whose title contains `barack` or `barackobama`. The `*`
+///   can be escaped with a `\`.
+///
+/// * regex terms: A regex delimited by slashes will match all the terms matching the regex, e.g.
+///   `title:/ba[rs]ack/`. Slashes inside of the regex are escaped with a `\`, and the targeted
+///   field must be specified.
+///
+/// * custom syntax: `name(args)`, e.g. `near(a, b, 5)` or `location:circle(48.8, 2.3, 10km)`, is
+///   interpreted by the [`QuerySyntaxHandler`] registered for `name` with
+///   [`QueryParser::register_syntax_handler`]. Without a handler for `name`, it is parsed as usual.
+///
+/// Prefix and regex queries can be expensive, since they may expand into a large number of
+/// terms. They can be disabled with [`QueryParser::set_prefix_queries_enabled`] and
+/// [`QueryParser::set_regex_queries_enabled`], for instance when parsing queries typed by users.
+///
 /// Parts of the queries can be boosted by appending `^boostfactor`.
 /// For instance, `"SRE"^2.0 OR devops^0.4` will boost documents containing `SRE` instead of
 /// devops. Negative boosts are not allowed.
@@ -207,6 +238,32 @@ pub struct QueryParser {
     tokenizer_manager: TokenizerManager,
     boost: FxHashMap<Field, Score>,
     fuzzy: FxHashMap<Field, Fuzzy>,
+    tokenizers: FxHashMap<Field, String>,
+    prefix_queries_enabled: bool,
+    regex_queries_enabled: bool,
+    query_expander: Option<Arc<dyn QueryExpander>>,
+    syntax_handlers: FxHashMap<String, Arc<dyn QuerySyntaxHandler>>,
+    stop_words_fallback: StopWordsFallback,
+    // Set when the query is analyzed again, because it only contained stop words.
+    ignore_field_analyzers: bool,
+}
+
+/// Defines how the [`QueryParser`] handles a query whose words are all removed
+/// by the analyzers of the fields, typically because they are stop words (e.g. `the the`).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StopWordsFallback {
+    /// The query matches no document.
+    #[default]
+    Empty,
+    /// The query matches all of the documents.
+    MatchAll,
+    /// The words of the query are searched as they are, ignoring the analyzers of the
+    /// fields: they are only split on non-alphanumeric characters and lowercased.
+    ///
+    /// This only matches documents if the stop words were kept at indexing time, e.g.
+    /// when they are only removed by the
+    /// [search tokenizer](crate::schema::TextFieldIndexing::set_search_tokenizer).
+    RawTerms,
 }
 
 #[derive(Clone)]
@@ -235,6 +292,24 @@ fn make_non_negative(ast: &mut LogicalAst) {
     }
 }
 
+// Returns true if the ast contains no leaf, e.g. because all of the words of the query
+// were removed by the analyzers.
+fn is_empty(ast: &LogicalAst) -> bool {
+    match ast {
+        LogicalAst::Leaf(_) => false,
+        LogicalAst::Boost(ref child_ast, _) => is_empty(child_ast),
+        LogicalAst::Clause(children) => children.iter().all(|(_, child)| is_empty(child)),
+    }
+}
+
+fn contains_literal(user_input_ast: &UserInputAst) -> bool {
+    match user_input_ast {
+        UserInputAst::Leaf(leaf) => matches!(**leaf, UserInputLeaf::Literal(_)),
+        UserInputAst::Boost(ref child_ast, _) => contains_literal(child_ast),
+        UserInputAst::Clause(children) => children.iter().any(|(_, child)| contains_literal(child)),
+    }
+}
+
 /// Similar to the try/? macro, but returns a tuple of (None, Vec<Error>) instead of Err(Error)
 macro_rules! try_tuple {
     ($expr:expr) => {{
@@ -261,6 +336,13 @@ impl QueryParser {
             conjunction_by_default: false,
             boost: Default::default(),
             fuzzy: Default::default(),
+            tokenizers: Default::default(),
+            prefix_queries_enabled: true,
+            regex_queries_enabled: true,
+            query_expander: None,
+            syntax_handlers: Default::default(),
+            stop_words_fallback: StopWordsFallback::default(),
+            ignore_field_analyzers: false,
         }
     }
 
@@ -322,6 +404,84 @@ impl QueryParser {
         );
     }
 
+    /// Sets the tokenizer used to analyze the query text for a specific field.
+    ///
+    /// By default, the query text is analyzed with the
+    /// [search tokenizer](crate::schema::TextFieldIndexing::search_tokenizer) of the field,
+    /// which unless specified in the schema is the tokenizer used at indexing time.
+    /// The tokenizer must be registered in the [`TokenizerManager`] of the `QueryParser`.
+    pub fn set_field_tokenizer(&mut self, field: Field, tokenizer_name: &str) {
+        self.tokenizers.insert(field, tokenizer_name.to_string());
+    }
+
+    /// Returns the text analyzer used to analyze the query text for the given field.
+    fn text_analyzer(
+        &self,
+        field: Field,
+        field_name: &str,
+        indexing_options: &TextFieldIndexing,
+    ) -> Result<TextAnalyzer, QueryParserError> {
+        if self.ignore_field_analyzers {
+            return Ok(TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(LowerCaser)
+                .build());
+        }
+        let tokenizer_name = self
+            .tokenizers
+            .get(&field)
+            .map(String::as_str)
+            .unwrap_or_else(|| indexing_options.search_tokenizer());
+        self.tokenizer_manager.get(tokenizer_name).ok_or_else(|| {
+            QueryParserError::UnknownTokenizer {
+                field: field_name.to_string(),
+                tokenizer: tokenizer_name.to_string(),
+            }
+        })
+    }
+
+    /// Sets whether prefix terms, such as `title:barack*`, are allowed.
+    ///
+    /// Prefix queries are enabled by default. When disabled, parsing a query
+    /// containing a prefix term returns an error.
+    pub fn set_prefix_queries_enabled(&mut self, enabled: bool) {
+        self.prefix_queries_enabled = enabled;
+    }
+
+    /// Sets whether regex terms, such as `title:/ba[rs]ack/`, are allowed.
+    ///
+    /// Regex queries are enabled by default. When disabled, parsing a query
+    /// containing a regex term returns an error.
+    pub fn set_regex_queries_enabled(&mut self, enabled: bool) {
+        self.regex_queries_enabled = enabled;
+    }
+
+    /// Sets the [`QueryExpander`] used to search the text terms of the query
+    /// along with their weighted alternatives, e.g. their synonyms.
+    ///
+    /// Replacing the expander only affects the queries parsed afterwards.
+    pub fn set_query_expander<E: QueryExpander>(&mut self, query_expander: E) {
+        self.query_expander = Some(Arc::new(query_expander));
+    }
+
+    /// Registers the [`QuerySyntaxHandler`] building the queries of the custom syntax
+    /// `name(args)`, e.g. `near(a, b, 5)`.
+    ///
+    /// Registering a handler for a name replaces the previous one. `name(args)` is only
+    /// interpreted as a custom syntax if a handler is registered for `name`, and is otherwise
+    /// parsed as usual, e.g. as a term followed by a group. `AND`, `OR`, `NOT` and `IN` are
+    /// operators of the query language, and cannot be used as names.
+    pub fn register_syntax_handler<H: QuerySyntaxHandler>(&mut self, name: &str, handler: H) {
+        self.syntax_handlers
+            .insert(name.to_string(), Arc::new(handler));
+    }
+
+    /// Sets how a query whose words are all stop words is handled.
+    ///
+    /// By default, such a query matches no document. See [`StopWordsFallback`].
+    pub fn set_stop_words_fallback(&mut self, stop_words_fallback: StopWordsFallback) {
+        self.stop_words_fallback = stop_words_fallback;
+    }
+
     /// Parse a query
     ///
     /// Note that `parse_query` returns an error if the input
@@ -371,10 +531,15 @@ impl QueryParser {
         (convert_to_query(&self.fuzzy, logical_ast), errors)
     }
 
+    fn syntax_handler_names(&self) -> Vec<&str> {
+        self.syntax_handlers.keys().map(String::as_str).collect()
+    }
+
     /// Parse the user query into an AST.
     fn parse_query_to_logical_ast(&self, query: &str) -> Result<LogicalAst, QueryParserError> {
-        let user_input_ast = query_grammar::parse_query(query)
-            .map_err(|_| QueryParserError::SyntaxError(query.to_string()))?;
+        let user_input_ast =
+            query_grammar::parse_query_with_extensions(query, &self.syntax_handler_names())
+                .map_err(|_| QueryParserError::SyntaxError(query.to_string()))?;
         let (ast, mut err) = self.compute_logical_ast_lenient(user_input_ast);
         if !err.is_empty() {
             return Err(err.swap_remove(0));
@@ -387,7 +552,8 @@ impl QueryParser {
         &self,
         query: &str,
     ) -> (LogicalAst, Vec<QueryParserError>) {
-        let (user_input_ast, errors) = query_grammar::parse_query_lenient(query);
+        let (user_input_ast, errors) =
+            query_grammar::parse_query_lenient_with_extensions(query, &self.syntax_handler_names());
         let mut errors: Vec<_> = errors
             .into_iter()
             .map(|error| {
@@ -406,7 +572,30 @@ impl QueryParser {
         &self,
         user_input_ast: UserInputAst,
     ) -> (LogicalAst, Vec<QueryParserError>) {
+        let raw_terms_user_input_ast = if self.stop_words_fallback == StopWordsFallback::RawTerms {
+            Some(user_input_ast.clone())
+        } else {
+            None
+        };
+        let has_literal = contains_literal(&user_input_ast);
         let (mut ast, mut err) = self.compute_logical_ast_with_occur_lenient(user_input_ast);
+        if has_literal && err.is_empty() && is_empty(&ast) {
+            // All of the words of the query were removed by the analyzers.
+            match self.stop_words_fallback {
+                StopWordsFallback::Empty => {}
+                StopWordsFallback::MatchAll => {
+                    return (LogicalLiteral::All.into(), err);
+                }
+                StopWordsFallback::RawTerms => {
+                    if let Some(user_input_ast) = raw_terms_user_input_ast {
+                        let mut raw_terms_parser = self.clone();
+                        raw_terms_parser.stop_words_fallback = StopWordsFallback::Empty;
+                        raw_terms_parser.ignore_field_analyzers = true;
+                        return raw_terms_parser.compute_logical_ast_lenient(user_input_ast);
+                    }
+                }
+            }
+        }
         if let LogicalAst::Clause(children) = &ast {
             if children.is_empty() {
                 return (ast, err);
@@ -460,20 +649,17 @@ impl QueryParser {
             }
             FieldType::Date(_) => {
                 let dt = OffsetDateTime::parse(phrase, &Rfc3339)?;
-                Ok(Term::from_field_date(field, DateTime::from_utc(dt)))
+                Ok(Term::from_field_date_full_precision(
+                    field,
+                    DateTime::from_utc(dt),
+                ))
             }
             FieldType::Str(ref str_options) => {
                 let option = str_options.get_indexing_options().ok_or_else(|| {
                     // This should have been seen earlier really.
                     QueryParserError::FieldNotIndexed(field_entry.name().to_string())
                 })?;
-                let mut text_analyzer =
-                    self.tokenizer_manager
-                        .get(option.tokenizer())
-                        .ok_or_else(|| QueryParserError::UnknownTokenizer {
-                            field: field_entry.name().to_string(),
-                            tokenizer: option.tokenizer().to_string(),
-                        })?;
+                let mut text_analyzer = self.text_analyzer(field, field_entry.name(), option)?;
                 let mut terms: Vec<Term> = Vec::new();
                 let mut token_stream = text_analyzer.token_stream(phrase);
                 token_stream.process(&mut |token| {
@@ -510,6 +696,7 @@ impl QueryParser {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn compute_logical_ast_for_leaf(
         &self,
         field: Field,
@@ -517,6 +704,7 @@ impl QueryParser {
         phrase: &str,
         slop: u32,
         prefix: bool,
+        term_prefix: bool,
     ) -> Result<Vec<LogicalLiteral>, QueryParserError> {
         let field_entry = self.schema.get_field_entry(field);
         let field_type = field_entry.field_type();
@@ -530,6 +718,12 @@ impl QueryParser {
                 "{field_name}.{json_path}"
             )));
         }
+        if term_prefix && field_type.value_type() != Type::Str {
+            return Err(QueryParserError::UnsupportedQuery(format!(
+                "Prefix queries are only supported on text fields, '{field_name}' is not a text \
+                 field."
+            )));
+        }
         match *field_type {
             FieldType::U64(_) => {
                 let val: u64 = u64::from_str(phrase)?;
@@ -561,14 +755,17 @@ impl QueryParser {
                     // This should have been seen earlier really.
                     QueryParserError::FieldNotIndexed(field_name.to_string())
                 })?;
-                let mut text_analyzer = self
-                    .tokenizer_manager
-                    .get(indexing_options.tokenizer())
-                    .ok_or_else(|| QueryParserError::UnknownTokenizer {
-                        field: field_name.to_string(),
-                        tokenizer: indexing_options.tokenizer().to_string(),
-                    })?;
-                Ok(generate_literals_for_str(
+                let mut text_analyzer = self.text_analyzer(field, field_name, indexing_options)?;
+                if term_prefix {
+                    return generate_prefix_literals_for_str(
+                        field_name,
+                        field,
+                        phrase,
+                        indexing_options,
+                        &mut text_analyzer,
+                    );
+                }
+                generate_literals_for_str(
                     field_name,
                     field,
                     phrase,
@@ -576,18 +773,24 @@ impl QueryParser {
                     prefix,
                     indexing_options,
                     &mut text_analyzer,
-                )?
-                .into_iter()
-                .collect())
+                )
+            }
+            FieldType::JsonObject(ref json_options) => {
+                let text_options = json_options.get_text_indexing_options().ok_or_else(|| {
+                    // This should have been seen earlier really.
+                    QueryParserError::FieldNotIndexed(field_name.to_string())
+                })?;
+                let mut text_analyzer = self.text_analyzer(field, field_name, text_options)?;
+                generate_literals_for_json_object(
+                    field_name,
+                    field,
+                    json_path,
+                    phrase,
+                    &mut text_analyzer,
+                    text_options,
+                    json_options,
+                )
             }
-            FieldType::JsonObject(ref json_options) => generate_literals_for_json_object(
-                field_name,
-                field,
-                json_path,
-                phrase,
-                &self.tokenizer_manager,
-                json_options,
-            ),
             FieldType::Facet(_) => match Facet::from_text(phrase) {
                 Ok(facet) => {
                     let facet_term = Term::from_facet(field, &facet);
@@ -669,6 +872,33 @@ impl QueryParser {
         }
     }
 
+    /// Turns a text term into a disjunction of the term and of its alternatives,
+    /// as returned by the query expander.
+    fn expand_literal(&self, field: Field, literal: LogicalLiteral) -> LogicalAst {
+        let alternatives = match (&self.query_expander, &literal) {
+            (Some(query_expander), LogicalLiteral::Term(term)) => term
+                .value()
+                .as_str()
+                .map(|text| {
+                    let mut alternatives = query_expander.expand(field, text);
+                    alternatives.retain(|(alternative, _)| alternative != text);
+                    alternatives
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        if alternatives.is_empty() {
+            return LogicalAst::Leaf(Box::new(literal));
+        }
+        let mut clause = vec![(Occur::Should, LogicalAst::Leaf(Box::new(literal)))];
+        for (alternative, weight) in alternatives {
+            let term = Term::from_field_text(field, &alternative);
+            let ast = LogicalAst::Leaf(Box::new(LogicalLiteral::Term(term))).boost(weight);
+            clause.push((Occur::Should, ast));
+        }
+        LogicalAst::Clause(clause)
+    }
+
     fn field_boost(&self, field: Field) -> Score {
         self.boost.get(&field).cloned().unwrap_or(1.0)
     }
@@ -739,6 +969,17 @@ impl QueryParser {
     ) -> (Option<LogicalAst>, Vec<QueryParserError>) {
         match leaf {
             UserInputLeaf::Literal(literal) => {
+                // A prefix on an unquoted word, such as `barack*`, is a term prefix query.
+                // On a quoted phrase, it is a phrase prefix query.
+                let term_prefix = literal.prefix && literal.delimiter == Delimiter::None;
+                if term_prefix && !self.prefix_queries_enabled {
+                    return (
+                        None,
+                        vec![QueryParserError::UnsupportedQuery(
+                            "Prefix queries are disabled.".to_string(),
+                        )],
+                    );
+                }
                 let term_phrases: Vec<(Field, &str, &str)> =
                     try_tuple!(self.compute_path_triplets_for_literal(&literal));
                 let mut asts: Vec<LogicalAst> = Vec::new();
@@ -750,6 +991,7 @@ impl QueryParser {
                         phrase,
                         literal.slop,
                         literal.prefix,
+                        term_prefix,
                     ) {
                         Ok(asts) => asts,
                         Err(e) => {
@@ -760,7 +1002,7 @@ impl QueryParser {
                     for ast in unboosted_asts {
                         // Apply some field specific boost defined at the query parser level.
                         let boost = self.field_boost(field);
-                        asts.push(LogicalAst::Leaf(Box::new(ast)).boost(boost));
+                        asts.push(self.expand_literal(field, ast).boost(boost));
                     }
                 }
                 let result_ast: LogicalAst = if asts.len() == 1 {
@@ -834,21 +1076,102 @@ impl QueryParser {
                     .ok_or_else(|| QueryParserError::FieldDoesNotExist(full_path.clone())));
                 let (elements, errors) = elements
                     .into_iter()
-                    .map(|element| self.compute_boundary_term(field, json_path, &element))
+                    .map(|element| {
+                        self.compute_boundary_term(field, json_path, &element)
+                            .map(truncate_date_term)
+                    })
                     .partition_result();
                 let logical_ast = LogicalAst::Leaf(Box::new(LogicalLiteral::Set { elements }));
                 (Some(logical_ast), errors)
             }
-            UserInputLeaf::Exists { .. } => (
-                None,
-                vec![QueryParserError::UnsupportedQuery(
-                    "Range query need to target a specific field.".to_string(),
-                )],
-            ),
+            UserInputLeaf::Exists { field: full_path } => {
+                let (field, _json_path) = try_tuple!(self
+                    .split_full_path(&full_path)
+                    .ok_or_else(|| QueryParserError::FieldDoesNotExist(full_path.clone())));
+                if !self.schema.get_field_entry(field).is_fast() {
+                    return (
+                        None,
+                        vec![QueryParserError::UnsupportedQuery(format!(
+                            "Exists queries are only supported on fast fields, '{full_path}' is \
+                             not a fast field."
+                        ))],
+                    );
+                }
+                let logical_ast = LogicalAst::Leaf(Box::new(LogicalLiteral::Exists {
+                    field_name: full_path,
+                }));
+                (Some(logical_ast), Vec::new())
+            }
+            UserInputLeaf::Regex {
+                field: full_path,
+                pattern,
+            } => {
+                if !self.regex_queries_enabled {
+                    return (
+                        None,
+                        vec![QueryParserError::UnsupportedQuery(
+                            "Regex queries are disabled.".to_string(),
+                        )],
+                    );
+                }
+                let (field, json_path) = try_tuple!(self
+                    .split_full_path(&full_path)
+                    .ok_or_else(|| QueryParserError::FieldDoesNotExist(full_path.clone())));
+                let field_entry = self.schema.get_field_entry(field);
+                if !field_entry.is_indexed() {
+                    return (None, vec![QueryParserError::FieldNotIndexed(full_path)]);
+                }
+                if field_entry.field_type().value_type() != Type::Str || !json_path.is_empty() {
+                    return (
+                        None,
+                        vec![QueryParserError::UnsupportedQuery(format!(
+                            "Regex queries are only supported on text fields, '{full_path}' is \
+                             not a text field."
+                        ))],
+                    );
+                }
+                let regex = try_tuple!(Regex::new(&pattern).map_err(|err| {
+                    QueryParserError::InvalidRegex {
+                        pattern: pattern.clone(),
+                        message: err.to_string(),
+                    }
+                }));
+                let logical_ast = LogicalAst::Leaf(Box::new(LogicalLiteral::Regex {
+                    field,
+                    pattern,
+                    regex: Arc::new(regex),
+                }))
+                .boost(self.field_boost(field));
+                (Some(logical_ast), Vec::new())
+            }
+            UserInputLeaf::Extension { field, name, args } => {
+                let Some(syntax_handler) = self.syntax_handlers.get(&name) else {
+                    return (
+                        None,
+                        vec![QueryParserError::UnsupportedQuery(format!(
+                            "No syntax handler is registered for '{name}'."
+                        ))],
+                    );
+                };
+                let query = try_tuple!(syntax_handler.build_query(field.as_deref(), &args));
+                let logical_ast =
+                    LogicalAst::Leaf(Box::new(LogicalLiteral::Extension(Arc::from(query))));
+                (Some(logical_ast), Vec::new())
+            }
         }
     }
 }
 
+/// The elements of a set are matched against the indexed terms, like single terms, so
+/// their dates are truncated to the precision of the index instead of being kept at full
+/// precision like range bounds.
+fn truncate_date_term(term: Term) -> Term {
+    match term.value().as_date() {
+        Some(date) => Term::from_field_date(term.field(), date),
+        None => term,
+    }
+}
+
 fn convert_literal_to_query(
     fuzzy: &FxHashMap<Field, Fuzzy>,
     logical_literal: LogicalLiteral,
@@ -894,6 +1217,15 @@ fn convert_literal_to_query(
         )),
         LogicalLiteral::Set { elements, .. } => Box::new(TermSetQuery::new(elements)),
         LogicalLiteral::All => Box::new(AllQuery),
+        // A prefix query is a fuzzy prefix query with a distance of 0.
+        LogicalLiteral::Prefix(term) => Box::new(FuzzyTermQuery::new_prefix(term, 0, true)),
+        LogicalLiteral::Regex { field, regex, .. } => {
+            Box::new(RegexQuery::from_regex(regex, field))
+        }
+        LogicalLiteral::Extension(query) => query.box_clone(),
+        LogicalLiteral::Exists { field_name } => {
+            Box::new(ExistsQuery::new_exists_query(field_name))
+        }
     }
 }
 
@@ -905,36 +1237,73 @@ fn generate_literals_for_str(
     prefix: bool,
     indexing_options: &TextFieldIndexing,
     text_analyzer: &mut TextAnalyzer,
-) -> Result<Option<LogicalLiteral>, QueryParserError> {
+) -> Result<Vec<LogicalLiteral>, QueryParserError> {
     let mut terms: Vec<(usize, Term)> = Vec::new();
+    // Whether all of the tokens are alternatives for the same text, e.g. a word and its synonyms.
+    let mut same_token_span = true;
+    let mut first_token_span = None;
     let mut token_stream = text_analyzer.token_stream(phrase);
     token_stream.process(&mut |token| {
         let term = Term::from_field_text(field, &token.text);
         terms.push((token.position, term));
+        let token_span = (token.position, token.offset_from, token.offset_to);
+        same_token_span &= *first_token_span.get_or_insert(token_span) == token_span;
     });
-    if terms.len() <= 1 {
-        if prefix {
-            return Err(QueryParserError::PhrasePrefixRequiresAtLeastTwoTerms {
-                phrase: phrase.to_owned(),
-                tokenizer: indexing_options.tokenizer().to_owned(),
-            });
-        }
-        let term_literal_opt = terms
+    if terms.len() <= 1 && prefix {
+        return Err(QueryParserError::PhrasePrefixRequiresAtLeastTwoTerms {
+            phrase: phrase.to_owned(),
+            tokenizer: indexing_options.tokenizer().to_owned(),
+        });
+    }
+    if terms.len() <= 1 || (same_token_span && !prefix) {
+        // A single term, or several alternative terms, which are searched as a disjunction.
+        return Ok(terms
             .into_iter()
-            .next()
-            .map(|(_, term)| LogicalLiteral::Term(term));
-        return Ok(term_literal_opt);
+            .map(|(_, term)| LogicalLiteral::Term(term))
+            .collect());
     }
     if !indexing_options.index_option().has_positions() {
         return Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
             field_name.to_string(),
         ));
     }
-    Ok(Some(LogicalLiteral::Phrase {
+    Ok(vec![LogicalLiteral::Phrase {
         terms,
         slop,
         prefix,
-    }))
+    }])
+}
+
+fn generate_prefix_literals_for_str(
+    field_name: &str,
+    field: Field,
+    prefix: &str,
+    indexing_options: &TextFieldIndexing,
+    text_analyzer: &mut TextAnalyzer,
+) -> Result<Vec<LogicalLiteral>, QueryParserError> {
+    let mut terms: Vec<(usize, Term)> = Vec::new();
+    let mut token_stream = text_analyzer.token_stream(prefix);
+    token_stream.process(&mut |token| {
+        let term = Term::from_field_text(field, &token.text);
+        terms.push((token.position, term));
+    });
+    if terms.len() <= 1 {
+        return Ok(terms
+            .into_iter()
+            .map(|(_, term)| LogicalLiteral::Prefix(term))
+            .collect());
+    }
+    // The prefix is made of several tokens, e.g. `wi-f*`: the last one is a prefix.
+    if !indexing_options.index_option().has_positions() {
+        return Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
+            field_name.to_string(),
+        ));
+    }
+    Ok(vec![LogicalLiteral::Phrase {
+        terms,
+        slop: 0,
+        prefix: true,
+    }])
 }
 
 fn generate_literals_for_json_object(
@@ -942,19 +1311,10 @@ fn generate_literals_for_json_object(
     field: Field,
     json_path: &str,
     phrase: &str,
-    tokenizer_manager: &TokenizerManager,
+    text_analyzer: &mut TextAnalyzer,
+    text_options: &TextFieldIndexing,
     json_options: &JsonObjectOptions,
 ) -> Result<Vec<LogicalLiteral>, QueryParserError> {
-    let text_options = json_options.get_text_indexing_options().ok_or_else(|| {
-        // This should have been seen earlier really.
-        QueryParserError::FieldNotIndexed(field_name.to_string())
-    })?;
-    let mut text_analyzer = tokenizer_manager
-        .get(text_options.tokenizer())
-        .ok_or_else(|| QueryParserError::UnknownTokenizer {
-            field: field_name.to_string(),
-            tokenizer: text_options.tokenizer().to_string(),
-        })?;
     let index_record_option = text_options.index_option();
     let mut logical_literals = Vec::new();
 
@@ -1025,16 +1385,17 @@ mod test {
     use matches::assert_matches;
 
     use super::super::logical_ast::*;
-    use super::{QueryParser, QueryParserError};
+    use super::{QueryParser, QueryParserError, StopWordsFallback};
+    use crate::collector::Count;
     use crate::query::Query;
     use crate::schema::{
         FacetOptions, Field, IndexRecordOption, Schema, Term, TextFieldIndexing, TextOptions, FAST,
         INDEXED, STORED, STRING, TEXT,
     };
     use crate::tokenizer::{
-        LowerCaser, SimpleTokenizer, StopWordFilter, TextAnalyzer, TokenizerManager,
+        LowerCaser, SimpleTokenizer, StopWordFilter, SynonymFilter, TextAnalyzer, TokenizerManager,
     };
-    use crate::Index;
+    use crate::{doc, Index, IndexWriter, TantivyDocument};
 
     fn make_schema() -> Schema {
         let mut schema_builder = Schema::builder();
@@ -1118,6 +1479,137 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_stop_words_fallback() {
+        let mut query_parser = make_query_parser_with_default_fields(&["with_stop_words"]);
+        let parse = |query_parser: &QueryParser, query: &str| {
+            format!("{:?}", query_parser.parse_query(query).unwrap())
+        };
+        assert_eq!(parse(&query_parser, "The the"), "EmptyQuery");
+
+        query_parser.set_stop_words_fallback(StopWordsFallback::MatchAll);
+        assert_eq!(parse(&query_parser, "The the"), "AllQuery");
+        // Queries with other words and queries without words are not affected.
+        assert_eq!(
+            parse(&query_parser, "the cat"),
+            "BooleanQuery { subqueries: [(Should, TermQuery(Term(field=8, type=Str, \"cat\")))], \
+             minimum_number_should_match: 1 }"
+        );
+        assert_eq!(parse(&query_parser, ""), "EmptyQuery");
+
+        query_parser.set_stop_words_fallback(StopWordsFallback::RawTerms);
+        assert_eq!(
+            parse(&query_parser, "The"),
+            "TermQuery(Term(field=8, type=Str, \"the\"))"
+        );
+        assert_eq!(
+            parse(&query_parser, "the cat"),
+            "BooleanQuery { subqueries: [(Should, TermQuery(Term(field=8, type=Str, \"cat\")))], \
+             minimum_number_should_match: 1 }"
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_syntax_handler() {
+        let mut query_parser = make_query_parser();
+        let title = Field::from_field_id(0);
+        query_parser.register_syntax_handler(
+            "either",
+            move |field_name: Option<&str>,
+                  args: &str|
+                  -> Result<Box<dyn Query>, QueryParserError> {
+                if field_name.is_some() {
+                    return Err(QueryParserError::UnsupportedQuery(
+                        "either() does not target a field".to_string(),
+                    ));
+                }
+                let terms = args
+                    .split(',')
+                    .map(|word| Term::from_field_text(title, word.trim()))
+                    .collect();
+                Ok(Box::new(TermSetQuery::new(terms)))
+            },
+        );
+        let logical_ast = query_parser
+            .parse_query_to_logical_ast("either(a, b) +title:c")
+            .unwrap();
+        assert_eq!(
+            format!("{logical_ast:?}"),
+            r#"(TermSetQuery { terms_map: {Field(0): [Term(field=0, type=Str, "a"), Term(field=0, type=Str, "b")]} } +Term(field=0, type=Str, "c"))"#
+        );
+        assert_matches!(
+            query_parser.parse_query("title:either(a, b)"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+        // Without a handler, `name(args)` is parsed as usual.
+        let query_parser_without_handler = make_query_parser();
+        for query in ["neither(a, b)", "iphone(2020)", "a AND(b OR c)", "NOT(x) y"] {
+            assert_eq!(
+                format!("{:?}", query_parser.parse_query_to_logical_ast(query)),
+                format!(
+                    "{:?}",
+                    query_parser_without_handler.parse_query_to_logical_ast(query)
+                ),
+            );
+            assert_eq!(
+                format!(
+                    "{:?}",
+                    query_parser.parse_query_to_logical_ast_lenient(query)
+                ),
+                format!(
+                    "{:?}",
+                    query_parser_without_handler.parse_query_to_logical_ast_lenient(query)
+                ),
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_parse_query_expander() {
+        let mut query_parser = make_query_parser();
+        query_parser.set_query_expander(|field: Field, text: &str| {
+            if field == Field::from_field_id(0) && text == "car" {
+                vec![("automobile".to_string(), 0.5), ("car".to_string(), 2.0)]
+            } else {
+                Vec::new()
+            }
+        });
+        let query = query_parser.parse_query("title:Car").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            "BooleanQuery { subqueries: [(Should, TermQuery(Term(field=0, type=Str, \"car\"))), \
+             (Should, Boost(query=TermQuery(Term(field=0, type=Str, \"automobile\")), \
+             boost=0.5))], minimum_number_should_match: 1 }"
+        );
+        // Only the terms of the fields accepted by the expander are expanded.
+        let query = query_parser.parse_query("text:car").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            "TermQuery(Term(field=1, type=Str, \"car\"))"
+        );
+        // The terms of a phrase are not expanded.
+        let query = query_parser.parse_query("title:\"red car\"").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            "PhraseQuery { field: Field(0), phrase_terms: [(0, Term(field=0, type=Str, \"red\")), \
+             (1, Term(field=0, type=Str, \"car\"))], slop: 0 }"
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_field_alias() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_field_alias("content", "body");
+        let schema = schema_builder.build();
+        let query_parser = QueryParser::new(schema, Vec::new(), TokenizerManager::default());
+        let query = query_parser.parse_query("content:hello").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            r#"TermQuery(Term(field=0, type=Str, "hello"))"#
+        );
+    }
+
     #[test]
     pub fn test_parse_query_with_boost() {
         let mut query_parser = make_query_parser();
@@ -1639,6 +2131,11 @@ mod test {
             r#"Term(field=9, type=Date, 1985-04-12T23:20:50Z)"#,
             true,
         );
+        test_parse_query_to_logical_ast_helper(
+            r#"date: IN ["1985-04-12T23:20:50.52Z" "2010-11-21T07:55:06Z"]"#,
+            r#"IN [Term(field=9, type=Date, 1985-04-12T23:20:50Z), Term(field=9, type=Date, 2010-11-21T07:55:06Z)]"#,
+            true,
+        );
     }
 
     #[test]
@@ -1904,4 +2401,247 @@ mod test {
             );
         }
     }
+
+    #[test]
+    pub fn test_parse_query_exists() {
+        test_parse_query_to_logical_ast_helper("u64_ff:*", r#""u64_ff":*"#, false);
+        let query_parser = make_query_parser();
+        let query = query_parser.parse_query("u64_ff:*").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            r#"ExistsQuery { field_name: "u64_ff" }"#
+        );
+        assert_matches!(
+            query_parser.parse_query("title:*"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+        assert_eq!(
+            query_parser.parse_query("missing:*").unwrap_err(),
+            QueryParserError::FieldDoesNotExist("missing".to_string())
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_term_prefix() {
+        test_parse_query_to_logical_ast_helper(
+            "title:Bar*",
+            r#"Term(field=0, type=Str, "bar")*"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "bar*",
+            r#"(Term(field=0, type=Str, "bar")* Term(field=1, type=Str, "bar")*)"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:wi-f*",
+            r#""[(0, Term(field=0, type=Str, "wi")), (1, Term(field=0, type=Str, "f"))]"*"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            r#"title:bar\*"#,
+            r#"Term(field=0, type=Str, "bar")"#,
+            false,
+        );
+        let mut query_parser = make_query_parser();
+        let query = query_parser.parse_query("title:bar*").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            "FuzzyTermQuery { term: Term(field=0, type=Str, \"bar\"), distance: 0, \
+             transposition_cost_one: true, prefix: true }"
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:1*"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+        query_parser.set_prefix_queries_enabled(false);
+        assert_matches!(
+            query_parser.parse_query("title:bar*"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+        // Phrase prefix queries are not affected.
+        assert!(query_parser.parse_query("title:\"big ba\"*").is_ok());
+    }
+
+    #[test]
+    pub fn test_parse_query_regex() {
+        test_parse_query_to_logical_ast_helper(
+            "title:/ba[rs]ack/",
+            "Regex(field=0, /ba[rs]ack/)",
+            false,
+        );
+        let mut query_parser = make_query_parser();
+        assert!(query_parser.parse_query("title:/ba[rs]ack/ obama").is_ok());
+        assert_eq!(
+            query_parser.parse_query("title:/ba(/").unwrap_err(),
+            QueryParserError::InvalidRegex {
+                pattern: "ba(".to_string(),
+                message: tantivy_fst::Regex::new("ba(").unwrap_err().to_string(),
+            }
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:/1+/"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("notindexed_text:/a+/"),
+            Err(QueryParserError::FieldNotIndexed(_))
+        );
+        query_parser.set_regex_queries_enabled(false);
+        assert_matches!(
+            query_parser.parse_query("title:/ba[rs]ack/"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+    }
+
+    #[test]
+    pub fn test_prefix_regex_and_exists_queries_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let rating = schema_builder.add_u64_field("rating", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "Barack Obama", rating => 3u64))?;
+        index_writer.add_document(doc!(title => "Barrack of the fort"))?;
+        index_writer.add_document(doc!(title => "Bars and restaurants", rating => 5u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("bar*")?, 3);
+        assert_eq!(count("title:barr*")?, 1);
+        assert_eq!(count("title:/bar+ack/")?, 2);
+        assert_eq!(count("rating:*")?, 2);
+        assert_eq!(count("rating:* AND bar*")?, 2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_bool_field_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let active = schema_builder.add_bool_field("active", INDEXED | FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "Barack Obama", active => true))?;
+        index_writer.add_document(doc!(title => "Barrack of the fort", active => false))?;
+        index_writer.add_document(doc!(title => "Bars and restaurants", active => true))?;
+        index_writer.add_document(doc!(title => "Bartender"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("active:true")?, 2);
+        assert_eq!(count("active:false")?, 1);
+        assert_eq!(count("active:*")?, 3);
+        assert_eq!(count("restaurants AND active:true")?, 1);
+        let active_column = searcher.segment_reader(0u32).fast_fields().bool("active")?;
+        let values: Vec<Option<bool>> = (0..4).map(|doc| active_column.first(doc)).collect();
+        assert_eq!(values, vec![Some(true), Some(false), Some(true), None]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_json_field_nested_paths_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_json_field("attrs", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let schema = index.schema();
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        // Each document has its own structure.
+        for doc_json in [
+            r#"{"attrs": {"color": "red", "size": 42}}"#,
+            r#"{"attrs": {"color": "blue", "dims": {"width": 3}}}"#,
+            r#"{"attrs": {"variants": [{"color": "red"}, {"color": "green"}]}}"#,
+        ] {
+            index_writer.add_document(TantivyDocument::parse_json(&schema, doc_json)?)?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, Vec::new());
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("attrs.color:red")?, 1);
+        assert_eq!(count("attrs.size:42")?, 1);
+        assert_eq!(count("attrs.dims.width:3")?, 1);
+        assert_eq!(count("attrs.variants.color:red")?, 1);
+        assert_eq!(count("attrs.variants.color:green AND attrs.color:red")?, 0);
+        assert_eq!(count("attrs.color:red OR attrs.variants.color:red")?, 2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_query_parser_field_tokenizer() {
+        let mut query_parser = make_query_parser();
+        let title = query_parser.schema.get_field("title").unwrap();
+        let text = query_parser.schema.get_field("text").unwrap();
+        query_parser.set_field_tokenizer(title, "raw");
+        let title_ast = query_parser
+            .parse_query_to_logical_ast("title:Hello")
+            .unwrap();
+        assert_eq!(
+            format!("{title_ast:?}"),
+            r#"Term(field=0, type=Str, "Hello")"#
+        );
+        let text_ast = query_parser
+            .parse_query_to_logical_ast("text:Hello")
+            .unwrap();
+        assert_eq!(
+            format!("{text_ast:?}"),
+            r#"Term(field=1, type=Str, "hello")"#
+        );
+        query_parser.set_field_tokenizer(text, "nonexistingtokenizer");
+        assert_eq!(
+            query_parser.parse_query("text:hello").unwrap_err(),
+            QueryParserError::UnknownTokenizer {
+                field: "text".to_string(),
+                tokenizer: "nonexistingtokenizer".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_search_tokenizer_expands_synonyms() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_search_tokenizer("synonyms")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            "synonyms",
+            TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(LowerCaser)
+                .filter(SynonymFilter::default().add_equivalents(["car", "automobile"]))
+                .build(),
+        );
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "A red car"))?;
+        index_writer.add_document(doc!(text => "An automobile museum"))?;
+        index_writer.add_document(doc!(text => "A red bicycle"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![text]);
+        let count = |query_parser: &QueryParser, query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        // The synonyms are only expanded at query time.
+        let automobile = Term::from_field_text(text, "automobile");
+        assert_eq!(searcher.doc_freq(&automobile)?, 1);
+        assert_eq!(count(&query_parser, "car")?, 2);
+        assert_eq!(count(&query_parser, "Automobile")?, 2);
+        assert_eq!(count(&query_parser, "car AND red")?, 1);
+        assert_eq!(count(&query_parser, "bicycle")?, 1);
+        query_parser.set_field_tokenizer(text, "default");
+        assert_eq!(count(&query_parser, "car")?, 1);
+        Ok(())
+    }
 }
@@ -0,0 +1,65 @@
+use crate::query::{Query, QueryParserError};
+
+/// Builds the queries of a custom syntax of the query language.
+///
+/// A [`QueryParser`](crate::query::QueryParser) interprets `name(args)` with the handler
+/// registered for `name` with
+/// [`QueryParser::register_syntax_handler`](crate::query::QueryParser::register_syntax_handler).
+/// The name must be immediately followed by the arguments, which are given verbatim to the
+/// handler: parentheses inside of the arguments must be balanced, or escaped with a `\`.
+///
+/// The syntax can be prefixed by a field, as in `location:circle(48.8, 2.3, 10km)`. The field
+/// name is then given to the handler as written in the query, and is not checked against the
+/// schema.
+///
+/// `QuerySyntaxHandler` is implemented by closures.
+///
+/// ```rust
+/// use tantivy::query::{PhraseQuery, Query, QueryParser, QueryParserError};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{Index, Term};
+///
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+///
+/// let mut query_parser = QueryParser::for_index(&index, vec![title]);
+/// // `near(a, b, slop)` matches `a` followed by `b`, with at most `slop` words between them.
+/// query_parser.register_syntax_handler(
+///     "near",
+///     move |_field_name: Option<&str>, args: &str| -> Result<Box<dyn Query>, QueryParserError> {
+///         let words: Vec<&str> = args.split(',').map(str::trim).collect();
+///         let [left, right, slop] = words.as_slice() else {
+///             return Err(QueryParserError::SyntaxError(format!("near({args})")));
+///         };
+///         let mut phrase_query = PhraseQuery::new(vec![
+///             Term::from_field_text(title, left),
+///             Term::from_field_text(title, right),
+///         ]);
+///         phrase_query.set_slop(slop.parse()?);
+///         Ok(Box::new(phrase_query))
+///     },
+/// );
+/// assert!(query_parser.parse_query("near(old, sea, 2) fisherman").is_ok());
+/// assert!(query_parser.parse_query("far(old, sea)").is_err());
+/// ```
+pub trait QuerySyntaxHandler: Send + Sync + 'static {
+    /// Builds the query of `name(args)`, given the field prefixing the syntax if any.
+    fn build_query(
+        &self,
+        field_name: Option<&str>,
+        args: &str,
+    ) -> Result<Box<dyn Query>, QueryParserError>;
+}
+
+impl<F> QuerySyntaxHandler for F
+where F: Fn(Option<&str>, &str) -> Result<Box<dyn Query>, QueryParserError> + Send + Sync + 'static
+{
+    fn build_query(
+        &self,
+        field_name: Option<&str>,
+        args: &str,
+    ) -> Result<Box<dyn Query>, QueryParserError> {
+        (self)(field_name, args)
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::SegmentOrdinal;
+
+/// Description of how a query would be executed, as returned by
+/// [`Searcher::explain_plan`](crate::Searcher::explain_plan).
+///
+/// The plan is built without iterating over the matching documents. It makes it
+/// possible to review the cost of user-supplied queries before running them,
+/// for instance to reject a regex or a range expanding to too many terms.
+#[derive(Clone, Debug, Serialize)]
+pub struct QueryPlan {
+    query: String,
+    segments: Vec<SegmentQueryPlan>,
+}
+
+/// Execution plan of a query on a single segment.
+#[derive(Clone, Debug, Serialize)]
+pub struct SegmentQueryPlan {
+    segment_ord: SegmentOrdinal,
+    num_docs: u32,
+    estimated_cost: u64,
+    num_expanded_terms: u64,
+}
+
+/// Estimation of the cost of running a [`Weight`](crate::query::Weight) on a segment,
+/// as returned by [`Weight::estimate_cost`](crate::query::Weight::estimate_cost).
+///
+/// It is computed from the term dictionary of the segment, without opening any postings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// Estimated number of documents the scorer of the weight goes through.
+    pub num_docs: u64,
+    /// Number of terms of the segment whose postings are read by the scorer of the weight.
+    pub num_expanded_terms: u64,
+}
+
+impl QueryPlan {
+    pub(crate) fn new(query: String, segments: Vec<SegmentQueryPlan>) -> QueryPlan {
+        QueryPlan { query, segments }
+    }
+
+    /// Returns the query tree, after it was rewritten by the query parser.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the plan of each of the segments of the searcher.
+    pub fn segments(&self) -> &[SegmentQueryPlan] {
+        &self.segments
+    }
+
+    /// Returns the estimated number of documents the query would go through, summed over
+    /// all of the segments.
+    pub fn estimated_cost(&self) -> u64 {
+        self.segments
+            .iter()
+            .map(|segment| segment.estimated_cost)
+            .sum()
+    }
+
+    /// Returns the number of terms the query expands to, summed over all of the segments.
+    pub fn num_expanded_terms(&self) -> u64 {
+        self.segments
+            .iter()
+            .map(|segment| segment.num_expanded_terms)
+            .sum()
+    }
+
+    /// Returns a pretty JSON representation of the plan.
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+impl SegmentQueryPlan {
+    pub(crate) fn new(
+        segment_ord: SegmentOrdinal,
+        num_docs: u32,
+        cost_estimate: CostEstimate,
+    ) -> SegmentQueryPlan {
+        SegmentQueryPlan {
+            segment_ord,
+            num_docs,
+            estimated_cost: cost_estimate.num_docs,
+            num_expanded_terms: cost_estimate.num_expanded_terms,
+        }
+    }
+
+    /// Returns the ordinal of the segment.
+    pub fn segment_ord(&self) -> SegmentOrdinal {
+        self.segment_ord
+    }
+
+    /// Returns the number of alive documents of the segment.
+    pub fn num_docs(&self) -> u32 {
+        self.num_docs
+    }
+
+    /// Returns the estimated number of documents the query goes through on the segment.
+    pub fn estimated_cost(&self) -> u64 {
+        self.estimated_cost
+    }
+
+    /// Returns the number of terms of the segment the query expands to.
+    pub fn num_expanded_terms(&self) -> u64 {
+        self.num_expanded_terms
+    }
+}
+
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.query)?;
+        for segment in &self.segments {
+            writeln!(
+                f,
+                "segment {} ({} docs): estimated_cost={} expanded_terms={}",
+                segment.segment_ord,
+                segment.num_docs,
+                segment.estimated_cost,
+                segment.num_expanded_terms
+            )?;
+        }
+        write!(
+            f,
+            "estimated_cost={} expanded_terms={}",
+            self.estimated_cost(),
+            self.num_expanded_terms()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::{BooleanQuery, Occur, Query, RegexQuery, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, STRING};
+    use crate::{doc, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_explain_plan() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "apple"))?;
+        index_writer.add_document(doc!(title => "apricot"))?;
+        index_writer.add_document(doc!(title => "banana"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(title => "avocado"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let query = BooleanQuery::new(vec![
+            (
+                Occur::Should,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(title, "banana"),
+                    IndexRecordOption::Basic,
+                )) as Box<dyn Query>,
+            ),
+            (
+                Occur::Should,
+                Box::new(RegexQuery::from_pattern("a.*", title)?),
+            ),
+        ]);
+        let plan = searcher.explain_plan(&query)?;
+        assert!(plan.query().contains("BooleanQuery"));
+        assert_eq!(plan.segments().len(), 2);
+        let mut segments = plan.segments().to_vec();
+        segments.sort_by_key(|segment| segment.num_docs());
+        assert_eq!(segments[0].num_docs(), 1);
+        // "banana" is not in the segment, "avocado" is.
+        assert_eq!(segments[0].num_expanded_terms(), 1);
+        assert_eq!(segments[1].num_docs(), 3);
+        // "banana", "apple" and "apricot".
+        assert_eq!(segments[1].num_expanded_terms(), 3);
+        // The costs of the clauses of a union add up.
+        assert_eq!(segments[0].estimated_cost(), 1);
+        assert_eq!(segments[1].estimated_cost(), 3);
+        assert_eq!(plan.num_expanded_terms(), 4);
+        assert_eq!(plan.estimated_cost(), 4);
+
+        let term_query = TermQuery::new(
+            Term::from_field_text(title, "banana"),
+            IndexRecordOption::Basic,
+        );
+        let plan = searcher.explain_plan(&term_query)?;
+        assert_eq!(plan.estimated_cost(), 1);
+        assert!(plan.to_string().contains("expanded_terms=1"));
+
+        // The cost of an intersection is bounded by its cheapest required clause.
+        let intersection = BooleanQuery::intersection(vec![
+            Box::new(RegexQuery::from_pattern("a.*", title)?),
+            Box::new(TermQuery::new(
+                Term::from_field_text(title, "apple"),
+                IndexRecordOption::Basic,
+            )),
+        ]);
+        let plan = searcher.explain_plan(&intersection)?;
+        assert_eq!(plan.estimated_cost(), 1);
+        assert_eq!(plan.num_expanded_terms(), 4);
+        Ok(())
+    }
+}
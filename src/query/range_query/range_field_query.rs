@@ -0,0 +1,256 @@
+use std::ops::{Bound, RangeInclusive};
+
+use columnar::{HasAssociatedColumnType, MonotonicallyMappableToU64};
+
+use super::FastFieldRangeWeight;
+use crate::query::{BooleanQuery, ConstScoreQuery, EnableScoring, Query, Weight};
+use crate::TantivyError;
+
+/// Relation between the interval of a document and the interval of a [`RangeFieldQuery`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeRelation {
+    /// The interval of the document and the query interval share at least one value.
+    Intersects,
+    /// The interval of the document contains the query interval.
+    Contains,
+    /// The interval of the document is within the query interval.
+    Within,
+}
+
+/// Query matching documents holding an interval `[start, end]` in relation with a given
+/// interval.
+///
+/// The interval of a document is stored in two single-valued fast fields, one holding
+/// the start and one holding the end of the interval. Both bounds are inclusive.
+/// Intervals can be defined over `u64`, `i64` or `DateTime` values.
+///
+/// Since only the bounds of the interval are indexed, this is much cheaper than indexing
+/// one term per unit of the interval, for instance for calendar availability
+/// or version ranges.
+///
+/// All of the matched documents get the score 1.0.
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::{RangeFieldQuery, RangeRelation};
+/// use tantivy::schema::{Schema, FAST};
+/// use tantivy::{doc, Index, IndexWriter};
+///
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let since = schema_builder.add_u64_field("since_version", FAST);
+/// let until = schema_builder.add_u64_field("until_version", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer: IndexWriter = index.writer_with_num_threads(1, 15_000_000)?;
+/// index_writer.add_document(doc!(since => 1u64, until => 3u64))?;
+/// index_writer.add_document(doc!(since => 2u64, until => 8u64))?;
+/// index_writer.add_document(doc!(since => 6u64, until => 9u64))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = RangeFieldQuery::new(
+///     "since_version".to_string(),
+///     "until_version".to_string(),
+///     RangeRelation::Contains,
+///     4u64..=5u64,
+/// );
+/// assert_eq!(searcher.search(&query, &Count)?, 1);
+/// # Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct RangeFieldQuery {
+    start_field: String,
+    end_field: String,
+    start_weight: FastFieldRangeWeight,
+    end_weight: FastFieldRangeWeight,
+}
+
+impl RangeFieldQuery {
+    /// Creates a new `RangeFieldQuery`.
+    ///
+    /// `start_field` and `end_field` are the names of the fast fields holding
+    /// respectively the start and the end of the interval of each document.
+    pub fn new<T: HasAssociatedColumnType + MonotonicallyMappableToU64>(
+        start_field: String,
+        end_field: String,
+        relation: RangeRelation,
+        range: RangeInclusive<T>,
+    ) -> RangeFieldQuery {
+        let (lower, upper) = range.into_inner();
+        let (start_bounds, end_bounds) = match relation {
+            // start <= upper && end >= lower
+            RangeRelation::Intersects => (
+                (Bound::Unbounded, Bound::Included(upper)),
+                (Bound::Included(lower), Bound::Unbounded),
+            ),
+            // start <= lower && end >= upper
+            RangeRelation::Contains => (
+                (Bound::Unbounded, Bound::Included(lower)),
+                (Bound::Included(upper), Bound::Unbounded),
+            ),
+            // start >= lower && end <= upper
+            RangeRelation::Within => (
+                (Bound::Included(lower), Bound::Unbounded),
+                (Bound::Unbounded, Bound::Included(upper)),
+            ),
+        };
+        RangeFieldQuery {
+            start_weight: FastFieldRangeWeight::new(
+                start_field.clone(),
+                start_bounds.0,
+                start_bounds.1,
+            ),
+            end_weight: FastFieldRangeWeight::new(end_field.clone(), end_bounds.0, end_bounds.1),
+            start_field,
+            end_field,
+        }
+    }
+}
+
+impl Query for RangeFieldQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
+        let schema = enable_scoring.schema();
+        for field_name in [&self.start_field, &self.end_field] {
+            let field = schema.get_field(field_name)?;
+            if !schema.get_field_entry(field).is_fast() {
+                return Err(TantivyError::SchemaError(format!(
+                    "Field {field_name} is not a fast field."
+                )));
+            }
+        }
+        let intersection = BooleanQuery::intersection(vec![
+            Box::new(self.start_weight.clone()),
+            Box::new(self.end_weight.clone()),
+        ]);
+        ConstScoreQuery::new(Box::new(intersection), 1.0).weight(enable_scoring)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::DateTime;
+
+    use super::{RangeFieldQuery, RangeRelation};
+    use crate::collector::{Count, DocSetCollector};
+    use crate::schema::{Schema, FAST, INDEXED};
+    use crate::{DocAddress, Index, IndexWriter};
+
+    fn test_index() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let start = schema_builder.add_i64_field("start", FAST);
+        let end = schema_builder.add_i64_field("end", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for (start_val, end_val) in [(-10i64, -5i64), (-2, 3), (0, 10), (4, 4), (8, 20)] {
+            index_writer.add_document(doc!(start => start_val, end => end_val))?;
+        }
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    fn matching_docs(
+        index: &Index,
+        relation: RangeRelation,
+        lower: i64,
+        upper: i64,
+    ) -> crate::Result<Vec<u32>> {
+        let query = RangeFieldQuery::new(
+            "start".to_string(),
+            "end".to_string(),
+            relation,
+            lower..=upper,
+        );
+        let searcher = index.reader()?.searcher();
+        let mut docs: Vec<u32> = searcher
+            .search(&query, &DocSetCollector)?
+            .into_iter()
+            .map(|DocAddress { doc_id, .. }| doc_id)
+            .collect();
+        docs.sort();
+        Ok(docs)
+    }
+
+    #[test]
+    fn test_range_field_query_intersects() -> crate::Result<()> {
+        let index = test_index()?;
+        assert_eq!(
+            matching_docs(&index, RangeRelation::Intersects, 3, 5)?,
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            matching_docs(&index, RangeRelation::Intersects, -5, -5)?,
+            vec![0]
+        );
+        assert!(matching_docs(&index, RangeRelation::Intersects, 21, 30)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_field_query_contains() -> crate::Result<()> {
+        let index = test_index()?;
+        assert_eq!(
+            matching_docs(&index, RangeRelation::Contains, 4, 4)?,
+            vec![2, 3]
+        );
+        assert_eq!(
+            matching_docs(&index, RangeRelation::Contains, 1, 9)?,
+            vec![2]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_field_query_within() -> crate::Result<()> {
+        let index = test_index()?;
+        assert_eq!(
+            matching_docs(&index, RangeRelation::Within, -2, 10)?,
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            matching_docs(&index, RangeRelation::Within, -100, 100)?,
+            vec![0, 1, 2, 3, 4]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_field_query_date() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let available_from = schema_builder.add_date_field("available_from", FAST);
+        let available_to = schema_builder.add_date_field("available_to", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let day = |num_days: i64| DateTime::from_timestamp_secs(num_days * 86_400);
+        index_writer.add_document(doc!(available_from => day(1), available_to => day(5)))?;
+        index_writer.add_document(doc!(available_from => day(3), available_to => day(4)))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query = RangeFieldQuery::new(
+            "available_from".to_string(),
+            "available_to".to_string(),
+            RangeRelation::Contains,
+            day(2)..=day(4),
+        );
+        assert_eq!(searcher.search(&query, &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_field_query_not_fast() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_u64_field("start", INDEXED);
+        schema_builder.add_u64_field("end", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader()?.searcher();
+        let query = RangeFieldQuery::new(
+            "start".to_string(),
+            "end".to_string(),
+            RangeRelation::Intersects,
+            0u64..=1u64,
+        );
+        assert!(searcher.search(&query, &Count).is_err());
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use std::io;
 use std::net::Ipv6Addr;
 use std::ops::{Bound, Range};
 
-use columnar::MonotonicallyMappableToU128;
+use columnar::{MonotonicallyMappableToU128, MonotonicallyMappableToU64};
 use common::{BinarySerializable, BitSet};
 
 use super::map_bound;
@@ -12,8 +12,12 @@ use crate::index::SegmentReader;
 use crate::query::explanation::does_not_match;
 use crate::query::range_query::range_query_ip_fastfield::IPFastFieldRangeWeight;
 use crate::query::range_query::{is_type_valid_for_fastfield_range_query, map_bound_res};
-use crate::query::{BitSetDocSet, ConstScorer, EnableScoring, Explanation, Query, Scorer, Weight};
-use crate::schema::{Field, IndexRecordOption, Term, Type};
+use crate::query::{
+    BitSetDocSet, ConstScorer, CostEstimate, EnableScoring, Explanation, Query, Scorer, Weight,
+};
+use crate::schema::{
+    DateTimePrecision, Field, FieldType, IndexRecordOption, Term, Type, DATE_TIME_PRECISION_INDEXED,
+};
 use crate::termdict::{TermDictionary, TermStreamer};
 use crate::{DateTime, DocId, Score};
 
@@ -238,6 +242,10 @@ impl RangeQuery {
     /// The two `Bound` arguments make it possible to create more complex
     /// ranges than semi-inclusive range.
     ///
+    /// The bounds are truncated to the precision of the field: the
+    /// [precision](crate::schema::DateOptions::set_precision) of its fast field
+    /// if it is fast, [`DATE_TIME_PRECISION_INDEXED`] otherwise.
+    ///
     /// If the field is not of the type `date`, tantivy
     /// will panic when the `Weight` object is created.
     pub fn new_date_bounds(
@@ -245,8 +253,9 @@ impl RangeQuery {
         lower_bound: Bound<DateTime>,
         upper_bound: Bound<DateTime>,
     ) -> RangeQuery {
+        // The bounds are truncated to the precision of the field when the weight is created.
         let make_term_val = |val: &DateTime| {
-            Term::from_field_date(Field::from_field_id(0), *val)
+            Term::from_field_date_full_precision(Field::from_field_id(0), *val)
                 .serialized_value_bytes()
                 .to_owned()
         };
@@ -328,6 +337,15 @@ pub(crate) fn maps_to_u64_fastfield(typ: Type) -> bool {
     }
 }
 
+/// Truncates a serialized date bound to the given precision.
+fn truncate_date(data: &[u8], precision: DateTimePrecision) -> Vec<u8> {
+    let val = u64::from_be(BinarySerializable::deserialize(&mut &data[..]).unwrap());
+    let date = DateTime::from_u64(val).truncate(precision);
+    Term::from_field_date_full_precision(Field::from_field_id(0), date)
+        .serialized_value_bytes()
+        .to_owned()
+}
+
 impl Query for RangeQuery {
     fn weight(&self, enable_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
         let schema = enable_scoring.schema();
@@ -344,6 +362,22 @@ impl Query for RangeQuery {
             return Err(TantivyError::SchemaError(err_msg));
         }
 
+        let (lower_bound, upper_bound) = if let FieldType::Date(date_options) = field_type {
+            // Fast field values are truncated to the precision of the field,
+            // indexed values to `DATE_TIME_PRECISION_INDEXED`.
+            let precision = if date_options.is_fast() {
+                date_options.get_precision()
+            } else {
+                DATE_TIME_PRECISION_INDEXED
+            };
+            (
+                map_bound(&self.lower_bound, |data| truncate_date(data, precision)),
+                map_bound(&self.upper_bound, |data| truncate_date(data, precision)),
+            )
+        } else {
+            (self.lower_bound.clone(), self.upper_bound.clone())
+        };
+
         if field_type.is_fast() && is_type_valid_for_fastfield_range_query(self.value_type) {
             if field_type.is_ip_addr() {
                 let parse_ip_from_bytes = |data: &Vec<u8>| {
@@ -355,8 +389,8 @@ impl Query for RangeQuery {
                     let ip_u128 = u128::from_be_bytes(ip_u128_bytes);
                     crate::Result::<Ipv6Addr>::Ok(Ipv6Addr::from_u128(ip_u128))
                 };
-                let lower_bound = map_bound_res(&self.lower_bound, parse_ip_from_bytes)?;
-                let upper_bound = map_bound_res(&self.upper_bound, parse_ip_from_bytes)?;
+                let lower_bound = map_bound_res(&lower_bound, parse_ip_from_bytes)?;
+                let upper_bound = map_bound_res(&upper_bound, parse_ip_from_bytes)?;
                 Ok(Box::new(IPFastFieldRangeWeight::new(
                     self.field.to_string(),
                     lower_bound,
@@ -370,8 +404,8 @@ impl Query for RangeQuery {
                     u64::from_be(BinarySerializable::deserialize(&mut &data[..]).unwrap())
                 };
 
-                let lower_bound = map_bound(&self.lower_bound, parse_from_bytes);
-                let upper_bound = map_bound(&self.upper_bound, parse_from_bytes);
+                let lower_bound = map_bound(&lower_bound, parse_from_bytes);
+                let upper_bound = map_bound(&upper_bound, parse_from_bytes);
                 Ok(Box::new(FastFieldRangeWeight::new_u64_lenient(
                     self.field.to_string(),
                     lower_bound,
@@ -381,8 +415,8 @@ impl Query for RangeQuery {
         } else {
             Ok(Box::new(RangeWeight {
                 field: self.field.to_string(),
-                lower_bound: self.lower_bound.clone(),
-                upper_bound: self.upper_bound.clone(),
+                lower_bound,
+                upper_bound,
                 limit: self.limit,
             }))
         }
@@ -452,6 +486,23 @@ impl Weight for RangeWeight {
         Ok(Box::new(ConstScorer::new(doc_bitset, boost)))
     }
 
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        let inverted_index = reader.inverted_index(reader.schema().get_field(&self.field)?)?;
+        let mut term_range = self.term_range(inverted_index.terms())?;
+        let mut cost_estimate = CostEstimate::default();
+        while term_range.advance() {
+            if let Some(limit) = self.limit {
+                if limit <= cost_estimate.num_expanded_terms {
+                    break;
+                }
+            }
+            cost_estimate.num_docs += term_range.value().doc_freq as u64;
+            cost_estimate.num_expanded_terms += 1;
+        }
+        cost_estimate.num_docs = cost_estimate.num_docs.min(reader.max_doc() as u64);
+        Ok(cost_estimate)
+    }
+
     fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
         let mut scorer = self.scorer(reader, 1.0)?;
         if scorer.seek(doc) != doc {
@@ -465,7 +516,7 @@ impl Weight for RangeWeight {
 mod tests {
 
     use std::net::IpAddr;
-    use std::ops::Bound;
+    use std::ops::{Bound, Range};
     use std::str::FromStr;
 
     use rand::seq::SliceRandom;
@@ -475,9 +526,10 @@ mod tests {
     use crate::indexer::NoMergePolicy;
     use crate::query::QueryParser;
     use crate::schema::{
-        Field, IntoIpv6Addr, Schema, TantivyDocument, FAST, INDEXED, STORED, TEXT,
+        DateOptions, DateTimePrecision, Field, IntoIpv6Addr, Schema, TantivyDocument, FAST,
+        INDEXED, STORED, TEXT,
     };
-    use crate::{Index, IndexWriter};
+    use crate::{DateTime, Index, IndexWriter, Order};
 
     #[test]
     fn test_range_query_simple() -> crate::Result<()> {
@@ -605,6 +657,117 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_range_query_date_precision() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_date_field("indexed", INDEXED);
+        schema_builder.add_date_field(
+            "fast",
+            DateOptions::from(INDEXED)
+                .set_fast()
+                .set_precision(DateTimePrecision::Milliseconds),
+        );
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema.clone());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for date in [
+            "2023-01-01T00:00:00Z",
+            "2023-01-01T00:00:00.250999Z",
+            "2023-01-01T00:00:00.500Z",
+            "2023-01-01T00:00:01.500Z",
+        ] {
+            let doc_json = format!(r#"{{"indexed": "{date}", "fast": "{date}"}}"#);
+            index_writer.add_document(TantivyDocument::parse_json(&schema, &doc_json)?)?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let base = DateTime::from_timestamp_secs(1_672_531_200);
+        let millis = |millis: i64| {
+            DateTime::from_timestamp_nanos(base.into_timestamp_nanos() + millis * 1_000_000)
+        };
+        let count = |field_name: &str, range: Range<DateTime>| {
+            let range_query = RangeQuery::new_date(field_name.to_string(), range);
+            searcher.search(&range_query, &Count).unwrap()
+        };
+        // The fast field keeps the milliseconds.
+        assert_eq!(count("fast", millis(200)..millis(600)), 2);
+        assert_eq!(count("fast", millis(250)..millis(251)), 1);
+        // The indexed terms, and the bounds of the range, are truncated to the second.
+        assert_eq!(count("indexed", millis(200)..millis(1200)), 3);
+
+        let query_parser = QueryParser::for_index(&index, vec![]);
+        let query =
+            query_parser.parse_query("fast:[2023-01-01T00:00:00.2Z TO 2023-01-01T00:00:00.6Z]")?;
+        assert_eq!(searcher.search(&query, &Count)?, 2);
+
+        let top_docs = searcher.search(
+            &RangeQuery::new_date("fast".to_string(), millis(100)..millis(2000)),
+            &TopDocs::with_limit(3).order_by_fast_field::<DateTime>("fast", Order::Asc),
+        )?;
+        let dates: Vec<DateTime> = top_docs.into_iter().map(|(date, _)| date).collect();
+        assert_eq!(dates, vec![millis(250), millis(500), millis(1500)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_query_negative_i64() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let indexed_field = schema_builder.add_i64_field("indexed", INDEXED);
+        let fast_field = schema_builder.add_i64_field("fast", INDEXED | FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for temperature in -20i64..=20i64 {
+            index_writer
+                .add_document(doc!(indexed_field => temperature, fast_field => temperature))?;
+        }
+        index_writer.add_document(doc!(indexed_field => i64::MIN, fast_field => i64::MIN))?;
+        index_writer.add_document(doc!(indexed_field => i64::MAX, fast_field => i64::MAX))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        for field_name in ["indexed", "fast"] {
+            let count = |range_query: RangeQuery| searcher.search(&range_query, &Count).unwrap();
+            assert_eq!(
+                count(RangeQuery::new_i64(field_name.to_string(), -5..5)),
+                10
+            );
+            assert_eq!(
+                count(RangeQuery::new_i64(field_name.to_string(), -20..-10)),
+                10
+            );
+            assert_eq!(
+                count(RangeQuery::new_i64_bounds(
+                    field_name.to_string(),
+                    Bound::Unbounded,
+                    Bound::Excluded(0)
+                )),
+                21
+            );
+            assert_eq!(
+                count(RangeQuery::new_i64_bounds(
+                    field_name.to_string(),
+                    Bound::Excluded(i64::MIN),
+                    Bound::Excluded(i64::MAX)
+                )),
+                41
+            );
+        }
+
+        // Negative values are sorted before positive values.
+        let top_docs = searcher.search(
+            &RangeQuery::new_i64("fast".to_string(), -3..3),
+            &TopDocs::with_limit(3).order_by_fast_field::<i64>("fast", Order::Asc),
+        )?;
+        let temperatures: Vec<i64> = top_docs
+            .into_iter()
+            .map(|(temperature, _)| temperature)
+            .collect();
+        assert_eq!(temperatures, vec![-3, -2, -1]);
+        Ok(())
+    }
+
     #[test]
     fn test_range_float() -> crate::Result<()> {
         let float_field: Field;
@@ -0,0 +1,85 @@
+use std::fmt;
+
+use crate::{Score, Term};
+
+/// Compact breakdown of the score of a document into the contributions of the
+/// clauses of the query.
+///
+/// Unlike an [`Explanation`](crate::query::Explanation), a `ScoreBreakdown` does not
+/// describe how each score was computed. It only lists the partial score of each term
+/// of the query matching the document, which makes it cheap enough to be computed
+/// and logged for a sample of the production queries.
+///
+/// The score of the clauses which cannot be broken down into terms, such as
+/// a [`ConstScoreQuery`](crate::query::ConstScoreQuery) or the clauses of a
+/// [`DisjunctionMaxQuery`](crate::query::DisjunctionMaxQuery), is reported as
+/// [`ScoreBreakdown::other_score`].
+///
+/// See [`TopDocs::with_score_breakdown`](crate::collector::TopDocs::with_score_breakdown).
+#[derive(Clone, Default, PartialEq)]
+pub struct ScoreBreakdown {
+    term_scores: Vec<(Term, Score)>,
+    other_score: Score,
+}
+
+impl ScoreBreakdown {
+    /// Returns the partial score of each of the terms matching the document,
+    /// in the order of the clauses of the query.
+    pub fn term_scores(&self) -> &[(Term, Score)] {
+        &self.term_scores
+    }
+
+    /// Returns the partial score of the given term, if it matched the document.
+    pub fn term_score(&self, term: &Term) -> Option<Score> {
+        self.term_scores
+            .iter()
+            .find(|(scored_term, _)| scored_term == term)
+            .map(|(_, score)| *score)
+    }
+
+    /// Returns the sum of the scores of the clauses that are not broken down into terms.
+    pub fn other_score(&self) -> Score {
+        self.other_score
+    }
+
+    /// Returns the sum of all of the contributions.
+    pub fn total_score(&self) -> Score {
+        self.term_scores
+            .iter()
+            .map(|(_, score)| *score)
+            .sum::<Score>()
+            + self.other_score
+    }
+
+    /// Adds the partial score of a term. The scores of a term appearing
+    /// in several clauses are summed.
+    pub fn add_term_score(&mut self, term: Term, score: Score) {
+        if let Some((_, term_score)) = self
+            .term_scores
+            .iter_mut()
+            .find(|(scored_term, _)| *scored_term == term)
+        {
+            *term_score += score;
+        } else {
+            self.term_scores.push((term, score));
+        }
+    }
+
+    /// Adds the score of a clause that cannot be broken down into terms.
+    pub fn add_other_score(&mut self, score: Score) {
+        self.other_score += score;
+    }
+}
+
+impl fmt::Debug for ScoreBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug_map = f.debug_map();
+        for (term, score) in &self.term_scores {
+            debug_map.entry(term, score);
+        }
+        if self.other_score != 0.0 {
+            debug_map.entry(&"other", &self.other_score);
+        }
+        debug_map.finish()
+    }
+}
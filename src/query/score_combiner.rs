@@ -15,6 +15,13 @@ pub trait ScoreCombiner: Default + Clone + Send + Copy + 'static {
 
     /// Returns the aggregate score.
     fn score(&self) -> Score;
+
+    /// Returns true if the aggregate score is the sum of the scores.
+    ///
+    /// The aggregate score can then be broken down into the scores of the scorers.
+    fn is_sum() -> bool {
+        false
+    }
 }
 
 /// Just ignores scores. The `DoNothingCombiner` does not
@@ -52,6 +59,10 @@ impl ScoreCombiner for SumCombiner {
     fn score(&self) -> Score {
         self.score
     }
+
+    fn is_sum() -> bool {
+        true
+    }
 }
 
 /// Sums the score of different scorers and keeps the count
@@ -76,6 +87,10 @@ impl ScoreCombiner for SumWithCoordsCombiner {
     fn score(&self) -> Score {
         self.score
     }
+
+    fn is_sum() -> bool {
+        true
+    }
 }
 
 /// Take max score of different scorers
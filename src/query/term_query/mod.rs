@@ -195,6 +195,14 @@ mod tests {
         {
             let explanation = term_query.explain(&searcher, DocAddress::new(0u32, 1u32))?;
             assert_nearly_equals!(explanation.value(), std::f32::consts::LN_2);
+            assert_eq!(explanation.description(), "TermQuery, product of...");
+            let details = explanation.details();
+            assert_eq!(details.len(), 3);
+            assert_eq!(details[0].description(), "(K1+1)");
+            assert!(details[2]
+                .details()
+                .iter()
+                .any(|detail| detail.description() == "dl, length of field"));
         }
         {
             let explanation_err = term_query.explain(&searcher, DocAddress::new(0u32, 0u32));
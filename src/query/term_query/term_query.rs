@@ -58,6 +58,7 @@ use crate::Term;
 pub struct TermQuery {
     term: Term,
     index_record_option: IndexRecordOption,
+    top_impacts_only: bool,
 }
 
 impl fmt::Debug for TermQuery {
@@ -72,9 +73,21 @@ impl TermQuery {
         TermQuery {
             term,
             index_record_option: segment_postings_options,
+            top_impacts_only: false,
         }
     }
 
+    /// Only reads the top impacts list of the term, in the segments where it has one.
+    ///
+    /// This makes top-k retrieval on frequent terms much cheaper, at the cost of recall:
+    /// the documents that are not part of the top impacts lists do not match. Positions
+    /// are not available through the top impacts lists.
+    ///
+    /// See [`TextFieldIndexing::set_top_impacts_per_term`](crate::schema::TextFieldIndexing::set_top_impacts_per_term).
+    pub fn set_top_impacts_only(&mut self, top_impacts_only: bool) {
+        self.top_impacts_only = top_impacts_only;
+    }
+
     /// The `Term` this query is built out of.
     pub fn term(&self) -> &Term {
         &self.term
@@ -116,7 +129,8 @@ impl TermQuery {
             index_record_option,
             bm25_weight,
             scoring_enabled,
-        ))
+        )
+        .with_top_impacts_only(self.top_impacts_only))
     }
 }
 
@@ -1,12 +1,14 @@
+use std::io;
+
 use super::term_scorer::TermScorer;
 use crate::docset::{DocSet, COLLECT_BLOCK_BUFFER_LEN};
 use crate::fieldnorm::FieldNormReader;
-use crate::index::SegmentReader;
-use crate::postings::SegmentPostings;
+use crate::index::{InvertedIndexReader, SegmentReader};
+use crate::postings::{SegmentPostings, TermInfo};
 use crate::query::bm25::Bm25Weight;
 use crate::query::explanation::does_not_match;
 use crate::query::weight::{for_each_docset_buffered, for_each_scorer};
-use crate::query::{Explanation, Scorer, Weight};
+use crate::query::{CostEstimate, Explanation, ScoreBreakdown, Scorer, Weight};
 use crate::schema::IndexRecordOption;
 use crate::{DocId, Score, Term};
 
@@ -15,6 +17,7 @@ pub struct TermWeight {
     index_record_option: IndexRecordOption,
     similarity_weight: Bm25Weight,
     scoring_enabled: bool,
+    top_impacts_only: bool,
 }
 
 impl Weight for TermWeight {
@@ -33,9 +36,37 @@ impl Weight for TermWeight {
         Ok(explanation)
     }
 
+    fn score_breakdown(
+        &self,
+        reader: &SegmentReader,
+        doc: DocId,
+        boost: Score,
+        breakdown: &mut ScoreBreakdown,
+    ) -> crate::Result<()> {
+        let mut scorer = self.specialized_scorer(reader, boost)?;
+        if scorer.doc() <= doc && scorer.seek(doc) == doc {
+            breakdown.add_term_score(self.term.clone(), scorer.score());
+        }
+        Ok(())
+    }
+
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        let inverted_index = reader.inverted_index(self.term.field())?;
+        let cost_estimate = match inverted_index.get_term_info(&self.term)? {
+            Some(term_info) => CostEstimate {
+                num_docs: term_info.doc_freq as u64,
+                num_expanded_terms: 1,
+            },
+            None => CostEstimate::default(),
+        };
+        Ok(cost_estimate)
+    }
+
     fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
         if let Some(alive_bitset) = reader.alive_bitset() {
             Ok(self.scorer(reader, 1.0)?.count(alive_bitset))
+        } else if self.top_impacts_only {
+            Ok(self.scorer(reader, 1.0)?.count_including_deleted())
         } else {
             let field = self.term.field();
             let inv_index = reader.inverted_index(field)?;
@@ -103,13 +134,36 @@ impl TermWeight {
             index_record_option,
             similarity_weight,
             scoring_enabled,
+            top_impacts_only: false,
         }
     }
 
+    /// Only reads the top impacts lists of the term, when available.
+    #[must_use]
+    pub(crate) fn with_top_impacts_only(mut self, top_impacts_only: bool) -> TermWeight {
+        self.top_impacts_only = top_impacts_only;
+        self
+    }
+
     pub fn term(&self) -> &Term {
         &self.term
     }
 
+    fn read_postings(
+        &self,
+        inverted_index: &InvertedIndexReader,
+        term_info: &TermInfo,
+    ) -> io::Result<SegmentPostings> {
+        if self.top_impacts_only {
+            if let Some(top_impacts_postings) = inverted_index
+                .read_top_impacts_postings_from_terminfo(term_info, self.index_record_option)?
+            {
+                return Ok(top_impacts_postings);
+            }
+        }
+        inverted_index.read_postings_from_terminfo(term_info, self.index_record_option)
+    }
+
     pub(crate) fn specialized_scorer(
         &self,
         reader: &SegmentReader,
@@ -125,8 +179,10 @@ impl TermWeight {
         let fieldnorm_reader =
             fieldnorm_reader_opt.unwrap_or_else(|| FieldNormReader::constant(reader.max_doc(), 1));
         let similarity_weight = self.similarity_weight.boost_by(boost);
-        let postings_opt: Option<SegmentPostings> =
-            inverted_index.read_postings(&self.term, self.index_record_option)?;
+        let postings_opt: Option<SegmentPostings> = inverted_index
+            .get_term_info(&self.term)?
+            .map(|term_info| self.read_postings(&inverted_index, &term_info))
+            .transpose()?;
         if let Some(segment_postings) = postings_opt {
             Ok(TermScorer::new(
                 segment_postings,
@@ -1,7 +1,7 @@
 use super::Scorer;
 use crate::docset::COLLECT_BLOCK_BUFFER_LEN;
 use crate::index::SegmentReader;
-use crate::query::Explanation;
+use crate::query::{CostEstimate, Explanation, ScoreBreakdown};
 use crate::{DocId, DocSet, Score, TERMINATED};
 
 /// Iterates through all of the documents and scores matched by the DocSet
@@ -54,11 +54,31 @@ pub(crate) fn for_each_pruning_scorer<TScorer: Scorer + ?Sized>(
         let score = scorer.score();
         if score > threshold {
             threshold = callback(doc, score);
+            // No document can score above an infinite threshold.
+            if threshold == Score::INFINITY {
+                break;
+            }
         }
         doc = scorer.advance();
     }
 }
 
+/// Adds the score of `doc` to the `breakdown`, as a single contribution,
+/// if the document matches.
+pub(crate) fn score_breakdown_whole<TWeight: Weight + ?Sized>(
+    weight: &TWeight,
+    reader: &SegmentReader,
+    doc: DocId,
+    boost: Score,
+    breakdown: &mut ScoreBreakdown,
+) -> crate::Result<()> {
+    let mut scorer = weight.scorer(reader, boost)?;
+    if scorer.doc() <= doc && scorer.seek(doc) == doc {
+        breakdown.add_other_score(scorer.score());
+    }
+    Ok(())
+}
+
 /// A Weight is the specialization of a `Query`
 /// for a given set of segments.
 ///
@@ -74,6 +94,38 @@ pub trait Weight: Send + Sync + 'static {
     /// Returns an [`Explanation`] for the given document.
     fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation>;
 
+    /// Adds to `breakdown` the contributions of the clauses of the query
+    /// to the score of the given document, multiplied by `boost`.
+    ///
+    /// Nothing is added if the document does not match.
+    /// By default, the score of the document is added as a single contribution.
+    fn score_breakdown(
+        &self,
+        reader: &SegmentReader,
+        doc: DocId,
+        boost: Score,
+        breakdown: &mut ScoreBreakdown,
+    ) -> crate::Result<()> {
+        score_breakdown_whole(self, reader, doc, boost, breakdown)
+    }
+
+    /// Estimates the number of documents the scorer of the weight would go through, and
+    /// the number of terms of the segment whose postings it would read.
+    ///
+    /// The estimation only relies on the term dictionary and the document frequencies of
+    /// the terms: no scorer is built and no postings are opened. This makes it possible to
+    /// check how many terms a multi-term query, such as a
+    /// [`RegexQuery`](crate::query::RegexQuery), expands to.
+    /// See [`Searcher::explain_plan`](crate::Searcher::explain_plan).
+    ///
+    /// By default, the weight is assumed to go through all of the documents of the segment.
+    fn estimate_cost(&self, reader: &SegmentReader) -> crate::Result<CostEstimate> {
+        Ok(CostEstimate {
+            num_docs: reader.max_doc() as u64,
+            num_expanded_terms: 0,
+        })
+    }
+
     /// Returns the number documents within the given [`SegmentReader`].
     fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
         let mut scorer = self.scorer(reader, 1.0)?;
@@ -0,0 +1,287 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::directory::FileSlice;
+use crate::index::SegmentId;
+use crate::query::Query;
+use crate::reader::Warmer;
+use crate::schema::Field;
+use crate::{Searcher, SearcherGeneration, SegmentReader};
+
+/// Pages of memory mapped files are loaded by touching one byte every `PAGE_SIZE` bytes.
+const PAGE_SIZE: usize = 4_096;
+
+/// Component of the index read by a query.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+enum Component {
+    /// Term dictionary, postings and fieldnorms of an indexed field.
+    InvertedIndex(Field),
+    /// Term dictionary, postings, fieldnorms and positions of an indexed field.
+    InvertedIndexWithPositions(Field),
+    /// Columns of a fast field.
+    FastField(String),
+}
+
+#[derive(Default)]
+struct AdaptiveWarmerState {
+    num_queries: u64,
+    // Number of queries recorded when each component was last used.
+    last_used: HashMap<Component, u64>,
+    warmed_segment_ids: HashSet<SegmentId>,
+}
+
+/// [`Warmer`] preloading the components of the index that recent queries used.
+///
+/// The queries are reported with [`AdaptiveWarmer::record_query`], which tracks the fields
+/// whose terms they search, and with [`AdaptiveWarmer::record_fast_field`], for the fast fields
+/// they sort or aggregate on. When the reader reloads, after a commit or a merge, the
+/// components used by the last `history_size` queries are loaded for the new segments,
+/// so that the first queries hitting a new segment do not pay for reading them from disk.
+///
+/// Segments are only preloaded once, when they first appear in a searcher.
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use tantivy::collector::Count;
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{AdaptiveWarmer, Index, Warmer};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let warmer = Arc::new(AdaptiveWarmer::new(1_000));
+/// let warmer_ref: Arc<dyn Warmer> = warmer.clone();
+/// let reader = index
+///     .reader_builder()
+///     .warmers(vec![Arc::downgrade(&warmer_ref)])
+///     .try_into()?;
+///
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("hello")?;
+/// warmer.record_query(&query);
+/// let count = reader.searcher().search(&query, &Count)?;
+/// assert_eq!(count, 0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct AdaptiveWarmer {
+    history_size: u64,
+    state: Mutex<AdaptiveWarmerState>,
+    num_preloaded_bytes: AtomicU64,
+}
+
+impl AdaptiveWarmer {
+    /// Creates an `AdaptiveWarmer` preloading the components used by the last
+    /// `history_size` recorded queries.
+    pub fn new(history_size: usize) -> AdaptiveWarmer {
+        AdaptiveWarmer {
+            history_size: history_size.max(1) as u64,
+            state: Mutex::default(),
+            num_preloaded_bytes: AtomicU64::default(),
+        }
+    }
+
+    /// Records the fields whose terms the query searches.
+    ///
+    /// Only the queries exposing their terms through [`Query::query_terms`] are tracked.
+    /// The fields read by other queries, like range queries on fast fields, can be
+    /// recorded with [`AdaptiveWarmer::record_fast_field`].
+    pub fn record_query(&self, query: &dyn Query) {
+        let mut components = HashSet::new();
+        query.query_terms(&mut |term, need_positions| {
+            let component = if need_positions {
+                Component::InvertedIndexWithPositions(term.field())
+            } else {
+                Component::InvertedIndex(term.field())
+            };
+            components.insert(component);
+        });
+        self.record(components);
+    }
+
+    /// Records a query sorting, aggregating or filtering on the given fast field.
+    pub fn record_fast_field(&self, field_name: &str) {
+        let component = Component::FastField(field_name.to_string());
+        self.record(HashSet::from([component]));
+    }
+
+    fn record(&self, components: HashSet<Component>) {
+        let mut state = self.state.lock().unwrap();
+        state.num_queries += 1;
+        let num_queries = state.num_queries;
+        for component in components {
+            state.last_used.insert(component, num_queries);
+        }
+        let history_size = self.history_size;
+        state
+            .last_used
+            .retain(|_, last_used| *last_used + history_size > num_queries);
+    }
+
+    /// Returns the number of bytes preloaded so far.
+    pub fn num_preloaded_bytes(&self) -> u64 {
+        self.num_preloaded_bytes.load(Ordering::Relaxed)
+    }
+
+    fn preload_segment(
+        &self,
+        segment_reader: &SegmentReader,
+        components: &[Component],
+    ) -> crate::Result<()> {
+        for component in components {
+            let files = match component {
+                Component::InvertedIndex(field) => {
+                    segment_reader.inverted_index_files(*field, false)
+                }
+                Component::InvertedIndexWithPositions(field) => {
+                    segment_reader.inverted_index_files(*field, true)
+                }
+                Component::FastField(field_name) => segment_reader
+                    .fast_fields()
+                    .dynamic_column_handles(field_name)?
+                    .iter()
+                    .map(|column_handle| column_handle.file_slice().clone())
+                    .collect(),
+            };
+            for file in &files {
+                let num_bytes = preload(file)?;
+                self.num_preloaded_bytes
+                    .fetch_add(num_bytes, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the file, making sure its pages are loaded if it is memory mapped.
+fn preload(file: &FileSlice) -> io::Result<u64> {
+    let bytes = file.read_bytes()?;
+    let checksum = bytes
+        .as_slice()
+        .iter()
+        .step_by(PAGE_SIZE)
+        .fold(0u8, |checksum, byte| checksum ^ byte);
+    std::hint::black_box(checksum);
+    Ok(bytes.len() as u64)
+}
+
+impl Warmer for AdaptiveWarmer {
+    fn warm(&self, searcher: &Searcher) -> crate::Result<()> {
+        // The lock is not held while preloading, so that queries can still be recorded.
+        let (components, new_segment_readers) = {
+            let state = self.state.lock().unwrap();
+            let components: Vec<Component> = state.last_used.keys().cloned().collect();
+            let new_segment_readers: Vec<&SegmentReader> = searcher
+                .segment_readers()
+                .iter()
+                .filter(|segment_reader| {
+                    !state
+                        .warmed_segment_ids
+                        .contains(&segment_reader.segment_id())
+                })
+                .collect();
+            (components, new_segment_readers)
+        };
+        for segment_reader in new_segment_readers {
+            self.preload_segment(segment_reader, &components)?;
+            self.state
+                .lock()
+                .unwrap()
+                .warmed_segment_ids
+                .insert(segment_reader.segment_id());
+        }
+        Ok(())
+    }
+
+    fn garbage_collect(&self, live_generations: &[&SearcherGeneration]) {
+        let live_segment_ids: HashSet<SegmentId> = live_generations
+            .iter()
+            .flat_map(|searcher_generation| searcher_generation.segments().keys().copied())
+            .collect();
+        self.state
+            .lock()
+            .unwrap()
+            .warmed_segment_ids
+            .retain(|segment_id| live_segment_ids.contains(segment_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{AdaptiveWarmer, Component};
+    use crate::query::QueryParser;
+    use crate::schema::{Schema, FAST, TEXT};
+    use crate::{doc, Index, IndexWriter, ReloadPolicy, Warmer};
+
+    #[test]
+    fn test_adaptive_warmer_history() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let warmer = AdaptiveWarmer::new(2);
+        warmer.record_query(&query_parser.parse_query("title:hello").unwrap());
+        warmer.record_query(&query_parser.parse_query("body:\"happy tax\"").unwrap());
+        {
+            let state = warmer.state.lock().unwrap();
+            assert!(state
+                .last_used
+                .contains_key(&Component::InvertedIndex(title)));
+            assert!(state
+                .last_used
+                .contains_key(&Component::InvertedIndexWithPositions(body)));
+        }
+        warmer.record_fast_field("price");
+        let state = warmer.state.lock().unwrap();
+        // The first query fell out of the history.
+        assert_eq!(state.last_used.len(), 2);
+        assert!(!state
+            .last_used
+            .contains_key(&Component::InvertedIndex(title)));
+        assert!(state
+            .last_used
+            .contains_key(&Component::FastField("price".to_string())));
+    }
+
+    #[test]
+    fn test_adaptive_warmer_preloads_new_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let price = schema_builder.add_u64_field("price", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let warmer = Arc::new(AdaptiveWarmer::new(10));
+        let warmer_ref: Arc<dyn Warmer> = warmer.clone();
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .warmers(vec![Arc::downgrade(&warmer_ref)])
+            .try_into()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "hello", price => 3u64))?;
+        index_writer.commit()?;
+        reader.reload()?;
+        // No query was recorded yet.
+        assert_eq!(warmer.num_preloaded_bytes(), 0);
+
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        warmer.record_query(&query_parser.parse_query("hello")?);
+        warmer.record_fast_field("price");
+        index_writer.add_document(doc!(title => "hello world", price => 4u64))?;
+        index_writer.commit()?;
+        reader.reload()?;
+        let num_preloaded_bytes = warmer.num_preloaded_bytes();
+        assert!(num_preloaded_bytes > 0);
+
+        // The segments already warmed are not preloaded again.
+        reader.reload()?;
+        assert_eq!(warmer.num_preloaded_bytes(), num_preloaded_bytes);
+        Ok(())
+    }
+}
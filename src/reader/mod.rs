@@ -1,3 +1,4 @@
+mod adaptive_warmer;
 mod warming;
 
 use std::sync::atomic::AtomicU64;
@@ -6,9 +7,11 @@ use std::sync::{atomic, Arc, Weak};
 use arc_swap::ArcSwap;
 pub use warming::Warmer;
 
+pub use self::adaptive_warmer::AdaptiveWarmer;
 use self::warming::WarmingState;
 use crate::core::searcher::{SearcherGeneration, SearcherInner};
 use crate::directory::{Directory, WatchCallback, WatchHandle, META_LOCK};
+use crate::index::SegmentComponent;
 use crate::store::DOCSTORE_CACHE_CAPACITY;
 use crate::{Index, Inventory, Searcher, SegmentReader, TrackedObject};
 
@@ -37,6 +40,7 @@ pub enum ReloadPolicy {
 /// - [`Warmer`] implementations
 /// - number of warming threads, for parallelizing warming work
 /// - The cache size of the underlying doc store readers.
+/// - The segment components that should not be opened.
 #[derive(Clone)]
 pub struct IndexReaderBuilder {
     reload_policy: ReloadPolicy,
@@ -44,6 +48,7 @@ pub struct IndexReaderBuilder {
     warmers: Vec<Weak<dyn Warmer>>,
     num_warming_threads: usize,
     doc_store_cache_num_blocks: usize,
+    skipped_components: Vec<SegmentComponent>,
 }
 
 impl IndexReaderBuilder {
@@ -55,6 +60,7 @@ impl IndexReaderBuilder {
             warmers: Vec::new(),
             num_warming_threads: 1,
             doc_store_cache_num_blocks: DOCSTORE_CACHE_CAPACITY,
+            skipped_components: Vec::new(),
         }
     }
 
@@ -72,6 +78,7 @@ impl IndexReaderBuilder {
         )?;
         let inner_reader = InnerIndexReader::new(
             self.doc_store_cache_num_blocks,
+            self.skipped_components,
             self.index,
             warming_state,
             searcher_generation_inventory,
@@ -126,6 +133,19 @@ impl IndexReaderBuilder {
         self
     }
 
+    /// Sets the segment components that should not be opened by the reader.
+    ///
+    /// See [`SegmentReader::open_without_components`] for the components
+    /// that can be skipped, and the resulting limitations.
+    #[must_use]
+    pub fn skip_components(
+        mut self,
+        skipped_components: Vec<SegmentComponent>,
+    ) -> IndexReaderBuilder {
+        self.skipped_components = skipped_components;
+        self
+    }
+
     /// Set the [`Warmer`]s that are invoked when reloading searchable segments.
     #[must_use]
     pub fn warmers(mut self, warmers: Vec<Weak<dyn Warmer>>) -> IndexReaderBuilder {
@@ -154,6 +174,7 @@ impl TryInto<IndexReader> for IndexReaderBuilder {
 
 struct InnerIndexReader {
     doc_store_cache_num_blocks: usize,
+    skipped_components: Vec<SegmentComponent>,
     index: Index,
     warming_state: WarmingState,
     searcher: arc_swap::ArcSwap<SearcherInner>,
@@ -164,6 +185,7 @@ struct InnerIndexReader {
 impl InnerIndexReader {
     fn new(
         doc_store_cache_num_blocks: usize,
+        skipped_components: Vec<SegmentComponent>,
         index: Index,
         warming_state: WarmingState,
         // The searcher_generation_inventory is not used as source, but as target to track the
@@ -175,12 +197,14 @@ impl InnerIndexReader {
         let searcher = Self::create_searcher(
             &index,
             doc_store_cache_num_blocks,
+            &skipped_components,
             &warming_state,
             &searcher_generation_counter,
             &searcher_generation_inventory,
         )?;
         Ok(InnerIndexReader {
             doc_store_cache_num_blocks,
+            skipped_components,
             index,
             warming_state,
             searcher: ArcSwap::from(searcher),
@@ -192,13 +216,16 @@ impl InnerIndexReader {
     ///
     /// This function acquires a lock to prevent GC from removing files
     /// as we are opening our index.
-    fn open_segment_readers(index: &Index) -> crate::Result<Vec<SegmentReader>> {
+    fn open_segment_readers(
+        index: &Index,
+        skipped_components: &[SegmentComponent],
+    ) -> crate::Result<Vec<SegmentReader>> {
         // Prevents segment files from getting deleted while we are in the process of opening them
         let _meta_lock = index.directory().acquire_lock(&META_LOCK)?;
         let searchable_segments = index.searchable_segments()?;
         let segment_readers = searchable_segments
             .iter()
-            .map(SegmentReader::open)
+            .map(|segment| SegmentReader::open_without_components(segment, skipped_components))
             .collect::<crate::Result<_>>()?;
         Ok(segment_readers)
     }
@@ -217,11 +244,12 @@ impl InnerIndexReader {
     fn create_searcher(
         index: &Index,
         doc_store_cache_num_blocks: usize,
+        skipped_components: &[SegmentComponent],
         warming_state: &WarmingState,
         searcher_generation_counter: &Arc<AtomicU64>,
         searcher_generation_inventory: &Inventory<SearcherGeneration>,
     ) -> crate::Result<Arc<SearcherInner>> {
-        let segment_readers = Self::open_segment_readers(index)?;
+        let segment_readers = Self::open_segment_readers(index, skipped_components)?;
         let searcher_generation = Self::track_segment_readers_in_inventory(
             &segment_readers,
             searcher_generation_counter,
@@ -245,6 +273,7 @@ impl InnerIndexReader {
         let searcher = Self::create_searcher(
             &self.index,
             self.doc_store_cache_num_blocks,
+            &self.skipped_components,
             &self.warming_state,
             &self.searcher_generation_counter,
             &self.searcher_generation_inventory,
@@ -21,6 +21,8 @@ pub struct DateOptions {
     // compression on fast fields.
     #[serde(default)]
     precision: DateTimePrecision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date_facet: Option<String>,
 }
 
 impl DateOptions {
@@ -106,6 +108,29 @@ impl DateOptions {
     pub fn get_precision(&self) -> DateTimePrecision {
         self.precision
     }
+
+    /// Derives a hierarchical facet from each value of the field, into the given facet field.
+    ///
+    /// The facet of a date is its year, month and day in UTC, e.g. `/2017/05/12`. The month
+    /// and the day are zero-padded, so that the facets sort chronologically. This makes it
+    /// possible to drill down into the dates with a
+    /// [`FacetCollector`](crate::collector::FacetCollector), without computing the facets
+    /// on the client side.
+    ///
+    /// The derived facets are indexed and added to the fast field of the facet field, but are
+    /// not stored.
+    ///
+    /// Building the schema panics if `facet_field_name` is not a facet field of the schema.
+    #[must_use]
+    pub fn set_date_facet(mut self, facet_field_name: &str) -> DateOptions {
+        self.date_facet = Some(facet_field_name.to_string());
+        self
+    }
+
+    /// Returns the name of the facet field the values of the field are derived into, if any.
+    pub fn date_facet(&self) -> Option<&str> {
+        self.date_facet.as_deref()
+    }
 }
 
 impl From<()> for DateOptions {
@@ -153,6 +178,7 @@ impl<T: Into<DateOptions>> BitOr<T> for DateOptions {
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
             precision: self.precision,
+            date_facet: self.date_facet.or(other.date_facet),
         }
     }
 }
@@ -12,7 +12,9 @@ use crate::schema::document::{
     DeserializeError, Document, DocumentDeserialize, DocumentDeserializer,
 };
 use crate::schema::field_type::ValueParsingError;
-use crate::schema::{Facet, Field, NamedFieldDocument, OwnedValue, Schema};
+use crate::schema::{
+    Facet, Field, NamedFieldDocument, OwnedValue, Schema, TypedField, TypedFieldValue,
+};
 use crate::tokenizer::PreTokenizedString;
 
 #[repr(packed)]
@@ -113,6 +115,11 @@ impl CompactDoc {
         self.add_leaf_field_value(field, value);
     }
 
+    /// Add a value to a typed field.
+    pub fn add_typed<T: TypedFieldValue>(&mut self, field: TypedField<T>, value: &T) {
+        T::add_to_document(self, field.field(), value);
+    }
+
     /// Add a bytes field
     pub fn add_bytes(&mut self, field: Field, value: &[u8]) {
         self.add_leaf_field_value(field, value);
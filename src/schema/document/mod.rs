@@ -162,11 +162,18 @@ mod default_document;
 mod existing_type_impls;
 mod owned_value;
 mod se;
+mod typed_document;
 mod value;
 
 use std::collections::BTreeMap;
 use std::mem;
 
+/// Derives [`TypedDocument`] for a struct with named fields.
+///
+/// See the [`tantivy_derive`] crate for the supported field types and attributes.
+#[cfg(feature = "derive")]
+pub use tantivy_derive::TypedDocument;
+
 pub(crate) use self::de::BinaryDocumentDeserializer;
 pub use self::de::{
     ArrayAccess, DeserializeError, DocumentDeserialize, DocumentDeserializer, ObjectAccess,
@@ -177,6 +184,7 @@ pub use self::default_document::{
 };
 pub use self::owned_value::OwnedValue;
 pub(crate) use self::se::BinaryDocumentSerializer;
+pub use self::typed_document::TypedDocument;
 pub use self::value::{ReferenceValue, ReferenceValueLeaf, Value};
 use super::*;
 
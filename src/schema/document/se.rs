@@ -7,7 +7,7 @@ use common::{f64_to_u64, BinarySerializable, VInt};
 
 use super::{OwnedValue, ReferenceValueLeaf};
 use crate::schema::document::{type_codes, Document, ReferenceValue, Value};
-use crate::schema::Schema;
+use crate::schema::{Field, Schema};
 
 /// A serializer writing documents which implement [`Document`] to a provided writer.
 pub struct BinaryDocumentSerializer<'se, W> {
@@ -28,15 +28,34 @@ where W: Write
     #[inline]
     pub(crate) fn serialize_doc<D>(&mut self, doc: &D) -> io::Result<()>
     where D: Document {
-        let stored_field_values = || {
+        let schema = self.schema;
+        self.serialize_field_values(doc, |field| schema.get_field_entry(field).is_stored())
+    }
+
+    /// Same as [`BinaryDocumentSerializer::serialize_doc`], but the values of
+    /// all of the fields are serialized, whether they are stored or not.
+    pub(crate) fn serialize_doc_with_all_fields<D>(&mut self, doc: &D) -> io::Result<()>
+    where D: Document {
+        self.serialize_field_values(doc, |_| true)
+    }
+
+    fn serialize_field_values<D>(
+        &mut self,
+        doc: &D,
+        include_field: impl Fn(Field) -> bool,
+    ) -> io::Result<()>
+    where
+        D: Document,
+    {
+        let field_values = || {
             doc.iter_fields_and_values()
-                .filter(|(field, _)| self.schema.get_field_entry(*field).is_stored())
+                .filter(|(field, _)| include_field(*field))
         };
-        let num_field_values = stored_field_values().count();
+        let num_field_values = field_values().count();
         let mut actual_length = 0;
 
         VInt(num_field_values as u64).serialize(self.writer)?;
-        for (field, value_access) in stored_field_values() {
+        for (field, value_access) in field_values() {
             field.serialize(self.writer)?;
 
             let mut serializer = BinaryValueSerializer::new(self.writer);
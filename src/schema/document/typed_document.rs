@@ -0,0 +1,52 @@
+use crate::schema::{Schema, SchemaBuilder, TantivyDocument};
+
+/// A Rust type that maps to a set of schema fields, and that can be converted
+/// to and from a [`TantivyDocument`].
+///
+/// This trait is usually not implemented by hand, but derived with
+/// `#[derive(TypedDocument)]`, which requires the `derive` feature.
+/// Fields are looked up by name in the schema, so that application code
+/// does not need to keep track of field handles.
+#[cfg_attr(feature = "derive", doc = "```")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use tantivy::schema::TypedDocument;
+///
+/// #[derive(TypedDocument, Debug, PartialEq)]
+/// struct Book {
+///     #[tantivy(text, stored)]
+///     title: String,
+///     #[tantivy(indexed, fast, stored)]
+///     year: u64,
+/// }
+///
+/// let schema = Book::schema();
+/// let book = Book {
+///     title: "Of Mice and Men".to_string(),
+///     year: 1937,
+/// };
+/// let doc = book.to_document(&schema)?;
+/// assert_eq!(Book::from_document(&doc, &schema)?, book);
+/// # Ok::<(), tantivy::TantivyError>(())
+/// ```
+pub trait TypedDocument: Sized {
+    /// Adds the fields of the type to the schema builder.
+    fn register_fields(schema_builder: &mut SchemaBuilder);
+
+    /// Builds a schema containing the fields of the type only.
+    fn schema() -> Schema {
+        let mut schema_builder = Schema::builder();
+        Self::register_fields(&mut schema_builder);
+        schema_builder.build()
+    }
+
+    /// Converts the value into a document for the given schema.
+    ///
+    /// Returns an error if one of the fields is missing from the schema.
+    fn to_document(&self, schema: &Schema) -> crate::Result<TantivyDocument>;
+
+    /// Builds a value from a document, typically fetched from the doc store.
+    ///
+    /// Fields without any value in the document (e.g. fields that are not stored)
+    /// are set to their default value.
+    fn from_document(doc: &TantivyDocument, schema: &Schema) -> crate::Result<Self>;
+}
@@ -167,6 +167,36 @@ impl Facet {
         other_str.starts_with(self_str) && other_str.as_bytes()[self_str.len()] == FACET_SEP_BYTE
     }
 
+    /// Returns the number of levels of the facet.
+    ///
+    /// The root facet `/` has a depth of 0, `/category/electronics` has a depth of 2.
+    pub fn depth(&self) -> usize {
+        if self.is_root() {
+            return 0;
+        }
+        self.encoded_str()
+            .bytes()
+            .filter(|b| *b == FACET_SEP_BYTE)
+            .count()
+            + 1
+    }
+
+    /// Returns the parent of the facet, or `None` for the root facet.
+    ///
+    /// This is handy to build the drill-up links of a faceted navigation,
+    /// e.g. `/category/electronics` for `/category/electronics/phones`.
+    pub fn parent(&self) -> Option<Facet> {
+        if self.is_root() {
+            return None;
+        }
+        let encoded_str = self.encoded_str();
+        let parent_len = encoded_str
+            .bytes()
+            .rposition(|b| b == FACET_SEP_BYTE)
+            .unwrap_or(0);
+        Some(Facet(encoded_str[..parent_len].to_string()))
+    }
+
     /// Extract path from the `Facet`.
     pub fn to_path(&self) -> Vec<&str> {
         self.encoded_str().split(|c| c == FACET_SEP_CHAR).collect()
@@ -287,6 +317,20 @@ mod tests {
         assert_eq!(facet.to_path(), v);
     }
 
+    #[test]
+    fn test_depth_and_parent() {
+        let facet = Facet::from("/category/electronics/phones");
+        assert_eq!(facet.depth(), 3);
+        let parent = facet.parent().unwrap();
+        assert_eq!(parent, Facet::from("/category/electronics"));
+        assert_eq!(parent.depth(), 2);
+        let category = parent.parent().unwrap();
+        assert_eq!(category, Facet::from("/category"));
+        assert_eq!(category.parent(), Some(Facet::root()));
+        assert_eq!(Facet::root().depth(), 0);
+        assert_eq!(Facet::root().parent(), None);
+    }
+
     #[test]
     fn test_to_path_string() {
         let v = ["first", "second", "third/not_fourth"];
@@ -19,6 +19,8 @@ pub struct FieldEntry {
     name: String,
     #[serde(flatten)]
     field_type: FieldType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
 }
 
 impl FieldEntry {
@@ -28,6 +30,7 @@ impl FieldEntry {
         FieldEntry {
             name: field_name,
             field_type,
+            aliases: Vec::new(),
         }
     }
 
@@ -86,6 +89,30 @@ impl FieldEntry {
         &self.name
     }
 
+    /// Adds an alias to the field.
+    ///
+    /// An alias is another name resolving to the field, in [`Schema::get_field`]
+    /// and everywhere field names are resolved (queries, fast fields, JSON documents).
+    /// This makes it possible to rename a field without reindexing, while keeping
+    /// the old name working.
+    ///
+    /// [`Schema::get_field`]: crate::schema::Schema::get_field
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alias` is not a valid field name.
+    #[must_use]
+    pub fn with_alias(mut self, alias: &str) -> FieldEntry {
+        assert!(is_valid_field_name(alias));
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    /// Returns the aliases of the field.
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
     /// Returns the field type
     pub fn field_type(&self) -> &FieldType {
         &self.field_type
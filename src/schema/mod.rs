@@ -52,6 +52,19 @@
 //! On the other hand setting the field as stored or not determines whether the field should be
 //! returned when [`Searcher::doc()`](crate::Searcher::doc) is called.
 //!
+//! A field that is only [`STORED`] is written to the doc store, but is neither tokenized nor
+//! added to the inverted index. This is handy for payloads that need to travel with the
+//! documents without bloating the term dictionary.
+//!
+//! ```
+//! use tantivy::schema::*;
+//! let mut schema_builder = Schema::builder();
+//! schema_builder.add_text_field("title", TEXT | STORED);
+//! schema_builder.add_text_field("thumbnail_url", STORED);
+//! schema_builder.add_bytes_field("payload", STORED);
+//! let schema = schema_builder.build();
+//! ```
+//!
 //!
 //! ## Setting a u64, a i64 or a f64 field
 //!
@@ -110,6 +123,7 @@ pub mod document;
 mod facet;
 mod facet_options;
 mod schema;
+mod schema_diff;
 pub(crate) mod term;
 
 mod field_entry;
@@ -125,12 +139,15 @@ mod json_object_options;
 mod named_field_document;
 mod numeric_options;
 mod text_options;
+mod typed_field;
 
 use columnar::ColumnType;
 
 pub use self::bytes_options::BytesOptions;
 pub use self::date_time_options::{DateOptions, DateTimePrecision, DATE_TIME_PRECISION_INDEXED};
-pub use self::document::{DocParsingError, Document, OwnedValue, TantivyDocument, Value};
+pub use self::document::{
+    DocParsingError, Document, OwnedValue, TantivyDocument, TypedDocument, Value,
+};
 pub(crate) use self::facet::FACET_SEP_BYTE;
 pub use self::facet::{Facet, FacetParseError};
 pub use self::facet_options::FacetOptions;
@@ -144,8 +161,11 @@ pub use self::json_object_options::JsonObjectOptions;
 pub use self::named_field_document::NamedFieldDocument;
 pub use self::numeric_options::NumericOptions;
 pub use self::schema::{Schema, SchemaBuilder};
+pub use self::schema_diff::{FieldChange, SchemaCompatibility, SchemaDiff};
 pub use self::term::{Term, ValueBytes};
-pub use self::text_options::{TextFieldIndexing, TextOptions, STRING, TEXT};
+pub(crate) use self::text_options::DEFAULT_POSITION_GAP;
+pub use self::text_options::{Bm25Options, TextFieldIndexing, TextOptions, STRING, TEXT};
+pub use self::typed_field::{TypedField, TypedFieldValue};
 
 /// Validator for a potential `field_name`.
 /// Returns true if the name can be use for a field name.
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
-use serde::de::{SeqAccess, Visitor};
+use serde::de::{self, SeqAccess, Visitor};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -194,30 +194,164 @@ impl SchemaBuilder {
         self.add_field(field_entry)
     }
 
+    /// Adds a new field holding values of type `T`, and returns
+    /// the associated typed field handle.
+    ///
+    /// The options have to match the type of the field:
+    /// for instance, `TEXT` cannot be used for a `TypedField<u64>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when field already exists.
+    pub fn add_typed_field<T: TypedFieldValue>(
+        &mut self,
+        field_name: &str,
+        field_options: impl Into<T::Options>,
+    ) -> TypedField<T> {
+        TypedField::new(T::add_field(self, field_name, field_options.into()))
+    }
+
     /// Adds a field entry to the schema in build.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the field, or one of its aliases, already exists.
     pub fn add_field(&mut self, field_entry: FieldEntry) -> Field {
         let field = Field::from_field_id(self.fields.len() as u32);
         let field_name = field_entry.name().to_string();
         if let Some(_previous_value) = self.fields_map.insert(field_name, field) {
             panic!("Field already exists in schema {}", field_entry.name());
         };
+        for alias in field_entry.aliases() {
+            self.register_alias(alias, field);
+        }
         self.fields.push(field_entry);
         field
     }
 
+    /// Adds an alias to the field named `field_name`.
+    ///
+    /// See [`FieldEntry::with_alias`].
+    ///
+    /// # Panics
+    ///
+    /// Panics when there is no field named `field_name`, or when `alias` is
+    /// already the name or the alias of a field.
+    pub fn add_field_alias(&mut self, alias: &str, field_name: &str) {
+        let field = *self
+            .fields_map
+            .get(field_name)
+            .unwrap_or_else(|| panic!("Cannot alias the unknown field {field_name}"));
+        self.register_alias(alias, field);
+        let field_entry = &mut self.fields[field.field_id() as usize];
+        *field_entry = field_entry.clone().with_alias(alias);
+    }
+
+    fn register_alias(&mut self, alias: &str, field: Field) {
+        if let Some(_previous_value) = self.fields_map.insert(alias.to_string(), field) {
+            panic!("Field already exists in schema {alias}");
+        }
+    }
+
     /// Finalize the creation of a `Schema`
     /// This will consume your `SchemaBuilder`
+    ///
+    /// # Panics
+    ///
+    /// Panics when the values of a field are [copied](TextOptions::set_copy_to) to a field
+    /// that is not an indexed text field of the schema, or when the
+    /// [date facets](DateOptions::set_date_facet) of a field are derived into a field that is
+    /// not a facet field of the schema.
     pub fn build(self) -> Schema {
-        Schema(Arc::new(InnerSchema {
+        self.build_checked().unwrap_or_else(|msg| panic!("{msg}"))
+    }
+
+    /// Finalize the creation of a `Schema`, like [`SchemaBuilder::build`], but returns an
+    /// error instead of panicking when the options of a field are inconsistent with the
+    /// other fields of the schema.
+    pub fn try_build(self) -> crate::Result<Schema> {
+        self.build_checked().map_err(TantivyError::SchemaError)
+    }
+
+    fn build_checked(self) -> Result<Schema, String> {
+        let copy_to_fields = self
+            .fields
+            .iter()
+            .map(|field_entry| self.resolve_copy_to_fields(field_entry))
+            .collect::<Result<_, String>>()?;
+        let date_facet_fields = self
+            .fields
+            .iter()
+            .map(|field_entry| self.resolve_date_facet_field(field_entry))
+            .collect::<Result<_, String>>()?;
+        Ok(Schema(Arc::new(InnerSchema {
             fields: self.fields,
             fields_map: self.fields_map,
-        }))
+            copy_to_fields,
+            date_facet_fields,
+        })))
+    }
+
+    fn resolve_date_facet_field(&self, field_entry: &FieldEntry) -> Result<Option<Field>, String> {
+        let FieldType::Date(date_options) = field_entry.field_type() else {
+            return Ok(None);
+        };
+        let Some(facet_field_name) = date_options.date_facet() else {
+            return Ok(None);
+        };
+        let facet_field = *self.fields_map.get(facet_field_name).ok_or_else(|| {
+            format!(
+                "The date facets of field {} are derived into the unknown field {facet_field_name}",
+                field_entry.name()
+            )
+        })?;
+        let facet_field_entry = &self.fields[facet_field.field_id() as usize];
+        if !matches!(facet_field_entry.field_type(), FieldType::Facet(_)) {
+            return Err(format!(
+                "The date facets of field {} cannot be derived into {facet_field_name}, which is \
+                 not a facet field",
+                field_entry.name()
+            ));
+        }
+        Ok(Some(facet_field))
+    }
+
+    fn resolve_copy_to_fields(&self, field_entry: &FieldEntry) -> Result<Vec<Field>, String> {
+        let FieldType::Str(text_options) = field_entry.field_type() else {
+            return Ok(Vec::new());
+        };
+        text_options
+            .copy_to()
+            .iter()
+            .map(|target_name| {
+                let target = *self.fields_map.get(target_name).ok_or_else(|| {
+                    format!(
+                        "Field {} is copied to the unknown field {target_name}",
+                        field_entry.name()
+                    )
+                })?;
+                let target_entry = &self.fields[target.field_id() as usize];
+                if target_entry.name() == field_entry.name()
+                    || !matches!(target_entry.field_type(), FieldType::Str(_))
+                    || !target_entry.is_indexed()
+                {
+                    return Err(format!(
+                        "Field {} cannot be copied to {target_name}, which must be another \
+                         indexed text field",
+                        field_entry.name()
+                    ));
+                }
+                Ok(target)
+            })
+            .collect()
     }
 }
 #[derive(Debug)]
 struct InnerSchema {
     fields: Vec<FieldEntry>,
-    fields_map: HashMap<String, Field>, // transient
+    fields_map: HashMap<String, Field>,    // transient
+    copy_to_fields: Vec<Vec<Field>>,       // transient
+    date_facet_fields: Vec<Option<Field>>, // transient
 }
 
 impl PartialEq for InnerSchema {
@@ -301,12 +435,38 @@ impl Schema {
             .map(|(field_id, field_entry)| (Field::from_field_id(field_id as u32), field_entry))
     }
 
+    /// Returns the fields the values of the given field are copied to when a document
+    /// is added.
+    ///
+    /// See [`TextOptions::set_copy_to`].
+    pub fn copy_to_fields(&self, field: Field) -> &[Field] {
+        &self.0.copy_to_fields[field.field_id() as usize]
+    }
+
+    /// Returns the facet field the values of the given date field are derived into, if any.
+    ///
+    /// See [`DateOptions::set_date_facet`].
+    pub fn date_facet_field(&self, field: Field) -> Option<Field> {
+        self.0.date_facet_fields[field.field_id() as usize]
+    }
+
+    /// Returns the list of differences between this schema and `other`,
+    /// `self` being considered as the old schema.
+    ///
+    /// The diff also tells whether an index created with this schema can be
+    /// opened with `other` (see [`SchemaDiff::compatibility`]).
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        SchemaDiff::compute(self, other)
+    }
+
     /// Creates a new builder.
     pub fn builder() -> SchemaBuilder {
         SchemaBuilder::default()
     }
 
     /// Returns the field option associated with a given name.
+    ///
+    /// The name can be an alias of the field (see [`FieldEntry::with_alias`]).
     pub fn get_field(&self, field_name: &str) -> crate::Result<Field> {
         self.0
             .fields_map
@@ -404,7 +564,7 @@ impl<'de> Deserialize<'de> for Schema {
                     schema.add_field(value);
                 }
 
-                Ok(schema.build())
+                schema.try_build().map_err(de::Error::custom)
             }
         }
 
@@ -423,6 +583,7 @@ mod tests {
     use crate::schema::field_type::ValueParsingError;
     use crate::schema::schema::DocParsingError::InvalidJson;
     use crate::schema::*;
+    use crate::TantivyError;
 
     #[test]
     fn test_locate_splitting_dots() {
@@ -818,6 +979,102 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_schema_copy_to_fields() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let title = schema_builder
+            .add_text_field("title", TEXT.set_copy_to("title_exact").set_copy_to("body"));
+        let title_exact = schema_builder.add_text_field("title_exact", STRING);
+        let schema = schema_builder.build();
+        assert_eq!(schema.copy_to_fields(title), &[title_exact, body]);
+        assert!(schema.copy_to_fields(body).is_empty());
+
+        let schema_json = serde_json::to_string(&schema).unwrap();
+        let schema_deser: Schema = serde_json::from_str(&schema_json).unwrap();
+        assert_eq!(schema, schema_deser);
+        assert_eq!(schema_deser.copy_to_fields(title), &[title_exact, body]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Field title is copied to the unknown field missing")]
+    pub fn test_schema_copy_to_unknown_field() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT.set_copy_to("missing"));
+        schema_builder.build();
+    }
+
+    #[test]
+    #[should_panic(expected = "must be another indexed text field")]
+    pub fn test_schema_copy_to_non_text_field() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT.set_copy_to("num"));
+        schema_builder.add_u64_field("num", INDEXED);
+        schema_builder.build();
+    }
+
+    #[test]
+    pub fn test_schema_copy_to_deserialize_errors() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT.set_copy_to("title_exact"));
+        schema_builder.add_text_field("title_exact", STRING);
+        let schema_json = serde_json::to_string(&schema_builder.build()).unwrap();
+        // A schema loaded from `meta.json` must not panic if it is inconsistent.
+        let unknown_field_json =
+            schema_json.replace(r#""copy_to":["title_exact"]"#, r#""copy_to":["missing"]"#);
+        assert_ne!(unknown_field_json, schema_json);
+        let err = serde_json::from_str::<Schema>(&unknown_field_json).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Field title is copied to the unknown field missing"));
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT.set_copy_to("num"));
+        schema_builder.add_u64_field("num", INDEXED);
+        assert_matches!(
+            schema_builder.try_build(),
+            Err(TantivyError::SchemaError(_))
+        );
+    }
+
+    #[test]
+    pub fn test_schema_field_alias() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_field_alias("content", "body");
+        let schema = schema_builder.build();
+        assert_eq!(schema.get_field("content").unwrap(), body);
+        assert_eq!(schema.get_field("body").unwrap(), body);
+        assert_eq!(schema.get_field_name(body), "body");
+        assert_eq!(
+            schema.get_field_entry(body).aliases(),
+            &["content".to_string()]
+        );
+        assert_eq!(schema.find_field("content"), Some((body, "")));
+
+        let schema_json = serde_json::to_string(&schema).unwrap();
+        assert!(schema_json.contains(r#""aliases":["content"]"#));
+        let schema_deser: Schema = serde_json::from_str(&schema_json).unwrap();
+        assert_eq!(schema, schema_deser);
+        assert_eq!(schema_deser.get_field("content").unwrap(), body);
+    }
+
+    #[test]
+    #[should_panic(expected = "Field already exists in schema title")]
+    pub fn test_schema_field_alias_conflict() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_field_alias("title", "body");
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot alias the unknown field missing")]
+    pub fn test_schema_field_alias_unknown_field() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_field_alias("content", "missing");
+    }
+
     #[test]
     pub fn test_schema_add_field() {
         let mut schema_builder = SchemaBuilder::default();
@@ -0,0 +1,308 @@
+use std::fmt;
+
+use super::{Field, FieldEntry, Schema};
+
+/// A change affecting a single field, between two schemas.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldChange {
+    /// The field only exists in the new schema.
+    Added {
+        /// Field in the new schema.
+        field: Field,
+        /// Entry of the field in the new schema.
+        field_entry: FieldEntry,
+    },
+    /// The field only exists in the old schema.
+    Removed {
+        /// Field in the old schema.
+        field: Field,
+        /// Entry of the field in the old schema.
+        field_entry: FieldEntry,
+    },
+    /// The field exists in both schemas, but with a different `Field` id.
+    Moved {
+        /// Name of the field.
+        field_name: String,
+        /// Field in the old schema.
+        before: Field,
+        /// Field in the new schema.
+        after: Field,
+    },
+    /// The field exists in both schemas, but its type or options differ.
+    Changed {
+        /// Entry of the field in the old schema.
+        before: FieldEntry,
+        /// Entry of the field in the new schema.
+        after: FieldEntry,
+    },
+}
+
+impl FieldChange {
+    /// Returns true if the segments indexed with the old schema
+    /// can be read with the new schema.
+    ///
+    /// Only adding a field, changing whether a field is stored and changing
+    /// the aliases of a field are compatible changes.
+    pub fn is_compatible(&self) -> bool {
+        match self {
+            FieldChange::Added { .. } => true,
+            FieldChange::Removed { .. } | FieldChange::Moved { .. } => false,
+            FieldChange::Changed { before, after } => only_stored_or_aliases_differ(before, after),
+        }
+    }
+
+    /// Returns true if the change does not affect the data of the existing
+    /// segments, nor how new documents are indexed, i.e. the field was added
+    /// or only its aliases changed.
+    pub fn is_additive(&self) -> bool {
+        match self {
+            FieldChange::Added { .. } => true,
+            FieldChange::Removed { .. } | FieldChange::Moved { .. } => false,
+            FieldChange::Changed { before, after } => before.field_type() == after.field_type(),
+        }
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldChange::Added { field_entry, .. } => {
+                write!(f, "field `{}` was added", field_entry.name())
+            }
+            FieldChange::Removed { field_entry, .. } => {
+                write!(f, "field `{}` was removed", field_entry.name())
+            }
+            FieldChange::Moved {
+                field_name,
+                before,
+                after,
+            } => write!(
+                f,
+                "field `{field_name}` was moved from position {} to {}",
+                before.field_id(),
+                after.field_id()
+            ),
+            FieldChange::Changed { before, after } => {
+                if before.field_type().value_type() != after.field_type().value_type() {
+                    write!(
+                        f,
+                        "field `{}` changed type from {:?} to {:?}",
+                        before.name(),
+                        before.field_type().value_type(),
+                        after.field_type().value_type()
+                    )
+                } else if before.field_type() == after.field_type() {
+                    write!(f, "field `{}` changed aliases", before.name())
+                } else {
+                    write!(f, "field `{}` changed options", before.name())
+                }
+            }
+        }
+    }
+}
+
+fn only_stored_or_aliases_differ(before: &FieldEntry, after: &FieldEntry) -> bool {
+    let without_stored = |field_entry: &FieldEntry| {
+        let mut field_entry_json = serde_json::to_value(field_entry).ok()?;
+        field_entry_json.as_object_mut()?.remove("aliases");
+        field_entry_json
+            .get_mut("options")?
+            .as_object_mut()?
+            .remove("stored");
+        Some(field_entry_json)
+    };
+    match (without_stored(before), without_stored(after)) {
+        (Some(before_json), Some(after_json)) => before_json == after_json,
+        _ => false,
+    }
+}
+
+/// Compatibility of an existing index with a new schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaCompatibility {
+    /// The schemas are identical.
+    Identical,
+    /// The schemas differ, but the segments indexed with the old schema
+    /// can be read with the new schema.
+    Compatible,
+    /// The documents need to be reindexed.
+    ReindexRequired,
+}
+
+/// Structured list of the differences between two schemas.
+///
+/// See [`Schema::diff`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaDiff {
+    changes: Vec<FieldChange>,
+}
+
+impl SchemaDiff {
+    pub(crate) fn compute(old_schema: &Schema, new_schema: &Schema) -> SchemaDiff {
+        let mut changes = Vec::new();
+        for (field, field_entry) in old_schema.fields() {
+            let Ok(new_field) = new_schema.get_field(field_entry.name()) else {
+                changes.push(FieldChange::Removed {
+                    field,
+                    field_entry: field_entry.clone(),
+                });
+                continue;
+            };
+            if new_field != field {
+                changes.push(FieldChange::Moved {
+                    field_name: field_entry.name().to_string(),
+                    before: field,
+                    after: new_field,
+                });
+            }
+            let new_field_entry = new_schema.get_field_entry(new_field);
+            if new_field_entry != field_entry {
+                changes.push(FieldChange::Changed {
+                    before: field_entry.clone(),
+                    after: new_field_entry.clone(),
+                });
+            }
+        }
+        for (field, field_entry) in new_schema.fields() {
+            if old_schema.get_field(field_entry.name()).is_err() {
+                changes.push(FieldChange::Added {
+                    field,
+                    field_entry: field_entry.clone(),
+                });
+            }
+        }
+        SchemaDiff { changes }
+    }
+
+    /// Returns the list of changes.
+    pub fn changes(&self) -> &[FieldChange] {
+        &self.changes
+    }
+
+    /// Returns true if the schemas are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Classifies the changes.
+    pub fn compatibility(&self) -> SchemaCompatibility {
+        if self.changes.is_empty() {
+            SchemaCompatibility::Identical
+        } else if self.changes.iter().all(FieldChange::is_compatible) {
+            SchemaCompatibility::Compatible
+        } else {
+            SchemaCompatibility::ReindexRequired
+        }
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "no changes");
+        }
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldChange, SchemaCompatibility};
+    use crate::schema::{Field, Schema, FAST, INDEXED, STORED, STRING, TEXT};
+
+    #[test]
+    fn test_schema_diff_identical() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        let schema = schema_builder.build();
+        let diff = schema.diff(&schema);
+        assert!(diff.is_empty());
+        assert_eq!(diff.compatibility(), SchemaCompatibility::Identical);
+    }
+
+    #[test]
+    fn test_schema_diff_compatible() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        let old_schema = schema_builder.build();
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_u64_field("year", FAST);
+        let new_schema = schema_builder.build();
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(diff.changes().len(), 2);
+        assert!(matches!(diff.changes()[0], FieldChange::Changed { .. }));
+        assert!(matches!(
+            diff.changes()[1],
+            FieldChange::Added { field, .. } if field == Field::from_field_id(1)
+        ));
+        assert_eq!(diff.compatibility(), SchemaCompatibility::Compatible);
+        assert_eq!(
+            diff.to_string(),
+            "field `title` changed options, field `year` was added"
+        );
+    }
+
+    #[test]
+    fn test_schema_diff_aliases_are_additive() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        let old_schema = schema_builder.build();
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_field_alias("content", "body");
+        let new_schema = schema_builder.build();
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(diff.changes().len(), 1);
+        assert!(diff.changes()[0].is_additive());
+        assert_eq!(diff.compatibility(), SchemaCompatibility::Compatible);
+        assert_eq!(diff.to_string(), "field `body` changed aliases");
+    }
+
+    #[test]
+    fn test_schema_diff_reindex_required() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("year", INDEXED);
+        schema_builder.add_text_field("isbn", STRING);
+        let old_schema = schema_builder.build();
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_i64_field("year", INDEXED);
+        schema_builder.add_text_field("title", TEXT);
+        let new_schema = schema_builder.build();
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(diff.compatibility(), SchemaCompatibility::ReindexRequired);
+        assert_eq!(
+            diff.to_string(),
+            "field `title` was moved from position 0 to 1, field `year` was moved from position 1 \
+             to 0, field `year` changed type from U64 to I64, field `isbn` was removed"
+        );
+    }
+
+    #[test]
+    fn test_schema_diff_indexing_change_requires_reindex() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        let old_schema = schema_builder.build();
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", STRING);
+        let new_schema = schema_builder.build();
+
+        let diff = old_schema.diff(&new_schema);
+        assert_eq!(diff.compatibility(), SchemaCompatibility::ReindexRequired);
+        assert_eq!(diff.to_string(), "field `title` changed options");
+    }
+}
@@ -123,6 +123,15 @@ impl Term {
         Term::from_fast_value(field, &val.truncate(DATE_TIME_PRECISION_INDEXED))
     }
 
+    /// Builds a term given a field, and a `DateTime` value, without truncating the value.
+    ///
+    /// This is used for the bounds of range queries, which are only truncated once
+    /// it is known whether they are checked against the indexed terms or against the
+    /// values of the fast field.
+    pub(crate) fn from_field_date_full_precision(field: Field, val: DateTime) -> Term {
+        Term::from_fast_value(field, &val)
+    }
+
     /// Creates a `Term` given a facet.
     pub fn from_facet(field: Field, facet: &Facet) -> Term {
         let facet_encoded_str = facet.encoded_str();
@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::num::NonZeroU32;
 use std::ops::BitOr;
 
 use serde::{Deserialize, Serialize};
@@ -21,6 +22,9 @@ pub struct TextOptions {
     #[serde(skip_serializing_if = "is_false")]
     /// coerce values into string if they are not of type string
     coerce: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    copy_to: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -158,6 +162,27 @@ impl TextOptions {
         self.indexing = Some(indexing);
         self
     }
+
+    /// Copies the values of this field into another text field when a document is added,
+    /// so that the same text can be indexed with different analyzers, e.g. `title` with the
+    /// default tokenizer, and `title_exact` with the raw tokenizer.
+    ///
+    /// The values are only indexed in the target field: they are not stored and are not
+    /// added to its fast field. Values copied into the target field are not copied any
+    /// further.
+    ///
+    /// The target field must be an indexed text field of the schema, otherwise
+    /// [`SchemaBuilder::build`](crate::schema::SchemaBuilder::build) panics.
+    #[must_use]
+    pub fn set_copy_to(mut self, field_name: &str) -> TextOptions {
+        self.copy_to.push(field_name.to_string());
+        self
+    }
+
+    /// Returns the names of the fields the values of this field are copied to.
+    pub fn copy_to(&self) -> &[String] {
+        &self.copy_to
+    }
 }
 
 #[derive(Clone, PartialEq, Debug, Eq, Serialize, Deserialize)]
@@ -194,6 +219,13 @@ impl TokenizerName {
 /// - The name of the `Tokenizer` that should be used to process the field.
 /// - Flag indicating, if fieldnorms should be stored (See [fieldnorm](crate::fieldnorm)). Defaults
 ///   to `true`.
+/// - Optionally, the block length of the term dictionary of the field.
+/// - Optionally, the name of the `Tokenizer` that should be used to process the queries on the
+///   field, if it differs from the one used at indexing time.
+/// - Optionally, the BM25 parameters used to score the field (See [`Bm25Options`]).
+/// - Optionally, limits on the positions recorded for the terms of the field.
+/// - Optionally, the position gap inserted between the values of a multivalued field.
+/// - Optionally, the number of top impact postings recorded for each frequent term.
 #[derive(Clone, PartialEq, Debug, Eq, Serialize, Deserialize)]
 pub struct TextFieldIndexing {
     #[serde(default)]
@@ -202,8 +234,26 @@ pub struct TextFieldIndexing {
     fieldnorms: bool,
     #[serde(default)]
     tokenizer: TokenizerName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    term_dict_block_len: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    search_tokenizer: Option<TokenizerName>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bm25: Option<Bm25Options>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_positions_per_term: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    positions_max_doc_freq: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position_gap: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    top_impacts_per_term: Option<NonZeroU32>,
 }
 
+/// Position gap inserted between the values of a multivalued text field, unless
+/// another one is set with [`TextFieldIndexing::set_position_gap`].
+pub(crate) const DEFAULT_POSITION_GAP: u32 = 1;
+
 pub(crate) fn default_fieldnorms() -> bool {
     true
 }
@@ -214,6 +264,13 @@ impl Default for TextFieldIndexing {
             tokenizer: TokenizerName::default(),
             record: IndexRecordOption::default(),
             fieldnorms: default_fieldnorms(),
+            term_dict_block_len: None,
+            search_tokenizer: None,
+            bm25: None,
+            max_positions_per_term: None,
+            positions_max_doc_freq: None,
+            position_gap: None,
+            top_impacts_per_term: None,
         }
     }
 }
@@ -231,7 +288,33 @@ impl TextFieldIndexing {
         self.tokenizer.name()
     }
 
-    /// Sets fieldnorms
+    /// Sets the tokenizer to be used to analyze the queries on this field,
+    /// if it differs from the tokenizer used at indexing time.
+    ///
+    /// For instance, synonyms can be expanded at query time only, by using a
+    /// search tokenizer chaining a [`SynonymFilter`](crate::tokenizer::SynonymFilter)
+    /// to the indexing tokenizer.
+    #[must_use]
+    pub fn set_search_tokenizer(mut self, tokenizer_name: &str) -> TextFieldIndexing {
+        self.search_tokenizer = Some(TokenizerName::from_name(tokenizer_name));
+        self
+    }
+
+    /// Returns the tokenizer that will be used to analyze the queries on this field.
+    ///
+    /// Unless a search tokenizer was set, this is the indexing [tokenizer](Self::tokenizer).
+    pub fn search_tokenizer(&self) -> &str {
+        self.search_tokenizer
+            .as_ref()
+            .unwrap_or(&self.tokenizer)
+            .name()
+    }
+
+    /// Sets whether [fieldnorms](crate::fieldnorm) should be stored for this field.
+    ///
+    /// Disabling them saves one byte per document for fields where the length is
+    /// meaningless, such as tags or ids. The length of such a field is then considered
+    /// the same for all documents, so that it does not impact their score.
     #[must_use]
     pub fn set_fieldnorms(mut self, fieldnorms: bool) -> TextFieldIndexing {
         self.fieldnorms = fieldnorms;
@@ -258,6 +341,207 @@ impl TextFieldIndexing {
     pub fn index_option(&self) -> IndexRecordOption {
         self.record
     }
+
+    /// Sets the block length of the term dictionary of this field.
+    ///
+    /// With the default FST term dictionary, this is the number of terms
+    /// per block of term infos (256 by default). With the SSTable term
+    /// dictionary, this is the approximate size of a block in bytes (4000 by default).
+    ///
+    /// Smaller blocks speed up term lookups at the cost of a larger term dictionary.
+    /// This can be worth it for fields with many unique, high entropy terms,
+    /// such as UUIDs or URLs. [`TermDictionary::stats`](crate::termdict::TermDictionary::stats)
+    /// reports how much the terms of a field share with each other.
+    ///
+    /// The block length is the only tunable parameter: the prefix sharing of the terms and
+    /// the index of the blocks are part of the format of the term dictionary.
+    #[must_use]
+    pub fn set_term_dict_block_len(mut self, block_len: usize) -> TextFieldIndexing {
+        self.term_dict_block_len = Some(block_len);
+        self
+    }
+
+    /// Returns the block length of the term dictionary of this field, if it was set.
+    pub fn term_dict_block_len(&self) -> Option<usize> {
+        self.term_dict_block_len
+    }
+
+    /// Sets the BM25 parameters used to score the terms of this field.
+    ///
+    /// The block-wand metadata of the postings depends on these parameters, so that
+    /// they are taken into account by the segments written after the change only.
+    #[must_use]
+    pub fn set_bm25_options(mut self, bm25_options: Bm25Options) -> TextFieldIndexing {
+        self.bm25 = Some(bm25_options);
+        self
+    }
+
+    /// Returns the BM25 parameters used to score the terms of this field.
+    ///
+    /// Unless they were set, these are the [default](Bm25Options::default) parameters.
+    pub fn bm25_options(&self) -> Bm25Options {
+        self.bm25.unwrap_or_default()
+    }
+
+    /// Caps the number of positions recorded for a term in a document.
+    ///
+    /// Only the first `max_positions_per_term` occurrences of a term in a document are
+    /// recorded, and its term frequency is capped accordingly. This bounds the size of the
+    /// positions of huge documents, at the cost of phrase queries not matching the
+    /// occurrences beyond the cap.
+    ///
+    /// # Panics
+    /// Panics if `max_positions_per_term` is 0.
+    #[must_use]
+    pub fn set_max_positions_per_term(mut self, max_positions_per_term: u32) -> TextFieldIndexing {
+        assert!(
+            max_positions_per_term > 0,
+            "The maximum number of positions per term must be strictly greater than 0."
+        );
+        self.max_positions_per_term = Some(max_positions_per_term);
+        self
+    }
+
+    /// Returns the maximum number of positions recorded for a term in a document, if it was
+    /// set.
+    pub fn max_positions_per_term(&self) -> Option<u32> {
+        self.max_positions_per_term
+    }
+
+    /// Skips the positions of the terms appearing in more than `max_doc_freq` documents
+    /// of a segment.
+    ///
+    /// Such terms, typically stop words, take most of the space of the positions.
+    /// Phrase queries still match on the positions of their other terms, and only
+    /// require the documents to contain the terms without positions.
+    #[must_use]
+    pub fn set_positions_max_doc_freq(mut self, max_doc_freq: u32) -> TextFieldIndexing {
+        self.positions_max_doc_freq = Some(max_doc_freq);
+        self
+    }
+
+    /// Returns the document frequency above which the positions of a term are skipped, if it
+    /// was set.
+    pub fn positions_max_doc_freq(&self) -> Option<u32> {
+        self.positions_max_doc_freq
+    }
+
+    /// Sets the number of positions inserted between the values of a document for this field.
+    ///
+    /// When a document has several values for the field, the first token of a value is
+    /// placed at the position of the last token of the previous value, plus 1, plus
+    /// `position_gap`. A phrase query only matches across value boundaries if its slop is at
+    /// least the gap: on a document with the authors `alice jones` and `smith bob`, the
+    /// default gap of 1 lets `"jones smith"` match with a slop of 1, but not with a slop of 0.
+    ///
+    /// The gap only applies to the documents indexed after the change.
+    #[must_use]
+    pub fn set_position_gap(mut self, position_gap: u32) -> TextFieldIndexing {
+        self.position_gap = Some(position_gap);
+        self
+    }
+
+    /// Returns the number of positions inserted between the values of a document.
+    ///
+    /// Unless it was set, this is 1, which is enough for exact phrase queries not to
+    /// match across values.
+    pub fn position_gap(&self) -> u32 {
+        self.position_gap.unwrap_or(DEFAULT_POSITION_GAP)
+    }
+
+    /// Records, for each term appearing in more than `num_docs` documents of a segment, the
+    /// `num_docs` documents contributing the most to its BM25 score.
+    ///
+    /// These top impacts lists are stored next to the postings of the field, and are built
+    /// again when segments are merged. A [`TermQuery`](crate::query::TermQuery) set with
+    /// [`set_top_impacts_only`](crate::query::TermQuery::set_top_impacts_only) only reads
+    /// them, trading recall for latency: its top `num_docs` hits per segment are exact, but
+    /// the documents beyond them are missed.
+    ///
+    /// The lists are only recorded if the field has term frequencies and fieldnorms.
+    ///
+    /// # Panics
+    /// Panics if `num_docs` is 0.
+    #[must_use]
+    pub fn set_top_impacts_per_term(mut self, num_docs: u32) -> TextFieldIndexing {
+        let num_docs = NonZeroU32::new(num_docs)
+            .expect("The number of top impacts per term must be strictly greater than 0.");
+        self.top_impacts_per_term = Some(num_docs);
+        self
+    }
+
+    /// Returns the number of top impact postings recorded per term, if it was set.
+    pub fn top_impacts_per_term(&self) -> Option<u32> {
+        self.top_impacts_per_term.map(NonZeroU32::get)
+    }
+}
+
+/// Parameters of the BM25 similarity used to score the terms of a text field.
+///
+/// - `k1` controls the term frequency saturation: the higher it is, the more additional occurrences
+///   of a term in a document increase its score. With `k1 = 0`, the score does not depend on the
+///   term frequency at all.
+/// - `b` controls the length normalization: with `b = 0`, the length of the field is ignored, with
+///   `b = 1`, the term frequency is fully normalized by the length of the field.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Bm25OptionsDeser")]
+pub struct Bm25Options {
+    k1: f32,
+    b: f32,
+}
+
+// The parameters are checked to be finite, both by `Bm25Options::new` and when deserializing.
+impl Eq for Bm25Options {}
+
+/// Intermediary used to validate the parameters of deserialized [`Bm25Options`].
+#[derive(Deserialize)]
+struct Bm25OptionsDeser {
+    k1: f32,
+    b: f32,
+}
+
+impl TryFrom<Bm25OptionsDeser> for Bm25Options {
+    type Error = String;
+
+    fn try_from(deser: Bm25OptionsDeser) -> Result<Self, Self::Error> {
+        Bm25Options::new_checked(deser.k1, deser.b)
+    }
+}
+
+impl Default for Bm25Options {
+    fn default() -> Bm25Options {
+        Bm25Options { k1: 1.2, b: 0.75 }
+    }
+}
+
+impl Bm25Options {
+    /// Creates BM25 parameters.
+    ///
+    /// # Panics
+    /// Panics if `k1` is negative or not finite, or if `b` is not within `[0, 1]`.
+    pub fn new(k1: f32, b: f32) -> Bm25Options {
+        Bm25Options::new_checked(k1, b).unwrap_or_else(|msg| panic!("{msg}"))
+    }
+
+    fn new_checked(k1: f32, b: f32) -> Result<Bm25Options, String> {
+        if !(k1.is_finite() && k1 >= 0.0) {
+            return Err(format!("k1 must be a positive finite number, got {k1}."));
+        }
+        if !(0.0..=1.0).contains(&b) {
+            return Err(format!("b must be within [0, 1], got {b}."));
+        }
+        Ok(Bm25Options { k1, b })
+    }
+
+    /// Returns the term frequency saturation parameter.
+    pub fn k1(&self) -> f32 {
+        self.k1
+    }
+
+    /// Returns the length normalization parameter.
+    pub fn b(&self) -> f32 {
+        self.b
+    }
 }
 
 /// The field will be untokenized and indexed.
@@ -266,10 +550,18 @@ pub const STRING: TextOptions = TextOptions {
         tokenizer: TokenizerName::from_static(NO_TOKENIZER_NAME),
         fieldnorms: true,
         record: IndexRecordOption::Basic,
+        term_dict_block_len: None,
+        search_tokenizer: None,
+        bm25: None,
+        max_positions_per_term: None,
+        positions_max_doc_freq: None,
+        position_gap: None,
+        top_impacts_per_term: None,
     }),
     stored: false,
     fast: FastFieldTextOptions::IsEnabled(false),
     coerce: false,
+    copy_to: Vec::new(),
 };
 
 /// The field will be tokenized and indexed.
@@ -278,10 +570,18 @@ pub const TEXT: TextOptions = TextOptions {
         tokenizer: TokenizerName::from_static(DEFAULT_TOKENIZER_NAME),
         fieldnorms: true,
         record: IndexRecordOption::WithFreqsAndPositions,
+        term_dict_block_len: None,
+        search_tokenizer: None,
+        bm25: None,
+        max_positions_per_term: None,
+        positions_max_doc_freq: None,
+        position_gap: None,
+        top_impacts_per_term: None,
     }),
     stored: false,
     coerce: false,
     fast: FastFieldTextOptions::IsEnabled(false),
+    copy_to: Vec::new(),
 };
 
 impl<T: Into<TextOptions>> BitOr<T> for TextOptions {
@@ -294,6 +594,7 @@ impl<T: Into<TextOptions>> BitOr<T> for TextOptions {
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
             coerce: self.coerce | other.coerce,
+            copy_to: self.copy_to.into_iter().chain(other.copy_to).collect(),
         }
     }
 }
@@ -311,6 +612,7 @@ impl From<StoredFlag> for TextOptions {
             stored: true,
             fast: FastFieldTextOptions::default(),
             coerce: false,
+            copy_to: Vec::new(),
         }
     }
 }
@@ -322,6 +624,7 @@ impl From<CoerceFlag> for TextOptions {
             stored: false,
             fast: FastFieldTextOptions::default(),
             coerce: true,
+            copy_to: Vec::new(),
         }
     }
 }
@@ -333,6 +636,7 @@ impl From<FastFlag> for TextOptions {
             stored: false,
             fast: FastFieldTextOptions::IsEnabled(true),
             coerce: false,
+            copy_to: Vec::new(),
         }
     }
 }
@@ -374,6 +678,60 @@ mod tests {
         assert!(IndexRecordOption::WithFreqs > IndexRecordOption::Basic);
     }
 
+    #[test]
+    fn test_term_dict_block_len() -> crate::Result<()> {
+        let indexing = TextFieldIndexing::default()
+            .set_tokenizer("raw")
+            .set_term_dict_block_len(2);
+        let text_options = TextOptions::default().set_indexing_options(indexing);
+        let json = serde_json::to_string(&text_options).unwrap();
+        assert!(json.contains(r#""term_dict_block_len":2"#));
+        let text_options_deser: TextOptions = serde_json::from_str(&json).unwrap();
+        assert_eq!(text_options_deser, text_options);
+
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", text_options);
+        let index = crate::Index::create_in_ram(schema_builder.build());
+        let mut index_writer: crate::IndexWriter = index.writer_for_tests()?;
+        for i in 0..10 {
+            index_writer.add_document(crate::doc!(id => format!("id-{i}")))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let inverted_index = searcher.segment_reader(0).inverted_index(id)?;
+        assert_eq!(inverted_index.terms().num_terms(), 10);
+        for i in 0..10 {
+            let term = Term::from_field_text(id, &format!("id-{i}"));
+            assert_eq!(inverted_index.doc_freq(&term)?, 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_tokenizer() {
+        let indexing = TextFieldIndexing::default().set_tokenizer("raw");
+        assert_eq!(indexing.search_tokenizer(), "raw");
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert!(!json.contains("search_tokenizer"));
+
+        let indexing = indexing.set_search_tokenizer("raw_lowercase");
+        assert_eq!(indexing.tokenizer(), "raw");
+        assert_eq!(indexing.search_tokenizer(), "raw_lowercase");
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert!(json.contains(r#""search_tokenizer":"raw_lowercase""#));
+        let indexing_deser: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert_eq!(indexing_deser, indexing);
+    }
+
+    #[test]
+    fn test_top_impacts_per_term_deserialize_zero() {
+        let json = r#"{"top_impacts_per_term": 2}"#;
+        let indexing: TextFieldIndexing = serde_json::from_str(json).unwrap();
+        assert_eq!(indexing.top_impacts_per_term(), Some(2));
+        let json = r#"{"top_impacts_per_term": 0}"#;
+        assert!(serde_json::from_str::<TextFieldIndexing>(json).is_err());
+    }
+
     #[test]
     fn serde_default_test() {
         let json = r#"
@@ -433,4 +791,36 @@ mod tests {
             serde_json::from_str(&serde_json::to_string(&options).unwrap()).unwrap();
         assert_eq!(options.fast, FastFieldTextOptions::IsEnabled(false));
     }
+
+    #[test]
+    fn test_bm25_options_serde() {
+        let indexing = TextFieldIndexing::default();
+        assert_eq!(indexing.bm25_options(), Bm25Options::default());
+        assert!(!serde_json::to_string(&indexing).unwrap().contains("bm25"));
+
+        let indexing = indexing.set_bm25_options(Bm25Options::new(2.0, 0.0));
+        let json = serde_json::to_string(&indexing).unwrap();
+        assert!(json.contains(r#""bm25":{"k1":2.0,"b":0.0}"#));
+        let indexing: TextFieldIndexing = serde_json::from_str(&json).unwrap();
+        assert_eq!(indexing.bm25_options().k1(), 2.0);
+        assert_eq!(indexing.bm25_options().b(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "b must be within [0, 1], got 1.5.")]
+    fn test_bm25_options_invalid_b() {
+        let _ = Bm25Options::new(1.2, 1.5);
+    }
+
+    #[test]
+    fn test_bm25_options_deserialize_invalid() {
+        let err = serde_json::from_str::<Bm25Options>(r#"{"k1":-1.0,"b":0.5}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("k1 must be a positive finite number, got -1."));
+        let err = serde_json::from_str::<Bm25Options>(r#"{"k1":1.2,"b":2.0}"#).unwrap_err();
+        assert!(err.to_string().contains("b must be within [0, 1], got 2."));
+        let options: Bm25Options = serde_json::from_str(r#"{"k1":0.0,"b":1.0}"#).unwrap();
+        assert_eq!(options, Bm25Options::new(0.0, 1.0));
+    }
 }
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use columnar::{Column, DynamicColumn, HasAssociatedColumnType};
+
+use crate::schema::{
+    BytesOptions, DateOptions, Field, NumericOptions, SchemaBuilder, TantivyDocument, Term,
+    TextOptions,
+};
+use crate::{DateTime, SegmentReader, TantivyError};
+
+/// Rust types that can be used as the value type of a [`TypedField`].
+pub trait TypedFieldValue: Sized {
+    /// Options of a field holding values of this type.
+    type Options;
+
+    /// Adds a field holding values of this type to the schema builder.
+    fn add_field(
+        schema_builder: &mut SchemaBuilder,
+        field_name: &str,
+        field_options: Self::Options,
+    ) -> Field;
+
+    /// Builds a term for the given field and value.
+    fn term(field: Field, value: &Self) -> Term;
+
+    /// Adds the value to the document.
+    fn add_to_document(doc: &mut TantivyDocument, field: Field, value: &Self);
+}
+
+macro_rules! impl_typed_field_value {
+    (
+        $type:ty,
+        $options:ty,
+        $add_field:ident,
+        $term:ident,
+        $add_value:ident,
+        $value:ident => $arg:expr
+    ) => {
+        impl TypedFieldValue for $type {
+            type Options = $options;
+
+            fn add_field(
+                schema_builder: &mut SchemaBuilder,
+                field_name: &str,
+                field_options: $options,
+            ) -> Field {
+                schema_builder.$add_field(field_name, field_options)
+            }
+
+            fn term(field: Field, $value: &Self) -> Term {
+                Term::$term(field, $arg)
+            }
+
+            fn add_to_document(doc: &mut TantivyDocument, field: Field, $value: &Self) {
+                doc.$add_value(field, $arg);
+            }
+        }
+    };
+}
+
+impl_typed_field_value!(u64, NumericOptions, add_u64_field, from_field_u64, add_u64, value => *value);
+impl_typed_field_value!(i64, NumericOptions, add_i64_field, from_field_i64, add_i64, value => *value);
+impl_typed_field_value!(f64, NumericOptions, add_f64_field, from_field_f64, add_f64, value => *value);
+impl_typed_field_value!(bool, NumericOptions, add_bool_field, from_field_bool, add_bool, value => *value);
+impl_typed_field_value!(DateTime, DateOptions, add_date_field, from_field_date, add_date, value => *value);
+impl_typed_field_value!(String, TextOptions, add_text_field, from_field_text, add_text, value => value);
+impl_typed_field_value!(Vec<u8>, BytesOptions, add_bytes_field, from_field_bytes, add_bytes, value => value);
+
+/// A [`Field`] handle that carries the type of the values of the field.
+///
+/// Typed fields are returned by [`SchemaBuilder::add_typed_field`].
+/// Building a term, adding a value to a document or opening a fast field
+/// with a value of the wrong type then fails at compile time.
+///
+/// ```
+/// use tantivy::schema::{Schema, TypedField, FAST, INDEXED};
+/// use tantivy::TantivyDocument;
+///
+/// let mut schema_builder = Schema::builder();
+/// let year: TypedField<u64> = schema_builder.add_typed_field("year", FAST | INDEXED);
+/// let mut doc = TantivyDocument::default();
+/// doc.add_typed(year, &1937);
+/// let term = year.term(&1937);
+/// assert_eq!(term.field(), year.field());
+/// ```
+pub struct TypedField<T> {
+    field: Field,
+    _value_type: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedField<T> {
+    pub(crate) fn new(field: Field) -> TypedField<T> {
+        TypedField {
+            field,
+            _value_type: PhantomData,
+        }
+    }
+
+    /// Returns the untyped field handle.
+    pub fn field(self) -> Field {
+        self.field
+    }
+}
+
+impl<T: TypedFieldValue> TypedField<T> {
+    /// Builds a term for the given value.
+    pub fn term(self, value: &T) -> Term {
+        T::term(self.field, value)
+    }
+}
+
+impl<T> TypedField<T>
+where
+    T: HasAssociatedColumnType,
+    DynamicColumn: Into<Option<Column<T>>>,
+{
+    /// Opens the fast field reader of the field in the given segment.
+    ///
+    /// Returns an error if the field is not configured as a fast field.
+    pub fn fast_field_reader(self, segment_reader: &SegmentReader) -> crate::Result<Column<T>> {
+        let field_name = segment_reader.schema().get_field_name(self.field);
+        segment_reader
+            .fast_fields()
+            .column_opt(field_name)?
+            .ok_or_else(|| {
+                TantivyError::SchemaError(format!(
+                    "Field `{field_name}` is missing or is not configured as a fast field."
+                ))
+            })
+    }
+}
+
+impl<T> From<TypedField<T>> for Field {
+    fn from(typed_field: TypedField<T>) -> Field {
+        typed_field.field
+    }
+}
+
+// Implemented by hand, in order to not require any bound on `T`.
+impl<T> Clone for TypedField<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedField<T> {}
+
+impl<T> PartialEq for TypedField<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.field == other.field
+    }
+}
+
+impl<T> Eq for TypedField<T> {}
+
+impl<T> Hash for TypedField<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.field.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for TypedField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedField")
+            .field("field", &self.field)
+            .field("value_type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::{Schema, TypedField, FAST, INDEXED, STORED, TEXT};
+    use crate::{DocSet, Index, IndexWriter, TantivyDocument, Term};
+
+    #[test]
+    fn test_typed_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title: TypedField<String> = schema_builder.add_typed_field("title", TEXT | STORED);
+        let year: TypedField<i64> = schema_builder.add_typed_field("year", FAST | INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        assert_eq!(
+            title.term(&"mice".to_string()),
+            Term::from_field_text(title.field(), "mice")
+        );
+
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let mut doc = TantivyDocument::default();
+        doc.add_typed(title, &"Of Mice and Men".to_string());
+        doc.add_typed(year, &1937);
+        index_writer.add_document(doc)?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_reader(0);
+        let year_column = year.fast_field_reader(segment_reader)?;
+        assert_eq!(year_column.first(0), Some(1937));
+        let mut postings = segment_reader
+            .inverted_index(year.field())?
+            .read_postings(&year.term(&1937), crate::schema::IndexRecordOption::Basic)?
+            .unwrap();
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.advance(), crate::TERMINATED);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_field_not_fast() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let year: TypedField<u64> = schema_builder.add_typed_field("year", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let mut doc = TantivyDocument::default();
+        doc.add_typed(year, &1937);
+        index_writer.add_document(doc)?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert!(year.fast_field_reader(searcher.segment_reader(0)).is_err());
+        Ok(())
+    }
+}
@@ -71,11 +71,11 @@ impl CheckpointBlock {
             return;
         }
         VInt(self.checkpoints[0].doc_range.start as u64).serialize_into_vec(buffer);
-        VInt(self.checkpoints[0].byte_range.start as u64).serialize_into_vec(buffer);
+        VInt(self.checkpoints[0].byte_range.start).serialize_into_vec(buffer);
         for checkpoint in &self.checkpoints {
             let delta_doc = checkpoint.doc_range.end - checkpoint.doc_range.start;
             VInt(delta_doc as u64).serialize_into_vec(buffer);
-            VInt((checkpoint.byte_range.end - checkpoint.byte_range.start) as u64)
+            VInt(checkpoint.byte_range.end - checkpoint.byte_range.start)
                 .serialize_into_vec(buffer);
         }
     }
@@ -90,10 +90,12 @@ impl CheckpointBlock {
             return Ok(());
         }
         let mut doc = read_u32_vint(data);
-        let mut start_offset = VInt::deserialize_u64(data)? as usize;
+        let mut start_offset = VInt::deserialize_u64(data)?;
         for _ in 0..len {
             let num_docs = read_u32_vint(data);
-            let block_num_bytes = read_u32_vint(data) as usize;
+            // Block sizes used to be read as `u32`. Their encoding is the same as
+            // long as they fit in a `u32`, so that existing indices remain readable.
+            let block_num_bytes = VInt::deserialize_u64(data)?;
             self.checkpoints.push(Checkpoint {
                 doc_range: doc..doc + num_docs,
                 byte_range: start_offset..start_offset + block_num_bytes,
@@ -156,9 +158,53 @@ mod tests {
         test_aux_ser_deser(&checkpoints)
     }
 
+    #[test]
+    fn test_block_serialize_byte_range_exceeding_u32() -> io::Result<()> {
+        let checkpoints = vec![
+            Checkpoint {
+                doc_range: 10..12,
+                byte_range: 100..5_000_000_100,
+            },
+            Checkpoint {
+                doc_range: 12..13,
+                byte_range: 5_000_000_100..5_000_000_200,
+            },
+        ];
+        test_aux_ser_deser(&checkpoints)
+    }
+
+    #[test]
+    fn test_block_deserialize_u32_block_sizes() -> io::Result<()> {
+        // Block sizes written as `u32` vints, as they used to be.
+        let mut buffer = Vec::new();
+        common::write_u32_vint(2, &mut buffer)?;
+        common::write_u32_vint(10, &mut buffer)?;
+        common::write_u32_vint(100, &mut buffer)?;
+        for (num_docs, num_bytes) in [(2, 300), (1, u32::MAX)] {
+            common::write_u32_vint(num_docs, &mut buffer)?;
+            common::write_u32_vint(num_bytes, &mut buffer)?;
+        }
+        let mut block = CheckpointBlock::default();
+        block.deserialize(&mut &buffer[..])?;
+        assert_eq!(
+            &block.checkpoints[..],
+            &[
+                Checkpoint {
+                    doc_range: 10..12,
+                    byte_range: 100..400,
+                },
+                Checkpoint {
+                    doc_range: 12..13,
+                    byte_range: 400..400 + u32::MAX as u64,
+                },
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_block_serialize() -> io::Result<()> {
-        let offsets: Vec<usize> = (0..11).map(|i| i * i * i).collect();
+        let offsets: Vec<u64> = (0..11).map(|i| i * i * i).collect();
         let mut checkpoints = vec![];
         let mut start_doc = 0;
         for i in 0..10 {
@@ -17,10 +17,13 @@ use crate::DocId;
 /// All of the intervals here defined are semi-open.
 /// The checkpoint describes that the block within the `byte_range`
 /// and spans over the `doc_range`.
+///
+/// Byte offsets are `u64`, so that the stored data of a single segment
+/// can exceed 4GB. Reading such a segment still requires a 64-bit platform.
 #[derive(Clone, Eq, PartialEq, Default)]
 pub struct Checkpoint {
     pub doc_range: Range<DocId>,
-    pub byte_range: Range<usize>,
+    pub byte_range: Range<u64>,
 }
 
 impl Checkpoint {
@@ -118,8 +121,8 @@ mod tests {
         Ok(())
     }
 
-    fn offset_test(doc: DocId) -> usize {
-        (doc as usize) * (doc as usize)
+    fn offset_test(doc: DocId) -> u64 {
+        (doc as u64) * (doc as u64)
     }
 
     #[test]
@@ -176,7 +179,33 @@ mod tests {
         Ok(())
     }
 
-    fn integrate_delta(vals: Vec<usize>) -> Vec<usize> {
+    #[test]
+    fn test_skip_index_offsets_exceeding_u32() -> io::Result<()> {
+        // 3GB blocks, so that the offsets of most of the blocks exceed `u32::MAX`.
+        const BLOCK_NUM_BYTES: u64 = 3_000_000_000;
+        let mut output: Vec<u8> = Vec::new();
+        let checkpoints: Vec<Checkpoint> = (0..100)
+            .map(|i| Checkpoint {
+                doc_range: i * 2..(i + 1) * 2,
+                byte_range: i as u64 * BLOCK_NUM_BYTES..(i + 1) as u64 * BLOCK_NUM_BYTES,
+            })
+            .collect();
+        let mut skip_index_builder = SkipIndexBuilder::new();
+        for checkpoint in &checkpoints {
+            skip_index_builder.insert(checkpoint.clone());
+        }
+        skip_index_builder.serialize_into(&mut output)?;
+        let skip_index = SkipIndex::open(OwnedBytes::new(output));
+        assert_eq!(
+            &skip_index.checkpoints().collect::<Vec<_>>()[..],
+            &checkpoints[..]
+        );
+        assert_eq!(skip_index.seek(199), Some(checkpoints[99].clone()));
+        test_skip_index_aux(skip_index, &checkpoints);
+        Ok(())
+    }
+
+    fn integrate_delta(vals: Vec<u64>) -> Vec<u64> {
         let mut output = Vec::with_capacity(vals.len() + 1);
         output.push(0);
         let mut prev = 0;
@@ -193,8 +222,8 @@ mod tests {
         (0..max_len)
             .prop_flat_map(move |len: usize| {
                 (
-                    proptest::collection::vec(1usize..20, len).prop_map(integrate_delta),
-                    proptest::collection::vec(1usize..26, len).prop_map(integrate_delta),
+                    proptest::collection::vec(1u64..20, len).prop_map(integrate_delta),
+                    proptest::collection::vec(1u64..26, len).prop_map(integrate_delta),
                 )
                     .prop_map(|(docs, offsets)| {
                         (0..docs.len() - 1)
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io;
 use std::iter::Sum;
 use std::num::NonZeroUsize;
@@ -17,9 +18,7 @@ use crate::fastfield::AliveBitSet;
 use crate::schema::document::{BinaryDocumentDeserializer, DocumentDeserialize};
 use crate::space_usage::StoreSpaceUsage;
 use crate::store::index::Checkpoint;
-use crate::DocId;
-#[cfg(feature = "quickwit")]
-use crate::Executor;
+use crate::{DocId, Executor};
 
 pub(crate) const DOCSTORE_CACHE_CAPACITY: usize = 100;
 
@@ -36,13 +35,13 @@ pub struct StoreReader {
 
 /// The cache for decompressed blocks.
 struct BlockCache {
-    cache: Option<Mutex<LruCache<usize, Block>>>,
+    cache: Option<Mutex<LruCache<u64, Block>>>,
     cache_hits: AtomicUsize,
     cache_misses: AtomicUsize,
 }
 
 impl BlockCache {
-    fn get_from_cache(&self, pos: usize) -> Option<Block> {
+    fn get_from_cache(&self, pos: u64) -> Option<Block> {
         if let Some(block) = self
             .cache
             .as_ref()
@@ -55,7 +54,7 @@ impl BlockCache {
         None
     }
 
-    fn put_into_cache(&self, pos: usize, data: Block) {
+    fn put_into_cache(&self, pos: u64, data: Block) {
         if let Some(cache) = self.cache.as_ref() {
             cache.lock().unwrap().put(pos, data);
         }
@@ -76,7 +75,7 @@ impl BlockCache {
     }
 
     #[cfg(test)]
-    fn peek_lru(&self) -> Option<usize> {
+    fn peek_lru(&self) -> Option<u64> {
         self.cache
             .as_ref()
             .and_then(|cache| cache.lock().unwrap().peek_lru().map(|(&k, _)| k))
@@ -118,7 +117,9 @@ impl StoreReader {
     /// Opens a store reader
     ///
     /// `cache_num_blocks` sets the number of decompressed blocks to be cached in an LRU.
-    /// The size of blocks is configurable, this should be reflexted in the
+    /// The size of blocks is configurable, see
+    /// [`IndexSettings::docstore_blocksize`](crate::IndexSettings::docstore_blocksize): the memory
+    /// used by the cache is roughly `cache_num_blocks` times the size of a block.
     pub fn open(store_file: FileSlice, cache_num_blocks: usize) -> io::Result<StoreReader> {
         let (footer, data_and_offset) = DocStoreFooter::extract_footer(store_file)?;
 
@@ -169,7 +170,7 @@ impl StoreReader {
     }
 
     fn get_compressed_block(&self, checkpoint: &Checkpoint) -> io::Result<OwnedBytes> {
-        self.data.slice(checkpoint.byte_range.clone()).read_bytes()
+        self.data.slice(block_byte_range(checkpoint)?).read_bytes()
     }
 
     /// Loads and decompresses a block.
@@ -308,12 +309,218 @@ impl StoreReader {
             })
     }
 
+    /// Fetches the given documents, in order, decompressing their blocks in the thread pool
+    /// of the executor.
+    ///
+    /// The blocks of the next documents are decompressed ahead, while the current ones are
+    /// consumed, so that fetching many documents (e.g. to export them) neither decompresses
+    /// all of the blocks in the calling thread nor holds all of the documents in memory.
+    /// With [`Executor::SingleThread`], or when called from a thread of the thread pool of the
+    /// executor, the blocks are decompressed in the calling thread.
+    ///
+    /// Returns an error if one of the documents is not part of the store.
+    pub fn get_many<'a, D: DocumentDeserialize + 'a>(
+        &'a self,
+        doc_ids: Vec<DocId>,
+        executor: &Executor,
+    ) -> crate::Result<impl Iterator<Item = crate::Result<D>> + 'a> {
+        let mut checkpoints: Vec<Checkpoint> = Vec::new();
+        for &doc_id in &doc_ids {
+            let in_last_block = checkpoints
+                .last()
+                .map_or(false, |checkpoint| checkpoint.doc_range.contains(&doc_id));
+            if !in_last_block {
+                checkpoints.push(self.block_checkpoint(doc_id)?);
+            }
+        }
+        let prefetched_blocks = PrefetchedBlocks::new(self, checkpoints, executor);
+        Ok(PrefetchedDocs {
+            doc_ids: doc_ids.into_iter(),
+            prefetched_blocks,
+            current_block: None,
+        }
+        .map(|doc_bytes_res| {
+            let mut doc_bytes = doc_bytes_res?;
+            let deserializer = BinaryDocumentDeserializer::from_reader(&mut doc_bytes)
+                .map_err(crate::TantivyError::from)?;
+            D::deserialize(deserializer).map_err(crate::TantivyError::from)
+        }))
+    }
+
     /// Summarize total space usage of this store reader.
     pub fn space_usage(&self) -> StoreSpaceUsage {
         self.space_usage.clone()
     }
 }
 
+type DecompressedBlockResult = (usize, io::Result<Vec<u8>>);
+
+/// Blocks of a store, decompressed ahead of their use in the thread pool of an executor.
+struct PrefetchedBlocks<'a> {
+    store_reader: &'a StoreReader,
+    checkpoints: Vec<Checkpoint>,
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    // Maximum number of blocks decompressed ahead of the current one.
+    max_blocks_ahead: usize,
+    // Ordinal of the next block to submit for decompression.
+    next_block_ord: usize,
+    ready_blocks: HashMap<usize, io::Result<Block>>,
+    sender: crossbeam_channel::Sender<DecompressedBlockResult>,
+    receiver: crossbeam_channel::Receiver<DecompressedBlockResult>,
+}
+
+impl<'a> PrefetchedBlocks<'a> {
+    fn new(
+        store_reader: &'a StoreReader,
+        checkpoints: Vec<Checkpoint>,
+        executor: &Executor,
+    ) -> PrefetchedBlocks<'a> {
+        let thread_pool = match executor {
+            Executor::SingleThread => None,
+            // Waiting for blocks decompressed by the thread pool from one of its own threads
+            // deadlocks once all of its threads are waiting.
+            Executor::ThreadPool(thread_pool) if thread_pool.current_thread_index().is_some() => {
+                None
+            }
+            Executor::ThreadPool(thread_pool) => Some(thread_pool.clone()),
+        };
+        let max_blocks_ahead = thread_pool
+            .as_ref()
+            .map_or(0, |thread_pool| 2 * thread_pool.current_num_threads());
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        PrefetchedBlocks {
+            store_reader,
+            checkpoints,
+            thread_pool,
+            max_blocks_ahead,
+            next_block_ord: 0,
+            ready_blocks: HashMap::new(),
+            sender,
+            receiver,
+        }
+    }
+
+    fn checkpoint(&self, block_ord: usize) -> &Checkpoint {
+        &self.checkpoints[block_ord]
+    }
+
+    /// Starts the decompression of a block, unless it is cached.
+    fn submit(&mut self, block_ord: usize) {
+        let checkpoint = &self.checkpoints[block_ord];
+        let cache_key = checkpoint.byte_range.start;
+        if let Some(block) = self.store_reader.cache.get_from_cache(cache_key) {
+            self.ready_blocks.insert(block_ord, Ok(block));
+            return;
+        }
+        let compressed_block = match self.store_reader.get_compressed_block(checkpoint) {
+            Ok(compressed_block) => compressed_block,
+            Err(io_error) => {
+                self.ready_blocks.insert(block_ord, Err(io_error));
+                return;
+            }
+        };
+        let decompressor = self.store_reader.decompressor;
+        let Some(thread_pool) = self.thread_pool.as_ref() else {
+            let block_res = decompressor
+                .decompress(compressed_block.as_ref())
+                .map(OwnedBytes::new);
+            self.ready_blocks.insert(block_ord, block_res);
+            return;
+        };
+        let sender = self.sender.clone();
+        thread_pool.spawn(move || {
+            let block_res = decompressor.decompress(compressed_block.as_ref());
+            // The receiver is dropped if the documents are not all consumed.
+            let _ = sender.send((block_ord, block_res));
+        });
+    }
+
+    /// Returns the block, once it is decompressed.
+    fn get(&mut self, block_ord: usize) -> io::Result<Block> {
+        while self.next_block_ord < self.checkpoints.len()
+            && self.next_block_ord <= block_ord + self.max_blocks_ahead
+        {
+            self.submit(self.next_block_ord);
+            self.next_block_ord += 1;
+        }
+        let block_res = loop {
+            if let Some(block_res) = self.ready_blocks.remove(&block_ord) {
+                break block_res;
+            }
+            // The sender of `self` is never dropped, so that `recv` cannot fail.
+            let (decompressed_block_ord, decompressed_block_res) =
+                self.receiver.recv().expect("the block sender was dropped");
+            self.ready_blocks.insert(
+                decompressed_block_ord,
+                decompressed_block_res.map(OwnedBytes::new),
+            );
+        };
+        let block = block_res?;
+        let cache_key = self.checkpoint(block_ord).byte_range.start;
+        self.store_reader
+            .cache
+            .put_into_cache(cache_key, block.clone());
+        Ok(block)
+    }
+}
+
+/// Iterator over the bytes of documents, whose blocks are prefetched.
+struct PrefetchedDocs<'a> {
+    doc_ids: std::vec::IntoIter<DocId>,
+    prefetched_blocks: PrefetchedBlocks<'a>,
+    // The ordinal of the block of the last document, and the block itself.
+    current_block: Option<(usize, io::Result<Block>)>,
+}
+
+impl<'a> Iterator for PrefetchedDocs<'a> {
+    type Item = crate::Result<OwnedBytes>;
+
+    fn next(&mut self) -> Option<crate::Result<OwnedBytes>> {
+        let doc_id = self.doc_ids.next()?;
+        let in_current_block = self.current_block.as_ref().map_or(false, |(block_ord, _)| {
+            let checkpoint = self.prefetched_blocks.checkpoint(*block_ord);
+            checkpoint.doc_range.contains(&doc_id)
+        });
+        if !in_current_block {
+            // The blocks are listed in the order of the documents.
+            let block_ord = self
+                .current_block
+                .as_ref()
+                .map_or(0, |(block_ord, _)| block_ord + 1);
+            let block_res = self.prefetched_blocks.get(block_ord);
+            self.current_block = Some((block_ord, block_res));
+        }
+        let (block_ord, block_res) = self.current_block.as_ref()?;
+        let block = match block_res {
+            Ok(block) => block.clone(),
+            Err(io_error) => {
+                return Some(Err(
+                    io::Error::new(io_error.kind(), io_error.to_string()).into()
+                ));
+            }
+        };
+        let checkpoint = self.prefetched_blocks.checkpoint(*block_ord);
+        Some(StoreReader::get_document_bytes_from_block(
+            block, doc_id, checkpoint,
+        ))
+    }
+}
+
+/// Returns the byte range of the compressed block described by the checkpoint.
+///
+/// Fails if the range cannot be addressed on this platform.
+fn block_byte_range(checkpoint: &Checkpoint) -> io::Result<Range<usize>> {
+    let to_usize = |offset: u64| {
+        usize::try_from(offset).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("doc store block offset {offset} exceeds the addressable range"),
+            )
+        })
+    };
+    Ok(to_usize(checkpoint.byte_range.start)?..to_usize(checkpoint.byte_range.end)?)
+}
+
 fn block_read_index(block: &[u8], doc_pos: u32) -> crate::Result<Range<usize>> {
     let doc_pos = doc_pos as usize;
     let size_of_u32 = std::mem::size_of::<u32>();
@@ -349,13 +556,13 @@ impl StoreReader {
         executor: &Executor,
     ) -> io::Result<Block> {
         let cache_key = checkpoint.byte_range.start;
-        if let Some(block) = self.cache.get_from_cache(checkpoint.byte_range.start) {
+        if let Some(block) = self.cache.get_from_cache(cache_key) {
             return Ok(block);
         }
 
         let compressed_block = self
             .data
-            .slice(checkpoint.byte_range.clone())
+            .slice(block_byte_range(checkpoint)?)
             .read_bytes_async()
             .await?;
 
@@ -457,4 +664,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_store_get_many() -> crate::Result<()> {
+        let directory = RamDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        let schema = write_lorem_ipsum_store(writer, 500, Compressor::default(), BLOCK_SIZE, true);
+        let title = schema.get_field("title").unwrap();
+        let store_file = directory.open_read(path)?;
+        let store = StoreReader::open(store_file, DOCSTORE_CACHE_CAPACITY)?;
+        assert!(store.block_checkpoints().count() > 2);
+
+        let doc_ids = vec![0, 1, 250, 499, 3, 3, 498];
+        for executor in [
+            Executor::single_thread(),
+            Executor::multi_thread(2, "store-test-")?,
+        ] {
+            let titles: Vec<String> = store
+                .get_many::<TantivyDocument>(doc_ids.clone(), &executor)?
+                .map(|doc| Ok(get_text_field(&doc?, &title).unwrap().to_string()))
+                .collect::<crate::Result<_>>()?;
+            let expected_titles: Vec<String> = doc_ids
+                .iter()
+                .map(|doc_id| format!("Doc {doc_id}"))
+                .collect();
+            assert_eq!(titles, expected_titles);
+        }
+        // Fetching documents from within the thread pool of the executor does not deadlock.
+        let executor = Executor::multi_thread(1, "store-test-")?;
+        let titles: Vec<String> = executor.map(
+            |doc_id| {
+                let doc: TantivyDocument = store
+                    .get_many(vec![doc_id, 499], &executor)?
+                    .next()
+                    .unwrap()?;
+                Ok(get_text_field(&doc, &title).unwrap().to_string())
+            },
+            [0, 250].into_iter(),
+        )?;
+        assert_eq!(titles, vec!["Doc 0".to_string(), "Doc 250".to_string()]);
+        assert!(store
+            .get_many::<TantivyDocument>(vec![0, 500], &Executor::single_thread())
+            .is_err());
+        Ok(())
+    }
 }
@@ -9,7 +9,10 @@ use crate::directory::{FileSlice, OwnedBytes};
 use crate::postings::TermInfo;
 use crate::termdict::TermOrdinal;
 
-const BLOCK_LEN: usize = 256;
+/// Default number of term infos per block.
+pub(crate) const DEFAULT_BLOCK_LEN: usize = 256;
+/// Maximum number of term infos per block.
+pub(crate) const MAX_BLOCK_LEN: usize = 1 << 16;
 
 #[derive(Debug, Eq, PartialEq, Default)]
 struct TermInfoBlockMeta {
@@ -60,8 +63,13 @@ impl TermInfoBlockMeta {
     // Here inner_offset is the offset within the block, WITHOUT the first term_info.
     // In other word, term_info #1,#2,#3 gets inner_offset 0,1,2... While term_info #0
     // is encoded without bitpacking.
-    fn deserialize_term_info(&self, data: &[u8], inner_offset: usize) -> TermInfo {
-        assert!(inner_offset < BLOCK_LEN - 1);
+    fn deserialize_term_info(
+        &self,
+        data: &[u8],
+        inner_offset: usize,
+        block_len: usize,
+    ) -> TermInfo {
+        assert!(inner_offset < block_len - 1);
         let num_bits = self.num_bits() as usize;
 
         let posting_start_addr = num_bits * inner_offset;
@@ -95,6 +103,7 @@ impl TermInfoBlockMeta {
 
 pub struct TermInfoStore {
     num_terms: usize,
+    block_len: usize,
     block_meta_bytes: OwnedBytes,
     term_info_bytes: OwnedBytes,
 }
@@ -121,7 +130,7 @@ fn extract_bits(data: &[u8], addr_bits: usize, num_bits: u8) -> u64 {
 }
 
 impl TermInfoStore {
-    pub fn open(term_info_store_file: FileSlice) -> io::Result<TermInfoStore> {
+    pub fn open(term_info_store_file: FileSlice, block_len: usize) -> io::Result<TermInfoStore> {
         let (len_slice, main_slice) = term_info_store_file.split(16);
         let mut bytes = len_slice.read_bytes()?;
         let len = u64::deserialize(&mut bytes)? as usize;
@@ -130,18 +139,19 @@ impl TermInfoStore {
         let term_info_bytes = term_info_file.read_bytes()?;
         Ok(TermInfoStore {
             num_terms,
+            block_len,
             block_meta_bytes: block_meta_file.read_bytes()?,
             term_info_bytes,
         })
     }
 
     pub fn get(&self, term_ord: TermOrdinal) -> TermInfo {
-        let block_id = (term_ord as usize) / BLOCK_LEN;
+        let block_id = (term_ord as usize) / self.block_len;
         let buffer = self.block_meta_bytes.as_slice();
         let mut block_data: &[u8] = &buffer[block_id * TermInfoBlockMeta::SIZE_IN_BYTES..];
         let term_info_block_data = TermInfoBlockMeta::deserialize(&mut block_data)
             .expect("Failed to deserialize terminfoblockmeta");
-        let inner_offset = (term_ord as usize) % BLOCK_LEN;
+        let inner_offset = (term_ord as usize) % self.block_len;
         if inner_offset == 0 {
             return term_info_block_data.ref_term_info;
         }
@@ -149,6 +159,7 @@ impl TermInfoStore {
         term_info_block_data.deserialize_term_info(
             &term_info_data[term_info_block_data.offset as usize..],
             inner_offset - 1,
+            self.block_len,
         )
     }
 
@@ -162,6 +173,7 @@ pub struct TermInfoStoreWriter {
     buffer_term_infos: Vec<u8>,
     term_infos: Vec<TermInfo>,
     num_terms: u64,
+    block_len: usize,
 }
 
 fn bitpack_serialize<W: Write>(
@@ -193,11 +205,26 @@ impl TermInfoStoreWriter {
         TermInfoStoreWriter {
             buffer_block_metas: Vec::new(),
             buffer_term_infos: Vec::new(),
-            term_infos: Vec::with_capacity(BLOCK_LEN),
+            term_infos: Vec::with_capacity(DEFAULT_BLOCK_LEN),
             num_terms: 0u64,
+            block_len: DEFAULT_BLOCK_LEN,
         }
     }
 
+    /// Sets the number of term infos per block.
+    ///
+    /// Smaller blocks make lookups faster, at the cost of a larger store.
+    /// The block length is clamped to `[1, MAX_BLOCK_LEN]`.
+    /// Must be called before any term info is written.
+    pub fn set_block_len(&mut self, block_len: usize) {
+        assert_eq!(self.num_terms, 0);
+        self.block_len = block_len.clamp(1, MAX_BLOCK_LEN);
+    }
+
+    pub fn block_len(&self) -> usize {
+        self.block_len
+    }
+
     fn flush_block(&mut self) -> io::Result<()> {
         let mut bit_packer = BitPacker::new();
         let ref_term_info = self.term_infos[0].clone();
@@ -266,7 +293,7 @@ impl TermInfoStoreWriter {
     pub fn write_term_info(&mut self, term_info: &TermInfo) -> io::Result<()> {
         self.num_terms += 1u64;
         self.term_infos.push(term_info.clone());
-        if self.term_infos.len() >= BLOCK_LEN {
+        if self.term_infos.len() >= self.block_len {
             self.flush_block()?;
         }
         Ok(())
@@ -291,7 +318,9 @@ mod tests {
     use common::BinarySerializable;
     use tantivy_bitpacker::{compute_num_bits, BitPacker};
 
-    use super::{extract_bits, TermInfoBlockMeta, TermInfoStore, TermInfoStoreWriter};
+    use super::{
+        extract_bits, TermInfoBlockMeta, TermInfoStore, TermInfoStoreWriter, DEFAULT_BLOCK_LEN,
+    };
     use crate::directory::FileSlice;
     use crate::postings::TermInfo;
 
@@ -353,7 +382,7 @@ mod tests {
         }
         let mut buffer = Vec::new();
         store_writer.serialize(&mut buffer)?;
-        let term_info_store = TermInfoStore::open(FileSlice::from(buffer))?;
+        let term_info_store = TermInfoStore::open(FileSlice::from(buffer), DEFAULT_BLOCK_LEN)?;
         for i in 0..1000 {
             assert_eq!(
                 term_info_store.get(i as u64),
@@ -5,9 +5,12 @@ use once_cell::sync::Lazy;
 use tantivy_fst::raw::Fst;
 use tantivy_fst::Automaton;
 
-use super::term_info_store::{TermInfoStore, TermInfoStoreWriter};
+use super::term_info_store::{
+    TermInfoStore, TermInfoStoreWriter, DEFAULT_BLOCK_LEN, MAX_BLOCK_LEN,
+};
 use super::{TermStreamer, TermStreamerBuilder};
 use crate::directory::{FileSlice, OwnedBytes};
+use crate::error::DataCorruption;
 use crate::postings::TermInfo;
 use crate::termdict::TermOrdinal;
 
@@ -16,6 +19,9 @@ fn convert_fst_error(e: tantivy_fst::Error) -> io::Error {
 }
 
 const FST_VERSION: u32 = 1;
+/// Version used when the term info store has a non-default block length.
+/// The block length is then serialized right before the footer size.
+const FST_VERSION_WITH_BLOCK_LEN: u32 = 2;
 
 /// Builder for the new term dictionary.
 ///
@@ -39,6 +45,16 @@ where W: Write
         })
     }
 
+    /// Sets the number of terms per term info block.
+    ///
+    /// Smaller blocks make term info lookups faster, but make
+    /// the term dictionary larger.
+    ///
+    /// Must be called before any term is inserted.
+    pub fn set_block_len(&mut self, block_len: usize) {
+        self.term_info_store_writer.set_block_len(block_len);
+    }
+
     /// Inserts a `(key, value)` pair in the term dictionary.
     ///
     /// *Keys have to be inserted in order.*
@@ -81,8 +97,15 @@ where W: Write
             self.term_info_store_writer
                 .serialize(&mut counting_writer)?;
             let footer_size = counting_writer.written_bytes();
-            footer_size.serialize(&mut counting_writer)?;
-            FST_VERSION.serialize(&mut counting_writer)?;
+            let block_len = self.term_info_store_writer.block_len();
+            if block_len == DEFAULT_BLOCK_LEN {
+                footer_size.serialize(&mut counting_writer)?;
+                FST_VERSION.serialize(&mut counting_writer)?;
+            } else {
+                (block_len as u64).serialize(&mut counting_writer)?;
+                footer_size.serialize(&mut counting_writer)?;
+                FST_VERSION_WITH_BLOCK_LEN.serialize(&mut counting_writer)?;
+            }
         }
         Ok(file)
     }
@@ -125,16 +148,34 @@ impl TermDictionary {
         let mut footer_len_bytes = footer_len_slice.read_bytes()?;
         let footer_size = u64::deserialize(&mut footer_len_bytes)?;
         let version = u32::deserialize(&mut footer_len_bytes)?;
-        if version != FST_VERSION {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Unsuported fst version, expected {version}, found {FST_VERSION}",),
-            ));
-        }
+        let (main_slice, block_len) = match version {
+            FST_VERSION => (main_slice, DEFAULT_BLOCK_LEN),
+            FST_VERSION_WITH_BLOCK_LEN => {
+                let (main_slice, block_len_slice) = main_slice.split_from_end(8);
+                let block_len = u64::deserialize(&mut block_len_slice.read_bytes()?)?;
+                if block_len == 0 || block_len > MAX_BLOCK_LEN as u64 {
+                    return Err(DataCorruption::comment_only(format!(
+                        "Invalid term info block length {block_len}, expected a value between 1 \
+                         and {MAX_BLOCK_LEN}"
+                    ))
+                    .into());
+                }
+                (main_slice, block_len as usize)
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "Unsupported fst version, expected {FST_VERSION} or \
+                         {FST_VERSION_WITH_BLOCK_LEN}, found {version}",
+                    ),
+                ));
+            }
+        };
 
         let (fst_file_slice, values_file_slice) = main_slice.split_from_end(footer_size as usize);
         let fst_index = open_fst_index(fst_file_slice)?;
-        let term_info_store = TermInfoStore::open(values_file_slice)?;
+        let term_info_store = TermInfoStore::open(values_file_slice, block_len)?;
         Ok(TermDictionary {
             fst_index,
             term_info_store,
@@ -60,6 +60,23 @@ const CURRENT_TYPE: DictionaryType = DictionaryType::Fst;
 #[cfg(feature = "quickwit")]
 const CURRENT_TYPE: DictionaryType = DictionaryType::SSTable;
 
+/// Statistics about the terms of a [`TermDictionary`], computed by
+/// [`TermDictionary::stats`].
+///
+/// Terms sharing few bytes with the previous term, such as UUIDs, compress poorly and
+/// make lookups slower. For such fields, it can be worth reducing the block length of the
+/// term dictionary (see
+/// [`TextFieldIndexing::set_term_dict_block_len`](crate::schema::TextFieldIndexing::set_term_dict_block_len)).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TermDictionaryStats {
+    /// Number of terms in the dictionary.
+    pub num_terms: u64,
+    /// Total length of the terms, in bytes.
+    pub num_term_bytes: u64,
+    /// Total length of the prefix each term shares with the previous term, in bytes.
+    pub num_shared_prefix_bytes: u64,
+}
+
 // TODO in the future this should become an enum of supported dictionaries
 /// A TermDictionary wrapping either an FST based dictionary or a SSTable based one.
 pub struct TermDictionary(InnerTermDict);
@@ -136,6 +153,29 @@ impl TermDictionary {
         self.0.stream()
     }
 
+    /// Computes the [`TermDictionaryStats`] of the dictionary.
+    ///
+    /// This streams all of the terms of the dictionary.
+    pub fn stats(&self) -> io::Result<TermDictionaryStats> {
+        let mut stats = TermDictionaryStats::default();
+        let mut previous_term: Vec<u8> = Vec::new();
+        let mut term_stream = self.stream()?;
+        while term_stream.advance() {
+            let term = term_stream.key();
+            let shared_prefix_len = term
+                .iter()
+                .zip(&previous_term)
+                .take_while(|(left, right)| left == right)
+                .count();
+            stats.num_terms += 1;
+            stats.num_term_bytes += term.len() as u64;
+            stats.num_shared_prefix_bytes += shared_prefix_len as u64;
+            previous_term.clear();
+            previous_term.extend_from_slice(term);
+        }
+        Ok(stats)
+    }
+
     /// Returns a search builder, to stream all of the terms
     /// within the Automaton
     pub fn search<'a, A: Automaton + 'a>(&'a self, automaton: A) -> TermStreamerBuilder<'a, A>
@@ -176,6 +216,17 @@ impl<W: io::Write> TermDictionaryBuilder<W> {
         InnerTermDictBuilder::create(w).map(TermDictionaryBuilder)
     }
 
+    /// Sets the block length of the term dictionary.
+    ///
+    /// For the FST based dictionary, this is the number of terms per
+    /// term info block. For the SSTable based dictionary, this is the
+    /// approximate size of a block in bytes.
+    ///
+    /// Must be called before any term is inserted.
+    pub fn set_block_len(&mut self, block_len: usize) {
+        self.0.set_block_len(block_len)
+    }
+
     /// Inserts a `(key, value)` pair in the term dictionary.
     ///
     /// *Keys have to be inserted in order.*
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use std::{io, str};
 
-use super::{TermDictionary, TermDictionaryBuilder, TermStreamer};
+use super::{TermDictionary, TermDictionaryBuilder, TermDictionaryStats, TermStreamer};
 use crate::directory::{Directory, FileSlice, RamDirectory, TerminatingWrite};
 use crate::postings::TermInfo;
 
@@ -127,6 +127,65 @@ fn test_term_dictionary_stream() -> crate::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_term_dictionary_block_len() -> crate::Result<()> {
+    let ids: Vec<_> = (0u32..1_000u32)
+        .map(|i| (format!("doc{i:0>6}"), i))
+        .collect();
+    for block_len in [1, 3, 64] {
+        let buffer: Vec<u8> = {
+            let mut term_dictionary_builder = TermDictionaryBuilder::create(vec![])?;
+            term_dictionary_builder.set_block_len(block_len);
+            for (id, i) in &ids {
+                term_dictionary_builder.insert(id.as_bytes(), &make_term_info(*i as u64))?;
+            }
+            term_dictionary_builder.finish()?
+        };
+        let term_dictionary: TermDictionary = TermDictionary::open(FileSlice::from(buffer))?;
+        assert_eq!(term_dictionary.num_terms(), ids.len());
+        for (id, i) in &ids {
+            assert_eq!(
+                term_dictionary.get(id.as_bytes())?,
+                Some(make_term_info(*i as u64))
+            );
+        }
+        let mut streamer = term_dictionary.stream()?;
+        let mut num_terms = 0;
+        while let Some((_, term_info)) = streamer.next() {
+            assert_eq!(term_info, &make_term_info(num_terms));
+            num_terms += 1;
+        }
+        assert_eq!(num_terms, ids.len() as u64);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_term_dictionary_stats() -> crate::Result<()> {
+    let buffer: Vec<u8> = {
+        let mut term_dictionary_builder = TermDictionaryBuilder::create(vec![])?;
+        term_dictionary_builder.insert("abc", &make_term_info(1))?;
+        term_dictionary_builder.insert("abcd", &make_term_info(2))?;
+        term_dictionary_builder.insert("abe", &make_term_info(3))?;
+        term_dictionary_builder.insert("x", &make_term_info(4))?;
+        term_dictionary_builder.finish()?
+    };
+    let term_dictionary = TermDictionary::open(FileSlice::from(buffer))?;
+    assert_eq!(
+        term_dictionary.stats()?,
+        TermDictionaryStats {
+            num_terms: 4,
+            num_term_bytes: 11,
+            num_shared_prefix_bytes: 5,
+        }
+    );
+    assert_eq!(
+        TermDictionary::empty().stats()?,
+        TermDictionaryStats::default()
+    );
+    Ok(())
+}
+
 #[test]
 fn test_stream_high_range_prefix_suffix() -> crate::Result<()> {
     let buffer: Vec<u8> = {
@@ -302,6 +361,26 @@ fn test_stream_range_boundaries_forward() -> crate::Result<()> {
     Ok(())
 }
 
+#[cfg(not(feature = "quickwit"))]
+#[test]
+fn test_term_dictionary_corrupted_block_len() -> crate::Result<()> {
+    let mut buffer: Vec<u8> = {
+        let mut term_dictionary_builder = TermDictionaryBuilder::create(vec![])?;
+        term_dictionary_builder.set_block_len(3);
+        for i in 0u32..10u32 {
+            term_dictionary_builder
+                .insert(format!("doc{i}").as_bytes(), &make_term_info(i as u64))?;
+        }
+        term_dictionary_builder.finish()?
+    };
+    // The block length is serialized right before the fst footer and the dictionary type.
+    let block_len_start = buffer.len() - 24;
+    buffer[block_len_start..][..8].copy_from_slice(&0u64.to_le_bytes());
+    let err = TermDictionary::open(FileSlice::from(buffer)).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    Ok(())
+}
+
 #[cfg(not(feature = "quickwit"))]
 #[test]
 fn test_stream_range_boundaries_backward() -> crate::Result<()> {
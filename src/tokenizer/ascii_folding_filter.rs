@@ -5,6 +5,21 @@ use super::{Token, TokenFilter, TokenStream, Tokenizer};
 /// This class converts alphabetic, numeric, and symbolic Unicode characters
 /// which are not in the first 127 ASCII characters (the "Basic Latin" Unicode
 /// block) into their ASCII equivalents, if one exists.
+///
+/// Combined with [`LowerCaser`](super::LowerCaser), it makes "Héllo" match "hello":
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+///     .filter(LowerCaser)
+///     .filter(AsciiFoldingFilter)
+///     .build();
+/// let mut stream = analyzer.token_stream("Héllo Wörld");
+/// assert_eq!(stream.next().unwrap().text, "hello");
+/// assert_eq!(stream.next().unwrap().text, "world");
+/// assert!(stream.next().is_none());
+/// ```
 #[derive(Clone)]
 pub struct AsciiFoldingFilter;
 
@@ -1573,6 +1588,40 @@ mod tests {
         assert_eq!(&folding_helper("âäàéè"), &["aaaee"]);
     }
 
+    #[test]
+    fn test_ascii_folding_index_and_query() -> crate::Result<()> {
+        use crate::collector::Count;
+        use crate::query::QueryParser;
+        use crate::schema::{Schema, TextFieldIndexing, TextOptions};
+        use crate::tokenizer::LowerCaser;
+        use crate::{doc, Index, IndexWriter};
+
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default()
+            .set_indexing_options(TextFieldIndexing::default().set_tokenizer("folding"));
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            "folding",
+            TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(LowerCaser)
+                .filter(AsciiFoldingFilter)
+                .build(),
+        );
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "Héllo"))?;
+        index_writer.add_document(doc!(text => "hello"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        for query in ["hello", "Héllo", "HELLÖ"] {
+            let query = query_parser.parse_query(query).unwrap();
+            assert_eq!(searcher.search(&query, &Count)?, 2);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_no_change() {
         assert_eq!(&folding_helper("Usagi"), &["Usagi"]);
@@ -0,0 +1,316 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use super::{Token, TokenStream, Tokenizer};
+
+/// Splits a run of Chinese, Japanese or Korean characters into words.
+///
+/// Implementations typically rely on a dictionary. They can be plugged
+/// into a [`CjkTokenizer`] with [`CjkTokenizer::with_segmenter`].
+pub trait CjkSegmenter: Send + Sync + 'static {
+    /// Appends to `segments` the byte ranges, within `text`, of the words of `text`.
+    ///
+    /// `text` only contains CJK characters. The ranges must be sorted and must lie on
+    /// char boundaries. Empty ranges are ignored.
+    fn segment(&self, text: &str, segments: &mut Vec<Range<usize>>);
+}
+
+/// Tokenizer for texts containing Chinese, Japanese or Korean.
+///
+/// These languages do not separate words with whitespaces. By default, runs
+/// of CJK characters are split into overlapping bigrams: `東京都` yields the tokens
+/// `東京` and `京都`. A run made of a single character yields a single token.
+/// Since a phrase query on the bigrams of a text matches the text,
+/// the [`QueryParser`](crate::query::QueryParser) searches CJK words as expected.
+///
+/// A dictionary-based [`CjkSegmenter`] can be plugged in instead
+/// with [`CjkTokenizer::with_segmenter`].
+///
+/// The other characters are tokenized like [`SimpleTokenizer`](super::SimpleTokenizer)
+/// does, by splitting on whitespaces and punctuation.
+///
+/// The `cjk` tokenizer registered by default in the
+/// [`TokenizerManager`](super::TokenizerManager) also lowercases the tokens
+/// and removes the tokens longer than 40 bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut tokenizer = CjkTokenizer::default();
+/// let mut stream = tokenizer.token_stream("東京都 Tokyo");
+/// assert_eq!(stream.next().unwrap().text, "東京");
+/// assert_eq!(stream.next().unwrap().text, "京都");
+/// assert_eq!(stream.next().unwrap().text, "Tokyo");
+/// assert!(stream.next().is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct CjkTokenizer {
+    segmenter: Option<Arc<dyn CjkSegmenter>>,
+    token: Token,
+    segments: Vec<Range<usize>>,
+}
+
+impl CjkTokenizer {
+    /// Creates a `CjkTokenizer` splitting the runs of CJK characters
+    /// with the given segmenter, rather than into bigrams.
+    pub fn with_segmenter<S: CjkSegmenter>(segmenter: S) -> CjkTokenizer {
+        CjkTokenizer {
+            segmenter: Some(Arc::new(segmenter)),
+            ..Default::default()
+        }
+    }
+}
+
+/// TokenStream produced by the `CjkTokenizer`.
+pub struct CjkTokenStream<'a> {
+    text: &'a str,
+    // Byte offset of the rest of the text to tokenize.
+    offset: usize,
+    segmenter: Option<&'a dyn CjkSegmenter>,
+    token: &'a mut Token,
+    // Byte ranges of the tokens of the current run of CJK characters
+    // left to emit, in reverse order.
+    segments: &'a mut Vec<Range<usize>>,
+}
+
+impl Tokenizer for CjkTokenizer {
+    type TokenStream<'a> = CjkTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CjkTokenStream<'a> {
+        self.token.reset();
+        self.segments.clear();
+        CjkTokenStream {
+            text,
+            offset: 0,
+            segmenter: self.segmenter.as_deref(),
+            token: &mut self.token,
+            segments: &mut self.segments,
+        }
+    }
+}
+
+/// Returns true if the character belongs to one of the scripts
+/// of Chinese, Japanese or Korean.
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x11FF // Hangul Jamo
+        | 0x3005..=0x3007 // Ideographic iteration mark, closing mark and number zero
+        | 0x3040..=0x309F // Hiragana
+        | 0x30A0..=0x30FF // Katakana
+        | 0x3130..=0x318F // Hangul Compatibility Jamo
+        | 0x31F0..=0x31FF // Katakana Phonetic Extensions
+        | 0x3400..=0x4DBF // CJK Unified Ideographs Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul Syllables
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+        | 0xFF66..=0xFF9F // Halfwidth Katakana
+        | 0x20000..=0x2FA1F // CJK Unified Ideographs Extensions B to F, and Supplement
+    )
+}
+
+impl<'a> CjkTokenStream<'a> {
+    /// Fills `segments` with the tokens of the run of CJK characters `start..end`.
+    fn segment_run(&mut self, start: usize, end: usize) {
+        let run = &self.text[start..end];
+        if let Some(segmenter) = self.segmenter {
+            segmenter.segment(run, self.segments);
+            self.segments.retain(|segment| !segment.is_empty());
+            for segment in self.segments.iter_mut() {
+                *segment = start + segment.start..start + segment.end;
+            }
+        } else {
+            let mut boundaries = run.char_indices().map(|(offset, _)| start + offset);
+            let Some(mut first) = boundaries.next() else {
+                return;
+            };
+            let Some(mut second) = boundaries.next() else {
+                self.segments.push(first..end);
+                return;
+            };
+            for third in boundaries.chain(std::iter::once(end)) {
+                self.segments.push(first..third);
+                first = second;
+                second = third;
+            }
+        }
+        self.segments.reverse();
+    }
+
+    fn emit(&mut self, range: Range<usize>) {
+        self.token.offset_from = range.start;
+        self.token.offset_to = range.end;
+        self.token.text.clear();
+        self.token.text.push_str(&self.text[range]);
+    }
+}
+
+impl<'a> TokenStream for CjkTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        self.token.position = self.token.position.wrapping_add(1);
+        loop {
+            if let Some(segment) = self.segments.pop() {
+                self.emit(segment);
+                return true;
+            }
+            let rest = &self.text[self.offset..];
+            let Some((token_start, c)) = rest
+                .char_indices()
+                .find(|(_, c)| c.is_alphanumeric() || is_cjk(*c))
+            else {
+                self.offset = self.text.len();
+                return false;
+            };
+            let start = self.offset + token_start;
+            let c_is_cjk = is_cjk(c);
+            let end = self.text[start..]
+                .char_indices()
+                .find(|(_, c)| {
+                    if c_is_cjk {
+                        !is_cjk(*c)
+                    } else {
+                        !c.is_alphanumeric() || is_cjk(*c)
+                    }
+                })
+                .map(|(offset, _)| start + offset)
+                .unwrap_or(self.text.len());
+            self.offset = end;
+            if c_is_cjk {
+                self.segment_run(start, end);
+            } else {
+                self.emit(start..end);
+                return true;
+            }
+        }
+    }
+
+    fn token(&self) -> &Token {
+        self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::{is_cjk, CjkSegmenter, CjkTokenizer};
+    use crate::collector::Count;
+    use crate::query::QueryParser;
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{TextAnalyzer, Token};
+    use crate::{doc, Index, IndexWriter};
+
+    fn token_stream_helper(text: &str, tokenizer: CjkTokenizer) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::from(tokenizer);
+        let mut tokens = Vec::new();
+        analyzer
+            .token_stream(text)
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_is_cjk() {
+        assert!(is_cjk('東'));
+        assert!(is_cjk('ひ'));
+        assert!(is_cjk('カ'));
+        assert!(is_cjk('한'));
+        assert!(!is_cjk('a'));
+        assert!(!is_cjk('é'));
+        assert!(!is_cjk('。'));
+    }
+
+    #[test]
+    fn test_cjk_tokenizer_bigrams() {
+        let tokens = token_stream_helper("我爱北京。中 Tantivy2 search", CjkTokenizer::default());
+        assert_eq!(tokens.len(), 6);
+        assert_token(&tokens[0], 0, "我爱", 0, 6);
+        assert_token(&tokens[1], 1, "爱北", 3, 9);
+        assert_token(&tokens[2], 2, "北京", 6, 12);
+        assert_token(&tokens[3], 3, "中", 15, 18);
+        assert_token(&tokens[4], 4, "Tantivy2", 19, 27);
+        assert_token(&tokens[5], 5, "search", 28, 34);
+    }
+
+    #[test]
+    fn test_cjk_tokenizer_mixed_scripts() {
+        let texts: Vec<String> = token_stream_helper("iPhone用ケース", CjkTokenizer::default())
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["iPhone", "用ケ", "ケー", "ース"]);
+        assert!(token_stream_helper("", CjkTokenizer::default()).is_empty());
+        assert!(token_stream_helper("。、！", CjkTokenizer::default()).is_empty());
+    }
+
+    struct DictionarySegmenter {
+        words: Vec<&'static str>,
+    }
+
+    impl CjkSegmenter for DictionarySegmenter {
+        // Greedy longest match, falling back to single characters.
+        fn segment(&self, text: &str, segments: &mut Vec<Range<usize>>) {
+            let mut offset = 0;
+            while offset < text.len() {
+                let rest = &text[offset..];
+                let len = self
+                    .words
+                    .iter()
+                    .filter(|word| rest.starts_with(*word))
+                    .map(|word| word.len())
+                    .max()
+                    .unwrap_or_else(|| rest.chars().next().unwrap().len_utf8());
+                segments.push(offset..offset + len);
+                offset += len;
+            }
+        }
+    }
+
+    #[test]
+    fn test_cjk_tokenizer_with_segmenter() {
+        let segmenter = DictionarySegmenter {
+            words: vec!["北京", "北京大学", "大学", "学生"],
+        };
+        let tokens = token_stream_helper(
+            "我是北京大学的学生",
+            CjkTokenizer::with_segmenter(segmenter),
+        );
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        assert_eq!(texts, vec!["我", "是", "北京大学", "的", "学生"]);
+        assert_token(&tokens[2], 2, "北京大学", 6, 18);
+    }
+
+    #[test]
+    fn test_cjk_tokenizer_index_and_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("cjk")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "東京都に住んでいます"))?;
+        index_writer.add_document(doc!(text => "京都の寺"))?;
+        index_writer.add_document(doc!(text => "Tokyo Tower"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("京都")?, 2);
+        assert_eq!(count("東京都")?, 1);
+        assert_eq!(count("東京の寺")?, 0);
+        assert_eq!(count("tokyo")?, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+use std::ops::Range;
+
+use super::{Token, TokenStream, Tokenizer};
+
+/// Inline tags do not separate words: `b<b>ol</b>d` contains the single word `bold`.
+/// All of the other tags are replaced by a space.
+const INLINE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "code", "em", "font", "i", "mark", "s", "small", "span", "strong", "sub",
+    "sup", "u",
+];
+
+/// Tags whose content is not text, and is stripped along with the tags.
+const RAW_TEXT_TAGS: &[&str] = &["script", "style"];
+
+/// Tokenizer wrapper that strips HTML (or XML) markup from the text
+/// before handing it to the wrapped tokenizer.
+///
+/// - Tags and comments are removed. Block-level tags such as `<p>` or `<br>` are replaced by a
+///   space, so that they separate words, while inline tags such as `<b>` are simply removed.
+/// - The content of `<script>` and `<style>` elements is removed.
+/// - Character references (`&amp;`, `&#233;`, `&#xE9;`, ...) are decoded.
+///
+/// The offsets of the emitted tokens refer to the original text, markup included,
+/// which makes it possible to highlight matches in the original document.
+///
+/// # Example
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut tokenizer = TextAnalyzer::builder(HtmlStripTokenizer::new(SimpleTokenizer::default()))
+///     .filter(LowerCaser)
+///     .build();
+///
+/// let text = "<p>Fish &amp; <b>Chips</b></p>";
+/// let mut stream = tokenizer.token_stream(text);
+/// assert_eq!(stream.next().unwrap().text, "fish");
+/// let token = stream.next().unwrap();
+/// assert_eq!(token.text, "chips");
+/// assert_eq!(&text[token.offset_from..token.offset_to], "Chips");
+/// assert!(stream.next().is_none());
+/// ```
+#[derive(Clone)]
+pub struct HtmlStripTokenizer<T> {
+    inner: T,
+    stripped: String,
+    // For each byte of `stripped`, the byte range of the original text it comes from.
+    offsets: Vec<Range<usize>>,
+}
+
+impl<T> HtmlStripTokenizer<T> {
+    /// Wraps the given tokenizer, so that it is fed the text stripped from its markup.
+    pub fn new(inner: T) -> HtmlStripTokenizer<T> {
+        HtmlStripTokenizer {
+            inner,
+            stripped: String::new(),
+            offsets: Vec::new(),
+        }
+    }
+}
+
+impl<T: Tokenizer> Tokenizer for HtmlStripTokenizer<T> {
+    type TokenStream<'a> = HtmlStripTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        strip_html(text, &mut self.stripped, &mut self.offsets);
+        HtmlStripTokenStream {
+            tail: self.inner.token_stream(&self.stripped),
+            offsets: &self.offsets,
+            text_len: text.len(),
+        }
+    }
+}
+
+pub struct HtmlStripTokenStream<'a, T> {
+    tail: T,
+    offsets: &'a [Range<usize>],
+    text_len: usize,
+}
+
+impl<'a, T: TokenStream> TokenStream for HtmlStripTokenStream<'a, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let token = self.tail.token_mut();
+        let offset_from = self
+            .offsets
+            .get(token.offset_from)
+            .map(|range| range.start)
+            .unwrap_or(self.text_len);
+        let offset_to = if token.offset_to > token.offset_from {
+            self.offsets[token.offset_to - 1].end
+        } else {
+            offset_from
+        };
+        token.offset_from = offset_from;
+        token.offset_to = offset_to;
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+fn push_str(stripped: &mut String, offsets: &mut Vec<Range<usize>>, s: &str, range: Range<usize>) {
+    stripped.push_str(s);
+    offsets.extend(std::iter::repeat(range).take(s.len()));
+}
+
+fn strip_html(text: &str, stripped: &mut String, offsets: &mut Vec<Range<usize>>) {
+    stripped.clear();
+    offsets.clear();
+    let mut missing_markers = MissingMarkers::default();
+    let mut pos = 0;
+    while pos < text.len() {
+        match text.as_bytes()[pos] {
+            b'<' => {
+                if let Some((end, is_inline)) = parse_tag(text, pos, &mut missing_markers) {
+                    if !is_inline {
+                        push_str(stripped, offsets, " ", pos..end);
+                    }
+                    pos = end;
+                    continue;
+                }
+            }
+            b'&' => {
+                if let Some((decoded, end)) = decode_character_reference(text, pos) {
+                    push_str(
+                        stripped,
+                        offsets,
+                        decoded.encode_utf8(&mut [0; 4]),
+                        pos..end,
+                    );
+                    pos = end;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        let ch_len = text[pos..].chars().next().map(char::len_utf8).unwrap_or(1);
+        stripped.push_str(&text[pos..pos + ch_len]);
+        offsets.extend((pos..pos + ch_len).map(|byte_pos| byte_pos..byte_pos + 1));
+        pos += ch_len;
+    }
+}
+
+/// The end markers that are known to be missing from the rest of the text.
+///
+/// Once the end of a tag or of a comment was not found, it cannot be found after any
+/// of the following `<` either: remembering it keeps text with many unterminated tags
+/// from being scanned again for each of them.
+#[derive(Default)]
+struct MissingMarkers {
+    tag_end: bool,
+    comment_end: bool,
+}
+
+/// Parses the tag or comment starting at `pos`.
+///
+/// Returns the end of the tag, and whether it is an inline tag, or `None`
+/// if the `<` at `pos` does not start a tag.
+fn parse_tag(
+    text: &str,
+    pos: usize,
+    missing_markers: &mut MissingMarkers,
+) -> Option<(usize, bool)> {
+    let rest = &text[pos..];
+    if let Some(comment) = rest.strip_prefix("<!--") {
+        if missing_markers.comment_end {
+            return None;
+        }
+        let Some(comment_len) = comment.find("-->") else {
+            missing_markers.comment_end = true;
+            return None;
+        };
+        return Some((pos + 4 + comment_len + 3, false));
+    }
+    match rest.as_bytes().get(1) {
+        Some(b) if b.is_ascii_alphabetic() || matches!(b, b'/' | b'!' | b'?') => {}
+        _ => return None,
+    }
+    if missing_markers.tag_end {
+        return None;
+    }
+    let Some(tag_end) = rest.find('>') else {
+        missing_markers.tag_end = true;
+        return None;
+    };
+    let tag_len = tag_end + 1;
+    let tag_content = &rest[1..tag_len - 1];
+    let is_closing = tag_content.starts_with('/');
+    let name: String = tag_content
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let is_inline = INLINE_TAGS.contains(&name.as_str());
+    let mut end = pos + tag_len;
+    if !is_closing && !tag_content.ends_with('/') && RAW_TEXT_TAGS.contains(&name.as_str()) {
+        // Skip the content of the element, up to its closing tag.
+        end = match find_closing_tag(&text[end..], &name) {
+            Some(closing_tag_pos) => {
+                let closing_tag_start = end + closing_tag_pos;
+                text[closing_tag_start..]
+                    .find('>')
+                    .map(|closing_tag_len| closing_tag_start + closing_tag_len + 1)
+                    .unwrap_or(text.len())
+            }
+            None => text.len(),
+        };
+    }
+    Some((end, is_inline))
+}
+
+/// Returns the position of the first closing tag with the given lowercase name,
+/// ignoring the case of the text.
+fn find_closing_tag(text: &str, name: &str) -> Option<usize> {
+    text.match_indices("</")
+        .map(|(closing_tag_pos, _)| closing_tag_pos)
+        .find(|&closing_tag_pos| {
+            text.as_bytes()[closing_tag_pos + 2..]
+                .get(..name.len())
+                .map_or(false, |tag_name| {
+                    tag_name.eq_ignore_ascii_case(name.as_bytes())
+                })
+        })
+}
+
+/// Decodes the character reference starting at `pos`.
+///
+/// Returns the decoded character and the end of the reference, or `None`
+/// if the `&` at `pos` does not start a known character reference.
+fn decode_character_reference(text: &str, pos: usize) -> Option<(char, usize)> {
+    const MAX_REFERENCE_LEN: usize = 32;
+    let rest = &text[pos + 1..];
+    let semicolon_pos = rest
+        .bytes()
+        .take(MAX_REFERENCE_LEN)
+        .position(|b| b == b';')?;
+    let reference = &rest[..semicolon_pos];
+    let decoded = if let Some(numeric) = reference.strip_prefix('#') {
+        let code_point = if let Some(hex) = numeric.strip_prefix(['x', 'X']) {
+            u32::from_str_radix(hex, 16).ok()?
+        } else {
+            numeric.parse::<u32>().ok()?
+        };
+        char::from_u32(code_point)?
+    } else {
+        match reference {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            "nbsp" => '\u{a0}',
+            "copy" => '©',
+            "reg" => '®',
+            "ndash" => '–',
+            "mdash" => '—',
+            "hellip" => '…',
+            _ => return None,
+        }
+    };
+    Some((decoded, pos + 1 + semicolon_pos + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{strip_html, HtmlStripTokenizer};
+    use crate::tokenizer::{SimpleTokenizer, TextAnalyzer, Token};
+
+    fn strip(text: &str) -> String {
+        let mut stripped = String::new();
+        let mut offsets = Vec::new();
+        strip_html(text, &mut stripped, &mut offsets);
+        assert_eq!(offsets.len(), stripped.len());
+        stripped
+    }
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(strip("no markup"), "no markup");
+        assert_eq!(strip("<p>one</p><p>two</p>"), " one  two ");
+        assert_eq!(strip("b<b>ol</b>d"), "bold");
+        assert_eq!(strip("a <!-- comment --> b"), "a   b");
+        assert_eq!(
+            strip("<style>p { color: red; }</style>text<script>if (a < b) {}</SCRIPT>"),
+            " text "
+        );
+        assert_eq!(strip("1 < 2 &amp;&amp; 3 > 2"), "1 < 2 && 3 > 2");
+        assert_eq!(
+            strip("caf&#233; caf&#xE9; &unknown; &amp"),
+            "café café &unknown; &amp"
+        );
+        assert_eq!(strip("unterminated <p"), "unterminated <p");
+        assert_eq!(strip("<br/>x<img src=\"a.png\" />"), " x ");
+    }
+
+    #[test]
+    fn test_strip_html_unterminated_markup() {
+        assert_eq!(strip("<a <b <!-- c <!-- d"), "<a <b <!-- c <!-- d");
+        assert_eq!(strip("<!-- a <p> b"), "<!-- a   b");
+        // The rest of the text is not scanned again for each unterminated tag.
+        let unterminated_tags = "<a ".repeat(100_000);
+        assert_eq!(strip(&unterminated_tags), unterminated_tags);
+        let scripts = "<script>x</script>y".repeat(10_000);
+        assert_eq!(strip(&scripts), " y".repeat(10_000));
+    }
+
+    #[test]
+    fn test_html_strip_tokenizer_offsets() {
+        let mut analyzer = TextAnalyzer::from(HtmlStripTokenizer::new(SimpleTokenizer::default()));
+        let text = "<h1>Caf&#233;</h1><p>Fish &amp; <em>ch</em>ips and <a \
+                    href=\"/x\">b<b>ol</b>d</a> choices</p>";
+        let mut tokens: Vec<Token> = Vec::new();
+        analyzer
+            .token_stream(text)
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        let texts_and_sources: Vec<(&str, &str)> = tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.text.as_str(),
+                    &text[token.offset_from..token.offset_to],
+                )
+            })
+            .collect();
+        assert_eq!(
+            texts_and_sources,
+            vec![
+                ("Café", "Caf&#233;"),
+                ("Fish", "Fish"),
+                ("chips", "ch</em>ips"),
+                ("and", "and"),
+                ("bold", "b<b>ol</b>d"),
+                ("choices", "choices"),
+            ]
+        );
+        for (position, token) in tokens.iter().enumerate() {
+            assert_eq!(token.position, position);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let mut tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+//!   .filter(LimitTokenCountFilter::limit(2))
+//!   .build();
+//!
+//! let mut stream = tokenizer.token_stream("one two three");
+//! // only the first 2 tokens are emitted.
+//! assert_eq!(stream.next().unwrap().text, "one");
+//! assert_eq!(stream.next().unwrap().text, "two");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// `LimitTokenCountFilter` stops the token stream once
+/// a given number of tokens has been emitted.
+///
+/// The limit applies to each text the tokenizer is given, i.e. to each value of a field.
+/// It protects the index from pathological documents, such as machine-generated blobs,
+/// and is typically combined with a [`RemoveLongFilter`](super::RemoveLongFilter),
+/// which removes the tokens longer than a given number of bytes.
+///
+/// The rest of the text is not tokenized, so that the filter also bounds the
+/// time spent tokenizing a value.
+#[derive(Clone)]
+pub struct LimitTokenCountFilter {
+    count_limit: usize,
+}
+
+impl LimitTokenCountFilter {
+    /// Creates a `LimitTokenCountFilter` given the maximum number of tokens to emit.
+    pub fn limit(count_limit: usize) -> LimitTokenCountFilter {
+        LimitTokenCountFilter { count_limit }
+    }
+}
+
+impl TokenFilter for LimitTokenCountFilter {
+    type Tokenizer<T: Tokenizer> = LimitTokenCountFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> LimitTokenCountFilterWrapper<T> {
+        LimitTokenCountFilterWrapper {
+            count_limit: self.count_limit,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LimitTokenCountFilterWrapper<T: Tokenizer> {
+    count_limit: usize,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for LimitTokenCountFilterWrapper<T> {
+    type TokenStream<'a> = LimitTokenCountFilterStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        LimitTokenCountFilterStream {
+            remaining: self.count_limit,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct LimitTokenCountFilterStream<T> {
+    remaining: usize,
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for LimitTokenCountFilterStream<T> {
+    fn advance(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        self.tail.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{
+        LimitTokenCountFilter, RemoveLongFilter, SimpleTokenizer, TextAnalyzer, Token,
+    };
+
+    #[test]
+    fn test_limit_token_count() {
+        let tokens = token_stream_helper("hello tantivy, happy searching!", 3);
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "hello", 0, 5);
+        assert_token(&tokens[1], 1, "tantivy", 6, 13);
+        assert_token(&tokens[2], 2, "happy", 15, 20);
+        assert_eq!(token_stream_helper("hello tantivy", 3).len(), 2);
+        assert!(token_stream_helper("hello tantivy", 0).is_empty());
+    }
+
+    #[test]
+    fn test_limit_token_count_after_remove_long() {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(RemoveLongFilter::limit(6))
+            .filter(LimitTokenCountFilter::limit(2))
+            .build();
+        let mut tokens: Vec<Token> = vec![];
+        analyzer
+            .token_stream("hello tantivy, happy searching, bye!")
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        // The tokens removed by the `RemoveLongFilter` are not counted.
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "hello", 0, 5);
+        assert_token(&tokens[1], 2, "happy", 15, 20);
+    }
+
+    fn token_stream_helper(text: &str, count_limit: usize) -> Vec<Token> {
+        let mut a = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LimitTokenCountFilter::limit(count_limit))
+            .build();
+        let mut token_stream = a.token_stream(text);
+        let mut tokens: Vec<Token> = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
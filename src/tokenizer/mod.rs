@@ -52,6 +52,12 @@
 //! remove their inflection. This tokenizer is slower than the default one,
 //! but is recommended to improve recall.
 //!
+//! ## `cjk`
+//!
+//! Tokenizer for Chinese, Japanese and Korean texts, which do not separate
+//! words with whitespaces. It splits runs of CJK characters into overlapping
+//! bigrams, and otherwise behaves like `default`. See [`CjkTokenizer`].
+//!
 //! # Custom tokenizer Library
 //! Avoid using tantivy as dependency and prefer `tantivy-tokenizer-api` instead.
 //!
@@ -123,17 +129,22 @@
 //! ```
 mod alphanum_only;
 mod ascii_folding_filter;
+mod cjk_tokenizer;
 mod empty_tokenizer;
 mod facet_tokenizer;
+mod html_strip;
+mod limit_token_count;
 mod lower_caser;
 mod ngram_tokenizer;
 mod raw_tokenizer;
 mod regex_tokenizer;
 mod remove_long;
+mod shingle_filter;
 mod simple_tokenizer;
 mod split_compound_words;
 mod stemmer;
 mod stop_word_filter;
+mod synonym_filter;
 mod tokenized_string;
 mod tokenizer;
 mod tokenizer_manager;
@@ -143,16 +154,21 @@ pub use tokenizer_api::{BoxTokenStream, Token, TokenFilter, TokenStream, Tokeniz
 
 pub use self::alphanum_only::AlphaNumOnlyFilter;
 pub use self::ascii_folding_filter::AsciiFoldingFilter;
+pub use self::cjk_tokenizer::{CjkSegmenter, CjkTokenStream, CjkTokenizer};
 pub use self::facet_tokenizer::FacetTokenizer;
+pub use self::html_strip::HtmlStripTokenizer;
+pub use self::limit_token_count::LimitTokenCountFilter;
 pub use self::lower_caser::LowerCaser;
 pub use self::ngram_tokenizer::NgramTokenizer;
 pub use self::raw_tokenizer::RawTokenizer;
 pub use self::regex_tokenizer::RegexTokenizer;
 pub use self::remove_long::RemoveLongFilter;
+pub use self::shingle_filter::ShingleFilter;
 pub use self::simple_tokenizer::{SimpleTokenStream, SimpleTokenizer};
 pub use self::split_compound_words::SplitCompoundWords;
 pub use self::stemmer::{Language, Stemmer};
 pub use self::stop_word_filter::StopWordFilter;
+pub use self::synonym_filter::SynonymFilter;
 pub use self::tokenized_string::{PreTokenizedStream, PreTokenizedString};
 pub use self::tokenizer::{TextAnalyzer, TextAnalyzerBuilder};
 pub use self::tokenizer_manager::TokenizerManager;
@@ -204,6 +220,35 @@ pub mod tests {
         assert_token(&tokens[0], 0, "Hello, happy tax payer!", 0, 23);
     }
 
+    #[test]
+    fn test_tokenizer_manager_tokenizer_names() {
+        let tokenizer_manager = TokenizerManager::default();
+        assert_eq!(
+            tokenizer_manager.tokenizer_names(),
+            vec![
+                "cjk",
+                "default",
+                "en_stem",
+                "raw",
+                "raw_lowercase",
+                "whitespace"
+            ]
+        );
+        tokenizer_manager.register("custom", SimpleTokenizer::default());
+        assert_eq!(
+            tokenizer_manager.tokenizer_names(),
+            vec![
+                "cjk",
+                "custom",
+                "default",
+                "en_stem",
+                "raw",
+                "raw_lowercase",
+                "whitespace"
+            ]
+        );
+    }
+
     #[test]
     fn test_en_tokenizer() {
         let tokenizer_manager = TokenizerManager::default();
@@ -118,13 +118,17 @@ impl NgramTokenizer {
 
     /// Create a `NGramTokenizer` which generates tokens for all inner ngrams.
     ///
-    /// This is as opposed to only prefix ngrams    .
+    /// This is as opposed to only prefix ngrams.
     pub fn all_ngrams(min_gram: usize, max_gram: usize) -> crate::Result<NgramTokenizer> {
         Self::new(min_gram, max_gram, false)
     }
 
     /// Create a `NGramTokenizer` which only generates tokens for the
-    /// prefix ngrams.
+    /// prefix ngrams (also known as edge ngrams).
+    ///
+    /// Indexing a field with this tokenizer makes it possible to implement
+    /// autocompletion with a simple `TermQuery` on the prefix typed by the user,
+    /// instead of expanding the prefix into all of the matching terms at query time.
     pub fn prefix_only(min_gram: usize, max_gram: usize) -> crate::Result<NgramTokenizer> {
         Self::new(min_gram, max_gram, true)
     }
@@ -436,6 +440,46 @@ mod tests {
         assert_token(&tokens[3], 0, "hεllo", 0, 6);
     }
 
+    #[test]
+    fn test_ngram_prefix_autocomplete() -> crate::Result<()> {
+        use crate::collector::Count;
+        use crate::query::TermQuery;
+        use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+        use crate::{doc, Index, IndexWriter, Term};
+
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("edge_ngram")
+                .set_index_option(IndexRecordOption::Basic),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index
+            .tokenizers()
+            .register("edge_ngram", NgramTokenizer::prefix_only(2, 5)?);
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "hello"))?;
+        index_writer.add_document(doc!(text => "help"))?;
+        index_writer.add_document(doc!(text => "shell"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let count = |prefix: &str| {
+            let query = TermQuery::new(
+                Term::from_field_text(text, prefix),
+                IndexRecordOption::Basic,
+            );
+            searcher.search(&query, &Count)
+        };
+        assert_eq!(count("h")?, 0);
+        assert_eq!(count("hel")?, 2);
+        assert_eq!(count("hell")?, 1);
+        assert_eq!(count("hello")?, 1);
+        assert_eq!(count("ell")?, 0);
+        Ok(())
+    }
+
     #[test]
     fn test_ngram_empty() {
         let tokens = test_helper(NgramTokenizer::all_ngrams(1, 5).unwrap().token_stream(""));
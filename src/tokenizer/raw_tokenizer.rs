@@ -1,6 +1,9 @@
 use super::{Token, TokenStream, Tokenizer};
 
 /// For each value of the field, emit a single unprocessed token.
+///
+/// It is registered by default as `raw`. The `raw_lowercase` tokenizer,
+/// also registered by default, additionally lowercases the token.
 #[derive(Clone, Default)]
 pub struct RawTokenizer {
     token: Token,
@@ -47,7 +50,7 @@ impl<'a> TokenStream for RawTokenStream<'a> {
 #[cfg(test)]
 mod tests {
     use crate::tokenizer::tests::assert_token;
-    use crate::tokenizer::{RawTokenizer, TextAnalyzer, Token};
+    use crate::tokenizer::{RawTokenizer, TextAnalyzer, Token, TokenizerManager};
 
     #[test]
     fn test_raw_tokenizer() {
@@ -56,6 +59,17 @@ mod tests {
         assert_token(&tokens[0], 0, "Hello, happy tax payer!", 0, 23);
     }
 
+    #[test]
+    fn test_raw_lowercase_tokenizer() {
+        let mut analyzer = TokenizerManager::default().get("raw_lowercase").unwrap();
+        let mut tokens: Vec<Token> = vec![];
+        analyzer
+            .token_stream("Hello, Happy Tax Payer!")
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "hello, happy tax payer!", 0, 23);
+    }
+
     fn token_stream_helper(text: &str) -> Vec<Token> {
         let mut a = TextAnalyzer::from(RawTokenizer::default());
         let mut token_stream = a.token_stream(text);
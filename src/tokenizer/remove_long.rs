@@ -19,6 +19,9 @@ use super::{Token, TokenFilter, TokenStream, Tokenizer};
 ///
 /// It is especially useful when indexing unconstrained content.
 /// e.g. Mail containing base-64 encoded pictures etc.
+///
+/// See also [`LimitTokenCountFilter`](super::LimitTokenCountFilter) to cap the
+/// number of tokens of a text.
 #[derive(Clone)]
 pub struct RemoveLongFilter {
     length_limit: usize,
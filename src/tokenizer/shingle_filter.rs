@@ -0,0 +1,276 @@
+use std::collections::VecDeque;
+
+use super::{Token, TokenFilter, TokenStream, Tokenizer};
+use crate::TantivyError;
+
+/// [`TokenFilter`] that emits shingles, i.e. groups of adjacent tokens
+/// joined by a separator (by default, a space).
+///
+/// A shingle is emitted at the position of its first token, with a `position_length`
+/// equal to the number of tokens it is made of. Shingles never span a position gap,
+/// such as the one left by a removed stop word.
+///
+/// Indexing shingles makes the index larger, but a phrase made of a few words
+/// can then be searched with a single [`TermQuery`](crate::query::TermQuery)
+/// on the corresponding shingle, which is much faster than a
+/// [`PhraseQuery`](crate::query::PhraseQuery).
+///
+/// # Example
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+///     .filter(LowerCaser)
+///     .filter(ShingleFilter::new(2, 2).unwrap())
+///     .build();
+///
+/// let mut stream = tokenizer.token_stream("New York City");
+/// assert_eq!(stream.next().unwrap().text, "new");
+/// assert_eq!(stream.next().unwrap().text, "new york");
+/// assert_eq!(stream.next().unwrap().text, "york");
+/// assert_eq!(stream.next().unwrap().text, "york city");
+/// assert_eq!(stream.next().unwrap().text, "city");
+/// assert!(stream.next().is_none());
+/// ```
+#[derive(Clone)]
+pub struct ShingleFilter {
+    min_shingle_size: usize,
+    max_shingle_size: usize,
+    output_unigrams: bool,
+    separator: String,
+}
+
+impl ShingleFilter {
+    /// Creates a `ShingleFilter` emitting shingles of `min_shingle_size` to
+    /// `max_shingle_size` tokens (both inclusive).
+    ///
+    /// `min_shingle_size` must be at least 2.
+    pub fn new(min_shingle_size: usize, max_shingle_size: usize) -> crate::Result<ShingleFilter> {
+        if min_shingle_size < 2 {
+            return Err(TantivyError::InvalidArgument(
+                "min_shingle_size must be at least 2".to_string(),
+            ));
+        }
+        if min_shingle_size > max_shingle_size {
+            return Err(TantivyError::InvalidArgument(
+                "min_shingle_size must not be greater than max_shingle_size".to_string(),
+            ));
+        }
+        Ok(ShingleFilter {
+            min_shingle_size,
+            max_shingle_size,
+            output_unigrams: true,
+            separator: " ".to_string(),
+        })
+    }
+
+    /// Sets whether the original tokens should be emitted along with the shingles.
+    ///
+    /// Defaults to `true`.
+    #[must_use]
+    pub fn output_unigrams(mut self, output_unigrams: bool) -> ShingleFilter {
+        self.output_unigrams = output_unigrams;
+        self
+    }
+
+    /// Sets the separator inserted between the tokens of a shingle.
+    ///
+    /// Defaults to a single space.
+    #[must_use]
+    pub fn separator(mut self, separator: &str) -> ShingleFilter {
+        self.separator = separator.to_string();
+        self
+    }
+}
+
+impl TokenFilter for ShingleFilter {
+    type Tokenizer<T: Tokenizer> = ShingleFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> ShingleFilterWrapper<T> {
+        ShingleFilterWrapper {
+            filter: self,
+            inner: tokenizer,
+            window: VecDeque::new(),
+            token: Token::default(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ShingleFilterWrapper<T> {
+    filter: ShingleFilter,
+    inner: T,
+    window: VecDeque<Token>,
+    token: Token,
+}
+
+impl<T: Tokenizer> Tokenizer for ShingleFilterWrapper<T> {
+    type TokenStream<'a> = ShingleFilterStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        self.window.clear();
+        ShingleFilterStream {
+            filter: &self.filter,
+            tail: self.inner.token_stream(text),
+            window: &mut self.window,
+            token: &mut self.token,
+            shingle_size: 0,
+        }
+    }
+}
+
+pub struct ShingleFilterStream<'a, T> {
+    filter: &'a ShingleFilter,
+    tail: T,
+    // Tokens starting at the first token of the current shingle.
+    window: &'a mut VecDeque<Token>,
+    token: &'a mut Token,
+    // Number of tokens of the last emitted shingle, or 0 if no
+    // shingle was emitted yet for the first token of the window.
+    shingle_size: usize,
+}
+
+impl<'a, T: TokenStream> ShingleFilterStream<'a, T> {
+    /// Returns the number of tokens at consecutive positions at the start of the window,
+    /// pulling up to `num_tokens` tokens from the underlying stream if necessary.
+    fn num_consecutive_tokens(&mut self, num_tokens: usize) -> usize {
+        while self.window.len() < num_tokens && self.tail.advance() {
+            self.window.push_back(self.tail.token().clone());
+        }
+        let Some(first_position) = self.window.front().map(|token| token.position) else {
+            return 0;
+        };
+        self.window
+            .iter()
+            .take(num_tokens)
+            .enumerate()
+            .take_while(|(i, token)| token.position == first_position + i)
+            .count()
+    }
+
+    fn build_shingle(&mut self, shingle_size: usize) {
+        let first = &self.window[0];
+        let last = &self.window[shingle_size - 1];
+        self.token.offset_from = first.offset_from;
+        self.token.offset_to = last.offset_to;
+        self.token.position = first.position;
+        self.token.position_length = shingle_size;
+        self.token.text.clear();
+        for (i, token) in self.window.iter().take(shingle_size).enumerate() {
+            if i > 0 {
+                self.token.text.push_str(&self.filter.separator);
+            }
+            self.token.text.push_str(&token.text);
+        }
+    }
+}
+
+impl<'a, T: TokenStream> TokenStream for ShingleFilterStream<'a, T> {
+    fn advance(&mut self) -> bool {
+        loop {
+            let shingle_size = match self.shingle_size {
+                0 if self.filter.output_unigrams => 1,
+                0 | 1 => self.filter.min_shingle_size,
+                shingle_size => shingle_size + 1,
+            };
+            if shingle_size <= self.filter.max_shingle_size
+                && self.num_consecutive_tokens(shingle_size) >= shingle_size
+            {
+                self.shingle_size = shingle_size;
+                if shingle_size == 1 {
+                    self.token.clone_from(&self.window[0]);
+                } else {
+                    self.build_shingle(shingle_size);
+                }
+                return true;
+            }
+            // No more shingles start at the first token of the window.
+            // If the window is empty, the underlying stream is exhausted.
+            self.shingle_size = 0;
+            if self.window.pop_front().is_none() {
+                return false;
+            }
+        }
+    }
+
+    fn token(&self) -> &Token {
+        self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShingleFilter;
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{SimpleTokenizer, StopWordFilter, TextAnalyzer, Token};
+
+    fn token_stream_helper(text: &str, filter: ShingleFilter) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut tokens = Vec::new();
+        analyzer
+            .token_stream(text)
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_shingle_filter_bigrams() {
+        let tokens = token_stream_helper("please divide this", ShingleFilter::new(2, 2).unwrap());
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "please", 0, 6);
+        assert_token(&tokens[1], 0, "please divide", 0, 13);
+        assert_eq!(tokens[1].position_length, 2);
+        assert_token(&tokens[2], 1, "divide", 7, 13);
+        assert_token(&tokens[3], 1, "divide this", 7, 18);
+        assert_token(&tokens[4], 2, "this", 14, 18);
+    }
+
+    #[test]
+    fn test_shingle_filter_bigrams_and_trigrams_without_unigrams() {
+        let filter = ShingleFilter::new(2, 3)
+            .unwrap()
+            .output_unigrams(false)
+            .separator("_");
+        let texts: Vec<String> = token_stream_helper("a b c d", filter)
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(texts, vec!["a_b", "a_b_c", "b_c", "b_c_d", "c_d"]);
+    }
+
+    #[test]
+    fn test_shingle_filter_short_input() {
+        let filter = ShingleFilter::new(2, 3).unwrap();
+        let tokens = token_stream_helper("single", filter.clone());
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "single", 0, 6);
+        assert!(token_stream_helper("", filter.clone()).is_empty());
+        assert!(token_stream_helper("single", filter.output_unigrams(false)).is_empty());
+    }
+
+    #[test]
+    fn test_shingle_filter_position_gap() {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(StopWordFilter::remove(vec!["the".to_string()]))
+            .filter(ShingleFilter::new(2, 2).unwrap())
+            .build();
+        let mut texts = Vec::new();
+        analyzer
+            .token_stream("over the moon river")
+            .process(&mut |token: &Token| texts.push(token.text.clone()));
+        assert_eq!(texts, vec!["over", "moon", "moon river", "river"]);
+    }
+
+    #[test]
+    fn test_shingle_filter_invalid_sizes() {
+        assert!(ShingleFilter::new(1, 2).is_err());
+        assert!(ShingleFilter::new(3, 2).is_err());
+    }
+}
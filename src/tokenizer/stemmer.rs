@@ -142,3 +142,50 @@ impl<T: TokenStream> TokenStream for StemmerTokenStream<T> {
         self.tail.token_mut()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{Language, LowerCaser, SimpleTokenizer, Stemmer, TextAnalyzer, Token};
+
+    #[test]
+    fn test_stemmer_english() {
+        let tokens = token_stream_helper("Running runs", Language::English);
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "run", 0, 7);
+        assert_token(&tokens[1], 1, "run", 8, 12);
+    }
+
+    #[test]
+    fn test_stemmer_inflected_forms() {
+        let inflected_forms = [
+            (Language::French, "chat", "chats"),
+            (Language::German, "Katze", "Katzen"),
+            (Language::Spanish, "gato", "gatos"),
+        ];
+        for (language, singular, plural) in inflected_forms {
+            let singular_tokens = token_stream_helper(singular, language);
+            let plural_tokens = token_stream_helper(plural, language);
+            assert_eq!(singular_tokens.len(), 1);
+            assert_eq!(plural_tokens.len(), 1);
+            assert_eq!(
+                singular_tokens[0].text, plural_tokens[0].text,
+                "{language:?}: {singular} and {plural} should share the same stem"
+            );
+        }
+    }
+
+    fn token_stream_helper(text: &str, language: Language) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(Stemmer::new(language))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
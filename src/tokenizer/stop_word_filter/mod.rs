@@ -145,6 +145,68 @@ mod tests {
         assert_token(&tokens[4], 9, "name", 29, 33);
     }
 
+    #[cfg(feature = "stopwords")]
+    #[test]
+    fn test_stop_word_filter_language() {
+        use crate::tokenizer::{Language, LowerCaser};
+
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(StopWordFilter::new(Language::English).unwrap())
+            .build();
+        let mut tokens = Vec::new();
+        analyzer
+            .token_stream("The fox IS crafty")
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 1, "fox", 4, 7);
+        assert_token(&tokens[1], 3, "crafty", 11, 17);
+
+        assert!(StopWordFilter::new(Language::French).is_some());
+        assert!(StopWordFilter::new(Language::Tamil).is_none());
+    }
+
+    #[test]
+    fn test_stop_word_filter_index_and_query() -> crate::Result<()> {
+        use crate::collector::Count;
+        use crate::query::QueryParser;
+        use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+        use crate::{doc, Index, IndexWriter};
+
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("stop")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            "stop",
+            TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(StopWordFilter::remove(vec![
+                    "the".to_string(),
+                    "is".to_string(),
+                ]))
+                .build(),
+        );
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "the fox is crafty"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("the")?, 0);
+        assert_eq!(count("fox")?, 1);
+        // Stop words are removed from the query as well, leaving the same position gap.
+        assert_eq!(count("\"fox is crafty\"")?, 1);
+        assert_eq!(count("\"fox the crafty\"")?, 1);
+        assert_eq!(count("\"fox crafty\"")?, 0);
+        Ok(())
+    }
+
     fn token_stream_helper(text: &str) -> Vec<Token> {
         let stops = vec![
             "a".to_string(),
@@ -0,0 +1,323 @@
+use std::sync::Arc;
+
+use rustc_hash::FxHashMap;
+
+use super::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// [`TokenFilter`] that expands or maps tokens according to a synonym dictionary.
+///
+/// Each token found in the dictionary is followed by its synonyms.
+/// Synonyms are emitted at the same position as the original token, and
+/// span the same offsets. A synonym made of several words, such as `"united states"`,
+/// is emitted as several tokens, all at the position of the original token.
+///
+/// The dictionary is looked up with the text of the token, as emitted by the
+/// previous filters: a `SynonymFilter` placed after a [`LowerCaser`](super::LowerCaser)
+/// should be given lowercase words.
+///
+/// The filter can be used both at indexing time and at query time, with a
+/// [search tokenizer](crate::schema::TextFieldIndexing::set_search_tokenizer).
+/// The [`QueryParser`](crate::query::QueryParser) searches a single word and its synonyms
+/// as a disjunction. However, it turns the tokens of several words into a phrase query,
+/// which requires all of the tokens emitted at a given position to be present in the
+/// document. Synonyms made of several words should therefore also be expanded
+/// at indexing time.
+///
+/// # Example
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+///     .filter(LowerCaser)
+///     .filter(
+///         SynonymFilter::default()
+///             .add_equivalents(["usa", "united states"])
+///             .add_synonyms("quick", ["fast"]),
+///     )
+///     .build();
+///
+/// let mut stream = tokenizer.token_stream("Quick trip to the USA");
+/// assert_eq!(stream.next().unwrap().text, "quick");
+/// let token = stream.next().unwrap();
+/// assert_eq!((token.text.as_str(), token.position), ("fast", 0));
+/// assert_eq!(stream.next().unwrap().text, "trip");
+/// assert_eq!(stream.next().unwrap().text, "to");
+/// assert_eq!(stream.next().unwrap().text, "the");
+/// assert_eq!(stream.next().unwrap().text, "usa");
+/// let token = stream.next().unwrap();
+/// assert_eq!((token.text.as_str(), token.position), ("united", 4));
+/// let token = stream.next().unwrap();
+/// assert_eq!((token.text.as_str(), token.position), ("states", 4));
+/// assert!(stream.next().is_none());
+/// ```
+#[derive(Clone)]
+pub struct SynonymFilter {
+    synonyms: Arc<FxHashMap<String, Vec<String>>>,
+    keep_original: bool,
+}
+
+impl Default for SynonymFilter {
+    fn default() -> SynonymFilter {
+        SynonymFilter {
+            synonyms: Arc::default(),
+            keep_original: true,
+        }
+    }
+}
+
+impl SynonymFilter {
+    /// Adds one-way synonyms: `word` is expanded into `synonyms`,
+    /// but the synonyms are not expanded into `word`.
+    ///
+    /// Synonyms containing whitespaces are split into several words.
+    #[must_use]
+    pub fn add_synonyms<I, S>(mut self, word: &str, synonyms: I) -> SynonymFilter
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = Arc::make_mut(&mut self.synonyms)
+            .entry(word.to_string())
+            .or_default();
+        for synonym in synonyms {
+            for synonym_word in synonym.as_ref().split_whitespace() {
+                if !words.iter().any(|word| word == synonym_word) {
+                    words.push(synonym_word.to_string());
+                }
+            }
+        }
+        self
+    }
+
+    /// Adds a group of equivalent words: each word of the group is
+    /// expanded into all of the other words of the group.
+    ///
+    /// Only single words can be looked up, so the entries containing
+    /// whitespaces are only emitted as synonyms of the other entries.
+    #[must_use]
+    pub fn add_equivalents<I, S>(mut self, words: I) -> SynonymFilter
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words: Vec<String> = words
+            .into_iter()
+            .map(|word| word.as_ref().to_string())
+            .collect();
+        for word in &words {
+            if word.split_whitespace().nth(1).is_some() {
+                continue;
+            }
+            let synonyms = words.iter().filter(|synonym| *synonym != word);
+            self = self.add_synonyms(word, synonyms);
+        }
+        self
+    }
+
+    /// Sets whether the original token should be emitted along with its synonyms.
+    ///
+    /// Defaults to `true`. If `false`, tokens with synonyms are replaced by their synonyms,
+    /// which makes it possible to map several words to a single canonical word.
+    #[must_use]
+    pub fn keep_original(mut self, keep_original: bool) -> SynonymFilter {
+        self.keep_original = keep_original;
+        self
+    }
+}
+
+impl TokenFilter for SynonymFilter {
+    type Tokenizer<T: Tokenizer> = SynonymFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> SynonymFilterWrapper<T> {
+        SynonymFilterWrapper {
+            synonyms: self.synonyms,
+            keep_original: self.keep_original,
+            inner: tokenizer,
+            pending: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SynonymFilterWrapper<T> {
+    synonyms: Arc<FxHashMap<String, Vec<String>>>,
+    keep_original: bool,
+    inner: T,
+    pending: Vec<Token>,
+}
+
+impl<T: Tokenizer> Tokenizer for SynonymFilterWrapper<T> {
+    type TokenStream<'a> = SynonymFilterStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        self.pending.clear();
+        SynonymFilterStream {
+            synonyms: &self.synonyms,
+            keep_original: self.keep_original,
+            tail: self.inner.token_stream(text),
+            pending: &mut self.pending,
+        }
+    }
+}
+
+pub struct SynonymFilterStream<'a, T> {
+    synonyms: &'a FxHashMap<String, Vec<String>>,
+    keep_original: bool,
+    tail: T,
+    // Tokens left to emit, in reverse order, so that `pending.pop()`
+    // yields them in their original order.
+    pending: &'a mut Vec<Token>,
+}
+
+impl<'a, T: TokenStream> SynonymFilterStream<'a, T> {
+    fn expand(&mut self) {
+        let token = self.tail.token();
+        let Some(synonyms) = self.synonyms.get(&token.text) else {
+            return;
+        };
+        for synonym in synonyms.iter().rev() {
+            self.pending.push(Token {
+                text: synonym.clone(),
+                position_length: 1,
+                ..*token
+            });
+        }
+        if self.keep_original && !self.pending.is_empty() {
+            self.pending.push(token.clone());
+        }
+    }
+}
+
+impl<'a, T: TokenStream> TokenStream for SynonymFilterStream<'a, T> {
+    fn advance(&mut self) -> bool {
+        self.pending.pop();
+        if !self.pending.is_empty() {
+            return true;
+        }
+        if !self.tail.advance() {
+            return false;
+        }
+        // Will yield either `self.pending.last()` or
+        // `self.tail.token()` if it has no synonyms.
+        self.expand();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.pending.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.pending
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SynonymFilter;
+    use crate::collector::Count;
+    use crate::query::QueryParser;
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+    use crate::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer, Token};
+    use crate::{doc, Index, IndexWriter};
+
+    fn token_stream_helper(text: &str, filter: SynonymFilter) -> Vec<(String, usize)> {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(filter)
+            .build();
+        let mut tokens = Vec::new();
+        analyzer
+            .token_stream(text)
+            .process(&mut |token: &Token| tokens.push((token.text.clone(), token.position)));
+        tokens
+    }
+
+    #[test]
+    fn test_synonym_filter_expand() {
+        let filter = SynonymFilter::default().add_synonyms("tv", ["television", "telly"]);
+        assert_eq!(
+            token_stream_helper("new TV set", filter),
+            vec![
+                ("new".to_string(), 0),
+                ("tv".to_string(), 1),
+                ("television".to_string(), 1),
+                ("telly".to_string(), 1),
+                ("set".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_synonym_filter_map() {
+        let filter = SynonymFilter::default()
+            .add_synonyms("colour", ["color"])
+            .keep_original(false);
+        assert_eq!(
+            token_stream_helper("Colour and color", filter),
+            vec![
+                ("color".to_string(), 0),
+                ("and".to_string(), 1),
+                ("color".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_synonym_filter_equivalents() {
+        let filter = SynonymFilter::default().add_equivalents(["nyc", "new york city", "gotham"]);
+        assert_eq!(
+            token_stream_helper("gotham", filter.clone()),
+            vec![
+                ("gotham".to_string(), 0),
+                ("nyc".to_string(), 0),
+                ("new".to_string(), 0),
+                ("york".to_string(), 0),
+                ("city".to_string(), 0),
+            ]
+        );
+        // Multi-word entries are not looked up.
+        assert_eq!(
+            token_stream_helper("new", filter),
+            vec![("new".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn test_synonym_filter_index_and_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("synonyms")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            "synonyms",
+            TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(LowerCaser)
+                .filter(SynonymFilter::default().add_equivalents(["car", "automobile"]))
+                .build(),
+        );
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "A red car"))?;
+        index_writer.add_document(doc!(text => "An automobile museum"))?;
+        index_writer.add_document(doc!(text => "A red bicycle"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("car")?, 2);
+        assert_eq!(count("automobile")?, 2);
+        assert_eq!(count("\"red automobile\"")?, 1);
+        assert_eq!(count("\"automobile museum\"")?, 1);
+        Ok(())
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::{Arc, RwLock};
 use crate::tokenizer::stemmer::Language;
 use crate::tokenizer::tokenizer::TextAnalyzer;
 use crate::tokenizer::{
-    LowerCaser, RawTokenizer, RemoveLongFilter, SimpleTokenizer, Stemmer, WhitespaceTokenizer,
+    CjkTokenizer, LowerCaser, RawTokenizer, RemoveLongFilter, SimpleTokenizer, Stemmer,
+    WhitespaceTokenizer,
 };
 
 /// The tokenizer manager serves as a store for
@@ -13,6 +14,8 @@ use crate::tokenizer::{
 /// By default, it is populated with the following managers.
 ///
 ///  * `raw` : does not process nor tokenize the text.
+///  * `raw_lowercase` : like `raw`, but lowercases the text. This is useful
+///  for case-insensitive exact matching on ids, tags, etc.
 ///  * `default` : Chops the text on according to whitespace and
 ///  punctuation, removes tokens that are too long, and lowercases
 ///  tokens
@@ -20,6 +23,8 @@ use crate::tokenizer::{
 ///  resulting tokens. Stemming can improve the recall of your
 ///  search engine.
 /// * `whitespace` : Splits the text on whitespaces.
+/// * `cjk` : Like `default`, but splits runs of Chinese, Japanese or Korean
+///  characters into bigrams.
 #[derive(Clone)]
 pub struct TokenizerManager {
     tokenizers: Arc<RwLock<HashMap<String, TextAnalyzer>>>,
@@ -51,6 +56,19 @@ impl TokenizerManager {
             .get(tokenizer_name)
             .cloned()
     }
+
+    /// Returns the sorted names of the registered tokenizers.
+    pub fn tokenizer_names(&self) -> Vec<String> {
+        let mut tokenizer_names: Vec<String> = self
+            .tokenizers
+            .read()
+            .expect("Acquiring the lock should never fail")
+            .keys()
+            .cloned()
+            .collect();
+        tokenizer_names.sort();
+        tokenizer_names
+    }
 }
 
 impl Default for TokenizerManager {
@@ -59,6 +77,12 @@ impl Default for TokenizerManager {
     fn default() -> TokenizerManager {
         let manager = TokenizerManager::new();
         manager.register("raw", RawTokenizer::default());
+        manager.register(
+            "raw_lowercase",
+            TextAnalyzer::builder(RawTokenizer::default())
+                .filter(LowerCaser)
+                .build(),
+        );
         manager.register(
             "default",
             TextAnalyzer::builder(SimpleTokenizer::default())
@@ -75,6 +99,13 @@ impl Default for TokenizerManager {
                 .build(),
         );
         manager.register("whitespace", WhitespaceTokenizer::default());
+        manager.register(
+            "cjk",
+            TextAnalyzer::builder(CjkTokenizer::default())
+                .filter(RemoveLongFilter::limit(40))
+                .filter(LowerCaser)
+                .build(),
+        );
         manager
     }
 }
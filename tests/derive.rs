@@ -0,0 +1,169 @@
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::schema::{FieldType, IndexRecordOption, TypedDocument};
+use tantivy::{DateTime, Index, IndexWriter, TantivyDocument};
+
+#[derive(TypedDocument, Debug, Default, PartialEq)]
+struct Book {
+    #[tantivy(text, stored)]
+    title: String,
+    #[tantivy(string, stored, name = "isbn_code")]
+    isbn: String,
+    #[tantivy(indexed, fast, stored)]
+    year: u64,
+    #[tantivy(stored)]
+    rating: Option<f64>,
+    #[tantivy(indexed, stored)]
+    in_print: bool,
+    #[tantivy(stored)]
+    published: Option<DateTime>,
+    #[tantivy(string, stored)]
+    tags: Vec<String>,
+    #[tantivy(stored)]
+    cover: Vec<u8>,
+    #[tantivy(indexed)]
+    delta: i64,
+    #[tantivy(skip)]
+    cached_len: usize,
+}
+
+fn book() -> Book {
+    Book {
+        title: "The Old Man and the Sea".to_string(),
+        isbn: "978-0684801223".to_string(),
+        year: 1952,
+        rating: Some(4.5),
+        in_print: true,
+        published: Some(DateTime::from_timestamp_secs(-546_393_600)),
+        tags: vec!["novel".to_string(), "fishing".to_string()],
+        cover: vec![1, 2, 3],
+        delta: -3,
+        cached_len: 23,
+    }
+}
+
+#[test]
+fn test_derive_schema() {
+    let schema = Book::schema();
+    let field_names: Vec<&str> = schema
+        .fields()
+        .map(|(_, field_entry)| field_entry.name())
+        .collect();
+    assert_eq!(
+        field_names,
+        [
+            "title",
+            "isbn_code",
+            "year",
+            "rating",
+            "in_print",
+            "published",
+            "tags",
+            "cover",
+            "delta"
+        ]
+    );
+    let isbn_entry = schema.get_field_entry(schema.get_field("isbn_code").unwrap());
+    let FieldType::Str(isbn_options) = isbn_entry.field_type() else {
+        panic!("isbn_code should be a text field");
+    };
+    let isbn_indexing = isbn_options.get_indexing_options().unwrap();
+    assert_eq!(isbn_indexing.tokenizer(), "raw");
+    assert_eq!(isbn_indexing.index_option(), IndexRecordOption::Basic);
+    assert!(isbn_entry.is_stored());
+
+    let year_entry = schema.get_field_entry(schema.get_field("year").unwrap());
+    assert!(year_entry.is_indexed());
+    assert!(year_entry.is_fast());
+    assert!(year_entry.is_stored());
+
+    let delta_entry = schema.get_field_entry(schema.get_field("delta").unwrap());
+    assert!(delta_entry.is_indexed());
+    assert!(!delta_entry.is_stored());
+}
+
+#[test]
+fn test_derive_document_roundtrip() -> tantivy::Result<()> {
+    let schema = Book::schema();
+    let book = book();
+    let doc = book.to_document(&schema)?;
+    let tags = schema.get_field("tags").unwrap();
+    assert_eq!(doc.get_all(tags).count(), 2);
+    let from_doc = Book::from_document(&doc, &schema)?;
+    assert_eq!(
+        from_doc,
+        Book {
+            cached_len: 0,
+            ..book
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_derive_missing_values() -> tantivy::Result<()> {
+    let schema = Book::schema();
+    let from_doc = Book::from_document(&TantivyDocument::default(), &schema)?;
+    assert_eq!(from_doc, Book::default());
+    Ok(())
+}
+
+#[test]
+fn test_derive_fields_named_like_parameters() -> tantivy::Result<()> {
+    #[derive(TypedDocument, Debug, PartialEq)]
+    struct Shadowing {
+        #[tantivy(stored)]
+        doc: String,
+        #[tantivy(stored)]
+        schema: u64,
+        #[tantivy(stored)]
+        field: Vec<i64>,
+    }
+    let schema = Shadowing::schema();
+    let shadowing = Shadowing {
+        doc: "doc".to_string(),
+        schema: 3,
+        field: vec![1, 2],
+    };
+    let doc = shadowing.to_document(&schema)?;
+    assert_eq!(Shadowing::from_document(&doc, &schema)?, shadowing);
+    Ok(())
+}
+
+#[test]
+fn test_derive_field_not_in_schema() {
+    #[derive(TypedDocument)]
+    struct Other {
+        #[tantivy(stored)]
+        _other: u64,
+    }
+    let schema = Other::schema();
+    assert!(book().to_document(&schema).is_err());
+}
+
+#[test]
+fn test_derive_index_and_fetch() -> tantivy::Result<()> {
+    let schema = Book::schema();
+    let index = Index::create_in_ram(schema.clone());
+    let mut index_writer: IndexWriter = index.writer_with_num_threads(1, 15_000_000)?;
+    index_writer.add_document(book().to_document(&schema)?)?;
+    index_writer.commit()?;
+
+    let searcher = index.reader()?.searcher();
+    let query_parser = QueryParser::for_index(&index, vec![schema.get_field("title").unwrap()]);
+    let query = query_parser.parse_query("sea")?;
+    let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
+    assert_eq!(top_docs.len(), 1);
+    let doc: TantivyDocument = searcher.doc(top_docs[0].1)?;
+    let fetched_book = Book::from_document(&doc, &schema)?;
+    assert_eq!(
+        fetched_book,
+        Book {
+            // `delta` is not stored.
+            delta: 0,
+            cached_len: 0,
+            ..book()
+        }
+    );
+    Ok(())
+}